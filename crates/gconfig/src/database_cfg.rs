@@ -7,7 +7,7 @@ use serde::Deserialize;
 /// 支持 SQLite 和 PostgreSQL 等数据库类型
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct DatabaseConfig {
-    /// 数据库类型: sqlite, postgresql 等
+    /// 数据库类型: sqlite 或 postgres（postgresql）
     #[serde(default = "default_db_type")]
     db_type: Option<String>,
     /// SQLite 文件路径（仅当 db_type=sqlite 时使用）
@@ -28,6 +28,18 @@ pub struct DatabaseConfig {
     /// 数据库连接池大小
     #[serde(default = "default_pool_size")]
     pool_size: Option<u32>,
+    /// 回收站保留天数，超过后自动彻底删除（0 表示永久保留）
+    #[serde(default = "default_trash_retention_days")]
+    trash_retention_days: Option<u32>,
+    /// 保留的每日备份份数（仅 SQLite）
+    #[serde(default = "default_backup_daily_keep")]
+    backup_daily_keep: Option<u32>,
+    /// 保留的每周备份份数（仅 SQLite）
+    #[serde(default = "default_backup_weekly_keep")]
+    backup_weekly_keep: Option<u32>,
+    /// 运行期间定时备份的间隔小时数（0 表示只在启动和迁移前备份）
+    #[serde(default = "default_backup_interval_hours")]
+    backup_interval_hours: Option<u32>,
 }
 
 /// 默认数据库类型
@@ -40,6 +52,26 @@ fn default_pool_size() -> Option<u32> {
     Some(10)
 }
 
+/// 默认回收站保留天数
+fn default_trash_retention_days() -> Option<u32> {
+    Some(30)
+}
+
+/// 默认保留的每日备份份数
+fn default_backup_daily_keep() -> Option<u32> {
+    Some(7)
+}
+
+/// 默认保留的每周备份份数
+fn default_backup_weekly_keep() -> Option<u32> {
+    Some(4)
+}
+
+/// 默认定时备份间隔
+fn default_backup_interval_hours() -> Option<u32> {
+    Some(6)
+}
+
 impl DatabaseConfig {
     /// 检查是否为 SQLite 数据库
     pub fn is_sqlite(&self) -> bool {
@@ -110,4 +142,24 @@ impl DatabaseConfig {
     pub fn pool_size(&self) -> u32 {
         self.pool_size.unwrap_or(10)
     }
+
+    /// 获取回收站保留天数（0 表示永久保留）
+    pub fn trash_retention_days(&self) -> u32 {
+        self.trash_retention_days.unwrap_or(30)
+    }
+
+    /// 获取保留的每日备份份数
+    pub fn backup_daily_keep(&self) -> u32 {
+        self.backup_daily_keep.unwrap_or(7)
+    }
+
+    /// 获取保留的每周备份份数
+    pub fn backup_weekly_keep(&self) -> u32 {
+        self.backup_weekly_keep.unwrap_or(4)
+    }
+
+    /// 获取定时备份间隔小时数（0 表示不定时备份）
+    pub fn backup_interval_hours(&self) -> u32 {
+        self.backup_interval_hours.unwrap_or(6)
+    }
 }
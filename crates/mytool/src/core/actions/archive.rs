@@ -0,0 +1,182 @@
+//! 归档
+//!
+//! 归档项目时整棵项目树（子项目、分区、未完成任务）从 TodoStore 中移除，
+//! 取消归档后重新加载各数据域；归档列表在每次变化后从数据库刷新。
+
+use std::sync::Arc;
+
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{
+    Store,
+    entity::{ProjectModel, SectionModel},
+    services::ArchivedProject,
+};
+use tracing::{error, info};
+
+use super::trash::reload_all_impl;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, TodoStore, get_store},
+};
+
+// 刷新归档列表
+pub fn load_archive(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        refresh_archive_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 归档项目（子项目、分区和未完成任务一起归档）
+pub fn archive_project(project: Arc<ProjectModel>, cx: &mut App) {
+    let store = get_store(cx);
+    let project_id = project.id.clone();
+    cx.spawn(async move |cx| {
+        match crate::state_service::archive_project_with_store(&project_id, store.clone()).await {
+            Ok(_) => {
+                info!("Successfully archived project: {}", project_id);
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.remove_project_tree(&project_id);
+                });
+                refresh_archive_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "archive_project",
+                    &project_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("归档失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 取消归档（还原后重新加载各数据域，子对象随项目一起回来）
+pub fn unarchive_project(entry: Arc<ArchivedProject>, cx: &mut App) {
+    let store = get_store(cx);
+    let project_id = entry.project.id.clone();
+    cx.spawn(async move |cx| {
+        match crate::state_service::unarchive_project_with_store(&project_id, store.clone()).await {
+            Ok(_) => {
+                info!("Successfully unarchived project: {}", project_id);
+                reload_all_impl(store.clone(), cx).await;
+                refresh_archive_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "unarchive_project",
+                    &project_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("取消归档失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 彻底删除归档的项目（不经过回收站）
+pub fn delete_archived_project(entry: Arc<ArchivedProject>, cx: &mut App) {
+    let project_id = entry.project.id.clone();
+    cx.update_global::<TodoStore, _>(|todo_store, _| {
+        let archive =
+            todo_store.archive.iter().filter(|e| e.project.id != project_id).map(|e| (**e).clone());
+        todo_store.set_archive(archive.collect());
+    });
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::delete_archived_project_with_store(&project_id, store.clone())
+            .await
+        {
+            Ok(_) => {
+                info!("Successfully deleted archived project: {}", project_id);
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "delete_archived_project",
+                    &project_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("删除失败：{}", context.format_user_message()));
+                });
+                refresh_archive_impl(store, cx).await;
+            },
+        }
+    })
+    .detach();
+}
+
+// 归档 / 取消归档分区（分区中未完成的任务一起归档）
+pub fn set_section_archived(section: Arc<SectionModel>, archived: bool, cx: &mut App) {
+    let store = get_store(cx);
+    let section_id = section.id.clone();
+    cx.spawn(async move |cx| {
+        match crate::state_service::set_section_archived_with_store(
+            &section_id,
+            archived,
+            store.clone(),
+        )
+        .await
+        {
+            Ok(updated) => {
+                info!("Section {} archived: {}", section_id, archived);
+                if archived {
+                    cx.update_global::<TodoStore, _>(|todo_store, _| {
+                        let archived_items: Vec<String> = todo_store
+                            .items_by_section(&section_id)
+                            .iter()
+                            .filter(|item| !item.checked)
+                            .map(|item| item.id.clone())
+                            .collect();
+                        for id in &archived_items {
+                            todo_store.remove_item(id);
+                        }
+                        todo_store.update_section(Arc::new(updated));
+                    });
+                } else {
+                    reload_all_impl(store, cx).await;
+                }
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "set_section_archived",
+                    &section_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("归档分区失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 重新加载归档列表
+pub(crate) async fn refresh_archive_impl(store: Arc<Store>, cx: &mut AsyncApp) {
+    match crate::state_service::load_archive_with_store(store).await {
+        Ok(archive) => {
+            cx.update_global::<TodoStore, _>(|todo_store, _| {
+                todo_store.set_archive(archive);
+            });
+        },
+        Err(e) => {
+            let context =
+                ErrorHandler::handle_with_location(AppError::Database(Box::new(e)), "load_archive");
+            error!("{}", context.format_user_message());
+        },
+    }
+}
@@ -0,0 +1,86 @@
+use std::{path::PathBuf, time::Duration};
+
+use gpui::{AnyWindowHandle, App, AsyncApp, BorrowAppContext};
+use todos::{BackupManager, BackupReason, BackupSnapshot, error::TodoError};
+use tracing::{error, info};
+
+use crate::{
+    Quit,
+    core::{
+        state::{ErrorNotifier, get_db_connection},
+        tokio_runtime::spawn_db_operation,
+    },
+    todo_state::DBState,
+};
+
+/// 当前数据库的备份管理器；不是 SQLite 时为 None
+pub fn backup_manager(cx: &App) -> Option<BackupManager> {
+    todos::backup_manager(get_db_connection(cx))
+}
+
+// 启动时备份一次，之后按配置的间隔定时备份并轮换
+pub fn schedule_backups(cx: &mut AsyncApp) {
+    let conn = cx.update_global::<DBState, _>(|db_state, _| db_state.get_connection());
+    let Some(manager) = todos::backup_manager(conn) else {
+        return;
+    };
+    let interval_hours = todos::backup_interval_hours();
+    cx.spawn(async move |cx| {
+        run_backup(manager.clone(), BackupReason::Startup).await;
+        if interval_hours == 0 {
+            return;
+        }
+        let interval = Duration::from_secs(u64::from(interval_hours) * 60 * 60);
+        loop {
+            cx.background_executor().timer(interval).await;
+            run_backup(manager.clone(), BackupReason::Scheduled).await;
+        }
+    })
+    .detach();
+}
+
+async fn run_backup(manager: BackupManager, reason: BackupReason) {
+    let result = spawn_db_operation(async move { manager.backup_and_rotate(reason).await }).await;
+    match result {
+        Ok(Ok(snapshot)) => {
+            info!("Database backup ({}) written to {}", reason.as_str(), snapshot.path.display())
+        },
+        Ok(Err(e)) => error!("Database backup ({}) failed: {}", reason.as_str(), e),
+        Err(join_err) => error!("Database backup task failed: {:?}", join_err),
+    }
+}
+
+// 列出所有快照及其中的任务数
+pub async fn list_backups(manager: BackupManager) -> Result<Vec<BackupSnapshot>, TodoError> {
+    spawn_db_operation(async move { manager.list().await })
+        .await
+        .unwrap_or_else(|join_err| Err(TodoError::InternalError(format!("{:?}", join_err))))
+}
+
+// 从快照恢复：先备份当前数据库并准备好替换文件，再退出程序，下次启动时替换
+pub fn restore_backup(snapshot: PathBuf, window: AnyWindowHandle, cx: &mut App) {
+    let Some(manager) = backup_manager(cx) else {
+        return;
+    };
+    cx.spawn(async move |cx| {
+        let path = snapshot.clone();
+        let result = spawn_db_operation(async move { manager.stage_restore(&path).await })
+            .await
+            .unwrap_or_else(|join_err| Err(TodoError::InternalError(format!("{:?}", join_err))));
+        match result {
+            Ok(_) => {
+                info!("Restore of {} staged, quitting to swap the database", snapshot.display());
+                let _ = window.update(cx, |_, window, cx| {
+                    window.dispatch_action(Box::new(Quit), cx);
+                });
+            },
+            Err(e) => {
+                error!("Failed to restore backup {}: {}", snapshot.display(), e);
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("恢复备份失败：{}", e));
+                });
+            },
+        }
+    })
+    .detach();
+}
@@ -4,13 +4,14 @@
 
 use std::sync::Arc;
 
-use gpui::App;
+use gpui::{App, BorrowAppContext};
 use todos::entity::ItemModel;
 use tracing::{error, info};
 
-use crate::core::state::TodoStore;
+use super::optimistic::roll_back_write;
+use crate::core::state::{FailedWrite, TodoStore, UndoCommand, UndoHistory};
 
-/// 批量更新任务
+/// 批量更新任务（乐观更新）
 pub fn batch_update_items(items: Vec<Arc<ItemModel>>, cx: &mut App) {
     if items.is_empty() {
         return;
@@ -19,6 +20,25 @@ pub fn batch_update_items(items: Vec<Arc<ItemModel>>, cx: &mut App) {
     let item_count = items.len();
     info!("Batch updating {} items", item_count);
 
+    let before: Vec<Arc<ItemModel>> = {
+        let todo_store = cx.global::<TodoStore>();
+        items.iter().filter_map(|item| todo_store.get_item(&item.id)).collect()
+    };
+    // 乐观更新：先更新界面，写入失败时回滚
+    cx.update_global::<TodoStore, _>(|todo_store, _| {
+        for item in &items {
+            todo_store.update_item(item.clone());
+        }
+    });
+    if !before.is_empty() {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::BatchUpdateItems {
+                before: before.clone(),
+                after: items.clone(),
+            });
+        });
+    }
+
     cx.spawn(async move |cx| {
         let store =
             cx.update_global::<crate::core::state::DBState, _>(|state, _| state.get_store());
@@ -35,6 +55,21 @@ pub fn batch_update_items(items: Vec<Arc<ItemModel>>, cx: &mut App) {
             },
             Err(e) => {
                 error!("Batch update items failed: {:?}", e);
+                // 批量写入在一个事务中，失败时全部回滚到修改前
+                let changes = before
+                    .into_iter()
+                    .filter_map(|before| {
+                        let after = items.iter().find(|item| item.id == before.id)?.clone();
+                        Some((before, after))
+                    })
+                    .collect();
+                roll_back_write(
+                    FailedWrite::BatchUpdateItems(items),
+                    Vec::new(),
+                    changes,
+                    e.to_string(),
+                    cx,
+                );
             },
         }
     })
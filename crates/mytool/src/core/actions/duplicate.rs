@@ -0,0 +1,85 @@
+//! 深拷贝
+//!
+//! 复制在数据库的一个事务中完成；副本插入后同级对象的排序会变化，完成后整体重新加载。
+
+use std::sync::Arc;
+
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel, SectionModel},
+    error::TodoError,
+    services::DuplicateResult,
+};
+use tracing::{error, info};
+
+use super::trash::reload_all_impl;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, get_store},
+};
+
+// 复制任务（含子任务、标签、提醒、附件），副本排在原任务之后
+pub fn duplicate_item(item: Arc<ItemModel>, cx: &mut App) {
+    let store = get_store(cx);
+    let item_id = item.id.clone();
+    cx.spawn(async move |cx| {
+        let result = crate::state_service::duplicate_item_with_store(&item_id, store.clone()).await;
+        finish_duplicate(result, "duplicate_item", &item_id, store, cx).await;
+    })
+    .detach();
+}
+
+// 复制分区及其中的任务，副本名称加上 "(copy)"
+pub fn duplicate_section(section: Arc<SectionModel>, cx: &mut App) {
+    let store = get_store(cx);
+    let section_id = section.id.clone();
+    cx.spawn(async move |cx| {
+        let result =
+            crate::state_service::duplicate_section_with_store(&section_id, store.clone()).await;
+        finish_duplicate(result, "duplicate_section", &section_id, store, cx).await;
+    })
+    .detach();
+}
+
+// 复制项目及其子项目、分区和任务，副本名称加上 "(copy)"
+pub fn duplicate_project(project: Arc<ProjectModel>, cx: &mut App) {
+    let store = get_store(cx);
+    let project_id = project.id.clone();
+    cx.spawn(async move |cx| {
+        let result =
+            crate::state_service::duplicate_project_with_store(&project_id, store.clone()).await;
+        finish_duplicate(result, "duplicate_project", &project_id, store, cx).await;
+    })
+    .detach();
+}
+
+async fn finish_duplicate(
+    result: Result<DuplicateResult, TodoError>,
+    operation: &str,
+    id: &str,
+    store: Arc<Store>,
+    cx: &mut AsyncApp,
+) {
+    match result {
+        Ok(result) => {
+            info!(
+                "{} {}: {} projects, {} sections, {} items copied",
+                operation,
+                id,
+                result.projects.len(),
+                result.sections.len(),
+                result.items.len()
+            );
+            reload_all_impl(store, cx).await;
+        },
+        Err(e) => {
+            let context =
+                ErrorHandler::handle_with_resource(AppError::Database(Box::new(e)), operation, id);
+            error!("{}", context.format_user_message());
+            cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                notifier.set_error(format!("复制失败：{}", context.format_user_message()));
+            });
+        },
+    }
+}
@@ -0,0 +1,78 @@
+use gpui::{App, BorrowAppContext};
+use todos::services::{ExportFormat, IcsComponent};
+use tracing::{error, info};
+
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, get_store},
+};
+
+// 导出工作区：选择保存位置后按指定格式写出全部项目与任务
+pub fn export_workspace(format: ExportFormat, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter(format.display_name(), &[format.extension()])
+            .set_file_name(format!("mytool-export.{}", format.extension()))
+            .save_file()
+            .await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+
+        match crate::state_service::export_workspace_with_store(path.clone(), format, None, store)
+            .await
+        {
+            Ok(()) => {
+                info!("Exported workspace as {} to {}", format.display_name(), path.display())
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "export_workspace",
+                    &path.display().to_string(),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("导出失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 导出到日历：有截止时间的任务写成 .ics 文件，供日历应用订阅或导入
+pub fn export_calendar(component: IcsComponent, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("iCalendar", &["ics"])
+            .set_file_name("mytool.ics")
+            .save_file()
+            .await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+
+        match crate::state_service::export_ics_with_store(path.clone(), component, None, store)
+            .await
+        {
+            Ok(()) => info!("Exported calendar ({}) to {}", component.name(), path.display()),
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "export_calendar",
+                    &path.display().to_string(),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("导出失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
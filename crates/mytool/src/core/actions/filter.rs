@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use gpui::{App, BorrowAppContext};
+use todos::entity::FilterModel;
+use tracing::{error, info};
+
+use crate::core::{
+    error_handler::{AppError, ErrorHandler, validation},
+    state::{TodoStore, get_store},
+};
+
+// 添加保存的过滤器
+pub fn add_filter(filter: Arc<FilterModel>, cx: &mut App) {
+    if let Err(e) = validation::validate_filter(&filter.name, &filter.query) {
+        let context = ErrorHandler::handle_with_location(e, "add_filter");
+        error!("{}", context.format_user_message());
+        return;
+    }
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::add_filter_with_store(filter.clone(), store).await {
+            Ok(new_filter) => {
+                info!("Successfully added filter: {}", new_filter.id);
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.update_filter(Arc::new(new_filter));
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "add_filter",
+                    &filter.name,
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+    })
+    .detach();
+}
+
+// 修改保存的过滤器
+pub fn update_filter(filter: Arc<FilterModel>, cx: &mut App) {
+    if let Err(e) = validation::validate_filter(&filter.name, &filter.query) {
+        let context = ErrorHandler::handle_with_location(e, "update_filter");
+        error!("{}", context.format_user_message());
+        return;
+    }
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::mod_filter_with_store(filter.clone(), store).await {
+            Ok(new_filter) => {
+                info!(
+                    "Successfully updated filter: {} (query: {})",
+                    new_filter.id, new_filter.query
+                );
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.update_filter(Arc::new(new_filter));
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "update_filter",
+                    &filter.id,
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+    })
+    .detach();
+}
+
+// 删除保存的过滤器（不影响任务，不进回收站）
+pub fn delete_filter(filter: Arc<FilterModel>, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::del_filter_with_store(filter.clone(), store).await {
+            Ok(_) => {
+                info!("Successfully deleted filter: {}", filter.id);
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.remove_filter(&filter.id);
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "delete_filter",
+                    &filter.id,
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+    })
+    .detach();
+}
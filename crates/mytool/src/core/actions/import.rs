@@ -0,0 +1,86 @@
+use gpui::{App, BorrowAppContext};
+use todos::services::ImportReport;
+use tracing::{error, info, warn};
+
+use super::trash::reload_all_impl;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, get_store},
+};
+
+// 导入 Todoist 备份：选择 zip 文件，导入完成后整体重新加载
+pub fn import_todoist_backup(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let Some(file) =
+            rfd::AsyncFileDialog::new().add_filter("Todoist backup", &["zip"]).pick_file().await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+
+        match crate::state_service::import_todoist_backup_with_store(path.clone(), store.clone())
+            .await
+        {
+            Ok(report) => {
+                info!("Imported Todoist backup {}: {}", path.display(), report.summary());
+                log_report_issues("Todoist import", &report);
+                reload_all_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "import_todoist_backup",
+                    &path.display().to_string(),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("导入失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 导入日历文件：选择 .ics 文件，其中的任务（VTODO）导入收件箱
+pub fn import_ics(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let Some(file) =
+            rfd::AsyncFileDialog::new().add_filter("iCalendar", &["ics"]).pick_file().await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+
+        match crate::state_service::import_ics_with_store(path.clone(), store.clone()).await {
+            Ok(report) => {
+                info!("Imported calendar {}: {}", path.display(), report.summary());
+                log_report_issues("iCalendar import", &report);
+                reload_all_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "import_ics",
+                    &path.display().to_string(),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("导入失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+fn log_report_issues(source: &str, report: &ImportReport) {
+    for (field, count) in &report.unmapped_fields {
+        warn!("{}: field `{}` not imported ({} times)", source, field, count);
+    }
+    for reason in &report.skipped {
+        warn!("{}: {}", source, reason);
+    }
+}
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{JournalOp, Store, error::TodoError};
+use tracing::{error, info};
+
+use super::trash::reload_all_impl;
+use crate::{
+    core::{
+        state::{ErrorNotifier, SaveResults, TodoStore, UndoHistory, WriteJournalState, get_store},
+        tokio_runtime::spawn_db_operation,
+    },
+    todo_state::DBState,
+};
+
+// 把没有落盘的写入追加到写入日志，连接恢复后按顺序重放
+pub(super) fn queue_write(op: JournalOp, reason: &TodoError, cx: &mut AsyncApp) {
+    let description = op.describe();
+    let result = cx.update_global::<WriteJournalState, _>(|state, _| {
+        state.journal().append(op, reason).map(|_| ())
+    });
+    let message = match result {
+        Ok(()) => {
+            format!("{}未能保存：{}。修改已记录在本地，连接恢复后会自动重试。", description, reason)
+        },
+        Err(e) => {
+            error!("Failed to queue {} in write journal: {}", description, e);
+            format!("{}未能保存：{}。", description, reason)
+        },
+    };
+    cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+        notifier.set_error(message);
+    });
+}
+
+// 写入成功说明连接已恢复：写入日志非空时在后台重放一次
+pub(super) fn replay_write_journal_if_needed(cx: &mut AsyncApp) {
+    if cx.update_global::<WriteJournalState, _>(|state, _| state.is_empty()) {
+        return;
+    }
+    let store = cx.update_global::<DBState, _>(|db_state, _| db_state.get_store());
+    cx.spawn(async move |cx| {
+        replay_write_journal_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 重放写入日志并把结果应用到 TodoStore：新建的任务换成数据库分配的 ID
+pub async fn replay_write_journal_impl(store: Arc<Store>, cx: &mut AsyncApp) {
+    let journal = cx.update_global::<WriteJournalState, _>(|state, _| state.journal());
+    if journal.is_empty() {
+        return;
+    }
+    let result = spawn_db_operation(async move { journal.replay(&store).await }).await;
+    let report = match result {
+        Ok(Ok(report)) => report,
+        Ok(Err(e)) => {
+            error!("Failed to replay write journal: {}", e);
+            return;
+        },
+        Err(join_err) => {
+            error!("Write journal replay task failed: {:?}", join_err);
+            return;
+        },
+    };
+
+    for saved in &report.saved {
+        let item = Arc::new(saved.item.clone());
+        match &saved.temp_id {
+            Some(temp_id) => {
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.replace_item_id(temp_id, item.clone());
+                });
+                cx.update_global::<UndoHistory, _>(|history, _| {
+                    history.replace_item_id(temp_id, &item);
+                });
+                cx.update_global::<SaveResults, _>(|results, _| {
+                    results.mark_succeeded(temp_id.clone());
+                    results.mark_succeeded(item.id.clone());
+                });
+            },
+            None => {
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.update_item(item.clone());
+                });
+            },
+        }
+    }
+    if !report.saved.is_empty() {
+        info!("Saved {} queued writes", report.saved.len());
+    }
+
+    if !report.failed.is_empty() {
+        let descriptions: Vec<String> =
+            report.failed.iter().map(|entry| entry.op.describe()).collect();
+        cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+            notifier.set_error(format!(
+                "{} 条修改无法保存：{}。可在标题栏中重试或放弃。",
+                descriptions.len(),
+                descriptions.join("；")
+            ));
+        });
+    }
+    // 通知标题栏刷新等待 / 失败的数量
+    cx.update_global::<WriteJournalState, _>(|_, _| {});
+}
+
+// 重试失败的写入
+pub fn retry_failed_writes(cx: &mut App) {
+    let result =
+        cx.update_global::<WriteJournalState, _>(|state, _| state.journal().retry_failed());
+    match result {
+        Ok(0) => return,
+        Ok(count) => info!("Retrying {} failed writes", count),
+        Err(e) => {
+            error!("Failed to retry queued writes: {}", e);
+            return;
+        },
+    }
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        replay_write_journal_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 放弃失败的写入，重新加载以撤掉界面中的修改
+pub fn discard_failed_writes(cx: &mut App) {
+    let result =
+        cx.update_global::<WriteJournalState, _>(|state, _| state.journal().discard_failed());
+    match result {
+        Ok(discarded) if discarded.is_empty() => return,
+        Ok(discarded) => info!("Discarded {} failed writes", discarded.len()),
+        Err(e) => {
+            error!("Failed to discard queued writes: {}", e);
+            return;
+        },
+    }
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        reload_all_impl(store, cx).await;
+    })
+    .detach();
+}
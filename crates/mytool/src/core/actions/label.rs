@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
-use gpui::App;
-use todos::entity::LabelModel;
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{Store, entity::LabelModel, error::TodoError};
 use tracing::{error, info};
 
+use super::trash::{refresh_trash_impl, reload_all_impl};
 use crate::core::{
     error_handler::{AppError, ErrorHandler, validation},
-    state::{TodoStore, get_store},
+    state::{ErrorNotifier, TodoStore, UndoCommand, UndoHistory, get_store},
 };
 
 // 添加 label
@@ -26,7 +27,10 @@ pub fn add_label(label: Arc<LabelModel>, cx: &mut App) {
                 // 增量更新 TodoStore
                 let arc_label = Arc::new(new_label);
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
-                    todo_store.add_label(arc_label);
+                    todo_store.add_label(arc_label.clone());
+                });
+                cx.update_global::<UndoHistory, _>(|history, _| {
+                    history.record(UndoCommand::AddLabel { label: arc_label });
                 });
             },
             Err(e) => {
@@ -51,6 +55,12 @@ pub fn update_label(label: Arc<LabelModel>, cx: &mut App) {
         return;
     }
 
+    if let Some(before) = cx.global::<TodoStore>().get_label(&label.id) {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::UpdateLabel { before, after: label.clone() });
+        });
+    }
+
     let store = get_store(cx);
     cx.spawn(async move |cx| {
         match crate::state_service::mod_label_with_store(label.clone(), store).await {
@@ -77,17 +87,22 @@ pub fn update_label(label: Arc<LabelModel>, cx: &mut App) {
 
 // 删除 label
 pub fn delete_label(label: Arc<LabelModel>, cx: &mut App) {
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::DeleteLabel { label: label.clone() });
+    });
+
     let store = get_store(cx);
     let label_id = label.id.clone();
 
     cx.spawn(async move |cx| {
-        match crate::state_service::del_label_with_store(label.clone(), store).await {
+        match crate::state_service::del_label_with_store(label.clone(), store.clone()).await {
             Ok(_) => {
                 info!("Successfully deleted label: {}", label_id);
                 // 增量更新 TodoStore
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
                     todo_store.remove_label(&label_id);
                 });
+                super::trash::refresh_trash_impl(store, cx).await;
             },
             Err(e) => {
                 let context = ErrorHandler::handle_with_resource(
@@ -101,3 +116,163 @@ pub fn delete_label(label: Arc<LabelModel>, cx: &mut App) {
     })
     .detach();
 }
+
+// 重命名 label：先按名称冲突检查改名，再保存颜色等其他字段
+pub fn rename_label(label: Arc<LabelModel>, cx: &mut App) {
+    if let Err(e) = validation::validate_label_name(&label.name) {
+        let context = ErrorHandler::handle_with_location(e, "rename_label");
+        error!("{}", context.format_user_message());
+        return;
+    }
+
+    if let Some(before) = cx.global::<TodoStore>().get_label(&label.id) {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::UpdateLabel { before, after: label.clone() });
+        });
+    }
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result = match crate::state_service::rename_label_with_store(
+            &label.id,
+            &label.name,
+            store.clone(),
+        )
+        .await
+        {
+            Ok(renamed) => {
+                let label = Arc::new(LabelModel { name: renamed.name, ..label.as_ref().clone() });
+                crate::state_service::mod_label_with_store(label, store).await
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(new_label) => {
+                info!("Successfully renamed label: {} (name: {})", new_label.id, new_label.name);
+                let arc_label = Arc::new(new_label);
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.update_label(arc_label);
+                });
+            },
+            Err(e) => {
+                let message = match &e {
+                    TodoError::AlreadyExists(name) => format!("{}已存在，可以改用合并", name),
+                    _ => e.to_string(),
+                };
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "rename_label",
+                    &label.id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("重命名标签失败：{}", message));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 把 source 合并到 target：任务改用 target，source 移入回收站
+pub fn merge_labels(source: Arc<LabelModel>, target: Arc<LabelModel>, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::merge_labels_with_store(&source.id, &target.id, store.clone())
+            .await
+        {
+            Ok(merge) => {
+                info!(
+                    "Merged label {} into {} ({} tasks)",
+                    source.id,
+                    target.id,
+                    merge.item_ids.len()
+                );
+                // 任务的 labels 字段随合并变化，整体重新加载
+                reload_all_impl(store.clone(), cx).await;
+                refresh_trash_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "merge_labels",
+                    &source.id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("合并标签失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 把 label 在列表中上移 / 下移 `offset` 位，整体重写 item_order
+pub fn move_label(label: Arc<LabelModel>, offset: isize, cx: &mut App) {
+    let mut ids: Vec<String> =
+        cx.global::<TodoStore>().labels.iter().map(|label| label.id.clone()).collect();
+    let Some(from) = ids.iter().position(|id| *id == label.id) else {
+        return;
+    };
+    let to = from.saturating_add_signed(offset).min(ids.len() - 1);
+    if to == from {
+        return;
+    }
+    let id = ids.remove(from);
+    ids.insert(to, id);
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::reorder_labels_with_store(ids, store.clone()).await {
+            Ok(_) => reload_labels_impl(store, cx).await,
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "move_label",
+                    &label.id,
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+    })
+    .detach();
+}
+
+// 收藏 / 取消收藏 label
+pub fn set_label_favorite(label: Arc<LabelModel>, is_favorite: bool, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::set_label_favorite_with_store(&label.id, is_favorite, store)
+            .await
+        {
+            Ok(new_label) => {
+                let arc_label = Arc::new(new_label);
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    todo_store.update_label(arc_label);
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "set_label_favorite",
+                    &label.id,
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+    })
+    .detach();
+}
+
+/// 从数据库重新加载标签（按 item_order 排序）
+async fn reload_labels_impl(store: Arc<Store>, cx: &mut AsyncApp) {
+    match crate::state_service::load_labels_with_store(store).await {
+        Ok(labels) => {
+            cx.update_global::<TodoStore, _>(|todo_store, _| {
+                todo_store.set_labels(labels);
+            });
+        },
+        Err(e) => error!("Failed to reload labels: {}", e),
+    }
+}
@@ -1,22 +1,50 @@
 //! todo_actions 层职责说明
 //!
 //! 本模块负责处理业务操作：调用 service 层做数据库写入，再更新 TodoStore。
-//! 乐观更新路径见 `optimistic`；批量操作见 `batch`。
+//! 乐观更新路径见 `optimistic`；批量操作见 `batch`；手动排序见 `order`；子任务树见
+//! `subtask`；归档见 `archive`；项目模板见 `template`；深拷贝见
+//! `duplicate`；撤销 / 重做见 `undo`。
 
+mod archive;
 mod attachment;
+mod backup;
 pub mod batch;
+mod duplicate;
+mod export;
+mod filter;
+mod import;
+mod journal;
 mod label;
 mod optimistic;
+mod order;
 mod project;
 mod project_item;
 mod reminder;
 mod section;
+mod subtask;
+mod sync;
+mod template;
+mod trash;
+mod undo;
 
+pub use archive::*;
 pub use attachment::*;
+pub use backup::*;
 pub use batch::*;
+pub use duplicate::*;
+pub use export::*;
+pub use filter::*;
+pub use import::*;
+pub use journal::*;
 pub use label::*;
 pub use optimistic::*;
+pub use order::*;
 pub use project::*;
 pub use project_item::*;
 pub use reminder::*;
 pub use section::*;
+pub use subtask::*;
+pub use sync::*;
+pub use template::*;
+pub use trash::*;
+pub use undo::*;
@@ -5,17 +5,22 @@
 //! 2. 异步保存到数据库（使用 cx.spawn + spawn_db_operation，不阻塞 UI）
 //! 3. 自动重试机制 + Store 就绪等待，确保数据可靠落盘
 //! 4. 窗口关闭时会等待 DB 操作完成后再退出
+//! 5. 重试耗尽的写入记录到写入日志，连接恢复或下次启动时按顺序重放
+//! 6. 无法重试的失败回滚到修改前的状态，并提示「无法保存 — 重试 / 放弃」
 
 use std::sync::Arc;
 
-use gpui::{App, BorrowAppContext};
-use todos::entity::ItemModel;
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{JournalOp, entity::ItemModel, error::TodoError};
 use tracing::{error, info, warn};
 
 use crate::{
     core::{
         error_handler::{AppError, ErrorHandler, validation},
-        state::{ErrorNotifier, TodoStore, get_store},
+        state::{
+            ErrorNotifier, FailedWrite, SaveFailures, TodoStore, UndoCommand, UndoHistory,
+            WriteJournalState, get_store,
+        },
         tokio_runtime::spawn_db_operation,
         utils::retry::{self, RetryConfig},
     },
@@ -47,8 +52,12 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
     info!("Optimistically adding item with temp ID: {}, content: '{}'", temp_id, item.content);
 
     // 2. ⚡ 立即更新 UI（乐观更新，用户无感知延迟）
+    let optimistic_item = Arc::new(optimistic_item);
     cx.update_global::<TodoStore, _>(|store, _| {
-        store.add_item(Arc::new(optimistic_item.clone()));
+        store.add_item(optimistic_item.clone());
+    });
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::AddItem { item: optimistic_item });
     });
 
     // 3. 🔄 异步保存到数据库（增强版：独立 Runtime + 重试机制）
@@ -57,6 +66,7 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
     let item_id_for_error = item.id.clone(); // 保存 item_id 用于错误处理
 
     let temp_id_for_async = temp_id.clone();
+    let item_for_retry = item.clone();
 
     // 使用 cx.spawn + .detach() 执行异步任务
     // .detach() 确保任务不会因为组件销毁而被取消
@@ -125,8 +135,12 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
                 );
 
                 // ✅ 更新 UI：将临时 ID 替换为真实 ID
+                let saved_item = Arc::new(saved_item);
                 cx.update_global::<TodoStore, _>(|store, _| {
-                    store.replace_item_id(&temp_id_for_async, Arc::new(saved_item.clone()));
+                    store.replace_item_id(&temp_id_for_async, saved_item.clone());
+                });
+                cx.update_global::<UndoHistory, _>(|history, _| {
+                    history.replace_item_id(&temp_id_for_async, &saved_item);
                 });
 
                 // ✅ 标记保存成功
@@ -134,8 +148,19 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
                     results.mark_succeeded(temp_id_for_async.clone());
                     results.mark_succeeded(real_id);
                 });
+                super::journal::replay_write_journal_if_needed(cx);
             },
             Ok(Err(e)) => {
+                // 可重试的错误：任务保留在界面中，写入日志负责之后落盘
+                let retryable = e.is_retryable();
+                if retryable {
+                    let op = JournalOp::AddItem {
+                        temp_id: Some(temp_id_for_async.clone()),
+                        item: (*item_for_retry).clone(),
+                    };
+                    super::journal::queue_write(op, &e, cx);
+                }
+
                 let context = ErrorHandler::handle_with_resource(
                     AppError::Database(Box::new(e)),
                     "add_item_optimistic",
@@ -143,14 +168,15 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
                 );
                 error!("❌ 添加任务失败（重试耗尽）: {}", context.format_user_message());
 
-                // ✅ 显示错误通知
-                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
-                    notifier.set_error(format!(
-                        "添加任务失败：{}。请稍后重试。",
-                        context.format_user_message()
-                    ));
-                });
-
+                if !retryable {
+                    roll_back_write(
+                        FailedWrite::AddItem(item_for_retry),
+                        vec![temp_id_for_async.clone()],
+                        Vec::new(),
+                        context.format_user_message(),
+                        cx,
+                    );
+                }
                 cx.update_global::<crate::core::state::SaveResults, _>(|results, _| {
                     results.mark_failed(temp_id_for_async.clone());
                 });
@@ -158,8 +184,15 @@ pub fn add_item_optimistic(item: Arc<ItemModel>, cx: &mut App) -> String {
             Err(join_err) => {
                 error!("❌ 添加任务异常（任务被取消或 panic）: {:?}", join_err);
 
-                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
-                    notifier.set_error("添加任务时发生内部错误。请稍后重试。".to_string());
+                roll_back_write(
+                    FailedWrite::AddItem(item_for_retry),
+                    vec![temp_id_for_async.clone()],
+                    Vec::new(),
+                    "添加任务时发生内部错误".to_string(),
+                    cx,
+                );
+                cx.update_global::<crate::core::state::SaveResults, _>(|results, _| {
+                    results.mark_failed(temp_id_for_async.clone());
                 });
             },
         }
@@ -177,24 +210,52 @@ pub fn update_item_optimistic(item: Arc<ItemModel>, cx: &mut App) {
         return;
     }
 
-    cx.update_global::<TodoStore, _>(|store, _| {
+    let before = cx.update_global::<TodoStore, _>(|store, _| {
+        let before = store.get_item(&item.id);
         store.update_item(item.clone());
+        before
     });
+    if let Some(before) = before.clone() {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::UpdateItem { before, after: item.clone() });
+        });
+    }
 
     // 为了避免在 Store 未初始化时 panic，异步等待 Store 准备后再执行数据库更新。
     let item_id = item.id.clone();
     let item_for_db = item.clone();
     let db_state = cx.global::<DBState>().clone();
 
+    // 该任务还有没落盘的写入（如新建还在日志中）：排在它们后面，不能直接写数据库
+    let queued = cx.update_global::<WriteJournalState, _>(|state, _| {
+        if !state.has_pending_for(&item.id) {
+            return None;
+        }
+        let reason = TodoError::InternalError("前面的修改尚未保存".to_string());
+        Some(state.journal().append(JournalOp::UpdateItem { item: (*item).clone() }, &reason))
+    });
+    if let Some(result) = queued {
+        if let Err(e) = result {
+            error!("Failed to queue update for {} in write journal: {}", item_id, e);
+        }
+        if db_state.is_store_ready() {
+            let store = get_store(cx);
+            cx.spawn(async move |cx| {
+                super::journal::replay_write_journal_impl(store, cx).await;
+            })
+            .detach();
+        }
+        return;
+    }
+
     cx.spawn(async move |cx| {
         // 等待 Store 初始化，最长 10 秒
         if let Err(e) =
             db_state.wait_for_store_ready(Some(std::time::Duration::from_secs(10))).await
         {
             error!("❌ 等待 Store 就绪超时: {}", e);
-            cx.update_global::<ErrorNotifier, _>(|notifier, _| {
-                notifier.set_error("更新任务失败：Store 未就绪，请稍后重试。".to_string());
-            });
+            let op = JournalOp::UpdateItem { item: (*item_for_db).clone() };
+            super::journal::queue_write(op, &e, cx);
             return;
         }
         let store = db_state.get_store_async().await;
@@ -206,20 +267,32 @@ pub fn update_item_optimistic(item: Arc<ItemModel>, cx: &mut App) {
                      due={:?}",
                     item_id, updated_item.priority, updated_item.content, updated_item.due
                 );
+                super::journal::replay_write_journal_if_needed(cx);
             },
             Err(e) => {
+                // 可重试的错误记录到写入日志；其他错误回滚到修改前
+                let retryable = e.is_retryable();
+                if retryable {
+                    let op = JournalOp::UpdateItem { item: (*item_for_db).clone() };
+                    super::journal::queue_write(op, &e, cx);
+                }
+
                 let context = ErrorHandler::handle_with_resource(
                     AppError::Database(Box::new(e)),
                     "update_item_optimistic",
                     &item_id,
                 );
                 error!("{}", context.format_user_message());
-                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
-                    notifier.set_error(format!(
-                        "更新任务失败：{}。您的更改已保存到本地，稍后会自动重试。",
-                        context.format_user_message()
-                    ));
-                });
+
+                if !retryable {
+                    roll_back_write(
+                        FailedWrite::UpdateItem(item_for_db.clone()),
+                        Vec::new(),
+                        before.into_iter().map(|before| (before, item_for_db.clone())).collect(),
+                        context.format_user_message(),
+                        cx,
+                    );
+                }
             },
         }
     })
@@ -232,20 +305,29 @@ pub fn delete_item_optimistic(item: Arc<ItemModel>, cx: &mut App) {
 
     info!("Optimistically deleting item: {}", item_id);
 
-    cx.update_global::<TodoStore, _>(|store, _| {
-        store.remove_item(&item_id);
+    // 子任务随父任务一起移入回收站
+    let removed_items = cx.update_global::<TodoStore, _>(|store, _| {
+        let mut removed = vec![store.get_item(&item_id).unwrap_or_else(|| item.clone())];
+        removed.extend(store.descendant_items(&item_id));
+        for removed_item in &removed {
+            store.remove_item(&removed_item.id);
+        }
+        removed
+    });
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::DeleteItem { item: removed_items[0].clone() });
     });
 
-    let item_for_recovery = item.clone();
     let store = get_store(cx);
     let item_clone = item.clone();
 
     cx.spawn(async move |cx| {
-        let result = state_service::del_item_with_store(item_clone.clone(), store).await;
+        let result = state_service::del_item_with_store(item_clone.clone(), store.clone()).await;
 
         match result {
             Ok(_) => {
                 info!("Successfully deleted item from database: {}", item_id);
+                super::trash::refresh_trash_impl(store, cx).await;
             },
             Err(e) => {
                 let context = ErrorHandler::handle_with_resource(
@@ -256,7 +338,9 @@ pub fn delete_item_optimistic(item: Arc<ItemModel>, cx: &mut App) {
                 error!("{}", context.format_user_message());
 
                 cx.update_global::<TodoStore, _>(|store, _| {
-                    store.add_item(item_for_recovery.clone());
+                    for removed_item in &removed_items {
+                        store.add_item(removed_item.clone());
+                    }
                 });
 
                 cx.update_global::<ErrorNotifier, _>(|notifier, _| {
@@ -284,6 +368,9 @@ pub fn set_item_pinned_optimistic(item: Arc<ItemModel>, pinned: bool, cx: &mut A
     cx.update_global::<TodoStore, _>(|store, _| {
         store.update_item(Arc::new(updated_item.clone()));
     });
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::PinItem { item_id: item_id.clone(), pinned });
+    });
 
     let store = get_store(cx);
     let item_id_clone = item_id.clone();
@@ -325,7 +412,6 @@ pub fn set_item_pinned_optimistic(item: Arc<ItemModel>, pinned: bool, cx: &mut A
 /// 乐观完成任务
 pub fn complete_item_optimistic(item: Arc<ItemModel>, checked: bool, cx: &mut App) {
     let item_id = item.id.clone();
-    let old_checked = item.checked;
 
     info!(
         "Optimistically {} item: {}",
@@ -334,11 +420,44 @@ pub fn complete_item_optimistic(item: Arc<ItemModel>, checked: bool, cx: &mut Ap
     );
 
     let mut updated_item = (*item).clone();
-    updated_item.checked = checked;
-    updated_item.completed_at = if checked { Some(chrono::Utc::now().naive_utc()) } else { None };
+    // 🔁 重复任务：完成后推进到下一次到期日期并保持未完成（与 ItemService::complete_item 一致）
+    let next_due =
+        if checked { item.due_date().and_then(|due| due.next_occurrence()) } else { None };
+    let is_recurring_roll = next_due.is_some();
+    if let Some(next_due) = next_due {
+        updated_item.set_due_date(Some(next_due));
+    } else {
+        updated_item.checked = checked;
+        updated_item.completed_at =
+            if checked { Some(chrono::Utc::now().naive_utc()) } else { None };
+    }
 
-    cx.update_global::<TodoStore, _>(|store, _| {
+    let (before, reset_subitems) = cx.update_global::<TodoStore, _>(|store, _| {
+        let before = store.get_item(&item_id).unwrap_or_else(|| item.clone());
         store.update_item(Arc::new(updated_item.clone()));
+
+        // 重复任务滚动时子任务全部重置为未完成，保留原值用于失败回滚
+        let mut reset_subitems = Vec::new();
+        if is_recurring_roll {
+            for sub in store.descendant_items(&item_id) {
+                if sub.checked {
+                    let mut reset = (*sub).clone();
+                    reset.checked = false;
+                    reset.completed_at = None;
+                    store.update_item(Arc::new(reset));
+                    reset_subitems.push(sub);
+                }
+            }
+        }
+        (before, reset_subitems)
+    });
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::CompleteItem {
+            before,
+            checked,
+            recurring_roll: is_recurring_roll,
+            reset_subitems: reset_subitems.clone(),
+        });
     });
 
     let store = get_store(cx);
@@ -370,12 +489,11 @@ pub fn complete_item_optimistic(item: Arc<ItemModel>, checked: bool, cx: &mut Ap
                 );
                 error!("{}", context.format_user_message());
 
-                let mut reverted_item = updated_item.clone();
-                reverted_item.checked = old_checked;
-                reverted_item.completed_at =
-                    if old_checked { Some(chrono::Utc::now().naive_utc()) } else { None };
                 cx.update_global::<TodoStore, _>(|store, _| {
-                    store.update_item(Arc::new(reverted_item));
+                    store.update_item(item_clone.clone());
+                    for sub in reset_subitems {
+                        store.update_item(sub);
+                    }
                 });
 
                 cx.update_global::<ErrorNotifier, _>(|notifier, _| {
@@ -391,6 +509,50 @@ pub fn complete_item_optimistic(item: Arc<ItemModel>, checked: bool, cx: &mut Ap
     .detach();
 }
 
+/// 保存最终失败：恢复乐观更新前的状态，并提示「无法保存 — 重试 / 放弃」
+///
+/// - `removed_ids`：没有落盘的新任务（临时 ID），直接移除
+/// - `changes`：`(修改前, 乐观写入的值)`；任务在此期间又被修改过时保留新的修改
+pub(super) fn roll_back_write(
+    write: FailedWrite,
+    removed_ids: Vec<String>,
+    changes: Vec<(Arc<ItemModel>, Arc<ItemModel>)>,
+    error: String,
+    cx: &mut AsyncApp,
+) {
+    warn!("Rolling back {}: {}", write.describe(), error);
+    cx.update_global::<TodoStore, _>(|store, _| {
+        let restored: Vec<Arc<ItemModel>> = changes
+            .into_iter()
+            .filter(|(_, after)| store.get_item(&after.id).is_some_and(|current| current == *after))
+            .map(|(before, _)| before)
+            .collect();
+        store.rollback_items(&removed_ids, &restored);
+    });
+    if !removed_ids.is_empty() {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            for id in &removed_ids {
+                history.forget_added_item(id);
+            }
+        });
+    }
+    cx.update_global::<SaveFailures, _>(|failures, _| {
+        failures.push(write, error);
+    });
+}
+
+/// 重新执行保存失败的写入（「无法保存」通知中的重试）
+pub fn retry_failed_write(write: FailedWrite, cx: &mut App) {
+    info!("Retrying {}", write.describe());
+    match write {
+        FailedWrite::AddItem(item) => {
+            add_item_optimistic(item, cx);
+        },
+        FailedWrite::UpdateItem(item) => update_item_optimistic(item, cx),
+        FailedWrite::BatchUpdateItems(items) => super::batch::batch_update_items(items, cx),
+    }
+}
+
 /// 获取连接池统计信息（用于诊断）
 ///
 /// 尝试从底层数据库连接中获取连接池状态。
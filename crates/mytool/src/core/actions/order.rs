@@ -0,0 +1,102 @@
+//! 手动排序
+//!
+//! 上移 / 下移换算成相对相邻任务的位置，排序键由 ItemService 计算；
+//! 写入成功后只把位置相关的字段合并回 TodoStore。
+
+use std::sync::Arc;
+
+use gpui::{App, BorrowAppContext};
+use todos::{entity::ItemModel, services::ItemPosition};
+use tracing::{error, info};
+
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, TodoStore, get_store},
+};
+
+/// 排序操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReorderStep {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+impl ReorderStep {
+    /// 在已排序的列表中计算目标位置；任务不在列表中或已在边界时返回 None
+    pub fn position(self, list: &[Arc<ItemModel>], item_id: &str) -> Option<ItemPosition> {
+        let ix = list.iter().position(|item| item.id == item_id)?;
+        match self {
+            Self::Up => ix.checked_sub(1).map(|prev| ItemPosition::Before(list[prev].id.clone())),
+            Self::Down => list.get(ix + 1).map(|next| ItemPosition::After(next.id.clone())),
+            Self::Top => (ix > 0).then_some(ItemPosition::Top),
+            Self::Bottom => (ix + 1 < list.len()).then_some(ItemPosition::Bottom),
+        }
+    }
+}
+
+/// 在项目 / 分区内调整任务顺序（child_order）
+pub fn reorder_item(item: Arc<ItemModel>, step: ReorderStep, cx: &mut App) {
+    let siblings = cx.global::<TodoStore>().sibling_items(&item);
+    if let Some(position) = step.position(&siblings, &item.id) {
+        spawn_reorder(item.id.clone(), position, false, cx);
+    }
+}
+
+/// 在"今天"列表中调整任务顺序（day_order），不在今天列表中的任务忽略
+pub fn reorder_today_item(item: Arc<ItemModel>, step: ReorderStep, cx: &mut App) {
+    let today = cx.global::<TodoStore>().today_items();
+    if let Some(position) = step.position(&today, &item.id) {
+        spawn_reorder(item.id.clone(), position, true, cx);
+    }
+}
+
+/// 把服务端移动后的任务合并回 TodoStore
+///
+/// 服务端返回的是数据库行，labels 等字段以 TodoStore 中的为准，只合并位置相关的字段。
+pub(super) fn apply_moved_items(todo_store: &mut TodoStore, moved: Vec<ItemModel>) {
+    for moved in moved {
+        if let Some(current) = todo_store.get_item(&moved.id) {
+            let mut item = (*current).clone();
+            item.project_id = moved.project_id;
+            item.section_id = moved.section_id;
+            item.parent_id = moved.parent_id;
+            item.child_order = moved.child_order;
+            item.day_order = moved.day_order;
+            item.updated_at = moved.updated_at;
+            todo_store.update_item(Arc::new(item));
+        }
+    }
+}
+
+fn spawn_reorder(item_id: String, position: ItemPosition, in_day: bool, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result = if in_day {
+            crate::state_service::move_item_in_day_with_store(&item_id, position, store).await
+        } else {
+            crate::state_service::move_item_with_store(&item_id, position, store).await
+        };
+        match result {
+            Ok(changed) => {
+                info!("Reordered item {}, {} rows written", item_id, changed.len());
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    apply_moved_items(todo_store, changed);
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "reorder_item",
+                    &item_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("调整顺序失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
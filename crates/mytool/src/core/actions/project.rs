@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
-use gpui::App;
+use gpui::{App, BorrowAppContext};
 use todos::entity::ProjectModel;
 use tracing::{error, info};
 
 use crate::core::{
     error_handler::{AppError, ErrorHandler, validation},
-    state::{TodoStore, get_store},
+    state::{TodoStore, UndoCommand, UndoHistory, get_store},
 };
 
 // 添加 project（使用增量更新和全局 Store）
@@ -26,7 +26,10 @@ pub fn add_project(project: Arc<ProjectModel>, cx: &mut App) {
                 // 增量更新：只添加新项目到 TodoStore
                 let arc_project = Arc::new(new_project);
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
-                    todo_store.add_project(arc_project);
+                    todo_store.add_project(arc_project.clone());
+                });
+                cx.update_global::<UndoHistory, _>(|history, _| {
+                    history.record(UndoCommand::AddProject { project: arc_project });
                 });
             },
             Err(e) => {
@@ -51,6 +54,12 @@ pub fn update_project(project: Arc<ProjectModel>, cx: &mut App) {
         return;
     }
 
+    if let Some(before) = cx.global::<TodoStore>().get_project(&project.id) {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::UpdateProject { before, after: project.clone() });
+        });
+    }
+
     let store = get_store(cx);
     cx.spawn(async move |cx| {
         match crate::state_service::mod_project_with_store(project.clone(), store).await {
@@ -77,17 +86,22 @@ pub fn update_project(project: Arc<ProjectModel>, cx: &mut App) {
 
 // 删除 project（使用增量更新和全局 Store）
 pub fn delete_project(project: Arc<ProjectModel>, cx: &mut App) {
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::DeleteProject { project: project.clone() });
+    });
+
     let store = get_store(cx);
     let project_id = project.id.clone();
 
     cx.spawn(async move |cx| {
-        match crate::state_service::del_project_with_store(project.clone(), store).await {
+        match crate::state_service::del_project_with_store(project.clone(), store.clone()).await {
             Ok(_) => {
                 info!("Successfully deleted project: {}", project_id);
-                // 增量更新：只删除指定的项目
+                // 增量更新：移除项目树及其分区、任务（已移入回收站）
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
-                    todo_store.remove_project(&project_id);
+                    todo_store.remove_project_tree(&project_id);
                 });
+                super::trash::refresh_trash_impl(store, cx).await;
             },
             Err(e) => {
                 let context = ErrorHandler::handle_with_resource(
@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use gpui::App;
+use gpui::{App, BorrowAppContext};
 use todos::entity::SectionModel;
 
-use crate::core::state::{TodoStore, get_store};
+use crate::core::state::{TodoStore, UndoCommand, UndoHistory, get_store};
 
 // Add Section（使用增量更新和全局 Store）
 pub fn add_section(section: Arc<SectionModel>, cx: &mut App) {
@@ -14,7 +14,10 @@ pub fn add_section(section: Arc<SectionModel>, cx: &mut App) {
                 // 增量更新：只添加新分区到 TodoStore
                 let arc_section = Arc::new(new_section);
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
-                    todo_store.add_section(arc_section);
+                    todo_store.add_section(arc_section.clone());
+                });
+                cx.update_global::<UndoHistory, _>(|history, _| {
+                    history.record(UndoCommand::AddSection { section: arc_section });
                 });
             },
             Err(e) => tracing::error!("add_section failed: {:?}", e),
@@ -25,6 +28,12 @@ pub fn add_section(section: Arc<SectionModel>, cx: &mut App) {
 
 // 修改 section（使用增量更新和全局 Store）
 pub fn update_section(section: Arc<SectionModel>, cx: &mut App) {
+    if let Some(before) = cx.global::<TodoStore>().get_section(&section.id) {
+        cx.update_global::<UndoHistory, _>(|history, _| {
+            history.record(UndoCommand::UpdateSection { before, after: section.clone() });
+        });
+    }
+
     let store = get_store(cx);
     cx.spawn(async move |cx| {
         match crate::state_service::mod_section_with_store(section.clone(), store).await {
@@ -43,15 +52,23 @@ pub fn update_section(section: Arc<SectionModel>, cx: &mut App) {
 
 // 删除 section（使用增量更新和全局 Store）
 pub fn delete_section(section: Arc<SectionModel>, cx: &mut App) {
+    cx.update_global::<UndoHistory, _>(|history, _| {
+        history.record(UndoCommand::DeleteSection { section: section.clone() });
+    });
+
     let store = get_store(cx);
     let section_id = section.id.clone();
     cx.spawn(async move |cx| {
-        match crate::state_service::del_section_with_store(section.clone(), store).await {
+        match crate::state_service::del_section_with_store(section.clone(), store.clone()).await {
             Ok(_) => {
-                // 增量更新：只删除指定的分区
+                // 增量更新：移除分区及其任务（已移入回收站）
                 cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    for item in todo_store.items_by_section(&section_id) {
+                        todo_store.remove_item_tree(&item.id);
+                    }
                     todo_store.remove_section(&section_id);
                 });
+                super::trash::refresh_trash_impl(store, cx).await;
             },
             Err(e) => tracing::error!("delete_section failed: {:?}", e),
         }
@@ -0,0 +1,145 @@
+//! 子任务树
+//!
+//! 缩进 / 取消缩进 / 跨项目移动由 ItemService 计算新的位置，写入成功后合并回 TodoStore；
+//! 折叠状态先乐观更新，写入失败时回滚。
+
+use std::sync::Arc;
+
+use gpui::{App, BorrowAppContext};
+use todos::entity::ItemModel;
+use tracing::{error, info};
+
+use super::order::apply_moved_items;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, TodoStore, get_store},
+};
+
+/// 缩进为前一个兄弟任务的子任务
+pub fn indent_item(item: Arc<ItemModel>, cx: &mut App) {
+    spawn_reparent(item.id.clone(), true, cx);
+}
+
+/// 取消缩进，移到父任务之后
+pub fn outdent_item(item: Arc<ItemModel>, cx: &mut App) {
+    if item.parent_id.as_deref().is_none_or(|parent| parent.is_empty()) {
+        return;
+    }
+    spawn_reparent(item.id.clone(), false, cx);
+}
+
+/// 把任务连同子孙移到其他项目 / 分区，`project_id` 为 None 表示收件箱
+///
+/// 移到其他项目时旧分区不会被带过去：`section_id` 为 None 时放在目标项目的分区之外。
+pub fn move_items_to_project(
+    items: Vec<Arc<ItemModel>>,
+    project_id: Option<String>,
+    section_id: Option<String>,
+    cx: &mut App,
+) {
+    if items.is_empty() {
+        return;
+    }
+    let item_ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result = crate::state_service::move_items_with_store(
+            &item_ids,
+            project_id.as_deref(),
+            section_id.as_deref(),
+            store,
+        )
+        .await;
+        match result {
+            Ok(changed) => {
+                info!("Moved {} items, {} rows written", item_ids.len(), changed.len());
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    apply_moved_items(todo_store, changed);
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "move_items_to_project",
+                    &item_ids.join(","),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("移动任务失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+/// 折叠 / 展开子任务
+pub fn toggle_item_collapsed(item: Arc<ItemModel>, cx: &mut App) {
+    let collapsed = !item.collapsed;
+    cx.update_global::<TodoStore, _>(|todo_store, _| {
+        set_collapsed_in_store(todo_store, &item.id, collapsed);
+    });
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::set_item_collapsed_with_store(&item.id, collapsed, store).await
+        {
+            Ok(_) => info!("Item {} collapsed: {}", item.id, collapsed),
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "toggle_item_collapsed",
+                    &item.id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    set_collapsed_in_store(todo_store, &item.id, !collapsed);
+                });
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier
+                        .set_error(format!("折叠子任务失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+fn set_collapsed_in_store(todo_store: &mut TodoStore, item_id: &str, collapsed: bool) {
+    if let Some(current) = todo_store.get_item(item_id) {
+        let mut item = (*current).clone();
+        item.collapsed = collapsed;
+        todo_store.update_item(Arc::new(item));
+    }
+}
+
+fn spawn_reparent(item_id: String, indent: bool, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result = if indent {
+            crate::state_service::indent_item_with_store(&item_id, store).await
+        } else {
+            crate::state_service::outdent_item_with_store(&item_id, store).await
+        };
+        match result {
+            Ok(changed) => {
+                info!("Moved subtree {}, {} rows written", item_id, changed.len());
+                cx.update_global::<TodoStore, _>(|todo_store, _| {
+                    apply_moved_items(todo_store, changed);
+                });
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    if indent { "indent_item" } else { "outdent_item" },
+                    &item_id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("调整层级失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
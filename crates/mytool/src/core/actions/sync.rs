@@ -0,0 +1,149 @@
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{
+    error::TodoError,
+    services::{CalDavConfig, TodoistConfig},
+};
+use tracing::{error, info};
+
+use super::trash::reload_all_impl;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, get_store},
+    tokio_runtime::spawn_db_operation,
+};
+
+// 添加 CalDAV 日历：先创建数据源和项目，再做一次完整同步
+pub fn add_caldav_calendar(config: CalDavConfig, cx: &mut App) {
+    let store = get_store(cx);
+    let calendar_url = config.calendar_url.clone();
+    cx.spawn(async move |cx| {
+        // 同步走 HTTP 请求，需要在 tokio runtime 中执行
+        let result = spawn_db_operation({
+            let store = store.clone();
+            async move {
+                let source =
+                    crate::state_service::add_caldav_source_with_store(config, store.clone())
+                        .await?;
+                crate::state_service::sync_caldav_source_with_store(source.id, store).await
+            }
+        })
+        .await
+        .unwrap_or_else(|e| Err(TodoError::InternalError(e.to_string())));
+
+        match result {
+            Ok(report) => {
+                info!("Added CalDAV calendar {}: {}", calendar_url, report.summary());
+            },
+            Err(e) => notify_sync_error(e, "add_caldav_calendar", &calendar_url, cx),
+        }
+        // 同步失败时数据源和项目已经建好，同样需要刷新
+        reload_all_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 同步所有 CalDAV 日历，有变化时整体重新加载
+pub fn sync_calendars(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result =
+            spawn_db_operation(crate::state_service::sync_all_caldav_with_store(store.clone()))
+                .await
+                .unwrap_or_else(|e| Err(TodoError::InternalError(e.to_string())));
+
+        let results = match result {
+            Ok(results) => results,
+            Err(e) => {
+                notify_sync_error(e, "sync_calendars", "caldav", cx);
+                return;
+            },
+        };
+        if results.is_empty() {
+            info!("No CalDAV calendars to sync");
+            return;
+        }
+
+        let mut changed = false;
+        for (name, result) in results {
+            match result {
+                Ok(report) => {
+                    info!("Synced CalDAV calendar {}: {}", name, report.summary());
+                    changed |= report.has_changes();
+                },
+                Err(e) => notify_sync_error(e, "sync_caldav", &name, cx),
+            }
+        }
+        if changed {
+            reload_all_impl(store, cx).await;
+        }
+    })
+    .detach();
+}
+
+// 添加 Todoist 账号并立即做一次全量同步
+pub fn add_todoist_account(config: TodoistConfig, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result = spawn_db_operation({
+            let store = store.clone();
+            async move {
+                crate::state_service::add_todoist_source_with_store(config, store.clone()).await?;
+                crate::state_service::sync_todoist_with_store(store).await
+            }
+        })
+        .await
+        .unwrap_or_else(|e| Err(TodoError::InternalError(e.to_string())));
+
+        match result {
+            Ok(report) => {
+                if let Some(report) = report {
+                    info!("Added Todoist account: {}", report.summary());
+                }
+            },
+            Err(e) => notify_sync_error(e, "add_todoist_account", "todoist", cx),
+        }
+        reload_all_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 同步 Todoist：离线时修改留在队列中，下次同步继续发送
+pub fn sync_todoist(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let result =
+            spawn_db_operation(crate::state_service::sync_todoist_with_store(store.clone()))
+                .await
+                .unwrap_or_else(|e| Err(TodoError::InternalError(e.to_string())));
+
+        match result {
+            Ok(None) => info!("No Todoist account to sync"),
+            Ok(Some(report)) => {
+                info!("Synced Todoist: {}", report.summary());
+                if !report.rejected.is_empty() {
+                    cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                        notifier.set_error(format!(
+                            "Todoist 拒绝了 {} 条修改：{}",
+                            report.rejected.len(),
+                            report.rejected.join("；")
+                        ));
+                    });
+                }
+                if report.has_changes() {
+                    reload_all_impl(store, cx).await;
+                }
+            },
+            Err(e) => notify_sync_error(e, "sync_todoist", "todoist", cx),
+        }
+    })
+    .detach();
+}
+
+fn notify_sync_error(e: TodoError, operation: &str, resource: &str, cx: &mut AsyncApp) {
+    let context =
+        ErrorHandler::handle_with_resource(AppError::Database(Box::new(e)), operation, resource);
+    error!("{}", context.format_user_message());
+    cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+        notifier.set_error(format!("同步失败：{}", context.format_user_message()));
+    });
+}
@@ -0,0 +1,94 @@
+//! 项目模板
+//!
+//! 保存模板只写文件，不改动数据；按模板新建项目后整体重新加载。
+
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::NaiveDate;
+use gpui::{App, BorrowAppContext};
+use todos::{TEMPLATE_EXTENSION, entity::ProjectModel};
+use tracing::{error, info};
+
+use super::trash::reload_all_impl;
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, get_store},
+};
+
+// 把项目保存为模板：选择保存位置后写出模板文件
+pub fn save_project_template(project: Arc<ProjectModel>, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Project template", &[TEMPLATE_EXTENSION])
+            .set_file_name(format!("{}.template.{}", project.name, TEMPLATE_EXTENSION))
+            .save_file()
+            .await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+
+        match crate::state_service::save_project_template_with_store(
+            &project.id,
+            path.clone(),
+            store,
+        )
+        .await
+        {
+            Ok(template) => info!(
+                "Saved project {} as template ({} tasks) to {}",
+                project.id,
+                template.items.len(),
+                path.display()
+            ),
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "save_project_template",
+                    &project.id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("保存模板失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 按模板文件新建项目，相对日期以 `start` 为第 0 天
+pub fn instantiate_template(path: PathBuf, name: Option<String>, start: NaiveDate, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::instantiate_template_file_with_store(
+            path.clone(),
+            name,
+            start,
+            store.clone(),
+        )
+        .await
+        {
+            Ok(project) => {
+                info!("Created project {} from template {}", project.id, path.display());
+                reload_all_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "instantiate_template",
+                    &path.display().to_string(),
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!(
+                        "按模板新建项目失败：{}",
+                        context.format_user_message()
+                    ));
+                });
+            },
+        }
+    })
+    .detach();
+}
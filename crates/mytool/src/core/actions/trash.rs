@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use gpui::{App, AsyncApp, BorrowAppContext};
+use todos::{
+    Store,
+    services::{TrashEntry, TrashObjectType},
+};
+use tracing::{error, info};
+
+use crate::core::{
+    error_handler::{AppError, ErrorHandler},
+    state::{ErrorNotifier, TodoStore, get_store},
+};
+
+// 刷新回收站列表
+pub fn load_trash(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        refresh_trash_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 从回收站恢复
+pub fn restore_from_trash(entry: Arc<TrashEntry>, cx: &mut App) {
+    restore_object(entry.object_type, entry.id.clone(), cx);
+}
+
+// 按类型和 ID 恢复（恢复后重新加载各数据域，子对象随根对象一起回来）
+pub fn restore_object(object_type: TrashObjectType, id: String, cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::restore_object_with_store(object_type, &id, store.clone()).await
+        {
+            Ok(_) => {
+                info!("Successfully restored {} from trash: {}", object_type.as_str(), id);
+                reload_all_impl(store.clone(), cx).await;
+                refresh_trash_impl(store, cx).await;
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "restore_from_trash",
+                    &id,
+                );
+                error!("{}", context.format_user_message());
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("恢复失败：{}", context.format_user_message()));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+// 彻底删除回收站中的对象
+pub fn purge_from_trash(entry: Arc<TrashEntry>, cx: &mut App) {
+    let entry_id = entry.id.clone();
+    cx.update_global::<TodoStore, _>(|todo_store, _| {
+        let trash = todo_store.trash.iter().filter(|e| e.id != entry_id).map(|e| (**e).clone());
+        todo_store.set_trash(trash.collect());
+    });
+
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::purge_trash_with_store(entry.clone(), store.clone()).await {
+            Ok(_) => {
+                info!(
+                    "Successfully purged {} from trash: {}",
+                    entry.object_type.as_str(),
+                    entry.id
+                );
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_resource(
+                    AppError::Database(Box::new(e)),
+                    "purge_from_trash",
+                    &entry.id,
+                );
+                error!("{}", context.format_user_message());
+                refresh_trash_impl(store, cx).await;
+            },
+        }
+    })
+    .detach();
+}
+
+// 清空回收站
+pub fn empty_trash(cx: &mut App) {
+    let store = get_store(cx);
+    cx.spawn(async move |cx| {
+        match crate::state_service::empty_trash_with_store(store.clone()).await {
+            Ok(count) => {
+                info!("Emptied trash: {} entries purged", count);
+            },
+            Err(e) => {
+                let context = ErrorHandler::handle_with_location(
+                    AppError::Database(Box::new(e)),
+                    "empty_trash",
+                );
+                error!("{}", context.format_user_message());
+            },
+        }
+        refresh_trash_impl(store, cx).await;
+    })
+    .detach();
+}
+
+// 重新加载回收站列表
+pub(crate) async fn refresh_trash_impl(store: Arc<Store>, cx: &mut AsyncApp) {
+    match crate::state_service::load_trash_with_store(store).await {
+        Ok(trash) => {
+            cx.update_global::<TodoStore, _>(|todo_store, _| {
+                todo_store.set_trash(trash);
+            });
+        },
+        Err(e) => {
+            let context =
+                ErrorHandler::handle_with_location(AppError::Database(Box::new(e)), "load_trash");
+            error!("{}", context.format_user_message());
+        },
+    }
+}
+
+// 恢复 / 导入可能同时带回项目、分区、任务和标签，整体重新加载
+pub(crate) async fn reload_all_impl(store: Arc<Store>, cx: &mut AsyncApp) {
+    let (items_r, projects_r, sections_r, labels_r) = tokio::join!(
+        crate::state_service::load_items_with_store(store.clone()),
+        crate::state_service::load_projects_with_store(store.clone()),
+        crate::state_service::load_sections_with_store(store.clone()),
+        crate::state_service::load_labels_with_store(store),
+    );
+
+    cx.update_global::<TodoStore, _>(|todo_store, _| {
+        if let Ok(items) = items_r {
+            todo_store.set_items(items);
+        }
+        if let Ok(projects) = projects_r {
+            todo_store.set_projects(projects);
+        }
+        if let Ok(sections) = sections_r {
+            todo_store.set_sections(sections);
+        }
+        if let Ok(labels) = labels_r {
+            todo_store.set_labels(labels);
+        }
+    });
+}
@@ -0,0 +1,227 @@
+//! 撤销 / 重做
+//!
+//! 历史由各 action 在变更时记录（见 `state::UndoHistory`），
+//! 这里把记录的快照按相反方向交回原有的 action 重放：
+//! 任务走乐观更新路径，删除通过回收站恢复，保证 TodoStore 与数据库一致。
+
+use std::sync::Arc;
+
+use gpui::{App, BorrowAppContext};
+use todos::{entity::ItemModel, services::TrashObjectType};
+use tracing::{info, warn};
+
+use super::{
+    batch_update_items, complete_item_optimistic, delete_item_optimistic, delete_label,
+    delete_project, delete_section, restore_object, set_item_pinned_optimistic,
+    update_item_optimistic, update_label, update_project, update_section,
+};
+use crate::core::state::{TodoStore, UndoCommand, UndoHistory};
+
+/// 撤销最近一次修改，返回被撤销操作的描述；没有可撤销的操作时返回 None
+pub fn undo(cx: &mut App) -> Option<&'static str> {
+    let command = cx.update_global::<UndoHistory, _>(|history, _| history.pop_undo())?;
+    let description = command.description();
+
+    let applied = replay(cx, |cx| apply_undo(&command, cx));
+    if applied {
+        info!("Undo: {}", description);
+        cx.update_global::<UndoHistory, _>(|history, _| history.push_redo(command));
+    } else {
+        warn!("Undo skipped, target no longer available: {}", description);
+    }
+    applied.then_some(description)
+}
+
+/// 重做最近一次撤销的修改，返回操作描述；没有可重做的操作时返回 None
+pub fn redo(cx: &mut App) -> Option<&'static str> {
+    let command = cx.update_global::<UndoHistory, _>(|history, _| history.pop_redo())?;
+    let description = command.description();
+
+    let applied = replay(cx, |cx| apply_redo(&command, cx));
+    if applied {
+        info!("Redo: {}", description);
+        cx.update_global::<UndoHistory, _>(|history, _| history.push_undo(command));
+    } else {
+        warn!("Redo skipped, target no longer available: {}", description);
+    }
+    applied.then_some(description)
+}
+
+// 重放期间 action 不再记录历史，避免撤销本身被当作新的修改清空重做栈
+fn replay(cx: &mut App, f: impl FnOnce(&mut App) -> bool) -> bool {
+    cx.update_global::<UndoHistory, _>(|history, _| history.set_replaying(true));
+    let applied = f(cx);
+    cx.update_global::<UndoHistory, _>(|history, _| history.set_replaying(false));
+    applied
+}
+
+// 取任务的当前版本；新增任务尚未落库（仍是临时 ID）时返回 None
+fn current_item(id: &str, cx: &App) -> Option<Arc<ItemModel>> {
+    let store = cx.global::<TodoStore>();
+    let id = store.get_real_id(id).map(String::as_str).unwrap_or(id);
+    if id.starts_with("temp_") {
+        return None;
+    }
+    store.get_item(id)
+}
+
+fn apply_undo(command: &UndoCommand, cx: &mut App) -> bool {
+    match command {
+        UndoCommand::AddItem { item } => match current_item(&item.id, cx) {
+            Some(current) => {
+                delete_item_optimistic(current, cx);
+                true
+            },
+            None => false,
+        },
+        UndoCommand::UpdateItem { before, .. } => {
+            update_item_optimistic(before.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteItem { item } => {
+            restore_object(TrashObjectType::Item, item.id.clone(), cx);
+            true
+        },
+        UndoCommand::CompleteItem { before, checked, recurring_roll, reset_subitems } => {
+            if *recurring_roll {
+                // 重复任务滚动改了到期日并重置了子任务，直接写回完成前的快照
+                let mut items = vec![before.clone()];
+                items.extend(reset_subitems.iter().cloned());
+                batch_update_items(items, cx);
+                return true;
+            }
+            match current_item(&before.id, cx) {
+                Some(current) => {
+                    complete_item_optimistic(current, !checked, cx);
+                    true
+                },
+                None => false,
+            }
+        },
+        UndoCommand::PinItem { item_id, pinned } => match current_item(item_id, cx) {
+            Some(current) => {
+                set_item_pinned_optimistic(current, !pinned, cx);
+                true
+            },
+            None => false,
+        },
+        UndoCommand::BatchUpdateItems { before, .. } => {
+            batch_update_items(before.clone(), cx);
+            true
+        },
+        UndoCommand::AddProject { project } => {
+            delete_project(project.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateProject { before, .. } => {
+            update_project(before.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteProject { project } => {
+            restore_object(TrashObjectType::Project, project.id.clone(), cx);
+            true
+        },
+        UndoCommand::AddSection { section } => {
+            delete_section(section.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateSection { before, .. } => {
+            update_section(before.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteSection { section } => {
+            restore_object(TrashObjectType::Section, section.id.clone(), cx);
+            true
+        },
+        UndoCommand::AddLabel { label } => {
+            delete_label(label.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateLabel { before, .. } => {
+            update_label(before.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteLabel { label } => {
+            restore_object(TrashObjectType::Label, label.id.clone(), cx);
+            true
+        },
+    }
+}
+
+fn apply_redo(command: &UndoCommand, cx: &mut App) -> bool {
+    match command {
+        // 撤销新增时任务已移入回收站，重做即恢复，保留原 ID
+        UndoCommand::AddItem { item } => {
+            if item.id.starts_with("temp_") {
+                return false;
+            }
+            restore_object(TrashObjectType::Item, item.id.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateItem { after, .. } => {
+            update_item_optimistic(after.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteItem { item } => match current_item(&item.id, cx) {
+            Some(current) => {
+                delete_item_optimistic(current, cx);
+                true
+            },
+            None => false,
+        },
+        UndoCommand::CompleteItem { before, checked, .. } => match current_item(&before.id, cx) {
+            Some(current) => {
+                complete_item_optimistic(current, *checked, cx);
+                true
+            },
+            None => false,
+        },
+        UndoCommand::PinItem { item_id, pinned } => match current_item(item_id, cx) {
+            Some(current) => {
+                set_item_pinned_optimistic(current, *pinned, cx);
+                true
+            },
+            None => false,
+        },
+        UndoCommand::BatchUpdateItems { after, .. } => {
+            batch_update_items(after.clone(), cx);
+            true
+        },
+        UndoCommand::AddProject { project } => {
+            restore_object(TrashObjectType::Project, project.id.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateProject { after, .. } => {
+            update_project(after.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteProject { project } => {
+            delete_project(project.clone(), cx);
+            true
+        },
+        UndoCommand::AddSection { section } => {
+            restore_object(TrashObjectType::Section, section.id.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateSection { after, .. } => {
+            update_section(after.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteSection { section } => {
+            delete_section(section.clone(), cx);
+            true
+        },
+        UndoCommand::AddLabel { label } => {
+            restore_object(TrashObjectType::Label, label.id.clone(), cx);
+            true
+        },
+        UndoCommand::UpdateLabel { after, .. } => {
+            update_label(after.clone(), cx);
+            true
+        },
+        UndoCommand::DeleteLabel { label } => {
+            delete_label(label.clone(), cx);
+            true
+        },
+    }
+}
@@ -366,6 +366,19 @@ pub mod validation {
         Ok(())
     }
 
+    /// 验证过滤器名称与表达式
+    pub fn validate_filter(name: &str, query: &str) -> AppResult<()> {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("过滤器名称不能为空".to_string()));
+        }
+
+        if let Err(e) = todos::FilterQuery::parse(query) {
+            return Err(AppError::Validation(format!("过滤表达式无效：{}", e)));
+        }
+
+        Ok(())
+    }
+
     /// 清理 HTML 内容
     pub fn sanitize_html(content: &str) -> String {
         content
@@ -415,6 +428,13 @@ mod tests {
         assert!(validation::validate_task_content("<script>alert('xss')</script>").is_err());
     }
 
+    #[test]
+    fn test_validation_filter() {
+        assert!(validation::validate_filter("Urgent", "(today | overdue) & p1").is_ok());
+        assert!(validation::validate_filter(" ", "p1").is_err());
+        assert!(validation::validate_filter("Urgent", "today &").is_err());
+    }
+
     #[test]
     fn test_sanitize_html() {
         let input = "<script>alert('test')</script>";
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::{ProjectModel, SectionModel},
+    error::TodoError,
+    services::ArchivedProject,
+};
+
+// ==================== 加载归档 ====================
+
+/// 使用全局 Store 加载归档的项目（推荐）
+pub async fn load_archive_with_store(store: Arc<Store>) -> Result<Vec<ArchivedProject>, TodoError> {
+    store.list_archive().await
+}
+
+// ==================== 归档 / 取消归档 ====================
+
+/// 归档项目（含子项目、分区和未完成任务）
+pub async fn archive_project_with_store(
+    project_id: &str,
+    store: Arc<Store>,
+) -> Result<ProjectModel, TodoError> {
+    store.archive_project(project_id).await
+}
+
+/// 取消归档，同一次归档的子对象一起还原
+pub async fn unarchive_project_with_store(
+    project_id: &str,
+    store: Arc<Store>,
+) -> Result<ProjectModel, TodoError> {
+    store.unarchive_project(project_id).await
+}
+
+/// 归档 / 取消归档分区（含分区中未完成的任务）
+pub async fn set_section_archived_with_store(
+    section_id: &str,
+    archived: bool,
+    store: Arc<Store>,
+) -> Result<SectionModel, TodoError> {
+    store.set_section_archived(section_id, archived).await
+}
+
+// ==================== 彻底删除 ====================
+
+/// 彻底删除已归档的项目
+pub async fn delete_archived_project_with_store(
+    project_id: &str,
+    store: Arc<Store>,
+) -> Result<(), TodoError> {
+    store.delete_archived_project(project_id).await
+}
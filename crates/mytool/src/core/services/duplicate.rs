@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use todos::{Store, error::TodoError, services::DuplicateResult};
+
+// ==================== 深拷贝 ====================
+
+/// 复制任务及其子任务、标签、提醒和附件
+pub async fn duplicate_item_with_store(
+    item_id: &str,
+    store: Arc<Store>,
+) -> Result<DuplicateResult, TodoError> {
+    store.duplicate_item(item_id).await
+}
+
+/// 复制分区及其中的任务
+pub async fn duplicate_section_with_store(
+    section_id: &str,
+    store: Arc<Store>,
+) -> Result<DuplicateResult, TodoError> {
+    store.duplicate_section(section_id).await
+}
+
+/// 复制项目及其子项目、分区和任务
+pub async fn duplicate_project_with_store(
+    project_id: &str,
+    store: Arc<Store>,
+) -> Result<DuplicateResult, TodoError> {
+    store.duplicate_project(project_id).await
+}
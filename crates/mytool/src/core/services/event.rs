@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use todos::{Store, error::TodoError, services::EventEntry};
+
+/// 使用全局 Store 加载任务的活动记录（按时间倒序）
+pub async fn load_item_events_with_store(
+    item_id: &str,
+    store: Arc<Store>,
+) -> Result<Vec<EventEntry>, TodoError> {
+    store.get_events_by_item(item_id).await
+}
@@ -0,0 +1,29 @@
+use std::{path::PathBuf, sync::Arc};
+
+use todos::{
+    Store,
+    error::TodoError,
+    services::{ExportFormat, IcsComponent},
+};
+
+// ==================== 导出工作区 ====================
+
+/// 导出整个工作区（或指定项目）到文件
+pub async fn export_workspace_with_store(
+    path: PathBuf,
+    format: ExportFormat,
+    project_id: Option<String>,
+    store: Arc<Store>,
+) -> Result<(), TodoError> {
+    store.export_to_file(&path, format, project_id.as_deref()).await
+}
+
+/// 把有截止时间的任务导出为 iCalendar（`.ics`）文件
+pub async fn export_ics_with_store(
+    path: PathBuf,
+    component: IcsComponent,
+    project_id: Option<String>,
+    store: Arc<Store>,
+) -> Result<(), TodoError> {
+    store.export_ics_to_file(&path, component, project_id.as_deref()).await
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use todos::{Store, entity::FilterModel, error::TodoError};
+
+// ==================== 加载过滤器 ====================
+
+/// 使用全局 Store 加载保存的过滤器
+pub async fn load_filters_with_store(store: Arc<Store>) -> Result<Vec<FilterModel>, TodoError> {
+    store.get_all_filters().await
+}
+
+// ==================== 添加过滤器 ====================
+
+pub async fn add_filter_with_store(
+    filter: Arc<FilterModel>,
+    store: Arc<Store>,
+) -> Result<FilterModel, TodoError> {
+    store.insert_filter(filter.as_ref().clone()).await
+}
+
+// ==================== 修改过滤器 ====================
+
+pub async fn mod_filter_with_store(
+    filter: Arc<FilterModel>,
+    store: Arc<Store>,
+) -> Result<FilterModel, TodoError> {
+    store.update_filter(filter.as_ref().clone()).await
+}
+
+// ==================== 删除过滤器 ====================
+
+pub async fn del_filter_with_store(
+    filter: Arc<FilterModel>,
+    store: Arc<Store>,
+) -> Result<u64, TodoError> {
+    store.delete_filter(&filter.id).await
+}
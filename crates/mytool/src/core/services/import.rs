@@ -0,0 +1,21 @@
+use std::{path::PathBuf, sync::Arc};
+
+use todos::{Store, error::TodoError, services::ImportReport};
+
+// ==================== 导入 Todoist 备份 ====================
+
+/// 导入 Todoist 备份 zip，所有对象在同一个事务中写入
+pub async fn import_todoist_backup_with_store(
+    path: PathBuf,
+    store: Arc<Store>,
+) -> Result<ImportReport, TodoError> {
+    store.import_todoist_backup(&path).await
+}
+
+/// 导入 `.ics` 文件中的任务（`VTODO`），放入收件箱
+pub async fn import_ics_with_store(
+    path: PathBuf,
+    store: Arc<Store>,
+) -> Result<ImportReport, TodoError> {
+    store.import_ics(&path).await
+}
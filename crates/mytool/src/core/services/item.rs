@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use todos::{Store, entity::ItemModel, error::TodoError};
+use todos::{Store, entity::ItemModel, error::TodoError, services::ItemPosition};
 
 // ==================== 加载任务 ====================
 
@@ -48,6 +48,63 @@ pub async fn finish_item_with_store(
     store.complete_item(&item.id, checked, complete_sub_items).await
 }
 
+// ==================== 手动排序 ====================
+
+/// 在项目/分区内调整任务顺序（child_order）
+pub async fn move_item_with_store(
+    item_id: &str,
+    position: ItemPosition,
+    store: Arc<Store>,
+) -> Result<Vec<ItemModel>, TodoError> {
+    store.move_item(item_id, position).await
+}
+
+/// 在"今天"列表中调整任务顺序（day_order）
+pub async fn move_item_in_day_with_store(
+    item_id: &str,
+    position: ItemPosition,
+    store: Arc<Store>,
+) -> Result<Vec<ItemModel>, TodoError> {
+    store.move_item_in_day(item_id, position).await
+}
+
+// ==================== 子任务树 ====================
+
+/// 缩进为前一个兄弟任务的子任务（推荐）
+pub async fn indent_item_with_store(
+    item_id: &str,
+    store: Arc<Store>,
+) -> Result<Vec<ItemModel>, TodoError> {
+    store.indent_item(item_id).await
+}
+
+/// 取消缩进，移到父任务之后（推荐）
+pub async fn outdent_item_with_store(
+    item_id: &str,
+    store: Arc<Store>,
+) -> Result<Vec<ItemModel>, TodoError> {
+    store.outdent_item(item_id).await
+}
+
+/// 把任务连同子孙移到其他项目 / 分区（推荐）
+pub async fn move_items_with_store(
+    item_ids: &[String],
+    project_id: Option<&str>,
+    section_id: Option<&str>,
+    store: Arc<Store>,
+) -> Result<Vec<ItemModel>, TodoError> {
+    store.move_items(item_ids, project_id, section_id).await
+}
+
+/// 折叠 / 展开子任务（推荐）
+pub async fn set_item_collapsed_with_store(
+    item_id: &str,
+    collapsed: bool,
+    store: Arc<Store>,
+) -> Result<ItemModel, TodoError> {
+    store.set_item_collapsed(item_id, collapsed).await
+}
+
 // ==================== 按项目查询 ====================
 
 /// 使用全局 Store 获取 tasks by project_id（推荐）
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use todos::{Store, entity::LabelModel, error::TodoError};
+use todos::{Store, entity::LabelModel, error::TodoError, services::LabelMerge};
 
 // ==================== 加载标签 ====================
 
@@ -38,3 +38,40 @@ pub async fn del_label_with_store(
 ) -> Result<u64, TodoError> {
     store.delete_label(&label.id).await
 }
+
+// ==================== 标签管理 ====================
+
+/// 重命名标签，与其他标签同名（不区分大小写）时返回 `AlreadyExists`
+pub async fn rename_label_with_store(
+    label_id: &str,
+    name: &str,
+    store: Arc<Store>,
+) -> Result<LabelModel, TodoError> {
+    store.rename_label(label_id, name).await
+}
+
+/// 把 `source` 合并到 `target`，源标签移入回收站
+pub async fn merge_labels_with_store(
+    source_id: &str,
+    target_id: &str,
+    store: Arc<Store>,
+) -> Result<LabelMerge, TodoError> {
+    store.merge_labels(source_id, target_id).await
+}
+
+/// 按给定顺序重写标签的 item_order
+pub async fn reorder_labels_with_store(
+    label_ids: Vec<String>,
+    store: Arc<Store>,
+) -> Result<Vec<LabelModel>, TodoError> {
+    store.reorder_labels(&label_ids).await
+}
+
+/// 收藏 / 取消收藏标签
+pub async fn set_label_favorite_with_store(
+    label_id: &str,
+    is_favorite: bool,
+    store: Arc<Store>,
+) -> Result<LabelModel, TodoError> {
+    store.set_label_favorite(label_id, is_favorite).await
+}
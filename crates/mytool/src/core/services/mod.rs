@@ -1,13 +1,33 @@
+mod archive;
 mod attachment;
+mod duplicate;
+mod event;
+mod export;
+mod filter;
+mod import;
 mod item;
 mod label;
 mod project;
 mod reminder;
+mod search;
 mod section;
+mod sync;
+mod template;
+mod trash;
 
+pub use archive::*;
 pub use attachment::*;
+pub use duplicate::*;
+pub use event::*;
+pub use export::*;
+pub use filter::*;
+pub use import::*;
 pub use item::*;
 pub use label::*;
 pub use project::*;
 pub use reminder::*;
+pub use search::*;
 pub use section::*;
+pub use sync::*;
+pub use template::*;
+pub use trash::*;
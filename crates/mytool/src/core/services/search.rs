@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use todos::{Store, error::TodoError, services::SearchHit};
+
+/// 使用全局 Store 进行全文搜索（按相关度排序）
+pub async fn search_with_store(
+    query: &str,
+    limit: u64,
+    store: Arc<Store>,
+) -> Result<Vec<SearchHit>, TodoError> {
+    store.search(query, limit).await
+}
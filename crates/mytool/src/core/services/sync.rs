@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::SourceModel,
+    error::TodoError,
+    services::{CalDavConfig, CalDavSyncReport, TodoistConfig, TodoistSyncReport},
+};
+
+// ==================== CalDAV 同步 ====================
+
+/// 添加一个 CalDAV 日历（同时创建对应的项目）
+pub async fn add_caldav_source_with_store(
+    config: CalDavConfig,
+    store: Arc<Store>,
+) -> Result<SourceModel, TodoError> {
+    store.add_caldav_source(config).await
+}
+
+/// 同步单个 CalDAV 日历
+pub async fn sync_caldav_source_with_store(
+    source_id: String,
+    store: Arc<Store>,
+) -> Result<CalDavSyncReport, TodoError> {
+    store.sync_caldav(&source_id).await
+}
+
+/// 依次同步所有 CalDAV 日历；单个日历失败不影响其他日历，结果按日历名称返回
+pub async fn sync_all_caldav_with_store(
+    store: Arc<Store>,
+) -> Result<Vec<(String, Result<CalDavSyncReport, TodoError>)>, TodoError> {
+    let mut results = Vec::new();
+    for source in store.get_caldav_sources().await? {
+        let name = source.display_name.clone().unwrap_or_else(|| source.id.clone());
+        results.push((name, store.sync_caldav(&source.id).await));
+    }
+    Ok(results)
+}
+
+// ==================== Todoist 同步 ====================
+
+/// 添加 Todoist 账号（只支持一个）
+pub async fn add_todoist_source_with_store(
+    config: TodoistConfig,
+    store: Arc<Store>,
+) -> Result<SourceModel, TodoError> {
+    store.add_todoist_source(config).await
+}
+
+/// 同步 Todoist 账号：发送排队的本地修改并拉取服务器上的变化；没有账号时返回 `None`
+pub async fn sync_todoist_with_store(
+    store: Arc<Store>,
+) -> Result<Option<TodoistSyncReport>, TodoError> {
+    match store.get_todoist_source().await? {
+        Some(source) => store.sync_todoist(&source.id).await.map(Some),
+        None => Ok(None),
+    }
+}
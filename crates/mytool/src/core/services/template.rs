@@ -0,0 +1,25 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::NaiveDate;
+use todos::{ProjectTemplate, Store, entity::ProjectModel, error::TodoError};
+
+// ==================== 项目模板 ====================
+
+/// 把项目保存为模板文件，相对日期以最早的截止日期为基准
+pub async fn save_project_template_with_store(
+    project_id: &str,
+    path: PathBuf,
+    store: Arc<Store>,
+) -> Result<ProjectTemplate, TodoError> {
+    store.save_project_template(project_id, &path, None).await
+}
+
+/// 按模板文件新建项目，相对日期以 `start` 为第 0 天
+pub async fn instantiate_template_file_with_store(
+    path: PathBuf,
+    name: Option<String>,
+    start: NaiveDate,
+    store: Arc<Store>,
+) -> Result<ProjectModel, TodoError> {
+    store.instantiate_template_file(&path, name.as_deref(), start).await
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    error::TodoError,
+    services::{TrashEntry, TrashObjectType},
+};
+
+// ==================== 加载回收站 ====================
+
+/// 使用全局 Store 加载回收站（推荐）
+pub async fn load_trash_with_store(store: Arc<Store>) -> Result<Vec<TrashEntry>, TodoError> {
+    store.list_trash().await
+}
+
+// ==================== 恢复 ====================
+
+/// 从回收站恢复对象（含同批删除的子对象），撤销删除也走这里
+pub async fn restore_object_with_store(
+    object_type: TrashObjectType,
+    id: &str,
+    store: Arc<Store>,
+) -> Result<(), TodoError> {
+    store.restore_from_trash(object_type, id).await
+}
+
+// ==================== 彻底删除 ====================
+
+/// 彻底删除回收站中的对象
+pub async fn purge_trash_with_store(
+    entry: Arc<TrashEntry>,
+    store: Arc<Store>,
+) -> Result<(), TodoError> {
+    store.purge_from_trash(entry.object_type, &entry.id).await
+}
+
+/// 清空回收站
+pub async fn empty_trash_with_store(store: Arc<Store>) -> Result<usize, TodoError> {
+    store.empty_trash().await
+}
+
+/// 清理超过保留期的对象
+pub async fn purge_expired_trash_with_store(
+    retention_days: u32,
+    store: Arc<Store>,
+) -> Result<usize, TodoError> {
+    store.purge_expired_trash(retention_days).await
+}
@@ -26,12 +26,20 @@ actions!(task_shortcuts, [
     DuplicateTask,
     /// 移动任务到项目 (Cmd/Ctrl + M)
     MoveTaskToProject,
+    /// 缩进为子任务 (Tab)
+    IndentTask,
+    /// 取消缩进 (Shift + Tab)
+    OutdentTask,
     /// 设置任务优先级 (Cmd/Ctrl + 1/2/3)
     SetTaskPriority,
     /// 添加标签 (Cmd/Ctrl + L)
     AddLabel,
     /// 设置截止日期 (Cmd/Ctrl + T)
     SetDueDate,
+    /// 撤销上一次修改 (Cmd/Ctrl + Z)
+    UndoChange,
+    /// 重做 (Cmd/Ctrl + Shift + Z)
+    RedoChange,
 ]);
 
 // ==================== 导航快捷键 ====================
@@ -210,6 +218,24 @@ pub fn get_all_shortcuts() -> Vec<ShortcutConfig> {
             description: "复制任务",
             category: ShortcutCategory::Task,
         },
+        ShortcutConfig {
+            action: "MoveTaskToProject",
+            key: "cmd-m",
+            description: "移动任务到项目（含子任务）",
+            category: ShortcutCategory::Task,
+        },
+        ShortcutConfig {
+            action: "IndentTask",
+            key: "tab",
+            description: "缩进为子任务",
+            category: ShortcutCategory::Task,
+        },
+        ShortcutConfig {
+            action: "OutdentTask",
+            key: "shift-tab",
+            description: "取消缩进",
+            category: ShortcutCategory::Task,
+        },
         ShortcutConfig {
             action: "AddLabel",
             key: "cmd-l",
@@ -222,6 +248,18 @@ pub fn get_all_shortcuts() -> Vec<ShortcutConfig> {
             description: "设置截止日期",
             category: ShortcutCategory::Task,
         },
+        ShortcutConfig {
+            action: "UndoChange",
+            key: "cmd-z",
+            description: "撤销",
+            category: ShortcutCategory::Task,
+        },
+        ShortcutConfig {
+            action: "RedoChange",
+            key: "cmd-shift-z",
+            description: "重做",
+            category: ShortcutCategory::Task,
+        },
         // 导航
         ShortcutConfig {
             action: "ShowInbox",
@@ -321,6 +359,12 @@ pub fn get_all_shortcuts() -> Vec<ShortcutConfig> {
             description: "批量删除选中任务",
             category: ShortcutCategory::Selection,
         },
+        ShortcutConfig {
+            action: "BatchMoveSelected",
+            key: "cmd-shift-m",
+            description: "移动当前列表中的未完成任务",
+            category: ShortcutCategory::Selection,
+        },
         // 项目和分区
         ShortcutConfig {
             action: "NewProject",
@@ -1,6 +1,9 @@
 //! 错误通知与异步保存结果追踪
 
+use std::sync::Arc;
+
 use gpui::Global;
+use todos::entity::ItemModel;
 
 /// 保存状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -88,3 +91,51 @@ impl SaveResults {
         self.failed.clear();
     }
 }
+
+/// 保存失败并已回滚的乐观更新，重试时重新执行
+#[derive(Debug, Clone)]
+pub enum FailedWrite {
+    AddItem(Arc<ItemModel>),
+    UpdateItem(Arc<ItemModel>),
+    BatchUpdateItems(Vec<Arc<ItemModel>>),
+}
+
+impl FailedWrite {
+    /// 通知中显示的描述
+    pub fn describe(&self) -> String {
+        match self {
+            Self::AddItem(item) => format!("添加任务「{}」", item.content),
+            Self::UpdateItem(item) => format!("修改任务「{}」", item.content),
+            Self::BatchUpdateItems(items) => format!("批量修改 {} 个任务", items.len()),
+        }
+    }
+}
+
+/// 等待显示「无法保存 — 重试 / 放弃」通知的写入
+///
+/// 后台任务无法访问窗口，由 `StoryRoot` 观察并弹出通知。
+#[derive(Debug, Default)]
+pub struct SaveFailures {
+    failures: Vec<(FailedWrite, String)>,
+}
+
+impl Global for SaveFailures {}
+impl SaveFailures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次失败及错误信息
+    pub fn push(&mut self, write: FailedWrite, error: String) {
+        self.failures.push((write, error));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// 取出全部等待通知的失败
+    pub fn take_all(&mut self) -> Vec<(FailedWrite, String)> {
+        std::mem::take(&mut self.failures)
+    }
+}
@@ -0,0 +1,273 @@
+//! 撤销 / 重做历史
+//!
+//! 每次变更在 actions 层同步记录一条 [`UndoCommand`]（保存变更前后的快照），
+//! 撤销时由 actions 层按相反方向重放，仍然走乐观更新路径，
+//! 保证 TodoStore 与数据库保持一致。
+
+use std::{collections::VecDeque, sync::Arc};
+
+use gpui::Global;
+use todos::entity::{ItemModel, LabelModel, ProjectModel, SectionModel};
+
+/// 历史记录最大条数，超过后丢弃最早的记录
+const MAX_HISTORY: usize = 100;
+
+/// 可撤销的变更
+///
+/// 删除均为软删除（移入回收站），撤销删除即从回收站恢复，
+/// 重做新增同样通过回收站恢复，避免重新生成 ID。
+#[derive(Debug, Clone)]
+pub enum UndoCommand {
+    AddItem {
+        item: Arc<ItemModel>,
+    },
+    UpdateItem {
+        before: Arc<ItemModel>,
+        after: Arc<ItemModel>,
+    },
+    DeleteItem {
+        item: Arc<ItemModel>,
+    },
+    /// `reset_subitems` 仅在重复任务滚动时非空：滚动会把已完成的子任务重置为未完成
+    CompleteItem {
+        before: Arc<ItemModel>,
+        checked: bool,
+        recurring_roll: bool,
+        reset_subitems: Vec<Arc<ItemModel>>,
+    },
+    PinItem {
+        item_id: String,
+        pinned: bool,
+    },
+    BatchUpdateItems {
+        before: Vec<Arc<ItemModel>>,
+        after: Vec<Arc<ItemModel>>,
+    },
+    AddProject {
+        project: Arc<ProjectModel>,
+    },
+    UpdateProject {
+        before: Arc<ProjectModel>,
+        after: Arc<ProjectModel>,
+    },
+    DeleteProject {
+        project: Arc<ProjectModel>,
+    },
+    AddSection {
+        section: Arc<SectionModel>,
+    },
+    UpdateSection {
+        before: Arc<SectionModel>,
+        after: Arc<SectionModel>,
+    },
+    DeleteSection {
+        section: Arc<SectionModel>,
+    },
+    AddLabel {
+        label: Arc<LabelModel>,
+    },
+    UpdateLabel {
+        before: Arc<LabelModel>,
+        after: Arc<LabelModel>,
+    },
+    DeleteLabel {
+        label: Arc<LabelModel>,
+    },
+}
+
+impl UndoCommand {
+    /// 用于通知的简短描述
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::AddItem { .. } => "添加任务",
+            Self::UpdateItem { before, after } => {
+                if before.project_id != after.project_id || before.section_id != after.section_id {
+                    "移动任务"
+                } else {
+                    "修改任务"
+                }
+            },
+            Self::DeleteItem { .. } => "删除任务",
+            Self::CompleteItem { checked: true, .. } => "完成任务",
+            Self::CompleteItem { checked: false, .. } => "取消完成任务",
+            Self::PinItem { pinned: true, .. } => "置顶任务",
+            Self::PinItem { pinned: false, .. } => "取消置顶任务",
+            Self::BatchUpdateItems { .. } => "批量修改任务",
+            Self::AddProject { .. } => "添加项目",
+            Self::UpdateProject { .. } => "修改项目",
+            Self::DeleteProject { .. } => "删除项目",
+            Self::AddSection { .. } => "添加分区",
+            Self::UpdateSection { .. } => "修改分区",
+            Self::DeleteSection { .. } => "删除分区",
+            Self::AddLabel { .. } => "添加标签",
+            Self::UpdateLabel { .. } => "修改标签",
+            Self::DeleteLabel { .. } => "删除标签",
+        }
+    }
+}
+
+/// 撤销 / 重做栈
+pub struct UndoHistory {
+    undo_stack: VecDeque<UndoCommand>,
+    redo_stack: Vec<UndoCommand>,
+    /// 重放撤销 / 重做期间为 true，此时 actions 层不再记录新的历史
+    replaying: bool,
+}
+
+impl Global for UndoHistory {}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self { undo_stack: VecDeque::new(), redo_stack: Vec::new(), replaying: false }
+    }
+
+    /// 记录一次新的变更（重放期间忽略），并清空重做栈
+    pub fn record(&mut self, command: UndoCommand) {
+        if self.replaying {
+            return;
+        }
+        self.push_undo(command);
+        self.redo_stack.clear();
+    }
+
+    /// 取出最近一次可撤销的变更
+    pub fn pop_undo(&mut self) -> Option<UndoCommand> {
+        self.undo_stack.pop_back()
+    }
+
+    /// 取出最近一次可重做的变更
+    pub fn pop_redo(&mut self) -> Option<UndoCommand> {
+        self.redo_stack.pop()
+    }
+
+    /// 放回撤销栈（重做成功或撤销无法执行时），不清空重做栈
+    pub fn push_undo(&mut self, command: UndoCommand) {
+        if self.undo_stack.len() == MAX_HISTORY {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(command);
+    }
+
+    /// 放入重做栈（撤销成功或重做无法执行时）
+    pub fn push_redo(&mut self, command: UndoCommand) {
+        self.redo_stack.push(command);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn set_replaying(&mut self, replaying: bool) {
+        self.replaying = replaying;
+    }
+
+    /// 新增任务保存成功后，将历史中的临时 ID 替换为真实 ID
+    pub fn replace_item_id(&mut self, temp_id: &str, saved: &Arc<ItemModel>) {
+        for command in self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut()) {
+            if let UndoCommand::AddItem { item } = command
+                && item.id == temp_id
+            {
+                *item = saved.clone();
+            }
+        }
+    }
+
+    /// 新增任务保存失败并已回滚后，移除历史中对应的新增记录
+    pub fn forget_added_item(&mut self, temp_id: &str) {
+        let added = |command: &UndoCommand| match command {
+            UndoCommand::AddItem { item } => item.id == temp_id,
+            _ => false,
+        };
+        self.undo_stack.retain(|command| !added(command));
+        self.redo_stack.retain(|command| !added(command));
+    }
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> Arc<ItemModel> {
+        Arc::new(ItemModel { id: id.to_string(), ..Default::default() })
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut history = UndoHistory::new();
+        history.record(UndoCommand::DeleteItem { item: item("a") });
+        history.record(UndoCommand::PinItem { item_id: "b".to_string(), pinned: true });
+
+        let undone = history.pop_undo().unwrap();
+        assert!(matches!(undone, UndoCommand::PinItem { .. }));
+        history.push_redo(undone);
+        assert!(history.can_redo());
+
+        let redone = history.pop_redo().unwrap();
+        assert!(matches!(redone, UndoCommand::PinItem { .. }));
+        history.push_undo(redone);
+        assert!(!history.can_redo());
+        assert!(matches!(history.pop_undo(), Some(UndoCommand::PinItem { .. })));
+        assert!(matches!(history.pop_undo(), Some(UndoCommand::DeleteItem { .. })));
+    }
+
+    #[test]
+    fn test_record_clears_redo_and_ignores_replay() {
+        let mut history = UndoHistory::new();
+        history.record(UndoCommand::DeleteItem { item: item("a") });
+        let undone = history.pop_undo().unwrap();
+        history.push_redo(undone);
+
+        history.set_replaying(true);
+        history.record(UndoCommand::DeleteItem { item: item("b") });
+        history.set_replaying(false);
+        assert!(history.can_redo(), "replayed mutations must not reset the redo stack");
+        assert!(!history.can_undo());
+
+        history.record(UndoCommand::DeleteItem { item: item("c") });
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = UndoHistory::new();
+        for i in 0..MAX_HISTORY + 5 {
+            history.record(UndoCommand::DeleteItem { item: item(&i.to_string()) });
+        }
+        let mut count = 0;
+        while history.pop_undo().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_HISTORY);
+    }
+
+    #[test]
+    fn test_replace_temp_item_id() {
+        let mut history = UndoHistory::new();
+        history.record(UndoCommand::AddItem { item: item("temp_1") });
+        history.replace_item_id("temp_1", &item("real"));
+        match history.pop_undo() {
+            Some(UndoCommand::AddItem { item }) => assert_eq!(item.id, "real"),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_forget_added_item() {
+        let mut history = UndoHistory::new();
+        history.record(UndoCommand::DeleteItem { item: item("a") });
+        history.record(UndoCommand::AddItem { item: item("temp_1") });
+        history.forget_added_item("temp_1");
+        assert!(matches!(history.pop_undo(), Some(UndoCommand::DeleteItem { .. })));
+        assert!(!history.can_undo());
+    }
+}
@@ -0,0 +1,80 @@
+//! 写入日志状态
+//!
+//! 持有数据库旁的 `todos::WriteJournal`。修改日志时通过 `cx.update_global` 进行，
+//! 标题栏观察该全局状态并显示等待 / 失败的写入。
+
+use gpui::Global;
+use todos::{JournalEntry, JournalStatus, WriteJournal};
+use tracing::error;
+
+pub struct WriteJournalState {
+    journal: WriteJournal,
+}
+
+impl Global for WriteJournalState {}
+
+impl WriteJournalState {
+    /// 打开数据库旁的写入日志；无法读取时改用临时目录中的日志，仍然失败则只保存在内存中，
+    /// 启动时提示用户（见 [`Self::is_persistent`]）
+    pub fn new() -> Self {
+        let path = todos::write_journal_path();
+        let fallback = std::env::temp_dir().join("mytool-pending_writes.jsonl");
+        let journal = WriteJournal::open(&path)
+            .or_else(|e| {
+                error!("Failed to open write journal {}: {}", path.display(), e);
+                WriteJournal::open(&fallback)
+            })
+            .unwrap_or_else(|e| {
+                error!(
+                    "Failed to open fallback write journal {}: {}; keeping it in memory",
+                    fallback.display(),
+                    e
+                );
+                WriteJournal::in_memory()
+            });
+        Self { journal }
+    }
+
+    /// 日志是否保存在磁盘上；为 false 时未落盘的写入在退出后会丢失
+    pub fn is_persistent(&self) -> bool {
+        self.journal.path().is_some()
+    }
+
+    /// 日志句柄（克隆后指向同一份数据，可以移入异步任务）
+    pub fn journal(&self) -> WriteJournal {
+        self.journal.clone()
+    }
+
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.journal.entries()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.journal.pending_count()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.journal.failed_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.journal.is_empty()
+    }
+
+    pub fn has_pending_for(&self, item_id: &str) -> bool {
+        self.journal.has_pending_for(item_id)
+    }
+
+    /// 标题栏中列出的写入（失败的在前）
+    pub fn display_entries(&self) -> Vec<JournalEntry> {
+        let mut entries = self.entries();
+        entries.sort_by_key(|entry| entry.status != JournalStatus::Failed);
+        entries
+    }
+}
+
+impl Default for WriteJournalState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
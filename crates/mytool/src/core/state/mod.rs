@@ -1,6 +1,9 @@
 mod cache;
 mod database;
 mod events;
+mod history;
+mod journal;
+mod navigation;
 mod pending_tasks;
 mod store;
 
@@ -10,6 +13,9 @@ pub use cache::*;
 pub use database::DBState;
 pub use events::*;
 use gpui::App;
+pub use history::*;
+pub use journal::*;
+pub use navigation::*;
 pub use pending_tasks::*;
 use sea_orm::DatabaseConnection;
 pub use store::*;
@@ -23,6 +29,14 @@ pub async fn get_todo_conn() -> Result<DatabaseConnection, sea_orm::DbErr> {
     todos::init_db().await
 }
 
+/// 预览尚未应用的数据库迁移
+///
+/// 使用单独的预览连接，不做迁移前备份，也不修改数据库。
+pub async fn pending_migrations() -> Result<Vec<todos::PatchStep>, sea_orm::DbErr> {
+    let db = todos::init_db_for_preview().await?;
+    todos::PatchManager::new(Arc::new(db)).dry_run().await
+}
+
 /// 获取数据库连接的便捷函数
 ///
 /// 这是一个辅助函数，用于简化从全局状态获取数据库连接的操作。
@@ -76,6 +90,23 @@ pub fn state_init(cx: &mut App, db: sea_orm::DatabaseConnection) {
     // 初始化保存结果状态
     cx.set_global(SaveResults::new());
 
+    // 初始化保存失败通知队列（回滚后提示重试 / 放弃）
+    cx.set_global(SaveFailures::new());
+
+    // 初始化撤销 / 重做历史
+    cx.set_global(UndoHistory::new());
+
+    // 初始化导航请求状态（搜索结果跳转）
+    cx.set_global(NavigationState::new());
+
+    // 初始化写入日志（上次没有落盘的乐观更新）
+    cx.set_global(WriteJournalState::new());
+    if !cx.global::<WriteJournalState>().is_persistent() {
+        cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+            notifier.set_error("写入日志无法保存到磁盘，保存失败的修改在退出后会丢失".to_string());
+        });
+    }
+
     // 异步创建 Store 并加载数据
     cx.spawn(async move |cx| {
         tracing::info!("Initializing Store asynchronously...");
@@ -90,13 +121,33 @@ pub fn state_init(cx: &mut App, db: sea_orm::DatabaseConnection) {
 
         tracing::info!("Store initialized, loading data...");
 
-        // 并行冷加载：items / projects / sections / labels
-        tracing::info!("Loading items, projects, sections, labels in parallel...");
-        let (items_r, projects_r, sections_r, labels_r) = tokio::join!(
+        // 回收站：先清理超过保留期的对象，失败不影响冷加载
+        let retention_days = todos::trash_retention_days();
+        if let Err(e) =
+            crate::state_service::purge_expired_trash_with_store(retention_days, store.clone())
+                .await
+        {
+            error!(error = %e, "purge_expired_trash_with_store failed during startup");
+        }
+
+        // 重放上次没有落盘的写入，放在冷加载之前，加载结果已包含这些修改
+        crate::todo_actions::replay_write_journal_impl(store.clone(), cx).await;
+
+        // 启动备份（迁移已完成）并开始定时备份
+        crate::todo_actions::schedule_backups(cx);
+
+        // 并行冷加载：items / projects / sections / labels / trash / archive / filters
+        tracing::info!(
+            "Loading items, projects, sections, labels, trash, archive, filters in parallel..."
+        );
+        let (items_r, projects_r, sections_r, labels_r, trash_r, archive_r, filters_r) = tokio::join!(
             crate::state_service::load_items_with_store(store.clone()),
             crate::state_service::load_projects_with_store(store.clone()),
             crate::state_service::load_sections_with_store(store.clone()),
             crate::state_service::load_labels_with_store(store.clone()),
+            crate::state_service::load_trash_with_store(store.clone()),
+            crate::state_service::load_archive_with_store(store.clone()),
+            crate::state_service::load_filters_with_store(store.clone()),
         );
 
         if let Ok(ref items) = items_r {
@@ -129,6 +180,15 @@ pub fn state_init(cx: &mut App, db: sea_orm::DatabaseConnection) {
             error!(error = %e, "load_labels_with_store failed during startup");
             load_failures.push(format!("标签加载失败: {e}"));
         }
+        if let Err(ref e) = trash_r {
+            error!(error = %e, "load_trash_with_store failed during startup");
+        }
+        if let Err(ref e) = archive_r {
+            error!(error = %e, "load_archive_with_store failed during startup");
+        }
+        if let Err(ref e) = filters_r {
+            error!(error = %e, "load_filters_with_store failed during startup");
+        }
 
         // 仅应用成功的查询，避免把失败误呈现为「空列表」
         cx.update_global::<TodoStore, _>(|todo_store, _| {
@@ -144,6 +204,15 @@ pub fn state_init(cx: &mut App, db: sea_orm::DatabaseConnection) {
             if let Ok(labels) = labels_r {
                 todo_store.set_labels(labels);
             }
+            if let Ok(trash) = trash_r {
+                todo_store.set_trash(trash);
+            }
+            if let Ok(archive) = archive_r {
+                todo_store.set_archive(archive);
+            }
+            if let Ok(filters) = filters_r {
+                todo_store.set_filters(filters);
+            }
             tracing::info!("TodoStore cold-load apply finished (partial if any query failed)");
         });
 
@@ -0,0 +1,43 @@
+//! 跨视图导航请求
+//!
+//! 搜索面板等位于对话框层的组件无法直接访问侧边栏，
+//! 通过该全局状态提交导航请求，由 `TodoStory` 观察并切换到对应的看板或项目。
+
+use gpui::Global;
+
+/// 导航目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationTarget {
+    /// 看板（`Board::klass()`）
+    Board(&'static str),
+    /// 项目 ID
+    Project(String),
+}
+
+/// 待处理的导航请求
+#[derive(Default)]
+pub struct NavigationState {
+    pending: Option<NavigationTarget>,
+}
+
+impl Global for NavigationState {}
+
+impl NavigationState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 提交导航请求（覆盖尚未处理的请求）
+    pub fn request(&mut self, target: NavigationTarget) {
+        self.pending = Some(target);
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// 取出待处理的导航请求
+    pub fn take(&mut self) -> Option<NavigationTarget> {
+        self.pending.take()
+    }
+}
@@ -18,7 +18,11 @@ use std::{
 };
 
 use gpui::Global;
-use todos::entity::{ItemModel, LabelModel, ProjectModel, SectionModel};
+use todos::{
+    FilterIndex, FilterQuery, QuickAddIndex,
+    entity::{FilterModel, ItemModel, LabelModel, ProjectModel, SectionModel},
+    services::{ArchivedProject, TrashEntry},
+};
 
 // ==================== 变更掩码 ====================
 
@@ -33,6 +37,9 @@ pub struct ChangeMask {
     pub sections_changed: bool,
     pub labels_changed: bool,
     pub active_project_changed: bool,
+    pub trash_changed: bool,
+    pub archive_changed: bool,
+    pub filters_changed: bool,
 }
 
 impl ChangeMask {
@@ -44,6 +51,9 @@ impl ChangeMask {
             sections_changed: false,
             labels_changed: false,
             active_project_changed: false,
+            trash_changed: false,
+            archive_changed: false,
+            filters_changed: false,
         }
     }
 
@@ -55,6 +65,9 @@ impl ChangeMask {
             sections_changed: true,
             labels_changed: true,
             active_project_changed: true,
+            trash_changed: true,
+            archive_changed: true,
+            filters_changed: true,
         }
     }
 
@@ -96,6 +109,25 @@ impl ChangeMask {
         self.items_changed || self.labels_changed
     }
 
+    /// 检查是否影响回收站视图
+    pub fn affects_trash(&self) -> bool {
+        self.trash_changed
+    }
+
+    /// 检查是否影响归档视图
+    pub fn affects_archive(&self) -> bool {
+        self.archive_changed
+    }
+
+    /// 检查是否影响保存的过滤器视图（过滤条件可能引用项目 / 分区 / 标签名称）
+    pub fn affects_filters(&self) -> bool {
+        self.items_changed
+            || self.projects_changed
+            || self.sections_changed
+            || self.labels_changed
+            || self.filters_changed
+    }
+
     /// 合并两个掩码
     pub fn merge(&mut self, other: &Self) {
         self.items_changed |= other.items_changed;
@@ -103,6 +135,9 @@ impl ChangeMask {
         self.sections_changed |= other.sections_changed;
         self.labels_changed |= other.labels_changed;
         self.active_project_changed |= other.active_project_changed;
+        self.trash_changed |= other.trash_changed;
+        self.archive_changed |= other.archive_changed;
+        self.filters_changed |= other.filters_changed;
     }
 
     /// 清空所有掩码位
@@ -174,6 +209,12 @@ pub struct TodoStore {
     pub sections: Vec<Arc<SectionModel>>,
     /// 当前活跃项目
     pub active_project: Option<Arc<ProjectModel>>,
+    /// 回收站中的根对象（按删除时间倒序）
+    pub trash: Vec<Arc<TrashEntry>>,
+    /// 归档的根项目（按归档时间倒序）
+    pub archive: Vec<Arc<ArchivedProject>>,
+    /// 保存的过滤器
+    pub filters: Vec<Arc<FilterModel>>,
 
     /// 索引结构（用于优化查询性能）
     /// 项目索引：按 project_id 分组
@@ -261,6 +302,9 @@ impl TodoStore {
             labels: vec![],
             sections: vec![],
             active_project: None,
+            trash: vec![],
+            archive: vec![],
+            filters: vec![],
             project_index: HashMap::new(),
             section_index: HashMap::new(),
             checked_set: HashSet::new(),
@@ -479,10 +523,12 @@ impl TodoStore {
 
     /// 获取今日到期的任务
     ///
-    /// 使用通用查询方法
+    /// 使用通用查询方法，按 day_order 排序（"今天"列表可手动调整顺序）
     pub fn today_items(&self) -> Vec<Arc<ItemModel>> {
         let today = chrono::Utc::now().naive_utc().date();
-        self.query_items(|item| !item.checked && item.is_due_on_date(today))
+        let mut items = self.query_items(|item| !item.checked && item.is_due_on_date(today));
+        items.sort_by(|a, b| a.cmp_day_order(b));
+        items
     }
 
     /// 获取今日到期的任务（带缓存）
@@ -591,6 +637,33 @@ impl TodoStore {
         self.section_index.get(section_id).cloned().unwrap_or_default()
     }
 
+    /// 与任务处于同一手动排序列表的任务（项目、分区、父任务都相同），按 child_order
+    /// 排序，包含任务本身
+    pub fn sibling_items(&self, item: &ItemModel) -> Vec<Arc<ItemModel>> {
+        let same = |a: &Option<String>, b: &Option<String>| {
+            a.as_deref().unwrap_or_default() == b.as_deref().unwrap_or_default()
+        };
+        let candidates = match (item.section_id.as_deref(), item.project_id.as_deref()) {
+            (Some(section_id), _) if !section_id.is_empty() => self.items_by_section(section_id),
+            (_, Some(project_id)) if !project_id.is_empty() => self.items_by_project(project_id),
+            _ => {
+                let mut items = self.query_items(|other| {
+                    other.project_id.as_deref().unwrap_or_default().is_empty()
+                });
+                items.sort_by(|a, b| a.cmp_child_order(b));
+                items
+            },
+        };
+        candidates
+            .into_iter()
+            .filter(|other| {
+                same(&other.project_id, &item.project_id)
+                    && same(&other.section_id, &item.section_id)
+                    && same(&other.parent_id, &item.parent_id)
+            })
+            .collect()
+    }
+
     /// 获取指定标签的任务（label_index + id_map）
     pub fn items_by_label(&self, label_id: &str) -> Vec<Arc<ItemModel>> {
         if let Some(item_ids) = self.label_index.get(label_id) {
@@ -647,6 +720,30 @@ impl TodoStore {
         self.change_mask.active_project_changed = true;
     }
 
+    /// 更新回收站列表
+    pub fn set_trash(&mut self, trash: Vec<TrashEntry>) {
+        self.trash = trash.into_iter().map(Arc::new).collect();
+        // 增加版本号并设置掩码
+        self.bump_version();
+        self.change_mask.trash_changed = true;
+    }
+
+    /// 更新归档列表
+    pub fn set_archive(&mut self, archive: Vec<ArchivedProject>) {
+        self.archive = archive.into_iter().map(Arc::new).collect();
+        // 增加版本号并设置掩码
+        self.bump_version();
+        self.change_mask.archive_changed = true;
+    }
+
+    /// 更新所有过滤器
+    pub fn set_filters(&mut self, filters: Vec<FilterModel>) {
+        self.filters = filters.into_iter().map(Arc::new).collect();
+        // 增加版本号并设置掩码
+        self.bump_version();
+        self.change_mask.filters_changed = true;
+    }
+
     // ==================== 增量更新方法 ====================
 
     /// 增量更新单个任务
@@ -692,6 +789,14 @@ impl TodoStore {
         self.change_mask.items_changed = true;
     }
 
+    /// 删除任务及其所有子孙任务
+    pub fn remove_item_tree(&mut self, id: &str) {
+        for child in self.descendant_items(id) {
+            self.remove_item(&child.id);
+        }
+        self.remove_item(id);
+    }
+
     /// 原子地替换任务的 ID（用于临时 ID 变为真实 ID）
     ///
     /// 这个方法会在一个操作中完成 ID 替换，避免触发两次通知
@@ -720,6 +825,35 @@ impl TodoStore {
         tracing::info!("TodoStore: replaced temp ID {} with real ID {}", old_id, new_item.id);
     }
 
+    /// 回滚保存失败的乐观更新：移除未落盘的新任务、恢复修改前的任务
+    ///
+    /// 一次操作完成，只递增一次版本号，视图按掩码刷新。
+    pub fn rollback_items(&mut self, removed_ids: &[String], restored: &[Arc<ItemModel>]) {
+        for id in removed_ids {
+            if let Some(item) = self.all_items.iter().find(|i| i.id == *id).cloned() {
+                self.remove_item_from_index(&item);
+            }
+            self.all_items.retain(|i| i.id != *id);
+        }
+        for item in restored {
+            if let Some(pos) = self.all_items.iter().position(|i| i.id == item.id) {
+                let old_item = self.all_items[pos].clone();
+                self.all_items[pos] = item.clone();
+                self.update_item_index(&old_item, item);
+            } else {
+                self.all_items.push(item.clone());
+                self.add_item_to_index(item);
+            }
+        }
+        self.bump_version();
+        self.change_mask.items_changed = true;
+        tracing::info!(
+            "TodoStore: rolled back {} unsaved and {} modified items",
+            removed_ids.len(),
+            restored.len()
+        );
+    }
+
     /// 添加单个任务
     pub fn add_item(&mut self, item: Arc<ItemModel>) {
         self.all_items.push(item.clone());
@@ -735,6 +869,26 @@ impl TodoStore {
         self.id_map.get(id).cloned()
     }
 
+    /// 获取任务的所有子孙任务（按层遍历，不包含自身）
+    pub fn descendant_items(&self, root_id: &str) -> Vec<Arc<ItemModel>> {
+        let mut result = Vec::new();
+        let mut visited: HashSet<String> = HashSet::from([root_id.to_string()]);
+        let mut parents = vec![root_id.to_string()];
+
+        while let Some(parent_id) = parents.pop() {
+            for item in self.all_items.iter() {
+                if item.parent_id.as_deref() == Some(parent_id.as_str())
+                    && visited.insert(item.id.clone())
+                {
+                    parents.push(item.id.clone());
+                    result.push(item.clone());
+                }
+            }
+        }
+
+        result
+    }
+
     /// 增量更新单个项目
     pub fn update_project(&mut self, project: Arc<ProjectModel>) {
         if let Some(pos) = self.projects.iter().position(|p| p.id == project.id) {
@@ -797,6 +951,51 @@ impl TodoStore {
         next_project
     }
 
+    /// 删除项目树（含子项目、分区及其任务），返回下一个应该激活的项目
+    pub fn remove_project_tree(&mut self, id: &str) -> Option<Arc<ProjectModel>> {
+        let mut project_ids = vec![id.to_string()];
+        let mut idx = 0;
+        while idx < project_ids.len() {
+            let parent_id = project_ids[idx].clone();
+            idx += 1;
+            for project in &self.projects {
+                if project.parent_id.as_deref() == Some(parent_id.as_str())
+                    && !project_ids.contains(&project.id)
+                {
+                    project_ids.push(project.id.clone());
+                }
+            }
+        }
+
+        let section_ids: Vec<String> = self
+            .sections
+            .iter()
+            .filter(|s| s.project_id.as_ref().is_some_and(|pid| project_ids.contains(pid)))
+            .map(|s| s.id.clone())
+            .collect();
+        let item_ids: Vec<String> = self
+            .all_items
+            .iter()
+            .filter(|i| {
+                i.project_id.as_ref().is_some_and(|pid| project_ids.contains(pid))
+                    || i.section_id.as_ref().is_some_and(|sid| section_ids.contains(sid))
+            })
+            .map(|i| i.id.clone())
+            .collect();
+
+        for item_id in &item_ids {
+            self.remove_item_tree(item_id);
+        }
+        for section_id in &section_ids {
+            self.remove_section(section_id);
+        }
+        // 先移除子项目，最后移除根项目以确定下一个活跃项目
+        for project_id in project_ids.iter().skip(1) {
+            self.remove_project(project_id);
+        }
+        self.remove_project(id)
+    }
+
     /// 添加单个项目
     pub fn add_project(&mut self, project: Arc<ProjectModel>) {
         self.projects.push(project);
@@ -878,6 +1077,38 @@ impl TodoStore {
         self.labels.iter().find(|l| l.id == id).cloned()
     }
 
+    // ==================== Filter 增量更新方法 ====================
+
+    /// 增量更新单个过滤器（不存在时添加）
+    pub fn update_filter(&mut self, filter: Arc<FilterModel>) {
+        if let Some(pos) = self.filters.iter().position(|f| f.id == filter.id) {
+            self.filters[pos] = filter;
+        } else {
+            self.filters.push(filter);
+        }
+        // 增加版本号并设置掩码
+        self.bump_version();
+        self.change_mask.filters_changed = true;
+    }
+
+    /// 删除单个过滤器
+    pub fn remove_filter(&mut self, id: &str) {
+        self.filters.retain(|f| f.id != id);
+        // 增加版本号并设置掩码
+        self.bump_version();
+        self.change_mask.filters_changed = true;
+    }
+
+    /// 根据ID获取单个过滤器
+    pub fn get_filter(&self, id: &str) -> Option<Arc<FilterModel>> {
+        self.filters.iter().find(|f| f.id == id).cloned()
+    }
+
+    /// 按过滤表达式筛选未完成的任务
+    pub fn filter_items(&self, query: &FilterQuery) -> Vec<Arc<ItemModel>> {
+        query.evaluate(self)
+    }
+
     /// 批量增量更新
     ///
     /// 用于批量操作，如导入数据
@@ -923,7 +1154,9 @@ impl TodoStore {
         let start = std::time::Instant::now();
 
         // 🚀 优化 1: 检查项目 ID 是否变化
-        if old_item.project_id != new_item.project_id {
+        if old_item.project_id != new_item.project_id
+            || old_item.child_order != new_item.child_order
+        {
             self.update_project_index(old_item, false);
             self.update_project_index(new_item, true);
         } else if let Some(project_id) = &new_item.project_id
@@ -935,7 +1168,9 @@ impl TodoStore {
         }
 
         // 🚀 优化 2: 检查分区 ID 是否变化
-        if old_item.section_id != new_item.section_id {
+        if old_item.section_id != new_item.section_id
+            || old_item.child_order != new_item.child_order
+        {
             self.update_section_index(old_item, false);
             self.update_section_index(new_item, true);
         } else if let Some(section_id) = &new_item.section_id
@@ -996,7 +1231,10 @@ impl IndexOperation for TodoStore {
             && !project_id.is_empty()
         {
             if add {
-                self.project_index.entry(project_id.clone()).or_default().push(item.clone());
+                insert_by_child_order(
+                    self.project_index.entry(project_id.clone()).or_default(),
+                    item,
+                );
             } else if let Some(items) = self.project_index.get_mut(project_id) {
                 items.retain(|i| i.id != item.id);
                 if items.is_empty() {
@@ -1012,7 +1250,10 @@ impl IndexOperation for TodoStore {
             && !section_id.is_empty()
         {
             if add {
-                self.section_index.entry(section_id.clone()).or_default().push(item.clone());
+                insert_by_child_order(
+                    self.section_index.entry(section_id.clone()).or_default(),
+                    item,
+                );
             } else if let Some(items) = self.section_index.get_mut(section_id) {
                 items.retain(|i| i.id != item.id);
                 if items.is_empty() {
@@ -1096,6 +1337,99 @@ impl Default for TodoStore {
     }
 }
 
+// ==================== 过滤表达式数据源 ====================
+
+/// 过滤表达式中的项目 / 分区 / 标签条件先按名称解析出 ID，再走对应的索引
+impl FilterIndex for TodoStore {
+    fn today(&self) -> chrono::NaiveDate {
+        // 与 today_items 保持一致
+        chrono::Utc::now().naive_utc().date()
+    }
+
+    fn open_items(&self) -> Vec<Arc<ItemModel>> {
+        self.query_items(|item| !item.checked)
+    }
+
+    fn items_in_project(&self, name: &str, include_subprojects: bool) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        let mut project_ids: Vec<&str> = self
+            .projects
+            .iter()
+            .filter(|p| p.name.to_lowercase() == name)
+            .map(|p| p.id.as_str())
+            .collect();
+        if include_subprojects {
+            // 逐层收集子项目（跳过已收集的，防止 parent_id 成环）
+            let mut ix = 0;
+            while ix < project_ids.len() {
+                let parent = project_ids[ix];
+                let children: Vec<&str> = self
+                    .projects
+                    .iter()
+                    .filter(|p| p.parent_id.as_deref() == Some(parent))
+                    .map(|p| p.id.as_str())
+                    .filter(|id| !project_ids.contains(id))
+                    .collect();
+                project_ids.extend(children);
+                ix += 1;
+            }
+        }
+        project_ids.iter().flat_map(|id| self.items_by_project(id)).collect()
+    }
+
+    fn items_in_section(&self, name: &str) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        self.sections
+            .iter()
+            .filter(|s| s.name.to_lowercase() == name)
+            .flat_map(|s| self.items_by_section(&s.id))
+            .collect()
+    }
+
+    fn items_with_label(&self, name: &str) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        self.labels
+            .iter()
+            .filter(|l| l.name.to_lowercase() == name)
+            .flat_map(|l| self.items_by_label(&l.id))
+            .collect()
+    }
+
+    fn pinned_items(&self) -> Vec<Arc<ItemModel>> {
+        TodoStore::pinned_items(self)
+    }
+}
+
+// ==================== 快速添加数据源 ====================
+
+/// 快速添加中的项目 / 分区 / 标签按名称（不区分大小写）解析为 ID
+impl QuickAddIndex for TodoStore {
+    fn project_id(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        self.projects.iter().find(|p| p.name.to_lowercase() == name).map(|p| p.id.clone())
+    }
+
+    fn section(&self, project_id: Option<&str>, name: &str) -> Option<(String, Option<String>)> {
+        let name = name.to_lowercase();
+        self.sections
+            .iter()
+            .filter(|s| project_id.is_none() || s.project_id.as_deref() == project_id)
+            .find(|s| s.name.to_lowercase() == name)
+            .map(|s| (s.id.clone(), s.project_id.clone()))
+    }
+
+    fn label_id(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        self.labels.iter().find(|l| l.name.to_lowercase() == name).map(|l| l.id.clone())
+    }
+}
+
+/// 按 child_order 插入到已排序的索引列表中，保持项目/分区列表的手动顺序
+fn insert_by_child_order(items: &mut Vec<Arc<ItemModel>>, item: &Arc<ItemModel>) {
+    let pos = items.partition_point(|other| other.cmp_child_order(item).is_le());
+    items.insert(pos, item.clone());
+}
+
 #[cfg(test)]
 mod tests {
     use todos::DueDate;
@@ -1219,4 +1553,141 @@ mod tests {
         store.set_items(vec![a]);
         assert_eq!(store.items_by_label("l1").len(), 1);
     }
+
+    #[test]
+    fn test_filter_items_uses_names_and_subprojects() {
+        let mut store = TodoStore::new();
+        store.set_projects(vec![
+            ProjectModel { id: "p1".to_string(), name: "Work".to_string(), ..Default::default() },
+            ProjectModel {
+                id: "p2".to_string(),
+                name: "Infra".to_string(),
+                parent_id: Some("p1".to_string()),
+                ..Default::default()
+            },
+        ]);
+        store.set_labels(vec![LabelModel {
+            id: "l1".to_string(),
+            name: "waiting".to_string(),
+            ..Default::default()
+        }]);
+        let mut blocked = create_test_item_with_project("1", false, false, None, "p1");
+        blocked.labels = Some("l1".to_string());
+        store.set_items(vec![
+            blocked,
+            create_test_item_with_project("2", false, true, None, "p2"),
+            create_test_item_with_project("3", true, false, None, "p1"),
+            create_test_item("4", false, true, None),
+        ]);
+
+        let ids = |query: &str| -> Vec<String> {
+            let query = FilterQuery::parse(query).unwrap();
+            store.filter_items(&query).iter().map(|i| i.id.clone()).collect()
+        };
+        assert_eq!(ids("#work"), vec!["1"]);
+        assert_eq!(ids("##WORK"), vec!["1", "2"]);
+        assert_eq!(ids("##work & !@waiting"), vec!["2"]);
+        assert_eq!(ids("pinned & !#infra"), vec!["4"]);
+    }
+
+    #[test]
+    fn test_quick_add_resolves_names() {
+        let mut store = TodoStore::new();
+        store.set_projects(vec![ProjectModel {
+            id: "p1".to_string(),
+            name: "Finance".to_string(),
+            ..Default::default()
+        }]);
+        store.set_sections(vec![
+            SectionModel {
+                id: "s1".to_string(),
+                name: "Bills".to_string(),
+                project_id: Some("p1".to_string()),
+                ..Default::default()
+            },
+            SectionModel {
+                id: "s2".to_string(),
+                name: "Bills".to_string(),
+                project_id: Some("p2".to_string()),
+                ..Default::default()
+            },
+        ]);
+        store.set_labels(vec![LabelModel {
+            id: "l1".to_string(),
+            name: "home".to_string(),
+            ..Default::default()
+        }]);
+
+        assert_eq!(store.project_id("finance"), Some("p1".to_string()));
+        assert_eq!(
+            store.section(Some("p2"), "BILLS"),
+            Some(("s2".to_string(), Some("p2".to_string())))
+        );
+        assert_eq!(store.section(Some("p3"), "bills"), None);
+        assert_eq!(store.label_id("Home"), Some("l1".to_string()));
+        assert_eq!(store.label_id("work"), None);
+    }
+
+    #[test]
+    fn test_project_index_follows_child_order() {
+        let ordered = |id: &str, order: Option<i32>| {
+            let mut item = create_test_item_with_project(id, false, false, None, "p1");
+            item.child_order = order;
+            item
+        };
+        let mut store = TodoStore::new();
+        store.set_items(vec![
+            ordered("a", None),
+            ordered("b", Some(2048)),
+            ordered("c", Some(1024)),
+        ]);
+        let ids = |store: &TodoStore| -> Vec<String> {
+            store.items_by_project("p1").iter().map(|item| item.id.clone()).collect()
+        };
+        assert_eq!(ids(&store), ["c", "b", "a"]);
+
+        store.update_item(Arc::new(ordered("a", Some(512))));
+        assert_eq!(ids(&store), ["a", "c", "b"]);
+
+        store.add_item(Arc::new(ordered("d", Some(1536))));
+        assert_eq!(ids(&store), ["a", "c", "d", "b"]);
+    }
+
+    #[test]
+    fn test_today_items_follow_day_order() {
+        let today = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut first = create_test_item("first", false, false, Some(&today));
+        first.day_order = Some(1024);
+        let mut second = create_test_item("second", false, false, Some(&today));
+        second.day_order = Some(2048);
+        let unordered = create_test_item("unordered", false, false, Some(&today));
+
+        let mut store = TodoStore::new();
+        store.set_items(vec![unordered, second, first]);
+        let ids: Vec<String> = store.today_items().iter().map(|item| item.id.clone()).collect();
+        assert_eq!(ids, ["first", "second", "unordered"]);
+    }
+
+    #[test]
+    fn test_rollback_items() {
+        let mut store = TodoStore::new();
+        store.set_items(vec![create_test_item("1", false, false, None)]);
+
+        let mut edited = create_test_item("1", false, true, None);
+        edited.content = "edited".to_string();
+        store.update_item(Arc::new(edited));
+        store.add_item(Arc::new(create_test_item("temp_1", false, false, None)));
+        assert_eq!(store.pinned_items().len(), 1);
+        store.take_change_mask();
+
+        let before = Arc::new(create_test_item("1", false, false, None));
+        store.rollback_items(&["temp_1".to_string()], &[before]);
+
+        assert!(store.get_item("temp_1").is_none());
+        let item = store.get_item("1").unwrap();
+        assert!(!item.pinned);
+        assert!(item.content.is_empty());
+        assert!(store.pinned_items().is_empty());
+        assert!(store.peek_change_mask().items_changed);
+    }
 }
@@ -18,6 +18,7 @@ use gpui_component::{
     text::markdown,
 };
 use serde::Deserialize;
+use todos::services::{ExportFormat, IcsComponent};
 use tracing_subscriber::{layer::SubscriberExt as _, util::SubscriberInitExt as _};
 // 核心模块
 pub mod core;
@@ -68,9 +69,27 @@ pub struct SelectFont(usize);
 #[action(namespace = mytool, no_json)]
 pub struct SelectRadius(usize);
 
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = mytool, no_json)]
+pub struct ExportWorkspace(ExportFormat);
+
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = mytool, no_json)]
+pub struct ExportCalendar(IcsComponent);
+
 actions!(mytool, [
     About,
     Open,
+    ImportTodoist,
+    ImportCalendar,
+    NewProjectFromTemplate,
+    AddCalDavCalendar,
+    SyncCalendars,
+    AddTodoistAccount,
+    SyncTodoist,
+    RestoreBackup,
+    RetryFailedWrites,
+    DiscardFailedWrites,
     Quit,
     ToggleSearch,
     TestAction,
@@ -248,6 +267,18 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("cmd-q", Quit, None),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("alt-f4", Quit, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-z", UndoChange, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-z", UndoChange, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-z", RedoChange, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-z", RedoChange, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-d", DuplicateTask, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-d", DuplicateTask, None),
     ]);
 
     cx.on_action(|_: &Quit, cx: &mut App| {
@@ -264,6 +295,74 @@ pub fn init(cx: &mut App) {
         request_shutdown();
     });
 
+    cx.on_action(|_: &ImportTodoist, cx: &mut App| {
+        todo_actions::import_todoist_backup(cx);
+    });
+
+    cx.on_action(|action: &ExportWorkspace, cx: &mut App| {
+        todo_actions::export_workspace(action.0, cx);
+    });
+
+    cx.on_action(|_: &ImportCalendar, cx: &mut App| {
+        todo_actions::import_ics(cx);
+    });
+
+    cx.on_action(|action: &ExportCalendar, cx: &mut App| {
+        todo_actions::export_calendar(action.0, cx);
+    });
+
+    cx.on_action(|_: &NewProjectFromTemplate, cx: &mut App| {
+        if let Some(window) = cx.active_window().and_then(|w| w.downcast::<Root>()) {
+            open_template_dialog(window, cx);
+        }
+    });
+
+    cx.on_action(|_: &SyncCalendars, cx: &mut App| {
+        todo_actions::sync_calendars(cx);
+    });
+
+    cx.on_action(|_: &AddCalDavCalendar, cx: &mut App| {
+        if let Some(window) = cx.active_window().and_then(|w| w.downcast::<Root>()) {
+            cx.defer(move |cx| {
+                window
+                    .update(cx, |_, window, cx| {
+                        window.defer(cx, |window, cx| show_caldav_dialog(window, cx));
+                    })
+                    .unwrap();
+            });
+        }
+    });
+
+    cx.on_action(|_: &SyncTodoist, cx: &mut App| {
+        todo_actions::sync_todoist(cx);
+    });
+
+    cx.on_action(|_: &RestoreBackup, cx: &mut App| {
+        if let Some(window) = cx.active_window().and_then(|w| w.downcast::<Root>()) {
+            open_restore_dialog(window, cx);
+        }
+    });
+
+    cx.on_action(|_: &RetryFailedWrites, cx: &mut App| {
+        todo_actions::retry_failed_writes(cx);
+    });
+
+    cx.on_action(|_: &DiscardFailedWrites, cx: &mut App| {
+        todo_actions::discard_failed_writes(cx);
+    });
+
+    cx.on_action(|_: &AddTodoistAccount, cx: &mut App| {
+        if let Some(window) = cx.active_window().and_then(|w| w.downcast::<Root>()) {
+            cx.defer(move |cx| {
+                window
+                    .update(cx, |_, window, cx| {
+                        window.defer(cx, |window, cx| show_todoist_dialog(window, cx));
+                    })
+                    .unwrap();
+            });
+        }
+    });
+
     cx.on_action(|_: &About, cx: &mut App| {
         if let Some(window) = cx.active_window().and_then(|w| w.downcast::<Root>()) {
             cx.defer(move |cx| {
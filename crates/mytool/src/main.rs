@@ -5,13 +5,34 @@
 use std::{process, sync::mpsc::channel, thread};
 
 use gpui_component_assets::Assets;
-use mytool::{Gallery, todo_state::get_todo_conn};
+use mytool::{
+    Gallery,
+    todo_state::{get_todo_conn, pending_migrations},
+};
 
 #[tokio::main]
 async fn main() {
     let app = gpui_platform::application().with_assets(Assets);
     let name = std::env::args().nth(1);
 
+    // 只预览待执行的数据库迁移，不启动界面；在建立正式连接之前处理，避免触发迁移前备份
+    if name.as_deref() == Some("--migrate-dry-run") {
+        match pending_migrations().await {
+            Ok(pending) if pending.is_empty() => println!("数据库已是最新版本"),
+            Ok(pending) => {
+                for step in pending {
+                    println!("{:>4}  {}  ({})", step.version, step.description, step.checksum);
+                }
+            },
+            Err(e) => {
+                eprintln!("❌ 读取数据库版本失败: {}", e);
+                process::exit(1);
+            },
+        }
+        process::exit(0);
+    }
+
+    // 数据库比程序新时，Store 初始化执行迁移前的版本检查会拒绝启动
     let db = match get_todo_conn().await {
         Ok(db) => db,
         Err(e) => {
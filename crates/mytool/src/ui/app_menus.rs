@@ -2,9 +2,12 @@ use gpui::{App, Entity, Menu, MenuItem, SharedString};
 use gpui_component::{
     ActiveTheme as _, GlobalState, Theme, ThemeMode, ThemeRegistry, menu::AppMenuBar,
 };
+use todos::services::{ExportFormat, IcsComponent};
 
 use crate::{
-    About, Open, Quit, SelectLocale, ToggleSearch,
+    About, AddCalDavCalendar, AddTodoistAccount, ExportCalendar, ExportWorkspace, ImportCalendar,
+    ImportTodoist, NewProjectFromTemplate, Open, Quit, RestoreBackup, SelectLocale, SyncCalendars,
+    SyncTodoist, ToggleSearch,
     themes::{SwitchTheme, SwitchThemeMode},
 };
 
@@ -73,6 +76,35 @@ fn build_menus(title: impl Into<SharedString>, cx: &App) -> Vec<Menu> {
             ],
             disabled: false,
         },
+        Menu {
+            name: "File".into(),
+            items: vec![
+                MenuItem::action("Import from Todoist...", ImportTodoist),
+                MenuItem::action("Import from Calendar (.ics)...", ImportCalendar),
+                MenuItem::action("New Project from Template...", NewProjectFromTemplate),
+                MenuItem::Separator,
+                MenuItem::action("Export as JSON...", ExportWorkspace(ExportFormat::Json)),
+                MenuItem::action("Export as CSV...", ExportWorkspace(ExportFormat::Csv)),
+                MenuItem::action("Export as Markdown...", ExportWorkspace(ExportFormat::Markdown)),
+                MenuItem::Separator,
+                MenuItem::action(
+                    "Export to Calendar (.ics)...",
+                    ExportCalendar(IcsComponent::Event),
+                ),
+                MenuItem::action(
+                    "Export Tasks as VTODO (.ics)...",
+                    ExportCalendar(IcsComponent::Todo),
+                ),
+                MenuItem::Separator,
+                MenuItem::action("Add CalDAV Calendar...", AddCalDavCalendar),
+                MenuItem::action("Sync Calendars", SyncCalendars),
+                MenuItem::action("Add Todoist Account...", AddTodoistAccount),
+                MenuItem::action("Sync Todoist", SyncTodoist),
+                MenuItem::Separator,
+                MenuItem::action("Restore from Backup...", RestoreBackup),
+            ],
+            disabled: false,
+        },
         Menu {
             name: "Edit".into(),
             items: vec![
@@ -183,7 +183,7 @@ impl AttachmentButtonState {
             let file_path = file_handle.path().to_path_buf();
             let file_name = file_handle.file_name();
             let file_size = match std::fs::metadata(&file_path) {
-                Ok(metadata) => metadata.len(),
+                Ok(metadata) => metadata.len() as i64,
                 Err(e) => {
                     cx.update_entity(&view, |_this, cx| {
                         cx.emit(AttachmentButtonEvent::Error(Box::new(
@@ -0,0 +1,76 @@
+//! 添加 CalDAV 日历的对话框
+//!
+//! 填写日历集合地址与账号后创建一个同步项目，并立即做一次同步。
+
+use gpui::{App, AppContext, ParentElement, Styled, Window};
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogAction, DialogClose, DialogFooter},
+    input::{Input, InputState},
+    v_flex,
+};
+use todos::{services::CalDavConfig, sync::CalDavClient};
+
+use crate::{VisualHierarchy, todo_actions::add_caldav_calendar};
+
+pub fn show_caldav_dialog(window: &mut Window, cx: &mut App) {
+    let url_input = cx.new(|cx| {
+        InputState::new(window, cx).placeholder("https://dav.example.com/calendars/me/tasks/")
+    });
+    let username_input = cx.new(|cx| InputState::new(window, cx).placeholder("Username"));
+    let password_input =
+        cx.new(|cx| InputState::new(window, cx).placeholder("Password").masked(true));
+    let name_input =
+        cx.new(|cx| InputState::new(window, cx).placeholder("Project Name (optional)"));
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        modal
+            .title("Add CalDAV Calendar")
+            .overlay(false)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(Input::new(&url_input))
+                    .child(Input::new(&username_input))
+                    .child(Input::new(&password_input))
+                    .child(Input::new(&name_input))
+                    .child(gpui::div().text_xs().text_color(muted).child(
+                        "Tasks (VTODO) in the calendar are synced both ways with a new project.",
+                    )),
+            )
+            .footer(
+                DialogFooter::new()
+                    .child(
+                        DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                    )
+                    .child(DialogAction::new().child(Button::new("save").primary().label("Add"))),
+            )
+            .on_ok({
+                let url_input = url_input.clone();
+                let username_input = username_input.clone();
+                let password_input = password_input.clone();
+                let name_input = name_input.clone();
+                move |_, window: &mut Window, cx| {
+                    let calendar_url = url_input.read(cx).value().trim().to_string();
+                    if let Err(e) = CalDavClient::normalize_url(&calendar_url) {
+                        window.push_notification(e.to_string(), cx);
+                        return false;
+                    }
+                    let display_name = name_input.read(cx).value().trim().to_string();
+                    let config = CalDavConfig {
+                        calendar_url,
+                        username: username_input.read(cx).value().trim().to_string(),
+                        password: password_input.read(cx).value().to_string(),
+                        display_name: (!display_name.is_empty()).then_some(display_name),
+                    };
+                    add_caldav_calendar(config, cx);
+                    window.push_notification("Syncing calendar...", cx);
+                    true
+                }
+            })
+    });
+}
@@ -17,7 +17,7 @@ use crate::{
         notification::{NotificationExt as _, NotificationSystem},
         state::TodoStore,
     },
-    todo_actions::update_item_optimistic,
+    todo_actions::{move_items_to_project, update_item_optimistic},
 };
 
 impl ItemInfoState {
@@ -32,6 +32,7 @@ impl ItemInfoState {
             InputEvent::Change => {
                 let text = state.read(cx).value().to_string();
                 if state == &self.name_input {
+                    self.update_quick_add(&text, cx);
                     self.state_manager.set_content(text);
                 } else {
                     self.state_manager.set_description(Some(text));
@@ -138,12 +139,15 @@ impl ItemInfoState {
                         section_state.set_section(None, window, cx);
                     });
 
-                    // 如果是新建任务，只更新 state_manager，不保存到数据库
+                    // 如果是新建任务，只更新 state_manager，不保存到数据库；
+                    // 已有任务连同子任务一起移动，旧项目的分区不会带过去
                     if !self.state_manager.is_new_item() {
-                        // 🚀 使用乐观更新（立即更新 UI）
-                        update_item_optimistic(self.state_manager.item.clone(), cx);
-                        // 设置标志以避免在 handle_item_info_event 中重复更新
-                        self.state_manager.skip_next_update = true;
+                        move_items_to_project(
+                            vec![self.state_manager.item.clone()],
+                            new_project_id,
+                            None,
+                            cx,
+                        );
                     }
                 }
             },
@@ -167,14 +171,14 @@ impl ItemInfoState {
 
                 // 只有当section_id实际变化时才更新
                 if current_item.section_id != new_section_id {
-                    self.state_manager.set_section_id(new_section_id);
+                    self.state_manager.set_section_id(new_section_id.clone());
 
-                    // 如果是新建任务，只更新 state_manager，不保存到数据库
+                    // 如果是新建任务，只更新 state_manager，不保存到数据库；
+                    // 已有任务连同子任务一起移到新分区
                     if !self.state_manager.is_new_item() {
-                        // 🚀 使用乐观更新（立即更新 UI）
-                        update_item_optimistic(self.state_manager.item.clone(), cx);
-                        // 设置标志以避免在 handle_item_info_event 中重复更新
-                        self.state_manager.skip_next_update = true;
+                        let item = self.state_manager.item.clone();
+                        let project_id = item.project_id.clone();
+                        move_items_to_project(vec![item], project_id, new_section_id, cx);
                     }
                     // 立即通知UI更新
                     cx.notify();
@@ -0,0 +1,179 @@
+use gpui::{
+    Context, IntoElement, ParentElement as _, Styled, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    theme::ActiveTheme,
+    v_flex,
+};
+use todos::{
+    enums::item_priority::ItemPriority,
+    services::{EventEntry, EventKind},
+};
+
+use super::{ItemInfoState, ItemInfoTab};
+use crate::core::{notification::NotificationSystem, state::TodoStore};
+
+impl ItemInfoState {
+    /// 切换详情 / 历史标签页，进入历史页时重新加载活动记录
+    pub(super) fn set_active_tab(&mut self, tab: ItemInfoTab, cx: &mut Context<Self>) {
+        if self.active_tab == tab {
+            return;
+        }
+        self.active_tab = tab;
+        if tab == ItemInfoTab::History {
+            self.load_history(cx);
+        }
+        cx.notify();
+    }
+
+    /// 异步加载当前任务的活动记录（临时 ID 尚未落库，没有记录）
+    pub(super) fn load_history(&mut self, cx: &mut Context<Self>) {
+        let item_id = self.state_manager.item.id.clone();
+        if item_id.is_empty() || item_id.starts_with("temp_") {
+            self.history.clear();
+            return;
+        }
+
+        let db_state = cx.global::<crate::todo_state::DBState>().clone();
+        let this_entity = cx.entity();
+        cx.spawn(async move |_this, cx| {
+            if !db_state.is_store_ready() {
+                return;
+            }
+            let store = db_state.get_store_async().await;
+            match crate::state_service::load_item_events_with_store(&item_id, store).await {
+                Ok(events) => {
+                    cx.update_entity(&this_entity, |this, cx| {
+                        // 加载期间切换了任务则丢弃结果
+                        if this.state_manager.item.id == item_id {
+                            this.history = events;
+                            cx.notify();
+                        }
+                    });
+                },
+                Err(e) => NotificationSystem::log_error("Failed to load item history", e),
+            }
+        })
+        .detach();
+    }
+
+    pub(super) fn render_tabs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let tabs = [
+            (ItemInfoTab::Details, "item-tab-details", "Details"),
+            (ItemInfoTab::History, "item-tab-history", "History"),
+        ];
+        h_flex().gap_1().px(px(6.0)).pt(px(4.0)).children(tabs.map(|(tab, id, label)| {
+            let active = self.active_tab == tab;
+            Button::new(id)
+                .small()
+                .compact()
+                .label(label)
+                .when(active, |this| this.primary())
+                .when(!active, |this| this.ghost())
+                .on_click(cx.listener(move |this, _event, _window, cx| {
+                    this.set_active_tab(tab, cx);
+                }))
+        }))
+    }
+
+    pub(super) fn render_history(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let rows: Vec<(String, String)> = self
+            .history
+            .iter()
+            .map(|event| {
+                let when = event
+                    .event_date
+                    .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                (when, describe_event(event, cx.global::<TodoStore>()))
+            })
+            .collect();
+
+        v_flex()
+            .gap_1()
+            .p(px(6.0))
+            .max_h(px(240.0))
+            .overflow_hidden()
+            .when(rows.is_empty(), |this| {
+                this.child(div().text_sm().text_color(muted).child("No activity yet"))
+            })
+            .children(rows.into_iter().map(|(when, text)| {
+                h_flex()
+                    .gap_2()
+                    .items_start()
+                    .py(px(2.0))
+                    .border_b_1()
+                    .border_color(border.opacity(0.3))
+                    .child(div().w(px(110.0)).flex_none().text_xs().text_color(muted).child(when))
+                    .child(div().flex_1().text_sm().child(text))
+            }))
+    }
+}
+
+/// 把一条活动记录转成可读文本
+fn describe_event(event: &EventEntry, store: &TodoStore) -> String {
+    let old = event.old_value.as_deref().unwrap_or_default();
+    let new = event.new_value.as_deref().unwrap_or_default();
+
+    match event.kind {
+        EventKind::Created => "Created".to_string(),
+        EventKind::ContentChanged => format!("Renamed “{}” → “{}”", old, new),
+        EventKind::DescriptionChanged => "Description updated".to_string(),
+        EventKind::DueChanged => {
+            format!("Due date {} → {}", due_date_text(old), due_date_text(new))
+        },
+        EventKind::PriorityChanged => {
+            format!("Priority {} → {}", priority_text(old), priority_text(new))
+        },
+        EventKind::LabelsChanged => "Labels updated".to_string(),
+        EventKind::PinnedChanged => {
+            if is_true(new) {
+                "Pinned".to_string()
+            } else {
+                "Unpinned".to_string()
+            }
+        },
+        EventKind::CheckedChanged => {
+            if is_true(new) {
+                "Completed".to_string()
+            } else {
+                "Reopened".to_string()
+            }
+        },
+        EventKind::SectionChanged => {
+            let name = store.get_section(new).map(|s| s.name.clone());
+            format!("Moved to section {}", name.unwrap_or_else(|| "(none)".to_string()))
+        },
+        EventKind::ProjectChanged => {
+            let name = store.get_project(new).map(|p| p.name.clone());
+            format!("Moved to project {}", name.unwrap_or_else(|| "Inbox".to_string()))
+        },
+        EventKind::RecurringCompleted => {
+            format!("Completed occurrence {}, next due {}", old, new)
+        },
+        EventKind::Other => format!("{} changed", event.object_key),
+    }
+}
+
+// 触发器写入的是数据库原值：布尔为 0/1，due 为 DueDate 的 JSON
+fn is_true(value: &str) -> bool {
+    matches!(value, "1" | "true")
+}
+
+fn due_date_text(value: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(value)
+        .ok()
+        .and_then(|due| due.get("date").and_then(|d| d.as_str()).map(str::to_string))
+        .filter(|date| !date.is_empty())
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+fn priority_text(value: &str) -> String {
+    let priority = ItemPriority::from_i32(value.parse().unwrap_or_default());
+    format!("P{}", priority as i32)
+}
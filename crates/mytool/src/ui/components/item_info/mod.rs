@@ -15,7 +15,9 @@ use gpui_component::{
     theme::ActiveTheme,
     v_flex,
 };
-use todos::{entity::ItemModel, enums::item_priority::ItemPriority};
+use todos::{
+    QuickAdd, entity::ItemModel, enums::item_priority::ItemPriority, services::EventEntry,
+};
 use tracing::{info, warn};
 
 use super::{
@@ -34,13 +36,15 @@ use crate::{
 };
 
 mod handlers;
+mod history;
 mod item_state_manager;
 mod labels;
+mod quick_add;
 mod save;
 mod types;
 
 pub use item_state_manager::{ItemStateManager, SaveItemStatus};
-pub use types::ItemInfoEvent;
+pub use types::{ItemInfoEvent, ItemInfoTab};
 
 const CONTEXT: &str = "ItemInfo";
 
@@ -60,6 +64,11 @@ pub struct ItemInfoState {
     label_popover_list: Entity<LabelsPopoverList>,
     attachment_state: Entity<AttachmentButtonState>,
     reminder_state: Entity<ReminderButtonState>,
+    // history view
+    active_tab: ItemInfoTab,
+    history: Vec<EventEntry>,
+    /// 新建任务时名称输入框中识别出的快速添加语法
+    quick_add: Option<QuickAdd>,
 }
 
 impl Focusable for ItemInfoState {
@@ -72,7 +81,10 @@ impl ItemInfoState {
     pub fn new(item: Arc<ItemModel>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let item = item.clone();
 
-        let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Task name..."));
+        let name_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Task name... e.g. Pay rent monthly on the 1st 9am p1 #Finance @home")
+        });
 
         let desc_input = cx.new(|cx| {
             InputState::new(window, cx).auto_grow(5, 20).placeholder("Add description...")
@@ -177,6 +189,9 @@ impl ItemInfoState {
             label_popover_list,
             attachment_state,
             reminder_state,
+            active_tab: ItemInfoTab::default(),
+            history: Vec::new(),
+            quick_add: None,
         };
         this.set_item(item, window, cx);
         this
@@ -208,6 +223,13 @@ impl ItemInfoState {
         // 更新 state_manager
         self.state_manager = ItemStateManager::new(item.clone());
 
+        // 切换任务后历史记录失效，历史页打开时重新加载
+        self.history.clear();
+        self.quick_add = None;
+        if self.active_tab == ItemInfoTab::History {
+            self.load_history(cx);
+        }
+
         self.name_input.update(cx, |this, cx| {
             this.set_value(item.content.clone(), window, cx);
         });
@@ -514,88 +536,96 @@ impl Render for ItemInfoState {
                         },
                     ),
             )
-            .child(
-                Input::new(&self.desc_input)
-                    .bordered(false)
-                    .px(px(6.0))
-                    .py(px(4.0))
-                    .bg(cx.theme().background.opacity(0.5))
-            )
-            .child(
-                h_flex()
-                    .gap_2()
-                    .p(px(6.0))
-                    .flex_wrap()
-                    .children(labels.iter().map(|label| {
-                        let label_clone = label.clone();
-                        let view_clone = view.clone();
-                        let is_checked = selected_labels.iter().any(|l| l.id == label.id);
-                        div()
-                            .flex()
-                            .items_center()
-                            .gap_2()
-                            .p_1()
-                            .rounded(px(4.0))
-                            .hover(|style| style.bg(cx.theme().accent.opacity(0.1)))
-                            .child(
-                                Checkbox::new(format!("label-checkbox-{}", label.id))
-                                    .checked(is_checked)
-                                    .on_click(cx.listener(move |_this, _event, window, cx| {
-                                        info!("Label checkbox clicked! Label: {}", label_clone.name);
-                                        let label_model = label_clone.as_ref().clone();
-                                        cx.update_entity(&view_clone, |view, cx| {
-                                            let new_checked = !view.selected_labels(cx).iter().any(|l| l.id == label_clone.id);
-                                            view.label_toggle_checked(Arc::new(label_model), &new_checked, window, cx);
-                                        });
-                                    }))
-                            )
-                            .child(label.name.clone())
-                    }))
-            )
-            .child(
-                h_flex()
-                    .items_center()
-                    .justify_between()
-                    .gap_1()
-                    .p(px(6.0))
-                    .bg(cx.theme().background.opacity(0.3))
-                    .border_t_1()
-                    .border_color(cx.theme().border.opacity(0.5))
+            .children(self.render_quick_add(cx))
+            .child(self.render_tabs(cx))
+            .when(self.active_tab == ItemInfoTab::History, |this| {
+                this.child(self.render_history(cx))
+            })
+            .when(self.active_tab == ItemInfoTab::Details, |this| {
+                this
                     .child(
-                        h_flex().gap_1().child(
-                            h_flex()
-                                .gap_1()
-                                .overflow_x_hidden()
-                                .flex_nowrap()
-                                .child(ScheduleButton::new(&self.schedule_button_state))
-                                .child(RecurrencyButton::new(&self.recurrency_button_state)),
-                        ),
+                        Input::new(&self.desc_input)
+                            .bordered(false)
+                            .px(px(6.0))
+                            .py(px(4.0))
+                            .bg(cx.theme().background.opacity(0.5))
                     )
                     .child(
                         h_flex()
-                            .gap_1()
-                            .items_center()
-                            .justify_end()
-                            .child(AttachmentButton::new(&self.attachment_state))
-                            .child(self.label_popover_list.clone()) // tags
-                            .child(PriorityButton::new(&self.priority_state)) // priority
-                            .child(ReminderButton::new(&self.reminder_state)),
-                ),
-            )
-            .child(Separator::horizontal().p_1())
-            .child(
-                h_flex().items_center().justify_between().gap_1().child(
-                    h_flex().gap_1().child(
+                            .gap_2()
+                            .p(px(6.0))
+                            .flex_wrap()
+                            .children(labels.iter().map(|label| {
+                                let label_clone = label.clone();
+                                let view_clone = view.clone();
+                                let is_checked = selected_labels.iter().any(|l| l.id == label.id);
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .p_1()
+                                    .rounded(px(4.0))
+                                    .hover(|style| style.bg(cx.theme().accent.opacity(0.1)))
+                                    .child(
+                                        Checkbox::new(format!("label-checkbox-{}", label.id))
+                                            .checked(is_checked)
+                                            .on_click(cx.listener(move |_this, _event, window, cx| {
+                                                info!("Label checkbox clicked! Label: {}", label_clone.name);
+                                                let label_model = label_clone.as_ref().clone();
+                                                cx.update_entity(&view_clone, |view, cx| {
+                                                    let new_checked = !view.selected_labels(cx).iter().any(|l| l.id == label_clone.id);
+                                                    view.label_toggle_checked(Arc::new(label_model), &new_checked, window, cx);
+                                                });
+                                            }))
+                                    )
+                                    .child(label.name.clone())
+                            }))
+                    )
+                    .child(
                         h_flex()
+                            .items_center()
+                            .justify_between()
                             .gap_1()
-                            .overflow_x_hidden()
-                            .flex_nowrap()
-                            .child(ProjectButton::new(&self.project_state))
-                            .child("——>")
-                            .child(SectionButton::new(&self.section_state)),
-                    ),
-                ),
-            )
+                            .p(px(6.0))
+                            .bg(cx.theme().background.opacity(0.3))
+                            .border_t_1()
+                            .border_color(cx.theme().border.opacity(0.5))
+                            .child(
+                                h_flex().gap_1().child(
+                                    h_flex()
+                                        .gap_1()
+                                        .overflow_x_hidden()
+                                        .flex_nowrap()
+                                        .child(ScheduleButton::new(&self.schedule_button_state))
+                                        .child(RecurrencyButton::new(&self.recurrency_button_state)),
+                                ),
+                            )
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .items_center()
+                                    .justify_end()
+                                    .child(AttachmentButton::new(&self.attachment_state))
+                                    .child(self.label_popover_list.clone()) // tags
+                                    .child(PriorityButton::new(&self.priority_state)) // priority
+                                    .child(ReminderButton::new(&self.reminder_state)),
+                        ),
+                    )
+                    .child(Separator::horizontal().p_1())
+                    .child(
+                        h_flex().items_center().justify_between().gap_1().child(
+                            h_flex().gap_1().child(
+                                h_flex()
+                                    .gap_1()
+                                    .overflow_x_hidden()
+                                    .flex_nowrap()
+                                    .child(ProjectButton::new(&self.project_state))
+                                    .child("——>")
+                                    .child(SectionButton::new(&self.section_state)),
+                            ),
+                        ),
+                    )
+            })
     }
 }
 
@@ -0,0 +1,72 @@
+use gpui::{
+    Context, FontWeight, HighlightStyle, IntoElement, ParentElement as _, Styled, StyledText, div,
+    px,
+};
+use gpui_component::theme::ActiveTheme;
+use todos::{QuickAdd, QuickAddTokenKind};
+
+use super::ItemInfoState;
+use crate::{core::state::TodoStore, ui::theme::visual_enhancements::SemanticColors};
+
+impl ItemInfoState {
+    /// 新建任务时解析名称输入框中的快速添加语法（日期、优先级、#项目、/分区、@标签）
+    pub(super) fn update_quick_add(&mut self, text: &str, cx: &mut Context<Self>) {
+        if !self.state_manager.is_new_item() {
+            self.quick_add = None;
+            return;
+        }
+        let now = chrono::Local::now().naive_local();
+        let parsed = QuickAdd::parse(text, now, cx.global::<TodoStore>());
+        self.quick_add = parsed.has_attributes().then_some(parsed);
+    }
+
+    /// 把识别出的属性写入新任务，内容去掉识别出的片段
+    pub(super) fn apply_quick_add(&mut self) {
+        let Some(parsed) = self.quick_add.take() else {
+            return;
+        };
+        if parsed.content.trim().is_empty() {
+            return;
+        }
+        self.state_manager.update_item(|item| parsed.apply(item));
+        self.state_manager.mark_dirty();
+    }
+
+    /// 名称输入框下方的预览行，高亮识别出的片段
+    pub(super) fn render_quick_add(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let parsed = self.quick_add.as_ref()?;
+        let source = self.name_input.read(cx).value().to_string();
+        let colors = SemanticColors::from_theme(cx);
+        let theme = cx.theme();
+        let highlights: Vec<_> = parsed
+            .tokens
+            .iter()
+            .filter(|token| token.range.end <= source.len())
+            .map(|token| {
+                let color = match token.kind {
+                    QuickAddTokenKind::Date => colors.status_scheduled,
+                    QuickAddTokenKind::Priority => {
+                        colors.priority_color(parsed.priority.unwrap_or_default())
+                    },
+                    QuickAddTokenKind::Project | QuickAddTokenKind::Section => theme.primary,
+                    QuickAddTokenKind::Label => colors.success,
+                };
+                let style = HighlightStyle {
+                    color: Some(color),
+                    font_weight: Some(FontWeight::SEMIBOLD),
+                    ..Default::default()
+                };
+                (token.range.clone(), style)
+            })
+            .collect();
+
+        Some(
+            div()
+                .px(px(8.0))
+                .pb(px(4.0))
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child(StyledText::new(source).with_highlights(highlights)),
+        )
+    }
+}
@@ -29,8 +29,9 @@ impl ItemInfoState {
         tracing::debug!("save_all_changes START - item_id: {}", self.state_manager.item.id);
         info!("🔔🔔🔔 save_all_changes START - item_id: {}", self.state_manager.item.id);
 
-        // 同步输入框内容
+        // 同步输入框内容，新建任务再应用快速添加语法
         let has_input_changes = self.sync_inputs(cx);
+        self.apply_quick_add();
 
         // 先克隆需要的数据，避免借用冲突
         let current_item = self.state_manager.item.clone();
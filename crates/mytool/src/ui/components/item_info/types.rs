@@ -9,3 +9,11 @@ pub enum ItemInfoEvent {
     SaveSucceeded(), // 🚀 7.0: 异步保存成功
     SaveFailed(),    // 🚀 7.0: 异步保存失败
 }
+
+/// ItemInfo 的标签页
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ItemInfoTab {
+    #[default]
+    Details, // 任务详情
+    History, // 活动记录
+}
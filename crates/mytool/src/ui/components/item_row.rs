@@ -248,6 +248,36 @@ impl ItemRowState {
         true
     }
 
+    /// 处理移动任务快捷键 (Cmd/Ctrl + M)：连同子任务移到其他项目 / 分区
+    fn handle_move_shortcut(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        crate::show_move_items_dialog(vec![self.item.clone()], window, cx);
+        true
+    }
+
+    /// 处理批量移动快捷键 (Cmd/Ctrl + Shift + M)：当前列表中未完成的同级任务一起移动
+    fn handle_batch_move_shortcut(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let items: Vec<Arc<ItemModel>> = cx
+            .global::<TodoStore>()
+            .sibling_items(&self.item)
+            .into_iter()
+            .filter(|item| !item.checked)
+            .collect();
+        crate::show_move_items_dialog(items, window, cx);
+        true
+    }
+
+    /// 处理缩进快捷键 (Tab)：成为前一个任务的子任务
+    fn handle_indent_shortcut(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> bool {
+        crate::todo_actions::indent_item(self.item.clone(), cx);
+        true
+    }
+
+    /// 处理取消缩进快捷键 (Shift + Tab)
+    fn handle_outdent_shortcut(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> bool {
+        crate::todo_actions::outdent_item(self.item.clone(), cx);
+        true
+    }
+
     /// 处理收起并取消快捷键 (Escape)
     fn handle_escape_shortcut(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> bool {
         if self.is_open {
@@ -271,11 +301,15 @@ impl ItemRowState {
     ) -> bool {
         let is_cmd = event.keystroke.modifiers == gpui::Modifiers::command();
         let is_plain = event.keystroke.modifiers == gpui::Modifiers::default();
+        let is_shift = event.keystroke.modifiers == gpui::Modifiers::shift();
+        let is_cmd_shift = event.keystroke.modifiers == gpui::Modifiers::command_shift();
         let key = event.keystroke.key.as_str();
 
         match (key, is_cmd) {
             ("d", true) => return self.handle_delete_shortcut(window, cx),
             ("p", true) => return self.handle_toggle_pin_shortcut(window, cx),
+            ("m", true) => return self.handle_move_shortcut(window, cx),
+            ("m", _) if is_cmd_shift => return self.handle_batch_move_shortcut(window, cx),
             _ => {},
         }
 
@@ -296,6 +330,8 @@ impl ItemRowState {
                 },
                 ("space", true, _) => return self.handle_toggle_complete_shortcut(window, cx),
                 ("e", _, true) => return self.handle_edit_shortcut(window, cx),
+                ("tab", true, _) => return self.handle_indent_shortcut(window, cx),
+                ("tab", ..) if is_shift => return self.handle_outdent_shortcut(window, cx),
                 _ => {},
             }
         }
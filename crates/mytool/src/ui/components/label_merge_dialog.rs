@@ -0,0 +1,83 @@
+//! 合并标签的对话框
+//!
+//! 列出其他标签及其任务数，选中后源标签的任务改用目标标签，源标签移入回收站。
+
+use std::sync::Arc;
+
+use gpui::{
+    App, InteractiveElement, ParentElement, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogClose, DialogFooter},
+    h_flex, v_flex,
+};
+use todos::entity::LabelModel;
+
+use crate::{VisualHierarchy, todo_actions::merge_labels, todo_state::TodoStore};
+
+/// 打开合并对话框，把 `source` 合并到选中的标签
+pub fn show_merge_label_dialog(source: Arc<LabelModel>, window: &mut Window, cx: &mut App) {
+    let store = cx.global::<TodoStore>();
+    let usage = store.items_by_label(&source.id).len();
+    let targets: Vec<(Arc<LabelModel>, usize)> = store
+        .labels
+        .iter()
+        .filter(|label| label.id != source.id)
+        .map(|label| (label.clone(), store.items_by_label(&label.id).len()))
+        .collect();
+    let summary = format!(
+        "{} tasks will use the chosen label instead. \"{}\" moves to the trash.",
+        usage, source.name
+    );
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let rows = targets.iter().enumerate().map(|(index, (target, usage))| {
+            let source = source.clone();
+            let target = target.clone();
+            h_flex()
+                .gap_2()
+                .py(px(4.0))
+                .border_b_1()
+                .border_color(border.opacity(0.3))
+                .child(div().flex_1().text_sm().child(target.name.clone()))
+                .child(div().text_xs().text_color(muted).child(format!("{} tasks", usage)))
+                .child(
+                    Button::new(("merge-target", index))
+                        .small()
+                        .outline()
+                        .label("Merge Here")
+                        .on_click(move |_, window, cx| {
+                            merge_labels(source.clone(), target.clone(), cx);
+                            window.close_dialog(cx);
+                        }),
+                )
+        });
+
+        modal
+            .title(format!("Merge \"{}\"", source.name))
+            .overlay(true)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(div().text_xs().text_color(muted).child(summary.clone()))
+                    .child(
+                        v_flex()
+                            .id("merge-target-list")
+                            .max_h(px(360.0))
+                            .overflow_y_scroll()
+                            .children(rows),
+                    ),
+            )
+            .footer(
+                DialogFooter::new().child(
+                    DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                ),
+            )
+    });
+}
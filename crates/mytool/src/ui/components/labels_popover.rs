@@ -203,6 +203,7 @@ impl LabelsPopoverList {
             color,
             item_order: 0,
             is_deleted: false,
+            deleted_at: None,
             is_favorite: false,
             backend_type: None,
             source_id: None,
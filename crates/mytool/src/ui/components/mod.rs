@@ -1,4 +1,5 @@
 mod attachment_button;
+mod caldav_dialog;
 mod color_group;
 mod dialog;
 mod drop_btn;
@@ -7,14 +8,21 @@ mod dropbtn_project;
 mod dropbtn_section;
 mod item_info;
 mod item_row;
+mod label_merge_dialog;
 mod labels_popover;
 mod manage_sections;
+mod move_dialog;
 mod popover_base;
 mod popover_schedule;
 mod recurrency_button;
 mod reminder_button;
+mod restore_dialog;
+mod search_panel;
+mod template_dialog;
+mod todoist_dialog;
 
 pub use attachment_button::*;
+pub use caldav_dialog::*;
 pub use color_group::*;
 pub use dialog::*;
 pub use drop_btn::*;
@@ -23,9 +31,15 @@ pub use dropbtn_project::*;
 pub use dropbtn_section::*;
 pub use item_info::*;
 pub use item_row::*;
+pub use label_merge_dialog::*;
 pub use labels_popover::*;
 pub use manage_sections::*;
+pub use move_dialog::*;
 pub use popover_base::*;
 pub use popover_schedule::*;
 pub use recurrency_button::*;
 pub use reminder_button::*;
+pub use restore_dialog::*;
+pub use search_panel::*;
+pub use template_dialog::*;
+pub use todoist_dialog::*;
@@ -0,0 +1,138 @@
+//! 移动任务的对话框
+//!
+//! 列出收件箱和所有未归档的项目及其分区，选中后把任务连同子任务一起移过去。
+
+use std::sync::Arc;
+
+use gpui::{
+    App, InteractiveElement, ParentElement, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogClose, DialogFooter},
+    h_flex, v_flex,
+};
+use todos::entity::ItemModel;
+
+use crate::{VisualHierarchy, todo_actions::move_items_to_project, todo_state::TodoStore};
+
+/// 移动目标：项目（None 为收件箱）和分区
+struct MoveTarget {
+    label: String,
+    depth: usize,
+    project_id: Option<String>,
+    section_id: Option<String>,
+}
+
+fn move_targets(cx: &App) -> Vec<MoveTarget> {
+    let store = cx.global::<TodoStore>();
+    let mut targets = vec![MoveTarget {
+        label: "Inbox".to_string(),
+        depth: 0,
+        project_id: None,
+        section_id: None,
+    }];
+    for project in store.projects.iter().filter(|p| !p.is_deleted && !p.is_archived) {
+        targets.push(MoveTarget {
+            label: project.name.clone(),
+            depth: 0,
+            project_id: Some(project.id.clone()),
+            section_id: None,
+        });
+        for section in store.sections.iter().filter(|s| {
+            !s.is_deleted && !s.is_archived && s.project_id.as_deref() == Some(project.id.as_str())
+        }) {
+            targets.push(MoveTarget {
+                label: section.name.clone(),
+                depth: 1,
+                project_id: Some(project.id.clone()),
+                section_id: Some(section.id.clone()),
+            });
+        }
+    }
+    targets
+}
+
+/// 打开移动对话框，`items` 中每个任务的子任务会一起移动
+pub fn show_move_items_dialog(items: Vec<Arc<ItemModel>>, window: &mut Window, cx: &mut App) {
+    if items.is_empty() {
+        return;
+    }
+    let targets = move_targets(cx);
+    let title = match items.as_slice() {
+        [item] => format!("Move \"{}\"", item.content),
+        _ => format!("Move {} Tasks", items.len()),
+    };
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        // 所有任务已经在同一位置时，该位置不可选
+        let current = |target: &MoveTarget| {
+            items.iter().all(|item| {
+                item.parent_id.as_deref().is_none_or(|parent| parent.is_empty())
+                    && item.project_id.as_deref().filter(|id| !id.is_empty())
+                        == target.project_id.as_deref()
+                    && item.section_id.as_deref().filter(|id| !id.is_empty())
+                        == target.section_id.as_deref()
+            })
+        };
+        let rows = targets.iter().enumerate().map(|(index, target)| {
+            let items = items.clone();
+            let project_id = target.project_id.clone();
+            let section_id = target.section_id.clone();
+            h_flex()
+                .gap_2()
+                .py(px(4.0))
+                .pl(px(target.depth as f32 * 16.0))
+                .border_b_1()
+                .border_color(border.opacity(0.3))
+                .child(div().flex_1().text_sm().child(target.label.clone()))
+                .child(
+                    Button::new(("move-target", index))
+                        .small()
+                        .outline()
+                        .label("Move Here")
+                        .disabled(current(target))
+                        .on_click(move |_, window, cx| {
+                            move_items_to_project(
+                                items.clone(),
+                                project_id.clone(),
+                                section_id.clone(),
+                                cx,
+                            );
+                            window.close_dialog(cx);
+                        }),
+                )
+        });
+
+        modal
+            .title(title.clone())
+            .overlay(true)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(muted)
+                            .child("Subtasks move together with their parent task."),
+                    )
+                    .child(
+                        v_flex()
+                            .id("move-target-list")
+                            .max_h(px(360.0))
+                            .overflow_y_scroll()
+                            .children(rows),
+                    ),
+            )
+            .footer(
+                DialogFooter::new().child(
+                    DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                ),
+            )
+    });
+}
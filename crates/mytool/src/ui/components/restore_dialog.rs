@@ -0,0 +1,139 @@
+//! 从备份恢复的对话框
+//!
+//! 列出数据库旁 `backups/` 目录中的快照及其中的任务数。恢复时先备份当前数据库，
+//! 然后退出程序，下次启动、连接数据库之前替换为选中的快照。
+
+use gpui::{
+    App, BorrowAppContext, InteractiveElement, ParentElement, StatefulInteractiveElement, Styled,
+    Window, WindowHandle, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Root, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogClose, DialogFooter},
+    h_flex, v_flex,
+};
+use todos::{BackupReason, BackupSnapshot};
+
+use crate::{
+    VisualHierarchy,
+    todo_actions::{backup_manager, list_backups, restore_backup},
+    todo_state::ErrorNotifier,
+};
+
+/// 读取快照列表后打开对话框
+pub fn open_restore_dialog(window: WindowHandle<Root>, cx: &mut App) {
+    let Some(manager) = backup_manager(cx) else {
+        cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+            notifier.set_error("只有 SQLite 数据库支持备份与恢复".to_string());
+        });
+        return;
+    };
+    cx.spawn(async move |cx| match list_backups(manager).await {
+        Ok(snapshots) => {
+            let _ = window.update(cx, |_, window, cx| {
+                show_restore_dialog(snapshots, window, cx);
+            });
+        },
+        Err(e) => {
+            cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                notifier.set_error(format!("读取备份失败：{}", e));
+            });
+        },
+    })
+    .detach();
+}
+
+pub fn show_restore_dialog(snapshots: Vec<BackupSnapshot>, window: &mut Window, cx: &mut App) {
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let rows = snapshots.iter().enumerate().map(|(index, snapshot)| {
+            let path = snapshot.path.clone();
+            let items = match snapshot.item_count {
+                Some(1) => "1 task".to_string(),
+                Some(count) => format!("{} tasks", count),
+                None => "Unreadable".to_string(),
+            };
+            h_flex()
+                .gap_2()
+                .py(px(4.0))
+                .border_b_1()
+                .border_color(border.opacity(0.3))
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .child(
+                            div()
+                                .text_sm()
+                                .child(snapshot.created_at.format("%Y-%m-%d %H:%M").to_string()),
+                        )
+                        .child(div().text_xs().text_color(muted).child(format!(
+                            "{} · {}",
+                            reason_label(snapshot.reason),
+                            format_size(snapshot.size)
+                        ))),
+                )
+                .child(div().w(px(80.0)).text_sm().text_color(muted).child(items))
+                .child(
+                    Button::new(("restore-backup", index))
+                        .small()
+                        .outline()
+                        .label("Restore")
+                        .disabled(snapshot.item_count.is_none())
+                        .on_click(move |_, window, cx| {
+                            restore_backup(path.clone(), window.window_handle(), cx);
+                            window.close_dialog(cx);
+                            window.push_notification("Restoring backup, MyTool will close...", cx);
+                        }),
+                )
+        });
+
+        modal
+            .title("Restore from Backup")
+            .overlay(true)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(div().text_xs().text_color(muted).child(
+                        "The current data is backed up first. MyTool then closes to swap in the \
+                         backup; start it again to continue.",
+                    ))
+                    .child(
+                        v_flex()
+                            .id("backup-list")
+                            .max_h(px(360.0))
+                            .overflow_y_scroll()
+                            .when(snapshots.is_empty(), |this| {
+                                this.child(
+                                    div().text_sm().text_color(muted).child("No backups yet"),
+                                )
+                            })
+                            .children(rows),
+                    ),
+            )
+            .footer(
+                DialogFooter::new()
+                    .child(DialogClose::new().child(Button::new("close").label("Close").outline())),
+            )
+    });
+}
+
+fn reason_label(reason: BackupReason) -> &'static str {
+    match reason {
+        BackupReason::Startup => "On startup",
+        BackupReason::Scheduled => "Scheduled",
+        BackupReason::PreMigration => "Before upgrade",
+        BackupReason::PreRestore => "Before restore",
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
@@ -0,0 +1,234 @@
+//! 全文搜索面板
+//!
+//! 在对话框中输入关键词，实时显示任务、项目、分区和标签的搜索结果（命中部分高亮），
+//! 点击结果或按回车打开第一条结果，跳转到其所在的看板或项目。
+
+use gpui::{
+    App, AppContext, BorrowAppContext, ClickEvent, Context, Entity, FontWeight, HighlightStyle,
+    InteractiveElement, IntoElement, ParentElement, Render, StatefulInteractiveElement, Styled,
+    StyledText, Subscription, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, WindowExt, h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use todos::services::{DEFAULT_SEARCH_LIMIT, HighlightedText, SearchHit, SearchObjectType};
+
+use crate::{
+    Board, CompletedBoard, InboxBoard, LabelsBoard, TodayBoard,
+    core::{
+        notification::NotificationSystem,
+        state::{NavigationState, NavigationTarget, TodoStore},
+    },
+    todo_state::DBState,
+};
+
+pub struct SearchPanel {
+    input: Entity<InputState>,
+    /// 最近一次发起搜索的关键词，用于丢弃过期的结果
+    query: String,
+    hits: Vec<SearchHit>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SearchPanel {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Search tasks, projects, sections, labels...")
+        });
+        let _subscriptions =
+            vec![cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+                match event {
+                    InputEvent::Change => this.run_search(cx),
+                    InputEvent::PressEnter { .. } => this.open_hit(0, window, cx),
+                    _ => {},
+                }
+            })];
+        Self { input, query: String::new(), hits: Vec::new(), _subscriptions }
+    }
+
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut App) {
+        self.input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    fn run_search(&mut self, cx: &mut Context<Self>) {
+        let query = self.input.read(cx).value().trim().to_string();
+        if query == self.query {
+            return;
+        }
+        self.query = query.clone();
+        if query.is_empty() {
+            self.hits.clear();
+            cx.notify();
+            return;
+        }
+
+        let db_state = cx.global::<DBState>().clone();
+        let this_entity = cx.entity();
+        cx.spawn(async move |_this, cx| {
+            if !db_state.is_store_ready() {
+                return;
+            }
+            let store = db_state.get_store_async().await;
+            match crate::state_service::search_with_store(&query, DEFAULT_SEARCH_LIMIT, store).await
+            {
+                Ok(hits) => {
+                    cx.update_entity(&this_entity, |this, cx| {
+                        // 输入已经变化则丢弃结果
+                        if this.query == query {
+                            this.hits = hits;
+                            cx.notify();
+                        }
+                    });
+                },
+                Err(e) => NotificationSystem::log_error("Search failed", e),
+            }
+        })
+        .detach();
+    }
+
+    fn open_hit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(hit) = self.hits.get(ix) else {
+            return;
+        };
+        let target = navigation_target(hit, cx.global::<TodoStore>());
+        cx.update_global::<NavigationState, _>(|nav, _| nav.request(target));
+        window.close_dialog(cx);
+    }
+}
+
+/// 结果所在的位置：任务 / 分区跳到所属项目，无项目的任务跳到对应看板
+fn navigation_target(hit: &SearchHit, store: &TodoStore) -> NavigationTarget {
+    match hit.object_type {
+        SearchObjectType::Project => NavigationTarget::Project(hit.id.clone()),
+        SearchObjectType::Label => NavigationTarget::Board(LabelsBoard::klass()),
+        SearchObjectType::Section | SearchObjectType::Item => {
+            let project_id = hit.project_id.as_deref().filter(|id| !id.is_empty());
+            match project_id {
+                Some(project_id) => NavigationTarget::Project(project_id.to_string()),
+                None if hit.checked => NavigationTarget::Board(CompletedBoard::klass()),
+                None => {
+                    let today = chrono::Utc::now().naive_utc().date();
+                    let due_today =
+                        store.get_item(&hit.id).is_some_and(|item| item.is_due_on_date(today));
+                    if due_today {
+                        NavigationTarget::Board(TodayBoard::klass())
+                    } else {
+                        NavigationTarget::Board(InboxBoard::klass())
+                    }
+                },
+            }
+        },
+    }
+}
+
+fn type_label(object_type: SearchObjectType) -> &'static str {
+    match object_type {
+        SearchObjectType::Item => "Task",
+        SearchObjectType::Project => "Project",
+        SearchObjectType::Section => "Section",
+        SearchObjectType::Label => "Label",
+    }
+}
+
+/// 结果的上下文：任务显示「项目 / 分区」，分区显示所属项目
+fn hit_context(hit: &SearchHit, store: &TodoStore) -> Option<String> {
+    let project = hit.project_id.as_deref().and_then(|id| store.get_project(id));
+    match hit.object_type {
+        SearchObjectType::Item => {
+            let project_name =
+                project.map(|p| p.name.clone()).unwrap_or_else(|| "Inbox".to_string());
+            let section = hit.section_id.as_deref().and_then(|id| store.get_section(id));
+            Some(match section {
+                Some(section) => format!("{} / {}", project_name, section.name),
+                None => project_name,
+            })
+        },
+        SearchObjectType::Section => project.map(|p| p.name.clone()),
+        SearchObjectType::Project | SearchObjectType::Label => None,
+    }
+}
+
+fn highlighted(text: &HighlightedText, style: HighlightStyle) -> StyledText {
+    StyledText::new(text.text.clone())
+        .with_highlights(text.ranges.iter().map(|range| (range.clone(), style)))
+}
+
+impl Render for SearchPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let muted = theme.muted_foreground;
+        let hover_bg = theme.accent;
+        let border = theme.border;
+        let highlight = HighlightStyle {
+            color: Some(theme.primary),
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+        let store = cx.global::<TodoStore>();
+        let rows: Vec<_> = self
+            .hits
+            .iter()
+            .map(|hit| {
+                (
+                    type_label(hit.object_type),
+                    highlighted(&hit.title, highlight),
+                    hit.snippet.as_ref().map(|snippet| highlighted(snippet, highlight)),
+                    hit_context(hit, store),
+                )
+            })
+            .collect();
+        let no_results = rows.is_empty() && !self.query.is_empty();
+
+        v_flex()
+            .gap_2()
+            .w_full()
+            .child(Input::new(&self.input).cleanable(true))
+            .when(no_results, |this| {
+                this.child(div().text_sm().text_color(muted).child("No results"))
+            })
+            .child(v_flex().id("search-results").max_h(px(360.0)).overflow_y_scroll().children(
+                rows.into_iter().enumerate().map(|(ix, (kind, title, snippet, context))| {
+                    v_flex()
+                        .id(("search-hit", ix))
+                        .gap_0p5()
+                        .px_2()
+                        .py_1()
+                        .rounded_md()
+                        .border_b_1()
+                        .border_color(border.opacity(0.3))
+                        .cursor_pointer()
+                        .hover(move |style| style.bg(hover_bg))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .w(px(56.0))
+                                        .flex_none()
+                                        .text_xs()
+                                        .text_color(muted)
+                                        .child(kind),
+                                )
+                                .child(div().flex_1().text_sm().child(title))
+                                .when_some(context, |this, context| {
+                                    this.child(div().text_xs().text_color(muted).child(context))
+                                }),
+                        )
+                        .when_some(snippet, |this, snippet| {
+                            this.child(
+                                div().pl(px(64.0)).text_xs().text_color(muted).child(snippet),
+                            )
+                        })
+                        .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                            this.open_hit(ix, window, cx);
+                        }))
+                }),
+            ))
+    }
+}
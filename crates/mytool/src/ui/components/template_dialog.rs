@@ -0,0 +1,128 @@
+//! 按模板新建项目的对话框
+//!
+//! 先选择模板文件，再填写项目名称和开始日期；模板中的截止日期与提醒以开始日期为第 0 天换算。
+
+use std::{cell::Cell, path::PathBuf, rc::Rc};
+
+use gpui::{App, AppContext, BorrowAppContext, ParentElement, Styled, Window, WindowHandle, div};
+use gpui_component::{
+    ActiveTheme, Root, WindowExt,
+    button::{Button, ButtonVariants},
+    date_picker::{DatePicker, DatePickerEvent, DatePickerState},
+    dialog::{DialogAction, DialogClose, DialogFooter},
+    input::{Input, InputState},
+    v_flex,
+};
+use todos::{ProjectTemplate, TEMPLATE_EXTENSION};
+
+use crate::{VisualHierarchy, todo_actions::instantiate_template, todo_state::ErrorNotifier};
+
+/// 选择并读取模板文件后打开对话框
+pub fn open_template_dialog(window: WindowHandle<Root>, cx: &mut App) {
+    cx.spawn(async move |cx| {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Project template", &[TEMPLATE_EXTENSION])
+            .pick_file()
+            .await
+        else {
+            return; // User cancelled
+        };
+        let path = file.path().to_path_buf();
+        let template = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| ProjectTemplate::from_json(&json).map_err(|e| e.to_string()));
+        match template {
+            Ok(template) => {
+                let _ = window.update(cx, |_, window, cx| {
+                    show_template_dialog(path, template, window, cx);
+                });
+            },
+            Err(e) => {
+                cx.update_global::<ErrorNotifier, _>(|notifier, _| {
+                    notifier.set_error(format!("读取模板失败：{}", e));
+                });
+            },
+        }
+    })
+    .detach();
+}
+
+pub fn show_template_dialog(
+    path: PathBuf,
+    template: ProjectTemplate,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let today = chrono::Local::now().naive_local().date();
+    let start = Rc::new(Cell::new(today));
+    let name_input = cx.new(|cx| {
+        let mut state = InputState::new(window, cx).placeholder("Project Name");
+        state.set_value(template.project.name.clone(), window, cx);
+        state
+    });
+    let start_picker = cx.new(|cx| {
+        let mut picker = DatePickerState::new(window, cx);
+        picker.set_date(today, window, cx);
+        picker
+    });
+    cx.subscribe(&start_picker, {
+        let start = start.clone();
+        move |_, event, _| {
+            let DatePickerEvent::Change(date) = event;
+            if let Some(date) = date.format("%Y-%m-%d")
+                && let Ok(date) = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            {
+                start.set(date);
+            }
+        }
+    })
+    .detach();
+
+    let summary = format!(
+        "{} sections · {} tasks. Due dates and reminders are shifted so the template's first day \
+         falls on the start date.",
+        template.sections.len(),
+        template.items.len()
+    );
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        modal
+            .title("New Project from Template")
+            .overlay(false)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(Input::new(&name_input))
+                    .child(DatePicker::new(&start_picker).placeholder("Start Date"))
+                    .child(div().text_xs().text_color(muted).child(summary.clone())),
+            )
+            .footer(
+                DialogFooter::new()
+                    .child(
+                        DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                    )
+                    .child(
+                        DialogAction::new().child(Button::new("create").primary().label("Create")),
+                    ),
+            )
+            .on_ok({
+                let path = path.clone();
+                let name_input = name_input.clone();
+                let start = start.clone();
+                move |_, window: &mut Window, cx| {
+                    let name = name_input.read(cx).value().trim().to_string();
+                    instantiate_template(
+                        path.clone(),
+                        (!name.is_empty()).then_some(name),
+                        start.get(),
+                        cx,
+                    );
+                    window.push_notification("Creating project from template...", cx);
+                    true
+                }
+            })
+    });
+}
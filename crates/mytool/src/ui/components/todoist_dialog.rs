@@ -0,0 +1,66 @@
+//! 添加 Todoist 账号的对话框
+//!
+//! 填写 API 令牌后第一次同步拉取全部项目和任务，之后的本地修改排队发送。
+
+use gpui::{App, AppContext, ParentElement, Styled, Window};
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogAction, DialogClose, DialogFooter},
+    input::{Input, InputState},
+    v_flex,
+};
+use todos::services::TodoistConfig;
+
+use crate::{VisualHierarchy, todo_actions::add_todoist_account};
+
+pub fn show_todoist_dialog(window: &mut Window, cx: &mut App) {
+    let token_input =
+        cx.new(|cx| InputState::new(window, cx).placeholder("API Token").masked(true));
+    let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Name (optional)"));
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        modal
+            .title("Add Todoist Account")
+            .overlay(false)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(Input::new(&token_input))
+                    .child(Input::new(&name_input))
+                    .child(gpui::div().text_xs().text_color(muted).child(
+                        "Find the token in Todoist under Settings > Integrations > Developer.",
+                    )),
+            )
+            .footer(
+                DialogFooter::new()
+                    .child(
+                        DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                    )
+                    .child(DialogAction::new().child(Button::new("save").primary().label("Add"))),
+            )
+            .on_ok({
+                let token_input = token_input.clone();
+                let name_input = name_input.clone();
+                move |_, window: &mut Window, cx| {
+                    let token = token_input.read(cx).value().trim().to_string();
+                    if token.is_empty() {
+                        window.push_notification("API token is required", cx);
+                        return false;
+                    }
+                    let display_name = name_input.read(cx).value().trim().to_string();
+                    let config = TodoistConfig {
+                        token,
+                        display_name: (!display_name.is_empty()).then_some(display_name),
+                        sync_url: None,
+                    };
+                    add_todoist_account(config, cx);
+                    window.push_notification("Syncing Todoist...", cx);
+                    true
+                }
+            })
+    });
+}
@@ -1,15 +1,26 @@
 use gpui::{
-    AnyView, App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, ParentElement, Render, SharedString, Styled, Window, div,
+    AnyView, App, AppContext, BorrowAppContext, Context, Entity, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Window, div,
+};
+use gpui_component::{
+    Root, WindowExt,
+    button::{Button, ButtonVariants},
+    notification::Notification,
+    v_flex,
 };
-use gpui_component::{Root, WindowExt, notification::Notification, v_flex};
 
-use crate::{AppTitleBar, ShowPanelInfo, ToggleSearch};
+use crate::{
+    AppTitleBar, RedoChange, SearchPanel, ShowPanelInfo, ToggleSearch, UndoChange,
+    core::state::{FailedWrite, SaveFailures},
+    todo_actions,
+};
 
 pub struct StoryRoot {
     pub(crate) focus_handle: FocusHandle,
     pub(crate) title_bar: Entity<AppTitleBar>,
     pub(crate) view: AnyView,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl StoryRoot {
@@ -20,7 +31,17 @@ impl StoryRoot {
         cx: &mut Context<Self>,
     ) -> Self {
         let title_bar = cx.new(|cx| AppTitleBar::new(title, window, cx));
-        Self { focus_handle: cx.focus_handle(), title_bar, view: view.into() }
+        // 后台保存失败并回滚后，在窗口中提示重试或放弃
+        let _subscriptions = vec![cx.observe_global_in::<SaveFailures>(window, |_, window, cx| {
+            if cx.global::<SaveFailures>().is_empty() {
+                return;
+            }
+            let failures = cx.update_global::<SaveFailures, _>(|failures, _| failures.take_all());
+            for (write, error) in failures {
+                window.push_notification(save_failure_notification(write, error), cx);
+            }
+        })];
+        Self { focus_handle: cx.focus_handle(), title_bar, view: view.into(), _subscriptions }
     }
 
     fn on_action_panel_info(
@@ -45,10 +66,58 @@ impl StoryRoot {
             return;
         }
 
-        struct Search;
-        let note = Notification::new().message("You have toggled search.").id::<Search>();
-        window.push_notification(note, cx);
+        let panel = SearchPanel::view(window, cx);
+        panel.read(cx).focus(window, cx);
+        window.open_dialog(cx, move |modal, _, _| {
+            modal.title("Search").keyboard(true).overlay_closable(true).child(panel.clone())
+        });
+    }
+
+    fn on_action_undo(&mut self, _: &UndoChange, window: &mut Window, cx: &mut Context<Self>) {
+        // 输入框内的 Ctrl+Z 由输入框自己处理
+        if window.has_focused_input(cx) {
+            cx.propagate();
+            return;
+        }
+
+        struct Undo;
+        let message = match todo_actions::undo(cx) {
+            Some(description) => format!("已撤销：{}", description),
+            None => "没有可撤销的操作".to_string(),
+        };
+        window.push_notification(Notification::new().message(message).id::<Undo>(), cx);
     }
+
+    fn on_action_redo(&mut self, _: &RedoChange, window: &mut Window, cx: &mut Context<Self>) {
+        if window.has_focused_input(cx) {
+            cx.propagate();
+            return;
+        }
+
+        struct Redo;
+        let message = match todo_actions::redo(cx) {
+            Some(description) => format!("已重做：{}", description),
+            None => "没有可重做的操作".to_string(),
+        };
+        window.push_notification(Notification::new().message(message).id::<Redo>(), cx);
+    }
+}
+
+/// 「无法保存 — 重试 / 放弃」通知：修改已回滚，重试时重新执行，关闭即放弃
+fn save_failure_notification(write: FailedWrite, error: String) -> Notification {
+    let message =
+        format!("{}：{}。已恢复到修改前，关闭通知即放弃这次修改。", write.describe(), error);
+    Notification::new().title("Could not save").message(message).autohide(false).action(
+        move |_, _, cx| {
+            let write = write.clone();
+            Button::new("retry-save").primary().label("Retry").on_click(cx.listener(
+                move |this, _, window, cx| {
+                    todo_actions::retry_failed_write(write.clone(), cx);
+                    this.dismiss(window, cx);
+                },
+            ))
+        },
+    )
 }
 
 impl Focusable for StoryRoot {
@@ -67,6 +136,8 @@ impl Render for StoryRoot {
             .id("story-root")
             .on_action(cx.listener(Self::on_action_panel_info))
             .on_action(cx.listener(Self::on_action_toggle_search))
+            .on_action(cx.listener(Self::on_action_undo))
+            .on_action(cx.listener(Self::on_action_redo))
             .size_full()
             .child(
                 v_flex()
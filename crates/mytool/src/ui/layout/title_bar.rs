@@ -9,8 +9,12 @@ use gpui_component::{
     menu::{AppMenuBar, DropdownMenu as _},
     scroll::ScrollbarShow,
 };
+use todos::JournalStatus;
 
-use crate::{SelectFont, SelectRadius, SelectScrollbarShow, ToggleListActiveHighlight, app_menus};
+use crate::{
+    DiscardFailedWrites, RetryFailedWrites, SelectFont, SelectRadius, SelectScrollbarShow,
+    ToggleListActiveHighlight, app_menus, core::state::WriteJournalState,
+};
 
 pub struct AppTitleBar {
     app_menu_bar: Entity<AppMenuBar>,
@@ -26,8 +30,10 @@ impl AppTitleBar {
     ) -> Self {
         let app_menu_bar = app_menus::init(title, cx);
         let font_size_selector = cx.new(|cx| FontSizeSelector::new(window, cx));
+        // 写入日志变化时刷新等待保存的写入
+        let _subscriptions = vec![cx.observe_global::<WriteJournalState>(|_, cx| cx.notify())];
 
-        Self { app_menu_bar, font_size_selector, _subscriptions: vec![] }
+        Self { app_menu_bar, font_size_selector, _subscriptions }
     }
 }
 
@@ -46,6 +52,7 @@ impl Render for AppTitleBar {
                     .px_2()
                     .gap_2()
                     .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .children(pending_writes_button(cx))
                     .child(self.font_size_selector.clone())
                     .child(
                         Button::new("github")
@@ -71,6 +78,47 @@ impl Render for AppTitleBar {
     }
 }
 
+/// 没有落盘的写入：数量徽标 + 下拉列表（失败的写入可以重试或放弃）
+fn pending_writes_button(cx: &Context<AppTitleBar>) -> Option<impl IntoElement> {
+    let state = cx.global::<WriteJournalState>();
+    if state.is_empty() {
+        return None;
+    }
+    let entries = state.display_entries();
+    let failed_count = state.failed_count();
+
+    Some(
+        div().relative().child(
+            Badge::new().count(entries.len()).max(99).child(
+                Button::new("pending-writes")
+                    .small()
+                    .ghost()
+                    .compact()
+                    .icon(IconName::RefreshCw)
+                    .tooltip("Unsaved changes")
+                    .dropdown_menu(move |mut menu, _, _| {
+                        menu = menu.scrollable(true).max_h(px(480.));
+                        for entry in &entries {
+                            let status = match entry.status {
+                                JournalStatus::Pending => "Waiting",
+                                JournalStatus::Failed => "Failed",
+                            };
+                            menu = menu.label(format!("{}: {}", status, entry.op.describe()));
+                        }
+                        if failed_count > 0 {
+                            menu = menu
+                                .separator()
+                                .menu("Retry Failed Writes", Box::new(RetryFailedWrites))
+                                .menu("Discard Failed Writes", Box::new(DiscardFailedWrites));
+                        }
+                        menu
+                    })
+                    .anchor(gpui::Anchor::TopRight),
+            ),
+        ),
+    )
+}
+
 struct FontSizeSelector {
     focus_handle: FocusHandle,
 }
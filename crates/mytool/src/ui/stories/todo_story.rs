@@ -12,7 +12,7 @@ use todos::entity::ProjectModel;
 
 use crate::{
     BoardPanel, ProjectEvent, ProjectItemEvent, ProjectItemsPanel, ProjectsPanel, play_ogg_file,
-    todo_state::TodoStore,
+    todo_state::{NavigationState, NavigationTarget, TodoStore},
 };
 
 #[derive(Action, Clone, PartialEq, Eq, Deserialize)]
@@ -150,6 +150,7 @@ impl TodoStory {
                     },
                 }
             }),
+            cx.observe_global::<NavigationState>(|this, cx| this.handle_navigation(cx)),
         ];
         Self {
             collapsed: false,
@@ -194,6 +195,64 @@ impl TodoStory {
         cx.new(|cx| Self::new(Some(""), window, cx))
     }
 
+    /// 打开项目：选中侧边栏中的项目并取消看板选中
+    fn select_project(
+        &mut self,
+        project: Arc<ProjectModel>,
+        index: Option<usize>,
+        cx: &mut Context<Self>,
+    ) {
+        self.active_project = Some(project.clone());
+        self.project_panel.update(cx, |panel, cx| {
+            panel.update_active_index(index);
+            cx.notify();
+        });
+        self.project_items_panel.update(cx, |panel, cx| {
+            panel.set_project(project, cx);
+            cx.notify();
+        });
+        self.board_panel.update(cx, |panel, cx| {
+            panel.update_active_index(None);
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// 处理搜索结果等提交的导航请求
+    fn handle_navigation(&mut self, cx: &mut Context<Self>) {
+        // 先检查再取出：取出本身会再次通知观察者
+        if !cx.global::<NavigationState>().has_pending() {
+            return;
+        }
+        let Some(target) = cx.update_global::<NavigationState, _>(|nav, _| nav.take()) else {
+            return;
+        };
+
+        match target {
+            NavigationTarget::Board(klass) => {
+                let Some(index) = self.board_panel.read(cx).board_index_for_klass(klass, cx) else {
+                    return;
+                };
+                self.project_panel.update(cx, |panel, cx| {
+                    panel.update_active_index(None);
+                    cx.notify();
+                });
+                self.board_panel.update(cx, |panel, cx| {
+                    panel.update_active_index(Some(index));
+                    cx.notify();
+                });
+                cx.notify();
+            },
+            NavigationTarget::Project(project_id) => {
+                let store = cx.global::<TodoStore>();
+                let index = store.projects.iter().position(|p| p.id == project_id);
+                if let Some(project) = store.get_project(&project_id) {
+                    self.select_project(project, index, cx);
+                }
+            },
+        }
+    }
+
     fn add_project(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         let _ = play_ogg_file("assets/sounds/success.ogg");
         self.project_panel.update(cx, |project_panel, cx| {
@@ -249,20 +308,7 @@ impl Render for TodoStory {
                                 .on_click({
                                     let story = project.clone();
                                     cx.listener(move |this, _: &ClickEvent, _, cx| {
-                                        this.active_project = Some(story.clone());
-                                        this.project_panel.update(cx, |panel, cx| {
-                                            panel.update_active_index(Some(ix));
-                                            cx.notify();
-                                        });
-                                        this.project_items_panel.update(cx, |panel, cx| {
-                                            panel.set_project(story.clone(), cx);
-                                            cx.notify();
-                                        });
-                                        this.board_panel.update(cx, |panel, cx| {
-                                            panel.update_active_index(None);
-                                            cx.notify();
-                                        });
-                                        cx.notify();
+                                        this.select_project(story.clone(), Some(ix), cx);
                                     })
                                 })
                         },
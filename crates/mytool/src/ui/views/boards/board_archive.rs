@@ -0,0 +1,263 @@
+//! ArchiveBoard - 归档视图
+//!
+//! 列出归档的项目，支持按名称 / 描述搜索、取消归档与彻底删除。
+//! 随父项目一起归档的子项目不单独显示。
+
+use std::sync::Arc;
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, Hsla, InteractiveElement,
+    IntoElement, ParentElement, Render, Styled, Subscription, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    dock::PanelControl,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use todos::services::ArchivedProject;
+
+use crate::{
+    VisualHierarchy, todo_actions,
+    todo_state::TodoStore,
+    ui::views::boards::{board_common::render_board_header, container_board::Board},
+};
+
+pub struct ArchiveBoard {
+    focus_handle: FocusHandle,
+    search_input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ArchiveBoard {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Search archived projects..."));
+        let _subscriptions = vec![
+            // 仅在归档数据变化时重绘
+            cx.observe_global::<TodoStore>(|_, cx| {
+                if cx.global::<TodoStore>().peek_change_mask().affects_archive() {
+                    cx.notify();
+                }
+            }),
+            cx.subscribe(&search_input, |_, _, event, cx| {
+                if let InputEvent::Change = event {
+                    cx.notify();
+                }
+            }),
+        ];
+
+        Self { focus_handle: cx.focus_handle(), search_input, _subscriptions }
+    }
+
+    fn matches(entry: &ArchivedProject, query: &str) -> bool {
+        query.is_empty()
+            || entry.project.name.to_lowercase().contains(query)
+            || entry
+                .project
+                .description
+                .as_deref()
+                .is_some_and(|description| description.to_lowercase().contains(query))
+    }
+
+    fn render_entry(
+        ix: usize,
+        entry: Arc<ArchivedProject>,
+        view: Entity<Self>,
+        cx: &App,
+    ) -> impl IntoElement {
+        let mut detail = entry
+            .project
+            .archived_at
+            .map(|at| {
+                at.and_utc().with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+            })
+            .unwrap_or_default();
+        if entry.item_count > 0 {
+            detail.push_str(&format!(" · 包含 {} 个未完成任务", entry.item_count));
+        }
+        if entry.subproject_count > 0 {
+            detail.push_str(&format!(" · {} 个子项目", entry.subproject_count));
+        }
+
+        h_flex()
+            .id(("archive-entry", ix))
+            .w_full()
+            .justify_between()
+            .items_center()
+            .gap(VisualHierarchy::spacing(2.0))
+            .p(VisualHierarchy::spacing(2.0))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap(VisualHierarchy::spacing(2.0))
+                    .items_center()
+                    .overflow_hidden()
+                    .child(IconName::FolderOpen)
+                    .child(
+                        v_flex()
+                            .overflow_hidden()
+                            .child(div().text_sm().truncate().child(entry.project.name.clone()))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(detail),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap(VisualHierarchy::spacing(1.0))
+                    .child(
+                        Button::new(("unarchive-project", ix))
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::Undo)
+                            .tooltip("取消归档")
+                            .on_click({
+                                let entry = entry.clone();
+                                move |_event, _window, cx| {
+                                    todo_actions::unarchive_project(entry.clone(), cx);
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new(("delete-archived-project", ix))
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::Trash)
+                            .tooltip("彻底删除")
+                            .on_click({
+                                let entry = entry.clone();
+                                move |_event, window, cx| {
+                                    let entry = entry.clone();
+                                    view.update(cx, |_, cx| {
+                                        crate::ui::components::show_delete_dialog(
+                                            window,
+                                            cx,
+                                            "Permanently delete this archived project? All of its \
+                                             sections and tasks will be deleted and cannot be \
+                                             restored.",
+                                            move |cx| {
+                                                todo_actions::delete_archived_project(
+                                                    entry.clone(),
+                                                    cx,
+                                                )
+                                            },
+                                        );
+                                    });
+                                }
+                            }),
+                    ),
+            )
+    }
+}
+
+impl Board for ArchiveBoard {
+    fn icon() -> IconName {
+        IconName::FolderOpen
+    }
+
+    fn colors() -> Vec<Hsla> {
+        vec![gpui::rgb(0x9a9996).into(), gpui::rgb(0x5e5c64).into()]
+    }
+
+    fn count(cx: &mut App) -> usize {
+        cx.global::<TodoStore>().archive.len()
+    }
+
+    fn title() -> &'static str {
+        "Archive"
+    }
+
+    fn description() -> &'static str {
+        "已归档的项目"
+    }
+
+    fn zoomable() -> Option<PanelControl> {
+        None
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+}
+
+impl Focusable for ArchiveBoard {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ArchiveBoard {
+    fn render(
+        &mut self,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> impl gpui::IntoElement {
+        let view = cx.entity().clone();
+        let query = self.search_input.read(cx).value().trim().to_lowercase();
+        let archive = cx.global::<TodoStore>().archive.clone();
+        let has_archive = !archive.is_empty();
+        let entries: Vec<Arc<ArchivedProject>> =
+            archive.into_iter().filter(|entry| Self::matches(entry, &query)).collect();
+        let empty_hint =
+            if has_archive { "没有匹配的归档项目" } else { "没有归档的项目" };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .gap(VisualHierarchy::spacing(4.0))
+            .child(render_board_header(
+                cx,
+                <ArchiveBoard as Board>::icon(),
+                <ArchiveBoard as Board>::title(),
+                <ArchiveBoard as Board>::description(),
+                Button::new("refresh-archive")
+                    .small()
+                    .ghost()
+                    .compact()
+                    .icon(IconName::RefreshCw)
+                    .tooltip("刷新")
+                    .on_click(|_event, _window, cx| {
+                        todo_actions::load_archive(cx);
+                    }),
+            ))
+            .child(
+                div()
+                    .px(VisualHierarchy::spacing(3.0))
+                    .child(Input::new(&self.search_input).cleanable(true)),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .overflow_y_scrollbar()
+                    .p(VisualHierarchy::spacing(3.0))
+                    .when(entries.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(empty_hint),
+                        )
+                    })
+                    .children(
+                        entries
+                            .into_iter()
+                            .enumerate()
+                            .map(|(ix, entry)| Self::render_entry(ix, entry, view.clone(), cx)),
+                    ),
+            )
+    }
+}
@@ -2,12 +2,11 @@ use std::{cell::Cell, sync::Arc};
 
 use gpui::{AppContext, Context, Entity, FocusHandle, Subscription, Window};
 use gpui_component::{IndexPath, WindowExt};
-use sea_orm::sqlx::types::uuid;
 use todos::entity::SectionModel;
 
 use crate::{
     ItemInfoState, ItemRowState,
-    todo_actions::{add_section, update_section},
+    todo_actions::{add_section, duplicate_section, set_section_archived, update_section},
     todo_state::TodoStore,
 };
 
@@ -195,13 +194,10 @@ impl BoardBase {
         cx: &mut Context<V>,
         section_id: String,
     ) {
-        let sections = cx.global::<TodoStore>().sections.clone();
-        if let Some(section) = sections.iter().find(|s| s.id == section_id) {
-            let mut new_section = section.as_ref().clone();
-            new_section.id = uuid::Uuid::new_v4().to_string();
-            new_section.name = format!("{} (copy)", new_section.name);
-            add_section(Arc::new(new_section), cx);
-            window.push_notification("Section duplicated successfully.", cx);
+        // 连同分区中的任务一起复制
+        if let Some(section) = cx.global::<TodoStore>().get_section(&section_id) {
+            duplicate_section(section, cx);
+            window.push_notification("Duplicating section...", cx);
         }
     }
 
@@ -212,11 +208,8 @@ impl BoardBase {
         cx: &mut Context<V>,
         section_id: String,
     ) {
-        let sections = cx.global::<TodoStore>().sections.clone();
-        if let Some(section) = sections.iter().find(|s| s.id == section_id) {
-            let mut updated_section = section.as_ref().clone();
-            updated_section.is_archived = true;
-            update_section(Arc::new(updated_section), cx);
+        if let Some(section) = cx.global::<TodoStore>().get_section(&section_id) {
+            set_section_archived(section, true, cx);
             window.push_notification("Section archived successfully.", cx);
         }
     }
@@ -439,6 +432,9 @@ impl BoardBase {
             return;
         }
 
+        // 选中项跟随任务本身，而不是停在原来的行号（手动排序后仍选中被移动的任务）
+        let selected_id = self.active_index.and_then(|ix| item_row_ids.get(ix).cloned());
+
         // 1. 建立旧 item_rows 的 id -> Entity 映射
         let mut old_rows_map: HashMap<String, Entity<ItemRowState>> = HashMap::new();
         for (id, row) in item_row_ids.drain(..).zip(self.item_rows.drain(..)) {
@@ -460,6 +456,10 @@ impl BoardBase {
         }
 
         // 3. 剩余的 old_rows_map 中的 Entity 将被丢弃（自动释放）
+        if let Some(ix) = selected_id.and_then(|id| new_ids.iter().position(|new_id| *new_id == id))
+        {
+            self.active_index = Some(ix);
+        }
         self.item_rows = new_rows;
         *item_row_ids = new_ids;
     }
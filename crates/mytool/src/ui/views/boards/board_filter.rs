@@ -0,0 +1,307 @@
+//! FilterBoard - 保存的过滤器视图
+//!
+//! 每个保存的过滤器对应一个看板，显示满足过滤表达式的未完成任务。
+//! 表达式在 TodoStore 的内存索引上求值，任务或过滤器变化时增量刷新。
+
+use std::{cell::Cell, sync::Arc};
+
+use gpui::{
+    App, AppContext, Context, Entity, EventEmitter, Focusable, InteractiveElement, ParentElement,
+    Render, Styled, Window,
+};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::{DialogAction, DialogClose, DialogFooter},
+    h_flex,
+    input::{Input, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use todos::{FilterQuery, entity::FilterModel};
+
+use crate::{
+    BoardBase, VisualHierarchy,
+    core::error_handler::validation,
+    todo_actions::{add_filter, delete_filter, update_filter},
+    todo_state::TodoStore,
+    ui::views::boards::{
+        BoardView,
+        board_common::{BoardItemClickEvent, render_board_header},
+        board_renderer,
+    },
+};
+
+/// 过滤器看板在 BoardPanel 中的 klass 前缀，完整形式为 `filter:{id}`
+pub const FILTER_KLASS_PREFIX: &str = "filter:";
+
+impl EventEmitter<BoardItemClickEvent> for FilterBoard {}
+
+pub struct FilterBoard {
+    base: BoardBase,
+    filter: Arc<FilterModel>,
+    /// 解析后的表达式，表达式无效时为 None（不显示任何任务）
+    query: Option<FilterQuery>,
+    /// 跟踪当前 item_rows 对应的 item id 列表，用于增量更新
+    item_row_ids: Vec<String>,
+    /// 脏标记：当 TodoStore 数据变化时设为 true，
+    /// 在 render() 中执行实际的增量更新操作（需要 window 参数）
+    pending_refresh: Cell<bool>,
+    /// 延迟注册标记：避免在 new() 时立即注册全局观察者
+    observer_registered: Cell<bool>,
+}
+
+impl FilterBoard {
+    pub fn view(filter: Arc<FilterModel>, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(filter, window, cx))
+    }
+
+    pub(crate) fn new(
+        filter: Arc<FilterModel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base = BoardBase::new(window, cx);
+        let query = FilterQuery::parse(&filter.query).ok();
+
+        Self {
+            base,
+            filter,
+            query,
+            item_row_ids: Vec::new(),
+            // 首次 render 时需要加载一次数据
+            pending_refresh: Cell::new(true),
+            observer_registered: Cell::new(false),
+        }
+    }
+
+    pub fn klass_for(filter_id: &str) -> String {
+        format!("{}{}", FILTER_KLASS_PREFIX, filter_id)
+    }
+
+    /// 过滤器在 Store 中被修改后，同步名称和表达式
+    fn sync_filter(&mut self, cx: &App) {
+        let Some(filter) = cx.global::<TodoStore>().get_filter(&self.filter.id) else {
+            return;
+        };
+        if filter.query != self.filter.query {
+            self.query = FilterQuery::parse(&filter.query).ok();
+        }
+        self.filter = filter;
+    }
+
+    /// 在 render() 中执行实际的增量更新
+    ///
+    /// 只在 pending_refresh=true 时执行，避免每帧重复操作
+    fn apply_pending_refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !BoardBase::begin_pending_refresh(
+            &self.observer_registered,
+            &self.pending_refresh,
+            &mut self.base._subscriptions,
+            self.base.item_rows.is_empty(),
+            false,
+            cx,
+            crate::core::state::ChangeMask::affects_filters,
+            |this| &this.pending_refresh,
+        ) {
+            return;
+        }
+
+        self.sync_filter(cx);
+        let state_items = match &self.query {
+            Some(query) => cx.global::<TodoStore>().filter_items(query),
+            None => Vec::new(),
+        };
+
+        self.base.diff_update_item_rows(state_items.as_slice(), &mut self.item_row_ids, window, cx);
+        self.base.update_items(state_items.as_slice());
+        self.base.clamp_active_index();
+    }
+
+    pub fn show_item_dialog(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        is_edit: bool,
+        section_id: Option<String>,
+    ) {
+        self.base.show_item_dialog(window, cx, is_edit, section_id);
+    }
+
+    pub fn show_delete_filter_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let filter = self.filter.clone();
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .overlay(true)
+                .overlay_closable(true)
+                .child(format!("Delete filter \"{}\"? Tasks are not affected.", filter.name))
+                .on_ok({
+                    let filter = filter.clone();
+                    move |_, window: &mut Window, cx| {
+                        delete_filter(filter.clone(), cx);
+                        window.push_notification("Filter deleted.", cx);
+                        true
+                    }
+                })
+        });
+    }
+}
+
+/// 新建 / 编辑过滤器对话框
+///
+/// `filter` 为 None 时新建；表达式无效时提示错误并保持对话框打开。
+pub fn show_filter_dialog(filter: Option<Arc<FilterModel>>, window: &mut Window, cx: &mut App) {
+    let is_edit = filter.is_some();
+    let ori_filter = filter.unwrap_or_default();
+    let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("Filter Name"));
+    let query_input = cx.new(|cx| {
+        InputState::new(window, cx).placeholder("(today | overdue) & p1 & #Work & !@waiting")
+    });
+    if is_edit {
+        name_input.update(cx, |input, cx| input.set_value(ori_filter.name.clone(), window, cx));
+        query_input.update(cx, |input, cx| input.set_value(ori_filter.query.clone(), window, cx));
+    }
+
+    let dialog_title = if is_edit { "Edit Filter" } else { "New Filter" };
+    let button_label = if is_edit { "Save" } else { "Add" };
+    window.open_dialog(cx, move |modal, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        modal
+            .title(dialog_title)
+            .overlay(false)
+            .keyboard(true)
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap(VisualHierarchy::spacing(3.0))
+                    .child(Input::new(&name_input))
+                    .child(Input::new(&query_input))
+                    .child(gpui::div().text_xs().text_color(muted).child(
+                        "today, overdue, p1-p4, #project, ##project, /section, @label, due \
+                         before: +7d; combine with & | ! ( )",
+                    )),
+            )
+            .footer(
+                DialogFooter::new()
+                    .child(
+                        DialogClose::new().child(Button::new("cancel").label("Cancel").outline()),
+                    )
+                    .child(
+                        DialogAction::new()
+                            .child(Button::new("save").primary().label(button_label)),
+                    ),
+            )
+            .on_ok({
+                let ori_filter = ori_filter.clone();
+                let name_input = name_input.clone();
+                let query_input = query_input.clone();
+                move |_, window: &mut Window, cx| {
+                    let name = name_input.read(cx).value().trim().to_string();
+                    let query = query_input.read(cx).value().trim().to_string();
+                    if let Err(e) = validation::validate_filter(&name, &query) {
+                        window.push_notification(e.to_string(), cx);
+                        return false;
+                    }
+
+                    let filter = Arc::new(FilterModel { name, query, ..(*ori_filter).clone() });
+                    if is_edit {
+                        update_filter(filter, cx);
+                    } else {
+                        add_filter(filter, cx);
+                    }
+                    true
+                }
+            })
+    });
+}
+
+impl BoardView for FilterBoard {
+    fn set_active_index(&mut self, index: Option<usize>) {
+        self.base.set_active_index(index);
+    }
+}
+
+impl Focusable for FilterBoard {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.base.focus_handle.clone()
+    }
+}
+
+impl Render for FilterBoard {
+    fn render(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> impl gpui::IntoElement {
+        // 在 render 开头处理待执行的刷新操作
+        self.apply_pending_refresh(window, cx);
+
+        let view = cx.entity().clone();
+        let active_border = cx.theme().list_active_border;
+        let item_rows = &self.base.item_rows;
+        let active_index = self.base.active_index;
+        let description = if self.query.is_some() {
+            self.filter.query.clone()
+        } else {
+            format!("{} (invalid)", self.filter.query)
+        };
+
+        v_flex()
+            .track_focus(&self.base.focus_handle)
+            .size_full()
+            .gap(VisualHierarchy::spacing(4.0))
+            .child(render_board_header(
+                cx,
+                IconName::EditFindSymbolic,
+                self.filter.name.clone(),
+                description,
+                h_flex()
+                    .gap(VisualHierarchy::spacing(1.0))
+                    .child(
+                        Button::new("edit-filter")
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::Pencil)
+                            .on_click({
+                                let filter = self.filter.clone();
+                                move |_event, window, cx| {
+                                    show_filter_dialog(Some(filter.clone()), window, cx);
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new("delete-filter")
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::UserTrashSymbolic)
+                            .on_click({
+                                let view = view.clone();
+                                move |_event, window, cx| {
+                                    view.update(cx, |this, cx| {
+                                        this.show_delete_filter_dialog(window, cx);
+                                    })
+                                }
+                            }),
+                    ),
+            ))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .overflow_y_scrollbar()
+                    .p(VisualHierarchy::spacing(3.0))
+                    .gap(VisualHierarchy::spacing(2.0))
+                    .children(item_rows.iter().enumerate().map(move |(i, item_row)| {
+                        let is_active = active_index == Some(i);
+                        board_renderer::render_item_row(
+                            i,
+                            Some(item_row.clone()),
+                            is_active,
+                            active_border,
+                            view.clone(),
+                        )
+                    })),
+            )
+    }
+}
@@ -147,6 +147,74 @@ impl Render for LabelsBoard {
                                         }
                                     }),
                             )
+                            .child(
+                                Button::new("favorite-label")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::StarOutlineThickSymbolic)
+                                    .tooltip("Toggle Favorite")
+                                    .on_click({
+                                        let labels_panel = self.labels_panel.clone();
+                                        move |_event, _window, cx| {
+                                            labels_panel.update(cx, |labels_panel, cx| {
+                                                labels_panel.toggle_active_favorite(cx);
+                                                cx.notify();
+                                            })
+                                        }
+                                    }),
+                            )
+                            .child(
+                                Button::new("move-label-up")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::ChevronUp)
+                                    .tooltip("Move Up")
+                                    .on_click({
+                                        let labels_panel = self.labels_panel.clone();
+                                        move |_event, _window, cx| {
+                                            labels_panel.update(cx, |labels_panel, cx| {
+                                                labels_panel.move_active_label(-1, cx);
+                                                cx.notify();
+                                            })
+                                        }
+                                    }),
+                            )
+                            .child(
+                                Button::new("move-label-down")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::ChevronDown)
+                                    .tooltip("Move Down")
+                                    .on_click({
+                                        let labels_panel = self.labels_panel.clone();
+                                        move |_event, _window, cx| {
+                                            labels_panel.update(cx, |labels_panel, cx| {
+                                                labels_panel.move_active_label(1, cx);
+                                                cx.notify();
+                                            })
+                                        }
+                                    }),
+                            )
+                            .child(
+                                Button::new("merge-label")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::TagOutlineSymbolic)
+                                    .tooltip("Merge Into...")
+                                    .on_click({
+                                        let labels_panel = self.labels_panel.clone();
+                                        move |_event, window, cx| {
+                                            labels_panel.update(cx, |labels_panel, cx| {
+                                                labels_panel.show_merge_dialog(window, cx);
+                                                cx.notify();
+                                            })
+                                        }
+                                    }),
+                            )
                             .child(
                                 Button::new("delete-label")
                                     .icon(IconName::UserTrashSymbolic)
@@ -3,11 +3,11 @@
 //! 这个模块提供了可复用的 Board 渲染逻辑，减少各 Board 组件的重复代码。
 //! 由于 GPUI 的生命周期限制，这些函数只在 Board 内部使用。
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use gpui::{
     Entity, Hsla, InteractiveElement, IntoElement, ParentElement, Render,
-    StatefulInteractiveElement, Styled, Window, div, prelude::FluentBuilder,
+    StatefulInteractiveElement, Styled, Window, div, prelude::FluentBuilder, px,
 };
 use gpui_component::{
     IconName, Sizable,
@@ -45,8 +45,14 @@ where
         .children(item_row.map(|row| ItemRow::new(&row)))
 }
 
+/// 子任务每一层的缩进宽度
+const SUBTASK_INDENT: f32 = 20.0;
+
 /// 仅渲染任务列表（v_flex 行），不包 section；用于已有 section 标题的区块（如 No
 /// Section、动态分区）
+///
+/// 列表中含有父子任务时按子任务树缩进显示，有子任务的行前面带折叠按钮，
+/// 已折叠任务的子孙不显示。
 pub fn render_item_list<V>(
     items: &[(usize, Arc<ItemModel>)],
     item_rows: &[Entity<ItemRowState>],
@@ -57,11 +63,55 @@ pub fn render_item_list<V>(
 where
     V: BoardView + Render,
 {
-    v_flex().gap(VisualHierarchy::spacing(2.0)).w_full().children(items.iter().map(|(i, _)| {
-        let item_row = item_rows.get(*i).cloned();
-        let is_active = active_index == Some(*i);
-        render_item_row(*i, item_row, is_active, active_border, view.clone())
-    }))
+    let ids: HashSet<&str> = items.iter().map(|(_, item)| item.id.as_str()).collect();
+    let parents: HashSet<&str> = items
+        .iter()
+        .filter_map(|(_, item)| item.parent_id.as_deref())
+        .filter(|parent| ids.contains(parent))
+        .collect();
+    let nested = !parents.is_empty();
+    let rows: Vec<(usize, &(usize, Arc<ItemModel>))> = if nested {
+        todos::ItemNode::depth_first_by(items, |(_, item)| item.as_ref(), true)
+    } else {
+        items.iter().map(|entry| (0, entry)).collect()
+    };
+
+    v_flex().gap(VisualHierarchy::spacing(2.0)).w_full().children(rows.into_iter().map(
+        |(depth, (i, item))| {
+            let item_row = item_rows.get(*i).cloned();
+            let is_active = active_index == Some(*i);
+            let row = render_item_row(*i, item_row, is_active, active_border, view.clone());
+            if !nested {
+                return row.into_any_element();
+            }
+
+            let toggle = if parents.contains(item.id.as_str()) {
+                let item = item.clone();
+                Button::new(("toggle-subtasks", *i))
+                    .small()
+                    .ghost()
+                    .compact()
+                    .icon(if item.collapsed {
+                        IconName::ChevronRight
+                    } else {
+                        IconName::ChevronDown
+                    })
+                    .on_click(move |_, _, cx| {
+                        crate::todo_actions::toggle_item_collapsed(item.clone(), cx);
+                    })
+                    .into_any_element()
+            } else {
+                div().w(px(SUBTASK_INDENT)).into_any_element()
+            };
+            h_flex()
+                .w_full()
+                .items_start()
+                .pl(px(depth as f32 * SUBTASK_INDENT))
+                .child(toggle)
+                .child(div().flex_1().min_w_0().child(row))
+                .into_any_element()
+        },
+    ))
 }
 
 /// Section 区块渲染选项
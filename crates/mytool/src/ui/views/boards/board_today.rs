@@ -20,7 +20,7 @@ use gpui_component::{
 
 use crate::{
     BoardBase, ScheduleButtonEvent, ScheduleButtonState, VisualHierarchy,
-    core::actions::batch::batch_update_items,
+    core::actions::{ReorderStep, batch::batch_update_items, reorder_today_item},
     todo_state::TodoStore,
     ui::views::boards::{
         BoardView,
@@ -158,6 +158,13 @@ impl TodayBoard {
             show_item_delete_dialog(window, cx, item);
         });
     }
+
+    /// 在 Today 分组中移动选中的任务（写 day_order）
+    pub fn reorder_selected_item(&mut self, step: ReorderStep, cx: &mut Context<Self>) {
+        with_selected_item(self.base.active_index, &self.base, cx, |item, cx| {
+            reorder_today_item(item, step, cx);
+        });
+    }
 }
 
 crate::impl_board_section_forwards!(TodayBoard);
@@ -302,6 +309,40 @@ impl Render for TodayBoard {
                                             )),
                                     )
                                     .separator()
+                                    .item(PopupMenuItem::new("Move to Top").on_click(
+                                        window.listener_for(&view, |this, _, _, cx| {
+                                            this.reorder_selected_item(ReorderStep::Top, cx);
+                                        }),
+                                    ))
+                                    .item(
+                                        PopupMenuItem::new("Move Up")
+                                            .icon(IconName::ChevronUp)
+                                            .on_click(window.listener_for(
+                                                &view,
+                                                |this, _, _, cx| {
+                                                    this.reorder_selected_item(ReorderStep::Up, cx);
+                                                },
+                                            )),
+                                    )
+                                    .item(
+                                        PopupMenuItem::new("Move Down")
+                                            .icon(IconName::ChevronDown)
+                                            .on_click(window.listener_for(
+                                                &view,
+                                                |this, _, _, cx| {
+                                                    this.reorder_selected_item(
+                                                        ReorderStep::Down,
+                                                        cx,
+                                                    );
+                                                },
+                                            )),
+                                    )
+                                    .item(PopupMenuItem::new("Move to Bottom").on_click(
+                                        window.listener_for(&view, |this, _, _, cx| {
+                                            this.reorder_selected_item(ReorderStep::Bottom, cx);
+                                        }),
+                                    ))
+                                    .separator()
                                     .item(
                                         PopupMenuItem::new("Delete Item")
                                             .icon(IconName::UserTrashSymbolic)
@@ -0,0 +1,222 @@
+//! TrashBoard - 回收站视图
+//!
+//! 显示已删除的任务、分区、项目和标签，支持恢复与彻底删除。
+//! 级联删除的子对象随根对象一起显示为一条记录。
+//! 标签名称唯一，新建与回收站中同名的标签会把它取回，但不恢复原来的任务关联。
+
+use std::sync::Arc;
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, Hsla, InteractiveElement,
+    IntoElement, ParentElement, Render, Styled, Subscription, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    dock::PanelControl,
+    h_flex,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use todos::services::{TrashEntry, TrashObjectType};
+
+use crate::{
+    VisualHierarchy, todo_actions,
+    todo_state::TodoStore,
+    ui::views::boards::{board_common::render_board_header, container_board::Board},
+};
+
+pub struct TrashBoard {
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TrashBoard {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+        // 仅在回收站数据变化时重绘
+        let _subscriptions = vec![cx.observe_global::<TodoStore>(|_, cx| {
+            if cx.global::<TodoStore>().peek_change_mask().affects_trash() {
+                cx.notify();
+            }
+        })];
+
+        Self { focus_handle: cx.focus_handle(), _subscriptions }
+    }
+
+    fn object_icon(object_type: TrashObjectType) -> IconName {
+        match object_type {
+            TrashObjectType::Item => IconName::CheckRoundOutlineSymbolic,
+            TrashObjectType::Section => IconName::GalleryVerticalEnd,
+            TrashObjectType::Project => IconName::FolderOpen,
+            TrashObjectType::Label => IconName::TagOutlineSymbolic,
+        }
+    }
+
+    fn render_entry(ix: usize, entry: Arc<TrashEntry>, cx: &App) -> impl IntoElement {
+        let deleted_at = entry
+            .deleted_at
+            .map(|at| {
+                at.and_utc().with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+            })
+            .unwrap_or_default();
+        let detail = if entry.children_count > 0 {
+            format!("{} · 包含 {} 个任务", deleted_at, entry.children_count)
+        } else if entry.object_type == TrashObjectType::Label {
+            // 新建同名标签会直接取回它（名称唯一），但不带回原来的任务
+            format!("{} · 恢复会带回原来的任务；新建同名标签只取回标签本身", deleted_at)
+        } else {
+            deleted_at
+        };
+
+        h_flex()
+            .id(("trash-entry", ix))
+            .w_full()
+            .justify_between()
+            .items_center()
+            .gap(VisualHierarchy::spacing(2.0))
+            .p(VisualHierarchy::spacing(2.0))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap(VisualHierarchy::spacing(2.0))
+                    .items_center()
+                    .overflow_hidden()
+                    .child(Self::object_icon(entry.object_type))
+                    .child(
+                        v_flex()
+                            .overflow_hidden()
+                            .child(div().text_sm().truncate().child(entry.name.clone()))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(detail),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap(VisualHierarchy::spacing(1.0))
+                    .child(
+                        Button::new(("restore-trash", ix))
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::Undo)
+                            .tooltip("恢复")
+                            .on_click({
+                                let entry = entry.clone();
+                                move |_event, _window, cx| {
+                                    todo_actions::restore_from_trash(entry.clone(), cx);
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new(("purge-trash", ix))
+                            .small()
+                            .ghost()
+                            .compact()
+                            .icon(IconName::Trash)
+                            .tooltip("彻底删除")
+                            .on_click({
+                                let entry = entry.clone();
+                                move |_event, _window, cx| {
+                                    todo_actions::purge_from_trash(entry.clone(), cx);
+                                }
+                            }),
+                    ),
+            )
+    }
+}
+
+impl Board for TrashBoard {
+    fn icon() -> IconName {
+        IconName::UserTrashSymbolic
+    }
+
+    fn colors() -> Vec<Hsla> {
+        vec![gpui::rgb(0xc0bfbc).into(), gpui::rgb(0x77767b).into()]
+    }
+
+    fn count(cx: &mut App) -> usize {
+        cx.global::<TodoStore>().trash.len()
+    }
+
+    fn title() -> &'static str {
+        "Trash"
+    }
+
+    fn description() -> &'static str {
+        "回收站"
+    }
+
+    fn zoomable() -> Option<PanelControl> {
+        None
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+}
+
+impl Focusable for TrashBoard {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TrashBoard {
+    fn render(
+        &mut self,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> impl gpui::IntoElement {
+        let entries = cx.global::<TodoStore>().trash.clone();
+        let is_empty = entries.is_empty();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .gap(VisualHierarchy::spacing(4.0))
+            .child(render_board_header(
+                cx,
+                <TrashBoard as Board>::icon(),
+                <TrashBoard as Board>::title(),
+                <TrashBoard as Board>::description(),
+                Button::new("empty-trash")
+                    .small()
+                    .ghost()
+                    .compact()
+                    .icon(IconName::Trash)
+                    .tooltip("清空回收站")
+                    .on_click(|_event, _window, cx| {
+                        todo_actions::empty_trash(cx);
+                    }),
+            ))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .overflow_y_scrollbar()
+                    .p(VisualHierarchy::spacing(3.0))
+                    .when(is_empty, |this| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("回收站是空的"),
+                        )
+                    })
+                    .children(
+                        entries
+                            .into_iter()
+                            .enumerate()
+                            .map(|(ix, entry)| Self::render_entry(ix, entry, cx)),
+                    ),
+            )
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use gpui::{
     AnyView, App, AppContext, Context, Entity, EventEmitter, Focusable, Hsla, InteractiveElement,
     IntoElement, ParentElement, Pixels, Render, SharedString, StatefulInteractiveElement, Styled,
@@ -10,8 +12,9 @@ use gpui_component::{
     menu::PopupMenu,
     v_flex,
 };
+use todos::entity::FilterModel;
 
-use crate::{ShowPanelInfo, VisualHierarchy};
+use crate::{FilterBoard, ShowPanelInfo, VisualHierarchy};
 
 pub struct BoardContainer {
     focus_handle: gpui::FocusHandle,
@@ -123,6 +126,30 @@ impl BoardContainer {
         })
     }
 
+    /// 保存的过滤器看板，klass 为 `filter:{id}`，count 由 BoardPanel 刷新
+    pub fn filter_panel(
+        filter: Arc<FilterModel>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let klass = FilterBoard::klass_for(&filter.id);
+        let name = filter.name.clone();
+        let description = filter.query.clone();
+        let view = FilterBoard::view(filter, window, cx);
+        let focus_handle = cx.focus_handle();
+
+        cx.new(|cx| {
+            let mut board = Self::new(window, cx).board(view.into(), klass);
+            board.focus_handle = focus_handle;
+            board.zoomable = None;
+            board.name = name.into();
+            board.colors = vec![gpui::rgb(0x99c1f1).into(), gpui::rgb(0x1c71d8).into()];
+            board.icon = IconName::EditFindSymbolic;
+            board.description = description.into();
+            board
+        })
+    }
+
     pub fn board(mut self, board: AnyView, board_klass: impl Into<SharedString>) -> Self {
         self.board = Some(board);
         self.board_klass = Some(board_klass.into());
@@ -1,12 +1,15 @@
+pub mod board_archive;
 pub mod board_base;
 pub mod board_common;
 pub mod board_completed;
+pub mod board_filter;
 pub mod board_inbox;
 pub mod board_labels;
 pub mod board_pin;
 pub mod board_renderer;
 pub mod board_scheduled;
 pub mod board_today;
+pub mod board_trash;
 pub mod container_board;
 pub mod view;
 
@@ -11,10 +11,13 @@ use gpui_component::{
     sidebar::{SidebarBoard, SidebarBoardItem},
     v_flex,
 };
+use todos::FilterQuery;
 
 use crate::{
-    Board, BoardContainer, CompletedBoard, InboxBoard, ItemEvent, LabelEvent, LabelsBoard,
-    PinBoard, ScheduledBoard, TodayBoard, VisualHierarchy, todo_state::TodoStore,
+    ArchiveBoard, Board, BoardContainer, CompletedBoard, FilterBoard, InboxBoard, ItemEvent,
+    LabelEvent, LabelsBoard, PinBoard, ScheduledBoard, TodayBoard, TrashBoard, VisualHierarchy,
+    boards::board_filter::{FILTER_KLASS_PREFIX, show_filter_dialog},
+    todo_state::TodoStore,
 };
 
 pub struct BoardPanel {
@@ -24,23 +27,60 @@ pub struct BoardPanel {
     _subscriptions: Vec<Subscription>,
     /// 🚀 6.9修复：缓存上次各 board 的 count 值<br/>用于避免不必要的 notify，打破观察者循环
     cached_counts: Vec<usize>,
+    /// 内置看板的数量，其后为保存的过滤器看板
+    builtin_count: usize,
+    /// 当前过滤器看板对应的 (id, name, query)，与 TodoStore 不一致时重建
+    filter_snapshot: Vec<(String, String, String)>,
 }
 impl EventEmitter<LabelEvent> for BoardPanel {}
 impl EventEmitter<ItemEvent> for BoardPanel {}
 
 impl BoardPanel {
     fn board_count_for_klass(klass: &str, cx: &mut App) -> Option<usize> {
-        let map: [(&str, fn(&mut App) -> usize); 6] = [
+        let map: [(&str, fn(&mut App) -> usize); 8] = [
             (InboxBoard::klass(), InboxBoard::count),
             (TodayBoard::klass(), TodayBoard::count),
             (ScheduledBoard::klass(), ScheduledBoard::count),
             (PinBoard::klass(), PinBoard::count),
             (LabelsBoard::klass(), LabelsBoard::count),
             (CompletedBoard::klass(), CompletedBoard::count),
+            (ArchiveBoard::klass(), ArchiveBoard::count),
+            (TrashBoard::klass(), TrashBoard::count),
         ];
+        if let Some(filter_id) = klass.strip_prefix(FILTER_KLASS_PREFIX) {
+            let store = cx.global::<TodoStore>();
+            let filter = store.get_filter(filter_id)?;
+            return Some(FilterQuery::parse(&filter.query).map_or(0, |query| query.count(store)));
+        }
         map.iter().find(|(k, _)| *k == klass).map(|(_, f)| f(cx))
     }
 
+    /// 保存的过滤器增删改后，重建内置看板之后的过滤器看板
+    fn sync_filter_boards(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot: Vec<(String, String, String)> = cx
+            .global::<TodoStore>()
+            .filters
+            .iter()
+            .map(|f| (f.id.clone(), f.name.clone(), f.query.clone()))
+            .collect();
+        if snapshot == self.filter_snapshot {
+            return;
+        }
+
+        let filters = cx.global::<TodoStore>().filters.clone();
+        self.boards.truncate(self.builtin_count);
+        for filter in filters {
+            self.boards.push(BoardContainer::filter_panel(filter, window, cx));
+        }
+        self.filter_snapshot = snapshot;
+        if self.active_index.is_some_and(|ix| ix >= self.boards.len()) {
+            self.active_index = Some(0);
+        }
+        // 强制下一次刷新所有 count
+        self.cached_counts.clear();
+        self.refresh_counts_if_changed(cx);
+    }
+
     /// 🚀 6.9修复：安全地刷新 count 并检查是否有实际变化
     ///
     /// 只有在至少一个 board 的 count 发生变化时才返回 true，
@@ -82,7 +122,7 @@ impl BoardPanel {
 
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search..."));
-        // 🚀 7.0修复后：恢复所有 6 个 Board（InboxBoard 已使用延迟注册）
+        // 🚀 7.0修复后：恢复所有 Board（InboxBoard 已使用延迟注册），末尾为归档和回收站
         let boards = vec![
             BoardContainer::panel::<InboxBoard>(window, cx),
             BoardContainer::panel::<TodayBoard>(window, cx),
@@ -90,10 +130,13 @@ impl BoardPanel {
             BoardContainer::panel::<PinBoard>(window, cx),
             BoardContainer::panel::<LabelsBoard>(window, cx),
             BoardContainer::panel::<CompletedBoard>(window, cx),
+            BoardContainer::panel::<ArchiveBoard>(window, cx),
+            BoardContainer::panel::<TrashBoard>(window, cx),
         ];
 
         // 初始化缓存的 count 值（全为0，第一次回调时会更新）
         let cached_counts = vec![0; boards.len()];
+        let builtin_count = boards.len();
 
         let _subscriptions = vec![
             cx.subscribe(&search_input, |this, _, e, cx| {
@@ -109,7 +152,15 @@ impl BoardPanel {
                 }
             }),
         ];
-        Self { search_input, boards, active_index: Some(0), _subscriptions, cached_counts }
+        Self {
+            search_input,
+            boards,
+            active_index: Some(0),
+            _subscriptions,
+            cached_counts,
+            builtin_count,
+            filter_snapshot: Vec::new(),
+        }
     }
 
     pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
@@ -119,10 +170,16 @@ impl BoardPanel {
     pub fn update_active_index(&mut self, value: Option<usize>) {
         self.active_index = value;
     }
+
+    /// 按 `Board::klass()` 查找看板的索引
+    pub fn board_index_for_klass(&self, klass: &str, cx: &App) -> Option<usize> {
+        self.boards.iter().position(|board| board.read(cx).board_klass.as_deref() == Some(klass))
+    }
 }
 
 impl Render for BoardPanel {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_filter_boards(window, cx);
         let query = self.search_input.read(cx).value().trim().to_lowercase();
         let boards: Vec<_> = self
             .boards
@@ -148,11 +205,13 @@ impl Render for BoardPanel {
                             .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
                             .child(Input::new(&self.search_input).appearance(false).cleanable(true))
                             .child(
-                                Button::new("add-section")
+                                Button::new("add-filter")
                                     .small()
                                     .ghost()
                                     .compact()
-                                    .icon(IconName::EditFindSymbolic),
+                                    .icon(IconName::EditFindSymbolic)
+                                    .tooltip("New Filter")
+                                    .on_click(|_, window, cx| show_filter_dialog(None, window, cx)),
                             )
                             .child(
                                 Button::new("edit-section")
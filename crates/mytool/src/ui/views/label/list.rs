@@ -10,7 +10,7 @@ use gpui_component::{
 };
 use todos::entity::LabelModel;
 
-use crate::{UnSelectedCheckLabel, VisualHierarchy};
+use crate::{UnSelectedCheckLabel, VisualHierarchy, todo_state::TodoStore};
 
 actions!(label, [SelectedLabel, UnSelectedLabel]);
 #[derive(Debug)]
@@ -29,6 +29,8 @@ pub struct LabelListItem {
     label: Arc<LabelModel>,
     selected: bool,
     checked: bool,
+    /// 使用该标签的任务数
+    usage: usize,
 }
 
 impl LabelListItem {
@@ -37,8 +39,9 @@ impl LabelListItem {
         label: Arc<LabelModel>,
         selected: bool,
         checked: bool,
+        usage: usize,
     ) -> Self {
-        LabelListItem { label, base: ListItem::new(id), selected, checked }
+        LabelListItem { label, base: ListItem::new(id), selected, checked, usage }
     }
 }
 
@@ -95,7 +98,23 @@ impl RenderOnce for LabelListItem {
                             )
                             .child(div().w(px(120.)).child(self.label.name.clone())),
                     )
-                    .child(h_flex().gap(VisualHierarchy::spacing(1.0))),
+                    .child(
+                        h_flex()
+                            .gap(VisualHierarchy::spacing(1.0))
+                            .items_center()
+                            .when(self.label.is_favorite, |this| {
+                                this.child(
+                                    Icon::build(IconName::StarOutlineThickSymbolic)
+                                        .text_color(cx.theme().warning),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(self.usage.to_string()),
+                            ),
+                    ),
             )
     }
 }
@@ -202,14 +221,15 @@ impl ListDelegate for LabelListDelegate {
         &mut self,
         ix: IndexPath,
         _window: &mut Window,
-        _cx: &mut Context<ListState<Self>>,
+        cx: &mut Context<ListState<Self>>,
     ) -> Option<Self::Item> {
         let selected = Some(ix) == self.selected_index || Some(ix) == self.confirmed_index;
         if let Some(section) = self.matched_labels.get(ix.section)
             && let Some(label) = section.get(ix.row)
         {
             let checked = self.checked_labels.contains(label);
-            return Some(LabelListItem::new(ix, label.clone(), selected, checked));
+            let usage = cx.global::<TodoStore>().items_by_label(&label.id).len();
+            return Some(LabelListItem::new(ix, label.clone(), selected, checked, usage));
         }
         None
     }
@@ -17,7 +17,11 @@ use todos::entity::LabelModel;
 use super::LabelEvent;
 use crate::{
     ColorGroup, ColorGroupEvent, ColorGroupState, LabelListDelegate, VisualHierarchy,
-    todo_actions::{add_label, delete_label, update_label},
+    show_merge_label_dialog,
+    todo_actions::{
+        add_label, delete_label, merge_labels, move_label, rename_label, set_label_favorite,
+        update_label,
+    },
     todo_state::TodoStore,
 };
 
@@ -108,7 +112,16 @@ impl LabelsPanel {
             },
             LabelEvent::Modified(label) => {
                 tracing::info!("LabelEvent::Modified: {} (id: {})", label.name, label.id);
-                update_label(label.clone(), cx)
+                // 改名走带冲突检查的重命名
+                let renamed = cx
+                    .global::<TodoStore>()
+                    .get_label(&label.id)
+                    .is_some_and(|before| before.name != label.name);
+                if renamed {
+                    rename_label(label.clone(), cx)
+                } else {
+                    update_label(label.clone(), cx)
+                }
             },
             LabelEvent::Deleted(label) => {
                 tracing::info!("LabelEvent::Deleted: {} (id: {})", label.name, label.id);
@@ -178,10 +191,34 @@ impl LabelsPanel {
                     let view = view.clone();
                     let ori_label = ori_label.clone();
                     let name_input_clone = name_input.clone();
-                    move |_, _window: &mut Window, cx| {
+                    move |_, window: &mut Window, cx| {
+                        let name = name_input_clone.read(cx).value().trim().to_string();
+                        // 与已有标签同名时改为询问是否合并
+                        let existing = cx
+                            .global::<TodoStore>()
+                            .labels
+                            .iter()
+                            .find(|label| {
+                                label.id != ori_label.id
+                                    && label.name.to_lowercase() == name.to_lowercase()
+                            })
+                            .cloned();
+                        if let Some(existing) = existing {
+                            let source = is_edit
+                                .then(|| cx.global::<TodoStore>().get_label(&ori_label.id))
+                                .flatten();
+                            window.defer(cx, move |window, cx| match source {
+                                Some(source) => confirm_merge(source, existing, window, cx),
+                                None => window.push_notification(
+                                    format!("Label \"{}\" already exists.", existing.name),
+                                    cx,
+                                ),
+                            });
+                            return true;
+                        }
                         view.update(cx, |view, cx| {
                             let label = Arc::new(LabelModel {
-                                name: name_input_clone.read(cx).value().to_string(),
+                                name,
                                 color: view.selected_color.unwrap_or_default().to_hex(),
                                 ..ori_label.clone()
                             });
@@ -198,16 +235,62 @@ impl LabelsPanel {
         });
     }
 
+    fn active_label(&self, cx: &App) -> Option<Arc<LabelModel>> {
+        self.active_index.and_then(|index| self.get_selected_label(IndexPath::new(index), cx))
+    }
+
+    /// 把选中的标签合并到另一个标签
+    pub fn show_merge_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(label) = self.active_label(cx) {
+            show_merge_label_dialog(label, window, cx);
+        }
+    }
+
+    /// 收藏 / 取消收藏选中的标签
+    pub fn toggle_active_favorite(&mut self, cx: &mut Context<Self>) {
+        if let Some(label) = self.active_label(cx) {
+            let is_favorite = !label.is_favorite;
+            set_label_favorite(label, is_favorite, cx);
+        }
+    }
+
+    /// 上移 / 下移选中的标签，选中状态跟随标签
+    pub fn move_active_label(&mut self, offset: isize, cx: &mut Context<Self>) {
+        let (Some(index), Some(label)) = (self.active_index, self.active_label(cx)) else {
+            return;
+        };
+        let count = cx.global::<TodoStore>().labels.len();
+        let target = index.saturating_add_signed(offset).min(count.saturating_sub(1));
+        if target == index {
+            return;
+        }
+        move_label(label, offset, cx);
+        self.active_index = Some(target);
+        self.label_list.update(cx, |list, cx| {
+            list.delegate_mut().selected_index = Some(IndexPath::new(target));
+            cx.notify();
+        });
+    }
+
     pub fn show_label_delete_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(active_index) = self.active_index {
             let label_some = self.get_selected_label(IndexPath::new(active_index), cx);
             if let Some(label) = label_some {
                 let view = cx.entity().clone();
+                // 提示仍在使用该标签的任务数，避免误删
+                let message = match cx.global::<TodoStore>().items_by_label(&label.id).len() {
+                    0 => "Are you sure to delete the label?".to_string(),
+                    usage => format!(
+                        "{} tasks use \"{}\". It will be hidden from them until restored from the \
+                         trash. Delete it anyway?",
+                        usage, label.name
+                    ),
+                };
                 window.open_dialog(cx, move |dialog, _, _| {
                     dialog
                         .overlay(true)
                         .overlay_closable(true)
-                        .child("Are you sure to delete the label?")
+                        .child(message.clone())
                         .on_ok({
                             let view = view.clone();
                             let label = label.clone();
@@ -232,6 +315,35 @@ impl LabelsPanel {
     }
 }
 
+/// 改名与已有标签同名时，询问是否把当前标签合并过去
+fn confirm_merge(
+    source: Arc<LabelModel>,
+    target: Arc<LabelModel>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let usage = cx.global::<TodoStore>().items_by_label(&source.id).len();
+    let message = format!(
+        "Label \"{}\" already exists. Merge \"{}\" into it? {} tasks will use \"{}\" and \"{}\" \
+         moves to the trash.",
+        target.name, source.name, usage, target.name, source.name
+    );
+    window.open_dialog(cx, move |dialog, _, _| {
+        let source = source.clone();
+        let target = target.clone();
+        dialog
+            .title("Merge Labels")
+            .overlay(true)
+            .overlay_closable(true)
+            .child(message.clone())
+            .on_ok(move |_, window: &mut Window, cx| {
+                merge_labels(source.clone(), target.clone(), cx);
+                window.push_notification("Merging labels...", cx);
+                true
+            })
+    });
+}
+
 impl Render for LabelsPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         List::new(&self.label_list)
@@ -5,8 +5,9 @@ mod project;
 
 pub use boards::{
     BoardBase, BoardItemClickEvent, BoardSectionActions, BoardView, FinishItemDialogStyle,
-    board_completed::CompletedBoard, board_inbox::InboxBoard, board_labels::LabelsBoard,
-    board_pin::PinBoard, board_scheduled::ScheduledBoard, board_today::TodayBoard,
+    board_archive::ArchiveBoard, board_completed::CompletedBoard, board_filter::FilterBoard,
+    board_inbox::InboxBoard, board_labels::LabelsBoard, board_pin::PinBoard,
+    board_scheduled::ScheduledBoard, board_today::TodayBoard, board_trash::TrashBoard,
     container_board::*, view::*,
 };
 pub use item::*;
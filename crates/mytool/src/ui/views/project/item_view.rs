@@ -16,17 +16,19 @@ use gpui_component::{
     scroll::ScrollableElement,
     v_flex,
 };
-use sea_orm::sqlx::types::uuid;
 use todos::entity::{ItemModel, ProjectModel};
 
 use crate::{
-    ColorGroup, ColorGroupEvent, ColorGroupState, ItemEvent, ItemInfoEvent, ItemInfoState, ItemRow,
-    ItemRowState, VisualHierarchy, section,
+    ColorGroup, ColorGroupEvent, ColorGroupState, DuplicateTask, ItemEvent, ItemInfoEvent,
+    ItemInfoState, ItemRow, ItemRowState, VisualHierarchy, section, show_move_items_dialog,
     todo_actions::{
-        add_section, delete_project, delete_project_item, delete_section, load_project_items,
-        update_project, update_project_item, update_section,
+        ReorderStep, add_section, archive_project, delete_project, delete_project_item,
+        delete_section, duplicate_item, duplicate_project, duplicate_section, load_project_items,
+        reorder_item, save_project_template, set_section_archived, update_project,
+        update_project_item, update_section,
     },
     todo_state::TodoStore,
+    ui::views::boards::{BoardView, render_item_list},
 };
 
 pub enum ProjectItemEvent {
@@ -80,7 +82,17 @@ impl ProjectItemsPanel {
                     return;
                 }
 
+                // 重建前记下选中的任务，排序变化后选中项跟随任务
+                let selected_id = this
+                    .active_index
+                    .and_then(|ix| this.item_rows.get(ix))
+                    .map(|row| row.read(cx).item.id.clone());
                 let state_items = todo_store.items_by_project(&this.project.id);
+                if let Some(ix) =
+                    selected_id.and_then(|id| state_items.iter().position(|item| item.id == id))
+                {
+                    this.active_index = Some(ix);
+                }
                 this.item_rows = state_items
                     .iter()
                     .map(|item| cx.new(|cx| ItemRowState::new(item.clone(), window, cx)))
@@ -285,6 +297,59 @@ impl ProjectItemsPanel {
         }
     }
 
+    /// 在同一分区 / 父任务下移动选中的任务（写 child_order）
+    pub fn reorder_selected_item(&mut self, step: ReorderStep, cx: &mut Context<Self>) {
+        if let Some(item) =
+            self.active_index.and_then(|ix| self.get_selected_item(IndexPath::new(ix), cx))
+        {
+            reorder_item(item, step, cx);
+        }
+    }
+
+    /// 复制选中的任务（含子任务），副本排在原任务之后
+    pub fn duplicate_selected_item(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(item) =
+            self.active_index.and_then(|ix| self.get_selected_item(IndexPath::new(ix), cx))
+        {
+            duplicate_item(item, cx);
+            window.push_notification("Duplicating task...", cx);
+        }
+    }
+
+    fn on_action_duplicate_task(
+        &mut self,
+        _: &DuplicateTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if window.has_focused_input(cx) {
+            cx.propagate();
+            return;
+        }
+        self.duplicate_selected_item(window, cx);
+    }
+
+    /// 把分区中未完成的任务（连同子任务）移到其他项目 / 分区
+    pub fn show_move_section_items_dialog(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        section_id: String,
+    ) {
+        let items: Vec<Arc<ItemModel>> = self
+            .section_items_map
+            .get(&section_id)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter(|(_, item)| !item.checked)
+                    .map(|(_, item)| item.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        show_move_items_dialog(items, window, cx);
+    }
+
     pub fn show_section_dialog(
         &mut self,
         window: &mut Window,
@@ -373,13 +438,10 @@ impl ProjectItemsPanel {
         cx: &mut Context<Self>,
         section_id: String,
     ) {
-        let sections = cx.global::<TodoStore>().sections.clone();
-        if let Some(section) = sections.iter().find(|s| s.id == section_id) {
-            let mut new_section = section.as_ref().clone();
-            new_section.id = uuid::Uuid::new_v4().to_string();
-            new_section.name = format!("{} (copy)", new_section.name);
-            add_section(Arc::new(new_section), cx);
-            window.push_notification("Section duplicated successfully.", cx);
+        // 连同分区中的任务一起复制
+        if let Some(section) = cx.global::<TodoStore>().get_section(&section_id) {
+            duplicate_section(section, cx);
+            window.push_notification("Duplicating section...", cx);
         }
     }
 
@@ -389,11 +451,8 @@ impl ProjectItemsPanel {
         cx: &mut Context<Self>,
         section_id: String,
     ) {
-        let sections = cx.global::<TodoStore>().sections.clone();
-        if let Some(section) = sections.iter().find(|s| s.id == section_id) {
-            let mut updated_section = section.as_ref().clone();
-            updated_section.is_archived = true;
-            update_section(Arc::new(updated_section), cx);
+        if let Some(section) = cx.global::<TodoStore>().get_section(&section_id) {
+            set_section_archived(section, true, cx);
             window.push_notification("Section archived successfully.", cx);
         }
     }
@@ -501,6 +560,12 @@ impl ProjectItemsPanel {
     }
 }
 
+impl BoardView for ProjectItemsPanel {
+    fn set_active_index(&mut self, index: Option<usize>) {
+        self.active_index = index;
+    }
+}
+
 impl Focusable for ProjectItemsPanel {
     fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
         self.focus_handle.clone()
@@ -516,6 +581,7 @@ impl Render for ProjectItemsPanel {
 
         v_flex()
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::on_action_duplicate_task))
             .size_full()
             .gap(VisualHierarchy::spacing(4.0))
             .child(
@@ -546,6 +612,50 @@ impl Render for ProjectItemsPanel {
                                         }
                                     }),
                             )
+                            .child(
+                                Button::new("archive-project")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::FolderOpen)
+                                    .tooltip("Archive Project")
+                                    .on_click({
+                                        let project = self.project.clone();
+                                        move |_event, window, cx| {
+                                            archive_project(project.clone(), cx);
+                                            window.push_notification("Project archived.", cx);
+                                        }
+                                    }),
+                            )
+                            .child(
+                                Button::new("save-project-template")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::Copy)
+                                    .tooltip("Save as Template")
+                                    .on_click({
+                                        let project = self.project.clone();
+                                        move |_event, _window, cx| {
+                                            save_project_template(project.clone(), cx);
+                                        }
+                                    }),
+                            )
+                            .child(
+                                Button::new("duplicate-project")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::GalleryVerticalEnd)
+                                    .tooltip("Duplicate Project")
+                                    .on_click({
+                                        let project = self.project.clone();
+                                        move |_event, window, cx| {
+                                            duplicate_project(project.clone(), cx);
+                                            window.push_notification("Duplicating project...", cx);
+                                        }
+                                    }),
+                            )
                             .child(
                                 Button::new("delete-project")
                                     .small()
@@ -639,6 +749,22 @@ impl Render for ProjectItemsPanel {
                                         }
                                     }),
                             )
+                            .child(
+                                Button::new("duplicate-item")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .icon(IconName::Copy)
+                                    .tooltip("Duplicate Task")
+                                    .on_click({
+                                        let view = view.clone();
+                                        move |_event, window, cx| {
+                                            view.update(cx, |this, cx| {
+                                                this.duplicate_selected_item(window, cx);
+                                            })
+                                        }
+                                    }),
+                            )
                             .child(
                                 Button::new("delete-item")
                                     .icon(IconName::UserTrashSymbolic)
@@ -654,6 +780,43 @@ impl Render for ProjectItemsPanel {
                                         }
                                     }),
                             )
+                            .child(
+                                Button::new("reorder-item")
+                                    .small()
+                                    .ghost()
+                                    .compact()
+                                    .tooltip("Reorder")
+                                    .icon(IconName::MenuLargeSymbolic)
+                                    .dropdown_menu({
+                                        let view = view.clone();
+                                        move |this, window, _cx| {
+                                            this.item(PopupMenuItem::new("Move to Top").on_click(
+                                                window.listener_for(&view, |this, _, _, cx| {
+                                                    this.reorder_selected_item(ReorderStep::Top, cx);
+                                                }),
+                                            ))
+                                            .item(
+                                                PopupMenuItem::new("Move Up")
+                                                    .icon(IconName::ChevronUp)
+                                                    .on_click(window.listener_for(&view, |this, _, _, cx| {
+                                                        this.reorder_selected_item(ReorderStep::Up, cx);
+                                                    })),
+                                            )
+                                            .item(
+                                                PopupMenuItem::new("Move Down")
+                                                    .icon(IconName::ChevronDown)
+                                                    .on_click(window.listener_for(&view, |this, _, _, cx| {
+                                                        this.reorder_selected_item(ReorderStep::Down, cx);
+                                                    })),
+                                            )
+                                            .item(PopupMenuItem::new("Move to Bottom").on_click(
+                                                window.listener_for(&view, |this, _, _, cx| {
+                                                    this.reorder_selected_item(ReorderStep::Bottom, cx);
+                                                }),
+                                            ))
+                                        }
+                                    }),
+                            )
                             .child(
                                 Button::new("section-actions")
                                     .small()
@@ -762,25 +925,12 @@ impl Render for ProjectItemsPanel {
                                                 }),
                                         ),
                                     )
-                                    .child(v_flex().gap(VisualHierarchy::spacing(2.0)).w_full().children(
-                                        no_section_items.iter().map(|(i, _item)| {
-                                            let i = *i;
-                                            let view = view_clone.clone();
-                                            let is_active = self.active_index == Some(i);
-                                            let item_row = self.item_rows.get(i).cloned();
-                                            div()
-                                                .id(("item", i))
-                                                .on_click(move |_, _, cx| {
-                                                    view.update(cx, |this, cx| {
-                                                        this.active_index = Some(i);
-                                                        cx.notify();
-                                                    });
-                                                })
-                                                .when(is_active, |this| {
-                                                    this.border_color(cx.theme().list_active_border)
-                                                })
-                                                .children(item_row.map(|row| ItemRow::new(&row)))
-                                        }),
+                                    .child(render_item_list(
+                                        no_section_items,
+                                        &self.item_rows,
+                                        self.active_index,
+                                        cx.theme().list_active_border,
+                                        view_clone,
                                     )),
                             )
                         })
@@ -902,6 +1052,16 @@ impl Render for ProjectItemsPanel {
                                                                         })
                                                                     )
                                                                 })
+                                                                .item({
+                                                                    let view = view.clone();
+                                                                    let section_id = section_id.clone();
+                                                                    PopupMenuItem::new("Move Tasks").on_click(
+                                                                        window.listener_for(&view, move |this, _, window, cx| {
+                                                                            this.show_move_section_items_dialog(window, cx, section_id.clone());
+                                                                            cx.notify();
+                                                                        })
+                                                                    )
+                                                                })
                                                                 .separator()
                                                                 .item({
                                                                     let view = view.clone();
@@ -928,26 +1088,13 @@ impl Render for ProjectItemsPanel {
                                                     }),
                                             ),
                                     )
-                                    .child(v_flex().gap(VisualHierarchy::spacing(2.0)).w_full().children(items.iter().map(
-                                        |(i, _item)| {
-                                            let view = view_clone.clone();
-                                            let i = *i;
-                                            let is_active = self.active_index == Some(i);
-                                            let item_row = self.item_rows.get(i).cloned();
-                                            div()
-                                                .id(("item", i))
-                                                .on_click(move |_, _, cx| {
-                                                    view.update(cx, |this, cx| {
-                                                        this.active_index = Some(i);
-                                                        cx.notify();
-                                                    });
-                                                })
-                                                .when(is_active, |this| {
-                                                    this.border_color(cx.theme().list_active_border)
-                                                })
-                                                .children(item_row.map(|row| ItemRow::new(&row)))
-                                        },
-                                    ))),
+                                    .child(render_item_list(
+                                        items,
+                                        &self.item_rows,
+                                        self.active_index,
+                                        cx.theme().list_active_border,
+                                        view_clone,
+                                    )),
                             )
                         })),
                 ),
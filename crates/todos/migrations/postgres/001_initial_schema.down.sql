@@ -0,0 +1,17 @@
+-- =====================================================
+-- 回滚初始结构：删除全部表（索引和触发器随表一起删除）
+-- =====================================================
+DROP TABLE IF EXISTS item_labels;
+DROP TABLE IF EXISTS Sources;
+DROP TABLE IF EXISTS OEvents;
+DROP TABLE IF EXISTS Attachments;
+DROP TABLE IF EXISTS CurTempIds;
+DROP TABLE IF EXISTS Queue;
+DROP TABLE IF EXISTS Reminders;
+DROP TABLE IF EXISTS Items;
+DROP TABLE IF EXISTS Sections;
+DROP TABLE IF EXISTS Projects;
+DROP TABLE IF EXISTS Labels;
+
+DROP FUNCTION IF EXISTS oevents_item_insert();
+DROP FUNCTION IF EXISTS oevents_item_update();
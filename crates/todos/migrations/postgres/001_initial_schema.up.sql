@@ -0,0 +1,277 @@
+-- =====================================================
+-- 初始结构（PostgreSQL）
+-- 与 SQLite 版本的表、列一致；类型按实体绑定的值调整：
+-- 时间列为 TIMESTAMP，JSON 列为 JSONB，OEvents.id 为 BIGSERIAL。
+-- 表名不加引号，统一折叠为小写，与实体的 table_name 一致。
+-- =====================================================
+CREATE TABLE IF NOT EXISTS Labels (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    color TEXT,
+    item_order INTEGER,
+    is_deleted BOOLEAN,
+    is_favorite BOOLEAN,
+    backend_type TEXT,
+    source_id TEXT,
+    CONSTRAINT unique_label UNIQUE (name)
+);
+
+CREATE TABLE IF NOT EXISTS Projects (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    color TEXT,
+    backend_type TEXT,
+    inbox_project INTEGER,
+    team_inbox INTEGER,
+    child_order INTEGER,
+    is_deleted BOOLEAN,
+    is_archived BOOLEAN,
+    is_favorite BOOLEAN,
+    shared INTEGER,
+    view_style TEXT,
+    sort_order INTEGER,
+    parent_id TEXT,
+    collapsed BOOLEAN,
+    icon_style TEXT,
+    emoji TEXT,
+    show_completed INTEGER,
+    description TEXT,
+    due_date TEXT,
+    inbox_section_hidded INTEGER,
+    sync_id TEXT,
+    source_id TEXT
+);
+
+CREATE TABLE IF NOT EXISTS Sections (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    archived_at TIMESTAMP,
+    added_at TIMESTAMP,
+    project_id TEXT,
+    section_order INTEGER,
+    collapsed BOOLEAN,
+    is_deleted BOOLEAN,
+    is_archived BOOLEAN,
+    color TEXT,
+    description TEXT,
+    hidded BOOLEAN,
+    FOREIGN KEY (project_id) REFERENCES Projects (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS Items (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    description TEXT,
+    due JSONB,
+    added_at TIMESTAMP,
+    completed_at TIMESTAMP,
+    updated_at TIMESTAMP,
+    section_id TEXT,
+    project_id TEXT,
+    parent_id TEXT,
+    priority INTEGER,
+    child_order INTEGER,
+    checked BOOLEAN,
+    is_deleted BOOLEAN,
+    day_order INTEGER,
+    collapsed BOOLEAN,
+    pinned BOOLEAN,
+    labels TEXT,
+    extra_data JSONB,
+    item_type TEXT
+);
+
+CREATE TABLE IF NOT EXISTS Reminders (
+    id TEXT PRIMARY KEY,
+    notify_uid INTEGER,
+    item_id TEXT,
+    service TEXT,
+    type TEXT,
+    due TEXT,
+    mm_offset INTEGER,
+    is_deleted BOOLEAN,
+    FOREIGN KEY (item_id) REFERENCES Items (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS Queue (
+    uuid TEXT PRIMARY KEY,
+    object_id TEXT,
+    query TEXT,
+    temp_id TEXT,
+    args TEXT,
+    date_added TEXT
+);
+
+CREATE TABLE IF NOT EXISTS CurTempIds (
+    id TEXT PRIMARY KEY,
+    temp_id TEXT,
+    object TEXT
+);
+
+CREATE TABLE IF NOT EXISTS Attachments (
+    id TEXT PRIMARY KEY,
+    item_id TEXT,
+    file_type TEXT,
+    file_name TEXT,
+    file_size BIGINT,
+    file_path TEXT,
+    FOREIGN KEY (item_id) REFERENCES Items (id) ON DELETE CASCADE
+);
+
+-- event_date 与 SQLite 的 datetime('now', 'localtime') 格式相同（本地时间文本）
+CREATE TABLE IF NOT EXISTS OEvents (
+    id BIGSERIAL PRIMARY KEY,
+    event_type TEXT,
+    event_date TEXT DEFAULT to_char(LOCALTIMESTAMP, 'YYYY-MM-DD HH24:MI:SS'),
+    object_id TEXT,
+    object_type TEXT,
+    object_key TEXT,
+    object_old_value TEXT,
+    object_new_value TEXT,
+    parent_item_id TEXT,
+    parent_project_id TEXT
+);
+
+CREATE TABLE IF NOT EXISTS Sources (
+    id TEXT PRIMARY KEY,
+    source_type TEXT NOT NULL,
+    display_name TEXT,
+    added_at TIMESTAMP,
+    updated_at TIMESTAMP,
+    is_visible BOOLEAN,
+    child_order INTEGER,
+    sync_server INTEGER,
+    last_sync TEXT,
+    data TEXT
+);
+
+-- =====================================================
+-- Item-Label 关联表
+-- 替代原有的 items.labels 字段（分号分隔字符串）
+-- =====================================================
+CREATE TABLE IF NOT EXISTS item_labels (
+    item_id TEXT NOT NULL,
+    label_id TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (item_id, label_id),
+    FOREIGN KEY (item_id) REFERENCES Items(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES Labels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_item_labels_item_id ON item_labels(item_id);
+CREATE INDEX IF NOT EXISTS idx_item_labels_label_id ON item_labels(label_id);
+
+-- =====================================================
+-- 索引
+-- =====================================================
+CREATE INDEX IF NOT EXISTS idx_items_project_id ON Items(project_id);
+CREATE INDEX IF NOT EXISTS idx_items_section_id ON Items(section_id);
+CREATE INDEX IF NOT EXISTS idx_items_parent_id ON Items(parent_id);
+CREATE INDEX IF NOT EXISTS idx_items_checked ON Items(checked);
+CREATE INDEX IF NOT EXISTS idx_items_pinned ON Items(pinned);
+CREATE INDEX IF NOT EXISTS idx_items_due ON Items(due);
+
+CREATE INDEX IF NOT EXISTS idx_items_project_checked ON Items(project_id, checked);
+CREATE INDEX IF NOT EXISTS idx_items_section_checked ON Items(section_id, checked);
+CREATE INDEX IF NOT EXISTS idx_items_parent_checked ON Items(parent_id, checked);
+
+CREATE INDEX IF NOT EXISTS idx_sections_project_id ON Sections(project_id);
+CREATE INDEX IF NOT EXISTS idx_reminders_item_id ON Reminders(item_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_item_id ON Attachments(item_id);
+CREATE INDEX IF NOT EXISTS idx_oevents_object_id ON OEvents(object_id);
+CREATE INDEX IF NOT EXISTS idx_oevents_parent_project ON OEvents(parent_project_id);
+
+-- =====================================================
+-- OEvents 触发器
+-- 与 SQLite 版本相同的触发器名和条件（`<>` 遇到 NULL 不触发）。
+-- 旧值 / 新值取列的文本形式：布尔为 true / false，due 为 JSON 文本。
+-- =====================================================
+CREATE OR REPLACE FUNCTION oevents_item_insert() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO OEvents (
+        event_type,
+        object_id,
+        object_type,
+        object_key,
+        object_old_value,
+        object_new_value,
+        parent_project_id
+    )
+    VALUES ('insert', NEW.id, 'item', 'content', NEW.content, NEW.content, NEW.project_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- 参数：TG_ARGV[0] 为 object_key，TG_ARGV[1] 为列名
+CREATE OR REPLACE FUNCTION oevents_item_update() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO OEvents (
+        event_type,
+        object_id,
+        object_type,
+        object_key,
+        object_old_value,
+        object_new_value,
+        parent_project_id
+    )
+    VALUES (
+        'update',
+        NEW.id,
+        'item',
+        TG_ARGV[0],
+        to_jsonb(OLD) ->> TG_ARGV[1],
+        to_jsonb(NEW) ->> TG_ARGV[1],
+        NEW.project_id
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER after_insert_item
+AFTER INSERT ON Items
+FOR EACH ROW EXECUTE FUNCTION oevents_item_insert();
+
+CREATE TRIGGER after_update_content_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.content <> OLD.content)
+EXECUTE FUNCTION oevents_item_update('content', 'content');
+
+CREATE TRIGGER after_update_description_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.description <> OLD.description)
+EXECUTE FUNCTION oevents_item_update('description', 'description');
+
+CREATE TRIGGER after_update_due_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.due <> OLD.due)
+EXECUTE FUNCTION oevents_item_update('due', 'due');
+
+CREATE TRIGGER after_update_priority_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.priority <> OLD.priority)
+EXECUTE FUNCTION oevents_item_update('priority', 'priority');
+
+CREATE TRIGGER after_update_labels_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.labels <> OLD.labels)
+EXECUTE FUNCTION oevents_item_update('labels', 'labels');
+
+CREATE TRIGGER after_update_pinned_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.pinned <> OLD.pinned)
+EXECUTE FUNCTION oevents_item_update('pinned', 'pinned');
+
+CREATE TRIGGER after_update_checked_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.checked <> OLD.checked)
+EXECUTE FUNCTION oevents_item_update('checked', 'checked');
+
+CREATE TRIGGER after_update_section_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.section_id <> OLD.section_id)
+EXECUTE FUNCTION oevents_item_update('section', 'section_id');
+
+CREATE TRIGGER after_update_project_item
+AFTER UPDATE ON Items
+FOR EACH ROW WHEN (NEW.project_id <> OLD.project_id)
+EXECUTE FUNCTION oevents_item_update('project', 'project_id');
@@ -0,0 +1,18 @@
+-- =====================================================
+-- 回收站（软删除）
+-- 记录删除时间，用于整棵树一起恢复以及按保留期自动清理
+-- =====================================================
+ALTER TABLE Items ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE Projects ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE Sections ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE Labels ADD COLUMN deleted_at TIMESTAMP;
+
+-- 旧数据中 is_deleted 可能为 NULL，统一为 FALSE
+UPDATE Items SET is_deleted = FALSE WHERE is_deleted IS NULL;
+UPDATE Projects SET is_deleted = FALSE WHERE is_deleted IS NULL;
+UPDATE Sections SET is_deleted = FALSE WHERE is_deleted IS NULL;
+UPDATE Labels SET is_deleted = FALSE WHERE is_deleted IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_items_is_deleted ON Items(is_deleted);
+CREATE INDEX IF NOT EXISTS idx_projects_is_deleted ON Projects(is_deleted);
+CREATE INDEX IF NOT EXISTS idx_sections_is_deleted ON Sections(is_deleted);
@@ -0,0 +1,19 @@
+-- =====================================================
+-- 回滚全文搜索索引
+-- =====================================================
+DROP TRIGGER IF EXISTS search_items_insert ON Items;
+DROP TRIGGER IF EXISTS search_items_update ON Items;
+DROP TRIGGER IF EXISTS search_items_delete ON Items;
+DROP TRIGGER IF EXISTS search_projects_insert ON Projects;
+DROP TRIGGER IF EXISTS search_projects_update ON Projects;
+DROP TRIGGER IF EXISTS search_projects_delete ON Projects;
+DROP TRIGGER IF EXISTS search_sections_insert ON Sections;
+DROP TRIGGER IF EXISTS search_sections_update ON Sections;
+DROP TRIGGER IF EXISTS search_sections_delete ON Sections;
+DROP TRIGGER IF EXISTS search_labels_insert ON Labels;
+DROP TRIGGER IF EXISTS search_labels_update ON Labels;
+DROP TRIGGER IF EXISTS search_labels_delete ON Labels;
+
+DROP FUNCTION IF EXISTS search_index_sync();
+
+DROP TABLE IF EXISTS search_index;
@@ -0,0 +1,99 @@
+-- =====================================================
+-- 全文搜索索引（PostgreSQL）
+-- 与 SQLite 版本列相同的普通表，由触发器保持同步；查询用 ILIKE 子串匹配，
+-- 中文等无空格文本同样适用。只索引未删除的对象。
+-- =====================================================
+CREATE TABLE IF NOT EXISTS search_index (
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    object_type TEXT NOT NULL,
+    object_id TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_index_object ON search_index (object_type, object_id);
+
+-- 回填已有数据
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT content, COALESCE(description, ''), 'item', id FROM Items WHERE NOT COALESCE(is_deleted, FALSE);
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT name, COALESCE(description, ''), 'project', id FROM Projects WHERE NOT COALESCE(is_deleted, FALSE);
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(name, ''), COALESCE(description, ''), 'section', id FROM Sections WHERE NOT COALESCE(is_deleted, FALSE);
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(name, ''), '', 'label', id FROM Labels WHERE NOT COALESCE(is_deleted, FALSE);
+
+-- 四张表共用：TG_ARGV[0] 为 object_type，标题取 content（任务）或 name
+CREATE OR REPLACE FUNCTION search_index_sync() RETURNS trigger AS $$
+DECLARE
+    row_data JSONB;
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        DELETE FROM search_index WHERE object_type = TG_ARGV[0] AND object_id = OLD.id;
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        row_data := to_jsonb(NEW);
+        IF NOT COALESCE((row_data ->> 'is_deleted')::BOOLEAN, FALSE) THEN
+            INSERT INTO search_index (title, body, object_type, object_id)
+            VALUES (
+                COALESCE(row_data ->> 'content', row_data ->> 'name', ''),
+                COALESCE(row_data ->> 'description', ''),
+                TG_ARGV[0],
+                NEW.id
+            );
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Items
+CREATE TRIGGER search_items_insert
+AFTER INSERT ON Items
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('item');
+
+CREATE TRIGGER search_items_update
+AFTER UPDATE OF content, description, is_deleted ON Items
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('item');
+
+CREATE TRIGGER search_items_delete
+AFTER DELETE ON Items
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('item');
+
+-- Projects
+CREATE TRIGGER search_projects_insert
+AFTER INSERT ON Projects
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('project');
+
+CREATE TRIGGER search_projects_update
+AFTER UPDATE OF name, description, is_deleted ON Projects
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('project');
+
+CREATE TRIGGER search_projects_delete
+AFTER DELETE ON Projects
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('project');
+
+-- Sections
+CREATE TRIGGER search_sections_insert
+AFTER INSERT ON Sections
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('section');
+
+CREATE TRIGGER search_sections_update
+AFTER UPDATE OF name, description, is_deleted ON Sections
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('section');
+
+CREATE TRIGGER search_sections_delete
+AFTER DELETE ON Sections
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('section');
+
+-- Labels
+CREATE TRIGGER search_labels_insert
+AFTER INSERT ON Labels
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('label');
+
+CREATE TRIGGER search_labels_update
+AFTER UPDATE OF name, is_deleted ON Labels
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('label');
+
+CREATE TRIGGER search_labels_delete
+AFTER DELETE ON Labels
+FOR EACH ROW EXECUTE FUNCTION search_index_sync('label');
@@ -0,0 +1,4 @@
+-- =====================================================
+-- 回滚保存的过滤器
+-- =====================================================
+DROP TABLE IF EXISTS Filters;
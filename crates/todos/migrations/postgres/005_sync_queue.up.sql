@@ -0,0 +1,12 @@
+-- =====================================================
+-- Todoist 同步命令队列
+-- 两张表建好后一直没有使用，这里改成与实体一致的列名 / 表名：
+-- Queue.uuid → Queue.id（命令的 uuid），CurTempIds → cur_temp_ids（临时 ID → 服务器 ID）
+-- =====================================================
+ALTER TABLE Queue RENAME COLUMN uuid TO id;
+
+ALTER TABLE CurTempIds RENAME TO cur_temp_ids;
+
+CREATE INDEX IF NOT EXISTS idx_queue_date_added ON Queue (date_added);
+
+CREATE INDEX IF NOT EXISTS idx_cur_temp_ids_temp_id ON cur_temp_ids (temp_id);
@@ -0,0 +1,8 @@
+-- =====================================================
+-- 回滚项目归档列
+-- is_archived 中 NULL → FALSE 的统一无需恢复
+-- =====================================================
+DROP INDEX IF EXISTS idx_projects_is_archived;
+
+ALTER TABLE Projects DROP COLUMN archived_at;
+ALTER TABLE Items DROP COLUMN archived_at;
@@ -0,0 +1,11 @@
+-- =====================================================
+-- 项目归档
+-- 记录归档时间，取消归档时整棵项目树（分区、未完成任务）按同一时间一起还原
+-- =====================================================
+ALTER TABLE Projects ADD COLUMN archived_at TIMESTAMP;
+ALTER TABLE Items ADD COLUMN archived_at TIMESTAMP;
+
+-- 旧数据中 is_archived 可能为 NULL，统一为 FALSE
+UPDATE Projects SET is_archived = FALSE WHERE is_archived IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_projects_is_archived ON Projects(is_archived);
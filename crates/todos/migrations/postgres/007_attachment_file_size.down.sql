@@ -0,0 +1,6 @@
+-- =====================================================
+-- 回滚附件大小的非空约束
+-- NULL → 0 的统一无需恢复
+-- =====================================================
+ALTER TABLE Attachments ALTER COLUMN file_size DROP NOT NULL;
+ALTER TABLE Attachments ALTER COLUMN file_size DROP DEFAULT;
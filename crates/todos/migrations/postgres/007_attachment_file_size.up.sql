@@ -0,0 +1,7 @@
+-- =====================================================
+-- 附件大小改为非空整数
+-- PostgreSQL 中 file_size 已是 BIGINT，只需把旧数据中的 NULL 统一为 0（与 SQLite 一致）
+-- =====================================================
+UPDATE Attachments SET file_size = 0 WHERE file_size IS NULL;
+ALTER TABLE Attachments ALTER COLUMN file_size SET DEFAULT 0;
+ALTER TABLE Attachments ALTER COLUMN file_size SET NOT NULL;
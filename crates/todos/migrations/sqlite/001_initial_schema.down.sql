@@ -0,0 +1,25 @@
+-- =====================================================
+-- 回滚初始结构：删除全部表（索引随表一起删除）
+-- =====================================================
+DROP TRIGGER IF EXISTS after_insert_item;
+DROP TRIGGER IF EXISTS after_update_content_item;
+DROP TRIGGER IF EXISTS after_update_description_item;
+DROP TRIGGER IF EXISTS after_update_due_item;
+DROP TRIGGER IF EXISTS after_update_priority_item;
+DROP TRIGGER IF EXISTS after_update_labels_item;
+DROP TRIGGER IF EXISTS after_update_pinned_item;
+DROP TRIGGER IF EXISTS after_update_checked_item;
+DROP TRIGGER IF EXISTS after_update_section_item;
+DROP TRIGGER IF EXISTS after_update_project_item;
+
+DROP TABLE IF EXISTS item_labels;
+DROP TABLE IF EXISTS Sources;
+DROP TABLE IF EXISTS OEvents;
+DROP TABLE IF EXISTS Attachments;
+DROP TABLE IF EXISTS CurTempIds;
+DROP TABLE IF EXISTS Queue;
+DROP TABLE IF EXISTS Reminders;
+DROP TABLE IF EXISTS Items;
+DROP TABLE IF EXISTS Sections;
+DROP TABLE IF EXISTS Projects;
+DROP TABLE IF EXISTS Labels;
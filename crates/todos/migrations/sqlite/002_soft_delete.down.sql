@@ -0,0 +1,12 @@
+-- =====================================================
+-- 回滚回收站（软删除）列
+-- is_deleted 中 NULL → 0 的统一无需恢复
+-- =====================================================
+DROP INDEX IF EXISTS idx_items_is_deleted;
+DROP INDEX IF EXISTS idx_projects_is_deleted;
+DROP INDEX IF EXISTS idx_sections_is_deleted;
+
+ALTER TABLE Items DROP COLUMN deleted_at;
+ALTER TABLE Projects DROP COLUMN deleted_at;
+ALTER TABLE Sections DROP COLUMN deleted_at;
+ALTER TABLE Labels DROP COLUMN deleted_at;
@@ -0,0 +1,17 @@
+-- =====================================================
+-- 回滚全文搜索索引
+-- =====================================================
+DROP TRIGGER IF EXISTS search_items_insert;
+DROP TRIGGER IF EXISTS search_items_update;
+DROP TRIGGER IF EXISTS search_items_delete;
+DROP TRIGGER IF EXISTS search_projects_insert;
+DROP TRIGGER IF EXISTS search_projects_update;
+DROP TRIGGER IF EXISTS search_projects_delete;
+DROP TRIGGER IF EXISTS search_sections_insert;
+DROP TRIGGER IF EXISTS search_sections_update;
+DROP TRIGGER IF EXISTS search_sections_delete;
+DROP TRIGGER IF EXISTS search_labels_insert;
+DROP TRIGGER IF EXISTS search_labels_update;
+DROP TRIGGER IF EXISTS search_labels_delete;
+
+DROP TABLE IF EXISTS search_index;
@@ -0,0 +1,143 @@
+-- =====================================================
+-- 全文搜索（FTS5）
+-- 任务内容 / 描述与项目、分区、标签名称放在同一个索引中，统一排序。
+-- trigram 分词支持中文等无空格文本的子串匹配（查询词至少 3 个字符）。
+-- 只索引未删除的对象：移入回收站时移出索引，恢复时重新加入。
+-- =====================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    title,
+    body,
+    object_type UNINDEXED,
+    object_id UNINDEXED,
+    tokenize = 'trigram'
+);
+
+-- 回填已有数据
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT content, COALESCE(description, ''), 'item', id FROM Items WHERE COALESCE(is_deleted, 0) = 0;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT name, COALESCE(description, ''), 'project', id FROM Projects WHERE COALESCE(is_deleted, 0) = 0;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(name, ''), COALESCE(description, ''), 'section', id FROM Sections WHERE COALESCE(is_deleted, 0) = 0;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(name, ''), '', 'label', id FROM Labels WHERE COALESCE(is_deleted, 0) = 0;
+
+-- Items
+CREATE TRIGGER IF NOT EXISTS search_items_insert
+AFTER
+INSERT
+    ON Items
+    WHEN COALESCE(NEW.is_deleted, 0) = 0 BEGIN
+INSERT INTO search_index (title, body, object_type, object_id)
+VALUES (NEW.content, COALESCE(NEW.description, ''), 'item', NEW.id);
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_items_update
+AFTER
+UPDATE OF content, description, is_deleted
+    ON Items FOR EACH ROW BEGIN
+DELETE FROM search_index WHERE object_type = 'item' AND object_id = OLD.id;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT NEW.content, COALESCE(NEW.description, ''), 'item', NEW.id
+WHERE COALESCE(NEW.is_deleted, 0) = 0;
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_items_delete
+AFTER
+DELETE
+    ON Items BEGIN
+DELETE FROM search_index WHERE object_type = 'item' AND object_id = OLD.id;
+
+END;
+
+-- Projects
+CREATE TRIGGER IF NOT EXISTS search_projects_insert
+AFTER
+INSERT
+    ON Projects
+    WHEN COALESCE(NEW.is_deleted, 0) = 0 BEGIN
+INSERT INTO search_index (title, body, object_type, object_id)
+VALUES (NEW.name, COALESCE(NEW.description, ''), 'project', NEW.id);
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_projects_update
+AFTER
+UPDATE OF name, description, is_deleted
+    ON Projects FOR EACH ROW BEGIN
+DELETE FROM search_index WHERE object_type = 'project' AND object_id = OLD.id;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT NEW.name, COALESCE(NEW.description, ''), 'project', NEW.id
+WHERE COALESCE(NEW.is_deleted, 0) = 0;
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_projects_delete
+AFTER
+DELETE
+    ON Projects BEGIN
+DELETE FROM search_index WHERE object_type = 'project' AND object_id = OLD.id;
+
+END;
+
+-- Sections
+CREATE TRIGGER IF NOT EXISTS search_sections_insert
+AFTER
+INSERT
+    ON Sections
+    WHEN COALESCE(NEW.is_deleted, 0) = 0 BEGIN
+INSERT INTO search_index (title, body, object_type, object_id)
+VALUES (COALESCE(NEW.name, ''), COALESCE(NEW.description, ''), 'section', NEW.id);
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_sections_update
+AFTER
+UPDATE OF name, description, is_deleted
+    ON Sections FOR EACH ROW BEGIN
+DELETE FROM search_index WHERE object_type = 'section' AND object_id = OLD.id;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(NEW.name, ''), COALESCE(NEW.description, ''), 'section', NEW.id
+WHERE COALESCE(NEW.is_deleted, 0) = 0;
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_sections_delete
+AFTER
+DELETE
+    ON Sections BEGIN
+DELETE FROM search_index WHERE object_type = 'section' AND object_id = OLD.id;
+
+END;
+
+-- Labels
+CREATE TRIGGER IF NOT EXISTS search_labels_insert
+AFTER
+INSERT
+    ON Labels
+    WHEN COALESCE(NEW.is_deleted, 0) = 0 BEGIN
+INSERT INTO search_index (title, body, object_type, object_id)
+VALUES (COALESCE(NEW.name, ''), '', 'label', NEW.id);
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_labels_update
+AFTER
+UPDATE OF name, is_deleted
+    ON Labels FOR EACH ROW BEGIN
+DELETE FROM search_index WHERE object_type = 'label' AND object_id = OLD.id;
+INSERT INTO search_index (title, body, object_type, object_id)
+SELECT COALESCE(NEW.name, ''), '', 'label', NEW.id
+WHERE COALESCE(NEW.is_deleted, 0) = 0;
+
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_labels_delete
+AFTER
+DELETE
+    ON Labels BEGIN
+DELETE FROM search_index WHERE object_type = 'label' AND object_id = OLD.id;
+
+END;
@@ -0,0 +1,12 @@
+-- =====================================================
+-- 保存的过滤器
+-- query 为过滤表达式（如 `(today | overdue) & p1 & #work`），在内存中求值
+-- =====================================================
+CREATE TABLE IF NOT EXISTS Filters (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    color TEXT,
+    item_order INTEGER NOT NULL DEFAULT 0,
+    is_favorite BOOLEAN NOT NULL DEFAULT 0
+);
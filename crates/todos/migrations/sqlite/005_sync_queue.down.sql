@@ -0,0 +1,9 @@
+-- =====================================================
+-- 回滚 Todoist 同步命令队列的表名 / 列名
+-- =====================================================
+DROP INDEX IF EXISTS idx_queue_date_added;
+DROP INDEX IF EXISTS idx_cur_temp_ids_temp_id;
+
+ALTER TABLE cur_temp_ids RENAME TO CurTempIds;
+
+ALTER TABLE Queue RENAME COLUMN id TO uuid;
@@ -0,0 +1,21 @@
+-- =====================================================
+-- 回滚附件大小列类型（整数 → TEXT）
+-- =====================================================
+CREATE TABLE Attachments_old (
+    id TEXT PRIMARY KEY,
+    item_id TEXT,
+    file_type TEXT,
+    file_name TEXT,
+    file_size TEXT,
+    file_path TEXT,
+    FOREIGN KEY (item_id) REFERENCES Items (id) ON DELETE CASCADE
+);
+
+INSERT INTO Attachments_old (id, item_id, file_type, file_name, file_size, file_path)
+SELECT id, item_id, file_type, file_name, CAST(file_size AS TEXT), file_path
+FROM Attachments;
+
+DROP TABLE Attachments;
+ALTER TABLE Attachments_old RENAME TO Attachments;
+
+CREATE INDEX IF NOT EXISTS idx_attachments_item_id ON Attachments(item_id);
@@ -0,0 +1,23 @@
+-- =====================================================
+-- 附件大小改为整数列
+-- 初始表结构中 file_size 为 TEXT，与实体的 i64 及 PostgreSQL 的 BIGINT 不一致；
+-- SQLite 不能修改列类型，重建表并把旧值转换为整数（NULL 统一为 0）
+-- =====================================================
+CREATE TABLE Attachments_new (
+    id TEXT PRIMARY KEY,
+    item_id TEXT,
+    file_type TEXT,
+    file_name TEXT,
+    file_size INTEGER NOT NULL DEFAULT 0,
+    file_path TEXT,
+    FOREIGN KEY (item_id) REFERENCES Items (id) ON DELETE CASCADE
+);
+
+INSERT INTO Attachments_new (id, item_id, file_type, file_name, file_size, file_path)
+SELECT id, item_id, file_type, file_name, COALESCE(CAST(file_size AS INTEGER), 0), file_path
+FROM Attachments;
+
+DROP TABLE Attachments;
+ALTER TABLE Attachments_new RENAME TO Attachments;
+
+CREATE INDEX IF NOT EXISTS idx_attachments_item_id ON Attachments(item_id);
@@ -0,0 +1,384 @@
+//! 数据库备份
+//!
+//! 用 `VACUUM INTO` 把正在使用的 SQLite 数据库写成一份一致的快照（WAL 模式下不阻塞写入），
+//! 快照放在数据库旁的 `backups/` 目录，文件名记录时间和原因：`20261017-093000123-startup.sqlite`。
+//! 启动、定时和每次迁移前各备份一次，按「每天一份、每周一份」轮换。
+//!
+//! 恢复不直接覆盖正在使用的文件：先校验快照并备份当前数据库，再把快照复制为 `<db>.restore`，
+//! 下次启动、连接数据库之前替换。
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{Datelike, Duration, Local, NaiveDateTime};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement, Value};
+
+use super::patch::PatchManager;
+use crate::error::TodoError;
+
+const SNAPSHOT_EXTENSION: &str = "sqlite";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+/// 备份的触发原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackupReason {
+    Startup,
+    Scheduled,
+    /// 应用迁移之前
+    PreMigration,
+    /// 恢复其他快照之前
+    PreRestore,
+}
+
+impl BackupReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Startup => "startup",
+            Self::Scheduled => "scheduled",
+            Self::PreMigration => "pre-migration",
+            Self::PreRestore => "pre-restore",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Startup, Self::Scheduled, Self::PreMigration, Self::PreRestore]
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+    }
+
+    /// 迁移 / 恢复前的安全快照不参与按天、按周轮换，单独保留最近的几份
+    pub fn is_safety(&self) -> bool {
+        matches!(self, Self::PreMigration | Self::PreRestore)
+    }
+}
+
+/// 轮换策略：保留最近 `daily` 天每天最新的一份、最近 `weekly` 周每周最新的一份
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupPolicy {
+    pub daily: u32,
+    pub weekly: u32,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self { daily: 7, weekly: 4 }
+    }
+}
+
+/// 一份快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupSnapshot {
+    pub path: PathBuf,
+    pub created_at: NaiveDateTime,
+    pub reason: BackupReason,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 未删除的任务数；快照无法读取时为 None
+    pub item_count: Option<u64>,
+    /// 快照的数据库版本
+    pub schema_version: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupManager {
+    db: Arc<DatabaseConnection>,
+    db_path: PathBuf,
+    dir: PathBuf,
+    policy: BackupPolicy,
+}
+
+impl BackupManager {
+    /// `db_path` 是正在使用的数据库文件，快照放在它旁边的 `backups/` 目录
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        db_path: impl Into<PathBuf>,
+        policy: BackupPolicy,
+    ) -> Self {
+        let db_path = db_path.into();
+        let dir = db_path
+            .parent()
+            .map(|parent| parent.join("backups"))
+            .unwrap_or_else(|| PathBuf::from("backups"));
+        Self { db, db_path, dir, policy }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn policy(&self) -> BackupPolicy {
+        self.policy
+    }
+
+    /// 写一份快照
+    pub async fn create(&self, reason: BackupReason) -> Result<BackupSnapshot, TodoError> {
+        if self.db.get_database_backend() != DbBackend::Sqlite {
+            return Err(TodoError::ConfigError("只有 SQLite 数据库支持备份".to_string()));
+        }
+        fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+
+        // 同一毫秒内的多次备份顺延时间，文件名保持唯一
+        let mut created_at = Local::now().naive_local();
+        let path = loop {
+            let path = self.dir.join(snapshot_file_name(created_at, reason));
+            if !path.exists() {
+                break path;
+            }
+            created_at += Duration::milliseconds(1);
+        };
+
+        // 先写到临时文件，完成后再改名，中途退出不会留下不完整的快照
+        let partial = path.with_extension("partial");
+        if partial.exists() {
+            fs::remove_file(&partial).map_err(|e| io_error(&partial, e))?;
+        }
+        self.db
+            .execute(Statement::from_sql_and_values(DbBackend::Sqlite, "VACUUM INTO ?", [
+                Value::from(partial.to_string_lossy().into_owned()),
+            ]))
+            .await?;
+        fs::rename(&partial, &path).map_err(|e| io_error(&path, e))?;
+
+        let size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or_default();
+        tracing::info!("Database backup written: {} ({} bytes)", path.display(), size);
+        Ok(BackupSnapshot {
+            path,
+            created_at,
+            reason,
+            size,
+            item_count: None,
+            schema_version: None,
+        })
+    }
+
+    /// 写一份快照并按策略轮换
+    pub async fn backup_and_rotate(
+        &self,
+        reason: BackupReason,
+    ) -> Result<BackupSnapshot, TodoError> {
+        let snapshot = self.create(reason).await?;
+        let removed = self.rotate()?;
+        if !removed.is_empty() {
+            tracing::info!("Removed {} old database backups", removed.len());
+        }
+        Ok(snapshot)
+    }
+
+    /// 所有快照（新的在前），附带任务数和数据库版本
+    pub async fn list(&self) -> Result<Vec<BackupSnapshot>, TodoError> {
+        let mut snapshots = self.snapshot_files()?;
+        for snapshot in &mut snapshots {
+            match inspect_snapshot(&snapshot.path).await {
+                Ok((item_count, schema_version)) => {
+                    snapshot.item_count = Some(item_count);
+                    snapshot.schema_version = Some(schema_version);
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to read backup {}: {}", snapshot.path.display(), e)
+                },
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// 按策略删除多余的快照，返回删除的文件；最新的一份总是保留
+    pub fn rotate(&self) -> Result<Vec<PathBuf>, TodoError> {
+        let snapshots = self.snapshot_files()?;
+        let keep = retained(&snapshots, self.policy);
+        let mut removed = Vec::new();
+        for (snapshot, keep) in snapshots.into_iter().zip(keep) {
+            if keep {
+                continue;
+            }
+            fs::remove_file(&snapshot.path).map_err(|e| io_error(&snapshot.path, e))?;
+            removed.push(snapshot.path);
+        }
+        Ok(removed)
+    }
+
+    /// 准备从快照恢复：校验快照、备份当前数据库，再把快照复制为待替换文件
+    ///
+    /// 正在使用的数据库不会被改动，下次启动时由 [`apply_pending_restore`] 替换。
+    pub async fn stage_restore(&self, snapshot: &Path) -> Result<PathBuf, TodoError> {
+        verify_snapshot(snapshot).await?;
+        self.backup_and_rotate(BackupReason::PreRestore).await?;
+
+        let staged = pending_restore_path(&self.db_path);
+        let partial = staged.with_extension("restore.partial");
+        fs::copy(snapshot, &partial).map_err(|e| io_error(&partial, e))?;
+        fs::rename(&partial, &staged).map_err(|e| io_error(&staged, e))?;
+        tracing::info!("Restore of {} staged for next start", snapshot.display());
+        Ok(staged)
+    }
+
+    /// 目录中能识别的快照（新的在前），不打开文件
+    fn snapshot_files(&self) -> Result<Vec<BackupSnapshot>, TodoError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let entries = fs::read_dir(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let mut snapshots: Vec<BackupSnapshot> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let path = entry.path();
+                let (created_at, reason) = parse_snapshot_name(&path)?;
+                let size = entry.metadata().map(|meta| meta.len()).unwrap_or_default();
+                Some(BackupSnapshot {
+                    path,
+                    created_at,
+                    reason,
+                    size,
+                    item_count: None,
+                    schema_version: None,
+                })
+            })
+            .collect();
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+        Ok(snapshots)
+    }
+}
+
+/// 等待下次启动时替换数据库的快照副本
+pub fn pending_restore_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".restore");
+    PathBuf::from(path)
+}
+
+/// 连接数据库之前调用：有待恢复的快照时替换数据库文件，返回是否替换
+///
+/// 当前数据库在准备恢复时已经备份，旧的 WAL / SHM 文件属于被替换的数据库，一并删除。
+pub fn apply_pending_restore(db_path: &Path) -> Result<bool, TodoError> {
+    let staged = pending_restore_path(db_path);
+    if !staged.exists() {
+        return Ok(false);
+    }
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = PathBuf::from(sidecar);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar).map_err(|e| io_error(&sidecar, e))?;
+        }
+    }
+    fs::rename(&staged, db_path).map_err(|e| io_error(db_path, e))?;
+    tracing::info!("Database restored from backup: {}", db_path.display());
+    Ok(true)
+}
+
+fn snapshot_file_name(created_at: NaiveDateTime, reason: BackupReason) -> String {
+    format!("{}-{}.{}", created_at.format(TIMESTAMP_FORMAT), reason.as_str(), SNAPSHOT_EXTENSION)
+}
+
+fn parse_snapshot_name(path: &Path) -> Option<(NaiveDateTime, BackupReason)> {
+    if path.extension()? != SNAPSHOT_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    // 时间戳固定 18 个字符：YYYYMMDD-HHMMSSmmm
+    let (timestamp, reason) = stem.split_at_checked(18)?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let reason = BackupReason::parse(reason.strip_prefix('-')?)?;
+    Some((created_at, reason))
+}
+
+/// 标记要保留的快照（与 `snapshots` 一一对应，`snapshots` 按时间从新到旧）
+fn retained(snapshots: &[BackupSnapshot], policy: BackupPolicy) -> Vec<bool> {
+    let mut keep = vec![false; snapshots.len()];
+    let mut days = Vec::new();
+    let mut weeks = Vec::new();
+    let mut safety = 0;
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        if snapshot.reason.is_safety() {
+            if safety < policy.daily.max(1) {
+                safety += 1;
+                keep[index] = true;
+            }
+            continue;
+        }
+        let day = snapshot.created_at.date();
+        if !days.contains(&day) && days.len() < policy.daily as usize {
+            days.push(day);
+            keep[index] = true;
+        }
+        let week = (day.iso_week().year(), day.iso_week().week());
+        if !weeks.contains(&week) && weeks.len() < policy.weekly as usize {
+            weeks.push(week);
+            keep[index] = true;
+        }
+    }
+    if let Some(newest) = keep.first_mut() {
+        *newest = true;
+    }
+    keep
+}
+
+/// 以只读方式打开快照
+async fn open_snapshot(path: &Path) -> Result<DatabaseConnection, TodoError> {
+    if !path.is_file() {
+        return Err(TodoError::NotFound(format!("备份 {}", path.display())));
+    }
+    Ok(Database::connect(format!("sqlite://{}?mode=ro", path.display())).await?)
+}
+
+/// 快照中未删除的任务数和数据库版本
+async fn inspect_snapshot(path: &Path) -> Result<(u64, i32), TodoError> {
+    let conn = open_snapshot(path).await?;
+    let result = snapshot_summary(&conn).await;
+    let _ = conn.close().await;
+    result
+}
+
+async fn snapshot_summary(conn: &DatabaseConnection) -> Result<(u64, i32), TodoError> {
+    let schema_version =
+        PatchManager::new(Arc::new(conn.clone())).get_current_version_if_exists().await?;
+    // 加入回收站之前的快照没有 is_deleted 列
+    let mut row = None;
+    for sql in [
+        "SELECT COUNT(*) AS count FROM items WHERE is_deleted = 0",
+        "SELECT COUNT(*) AS count FROM items",
+    ] {
+        if let Ok(found) = conn.query_one(Statement::from_string(DbBackend::Sqlite, sql)).await {
+            row = found;
+            break;
+        }
+    }
+    let count = match row {
+        Some(row) => row.try_get::<i64>("", "count")?,
+        None => 0,
+    };
+    Ok((u64::try_from(count).unwrap_or_default(), schema_version))
+}
+
+/// 恢复前的检查：快照完整，且版本不比程序新
+async fn verify_snapshot(path: &Path) -> Result<(), TodoError> {
+    let conn = open_snapshot(path).await?;
+    let result = async {
+        let row = conn
+            .query_one(Statement::from_string(DbBackend::Sqlite, "PRAGMA integrity_check"))
+            .await?;
+        let status = match row {
+            Some(row) => row.try_get::<String>("", "integrity_check")?,
+            None => String::new(),
+        };
+        if status != "ok" {
+            return Err(TodoError::ValidationError(format!(
+                "备份 {} 已损坏: {}",
+                path.display(),
+                status
+            )));
+        }
+        PatchManager::new(Arc::new(conn.clone())).check_compatible().await?;
+        Ok(())
+    }
+    .await;
+    let _ = conn.close().await;
+    result
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> TodoError {
+    TodoError::InternalError(format!("备份文件 {} 读写失败: {}", path.display(), error))
+}
@@ -1,9 +1,18 @@
-use std::{path::Path, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use sea_orm::{
     ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, Statement,
 };
 
+use super::{
+    backup::{BackupManager, BackupPolicy, BackupReason, apply_pending_restore},
+    patch::PatchManager,
+};
+
 pub async fn init_db() -> Result<DatabaseConnection, DbErr> {
     use gconfig::get;
 
@@ -18,8 +27,71 @@ pub async fn init_db() -> Result<DatabaseConnection, DbErr> {
     }
 }
 
+/// 预览迁移用的连接：不应用待恢复的备份、不做迁移前备份，也不创建数据库文件或 schema
+///
+/// 只用来读取 `db_version`，调用方不应通过它写入数据。
+pub async fn init_db_for_preview() -> Result<DatabaseConnection, DbErr> {
+    let db_config = gconfig::get().read().expect("读取配置失败").database().clone();
+    if !db_config.is_sqlite() {
+        return connect_network_db(&db_config).await;
+    }
+
+    let db_path = resolve_db_path(db_config.sqlite_path());
+    // 数据库文件还不存在时所有补丁都待执行，用空的内存数据库预览
+    let url = if Path::new(&db_path).exists() {
+        format!("sqlite://{}?mode=rw", db_path)
+    } else {
+        "sqlite::memory:".to_string()
+    };
+    let mut options = ConnectOptions::new(url);
+    options.max_connections(1).connect_timeout(Duration::from_secs(3)).sqlx_logging(false);
+    Database::connect(options).await
+}
+
+/// 读取回收站保留天数配置（0 表示永久保留）
+pub fn trash_retention_days() -> u32 {
+    gconfig::get().read().expect("读取配置失败").database().trash_retention_days()
+}
+
+/// 写入日志的位置：SQLite 数据库文件旁边，其他数据库放在数据目录中
+pub fn write_journal_path() -> PathBuf {
+    let db_config = gconfig::get().read().expect("读取配置失败").database().clone();
+    if db_config.is_sqlite() {
+        PathBuf::from(format!("{}.pending.jsonl", resolve_db_path(db_config.sqlite_path())))
+    } else {
+        crate::utils::get_project_dirs().0.join("pending_writes.jsonl")
+    }
+}
+
+/// 数据库备份；只有 SQLite 支持，其他数据库返回 None
+pub fn backup_manager(db: Arc<DatabaseConnection>) -> Option<BackupManager> {
+    let db_config = gconfig::get().read().expect("读取配置失败").database().clone();
+    if !db_config.is_sqlite() {
+        return None;
+    }
+    let db_path = resolve_db_path(db_config.sqlite_path());
+    Some(BackupManager::new(db, db_path, backup_policy(&db_config)))
+}
+
+/// 运行期间定时备份的间隔小时数（0 表示不定时备份）
+pub fn backup_interval_hours() -> u32 {
+    gconfig::get().read().expect("读取配置失败").database().backup_interval_hours()
+}
+
+fn backup_policy(db_config: &gconfig::DatabaseConfig) -> BackupPolicy {
+    BackupPolicy { daily: db_config.backup_daily_keep(), weekly: db_config.backup_weekly_keep() }
+}
+
 async fn init_sqlite_db(db_config: &gconfig::DatabaseConfig) -> Result<DatabaseConnection, DbErr> {
     let db_path = resolve_db_path(db_config.sqlite_path());
+
+    // 上次准备好的恢复在连接之前替换数据库文件
+    match apply_pending_restore(Path::new(&db_path)) {
+        Ok(true) => tracing::info!("Applied pending database restore"),
+        Ok(false) => {},
+        Err(e) => tracing::error!("Failed to apply pending database restore: {}", e),
+    }
+
     // 🚀 修复 (2026-05-17)：移除 cache=shared 减少锁竞争
     // cache=shared 可能导致多个连接间的锁竞争，特别是在高并发情况下
     let base_url = format!("sqlite://{}?mode=rwc", db_path);
@@ -73,9 +145,10 @@ async fn init_sqlite_db(db_config: &gconfig::DatabaseConfig) -> Result<DatabaseC
 
     // 注意：数据库表的创建由 PatchManager::apply_patches() 统一管理
     // 我们只负责连接池创建、PRAGMA 设置和连接验证
-    // 这样可以避免 setup.sql 被执行两次（一次在这里，一次在 PatchManager 中）
+    // 这样可以避免初始迁移被执行两次（一次在这里，一次在 PatchManager 中）
 
     db.ping().await?;
+    backup_before_migration(&db, &db_path, backup_policy(db_config)).await?;
     tracing::info!(
         "SQLite database connection successful with WAL mode: {} (pool: max={}, min=2, \
          acquire_timeout=10s)",
@@ -85,7 +158,50 @@ async fn init_sqlite_db(db_config: &gconfig::DatabaseConfig) -> Result<DatabaseC
     Ok(db)
 }
 
+/// 有待应用的迁移时先备份；备份失败则不迁移，避免升级出错后无法回退
+async fn backup_before_migration(
+    db: &DatabaseConnection,
+    db_path: &str,
+    policy: BackupPolicy,
+) -> Result<(), DbErr> {
+    let db = Arc::new(db.clone());
+    let pending = PatchManager::new(db.clone()).dry_run().await?;
+    // 全新数据库从版本 1 开始，没有需要保留的数据
+    if pending.first().is_none_or(|step| step.version <= 1) {
+        return Ok(());
+    }
+    BackupManager::new(db, db_path, policy)
+        .backup_and_rotate(BackupReason::PreMigration)
+        .await
+        .map(|_| ())
+        .map_err(|e| DbErr::Custom(format!("迁移前备份数据库失败，已停止迁移: {}", e)))
+}
+
+/// PostgreSQL：表建在配置的 schema 中（默认 public），不存在时创建
 async fn init_network_db(db_config: &gconfig::DatabaseConfig) -> Result<DatabaseConnection, DbErr> {
+    let db = connect_network_db(db_config).await?;
+    let schema = db_config.schema().unwrap_or("public");
+    if db.get_database_backend() == DbBackend::Postgres {
+        db.execute_unprepared(&format!(
+            "CREATE SCHEMA IF NOT EXISTS \"{}\"",
+            schema.replace('"', "\"\"")
+        ))
+        .await?;
+    }
+    tracing::info!(
+        "Database connection successful: {}@{}:{}/{} (schema {})",
+        db_config.user().unwrap_or("postgres"),
+        db_config.host().unwrap_or("localhost"),
+        db_config.port().unwrap_or(5432),
+        db_config.database(),
+        schema
+    );
+    Ok(db)
+}
+
+async fn connect_network_db(
+    db_config: &gconfig::DatabaseConfig,
+) -> Result<DatabaseConnection, DbErr> {
     let host = db_config.host().unwrap_or("localhost");
     let port = db_config.port().unwrap_or(5432);
     let user = db_config.user().unwrap_or("postgres");
@@ -93,16 +209,8 @@ async fn init_network_db(db_config: &gconfig::DatabaseConfig) -> Result<Database
     let database = db_config.database();
     let schema = db_config.schema().unwrap_or("public");
 
-    let base_url = format!(
-        "{}://{}:{}@{}:{}/{}?schema={}",
-        db_config.db_type(),
-        user,
-        password,
-        host,
-        port,
-        database,
-        schema
-    );
+    let base_url =
+        format!("{}://{}:{}@{}:{}/{}", db_config.db_type(), user, password, host, port, database);
 
     let mut options = ConnectOptions::new(base_url);
     let pool_size = db_config.pool_size();
@@ -114,11 +222,11 @@ async fn init_network_db(db_config: &gconfig::DatabaseConfig) -> Result<Database
         .acquire_timeout(Duration::from_secs(10))
         .idle_timeout(Duration::from_secs(600))
         .max_lifetime(Duration::from_secs(3600))
+        .set_schema_search_path(schema)
         .sqlx_logging(true);
 
     let db = Database::connect(options).await?;
     db.ping().await?;
-    tracing::info!("Database connection successful: {}@{}:{}/{}", user, host, port, database);
     Ok(db)
 }
 
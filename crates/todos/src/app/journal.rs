@@ -0,0 +1,370 @@
+//! 写入日志：保存还没有落盘的乐观更新
+//!
+//! 界面先修改内存中的数据，再异步写数据库；重试耗尽后把这次写入追加到日志文件
+//! （JSON Lines，每行一条），下次连接成功或启动时按加入顺序重放：
+//! - 可重试的错误（数据库忙、超时）停止重放，剩余的写入保持等待，后面的修改不会先于前面的落盘；
+//! - 其他错误（如任务已被删除）或多次重放仍失败的写入标记为失败，留给用户重试或放弃。
+//!
+//! 每次修改后整体重写文件（先写临时文件再改名），进程随时退出都不会留下半行。
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Store, entity::ItemModel, error::TodoError};
+
+/// 同一条写入最多重放的次数，超过后标记为失败
+const MAX_ATTEMPTS: u32 = 5;
+
+/// 一次写入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalOp {
+    /// 新建任务；`temp_id` 是界面中使用的临时 ID，写入后换成数据库分配的 ID
+    AddItem {
+        temp_id: Option<String>,
+        item: ItemModel,
+    },
+    UpdateItem {
+        item: ItemModel,
+    },
+}
+
+impl JournalOp {
+    pub fn item(&self) -> &ItemModel {
+        match self {
+            Self::AddItem { item, .. } | Self::UpdateItem { item } => item,
+        }
+    }
+
+    /// 界面中显示的描述
+    pub fn describe(&self) -> String {
+        match self {
+            Self::AddItem { item, .. } => format!("添加任务「{}」", item.content),
+            Self::UpdateItem { item } => format!("修改任务「{}」", item.content),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalStatus {
+    /// 等待重放
+    Pending,
+    /// 重放失败，等待用户重试或放弃
+    Failed,
+}
+
+/// 日志中的一条写入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub op: JournalOp,
+    pub status: JournalStatus,
+    pub queued_at: NaiveDateTime,
+    /// 已重放的次数
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+/// 重放成功的写入
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayedWrite {
+    /// 新建任务时界面中的临时 ID
+    pub temp_id: Option<String>,
+    /// 数据库中的任务
+    pub item: ItemModel,
+}
+
+/// 一次重放的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub saved: Vec<ReplayedWrite>,
+    /// 这次重放中标记为失败的写入
+    pub failed: Vec<JournalEntry>,
+    /// 仍在等待的写入数
+    pub pending: usize,
+    /// 使重放停止的可重试错误
+    pub error: Option<String>,
+}
+
+/// 持久化的写入日志，可在线程间共享（克隆后指向同一份数据）
+#[derive(Clone, Debug)]
+pub struct WriteJournal {
+    /// 日志文件；为 None 时只保存在内存中
+    path: Option<PathBuf>,
+    entries: Arc<Mutex<Vec<JournalEntry>>>,
+    replaying: Arc<AtomicBool>,
+}
+
+impl WriteJournal {
+    /// 打开日志文件，不存在时为空；无法解析的行会被跳过
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, TodoError> {
+        let path = path.into();
+        let mut entries = Vec::new();
+        if path.exists() {
+            let text = fs::read_to_string(&path).map_err(|e| {
+                TodoError::InternalError(format!("读取写入日志 {} 失败: {}", path.display(), e))
+            })?;
+            for (index, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                match serde_json::from_str::<JournalEntry>(line) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => tracing::warn!(
+                        "Skipping unreadable write journal line {} in {}: {}",
+                        index + 1,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+        if !entries.is_empty() {
+            tracing::info!("Write journal {} has {} entries", path.display(), entries.len());
+        }
+        Ok(Self {
+            path: Some(path),
+            entries: Arc::new(Mutex::new(entries)),
+            replaying: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// 只保存在内存中的日志，用于日志文件无法打开时；本次运行内仍可重放，退出后丢失
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            replaying: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 日志文件，内存日志为 None
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 全部写入（按加入顺序）
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.lock().clone()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.lock().iter().filter(|entry| entry.status == JournalStatus::Pending).count()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.lock().iter().filter(|entry| entry.status == JournalStatus::Failed).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// 任务是否有等待重放的写入；之后对它的修改也要排队，不能越过前面的写入
+    pub fn has_pending_for(&self, item_id: &str) -> bool {
+        self.lock().iter().any(|entry| {
+            entry.status == JournalStatus::Pending
+                && match &entry.op {
+                    JournalOp::AddItem { temp_id, .. } => temp_id.as_deref() == Some(item_id),
+                    JournalOp::UpdateItem { item } => item.id == item_id,
+                }
+        })
+    }
+
+    /// 追加一条写入
+    ///
+    /// 修改还在等待新建的任务（临时 ID）时直接合并到新建的写入中。
+    pub fn append(&self, op: JournalOp, error: &TodoError) -> Result<JournalEntry, TodoError> {
+        let mut entries = self.lock();
+        if let JournalOp::UpdateItem { item } = &op
+            && let Some(entry) = entries.iter_mut().find(|entry| {
+                entry.status == JournalStatus::Pending
+                    && matches!(&entry.op, JournalOp::AddItem { temp_id: Some(temp_id), .. }
+                        if *temp_id == item.id)
+            })
+        {
+            if let JournalOp::AddItem { item: pending, .. } = &mut entry.op {
+                *pending = item.clone();
+            }
+            let entry = entry.clone();
+            self.save(&entries)?;
+            return Ok(entry);
+        }
+
+        let entry = JournalEntry {
+            id: Uuid::new_v4().to_string(),
+            op,
+            status: JournalStatus::Pending,
+            queued_at: Utc::now().naive_utc(),
+            attempts: 0,
+            last_error: Some(error.to_string()),
+        };
+        entries.push(entry.clone());
+        self.save(&entries)?;
+        tracing::warn!("Queued {} in write journal: {}", entry.op.describe(), error);
+        Ok(entry)
+    }
+
+    /// 把失败的写入重新设为等待，返回数量
+    pub fn retry_failed(&self) -> Result<usize, TodoError> {
+        let mut entries = self.lock();
+        let mut count = 0;
+        for entry in entries.iter_mut().filter(|entry| entry.status == JournalStatus::Failed) {
+            entry.status = JournalStatus::Pending;
+            entry.attempts = 0;
+            count += 1;
+        }
+        self.save(&entries)?;
+        Ok(count)
+    }
+
+    /// 放弃一条写入
+    pub fn discard(&self, entry_id: &str) -> Result<Option<JournalEntry>, TodoError> {
+        let mut entries = self.lock();
+        let Some(index) = entries.iter().position(|entry| entry.id == entry_id) else {
+            return Ok(None);
+        };
+        let entry = entries.remove(index);
+        self.save(&entries)?;
+        Ok(Some(entry))
+    }
+
+    /// 放弃所有失败的写入
+    pub fn discard_failed(&self) -> Result<Vec<JournalEntry>, TodoError> {
+        let mut entries = self.lock();
+        let (failed, kept) =
+            entries.drain(..).partition(|entry| entry.status == JournalStatus::Failed);
+        *entries = kept;
+        self.save(&entries)?;
+        Ok(failed)
+    }
+
+    /// 按顺序重放等待中的写入；已有重放在进行时直接返回
+    pub async fn replay(&self, store: &Store) -> Result<ReplayReport, TodoError> {
+        let mut report = ReplayReport::default();
+        if self.replaying.swap(true, Ordering::SeqCst) {
+            report.pending = self.pending_count();
+            return Ok(report);
+        }
+        let result = self.replay_pending(store, &mut report).await;
+        self.replaying.store(false, Ordering::SeqCst);
+        result?;
+
+        report.pending = self.pending_count();
+        if !report.saved.is_empty() || !report.failed.is_empty() {
+            tracing::info!(
+                "Write journal replayed: {} saved, {} failed, {} pending",
+                report.saved.len(),
+                report.failed.len(),
+                report.pending
+            );
+        }
+        Ok(report)
+    }
+
+    async fn replay_pending(
+        &self,
+        store: &Store,
+        report: &mut ReplayReport,
+    ) -> Result<(), TodoError> {
+        loop {
+            let next =
+                self.lock().iter().find(|entry| entry.status == JournalStatus::Pending).cloned();
+            let Some(entry) = next else {
+                return Ok(());
+            };
+
+            let result = match &entry.op {
+                JournalOp::AddItem { item, .. } => store.insert_item(item.clone(), true).await,
+                JournalOp::UpdateItem { item } => store.update_item(item.clone(), &item.id).await,
+            };
+            let mut entries = self.lock();
+            let Some(index) = entries.iter().position(|e| e.id == entry.id) else {
+                continue;
+            };
+            match result {
+                Ok(saved) => {
+                    entries.remove(index);
+                    let temp_id = match entry.op {
+                        JournalOp::AddItem { temp_id, .. } => temp_id,
+                        JournalOp::UpdateItem { .. } => None,
+                    };
+                    // 后面的写入中引用临时 ID 的地方换成数据库分配的 ID
+                    if let Some(temp_id) = &temp_id {
+                        for later in entries.iter_mut() {
+                            let item = match &mut later.op {
+                                JournalOp::AddItem { item, .. }
+                                | JournalOp::UpdateItem { item } => item,
+                            };
+                            if item.id == *temp_id {
+                                item.id = saved.id.clone();
+                            }
+                            if item.parent_id.as_ref() == Some(temp_id) {
+                                item.parent_id = Some(saved.id.clone());
+                            }
+                        }
+                    }
+                    self.save(&entries)?;
+                    report.saved.push(ReplayedWrite { temp_id, item: saved });
+                },
+                Err(e) => {
+                    let entry = &mut entries[index];
+                    entry.attempts += 1;
+                    entry.last_error = Some(e.to_string());
+                    let stop = e.is_retryable() && entry.attempts < MAX_ATTEMPTS;
+                    if !stop {
+                        entry.status = JournalStatus::Failed;
+                        report.failed.push(entry.clone());
+                    }
+                    self.save(&entries)?;
+                    if stop {
+                        report.error = Some(e.to_string());
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<JournalEntry>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 整体重写日志文件；没有写入时删除文件
+    fn save(&self, entries: &[JournalEntry]) -> Result<(), TodoError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let io_error = |e: std::io::Error| {
+            TodoError::InternalError(format!("写入日志 {} 保存失败: {}", path.display(), e))
+        };
+        if entries.is_empty() {
+            if path.exists() {
+                fs::remove_file(path).map_err(io_error)?;
+            }
+            return Ok(());
+        }
+
+        let mut text = String::new();
+        for entry in entries {
+            let line = serde_json::to_string(entry)
+                .map_err(|e| TodoError::InternalError(format!("写入日志序列化失败: {}", e)))?;
+            text.push_str(&line);
+            text.push('\n');
+        }
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, text).map_err(io_error)?;
+        fs::rename(&temp, path).map_err(io_error)
+    }
+}
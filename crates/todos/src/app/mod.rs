@@ -1,5 +1,17 @@
+mod backup;
 mod database;
+mod journal;
 mod patch;
 
-pub use database::init_db;
-pub use patch::PatchManager;
+pub use backup::{
+    BackupManager, BackupPolicy, BackupReason, BackupSnapshot, apply_pending_restore,
+    pending_restore_path,
+};
+pub use database::{
+    backup_interval_hours, backup_manager, init_db, init_db_for_preview, trash_retention_days,
+    write_journal_path,
+};
+pub use journal::{
+    JournalEntry, JournalOp, JournalStatus, ReplayReport, ReplayedWrite, WriteJournal,
+};
+pub use patch::{AppliedPatch, Patch, PatchManager, PatchStep};
@@ -1,12 +1,78 @@
+//! 数据库迁移
+//!
+//! 迁移脚本按数据库分目录（`migrations/sqlite/`、`migrations/postgres/`），编译时嵌入：
+//! `NNN_name.up.sql` 升级，`NNN_name.down.sql` 回滚；两个目录的版本号和文件名一一对应，
+//! 按连接的数据库选用。每个补丁在一个事务中执行，成功后把版本号、描述和脚本校验和写入
+//! `db_version`； 启动时数据库版本比程序新、或已应用的补丁脚本被修改过，都拒绝运行。
+
 use std::sync::Arc;
 
-use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement, TransactionTrait,
+    sea_query::{Alias, Expr, Query},
+};
+
+macro_rules! patch {
+    ($backend:expr, $version:expr, $name:literal, $description:expr) => {{
+        let (up, down) = match $backend {
+            DbBackend::Postgres => (
+                include_str!(concat!("../../migrations/postgres/", $name, ".up.sql")),
+                include_str!(concat!("../../migrations/postgres/", $name, ".down.sql")),
+            ),
+            _ => (
+                include_str!(concat!("../../migrations/sqlite/", $name, ".up.sql")),
+                include_str!(concat!("../../migrations/sqlite/", $name, ".down.sql")),
+            ),
+        };
+        Patch { version: $version, description: $description, up, down }
+    }};
+}
 
 #[derive(Debug)]
 pub struct Patch {
     pub version: i32,
     pub description: &'static str,
-    pub sql: &'static str,
+    /// 升级脚本
+    pub up: &'static str,
+    /// 回滚脚本
+    pub down: &'static str,
+}
+
+impl Patch {
+    /// 升级脚本的校验和（FNV-1a 64 位，统一换行符后计算，与检出时的换行设置无关）
+    pub fn checksum(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in self.up.replace("\r\n", "\n").bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        format!("{:016x}", hash)
+    }
+
+    fn step(&self) -> PatchStep {
+        PatchStep {
+            version: self.version,
+            description: self.description,
+            checksum: self.checksum(),
+        }
+    }
+}
+
+/// 待执行的补丁（预览用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchStep {
+    pub version: i32,
+    pub description: &'static str,
+    pub checksum: String,
+}
+
+/// `db_version` 中记录的已应用补丁
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedPatch {
+    pub version: i32,
+    pub description: Option<String>,
+    pub checksum: Option<String>,
+    pub applied_at: Option<String>,
 }
 
 #[derive(Debug)]
@@ -17,147 +83,204 @@ pub struct PatchManager {
 
 impl PatchManager {
     pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        let backend = db.get_database_backend();
         let patches = vec![
-            Patch {
-                version: 1,
-                description: "Initial database schema",
-                sql: include_str!("../../setup.sql"),
-            },
+            patch!(backend, 1, "001_initial_schema", "Initial database schema"),
+            patch!(backend, 2, "002_soft_delete", "Soft delete (trash bin) columns"),
+            patch!(backend, 3, "003_search_index", "Full-text search index (FTS5)"),
+            patch!(backend, 4, "004_filters", "Saved filters"),
+            patch!(backend, 5, "005_sync_queue", "Todoist sync queue"),
+            patch!(backend, 6, "006_archive", "Project archive columns"),
+            patch!(backend, 7, "007_attachment_file_size", "Attachment file size column type"),
             // 未来的补丁将添加在这里
         ];
 
         Self { db, patches }
     }
 
+    pub fn patches(&self) -> &[Patch] {
+        &self.patches
+    }
+
+    /// 程序支持的最新版本
+    pub fn latest_version(&self) -> i32 {
+        self.patches.iter().map(|patch| patch.version).max().unwrap_or(0)
+    }
+
     pub async fn apply_patches(&self) -> Result<(), DbErr> {
         // 确保 db_version 表存在
         self.ensure_version_table().await?;
 
-        // 获取当前版本
-        let current_version = self.get_current_version().await?;
-
-        // 检查是否有需要应用的补丁
-        let mut has_patches_to_apply = false;
-        for patch in &self.patches {
-            if patch.version > current_version {
-                has_patches_to_apply = true;
-                break;
-            }
+        // 获取当前版本，数据库比程序新时拒绝运行
+        let current_version = self.check_compatible().await?;
+        // 已应用的补丁脚本被修改过时拒绝迁移，数据库结构可能与脚本不一致
+        let mismatched = self.verify_checksums().await?;
+        if !mismatched.is_empty() {
+            let versions: Vec<String> = mismatched.iter().map(i32::to_string).collect();
+            return Err(DbErr::Migration(format!(
+                "补丁 {} 在应用后被修改，校验和与数据库记录不一致，已停止迁移",
+                versions.join(", ")
+            )));
         }
 
+        let pending: Vec<&Patch> =
+            self.patches.iter().filter(|patch| patch.version > current_version).collect();
         // 如果没有需要应用的补丁，直接返回
-        if !has_patches_to_apply {
+        if pending.is_empty() {
             return Ok(());
         }
 
-        // 打印当前版本
         tracing::info!("Current database version: {}", current_version);
 
-        // 应用未应用的补丁
-        for patch in &self.patches {
-            if patch.version > current_version {
-                tracing::info!("Applying patch version {}: {}", patch.version, patch.description);
-
-                // 🔧 关键修复：正确分割 SQL 语句，处理 TRIGGER 定义
-                // TRIGGER 定义中包含多个分号，需要作为一个整体执行
-                let statements = Self::split_sql_statements(patch.sql);
-
-                for stmt in statements {
-                    tracing::debug!("Executing patch SQL: {}", stmt);
-                    self.db
-                        .execute(Statement::from_string(sea_orm::DbBackend::Sqlite, stmt))
-                        .await?;
-                }
-
-                // 更新版本
-                self.update_version(patch.version).await?;
-                tracing::info!("Patch version {} applied successfully", patch.version);
-            }
+        for patch in pending {
+            tracing::info!("Applying patch version {}: {}", patch.version, patch.description);
+            let txn = self.db.begin().await?;
+            txn.execute_unprepared(patch.up).await.map_err(|e| patch_error(patch, "升级", e))?;
+            let insert = Query::insert()
+                .into_table(Alias::new("db_version"))
+                .columns([Alias::new("version"), Alias::new("description"), Alias::new("checksum")])
+                .values_panic([
+                    patch.version.into(),
+                    patch.description.into(),
+                    patch.checksum().into(),
+                ])
+                .to_owned();
+            txn.execute(self.backend().build(&insert)).await?;
+            txn.commit().await?;
+            tracing::info!("Patch version {} applied successfully", patch.version);
         }
 
         Ok(())
     }
 
-    /// 🔧 关键方法：正确分割 SQL 语句
-    ///
-    /// 问题：简单的按分号分割会破坏 TRIGGER 定义，因为 TRIGGER 内部包含多个分号
-    /// 解决方案：跟踪括号和 BEGIN/END 块，确保 TRIGGER 定义保持完整
-    fn split_sql_statements(sql: &str) -> Vec<String> {
-        let mut statements = Vec::new();
-        let mut current = String::new();
-        let mut in_trigger_body = false; // 是否在 TRIGGER 的 BEGIN...END 块内
-        let mut paren_depth = 0; // 括号深度
-
-        for line in sql.lines() {
-            let trimmed_line = line.trim();
-
-            // 跳过空行和注释
-            if trimmed_line.is_empty()
-                || trimmed_line.starts_with("--")
-                || trimmed_line.starts_with("/*")
-            {
-                continue;
-            }
+    /// 预览：列出尚未应用的补丁，不修改数据库
+    pub async fn dry_run(&self) -> Result<Vec<PatchStep>, DbErr> {
+        let current_version = self.get_current_version_if_exists().await?;
+        let pending: Vec<PatchStep> = self
+            .patches
+            .iter()
+            .filter(|patch| patch.version > current_version)
+            .map(Patch::step)
+            .collect();
+        for step in &pending {
+            tracing::info!(
+                "Pending patch version {}: {} ({})",
+                step.version,
+                step.description,
+                step.checksum
+            );
+        }
+        Ok(pending)
+    }
 
-            // 检查是否进入 TRIGGER 定义（查找 "BEGIN" 关键字）
-            if trimmed_line.contains("BEGIN") && !trimmed_line.contains("END") {
-                in_trigger_body = true;
-            }
+    /// 预览回滚到 `target_version` 需要执行的补丁（从新到旧）
+    pub async fn rollback_plan(&self, target_version: i32) -> Result<Vec<PatchStep>, DbErr> {
+        let current_version = self.get_current_version_if_exists().await?;
+        Ok(self.rollback_patches(current_version, target_version).map(Patch::step).collect())
+    }
 
-            // 计算括号深度
-            for ch in trimmed_line.chars() {
-                if ch == '(' {
-                    paren_depth += 1;
-                } else if ch == ')' {
-                    paren_depth -= 1;
-                }
-            }
+    /// 回滚到 `target_version`：按从新到旧的顺序执行回滚脚本，每个补丁一个事务
+    ///
+    /// 返回已回滚的版本号。
+    pub async fn rollback_to(&self, target_version: i32) -> Result<Vec<i32>, DbErr> {
+        self.ensure_version_table().await?;
+        let current_version = self.check_compatible().await?;
 
-            // 添加到当前语句
-            if !current.is_empty() {
-                current.push(' ');
-            }
-            current.push_str(trimmed_line);
-
-            // 检查是否结束 TRIGGER 定义（查找 "END;"）
-            if in_trigger_body && trimmed_line == "END;" {
-                in_trigger_body = false;
-                // 移除末尾的分号，添加完整语句
-                if current.ends_with(';') {
-                    current.pop();
-                }
-                statements.push(current.trim().to_string());
-                current = String::new();
-                continue;
-            }
+        let mut rolled_back = Vec::new();
+        for patch in self.rollback_patches(current_version, target_version) {
+            tracing::info!("Rolling back patch version {}: {}", patch.version, patch.description);
+            let txn = self.db.begin().await?;
+            txn.execute_unprepared(patch.down).await.map_err(|e| patch_error(patch, "回滚", e))?;
+            let delete = Query::delete()
+                .from_table(Alias::new("db_version"))
+                .and_where(Expr::col(Alias::new("version")).eq(patch.version))
+                .to_owned();
+            txn.execute(self.backend().build(&delete)).await?;
+            txn.commit().await?;
+            rolled_back.push(patch.version);
+        }
+        Ok(rolled_back)
+    }
 
-            // 如果不在 TRIGGER 体内，且以分号结尾，且括号已闭合，则是一个完整语句
-            if !in_trigger_body && trimmed_line.ends_with(';') && paren_depth == 0 {
-                // 移除末尾的分号
-                if current.ends_with(';') {
-                    current.pop();
-                }
-                statements.push(current.trim().to_string());
-                current = String::new();
-            }
+    /// 数据库版本不能比程序新；返回当前版本
+    pub async fn check_compatible(&self) -> Result<i32, DbErr> {
+        let current_version = self.get_current_version_if_exists().await?;
+        let latest_version = self.latest_version();
+        if current_version > latest_version {
+            return Err(DbErr::Custom(format!(
+                "数据库版本 {} 比程序支持的版本 {} 新，请升级程序后再打开",
+                current_version, latest_version
+            )));
         }
+        Ok(current_version)
+    }
 
-        // 处理剩余的语句（如果没有分号结尾）
-        if !current.is_empty() {
-            let stmt = current.trim().to_string();
-            if !stmt.is_empty() && !stmt.starts_with("--") {
-                statements.push(stmt);
+    /// 已应用的补丁（按版本排序）
+    pub async fn applied_patches(&self) -> Result<Vec<AppliedPatch>, DbErr> {
+        self.ensure_version_table().await?;
+        let rows = self
+            .db
+            .query_all(Statement::from_string(
+                self.backend(),
+                "SELECT version, description, checksum, CAST(applied_at AS TEXT) AS applied_at \
+                 FROM db_version ORDER BY version, id",
+            ))
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(AppliedPatch {
+                    version: row.try_get("", "version")?,
+                    description: row.try_get("", "description")?,
+                    checksum: row.try_get("", "checksum")?,
+                    applied_at: row.try_get("", "applied_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// 检查已应用补丁的校验和，返回与嵌入脚本不一致的版本
+    ///
+    /// 加入校验和之前应用的补丁没有记录，按当前脚本补写。
+    pub async fn verify_checksums(&self) -> Result<Vec<i32>, DbErr> {
+        let mut mismatched = Vec::new();
+        for applied in self.applied_patches().await? {
+            let Some(patch) = self.patches.iter().find(|patch| patch.version == applied.version)
+            else {
+                continue;
+            };
+            let checksum = patch.checksum();
+            match applied.checksum {
+                Some(recorded) if recorded != checksum => {
+                    tracing::warn!(
+                        "Patch version {} was modified after it was applied (recorded {}, \
+                         embedded {})",
+                        patch.version,
+                        recorded,
+                        checksum
+                    );
+                    mismatched.push(patch.version);
+                },
+                Some(_) => {},
+                None => {
+                    let update = Query::update()
+                        .table(Alias::new("db_version"))
+                        .value(Alias::new("description"), patch.description)
+                        .value(Alias::new("checksum"), checksum)
+                        .and_where(Expr::col(Alias::new("version")).eq(patch.version))
+                        .and_where(Expr::col(Alias::new("checksum")).is_null())
+                        .to_owned();
+                    self.db.execute(self.backend().build(&update)).await?;
+                },
             }
         }
-
-        statements
+        Ok(mismatched)
     }
 
     pub async fn get_current_version(&self) -> Result<i32, DbErr> {
         let result = self
             .db
             .query_one(Statement::from_string(
-                sea_orm::DbBackend::Sqlite,
+                self.backend(),
                 "SELECT version FROM db_version ORDER BY version DESC LIMIT 1",
             ))
             .await?;
@@ -168,24 +291,79 @@ impl PatchManager {
         }
     }
 
+    /// 当前版本；`db_version` 不存在（全新数据库）时为 0，不创建表
+    pub(super) async fn get_current_version_if_exists(&self) -> Result<i32, DbErr> {
+        if self.version_table_columns().await?.is_empty() {
+            return Ok(0);
+        }
+        self.get_current_version().await
+    }
+
+    fn rollback_patches(
+        &self,
+        current_version: i32,
+        target_version: i32,
+    ) -> impl Iterator<Item = &Patch> {
+        self.patches
+            .iter()
+            .rev()
+            .filter(move |patch| patch.version > target_version && patch.version <= current_version)
+    }
+
     async fn ensure_version_table(&self) -> Result<(), DbErr> {
-        let create_table_sql = r#"
+        let id_column = match self.backend() {
+            DbBackend::Postgres => "id SERIAL PRIMARY KEY",
+            _ => "id INTEGER PRIMARY KEY AUTOINCREMENT",
+        };
+        let create_table_sql = format!(
+            r#"
             CREATE TABLE IF NOT EXISTS db_version (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                {id_column},
                 version INTEGER NOT NULL,
-                applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                description TEXT,
+                checksum TEXT
             );
-        "#;
+        "#
+        );
 
-        self.db
-            .execute(Statement::from_string(sea_orm::DbBackend::Sqlite, create_table_sql))
-            .await?;
+        self.db.execute(Statement::from_string(self.backend(), create_table_sql)).await?;
+
+        // 旧版本创建的表没有描述和校验和列
+        let columns = self.version_table_columns().await?;
+        for column in ["description", "checksum"] {
+            if !columns.iter().any(|name| name == column) {
+                self.db
+                    .execute(Statement::from_string(
+                        self.backend(),
+                        format!("ALTER TABLE db_version ADD COLUMN {} TEXT", column),
+                    ))
+                    .await?;
+            }
+        }
         Ok(())
     }
 
-    async fn update_version(&self, version: i32) -> Result<(), DbErr> {
-        let insert_sql = format!("INSERT INTO db_version (version) VALUES ({})", version);
-        self.db.execute(Statement::from_string(sea_orm::DbBackend::Sqlite, insert_sql)).await?;
-        Ok(())
+    async fn version_table_columns(&self) -> Result<Vec<String>, DbErr> {
+        let sql = match self.backend() {
+            DbBackend::Postgres => {
+                "SELECT column_name::TEXT AS name FROM information_schema.columns WHERE \
+                 table_schema = current_schema() AND table_name = 'db_version'"
+            },
+            _ => "PRAGMA table_info(db_version)",
+        };
+        let rows = self.db.query_all(Statement::from_string(self.backend(), sql)).await?;
+        rows.iter().map(|row| row.try_get::<String>("", "name")).collect()
     }
+
+    fn backend(&self) -> DbBackend {
+        self.db.get_database_backend()
+    }
+}
+
+fn patch_error(patch: &Patch, action: &str, error: DbErr) -> DbErr {
+    DbErr::Migration(format!(
+        "补丁 {}（{}）{}失败: {}",
+        patch.version, patch.description, action, error
+    ))
 }
@@ -18,7 +18,7 @@ pub struct Model {
     #[sea_orm(column_type = "Text", unique)]
     pub file_name: String,
     #[sea_orm(column_type = "Integer")]
-    pub file_size: u64,
+    pub file_size: i64,
     #[sea_orm(column_type = "Text")]
     pub file_path: String,
 }
@@ -0,0 +1,40 @@
+//! 保存的过滤器（补丁 004 创建）
+
+use async_trait::async_trait;
+use sea_orm::{DbErr, Set, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, Default)]
+#[sea_orm(table_name = "filters")]
+#[serde(rename_all = "camelCase")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub id: String,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    /// 过滤表达式，见 `FilterQuery`
+    #[sea_orm(column_type = "Text")]
+    pub query: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub color: Option<String>,
+    pub item_order: i32,
+    pub is_favorite: bool,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut this = self;
+        if insert {
+            this.id = Set(Uuid::new_v4().to_string());
+        }
+
+        Ok(this)
+    }
+}
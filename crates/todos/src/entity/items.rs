@@ -52,6 +52,14 @@ pub struct Model {
     pub extra_data: Option<serde_json::Value>,
     #[sea_orm(column_type = "Text", nullable)]
     pub item_type: Option<String>,
+    /// 移入回收站的时间（未删除时为 None）
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub deleted_at: Option<NaiveDateTime>,
+    /// 随项目归档的时间（未归档时为 None）
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub archived_at: Option<NaiveDateTime>,
 }
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
@@ -142,4 +150,22 @@ impl Model {
         let today = chrono::Utc::now().naive_utc().date();
         self.due_date_naive().is_some_and(|due| due < today)
     }
+
+    /// 按手动排序（child_order）比较：未排序的排在最后，同键按创建时间
+    pub fn cmp_child_order(&self, other: &Self) -> std::cmp::Ordering {
+        Self::cmp_order(self.child_order, other.child_order)
+            .then_with(|| self.added_at.cmp(&other.added_at))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+
+    /// 按当日排序（day_order）比较，规则同 [`Self::cmp_child_order`]
+    pub fn cmp_day_order(&self, other: &Self) -> std::cmp::Ordering {
+        Self::cmp_order(self.day_order, other.day_order)
+            .then_with(|| self.added_at.cmp(&other.added_at))
+            .then_with(|| self.id.cmp(&other.id))
+    }
+
+    fn cmp_order(a: Option<i32>, b: Option<i32>) -> std::cmp::Ordering {
+        (a.is_none(), a).cmp(&(b.is_none(), b))
+    }
 }
@@ -1,6 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use sea_orm::{DbErr, Set, entity::prelude::*};
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +23,10 @@ pub struct Model {
     pub backend_type: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub source_id: Option<String>,
+    /// 移入回收站的时间（未删除时为 None）
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
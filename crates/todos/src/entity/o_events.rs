@@ -1,14 +1,16 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
-use async_trait::async_trait;
-use sea_orm::{DbErr, Set, entity::prelude::*};
+//!
+//! 注意：OEvents 由初始迁移（`migrations/*/001_initial_schema.up.sql`）中的触发器写入，
+//! `id` 为自增整数，`event_date` 为本地时间文本（`YYYY-MM-DD HH:MM:SS`）。
+use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
-#[sea_orm(table_name = "o_events")]
+#[sea_orm(table_name = "oevents")]
 #[serde(rename_all = "camelCase")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
-    pub id: String,
+    #[sea_orm(primary_key)]
+    pub id: i64,
     #[sea_orm(column_type = "Text", nullable)]
     pub event_type: Option<String>,
     #[sea_orm(column_type = "custom(\"DATETIME\")", nullable)]
@@ -31,17 +33,5 @@ pub struct Model {
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
-#[async_trait]
-impl ActiveModelBehavior for ActiveModel {
-    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
-    where
-        C: ConnectionTrait,
-    {
-        let mut this = self;
-        if insert {
-            this.id = Set(Uuid::new_v4().to_string());
-        }
 
-        Ok(this)
-    }
-}
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,7 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
 pub use super::{
-    attachments::Entity as AttachmentEntity, item_labels::Entity as ItemLabelEntity,
-    items::Entity as ItemEntity, labels::Entity as LabelEntity, projects::Entity as ProjectEntity,
+    attachments::Entity as AttachmentEntity, cur_temp_ids::Entity as CurTempIdEntity,
+    filters::Entity as FilterEntity,
+    item_labels::Entity as ItemLabelEntity, items::Entity as ItemEntity,
+    labels::Entity as LabelEntity, projects::Entity as ProjectEntity, queue::Entity as QueueEntity,
     reminders::Entity as ReminderEntity, sections::Entity as SectionEntity,
+    sources::Entity as SourceEntity,
 };
@@ -15,7 +15,7 @@ pub struct Model {
     pub item_id: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub service: Option<String>,
-    #[sea_orm(column_type = "Text", nullable)]
+    #[sea_orm(column_name = "type", column_type = "Text", nullable)]
     pub reminder_type: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub due: Option<String>,
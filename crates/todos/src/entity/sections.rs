@@ -29,6 +29,10 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
     pub hidded: bool,
+    /// 移入回收站的时间（未删除时为 None）
+    #[sea_orm(column_type = "Text", nullable)]
+    #[serde(default)]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
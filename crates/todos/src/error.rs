@@ -31,6 +31,8 @@ pub enum ErrorCode {
     PermissionDenied = 6000,
     /// 配置错误 (7xxx)
     ConfigError = 7000,
+    /// 网络 / 远程服务错误 (8xxx)
+    NetworkError = 8000,
     /// 内部错误 (9xxx)
     InternalError = 9000,
 }
@@ -51,13 +53,14 @@ impl ErrorCode {
             Self::Timeout => "TIMEOUT",
             Self::PermissionDenied => "PERMISSION",
             Self::ConfigError => "CONFIG",
+            Self::NetworkError => "NETWORK",
             Self::InternalError => "INTERNAL",
         }
     }
 
     /// 判断是否为可重试的错误
     pub fn is_retryable(&self) -> bool {
-        matches!(self, Self::Timeout | Self::DatabaseError)
+        matches!(self, Self::Timeout | Self::DatabaseError | Self::NetworkError)
     }
 
     /// 判断是否为客户端错误
@@ -202,6 +205,10 @@ pub enum TodoError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    /// 网络或远程服务错误（同步）
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
     /// 带上下文的错误
     #[error("{message} {context}")]
     WithContext { message: String, context: Box<ErrorContext>, source: Box<TodoError> },
@@ -220,6 +227,16 @@ impl From<sea_orm::DbErr> for TodoError {
     }
 }
 
+/// 事务错误：连接错误按数据库错误处理，事务体返回的错误原样传出
+impl From<sea_orm::TransactionError<TodoError>> for TodoError {
+    fn from(err: sea_orm::TransactionError<TodoError>) -> Self {
+        match err {
+            sea_orm::TransactionError::Connection(db_err) => TodoError::from(db_err),
+            sea_orm::TransactionError::Transaction(err) => err,
+        }
+    }
+}
+
 impl TodoError {
     /// 获取错误码
     pub fn error_code(&self) -> ErrorCode {
@@ -232,6 +249,7 @@ impl TodoError {
             Self::ValidationError(_) => ErrorCode::ValidationError,
             Self::PermissionDenied(_) => ErrorCode::PermissionDenied,
             Self::ConfigError(_) => ErrorCode::ConfigError,
+            Self::NetworkError(_) => ErrorCode::NetworkError,
             Self::WithContext { source, .. } => source.error_code(),
             Self::InternalError(_) => ErrorCode::InternalError,
         }
@@ -247,6 +265,7 @@ impl TodoError {
             Self::ValidationError(_) => ErrorSeverity::Warning,
             Self::PermissionDenied(_) => ErrorSeverity::Error,
             Self::ConfigError(_) => ErrorSeverity::Critical,
+            Self::NetworkError(_) => ErrorSeverity::Error,
             Self::WithContext { source, .. } => source.severity(),
             Self::InternalError(_) => ErrorSeverity::Critical,
         }
@@ -289,6 +308,7 @@ impl TodoError {
             Self::ValidationError(msg) => format!("数据验证失败: {}", msg),
             Self::PermissionDenied(msg) => format!("权限不足: {}", msg),
             Self::ConfigError(msg) => format!("配置错误: {}", msg),
+            Self::NetworkError(msg) => format!("网络错误: {}", msg),
             Self::WithContext { message, .. } => message.clone(),
             Self::InternalError(msg) => format!("内部错误: {}", msg),
         }
@@ -322,6 +342,11 @@ impl TodoError {
     pub fn timeout(operation: impl Into<String>) -> Self {
         Self::Timeout(operation.into())
     }
+
+    /// 创建网络错误
+    pub fn network(msg: impl Into<String>) -> Self {
+        Self::NetworkError(msg.into())
+    }
 }
 
 /// 结果类型别名
@@ -420,5 +445,9 @@ mod tests {
 
         let err = TodoError::timeout("test");
         assert!(matches!(err, TodoError::Timeout(_)));
+
+        let err = TodoError::network("test");
+        assert!(matches!(err, TodoError::NetworkError(_)));
+        assert!(err.is_retryable());
     }
 }
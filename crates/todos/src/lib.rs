@@ -7,8 +7,23 @@ pub mod error;
 mod objects;
 pub mod repositories;
 pub mod services;
+pub mod sync;
 pub mod utils;
 
-pub use app::init_db;
-pub use objects::due_date::DueDate;
+pub use app::{
+    AppliedPatch, BackupManager, BackupPolicy, BackupReason, BackupSnapshot, JournalEntry,
+    JournalOp, JournalStatus, Patch, PatchManager, PatchStep, ReplayReport, ReplayedWrite,
+    WriteJournal, apply_pending_restore, backup_interval_hours, backup_manager, init_db,
+    init_db_for_preview, pending_restore_path, trash_retention_days, write_journal_path,
+};
+pub use objects::{
+    due_date::DueDate,
+    filter_query::{DateRef, FilterExpr, FilterIndex, FilterQuery, FilterTerm},
+    item_tree::ItemNode,
+    project_template::{
+        ProjectTemplate, TEMPLATE_EXTENSION, TEMPLATE_VERSION, TemplateDue, TemplateItem,
+        TemplateProject, TemplateReminder, TemplateSection,
+    },
+    quick_add::{QuickAdd, QuickAddIndex, QuickAddToken, QuickAddTokenKind},
+};
 pub use services::Store;
@@ -1,6 +1,6 @@
 use std::{fmt, str::FromStr};
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -39,20 +39,24 @@ impl Default for DueDate {
 
 impl DueDate {
     pub fn datetime(&self) -> Option<NaiveDateTime> {
-        // 支持两种格式：带T的ISO格式和不带T的格式
-        if self.date.is_empty() {
+        Self::parse_datetime(&self.date)
+    }
+
+    /// 解析日期字符串，支持带T的ISO格式、带空格的格式和纯日期格式
+    fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+        if value.is_empty() {
             return None;
         }
         // 先尝试标准ISO格式 (2025-02-22T17:30:00)
-        if let Ok(dt) = NaiveDateTime::from_str(&self.date) {
+        if let Ok(dt) = NaiveDateTime::from_str(value) {
             return Some(dt);
         }
         // 再尝试带空格的格式 (2025-02-22 17:30:00)
-        if let Ok(dt) = NaiveDateTime::parse_from_str(&self.date, "%Y-%m-%d %H:%M:%S") {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
             return Some(dt);
         }
         // 最后尝试纯日期格式 (2025-02-22)，转换为当天的 00:00:00
-        if let Ok(date) = chrono::NaiveDate::from_str(&self.date) {
+        if let Ok(date) = chrono::NaiveDate::from_str(value) {
             return Some(date.and_hms_opt(0, 0, 0).unwrap());
         }
         None
@@ -63,7 +67,7 @@ impl DueDate {
     }
 
     pub fn end_datetime(&self) -> Option<NaiveDateTime> {
-        self.recurrency_end.parse().ok()
+        Self::parse_datetime(&self.recurrency_end)
     }
 
     pub fn has_weeks(&self) -> bool {
@@ -91,6 +95,29 @@ impl DueDate {
         }
     }
 
+    /// 计算重复任务的下一次到期日期
+    ///
+    /// 返回 `None` 表示不是重复任务或重复已结束（达到次数 / 超过结束日期），
+    /// 此时应按普通任务完成处理。按次数结束（AFTER）时，剩余次数减一。
+    pub fn next_occurrence(&self) -> Option<DueDate> {
+        if !self.is_recurring || self.is_recurrency_end() {
+            return None;
+        }
+
+        let current = self.datetime()?;
+        let next = DateTime::default().next_recurrency(current, self.clone());
+        if next <= current {
+            return None;
+        }
+
+        let mut due = self.duplicate();
+        due.set_datetime(next);
+        if self.end_type() == RecurrencyEndType::AFTER {
+            due.recurrency_count -= 1;
+        }
+        Some(due)
+    }
+
     pub fn is_recurrency_equal(&self, date: DueDate) -> bool {
         self.recurrency_type == date.recurrency_type
             && self.recurrency_interval == date.recurrency_interval
@@ -113,6 +140,143 @@ impl DueDate {
         self.recurrency_end = "".to_string();
     }
 
+    /// 转换为 RFC 5545 的 `RRULE` 值（不含 `RRULE:` 前缀），非重复任务返回 `None`
+    ///
+    /// `recurrency_weeks` 中 1 表示周一、7 表示周日；`UNTIL` 与 `DTSTART` 保持同一种值类型
+    /// （时间为 00:00 时按全天日期处理）。
+    pub fn rrule(&self) -> Option<String> {
+        if !self.is_recurring {
+            return None;
+        }
+        let freq = match self.recurrency_type {
+            RecurrencyType::MINUTELY => "MINUTELY",
+            RecurrencyType::HOURLY => "HOURLY",
+            RecurrencyType::EveryDay => "DAILY",
+            RecurrencyType::EveryWeek => "WEEKLY",
+            RecurrencyType::EveryMonth => "MONTHLY",
+            RecurrencyType::EveryYear => "YEARLY",
+            RecurrencyType::NONE => return None,
+        };
+        let mut rule = format!("FREQ={freq}");
+        if self.recurrency_interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.recurrency_interval));
+        }
+        if self.recurrency_type == RecurrencyType::EveryWeek && self.has_weeks() {
+            let days: Vec<&str> = self
+                .recurrency_weeks
+                .split(',')
+                .filter_map(|day| match day.trim() {
+                    "1" => Some("MO"),
+                    "2" => Some("TU"),
+                    "3" => Some("WE"),
+                    "4" => Some("TH"),
+                    "5" => Some("FR"),
+                    "6" => Some("SA"),
+                    "7" => Some("SU"),
+                    _ => None,
+                })
+                .collect();
+            if !days.is_empty() {
+                rule.push_str(&format!(";BYDAY={}", days.join(",")));
+            }
+        }
+        match self.end_type() {
+            RecurrencyEndType::AFTER => {
+                rule.push_str(&format!(";COUNT={}", self.recurrency_count));
+            },
+            RecurrencyEndType::OnDate => {
+                if let Some(end) = self.end_datetime() {
+                    let date_only = self.datetime().is_some_and(|dt| dt.time() == NaiveTime::MIN);
+                    let format = if date_only { "%Y%m%d" } else { "%Y%m%dT%H%M%S" };
+                    rule.push_str(&format!(";UNTIL={}", end.format(format)));
+                }
+            },
+            _ => {},
+        }
+        Some(rule)
+    }
+
+    /// 按 `RRULE` 值设置重复规则
+    ///
+    /// 只支持能用本地模型表达的规则（`FREQ`、`INTERVAL`、`COUNT`、`UNTIL`、每周的 `BYDAY`）；
+    /// 其他规则返回 `false`，`self` 保持不变。
+    pub fn set_rrule(&mut self, rrule: &str) -> bool {
+        let mut due = DueDate {
+            is_recurring: true,
+            recurrency_supported: true,
+            recurrency_interval: 1,
+            ..Default::default()
+        };
+        let mut weeks = Vec::new();
+        for part in rrule.trim().trim_start_matches("RRULE:").split(';') {
+            let Some((key, value)) = part.split_once('=') else {
+                return false;
+            };
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    due.recurrency_type = match value.to_ascii_uppercase().as_str() {
+                        "MINUTELY" => RecurrencyType::MINUTELY,
+                        "HOURLY" => RecurrencyType::HOURLY,
+                        "DAILY" => RecurrencyType::EveryDay,
+                        "WEEKLY" => RecurrencyType::EveryWeek,
+                        "MONTHLY" => RecurrencyType::EveryMonth,
+                        "YEARLY" => RecurrencyType::EveryYear,
+                        _ => return false,
+                    }
+                },
+                "INTERVAL" => match value.parse::<i64>() {
+                    Ok(interval) if interval > 0 => due.recurrency_interval = interval,
+                    _ => return false,
+                },
+                "COUNT" => match value.parse::<i64>() {
+                    Ok(count) if count > 0 => due.recurrency_count = count,
+                    _ => return false,
+                },
+                "UNTIL" => match DateTime::default().get_ical_datetime(value) {
+                    Some(until) => {
+                        due.recurrency_end = until.format("%Y-%m-%d %H:%M:%S").to_string()
+                    },
+                    None => return false,
+                },
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let week = match day.to_ascii_uppercase().as_str() {
+                            "MO" => "1",
+                            "TU" => "2",
+                            "WE" => "3",
+                            "TH" => "4",
+                            "FR" => "5",
+                            "SA" => "6",
+                            "SU" => "7",
+                            // 带序号的写法（如 `2TU`）无法表达
+                            _ => return false,
+                        };
+                        weeks.push(week);
+                    }
+                },
+                // 默认值以外的周起始日不影响本地计算
+                "WKST" => {},
+                _ => return false,
+            }
+        }
+        if due.recurrency_type == RecurrencyType::NONE
+            || (!weeks.is_empty() && due.recurrency_type != RecurrencyType::EveryWeek)
+        {
+            return false;
+        }
+        weeks.sort_unstable();
+        weeks.dedup();
+
+        self.is_recurring = true;
+        self.recurrency_supported = true;
+        self.recurrency_type = due.recurrency_type;
+        self.recurrency_interval = due.recurrency_interval;
+        self.recurrency_count = due.recurrency_count;
+        self.recurrency_end = due.recurrency_end;
+        self.recurrency_weeks = weeks.join(",");
+        true
+    }
+
     pub fn duplicate(&self) -> DueDate {
         DueDate {
             date: self.date.clone(),
@@ -132,3 +296,124 @@ impl fmt::Display for DueDate {
         write!(f, "{}", serde_json::to_string(self).unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recurring(date: &str, recurrency_type: RecurrencyType, interval: i64) -> DueDate {
+        DueDate {
+            date: date.to_string(),
+            is_recurring: true,
+            recurrency_type,
+            recurrency_interval: interval,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_next_occurrence_daily() {
+        let due = recurring("2025-02-22 09:00:00", RecurrencyType::EveryDay, 2);
+        let next = due.next_occurrence().unwrap();
+        assert_eq!(next.date, "2025-02-24 09:00:00");
+        assert!(next.is_recurring);
+    }
+
+    #[test]
+    fn test_next_occurrence_monthly_clamps_day() {
+        let due = recurring("2025-01-31 00:00:00", RecurrencyType::EveryMonth, 1);
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-02-28 00:00:00");
+    }
+
+    #[test]
+    fn test_next_occurrence_not_recurring() {
+        let due = DueDate { date: "2025-02-22 09:00:00".to_string(), ..Default::default() };
+        assert!(due.next_occurrence().is_none());
+    }
+
+    #[test]
+    fn test_next_occurrence_count_decrements_until_end() {
+        let mut due = recurring("2025-02-22 09:00:00", RecurrencyType::EveryDay, 1);
+        due.recurrency_count = 2;
+
+        let next = due.next_occurrence().unwrap();
+        assert_eq!(next.recurrency_count, 1);
+        assert!(next.next_occurrence().is_none());
+    }
+
+    #[test]
+    fn test_next_occurrence_respects_end_date() {
+        let mut due = recurring("2025-02-22 09:00:00", RecurrencyType::EveryWeek, 1);
+        due.recurrency_end = "2025-03-01 09:00:00".to_string();
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-03-01 09:00:00");
+
+        due.recurrency_end = "2025-02-28".to_string();
+        assert!(due.next_occurrence().is_none());
+    }
+
+    #[test]
+    fn test_next_occurrence_weekly_on_weekdays() {
+        // 2025-02-24 是周一
+        let mut due = recurring("2025-02-24 09:00:00", RecurrencyType::EveryWeek, 1);
+        due.recurrency_weeks = "1".to_string();
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-03-03 09:00:00");
+
+        due.recurrency_weeks = "1,3".to_string();
+        let next = due.next_occurrence().unwrap();
+        assert_eq!(next.date, "2025-02-26 09:00:00");
+        assert_eq!(next.next_occurrence().unwrap().date, "2025-03-03 09:00:00");
+
+        due.recurrency_weeks = "7".to_string();
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-03-02 09:00:00");
+    }
+
+    #[test]
+    fn test_next_occurrence_weekly_interval_applies_when_wrapping() {
+        let mut due = recurring("2025-02-24 09:00:00", RecurrencyType::EveryWeek, 2);
+        due.recurrency_weeks = "1,3".to_string();
+        let next = due.next_occurrence().unwrap();
+        assert_eq!(next.date, "2025-02-26 09:00:00");
+        assert_eq!(next.next_occurrence().unwrap().date, "2025-03-10 09:00:00");
+    }
+
+    #[test]
+    fn test_next_occurrence_weekly_ignores_malformed_weekdays() {
+        let mut due = recurring("2025-02-24 09:00:00", RecurrencyType::EveryWeek, 1);
+        due.recurrency_weeks = "x,3".to_string();
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-02-26 09:00:00");
+
+        due.recurrency_weeks = "x".to_string();
+        assert_eq!(due.next_occurrence().unwrap().date, "2025-03-03 09:00:00");
+    }
+
+    #[test]
+    fn test_rrule_round_trip() {
+        let mut due = recurring("2025-02-24 09:00:00", RecurrencyType::EveryWeek, 2);
+        due.recurrency_weeks = "1,3".to_string();
+        due.recurrency_end = "2025-06-30 09:00:00".to_string();
+        let rrule = due.rrule().unwrap();
+        assert_eq!(rrule, "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;UNTIL=20250630T090000");
+
+        let mut parsed = DueDate { date: due.date.clone(), ..Default::default() };
+        assert!(parsed.set_rrule(&rrule));
+        assert!(parsed.is_recurrency_equal(due));
+
+        let mut daily = recurring("2025-02-24 00:00:00", RecurrencyType::EveryDay, 1);
+        daily.recurrency_count = 5;
+        assert_eq!(daily.rrule().unwrap(), "FREQ=DAILY;COUNT=5");
+        assert!(DueDate::default().rrule().is_none());
+    }
+
+    #[test]
+    fn test_set_rrule_unsupported() {
+        let mut due = DueDate::default();
+        assert!(!due.set_rrule("FREQ=MONTHLY;BYDAY=2TU"));
+        assert!(!due.set_rrule("FREQ=MONTHLY;BYDAY=MO"));
+        assert!(!due.set_rrule("FREQ=YEARLY;BYMONTHDAY=1"));
+        assert!(!due.set_rrule("FREQ=SECONDLY"));
+        assert!(!due.is_recurring);
+
+        assert!(due.set_rrule("RRULE:FREQ=YEARLY;WKST=SU"));
+        assert_eq!((due.recurrency_type, due.recurrency_interval), (RecurrencyType::EveryYear, 1));
+    }
+}
@@ -0,0 +1,467 @@
+//! Todoist 风格的过滤表达式
+//!
+//! 语法（不区分大小写）：
+//!
+//! ```text
+//! expr    := and ( '|' and )*
+//! and     := unary ( '&' unary )*
+//! unary   := '!' unary | '(' expr ')' | term
+//! ```
+//!
+//! 支持的条件：
+//! - `today` / `tomorrow` / `overdue`（`od`）/ `no date` / `recurring` / `pinned` / `subtask` /
+//!   `all`（`*`）
+//! - `p1` ~ `p4`：优先级（p1 最高）
+//! - `#项目`（`##项目` 包含子项目）、`/分区`、`@标签`、`no labels`
+//! - `due: <日期>`、`due before: <日期>`、`due after: <日期>`，日期可以是 `today`、`tomorrow`、
+//!   `yesterday`、`+7d` / `-3d` / `+2w` 或 `2024-05-01`
+//! - `7 days` / `next 7 days`：今天起 7 天内到期
+//! - `search: 关键词`：内容包含关键词
+//!
+//! 例如 `(today | overdue) & p1 & #work & !@waiting & due before: +7d`。
+//! 过滤只作用于未完成的任务，求值通过 [`FilterIndex`] 交给数据源，
+//! 项目 / 分区 / 标签 / 置顶条件直接走数据源的索引，其余条件逐个检查候选任务。
+
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{Duration, NaiveDate};
+
+use crate::{entity::ItemModel, enums::item_priority::ItemPriority, error::TodoError};
+
+/// 日期引用，求值时相对于“今天”解析
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateRef {
+    /// 相对今天的天数偏移（`today` 为 0）
+    Relative(i64),
+    Absolute(NaiveDate),
+}
+
+impl DateRef {
+    pub fn resolve(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Relative(days) => today + Duration::days(*days),
+            Self::Absolute(date) => *date,
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        match text {
+            "today" => return Some(Self::Relative(0)),
+            "tomorrow" => return Some(Self::Relative(1)),
+            "yesterday" => return Some(Self::Relative(-1)),
+            _ => {},
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+            return Some(Self::Absolute(date));
+        }
+
+        // +7d / -3d / 2w
+        let (sign, rest) = match text.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let unit = rest.chars().last()?;
+        let amount: i64 = rest[..rest.len() - unit.len_utf8()].trim().parse().ok()?;
+        let days = match unit {
+            'd' => amount,
+            'w' => amount * 7,
+            _ => return None,
+        };
+        Some(Self::Relative(sign * days))
+    }
+}
+
+/// 单个过滤条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterTerm {
+    All,
+    Today,
+    Tomorrow,
+    Overdue,
+    NoDate,
+    /// 今天起 N 天内到期（含今天，不含过期）
+    NextDays(u32),
+    DueOn(DateRef),
+    DueBefore(DateRef),
+    DueAfter(DateRef),
+    /// 优先级，取值与 `ItemPriority` 一致（1 为最高）
+    Priority(i32),
+    Project {
+        name: String,
+        include_subprojects: bool,
+    },
+    Section(String),
+    Label(String),
+    NoLabels,
+    Pinned,
+    Recurring,
+    Subtask,
+    Search(String),
+}
+
+impl FilterTerm {
+    fn parse(raw: &str) -> Result<Self, TodoError> {
+        let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let lower = text.to_lowercase();
+        let invalid = || TodoError::ValidationError(format!("Unknown filter term: {}", text));
+
+        let term = match lower.as_str() {
+            "all" | "*" => Self::All,
+            "today" => Self::Today,
+            "tomorrow" => Self::Tomorrow,
+            "overdue" | "od" => Self::Overdue,
+            "no date" | "no due date" => Self::NoDate,
+            "no labels" | "no label" => Self::NoLabels,
+            "pinned" => Self::Pinned,
+            "recurring" => Self::Recurring,
+            "subtask" | "subtasks" => Self::Subtask,
+            "p1" | "p2" | "p3" | "p4" => Self::Priority(ItemPriority::parse(Some(&lower)) as i32),
+            _ => {
+                let name = |prefix: &str| text[prefix.len()..].trim().to_string();
+                if lower.starts_with("##") {
+                    Self::Project { name: name("##"), include_subprojects: true }
+                } else if lower.starts_with('#') {
+                    Self::Project { name: name("#"), include_subprojects: false }
+                } else if lower.starts_with('/') {
+                    Self::Section(name("/"))
+                } else if lower.starts_with('@') {
+                    Self::Label(name("@"))
+                } else if let Some(date) = lower.strip_prefix("due before:") {
+                    Self::DueBefore(DateRef::parse(date).ok_or_else(invalid)?)
+                } else if let Some(date) = lower.strip_prefix("due after:") {
+                    Self::DueAfter(DateRef::parse(date).ok_or_else(invalid)?)
+                } else if let Some(date) = lower.strip_prefix("due:") {
+                    Self::DueOn(DateRef::parse(date).ok_or_else(invalid)?)
+                } else if lower.starts_with("search:") {
+                    Self::Search(name("search:"))
+                } else if let Some(days) = lower
+                    .strip_prefix("next ")
+                    .unwrap_or(&lower)
+                    .strip_suffix(" days")
+                    .and_then(|n| n.trim().parse().ok())
+                {
+                    Self::NextDays(days)
+                } else {
+                    return Err(invalid());
+                }
+            },
+        };
+
+        match &term {
+            Self::Project { name, .. }
+            | Self::Section(name)
+            | Self::Label(name)
+            | Self::Search(name)
+                if name.is_empty() =>
+            {
+                Err(invalid())
+            },
+            _ => Ok(term),
+        }
+    }
+
+    /// 对单个任务求值（不依赖索引的条件）
+    fn matches(&self, item: &ItemModel, today: NaiveDate) -> bool {
+        let due = item.due_date_naive();
+        match self {
+            Self::All => true,
+            Self::Today => due == Some(today),
+            Self::Tomorrow => due == Some(today + Duration::days(1)),
+            Self::Overdue => due.is_some_and(|due| due < today),
+            Self::NoDate => due.is_none(),
+            Self::NextDays(days) => {
+                due.is_some_and(|due| due >= today && due <= today + Duration::days(*days as i64))
+            },
+            Self::DueOn(date) => due == Some(date.resolve(today)),
+            Self::DueBefore(date) => due.is_some_and(|due| due < date.resolve(today)),
+            Self::DueAfter(date) => due.is_some_and(|due| due > date.resolve(today)),
+            Self::Priority(priority) => {
+                item.priority.unwrap_or(ItemPriority::NONE as i32) == *priority
+            },
+            Self::NoLabels => item.labels.as_deref().is_none_or(|labels| labels.trim().is_empty()),
+            Self::Pinned => item.pinned,
+            Self::Recurring => item.due_date().is_some_and(|due| due.is_recurring),
+            Self::Subtask => item.parent_id.as_deref().is_some_and(|id| !id.is_empty()),
+            Self::Search(text) => {
+                let text = text.to_lowercase();
+                item.content.to_lowercase().contains(&text)
+                    || item.description.as_deref().is_some_and(|d| d.to_lowercase().contains(&text))
+            },
+            // 名称条件由索引求值，逐个检查时不会走到这里
+            Self::Project { .. } | Self::Section(_) | Self::Label(_) => false,
+        }
+    }
+}
+
+/// 过滤表达式语法树
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Term(FilterTerm),
+    Not(Box<FilterExpr>),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+/// 过滤求值所需的数据源
+///
+/// 返回的任务均应为未完成的任务；名称比较不区分大小写。
+pub trait FilterIndex {
+    /// 用于解析相对日期的“今天”
+    fn today(&self) -> NaiveDate;
+    /// 参与过滤的全部任务（决定结果顺序）
+    fn open_items(&self) -> Vec<Arc<ItemModel>>;
+    fn items_in_project(&self, name: &str, include_subprojects: bool) -> Vec<Arc<ItemModel>>;
+    fn items_in_section(&self, name: &str) -> Vec<Arc<ItemModel>>;
+    fn items_with_label(&self, name: &str) -> Vec<Arc<ItemModel>>;
+    fn pinned_items(&self) -> Vec<Arc<ItemModel>>;
+}
+
+/// 解析后的过滤表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterQuery {
+    source: String,
+    expr: FilterExpr,
+}
+
+impl FilterQuery {
+    /// 解析过滤表达式，语法错误返回 `ValidationError`
+    pub fn parse(source: &str) -> Result<Self, TodoError> {
+        let tokens = tokenize(source);
+        if tokens.is_empty() {
+            return Err(TodoError::ValidationError("Filter query is empty".to_string()));
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(TodoError::ValidationError(format!("Unexpected {:?} in filter", token)));
+        }
+        Ok(Self { source: source.trim().to_string(), expr })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn expr(&self) -> &FilterExpr {
+        &self.expr
+    }
+
+    /// 对数据源求值，按 `open_items` 的顺序返回命中的任务
+    pub fn evaluate(&self, index: &impl FilterIndex) -> Vec<Arc<ItemModel>> {
+        let items = index.open_items();
+        let evaluator = Evaluator { index, items: &items, today: index.today() };
+        let matched = evaluator.eval(&self.expr);
+        items.iter().filter(|item| matched.contains(item.id.as_str())).cloned().collect()
+    }
+
+    /// 统计命中的任务数
+    pub fn count(&self, index: &impl FilterIndex) -> usize {
+        let items = index.open_items();
+        let evaluator = Evaluator { index, items: &items, today: index.today() };
+        evaluator.eval(&self.expr).len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Term(String),
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let flush = |current: &mut String, tokens: &mut Vec<Token>| {
+        if !current.trim().is_empty() {
+            tokens.push(Token::Term(current.trim().to_string()));
+        }
+        current.clear();
+    };
+
+    for ch in source.chars() {
+        let token = match ch {
+            '&' => Token::And,
+            '|' => Token::Or,
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => {
+                current.push(ch);
+                continue;
+            },
+        };
+        flush(&mut current, &mut tokens);
+        tokens.push(token);
+    }
+    flush(&mut current, &mut tokens);
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, TodoError> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, TodoError> {
+        let mut expr = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = FilterExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr, TodoError> {
+        match self.next() {
+            Some(Token::Not) => Ok(FilterExpr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(TodoError::ValidationError("Missing ')' in filter".to_string())),
+                }
+            },
+            Some(Token::Term(term)) => Ok(FilterExpr::Term(FilterTerm::parse(&term)?)),
+            Some(token) => {
+                Err(TodoError::ValidationError(format!("Unexpected {:?} in filter", token)))
+            },
+            None => Err(TodoError::ValidationError("Filter ends unexpectedly".to_string())),
+        }
+    }
+}
+
+/// 以任务 ID 集合求值：与 / 或 / 非分别对应交集 / 并集 / 补集
+struct Evaluator<'a, I: FilterIndex> {
+    index: &'a I,
+    items: &'a [Arc<ItemModel>],
+    today: NaiveDate,
+}
+
+impl<'a, I: FilterIndex> Evaluator<'a, I> {
+    fn eval(&self, expr: &FilterExpr) -> HashSet<&'a str> {
+        match expr {
+            FilterExpr::Term(term) => self.eval_term(term),
+            FilterExpr::Not(inner) => {
+                let excluded = self.eval(inner);
+                self.ids(self.items.iter().filter(|item| !excluded.contains(item.id.as_str())))
+            },
+            FilterExpr::And(lhs, rhs) => {
+                let lhs = self.eval(lhs);
+                if lhs.is_empty() {
+                    return lhs;
+                }
+                let rhs = self.eval(rhs);
+                lhs.intersection(&rhs).copied().collect()
+            },
+            FilterExpr::Or(lhs, rhs) => {
+                let mut lhs = self.eval(lhs);
+                lhs.extend(self.eval(rhs));
+                lhs
+            },
+        }
+    }
+
+    fn eval_term(&self, term: &FilterTerm) -> HashSet<&'a str> {
+        let from_index = match term {
+            FilterTerm::Project { name, include_subprojects } => {
+                self.index.items_in_project(name, *include_subprojects)
+            },
+            FilterTerm::Section(name) => self.index.items_in_section(name),
+            FilterTerm::Label(name) => self.index.items_with_label(name),
+            FilterTerm::Pinned => self.index.pinned_items(),
+            _ => {
+                return self.ids(self.items.iter().filter(|item| term.matches(item, self.today)));
+            },
+        };
+        // 索引可能包含已完成的任务，只保留候选集合中的任务
+        let ids: HashSet<&str> = from_index.iter().map(|item| item.id.as_str()).collect();
+        self.ids(self.items.iter().filter(|item| ids.contains(item.id.as_str())))
+    }
+
+    fn ids(&self, items: impl Iterator<Item = &'a Arc<ItemModel>>) -> HashSet<&'a str> {
+        items.map(|item| item.id.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(text: &str) -> FilterTerm {
+        FilterTerm::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        assert_eq!(term("P1"), FilterTerm::Priority(1));
+        assert_eq!(term("p4"), FilterTerm::Priority(4));
+        assert_eq!(term("##Work Stuff"), FilterTerm::Project {
+            name: "Work Stuff".to_string(),
+            include_subprojects: true
+        });
+        assert_eq!(term("@waiting"), FilterTerm::Label("waiting".to_string()));
+        assert_eq!(term("due before:  +7d"), FilterTerm::DueBefore(DateRef::Relative(7)));
+        assert_eq!(term("due after: -2w"), FilterTerm::DueAfter(DateRef::Relative(-14)));
+        assert_eq!(
+            term("due: 2024-05-01"),
+            FilterTerm::DueOn(DateRef::Absolute(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()))
+        );
+        assert_eq!(term("next 7 days"), FilterTerm::NextDays(7));
+        assert_eq!(term("3 days"), FilterTerm::NextDays(3));
+        assert!(FilterTerm::parse("due before: someday").is_err());
+        assert!(FilterTerm::parse("#").is_err());
+        assert!(FilterTerm::parse("whatever").is_err());
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let query = FilterQuery::parse("today | overdue & p1").unwrap();
+        assert_eq!(
+            query.expr,
+            FilterExpr::Or(
+                Box::new(FilterExpr::Term(FilterTerm::Today)),
+                Box::new(FilterExpr::And(
+                    Box::new(FilterExpr::Term(FilterTerm::Overdue)),
+                    Box::new(FilterExpr::Term(FilterTerm::Priority(1))),
+                )),
+            )
+        );
+
+        let query = FilterQuery::parse("!(today | od) & !@waiting").unwrap();
+        assert!(matches!(query.expr, FilterExpr::And(..)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(FilterQuery::parse("").is_err());
+        assert!(FilterQuery::parse("(today | p1").is_err());
+        assert!(FilterQuery::parse("today &").is_err());
+        assert!(FilterQuery::parse("today )").is_err());
+        assert!(FilterQuery::parse("& today").is_err());
+    }
+}
@@ -0,0 +1,413 @@
+//! iCalendar (RFC 5545) 的最小读写实现
+//!
+//! 只处理内容行层面的格式：折行 / 展开、参数解析、TEXT 值的转义。
+//! 组件（`VCALENDAR`、`VTODO`、`VALARM`……）按 `BEGIN` / `END` 嵌套为 [`ICalComponent`] 树，
+//! 各属性的语义由调用方解释。
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::error::TodoError;
+
+/// 内容行建议的最大长度（字节，不含换行）
+const MAX_LINE_OCTETS: usize = 75;
+
+/// 一个内容行：`NAME;PARAM=VALUE:value`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ICalProperty {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    /// 原始值（TEXT 类型仍是转义后的形式，用 [`ICalProperty::text_value`] 读取）
+    pub value: String,
+}
+
+impl ICalProperty {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self { name: name.into(), params: Vec::new(), value: value.into() }
+    }
+
+    /// TEXT 类型的属性，值会被转义
+    pub fn text(name: impl Into<String>, text: &str) -> Self {
+        Self::new(name, escape_text(text))
+    }
+
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// 参数值（名称不区分大小写）
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// 按 TEXT 类型反转义后的值
+    pub fn text_value(&self) -> String {
+        unescape_text(&self.value)
+    }
+
+    /// 逗号分隔的多个 TEXT 值（如 `CATEGORIES`）
+    pub fn text_values(&self) -> Vec<String> {
+        split_unescaped(&self.value, ',')
+            .into_iter()
+            .map(|value| unescape_text(&value))
+            .filter(|value| !value.trim().is_empty())
+            .collect()
+    }
+
+    fn to_line(&self) -> String {
+        let mut line = self.name.clone();
+        for (name, value) in &self.params {
+            let quote = value.contains([':', ';', ',']);
+            if quote {
+                line.push_str(&format!(";{name}=\"{value}\""));
+            } else {
+                line.push_str(&format!(";{name}={value}"));
+            }
+        }
+        line.push(':');
+        line.push_str(&self.value);
+        line
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        // 参数值可以带引号，引号内的 `:` `;` 不是分隔符
+        let mut in_quotes = false;
+        let mut colon = None;
+        for (index, ch) in line.char_indices() {
+            match ch {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => {
+                    colon = Some(index);
+                    break;
+                },
+                _ => {},
+            }
+        }
+        let colon = colon?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = split_quoted(head, ';').into_iter();
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        if name.is_empty() {
+            return None;
+        }
+        let params = parts
+            .filter_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                Some((key.trim().to_ascii_uppercase(), value.trim_matches('"').to_string()))
+            })
+            .collect();
+        Some(Self { name, params, value: value.to_string() })
+    }
+}
+
+/// 一个组件（`BEGIN:NAME` … `END:NAME`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalComponent {
+    pub name: String,
+    pub properties: Vec<ICalProperty>,
+    pub components: Vec<ICalComponent>,
+}
+
+impl ICalComponent {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), properties: Vec::new(), components: Vec::new() }
+    }
+
+    pub fn push(&mut self, property: ICalProperty) {
+        self.properties.push(property);
+    }
+
+    /// 第一个同名属性（名称不区分大小写）
+    pub fn property(&self, name: &str) -> Option<&ICalProperty> {
+        self.properties.iter().find(|property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn properties_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a ICalProperty> + 'a {
+        self.properties.iter().filter(move |property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ICalComponent> + 'a {
+        self.components.iter().filter(move |component| component.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 解析 iCalendar 文本，返回顶层组件（通常只有一个 `VCALENDAR`）
+    pub fn parse(input: &str) -> Result<Vec<ICalComponent>, TodoError> {
+        let mut roots = Vec::new();
+        let mut stack: Vec<ICalComponent> = Vec::new();
+        for (number, line) in unfold(input).into_iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(property) = ICalProperty::parse_line(&line) else {
+                return Err(TodoError::validation(format!(
+                    "iCalendar 第 {} 行格式错误: {}",
+                    number + 1,
+                    line
+                )));
+            };
+            match property.name.as_str() {
+                "BEGIN" => {
+                    stack.push(ICalComponent::new(property.value.trim().to_ascii_uppercase()))
+                },
+                "END" => {
+                    let name = property.value.trim().to_ascii_uppercase();
+                    let component = match stack.pop() {
+                        Some(component) if component.name == name => component,
+                        _ => {
+                            return Err(TodoError::validation(format!(
+                                "iCalendar 中的 END:{} 没有对应的 BEGIN",
+                                name
+                            )));
+                        },
+                    };
+                    match stack.last_mut() {
+                        Some(parent) => parent.components.push(component),
+                        None => roots.push(component),
+                    }
+                },
+                _ => match stack.last_mut() {
+                    Some(component) => component.push(property),
+                    None => {
+                        return Err(TodoError::validation(format!(
+                            "iCalendar 属性 {} 不在任何组件中",
+                            property.name
+                        )));
+                    },
+                },
+            }
+        }
+        if let Some(component) = stack.last() {
+            return Err(TodoError::validation(format!(
+                "iCalendar 组件 {} 没有结束",
+                component.name
+            )));
+        }
+        Ok(roots)
+    }
+
+    /// 序列化为 iCalendar 文本（CRLF 换行，超过 75 字节的行折行）
+    pub fn to_ics(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output);
+        output
+    }
+
+    fn write(&self, output: &mut String) {
+        write_folded(output, &format!("BEGIN:{}", self.name));
+        for property in &self.properties {
+            write_folded(output, &property.to_line());
+        }
+        for component in &self.components {
+            component.write(output);
+        }
+        write_folded(output, &format!("END:{}", self.name));
+    }
+}
+
+/// 转义 TEXT 值中的 `\` `;` `,` 和换行
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {},
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// 解析 DURATION 值（`-PT30M`、`P1DT2H`、`-P1W`），不支持年 / 月
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut in_time = false;
+    let mut parsed_any = false;
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        total += match (unit, in_time) {
+            ('W', false) => Duration::weeks(amount),
+            ('D', false) => Duration::days(amount),
+            ('H', true) => Duration::hours(amount),
+            ('M', true) => Duration::minutes(amount),
+            ('S', true) => Duration::seconds(amount),
+            _ => return None,
+        };
+        parsed_any = true;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    parsed_any.then_some(if negative { -total } else { total })
+}
+
+/// 提前 `minutes` 分钟的 DURATION 值（`-PT30M`；0 分钟为 `PT0S`）
+pub fn format_offset_duration(minutes: i64) -> String {
+    match minutes {
+        0 => "PT0S".to_string(),
+        m if m > 0 => format!("-PT{}M", m),
+        m => format!("PT{}M", -m),
+    }
+}
+
+/// 展开折行：以空格或制表符开头的行接在上一行后面
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// 按字节数折行，不拆开多字节字符
+fn write_folded(output: &mut String, line: &str) {
+    let mut width = 0;
+    let mut limit = MAX_LINE_OCTETS;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > limit {
+            output.push_str("\r\n ");
+            width = 0;
+            // 续行的首个空格也计入长度
+            limit = MAX_LINE_OCTETS - 1;
+        }
+        output.push(ch);
+        width += ch.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// 按分隔符拆分，引号内的分隔符不算
+fn split_quoted(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut in_quotes = false;
+    for ch in value.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                parts.last_mut().unwrap().push(ch);
+            },
+            c if c == separator && !in_quotes => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+/// 按分隔符拆分，`\` 转义的分隔符不算（结果仍保留转义）
+fn split_unescaped(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                let current = parts.last_mut().unwrap();
+                current.push(ch);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            },
+            c if c == separator => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_components() {
+        let input = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nSUMMARY:Buy milk\\, \
+                     eggs\r\n and \
+                     bread\r\nDUE;VALUE=DATE:20300107\r\nBEGIN:VALARM\r\nTRIGGER;RELATED=END:\
+                     -PT30M\r\nEND:VALARM\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let roots = ICalComponent::parse(input).unwrap();
+        assert_eq!(roots.len(), 1);
+        let todo = roots[0].children("VTODO").next().unwrap();
+        assert_eq!(todo.property("summary").unwrap().text_value(), "Buy milk, eggsand bread");
+        assert_eq!(todo.property("DUE").unwrap().param("value"), Some("DATE"));
+        let alarm = todo.children("VALARM").next().unwrap();
+        assert_eq!(alarm.property("TRIGGER").unwrap().value, "-PT30M");
+
+        assert!(ICalComponent::parse("BEGIN:VCALENDAR\r\nEND:VTODO\r\n").is_err());
+        assert!(ICalComponent::parse("BEGIN:VCALENDAR\r\n").is_err());
+    }
+
+    #[test]
+    fn test_quoted_params_and_categories() {
+        let property =
+            ICalProperty::parse_line("ATTENDEE;CN=\"Doe; John\":mailto:john@example.com").unwrap();
+        assert_eq!(property.param("CN"), Some("Doe; John"));
+        assert_eq!(property.value, "mailto:john@example.com");
+
+        let categories = ICalProperty::parse_line("CATEGORIES:work,a\\,b,,home").unwrap();
+        assert_eq!(categories.text_values(), vec!["work", "a,b", "home"]);
+    }
+
+    #[test]
+    fn test_write_folds_long_lines() {
+        let mut todo = ICalComponent::new("VTODO");
+        let summary = "很长的任务标题".repeat(10);
+        todo.push(ICalProperty::text("SUMMARY", &summary));
+        todo.push(ICalProperty::text("DESCRIPTION", "line 1\nline 2; done"));
+        let ics = todo.to_ics();
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(ics.contains("DESCRIPTION:line 1\\nline 2\\; done\r\n"));
+
+        let parsed = ICalComponent::parse(&ics).unwrap();
+        assert_eq!(parsed[0].property("SUMMARY").unwrap().text_value(), summary);
+        assert_eq!(parsed[0].property("DESCRIPTION").unwrap().text_value(), "line 1\nline 2; done");
+    }
+
+    #[test]
+    fn test_durations() {
+        assert_eq!(parse_duration("-PT30M"), Some(Duration::minutes(-30)));
+        assert_eq!(parse_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(parse_duration("-P1W"), Some(Duration::weeks(-1)));
+        assert_eq!(parse_duration("PT0S"), Some(Duration::zero()));
+        assert_eq!(parse_duration("P1M"), None);
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(format_offset_duration(30), "-PT30M");
+        assert_eq!(format_offset_duration(0), "PT0S");
+    }
+}
@@ -0,0 +1,190 @@
+//! 子任务树
+//!
+//! 任务通过 `parent_id` 组成树，子任务按 `child_order` 排序（见
+//! [`ItemModel::cmp_child_order`]）。父任务不在给定列表中的任务视为根，
+//! 因此同一套逻辑既能处理整个项目，也能处理视图中筛选出的一部分任务
+//! （如按 `day_order` 排好的今天列表，根保持原有顺序）。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::entity::ItemModel;
+
+/// 任务树节点
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemNode {
+    pub item: ItemModel,
+    pub children: Vec<ItemNode>,
+}
+
+impl ItemNode {
+    /// 把平铺的任务列表组装成森林，根和子任务都按 `child_order` 排序
+    pub fn build_forest(items: Vec<ItemModel>) -> Vec<ItemNode> {
+        let mut indexed: Vec<(usize, &ItemModel)> = items.iter().enumerate().collect();
+        indexed.sort_by(|(_, a), (_, b)| a.cmp_child_order(b));
+        let order: Vec<(usize, usize)> = Self::depth_first_by(&indexed, |(_, item)| *item, false)
+            .into_iter()
+            .map(|(depth, (ix, _))| (depth, *ix))
+            .collect();
+
+        let mut slots: Vec<Option<ItemModel>> = items.into_iter().map(Some).collect();
+        let mut roots = Vec::new();
+        // 栈中保存当前路径上尚未挂到父节点的节点
+        let mut stack: Vec<(usize, ItemNode)> = Vec::new();
+        for (depth, ix) in order {
+            while stack.last().is_some_and(|(d, _)| *d >= depth) {
+                Self::pop_into(&mut stack, &mut roots);
+            }
+            if let Some(item) = slots[ix].take() {
+                stack.push((depth, ItemNode { item, children: Vec::new() }));
+            }
+        }
+        while !stack.is_empty() {
+            Self::pop_into(&mut stack, &mut roots);
+        }
+        roots
+    }
+
+    fn pop_into(stack: &mut Vec<(usize, ItemNode)>, roots: &mut Vec<ItemNode>) {
+        if let Some((_, node)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        }
+    }
+
+    /// 按深度优先顺序展开列表，返回 `(深度, 元素)`，根的深度为 0
+    ///
+    /// 根保持在 `items` 中的顺序，子任务按 `child_order` 排序。
+    /// `skip_collapsed` 为 true 时，已折叠任务（`collapsed`）的子孙不输出。
+    pub fn depth_first_by<'a, T>(
+        items: &'a [T],
+        item_of: impl Fn(&T) -> &ItemModel,
+        skip_collapsed: bool,
+    ) -> Vec<(usize, &'a T)> {
+        let ids: HashSet<&str> = items.iter().map(|entry| item_of(entry).id.as_str()).collect();
+        let mut children: HashMap<&str, Vec<&'a T>> = HashMap::new();
+        let mut roots: Vec<&'a T> = Vec::new();
+        for entry in items {
+            let item = item_of(entry);
+            match item.parent_id.as_deref() {
+                Some(parent) if parent != item.id && ids.contains(parent) => {
+                    children.entry(parent).or_default().push(entry)
+                },
+                _ => roots.push(entry),
+            }
+        }
+        for list in children.values_mut() {
+            list.sort_by(|a, b| item_of(a).cmp_child_order(item_of(b)));
+        }
+
+        let mut result = Vec::with_capacity(items.len());
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<(usize, &'a T)> =
+            roots.into_iter().rev().map(|entry| (0, entry)).collect();
+        let mut rest = items.iter();
+        loop {
+            let Some((depth, entry)) = stack.pop() else {
+                // 父子关系成环的任务没有根，从环上任意一个任务开始输出，保证不丢任务
+                match rest.find(|entry| !visited.contains(item_of(entry).id.as_str())) {
+                    Some(entry) => {
+                        stack.push((0, entry));
+                        continue;
+                    },
+                    None => break,
+                }
+            };
+            let item = item_of(entry);
+            if !visited.insert(item.id.as_str()) {
+                continue;
+            }
+            result.push((depth, entry));
+            if let Some(list) = children.get(item.id.as_str()) {
+                stack.extend(list.iter().rev().map(|child| (depth + 1, *child)));
+            }
+        }
+
+        if skip_collapsed {
+            // 折叠任务之后、深度更大的连续条目都是它的子孙
+            let mut collapsed_at: Option<usize> = None;
+            result.retain(|(depth, entry)| {
+                if collapsed_at.is_some_and(|at| *depth > at) {
+                    return false;
+                }
+                collapsed_at = item_of(entry).collapsed.then_some(*depth);
+                true
+            });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, parent: Option<&str>, order: i32) -> ItemModel {
+        ItemModel {
+            id: id.to_string(),
+            content: id.to_string(),
+            parent_id: parent.map(str::to_string),
+            child_order: Some(order),
+            ..Default::default()
+        }
+    }
+
+    fn fixture() -> Vec<ItemModel> {
+        vec![
+            item("b", None, 2),
+            item("a2", Some("a"), 2),
+            item("a", None, 1),
+            item("a1", Some("a"), 1),
+            item("a1x", Some("a1"), 1),
+            item("orphan", Some("missing"), 3),
+        ]
+    }
+
+    #[test]
+    fn test_depth_first_order() {
+        let items = fixture();
+        let order: Vec<(usize, &str)> = ItemNode::depth_first_by(&items, |item| item, false)
+            .into_iter()
+            .map(|(depth, item)| (depth, item.id.as_str()))
+            .collect();
+        // 根保持输入顺序
+        assert_eq!(order, [(0, "b"), (0, "a"), (1, "a1"), (2, "a1x"), (1, "a2"), (0, "orphan")]);
+    }
+
+    #[test]
+    fn test_depth_first_skips_collapsed() {
+        let mut items = fixture();
+        items.iter_mut().find(|item| item.id == "a1").unwrap().collapsed = true;
+        let ids: Vec<&str> = ItemNode::depth_first_by(&items, |item| item, true)
+            .into_iter()
+            .map(|(_, item)| item.id.as_str())
+            .collect();
+        assert_eq!(ids, ["b", "a", "a1", "a2", "orphan"]);
+    }
+
+    #[test]
+    fn test_build_forest() {
+        let forest = ItemNode::build_forest(fixture());
+        let roots: Vec<&str> = forest.iter().map(|node| node.item.id.as_str()).collect();
+        assert_eq!(roots, ["a", "b", "orphan"]);
+        let a = &forest[0];
+        assert_eq!(a.children.len(), 2);
+        assert_eq!(a.children[0].item.id, "a1");
+        assert_eq!(a.children[0].children[0].item.id, "a1x");
+        assert_eq!(a.children[1].item.id, "a2");
+    }
+
+    #[test]
+    fn test_cycle_keeps_every_item() {
+        let items = vec![item("x", Some("y"), 1), item("y", Some("x"), 2), item("z", None, 3)];
+        let order: Vec<(usize, &str)> = ItemNode::depth_first_by(&items, |item| item, false)
+            .into_iter()
+            .map(|(depth, item)| (depth, item.id.as_str()))
+            .collect();
+        assert_eq!(order, [(0, "z"), (0, "x"), (1, "y")]);
+    }
+}
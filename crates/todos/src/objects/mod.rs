@@ -1,5 +1,10 @@
 pub mod color;
 pub mod due_date;
+pub mod filter_query;
+pub mod ical;
+pub mod item_tree;
+pub mod project_template;
+pub mod quick_add;
 
 pub use color::*;
 pub use due_date::*;
@@ -0,0 +1,273 @@
+//! 项目模板
+//!
+//! 把一个项目（分区、任务、子任务、标签、提醒）保存为与数据库无关的 JSON 文件，
+//! 之后可以按选定的开始日期实例化为新项目。模板中不保存 ID，任务之间用 `key`
+//! 关联；截止日期保存为相对模板基准日（第 0 天）的天数，实例化时以开始日期为第 0 天换算。
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::{DueDate, error::TodoError};
+
+/// 模板格式的版本号，结构变化时递增
+pub const TEMPLATE_VERSION: u32 = 1;
+
+/// 模板文件的扩展名
+pub const TEMPLATE_EXTENSION: &str = "json";
+
+/// 项目模板（模板文件的内容）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTemplate {
+    pub version: u32,
+    pub created_at: NaiveDateTime,
+    pub project: TemplateProject,
+    pub sections: Vec<TemplateSection>,
+    /// 按深度优先顺序排列，父任务总在子任务之前
+    pub items: Vec<TemplateItem>,
+}
+
+/// 模板中的项目属性（对应 `ProjectModel` 中与具体数据无关的字段）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateProject {
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub emoji: Option<String>,
+    pub icon_style: Option<String>,
+    pub view_style: Option<String>,
+    pub sort_order: Option<i32>,
+    pub show_completed: Option<i32>,
+    pub is_favorite: bool,
+    /// 项目截止日期，相对基准日的天数
+    pub due_offset_days: Option<i64>,
+}
+
+/// 模板中的分区
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateSection {
+    pub key: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub collapsed: bool,
+}
+
+/// 模板中的任务
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateItem {
+    pub key: String,
+    pub content: String,
+    pub description: Option<String>,
+    /// 父任务的 `key`
+    pub parent: Option<String>,
+    /// 分区的 `key`
+    pub section: Option<String>,
+    pub priority: Option<i32>,
+    pub collapsed: bool,
+    pub pinned: bool,
+    /// 标签名称，实例化时按名称（不区分大小写）复用或新建
+    pub labels: Vec<String>,
+    pub due: Option<TemplateDue>,
+    pub reminders: Vec<TemplateReminder>,
+}
+
+/// 相对截止日期
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateDue {
+    /// 相对基准日的天数
+    pub offset_days: i64,
+    /// 截止时间，None 表示全天
+    pub time: Option<NaiveTime>,
+    /// 重复规则（`date` 与 `recurrency_end` 已清空）
+    pub recurrence: Option<DueDate>,
+    /// 重复结束日期，相对基准日的天数
+    pub end_offset_days: Option<i64>,
+}
+
+/// 模板中的提醒
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TemplateReminder {
+    /// 截止时间之前若干分钟（负数为之后）
+    #[serde(rename_all = "camelCase")]
+    BeforeDue { minutes: i64 },
+    /// 固定时间，日期相对基准日
+    #[serde(rename_all = "camelCase")]
+    At { offset_days: i64, time: NaiveTime },
+}
+
+impl ProjectTemplate {
+    /// 读回模板文件，拒绝更新版本写出的文件
+    pub fn from_json(json: &str) -> Result<Self, TodoError> {
+        let template: Self = serde_json::from_str(json)
+            .map_err(|e| TodoError::validation(format!("不是有效的模板文件: {}", e)))?;
+        if template.version > TEMPLATE_VERSION {
+            return Err(TodoError::validation(format!(
+                "模板文件版本 {} 高于当前支持的版本 {}",
+                template.version, TEMPLATE_VERSION
+            )));
+        }
+        Ok(template)
+    }
+
+    pub fn to_json(&self) -> Result<String, TodoError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| TodoError::InternalError(format!("JSON 序列化失败: {}", e)))
+    }
+}
+
+impl TemplateDue {
+    /// 由截止日期换算为相对 `base` 的形式；没有具体日期时返回 None
+    pub fn from_due(due: &DueDate, base: NaiveDate) -> Option<Self> {
+        let datetime = due.datetime()?;
+        let recurrence = due.is_recurring.then(|| DueDate {
+            date: String::new(),
+            recurrency_end: String::new(),
+            ..due.clone()
+        });
+        Some(Self {
+            offset_days: (datetime.date() - base).num_days(),
+            time: (datetime.time() != NaiveTime::MIN).then(|| datetime.time()),
+            end_offset_days: due
+                .is_recurring
+                .then(|| due.end_datetime())
+                .flatten()
+                .map(|end| (end.date() - base).num_days()),
+            recurrence,
+        })
+    }
+
+    /// 以 `start` 为第 0 天换算出截止日期
+    pub fn resolve(&self, start: NaiveDate) -> DueDate {
+        let mut due = self.recurrence.clone().unwrap_or_default();
+        due.set_datetime(
+            (start + Duration::days(self.offset_days))
+                .and_time(self.time.unwrap_or(NaiveTime::MIN)),
+        );
+        if let Some(end_offset) = self.end_offset_days {
+            due.recurrency_end = (start + Duration::days(end_offset))
+                .and_time(NaiveTime::MIN)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string();
+        }
+        due
+    }
+}
+
+impl TemplateReminder {
+    /// 由提醒时间换算；任务有截止时间时保存为相对截止时间的分钟数
+    pub fn from_reminder(
+        reminder_at: NaiveDateTime,
+        item_due: Option<NaiveDateTime>,
+        base: NaiveDate,
+    ) -> Self {
+        match item_due {
+            Some(due) => Self::BeforeDue { minutes: (due - reminder_at).num_minutes() },
+            None => Self::At {
+                offset_days: (reminder_at.date() - base).num_days(),
+                time: reminder_at.time(),
+            },
+        }
+    }
+
+    /// 换算出提醒时间；相对截止时间的提醒在任务没有截止时间时返回 None
+    pub fn resolve(
+        &self,
+        start: NaiveDate,
+        item_due: Option<NaiveDateTime>,
+    ) -> Option<NaiveDateTime> {
+        match self {
+            Self::BeforeDue { minutes } => item_due.map(|due| due - Duration::minutes(*minutes)),
+            Self::At { offset_days, time } => {
+                Some((start + Duration::days(*offset_days)).and_time(*time))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::RecurrencyType;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_due_round_trip_shifts_to_start() {
+        let mut due = DueDate::default();
+        due.set_datetime(date("2025-03-05").and_hms_opt(9, 30, 0).unwrap());
+        let relative = TemplateDue::from_due(&due, date("2025-03-03")).unwrap();
+        assert_eq!(relative.offset_days, 2);
+        assert_eq!(relative.time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(relative.recurrence, None);
+
+        let resolved = relative.resolve(date("2025-06-30"));
+        assert_eq!(resolved.datetime(), date("2025-07-02").and_hms_opt(9, 30, 0));
+    }
+
+    #[test]
+    fn test_recurring_due_keeps_rule() {
+        let due = DueDate {
+            date: "2025-03-03".to_string(),
+            is_recurring: true,
+            recurrency_type: RecurrencyType::EveryWeek,
+            recurrency_interval: 1,
+            recurrency_end: "2025-03-31".to_string(),
+            ..Default::default()
+        };
+        let relative = TemplateDue::from_due(&due, date("2025-03-03")).unwrap();
+        assert_eq!(relative.time, None);
+        assert_eq!(relative.end_offset_days, Some(28));
+        assert_eq!(relative.recurrence.as_ref().unwrap().date, "");
+
+        let resolved = relative.resolve(date("2025-05-01"));
+        assert!(resolved.is_recurring);
+        assert_eq!(resolved.recurrency_type, RecurrencyType::EveryWeek);
+        assert_eq!(resolved.datetime(), date("2025-05-01").and_hms_opt(0, 0, 0));
+        assert_eq!(resolved.end_datetime(), date("2025-05-29").and_hms_opt(0, 0, 0));
+    }
+
+    #[test]
+    fn test_reminders() {
+        let base = date("2025-03-03");
+        let due = date("2025-03-04").and_hms_opt(10, 0, 0).unwrap();
+        let before = TemplateReminder::from_reminder(
+            date("2025-03-04").and_hms_opt(9, 0, 0).unwrap(),
+            Some(due),
+            base,
+        );
+        assert_eq!(before, TemplateReminder::BeforeDue { minutes: 60 });
+        let start = date("2025-04-01");
+        let new_due = date("2025-04-02").and_hms_opt(10, 0, 0);
+        assert_eq!(before.resolve(start, new_due), date("2025-04-02").and_hms_opt(9, 0, 0));
+        assert_eq!(before.resolve(start, None), None);
+
+        let at = TemplateReminder::from_reminder(
+            date("2025-03-05").and_hms_opt(8, 0, 0).unwrap(),
+            None,
+            base,
+        );
+        assert_eq!(at.resolve(start, None), date("2025-04-03").and_hms_opt(8, 0, 0));
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let template = ProjectTemplate {
+            version: TEMPLATE_VERSION + 1,
+            created_at: NaiveDateTime::default(),
+            project: TemplateProject::default(),
+            sections: vec![],
+            items: vec![],
+        };
+        let json = template.to_json().unwrap();
+        assert!(ProjectTemplate::from_json(&json).is_err());
+        assert!(ProjectTemplate::from_json("not json").is_err());
+    }
+}
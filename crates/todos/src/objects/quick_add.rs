@@ -0,0 +1,591 @@
+//! 快速添加：从一行文本解析出任务
+//!
+//! 例如 `Pay rent every month on the 1st 9am p1 #Finance /Bills @home` 解析为内容
+//! `Pay rent`，截止日期为下一个 1 号 9:00 且每月重复，优先级 1，以及对应的项目、分区和标签。
+//!
+//! 支持的写法（不区分大小写）：
+//! - 日期：`today`（`tod`）/ `tomorrow`（`tom`）/ `next week` / `next month` / 星期（`mon`、 `next
+//!   friday`）/ `in 3 days`（`weeks` / `months` / `years`）/ `on the 1st` / `jan 5`、`5 jan` /
+//!   `2024-05-01`
+//! - 时间：`9am`、`9:30pm`、`21:00`、`noon`，可带 `at` 前缀
+//! - 重复：`daily` / `weekly` / `monthly` / `yearly` / `hourly`、`every day`、`every 2 weeks`、
+//!   `every other month`、`every monday`、`every month on the 1st`
+//! - `p1` ~ `p4`：优先级（p1 最高）
+//! - `#项目`、`/分区`、`@标签`：名称通过 [`QuickAddIndex`] 解析，含空格的名称取最长匹配
+//!
+//! 同类条件只识别第一次出现（标签除外），无法识别或名称不存在的词原样保留在内容中。
+
+use std::ops::Range;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::{
+    entity::ItemModel,
+    enums::{RecurrencyType, item_priority::ItemPriority},
+    objects::DueDate,
+    utils::DateTime,
+};
+
+/// 名称最多由几个单词组成（`#Side Project`）
+const MAX_NAME_WORDS: usize = 4;
+
+/// 识别出的片段类型（用于输入框高亮）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAddTokenKind {
+    /// 日期、时间或重复规则
+    Date,
+    Priority,
+    Project,
+    Section,
+    Label,
+}
+
+/// 识别出的片段，`range` 为原文中的字节范围
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickAddToken {
+    pub kind: QuickAddTokenKind,
+    pub range: Range<usize>,
+}
+
+/// 快速添加解析所需的数据源，名称比较不区分大小写
+pub trait QuickAddIndex {
+    fn project_id(&self, name: &str) -> Option<String>;
+    /// 查找分区，返回 `(分区 ID, 所属项目 ID)`；`project_id` 为 `None` 时在全部项目中查找
+    fn section(&self, project_id: Option<&str>, name: &str) -> Option<(String, Option<String>)>;
+    fn label_id(&self, name: &str) -> Option<String>;
+}
+
+/// 不解析名称，只识别日期和优先级（如导入 Todoist 的 due 字符串）
+impl QuickAddIndex for () {
+    fn project_id(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    fn section(&self, _project_id: Option<&str>, _name: &str) -> Option<(String, Option<String>)> {
+        None
+    }
+
+    fn label_id(&self, _name: &str) -> Option<String> {
+        None
+    }
+}
+
+/// 快速添加的解析结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickAdd {
+    /// 去掉识别出的片段后的任务内容
+    pub content: String,
+    pub due: Option<DueDate>,
+    pub priority: Option<i32>,
+    pub project_id: Option<String>,
+    pub section_id: Option<String>,
+    pub label_ids: Vec<String>,
+    /// 按出现顺序排列的识别片段
+    pub tokens: Vec<QuickAddToken>,
+}
+
+impl QuickAdd {
+    /// 解析快速添加文本，`now` 用于解析相对日期
+    pub fn parse(source: &str, now: NaiveDateTime, index: &impl QuickAddIndex) -> Self {
+        let mut parser = Parser::new(source, now.date(), index);
+        parser.run();
+        parser.finish(now)
+    }
+
+    /// 是否识别出了任何属性
+    pub fn has_attributes(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// 把解析结果写入任务：只覆盖识别出的属性，标签与已有标签合并
+    pub fn apply(&self, item: &mut ItemModel) {
+        item.content = self.content.clone();
+        if let Some(due) = &self.due {
+            item.set_due_date(Some(due.clone()));
+        }
+        if let Some(priority) = self.priority {
+            item.priority = Some(priority);
+        }
+        if let Some(project_id) = &self.project_id
+            && item.project_id.as_ref() != Some(project_id)
+        {
+            item.project_id = Some(project_id.clone());
+            item.section_id = None;
+        }
+        if let Some(section_id) = &self.section_id {
+            item.section_id = Some(section_id.clone());
+        }
+        if !self.label_ids.is_empty() {
+            let mut labels: Vec<String> = item
+                .labels
+                .as_deref()
+                .unwrap_or_default()
+                .split(';')
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            for id in &self.label_ids {
+                if !labels.contains(id) {
+                    labels.push(id.clone());
+                }
+            }
+            item.labels = Some(labels.join(";"));
+        }
+    }
+}
+
+struct Word {
+    lower: String,
+    range: Range<usize>,
+}
+
+struct Parser<'a, I: QuickAddIndex> {
+    source: &'a str,
+    words: Vec<Word>,
+    consumed: Vec<bool>,
+    today: NaiveDate,
+    index: &'a I,
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    recurrence: Option<(RecurrencyType, i64)>,
+    /// 分区依赖项目，等全部解析完再处理
+    pending_sections: Vec<usize>,
+    result: QuickAdd,
+}
+
+impl<'a, I: QuickAddIndex> Parser<'a, I> {
+    fn new(source: &'a str, today: NaiveDate, index: &'a I) -> Self {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (pos, ch) in source.char_indices().chain(std::iter::once((source.len(), ' '))) {
+            match (ch.is_whitespace(), start) {
+                (true, Some(begin)) => {
+                    words
+                        .push(Word { lower: source[begin..pos].to_lowercase(), range: begin..pos });
+                    start = None;
+                },
+                (false, None) => start = Some(pos),
+                _ => {},
+            }
+        }
+        let consumed = vec![false; words.len()];
+        Self {
+            source,
+            words,
+            consumed,
+            today,
+            index,
+            date: None,
+            time: None,
+            recurrence: None,
+            pending_sections: Vec::new(),
+            result: QuickAdd::default(),
+        }
+    }
+
+    fn lower(&self, ix: usize) -> Option<&str> {
+        self.words.get(ix).map(|word| word.lower.as_str())
+    }
+
+    fn run(&mut self) {
+        let mut ix = 0;
+        while ix < self.words.len() {
+            match self.match_at(ix) {
+                Some((kind, count)) => {
+                    self.consume(ix, count, kind);
+                    ix += count;
+                },
+                None => ix += 1,
+            }
+        }
+
+        // 分区优先在解析出的项目中查找，没有项目时取分区所属的项目
+        for ix in std::mem::take(&mut self.pending_sections) {
+            if self.consumed[ix] {
+                continue;
+            }
+            let project_id = self.result.project_id.clone();
+            let found =
+                self.match_name(ix, '/', |name| self.index.section(project_id.as_deref(), name));
+            if let Some(((section_id, section_project), count)) = found {
+                self.result.section_id = Some(section_id);
+                if self.result.project_id.is_none() {
+                    self.result.project_id = section_project;
+                }
+                self.consume(ix, count, QuickAddTokenKind::Section);
+                break;
+            }
+        }
+    }
+
+    fn consume(&mut self, ix: usize, count: usize, kind: QuickAddTokenKind) {
+        self.consumed[ix..ix + count].fill(true);
+        let range = self.words[ix].range.start..self.words[ix + count - 1].range.end;
+        self.result.tokens.push(QuickAddToken { kind, range });
+    }
+
+    fn match_at(&mut self, ix: usize) -> Option<(QuickAddTokenKind, usize)> {
+        let word = self.lower(ix)?;
+        if word.starts_with('#') {
+            if self.result.project_id.is_some() {
+                return None;
+            }
+            let (id, count) = self.match_name(ix, '#', |name| self.index.project_id(name))?;
+            self.result.project_id = Some(id);
+            return Some((QuickAddTokenKind::Project, count));
+        }
+        if word.starts_with('@') {
+            let (id, count) = self.match_name(ix, '@', |name| self.index.label_id(name))?;
+            if !self.result.label_ids.contains(&id) {
+                self.result.label_ids.push(id);
+            }
+            return Some((QuickAddTokenKind::Label, count));
+        }
+        if word.starts_with('/') {
+            self.pending_sections.push(ix);
+            return None;
+        }
+        if matches!(word, "p1" | "p2" | "p3" | "p4") {
+            if self.result.priority.is_some() {
+                return None;
+            }
+            let priority = ItemPriority::parse(Some(word)) as i32;
+            self.result.priority = Some(priority);
+            return Some((QuickAddTokenKind::Priority, 1));
+        }
+
+        let count = self
+            .match_recurrence(ix)
+            .or_else(|| self.match_date(ix))
+            .or_else(|| self.match_time(ix))?;
+        Some((QuickAddTokenKind::Date, count))
+    }
+
+    /// 以 `prefix` 开头的名称，从最长的连续未识别单词开始尝试
+    fn match_name<T>(
+        &self,
+        ix: usize,
+        prefix: char,
+        lookup: impl Fn(&str) -> Option<T>,
+    ) -> Option<(T, usize)> {
+        let max = (ix..self.words.len())
+            .take(MAX_NAME_WORDS)
+            .take_while(|&i| i == ix || !self.consumed[i])
+            .count();
+        (1..=max).rev().find_map(|count| {
+            let range = self.words[ix].range.start..self.words[ix + count - 1].range.end;
+            let name = self.source[range].strip_prefix(prefix)?;
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return None;
+            }
+            lookup(&name).map(|found| (found, count))
+        })
+    }
+
+    fn match_recurrence(&mut self, ix: usize) -> Option<usize> {
+        if self.recurrence.is_some() {
+            return None;
+        }
+        let (recurrence, mut count) = match self.lower(ix)? {
+            "hourly" => ((RecurrencyType::HOURLY, 1), 1),
+            "daily" => ((RecurrencyType::EveryDay, 1), 1),
+            "weekly" => ((RecurrencyType::EveryWeek, 1), 1),
+            "monthly" => ((RecurrencyType::EveryMonth, 1), 1),
+            "yearly" | "annually" => ((RecurrencyType::EveryYear, 1), 1),
+            "every" => {
+                let (interval, skip) = match self.lower(ix + 1)? {
+                    "other" => (2, 1),
+                    word => match word.parse::<i64>() {
+                        Ok(n) if n > 0 => (n, 1),
+                        _ => (1, 0),
+                    },
+                };
+                let unit = self.lower(ix + 1 + skip)?;
+                if let Some(weekday) = parse_weekday(unit) {
+                    if self.date.is_none() {
+                        self.date = Some(next_weekday(self.today, weekday, true));
+                    }
+                    ((RecurrencyType::EveryWeek, interval), 2 + skip)
+                } else {
+                    ((parse_unit(unit)?, interval), 2 + skip)
+                }
+            },
+            _ => return None,
+        };
+
+        // every month on the 1st
+        if self.date.is_none()
+            && let Some((date, anchor)) = self.day_of_month(ix + count)
+        {
+            self.date = Some(date);
+            count += anchor;
+        }
+        self.recurrence = Some(recurrence);
+        Some(count)
+    }
+
+    fn match_date(&mut self, ix: usize) -> Option<usize> {
+        if self.date.is_some() {
+            return None;
+        }
+        let today = self.today;
+        let (date, count) = match self.lower(ix)? {
+            "today" | "tod" => (today, 1),
+            "tomorrow" | "tom" => (today + Duration::days(1), 1),
+            "next" => match self.lower(ix + 1)? {
+                "week" => (next_weekday(today, Weekday::Mon, false), 2),
+                "month" => (today.with_day(1)?.checked_add_months(Months::new(1))?, 2),
+                word => (next_weekday(today, parse_weekday(word)?, false), 2),
+            },
+            "in" => {
+                let amount: u32 = self.lower(ix + 1)?.parse().ok()?;
+                let date = match parse_unit(self.lower(ix + 2)?)? {
+                    RecurrencyType::EveryDay => today + Duration::days(amount as i64),
+                    RecurrencyType::EveryWeek => today + Duration::weeks(amount as i64),
+                    RecurrencyType::EveryMonth => today.checked_add_months(Months::new(amount))?,
+                    RecurrencyType::EveryYear => {
+                        today.checked_add_months(Months::new(amount.checked_mul(12)?))?
+                    },
+                    _ => return None,
+                };
+                (date, 3)
+            },
+            word => {
+                if let Some(weekday) = parse_weekday(word) {
+                    (next_weekday(today, weekday, true), 1)
+                } else if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                    (date, 1)
+                } else if let Some(found) = self.month_day(ix).or_else(|| self.day_of_month(ix)) {
+                    found
+                } else if word == "on" {
+                    (next_weekday(today, parse_weekday(self.lower(ix + 1)?)?, true), 2)
+                } else {
+                    return None;
+                }
+            },
+        };
+        self.date = Some(date);
+        Some(count)
+    }
+
+    fn match_time(&mut self, ix: usize) -> Option<usize> {
+        if self.time.is_some() {
+            return None;
+        }
+        let skip = usize::from(self.lower(ix)? == "at");
+        let time = parse_time(self.lower(ix + skip)?)?;
+        self.time = Some(time);
+        Some(skip + 1)
+    }
+
+    /// `jan 5` / `5th jan`，已过去的日期取明年
+    fn month_day(&self, ix: usize) -> Option<(NaiveDate, usize)> {
+        let first = self.lower(ix)?;
+        let second = self.lower(ix + 1)?;
+        let (month, day) = match parse_month(first) {
+            Some(month) => (month, parse_ordinal(second)?.0),
+            None => (parse_month(second)?, parse_ordinal(first)?.0),
+        };
+        let this_year = NaiveDate::from_ymd_opt(self.today.year(), month, day)?;
+        let date = if this_year < self.today {
+            NaiveDate::from_ymd_opt(self.today.year() + 1, month, day)?
+        } else {
+            this_year
+        };
+        Some((date, 2))
+    }
+
+    /// `on the 1st` / `the 1st` / `1st`：今天起最近的一个该日（跳过没有这一天的月份）
+    fn day_of_month(&self, ix: usize) -> Option<(NaiveDate, usize)> {
+        let mut count = 0;
+        if self.lower(ix) == Some("on") {
+            count += 1;
+        }
+        if self.lower(ix + count) == Some("the") {
+            count += 1;
+        }
+        let (day, has_suffix) = parse_ordinal(self.lower(ix + count)?)?;
+        // 裸数字不当作日期
+        if count == 0 && !has_suffix {
+            return None;
+        }
+        let start = self.today.with_day(1)?;
+        let date = (0..12).find_map(|offset| {
+            let month = start.checked_add_months(Months::new(offset))?;
+            month.with_day(day).filter(|date| *date >= self.today)
+        })?;
+        Some((date, count + 1))
+    }
+
+    fn finish(mut self, now: NaiveDateTime) -> QuickAdd {
+        self.result.tokens.sort_by_key(|token| token.range.start);
+        self.result.content = self
+            .words
+            .iter()
+            .zip(&self.consumed)
+            .filter(|(_, consumed)| !**consumed)
+            .map(|(word, _)| &self.source[word.range.clone()])
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if self.date.is_none() && self.time.is_none() && self.recurrence.is_none() {
+            return self.result;
+        }
+        let mut due = DueDate::default();
+        if let Some((recurrency_type, interval)) = self.recurrence {
+            due.is_recurring = true;
+            due.recurrency_supported = true;
+            due.recurrency_type = recurrency_type;
+            due.recurrency_interval = interval;
+        }
+        let date = self.date.unwrap_or(self.today);
+        let mut datetime = date.and_time(self.time.unwrap_or(NaiveTime::MIN));
+        // 只写了时间或重复任务的首次时间已过，顺延到下一次
+        if self.time.is_some() && datetime < now && (due.is_recurring || self.date.is_none()) {
+            datetime = if due.is_recurring {
+                DateTime::default().next_recurrency(datetime, due.clone())
+            } else {
+                datetime + Duration::days(1)
+            };
+        }
+        due.set_datetime(datetime);
+        self.result.due = Some(due);
+        self.result
+    }
+}
+
+fn next_weekday(from: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let diff = weekday.num_days_from_monday() as i64 - from.weekday().num_days_from_monday() as i64;
+    let days = match diff.rem_euclid(7) {
+        0 if !include_today => 7,
+        days => days,
+    };
+    from + Duration::days(days)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    // 至少三个字母的前缀：jan / sept / march
+    let word = word.strip_suffix('.').unwrap_or(word);
+    if word.len() < 3 {
+        return None;
+    }
+    MONTHS.iter().position(|month| month.starts_with(word)).map(|pos| pos as u32 + 1)
+}
+
+/// `1st` / `22nd` / `5`，返回日和是否带序数后缀
+fn parse_ordinal(word: &str) -> Option<(u32, bool)> {
+    let digits = word.trim_end_matches(|ch: char| ch.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !matches!(suffix, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    let day: u32 = digits.parse().ok()?;
+    (1..=31).contains(&day).then_some((day, !suffix.is_empty()))
+}
+
+fn parse_unit(word: &str) -> Option<RecurrencyType> {
+    let unit = match word {
+        "hour" | "hours" => RecurrencyType::HOURLY,
+        "day" | "days" => RecurrencyType::EveryDay,
+        "week" | "weeks" => RecurrencyType::EveryWeek,
+        "month" | "months" => RecurrencyType::EveryMonth,
+        "year" | "years" => RecurrencyType::EveryYear,
+        _ => return None,
+    };
+    Some(unit)
+}
+
+/// `9am` / `9:30pm` / `21:00` / `noon`，裸数字不当作时间
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {},
+    }
+    let (clock, pm) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(false)),
+        (_, Some(clock)) => (clock, Some(true)),
+        _ => (word, None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None if pm.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        // 2024-05-10 是星期五
+        NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("9am"), NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(parse_time("12am"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(parse_time("9:30pm"), NaiveTime::from_hms_opt(21, 30, 0));
+        assert_eq!(parse_time("21:05"), NaiveTime::from_hms_opt(21, 5, 0));
+        assert_eq!(parse_time("9"), None);
+        assert_eq!(parse_time("13pm"), None);
+        assert_eq!(parse_time("9:5"), None);
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(parse_ordinal("1st"), Some((1, true)));
+        assert_eq!(parse_ordinal("22"), Some((22, false)));
+        assert_eq!(parse_ordinal("32nd"), None);
+        assert_eq!(parse_ordinal("1x"), None);
+        assert_eq!(parse_month("jan"), Some(1));
+        assert_eq!(parse_month("september"), Some(9));
+        assert_eq!(parse_month("sept"), Some(9));
+        assert_eq!(parse_month("marc"), Some(3));
+        assert_eq!(parse_month("mayday"), None);
+
+        let friday = now().date();
+        assert_eq!(next_weekday(friday, Weekday::Fri, true), friday);
+        assert_eq!(next_weekday(friday, Weekday::Fri, false), friday + Duration::days(7));
+        assert_eq!(next_weekday(friday, Weekday::Mon, true), friday + Duration::days(3));
+    }
+}
@@ -5,8 +5,8 @@
 use std::sync::Arc;
 
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
-    QuerySelect, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, JoinType, PaginatorTrait,
+    QueryFilter, QuerySelect, RelationTrait, Set,
 };
 
 use crate::{
@@ -150,9 +150,10 @@ impl ItemLabelRepository for ItemLabelRepositoryImpl {
             return Ok(vec![]);
         }
 
-        // 查询 Label 详情
+        // 查询 Label 详情（回收站中的标签不返回）
         LabelEntity::find()
             .filter(crate::entity::labels::Column::Id.is_in(label_ids))
+            .filter(crate::entity::labels::Column::IsDeleted.eq(false))
             .all(&*self.db)
             .await
             .map_err(|e| TodoError::DatabaseError(format!("Failed to get labels: {}", e)))
@@ -306,16 +307,18 @@ impl ItemLabelRepository for ItemLabelRepositoryImpl {
 
     /// 🚀 批量获取所有 Item-Label 关联（用于解决 N+1 查询问题）
     ///
-    /// 通过一次查询获取所有关联，然后按 item_id 分组
+    /// 通过一次查询获取所有关联，然后按 item_id 分组（跳过回收站中的标签）
     async fn get_all_item_labels(
         &self,
     ) -> Result<std::collections::HashMap<String, Vec<String>>, TodoError> {
-        use crate::entity::item_labels::Column as ItemLabelColumn;
+        use crate::entity::{item_labels, item_labels::Column as ItemLabelColumn, labels};
 
         let all_associations: Vec<(String, String)> = ItemLabelEntity::find()
             .select_only()
             .column(ItemLabelColumn::ItemId)
             .column(ItemLabelColumn::LabelId)
+            .join(JoinType::InnerJoin, item_labels::Relation::Label.def())
+            .filter(labels::Column::IsDeleted.eq(false))
             .into_tuple::<(String, String)>()
             .all(&*self.db)
             .await
@@ -53,6 +53,8 @@ impl ItemRepositoryImpl {
             day_order: sea_orm::Set(item.day_order),
             checked: sea_orm::Set(item.checked),
             is_deleted: sea_orm::Set(item.is_deleted),
+            deleted_at: sea_orm::Set(item.deleted_at),
+            archived_at: sea_orm::Set(item.archived_at),
             collapsed: sea_orm::Set(item.collapsed),
             pinned: sea_orm::Set(item.pinned),
             labels: sea_orm::Set(item.labels.clone()),
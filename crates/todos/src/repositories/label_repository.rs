@@ -53,6 +53,7 @@ impl LabelRepositoryImpl {
             color: sea_orm::Set(label.color.clone()),
             item_order: sea_orm::Set(label.item_order),
             is_deleted: sea_orm::Set(label.is_deleted),
+            deleted_at: sea_orm::Set(label.deleted_at),
             is_favorite: sea_orm::Set(label.is_favorite),
             backend_type: sea_orm::Set(label.backend_type.clone()),
             source_id: sea_orm::Set(label.source_id.clone()),
@@ -49,6 +49,7 @@ impl SectionRepositoryImpl {
             section_order: sea_orm::Set(section.section_order),
             collapsed: sea_orm::Set(section.collapsed),
             is_deleted: sea_orm::Set(section.is_deleted),
+            deleted_at: sea_orm::Set(section.deleted_at),
             is_archived: sea_orm::Set(section.is_archived),
             color: sea_orm::Set(section.color.clone()),
             description: sea_orm::Set(section.description.clone()),
@@ -0,0 +1,752 @@
+//! CalDAV two-way sync
+//!
+//! 一个 CalDAV 日历集合对应一个项目（`projects.backend_type = "caldav"`，`source_id` 指向
+//! `sources` 中的记录），集合中的每个 `VTODO` 资源对应项目中的一个任务。
+//! 同步状态保存在 `sources.data`：连接配置、集合的 `ctag`，以及每个资源的
+//! `href → (UID, 任务 ID, ETag, 同步时间)`。
+//!
+//! 一次同步分两步：
+//! 1. 拉取：`ctag` 未变时跳过；否则列出所有资源的 `ETag`，只读取新增或有变化的资源。
+//!    本地修改和新的同步状态在同一个事务中写入。
+//! 2. 推送：`updated_at` 晚于上次同步时间的任务用 `If-Match` 写回，新任务用 `If-None-Match: *`
+//!    创建，已删除或移出项目的任务在服务器上删除。 服务器返回 412 时不覆盖，留到下次同步先拉取。
+//!
+//! 冲突（两边都修改了同一个任务）按修改时间解决：服务器的 `LAST-MODIFIED` 晚于本地的
+//! `updated_at` 时采用服务器版本，否则保留本地版本；时间相同或服务器没有 `LAST-MODIFIED`
+//! 时采用服务器版本。一边修改、另一边删除时保留修改。
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, TransactionTrait, prelude::Expr,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    ExportService, ImportReport, TrashService, WorkspaceExport,
+    ical_service::{
+        ICS_PRODID, IcsComponent, ItemRelations, item_component, item_label_names, read_todo,
+        unmapped_properties, utc_timestamp,
+    },
+    import_service::{RawItem, RawReminder},
+    util::{RawLabel, insert_batched, replace_item_labels, resolve_labels},
+};
+use crate::{
+    DueDate,
+    entity::{
+        ItemActiveModel, ItemModel, ProjectActiveModel, ProjectModel, ReminderActiveModel,
+        ReminderModel, SourceActiveModel, SourceModel, items, projects, reminders, sources,
+    },
+    error::TodoError,
+    objects::ical::{ICalComponent, ICalProperty},
+    sync::{CalDavClient, CalDavResource, HttpTransport, ReqwestTransport, WriteResult},
+    utils::Util,
+};
+
+/// `sources.source_type` 和 `projects.backend_type` 中 CalDAV 的取值
+pub const CALDAV_SOURCE_TYPE: &str = "caldav";
+
+/// CalDAV 日历的连接配置
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalDavConfig {
+    /// 日历集合地址，如 `https://dav.example.com/calendars/me/tasks/`
+    pub calendar_url: String,
+    pub username: String,
+    pub password: String,
+    /// 项目名称，为空时使用地址的最后一段
+    pub display_name: Option<String>,
+}
+
+impl fmt::Debug for CalDavConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CalDavConfig")
+            .field("calendar_url", &self.calendar_url)
+            .field("username", &self.username)
+            .field("display_name", &self.display_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalDavSyncReport {
+    /// 从服务器新建 / 更新 / 移入回收站的本地任务
+    pub pulled_created: usize,
+    pub pulled_updated: usize,
+    pub pulled_deleted: usize,
+    /// 在服务器上新建 / 更新 / 删除的资源
+    pub pushed_created: usize,
+    pub pushed_updated: usize,
+    pub pushed_deleted: usize,
+    /// 两边都修改过的任务（包括推送时服务器返回 412 的资源）
+    pub conflicts: usize,
+    /// 无法识别而被忽略的内容及原因
+    pub skipped: Vec<String>,
+}
+
+impl CalDavSyncReport {
+    /// 是否有任何修改
+    pub fn has_changes(&self) -> bool {
+        self.pulled_created + self.pulled_updated + self.pulled_deleted > 0
+            || self.pushed_created + self.pushed_updated + self.pushed_deleted > 0
+    }
+
+    /// 一行摘要，供界面提示
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "拉取：新建 {}、更新 {}、删除 {}；推送：新建 {}、更新 {}、删除 {}",
+            self.pulled_created,
+            self.pulled_updated,
+            self.pulled_deleted,
+            self.pushed_created,
+            self.pushed_updated,
+            self.pushed_deleted
+        );
+        if self.conflicts > 0 {
+            summary.push_str(&format!("；{} 个冲突", self.conflicts));
+        }
+        if !self.skipped.is_empty() {
+            summary.push_str(&format!("；{} 项被跳过", self.skipped.len()));
+        }
+        summary
+    }
+}
+
+/// 保存在 `sources.data` 中的同步状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncState {
+    config: CalDavConfig,
+    project_id: String,
+    /// 上次拉取时集合的 `ctag`；推送过修改后清空，下次同步重新列出
+    ctag: Option<String>,
+    /// 已同步的资源，键为资源的 `href`
+    #[serde(default)]
+    resources: BTreeMap<String, ResourceState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResourceState {
+    uid: String,
+    item_id: String,
+    etag: Option<String>,
+    /// 上次同步时任务的 `updated_at`，之后的修改需要推送
+    synced_at: NaiveDateTime,
+    /// 没有对应字段的属性（`LOCATION`、`X-` 扩展……），推送时原样写回
+    #[serde(default)]
+    extra: Vec<ICalProperty>,
+}
+
+impl SyncState {
+    fn read(source: &SourceModel) -> Result<Self, TodoError> {
+        if source.source_type != CALDAV_SOURCE_TYPE {
+            return Err(TodoError::validation(format!(
+                "同步源 {} 不是 CalDAV 日历（{}）",
+                source.id, source.source_type
+            )));
+        }
+        serde_json::from_str(source.data.as_deref().unwrap_or_default()).map_err(|e| {
+            TodoError::InternalError(format!("同步源 {} 的状态无法解析: {}", source.id, e))
+        })
+    }
+
+    /// 资源对应的 `UID`，没有同步过的任务使用任务 ID
+    fn uid_of<'a>(&'a self, item_id: &'a str) -> &'a str {
+        self.resources
+            .values()
+            .find(|resource| resource.item_id == item_id)
+            .map_or(item_id, |resource| resource.uid.as_str())
+    }
+}
+
+/// 从服务器读取到的一个任务
+struct PulledTodo {
+    href: String,
+    etag: Option<String>,
+    item: RawItem,
+    reminders: Vec<RawReminder>,
+    last_modified: Option<NaiveDateTime>,
+    extra: Vec<ICalProperty>,
+}
+
+/// Service for CalDAV sync
+#[derive(Clone, Debug)]
+pub struct CalDavService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl CalDavService {
+    /// Create a new CalDavService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 添加 CalDAV 日历：创建同步源和对应的项目，第一次同步时拉取其中的任务
+    pub async fn add_source(&self, config: CalDavConfig) -> Result<SourceModel, TodoError> {
+        let calendar_url = CalDavClient::normalize_url(&config.calendar_url)?;
+        let name = config
+            .display_name
+            .clone()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| calendar_url.trim_end_matches('/').rsplit('/').next().map(str::to_string))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "CalDAV".to_string());
+
+        let now = Utc::now().naive_utc();
+        let util = Util::get_default();
+        let project = ProjectModel {
+            id: Uuid::new_v4().to_string(),
+            name: name.clone(),
+            color: Some(util.get_color(util.get_random_color())),
+            backend_type: Some(CALDAV_SOURCE_TYPE.to_string()),
+            sync_id: Some(calendar_url.clone()),
+            ..Default::default()
+        };
+        let state = SyncState {
+            config: CalDavConfig { calendar_url, display_name: Some(name.clone()), ..config },
+            project_id: project.id.clone(),
+            ..Default::default()
+        };
+        let source = SourceModel {
+            id: Uuid::new_v4().to_string(),
+            source_type: CALDAV_SOURCE_TYPE.to_string(),
+            display_name: Some(name),
+            added_at: now,
+            updated_at: now,
+            is_visible: true,
+            child_order: None,
+            sync_server: None,
+            last_sync: None,
+            data: Some(state_json(&state)?),
+        };
+        let project = ProjectModel { source_id: Some(source.id.clone()), ..project };
+
+        let result = source.clone();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    // ID 已预先分配，不走 `ActiveModelBehavior::before_save`
+                    insert_batched(txn, vec![SourceActiveModel::from(source)]).await?;
+                    insert_batched(txn, vec![ProjectActiveModel::from(project)]).await
+                })
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// 所有 CalDAV 同步源
+    pub async fn get_sources(&self) -> Result<Vec<SourceModel>, TodoError> {
+        Ok(sources::Entity::find()
+            .filter(sources::Column::SourceType.eq(CALDAV_SOURCE_TYPE))
+            .all(&*self.db)
+            .await?)
+    }
+
+    /// 移除同步源；项目和任务保留为本地数据，服务器上的内容不受影响
+    pub async fn remove_source(&self, source_id: &str) -> Result<(), TodoError> {
+        let source = self.get_source(source_id).await?;
+        let state = SyncState::read(&source)?;
+        let source_id = source.id;
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    projects::Entity::update_many()
+                        .col_expr(projects::Column::BackendType, Expr::value(None::<String>))
+                        .col_expr(projects::Column::SourceId, Expr::value(None::<String>))
+                        .col_expr(projects::Column::SyncId, Expr::value(None::<String>))
+                        .filter(projects::Column::Id.eq(state.project_id))
+                        .exec(txn)
+                        .await?;
+                    sources::Entity::delete_by_id(source_id).exec(txn).await?;
+                    Ok(())
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 同步一个 CalDAV 日历
+    pub async fn sync(&self, source_id: &str) -> Result<CalDavSyncReport, TodoError> {
+        let state = SyncState::read(&self.get_source(source_id).await?)?;
+        let transport = ReqwestTransport::new(state.config.username, state.config.password);
+        self.sync_with_transport(source_id, Arc::new(transport)).await
+    }
+
+    /// 通过指定的传输同步（测试中使用本地的替身服务器）
+    pub async fn sync_with_transport(
+        &self,
+        source_id: &str,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Result<CalDavSyncReport, TodoError> {
+        let source = self.get_source(source_id).await?;
+        let mut state = SyncState::read(&source)?;
+        let client = CalDavClient::new(transport, &state.config.calendar_url)?;
+        let mut report = CalDavSyncReport::default();
+
+        let result = async {
+            self.pull(&client, &source.id, &mut state, &mut report).await?;
+            self.push(&client, &mut state, &mut report).await
+        }
+        .await;
+        // 推送中途失败时也保存已完成的部分，下次同步从这里继续
+        save_state(&*self.db, &source.id, &state, result.is_ok()).await?;
+        result?;
+
+        tracing::info!(
+            "CalDAV sync of {} finished: {}",
+            state.config.calendar_url,
+            report.summary()
+        );
+        Ok(report)
+    }
+
+    async fn get_source(&self, source_id: &str) -> Result<SourceModel, TodoError> {
+        sources::Entity::find_by_id(source_id)
+            .one(&*self.db)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Source").with_entity("Source", source_id))
+    }
+
+    // ==================== 拉取 ====================
+
+    async fn pull(
+        &self,
+        client: &CalDavClient,
+        source_id: &str,
+        state: &mut SyncState,
+        report: &mut CalDavSyncReport,
+    ) -> Result<(), TodoError> {
+        let ctag = client.ctag().await?;
+        if ctag.is_some() && ctag == state.ctag {
+            return Ok(());
+        }
+
+        let listing = client.list().await?;
+        let remote: HashSet<&str> = listing.iter().map(|r| r.href.as_str()).collect();
+        let changed: Vec<String> = listing
+            .iter()
+            .filter(|r| {
+                r.etag.is_none()
+                    || state.resources.get(&r.href).is_none_or(|known| known.etag != r.etag)
+            })
+            .map(|r| r.href.clone())
+            .collect();
+        let deleted: Vec<String> = state
+            .resources
+            .keys()
+            .filter(|href| !remote.contains(href.as_str()))
+            .cloned()
+            .collect();
+
+        let pulled: Vec<PulledTodo> = client
+            .multiget(&changed)
+            .await?
+            .into_iter()
+            .filter_map(|resource| read_resource(resource, &mut report.skipped))
+            .collect();
+
+        let mut next = state.clone();
+        next.ctag = ctag;
+        let source_id = source_id.to_string();
+        let pull_report = std::mem::take(report);
+        let (next, pull_report) = self
+            .db
+            .transaction::<_, (SyncState, CalDavSyncReport), TodoError>(|txn| {
+                Box::pin(async move {
+                    let pull_report =
+                        apply_pull(txn, &mut next, pulled, deleted, pull_report).await?;
+                    save_state(txn, &source_id, &next, false).await?;
+                    Ok((next, pull_report))
+                })
+            })
+            .await?;
+        *state = next;
+        *report = pull_report;
+        Ok(())
+    }
+
+    // ==================== 推送 ====================
+
+    async fn push(
+        &self,
+        client: &CalDavClient,
+        state: &mut SyncState,
+        report: &mut CalDavSyncReport,
+    ) -> Result<(), TodoError> {
+        let snapshot =
+            ExportService::new(self.db.clone()).snapshot(Some(&state.project_id)).await?;
+        let items: Vec<&ItemModel> = snapshot
+            .items
+            .iter()
+            .filter(|item| item.project_id.as_deref() == Some(state.project_id.as_str()))
+            .collect();
+        let item_ids: HashSet<&str> = items.iter().map(|item| item.id.as_str()).collect();
+        let calendar = PushCalendar::new(&snapshot, state, &item_ids);
+        let mut wrote = false;
+
+        // ---------- 修改和删除 ----------
+        let synced: Vec<(String, ResourceState)> =
+            state.resources.iter().map(|(href, r)| (href.clone(), r.clone())).collect();
+        for (href, resource) in &synced {
+            let item = items.iter().find(|item| item.id == resource.item_id);
+            let result = match item {
+                None => client.delete(href, resource.etag.as_deref()).await?,
+                Some(item) if item.updated_at > resource.synced_at => {
+                    let ics = calendar.ics(item, &resource.uid, &resource.extra);
+                    client.put(href, ics, resource.etag.as_deref()).await?
+                },
+                Some(_) => continue,
+            };
+            wrote = true;
+            match (result, item) {
+                (WriteResult::Written(_), None) => {
+                    state.resources.remove(href);
+                    report.pushed_deleted += 1;
+                },
+                (WriteResult::Written(etag), Some(item)) => {
+                    if let Some(entry) = state.resources.get_mut(href) {
+                        entry.etag = etag;
+                        entry.synced_at = item.updated_at;
+                    }
+                    report.pushed_updated += 1;
+                },
+                (WriteResult::PreconditionFailed, _) => report.conflicts += 1,
+            }
+        }
+
+        // ---------- 新任务 ----------
+        let synced_items: HashSet<&str> =
+            synced.iter().map(|(_, resource)| resource.item_id.as_str()).collect();
+        for item in items.iter().filter(|item| !synced_items.contains(item.id.as_str())) {
+            let href = client.resource_href(&item.id);
+            let ics = calendar.ics(item, &item.id, &[]);
+            wrote = true;
+            match client.put(&href, ics, None).await? {
+                WriteResult::Written(etag) => {
+                    state.resources.insert(href, ResourceState {
+                        uid: item.id.clone(),
+                        item_id: item.id.clone(),
+                        etag,
+                        synced_at: item.updated_at,
+                        extra: Vec::new(),
+                    });
+                    report.pushed_created += 1;
+                },
+                // 同名资源已存在（例如上次推送后没有收到响应），下次拉取时按 UID 对应
+                WriteResult::PreconditionFailed => report.conflicts += 1,
+            }
+        }
+
+        // 推送会改变服务器的 ctag，下次同步需要重新列出
+        if wrote {
+            state.ctag = None;
+        }
+        Ok(())
+    }
+}
+
+/// 推送时组装 `VCALENDAR` 需要的数据
+struct PushCalendar<'a> {
+    snapshot: &'a WorkspaceExport,
+    label_names: HashMap<&'a str, &'a str>,
+    /// 同步范围内任务的 `UID`（父任务关系写成 `RELATED-TO`）
+    uids: HashMap<&'a str, String>,
+    stamp: String,
+}
+
+impl<'a> PushCalendar<'a> {
+    fn new(snapshot: &'a WorkspaceExport, state: &SyncState, item_ids: &HashSet<&'a str>) -> Self {
+        Self {
+            snapshot,
+            label_names: snapshot
+                .labels
+                .iter()
+                .map(|label| (label.id.as_str(), label.name.as_str()))
+                .collect(),
+            uids: item_ids.iter().map(|id| (*id, state.uid_of(id).to_string())).collect(),
+            stamp: Utc::now().format("%Y%m%dT%H%M%SZ").to_string(),
+        }
+    }
+
+    fn ics(&self, item: &ItemModel, uid: &str, extra: &[ICalProperty]) -> String {
+        let related = ItemRelations {
+            uid,
+            labels: item_label_names(item, &self.label_names),
+            parent: item
+                .parent_id
+                .as_deref()
+                .and_then(|parent| self.uids.get(parent))
+                .map(String::as_str),
+            reminders: self
+                .snapshot
+                .reminders
+                .iter()
+                .filter(|r| r.item_id.as_deref() == Some(item.id.as_str()))
+                .collect(),
+        };
+        let mut calendar = ICalComponent::new("VCALENDAR");
+        calendar.push(ICalProperty::new("VERSION", "2.0"));
+        calendar.push(ICalProperty::new("PRODID", ICS_PRODID));
+        if let Some(mut todo) = item_component(item, IcsComponent::Todo, &self.stamp, &related) {
+            todo.properties.extend(extra.iter().cloned());
+            calendar.components.push(todo);
+        }
+        calendar.to_ics()
+    }
+}
+
+fn state_json(state: &SyncState) -> Result<String, TodoError> {
+    serde_json::to_string(state)
+        .map_err(|e| TodoError::InternalError(format!("同步状态序列化失败: {}", e)))
+}
+
+async fn save_state<C: ConnectionTrait>(
+    conn: &C,
+    source_id: &str,
+    state: &SyncState,
+    synced: bool,
+) -> Result<(), TodoError> {
+    let now = Utc::now().naive_utc();
+    let mut update = sources::Entity::update_many()
+        .col_expr(sources::Column::Data, Expr::value(state_json(state)?))
+        .col_expr(sources::Column::UpdatedAt, Expr::value(now))
+        .filter(sources::Column::Id.eq(source_id));
+    if synced {
+        update = update.col_expr(
+            sources::Column::LastSync,
+            Expr::value(now.format("%Y-%m-%d %H:%M:%S").to_string()),
+        );
+    }
+    update.exec(conn).await?;
+    Ok(())
+}
+
+/// 解析 multiget 返回的资源，无法识别的内容记录在 `skipped` 中
+fn read_resource(resource: CalDavResource, skipped: &mut Vec<String>) -> Option<PulledTodo> {
+    let data = resource.data.unwrap_or_default();
+    let roots = match ICalComponent::parse(&data) {
+        Ok(roots) => roots,
+        Err(e) => {
+            skipped.push(format!("资源 {} 无法解析：{}", resource.href, e));
+            return None;
+        },
+    };
+    let Some(todo) = roots
+        .iter()
+        .filter(|root| root.name == "VCALENDAR")
+        .flat_map(|calendar| calendar.children("VTODO"))
+        .next()
+    else {
+        skipped.push(format!("资源 {} 中没有任务（VTODO）", resource.href));
+        return None;
+    };
+
+    let mut import_report = ImportReport::default();
+    let (mut item, reminders) = read_todo(todo, &mut import_report);
+    skipped.extend(import_report.skipped);
+    if item.content.is_empty() {
+        item.content = "无标题任务".to_string();
+    }
+    Some(PulledTodo {
+        href: resource.href,
+        etag: resource.etag,
+        reminders,
+        last_modified: todo.property("LAST-MODIFIED").and_then(|p| utc_timestamp(&p.value)),
+        extra: unmapped_properties(todo).cloned().collect(),
+        item,
+    })
+}
+
+/// 在事务中写入拉取到的修改，并更新同步状态
+async fn apply_pull<C: ConnectionTrait>(
+    conn: &C,
+    state: &mut SyncState,
+    pulled: Vec<PulledTodo>,
+    deleted: Vec<String>,
+    mut report: CalDavSyncReport,
+) -> Result<CalDavSyncReport, TodoError> {
+    let now = Utc::now().naive_utc();
+    // 同步过的任务（可能已在回收站中或被移出项目）以及项目中的任务
+    let known: Vec<String> = state.resources.values().map(|r| r.item_id.clone()).collect();
+    let local: HashMap<String, ItemModel> = items::Entity::find()
+        .filter(
+            Condition::any()
+                .add(items::Column::Id.is_in(known))
+                .add(items::Column::ProjectId.eq(state.project_id.clone())),
+        )
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|item| (item.id.clone(), item))
+        .collect();
+
+    // ---------- 服务器上删除的资源 ----------
+    for href in deleted {
+        let Some(resource) = state.resources.remove(&href) else {
+            continue;
+        };
+        let Some(item) = local.get(&resource.item_id).filter(|item| !item.is_deleted) else {
+            continue;
+        };
+        if item.updated_at > resource.synced_at {
+            // 本地修改过：保留任务，推送时重新创建
+            report.conflicts += 1;
+        } else {
+            TrashService::trash_item_in_conn(conn, &item.id, now).await?;
+            report.pulled_deleted += 1;
+        }
+    }
+
+    // ---------- 新增或修改的资源 ----------
+    let names: Vec<RawLabel> = pulled
+        .iter()
+        .flat_map(|todo| &todo.item.labels)
+        .map(|name| RawLabel { name: name.clone(), ..Default::default() })
+        .collect();
+    let (label_ids, _) = resolve_labels(conn, names).await?;
+
+    let mut parents = Vec::new();
+    for todo in pulled {
+        let known = state.resources.get(&todo.href);
+        // 没有记录的资源按 UID 对应（推送后没有收到响应时，服务器上的 UID 就是任务 ID）
+        let item_id = known
+            .map(|resource| resource.item_id.clone())
+            .or_else(|| local.contains_key(&todo.item.key).then(|| todo.item.key.clone()));
+        let existing = item_id.as_ref().and_then(|id| local.get(id));
+
+        let remote_wins = match (existing, known) {
+            (None, _) => true,
+            (Some(item), Some(known)) if item.updated_at > known.synced_at => {
+                report.conflicts += 1;
+                todo.last_modified.is_none_or(|remote| remote >= item.updated_at)
+            },
+            (Some(_), Some(_)) => true,
+            (Some(item), None) => todo.last_modified.is_none_or(|remote| remote >= item.updated_at),
+        };
+        if let (false, Some(item)) = (remote_wins, existing) {
+            // 保留本地版本：记下新的 ETag，推送时覆盖服务器上的版本
+            let synced_at = known.map_or(NaiveDateTime::MIN, |resource| resource.synced_at);
+            state.resources.insert(todo.href, ResourceState {
+                uid: todo.item.key,
+                item_id: item.id.clone(),
+                etag: todo.etag,
+                synced_at,
+                extra: todo.extra,
+            });
+            continue;
+        }
+
+        let written = write_item(conn, existing, &todo, &state.project_id, &label_ids, now).await?;
+        if existing.is_some() {
+            report.pulled_updated += 1;
+        } else {
+            report.pulled_created += 1;
+        }
+        parents.push((written.id.clone(), todo.item.parent.clone()));
+        state.resources.insert(todo.href, ResourceState {
+            uid: todo.item.key,
+            item_id: written.id,
+            etag: todo.etag,
+            synced_at: written.updated_at,
+            extra: todo.extra,
+        });
+    }
+
+    // ---------- 父任务（RELATED-TO 引用的是 UID），不改变 updated_at ----------
+    let item_ids: HashMap<&str, &str> = state
+        .resources
+        .values()
+        .map(|resource| (resource.uid.as_str(), resource.item_id.as_str()))
+        .collect();
+    for (item_id, parent) in parents {
+        let parent_id = parent.and_then(|uid| item_ids.get(uid.as_str()).map(|id| id.to_string()));
+        items::Entity::update_many()
+            .col_expr(items::Column::ParentId, Expr::value(parent_id))
+            .filter(items::Column::Id.eq(item_id))
+            .exec(conn)
+            .await?;
+    }
+
+    Ok(report)
+}
+
+/// 用服务器上的版本新建或覆盖本地任务（在回收站中的任务会被恢复）
+async fn write_item<C: ConnectionTrait>(
+    conn: &C,
+    existing: Option<&ItemModel>,
+    todo: &PulledTodo,
+    project_id: &str,
+    label_ids: &HashMap<String, String>,
+    now: NaiveDateTime,
+) -> Result<ItemModel, TodoError> {
+    let raw = &todo.item;
+    let mut labels: Vec<String> = Vec::new();
+    for name in &raw.labels {
+        if let Some(id) = label_ids.get(&name.to_lowercase())
+            && !labels.contains(id)
+        {
+            labels.push(id.clone());
+        }
+    }
+
+    let mut item = existing.cloned().unwrap_or_else(|| ItemModel {
+        id: Uuid::new_v4().to_string(),
+        project_id: Some(project_id.to_string()),
+        added_at: raw.added_at.unwrap_or(now),
+        priority: Some(4),
+        ..Default::default()
+    });
+    item.content = raw.content.clone();
+    item.description = raw.description.clone();
+    item.priority = Some(raw.priority);
+    item.set_due_date(raw.due.clone());
+    item.completed_at = raw.checked.then(|| raw.completed_at.or(item.completed_at).unwrap_or(now));
+    item.checked = raw.checked;
+    item.labels = (!labels.is_empty()).then(|| labels.join(";"));
+    item.is_deleted = false;
+    item.deleted_at = None;
+    item.updated_at = now;
+
+    let item = if existing.is_some() {
+        ItemActiveModel::from(item).reset_all().update(conn).await?
+    } else {
+        insert_batched(conn, vec![ItemActiveModel::from(item.clone())]).await?;
+        item
+    };
+
+    // 标签和提醒以服务器为准整体替换
+    replace_item_labels(conn, &item.id, &labels, now).await?;
+
+    reminders::Entity::delete_many()
+        .filter(reminders::Column::ItemId.eq(item.id.clone()))
+        .exec(conn)
+        .await?;
+    let item_due = raw.due.as_ref().and_then(DueDate::datetime);
+    let reminders: Vec<ReminderActiveModel> = todo
+        .reminders
+        .iter()
+        .filter_map(|reminder| {
+            let due = reminder.due.or_else(|| {
+                Some(item_due? - Duration::minutes(i64::from(reminder.minute_offset?)))
+            })?;
+            Some(ReminderActiveModel::from(ReminderModel {
+                id: Uuid::new_v4().to_string(),
+                item_id: Some(item.id.clone()),
+                due: Some(due.format("%Y-%m-%d %H:%M:%S").to_string()),
+                reminder_type: Some("time".to_string()),
+                mm_offset: reminder.minute_offset,
+                ..Default::default()
+            }))
+        })
+        .collect();
+    insert_batched(conn, reminders).await?;
+
+    Ok(item)
+}
@@ -0,0 +1,400 @@
+//! Duplicate service for deep copies
+//!
+//! 复制任务（含子任务、标签、提醒、附件）、分区（含其中的任务）和项目（含子项目、分区、任务）。
+//! 每次复制在一个事务中完成，返回原 ID 到新 ID 的映射和新建的对象。
+//! 已删除、已归档的对象不复制；附件只复制记录，与原任务共用同一个文件。
+
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Select,
+    TransactionTrait, prelude::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    entity::{
+        AttachmentActiveModel, AttachmentModel, ItemActiveModel, ItemLabelActiveModel,
+        ItemLabelModel, ItemModel, ProjectActiveModel, ProjectModel, ReminderActiveModel,
+        ReminderModel, SectionActiveModel, SectionModel, attachments, item_labels, items, projects,
+        reminders, sections,
+    },
+    error::TodoError,
+    services::{ItemService, TrashService, project_service::depth_first, util::insert_batched},
+};
+
+/// 深拷贝的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplicateResult {
+    /// 原项目 ID → 新项目 ID
+    pub project_ids: HashMap<String, String>,
+    /// 原分区 ID → 新分区 ID
+    pub section_ids: HashMap<String, String>,
+    /// 原任务 ID → 新任务 ID
+    pub item_ids: HashMap<String, String>,
+    /// 新建的项目，父项目在前
+    pub projects: Vec<ProjectModel>,
+    pub sections: Vec<SectionModel>,
+    /// 新建的任务，父任务在前
+    pub items: Vec<ItemModel>,
+}
+
+/// Service for deep-copy operations
+#[derive(Clone, Debug)]
+pub struct DuplicateService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl DuplicateService {
+    /// Create a new DuplicateService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 复制任务及其子任务，副本放在原任务之后
+    pub async fn duplicate_item(&self, item_id: &str) -> Result<DuplicateResult, TodoError> {
+        let item_id = item_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move { Self::duplicate_item_in_conn(txn, &item_id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn duplicate_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+    ) -> Result<DuplicateResult, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let original = items::Entity::find_by_id(item_id)
+            .filter(items::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Item").with_entity("Item", item_id))?;
+        let ids = ItemService::collect_descendant_ids_in_conn(conn, item_id).await?;
+        let tree = items::Entity::find()
+            .filter(items::Column::Id.is_in(ids))
+            .filter(items::Column::IsDeleted.eq(false))
+            .all(conn)
+            .await?;
+
+        let mut result = DuplicateResult::default();
+        Self::copy_items_in_conn(conn, &tree, &mut result, now).await?;
+        // 副本与原任务同级
+        let copy_id = result.item_ids[item_id].clone();
+        items::Entity::update_many()
+            .col_expr(items::Column::ParentId, Expr::value(original.parent_id.clone()))
+            .filter(items::Column::Id.eq(&copy_id))
+            .exec(conn)
+            .await?;
+        let mut copy = result.items.remove(0);
+        copy.parent_id = original.parent_id.clone();
+        for changed in ItemService::place_after_in_conn(conn, copy.clone(), item_id, now).await? {
+            if changed.id == copy_id {
+                copy = changed;
+            }
+        }
+        result.items.insert(0, copy);
+        Ok(result)
+    }
+
+    /// 复制分区及其中的任务，副本排在原分区之后，名称加上 "(copy)"
+    pub async fn duplicate_section(&self, section_id: &str) -> Result<DuplicateResult, TodoError> {
+        let section_id = section_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move { Self::duplicate_section_in_conn(txn, &section_id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn duplicate_section_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        section_id: &str,
+    ) -> Result<DuplicateResult, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let original = sections::Entity::find_by_id(section_id)
+            .filter(sections::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Section").with_entity("Section", section_id))?;
+        let order = original.section_order.unwrap_or(0);
+        // 后面的分区整体后移一位，给副本腾出位置
+        sections::Entity::update_many()
+            .col_expr(
+                sections::Column::SectionOrder,
+                Expr::col(sections::Column::SectionOrder).add(1),
+            )
+            .filter(sections::Column::ProjectId.eq(original.project_id.clone()))
+            .filter(sections::Column::SectionOrder.gt(order))
+            .exec(conn)
+            .await?;
+
+        let mut result = DuplicateResult::default();
+        let copy = SectionModel {
+            name: format!("{} (copy)", original.name),
+            section_order: Some(order + 1),
+            ..original.clone()
+        };
+        Self::copy_sections_in_conn(conn, vec![copy], &mut result, now).await?;
+
+        let items =
+            Self::live_items().filter(items::Column::SectionId.eq(section_id)).all(conn).await?;
+        Self::copy_items_in_conn(conn, &items, &mut result, now).await?;
+        Ok(result)
+    }
+
+    /// 复制项目及其子项目、分区和任务，副本排在原项目之后，名称加上 "(copy)"
+    pub async fn duplicate_project(&self, project_id: &str) -> Result<DuplicateResult, TodoError> {
+        let project_id = project_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move { Self::duplicate_project_in_conn(txn, &project_id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn duplicate_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project_id: &str,
+    ) -> Result<DuplicateResult, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let original = projects::Entity::find_by_id(project_id)
+            .filter(projects::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Project").with_entity("Project", project_id))?;
+        let order = original.child_order.unwrap_or(0);
+        projects::Entity::update_many()
+            .col_expr(projects::Column::ChildOrder, Expr::col(projects::Column::ChildOrder).add(1))
+            .filter(match &original.parent_id {
+                Some(parent_id) => projects::Column::ParentId.eq(parent_id.clone()),
+                None => projects::Column::ParentId.is_null(),
+            })
+            .filter(projects::Column::ChildOrder.gt(order))
+            .exec(conn)
+            .await?;
+
+        // 子项目按层级顺序排列（父项目在前），跳过已删除 / 已归档的子树
+        let tree = TrashService::collect_project_tree(conn, project_id).await?;
+        let mut loaded = projects::Entity::find()
+            .filter(projects::Column::Id.is_in(tree.clone()))
+            .all(conn)
+            .await?;
+        loaded.sort_by_key(|p| tree.iter().position(|id| *id == p.id));
+        let mut result = DuplicateResult::default();
+        let mut copies = Vec::new();
+        for project in loaded {
+            let (id, parent_id, name, child_order) = if project.id == project_id {
+                (
+                    Uuid::new_v4().to_string(),
+                    project.parent_id.clone(),
+                    format!("{} (copy)", project.name),
+                    Some(order + 1),
+                )
+            } else {
+                let Some(parent_id) =
+                    project.parent_id.as_ref().and_then(|parent| result.project_ids.get(parent))
+                else {
+                    continue;
+                };
+                if project.is_deleted || project.is_archived {
+                    continue;
+                }
+                (
+                    Uuid::new_v4().to_string(),
+                    Some(parent_id.clone()),
+                    project.name.clone(),
+                    project.child_order,
+                )
+            };
+            result.project_ids.insert(project.id.clone(), id.clone());
+            copies.push(ProjectModel {
+                id,
+                name,
+                parent_id,
+                child_order,
+                is_archived: false,
+                archived_at: None,
+                sync_id: None,
+                source_id: None,
+                backend_type: None,
+                inbox_project: None,
+                ..project
+            });
+        }
+        // 批量插入不经过 before_save，保留预先分配的 ID
+        insert_batched(conn, copies.iter().cloned().map(ProjectActiveModel::from).collect())
+            .await?;
+        result.projects = copies;
+
+        let project_ids: Vec<String> = result.project_ids.keys().cloned().collect();
+        let sections = sections::Entity::find()
+            .filter(sections::Column::ProjectId.is_in(project_ids.clone()))
+            .filter(sections::Column::IsDeleted.eq(false))
+            .filter(sections::Column::IsArchived.eq(false))
+            .all(conn)
+            .await?;
+        Self::copy_sections_in_conn(conn, sections, &mut result, now).await?;
+
+        let items = Self::live_items()
+            .filter(items::Column::ProjectId.is_in(project_ids))
+            .all(conn)
+            .await?;
+        Self::copy_items_in_conn(conn, &items, &mut result, now).await?;
+        Ok(result)
+    }
+
+    /// 未删除、未归档的任务
+    fn live_items() -> Select<items::Entity> {
+        items::Entity::find()
+            .filter(items::Column::IsDeleted.eq(false))
+            .filter(items::Column::ArchivedAt.is_null())
+    }
+
+    /// 以新 ID 插入分区副本，所属项目已复制时指向新项目
+    async fn copy_sections_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        originals: Vec<SectionModel>,
+        result: &mut DuplicateResult,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let mut copies = Vec::new();
+        for section in originals {
+            let id = Uuid::new_v4().to_string();
+            result.section_ids.insert(section.id.clone(), id.clone());
+            let project_id = section
+                .project_id
+                .as_ref()
+                .map(|project| result.project_ids.get(project).unwrap_or(project).clone());
+            copies.push(SectionModel {
+                id,
+                project_id,
+                added_at: now,
+                is_archived: false,
+                archived_at: None,
+                ..section
+            });
+        }
+        insert_batched(conn, copies.iter().cloned().map(SectionActiveModel::from).collect())
+            .await?;
+        result.sections.extend(copies);
+        Ok(())
+    }
+
+    /// 以新 ID 插入任务副本及其标签、提醒和附件
+    ///
+    /// 项目 / 分区 / 父任务已复制时指向副本；父任务不在 `originals` 中时作为顶层任务。
+    /// 副本是未完成的新任务，不带原任务的后端元数据（`extra_data`），与项目副本一样不关联远端。
+    async fn copy_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        originals: &[ItemModel],
+        result: &mut DuplicateResult,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let ordered = depth_first(originals);
+        for item in &ordered {
+            result.item_ids.insert(item.id.clone(), Uuid::new_v4().to_string());
+        }
+        let copies: Vec<ItemModel> = ordered
+            .iter()
+            .map(|item| {
+                let project_id = item.project_id.as_ref().and_then(|id| result.project_ids.get(id));
+                // 项目已复制时，没有一起复制的分区不能沿用
+                let section_id = match item.section_id.as_ref() {
+                    Some(id) => result
+                        .section_ids
+                        .get(id)
+                        .cloned()
+                        .or_else(|| project_id.is_none().then(|| id.clone())),
+                    None => None,
+                };
+                ItemModel {
+                    id: result.item_ids[&item.id].clone(),
+                    project_id: project_id.cloned().or_else(|| item.project_id.clone()),
+                    section_id,
+                    parent_id: item
+                        .parent_id
+                        .as_ref()
+                        .and_then(|parent| result.item_ids.get(parent).cloned()),
+                    added_at: now,
+                    updated_at: now,
+                    day_order: None,
+                    checked: false,
+                    completed_at: None,
+                    extra_data: None,
+                    ..(*item).clone()
+                }
+            })
+            .collect();
+        insert_batched(conn, copies.iter().cloned().map(ItemActiveModel::from).collect()).await?;
+
+        let original_ids: Vec<String> = result.item_ids.keys().cloned().collect();
+        let labels = item_labels::Entity::find()
+            .filter(item_labels::Column::ItemId.is_in(original_ids.clone()))
+            .all(conn)
+            .await?;
+        insert_batched(
+            conn,
+            labels
+                .into_iter()
+                .filter_map(|link| {
+                    Some(ItemLabelActiveModel::from(ItemLabelModel {
+                        item_id: result.item_ids.get(&link.item_id)?.clone(),
+                        created_at: now,
+                        ..link
+                    }))
+                })
+                .collect(),
+        )
+        .await?;
+
+        let item_reminders = reminders::Entity::find()
+            .filter(reminders::Column::ItemId.is_in(original_ids.clone()))
+            .filter(reminders::Column::IsDeleted.eq(false))
+            .all(conn)
+            .await?;
+        insert_batched(
+            conn,
+            item_reminders
+                .into_iter()
+                .filter_map(|reminder| {
+                    let item_id = result.item_ids.get(reminder.item_id.as_ref()?)?.clone();
+                    Some(ReminderActiveModel::from(ReminderModel {
+                        id: Uuid::new_v4().to_string(),
+                        item_id: Some(item_id),
+                        notify_uid: None,
+                        ..reminder
+                    }))
+                })
+                .collect(),
+        )
+        .await?;
+
+        let item_attachments = attachments::Entity::find()
+            .filter(attachments::Column::ItemId.is_in(original_ids))
+            .all(conn)
+            .await?;
+        insert_batched(
+            conn,
+            item_attachments
+                .into_iter()
+                .filter_map(|attachment| {
+                    Some(AttachmentActiveModel::from(AttachmentModel {
+                        id: Uuid::new_v4().to_string(),
+                        item_id: result.item_ids.get(&attachment.item_id)?.clone(),
+                        ..attachment
+                    }))
+                })
+                .collect(),
+        )
+        .await?;
+
+        result.items.extend(copies);
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+//! Event service for the OEvents activity log
+//!
+//! OEvents 由初始迁移（`migrations/*/001_initial_schema.up.sql`）中的触发器写入（任务新增，
+//! 内容 / 描述 / 到期 / 优先级 / 标签 / 置顶 / 完成状态 / 分区 / 项目变化），
+//! 重复任务完成时由 [`ItemService`] 额外写入 `complete` 事件。
+//! 本服务只读，按任务、项目或时间范围返回按时间倒序的事件。
+//!
+//! [`ItemService`]: crate::services::ItemService
+
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::{entity::o_events, error::TodoError};
+
+/// OEvents 中 `event_date` 的存储格式（本地时间文本，SQLite 与 PostgreSQL 相同）
+const EVENT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 事件类型（由 `event_type` + `object_key` 推导）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    /// 新建任务
+    Created,
+    ContentChanged,
+    DescriptionChanged,
+    DueChanged,
+    PriorityChanged,
+    LabelsChanged,
+    PinnedChanged,
+    /// 完成 / 取消完成
+    CheckedChanged,
+    /// 移动到其他分区
+    SectionChanged,
+    /// 移动到其他项目
+    ProjectChanged,
+    /// 重复任务完成一次，旧值 / 新值为前后两次到期日期
+    RecurringCompleted,
+    /// 无法识别的事件（保留原始字段）
+    Other,
+}
+
+impl EventKind {
+    pub fn from_row(event_type: &str, object_key: &str) -> Self {
+        match (event_type, object_key) {
+            ("insert", _) => Self::Created,
+            ("complete", _) => Self::RecurringCompleted,
+            ("update", "content") => Self::ContentChanged,
+            ("update", "description") => Self::DescriptionChanged,
+            ("update", "due") => Self::DueChanged,
+            ("update", "priority") => Self::PriorityChanged,
+            ("update", "labels") => Self::LabelsChanged,
+            ("update", "pinned") => Self::PinnedChanged,
+            ("update", "checked") => Self::CheckedChanged,
+            ("update", "section") => Self::SectionChanged,
+            ("update", "project") => Self::ProjectChanged,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// 一条活动记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventEntry {
+    pub id: i64,
+    pub kind: EventKind,
+    /// 本地时间
+    pub event_date: Option<NaiveDateTime>,
+    pub object_id: String,
+    pub object_type: String,
+    pub object_key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub parent_item_id: Option<String>,
+    pub parent_project_id: Option<String>,
+}
+
+impl From<o_events::Model> for EventEntry {
+    fn from(model: o_events::Model) -> Self {
+        let event_type = model.event_type.unwrap_or_default();
+        let object_key = model.object_key.unwrap_or_default();
+        Self {
+            id: model.id,
+            kind: EventKind::from_row(&event_type, &object_key),
+            event_date: model
+                .event_date
+                .and_then(|date| NaiveDateTime::parse_from_str(&date, EVENT_DATE_FORMAT).ok()),
+            object_id: model.object_id.unwrap_or_default(),
+            object_type: model.object_type.unwrap_or_default(),
+            object_key,
+            old_value: model.object_old_value,
+            new_value: model.object_new_value,
+            parent_item_id: model.parent_item_id,
+            parent_project_id: model.parent_project_id,
+        }
+    }
+}
+
+/// Service for reading the activity log
+#[derive(Clone, Debug)]
+pub struct EventService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl EventService {
+    /// Create a new EventService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 获取任务的活动记录（按时间倒序）
+    pub async fn get_events_by_item(&self, item_id: &str) -> Result<Vec<EventEntry>, TodoError> {
+        self.query(o_events::Entity::find().filter(o_events::Column::ObjectId.eq(item_id)), None)
+            .await
+    }
+
+    /// 获取项目下所有任务的活动记录（按时间倒序），`limit` 为 None 时不限制条数
+    pub async fn get_events_by_project(
+        &self,
+        project_id: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<EventEntry>, TodoError> {
+        self.query(
+            o_events::Entity::find().filter(o_events::Column::ParentProjectId.eq(project_id)),
+            limit,
+        )
+        .await
+    }
+
+    /// 获取 `[start, end)` 时间范围内的活动记录（本地时间，按时间倒序）
+    pub async fn get_events_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<EventEntry>, TodoError> {
+        if start >= end {
+            return Err(TodoError::ValidationError(
+                "event range start must be earlier than end".to_string(),
+            ));
+        }
+
+        self.query(
+            o_events::Entity::find()
+                .filter(
+                    o_events::Column::EventDate.gte(start.format(EVENT_DATE_FORMAT).to_string()),
+                )
+                .filter(o_events::Column::EventDate.lt(end.format(EVENT_DATE_FORMAT).to_string())),
+            None,
+        )
+        .await
+    }
+
+    async fn query(
+        &self,
+        select: sea_orm::Select<o_events::Entity>,
+        limit: Option<u64>,
+    ) -> Result<Vec<EventEntry>, TodoError> {
+        // 同一秒内的事件按自增 ID 排序，保证顺序稳定
+        let models = select
+            .order_by_desc(o_events::Column::EventDate)
+            .order_by_desc(o_events::Column::Id)
+            .limit(limit)
+            .all(&*self.db)
+            .await?;
+
+        Ok(models.into_iter().map(EventEntry::from).collect())
+    }
+}
@@ -0,0 +1,465 @@
+//! Export service for the whole workspace or a single project
+//!
+//! 三种格式：
+//! - JSON：带版本号的完整数据（项目、分区、任务、标签、提醒），可用 [`WorkspaceExport::from_json`]
+//!   原样读回；
+//! - CSV：每个任务一行，项目 / 分区 / 标签用名称表示，便于在表格软件中查看；
+//! - Markdown：按项目和分区分标题的待办清单，子任务按层级缩进。
+//!
+//! 回收站中的对象不导出；导出单个项目时包含其子项目。
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use chrono::{NaiveDateTime, NaiveTime};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DueDate,
+    entity::{
+        ItemModel, LabelModel, ProjectModel, ReminderModel, SectionModel, item_labels, items,
+        labels, projects, reminders, sections,
+    },
+    enums::RecurrencyType,
+    error::TodoError,
+};
+
+/// JSON 导出格式的版本号，结构变化时递增
+pub const EXPORT_VERSION: u32 = 1;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Markdown => "Markdown",
+        }
+    }
+}
+
+/// 导出的数据快照（JSON 格式的内容）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceExport {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    pub projects: Vec<ProjectModel>,
+    pub sections: Vec<SectionModel>,
+    pub items: Vec<ItemModel>,
+    pub labels: Vec<LabelModel>,
+    pub reminders: Vec<ReminderModel>,
+}
+
+impl WorkspaceExport {
+    /// 读回 JSON 导出，拒绝更新版本写出的文件
+    pub fn from_json(json: &str) -> Result<Self, TodoError> {
+        let export: Self = serde_json::from_str(json)
+            .map_err(|e| TodoError::validation(format!("不是有效的导出文件: {}", e)))?;
+        if export.version > EXPORT_VERSION {
+            return Err(TodoError::validation(format!(
+                "导出文件版本 {} 高于当前支持的版本 {}",
+                export.version, EXPORT_VERSION
+            )));
+        }
+        Ok(export)
+    }
+
+    pub fn to_json(&self) -> Result<String, TodoError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| TodoError::InternalError(format!("JSON 序列化失败: {}", e)))
+    }
+
+    /// 每个任务一行
+    pub fn to_csv(&self) -> Result<String, TodoError> {
+        let csv_error = |e: csv::Error| TodoError::InternalError(format!("CSV 写入失败: {}", e));
+        let projects = self.project_paths();
+        let sections: HashMap<&str, &str> =
+            self.sections.iter().map(|s| (s.id.as_str(), s.name.as_str())).collect();
+        let labels = self.label_names();
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record([
+                "id",
+                "content",
+                "description",
+                "project",
+                "section",
+                "parent_id",
+                "labels",
+                "priority",
+                "due",
+                "recurrence",
+                "checked",
+                "completed_at",
+                "added_at",
+            ])
+            .map_err(csv_error)?;
+        for item in &self.items {
+            let due = item.due_date();
+            writer
+                .write_record([
+                    item.id.clone(),
+                    item.content.clone(),
+                    item.description.clone().unwrap_or_default(),
+                    item.project_id
+                        .as_deref()
+                        .and_then(|id| projects.get(id).cloned())
+                        .unwrap_or_else(|| "Inbox".to_string()),
+                    item.section_id
+                        .as_deref()
+                        .and_then(|id| sections.get(id))
+                        .map(|name| name.to_string())
+                        .unwrap_or_default(),
+                    item.parent_id.clone().unwrap_or_default(),
+                    item_label_ids(item)
+                        .filter_map(|id| labels.get(id).copied())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    item.priority.map(|p| p.to_string()).unwrap_or_default(),
+                    due.as_ref().and_then(format_due).unwrap_or_default(),
+                    due.as_ref().and_then(format_recurrence).unwrap_or_default(),
+                    item.checked.to_string(),
+                    item.completed_at.map(|dt| dt.to_string()).unwrap_or_default(),
+                    item.added_at.to_string(),
+                ])
+                .map_err(csv_error)?;
+        }
+
+        let data = writer
+            .into_inner()
+            .map_err(|e| TodoError::InternalError(format!("CSV 写入失败: {}", e)))?;
+        String::from_utf8(data).map_err(|e| TodoError::InternalError(e.to_string()))
+    }
+
+    /// 按项目 / 分区分标题的待办清单
+    pub fn to_markdown(&self) -> String {
+        let labels = self.label_names();
+        let mut children: HashMap<Option<&str>, Vec<&ItemModel>> = HashMap::new();
+        let item_ids: HashSet<&str> = self.items.iter().map(|i| i.id.as_str()).collect();
+        for item in &self.items {
+            // 父任务不在导出范围内时作为顶层任务
+            let parent = item.parent_id.as_deref().filter(|id| item_ids.contains(id));
+            children.entry(parent).or_default().push(item);
+        }
+        let top_level = children.get(&None).cloned().unwrap_or_default();
+
+        let mut out = String::new();
+        let inbox: Vec<_> =
+            top_level.iter().copied().filter(|item| item.project_id.is_none()).collect();
+        if !inbox.is_empty() {
+            out.push_str("# Inbox\n\n");
+            write_markdown_items(&mut out, &inbox, &children, &labels);
+        }
+
+        let paths = self.project_paths();
+        for project in &self.projects {
+            let in_project =
+                |item: &&ItemModel| item.project_id.as_deref() == Some(project.id.as_str());
+            let project_sections: Vec<_> = self
+                .sections
+                .iter()
+                .filter(|s| s.project_id.as_deref() == Some(project.id.as_str()))
+                .collect();
+            let section_ids: HashSet<&str> =
+                project_sections.iter().map(|s| s.id.as_str()).collect();
+
+            out.push_str(&format!("# {}\n\n", paths[project.id.as_str()]));
+            let no_section: Vec<_> = top_level
+                .iter()
+                .copied()
+                .filter(in_project)
+                .filter(|item| {
+                    item.section_id.as_deref().is_none_or(|id| !section_ids.contains(id))
+                })
+                .collect();
+            write_markdown_items(&mut out, &no_section, &children, &labels);
+
+            for section in project_sections {
+                let section_items: Vec<_> = top_level
+                    .iter()
+                    .copied()
+                    .filter(in_project)
+                    .filter(|item| item.section_id.as_deref() == Some(section.id.as_str()))
+                    .collect();
+                out.push_str(&format!("## {}\n\n", section.name));
+                write_markdown_items(&mut out, &section_items, &children, &labels);
+            }
+        }
+
+        let mut markdown = out.trim_end().to_string();
+        markdown.push('\n');
+        markdown
+    }
+
+    /// 项目 ID → 含父项目的路径（`Work / Client`）
+    fn project_paths(&self) -> HashMap<&str, String> {
+        let by_id: HashMap<&str, &ProjectModel> =
+            self.projects.iter().map(|p| (p.id.as_str(), p)).collect();
+        self.projects
+            .iter()
+            .map(|project| {
+                let mut names = vec![project.name.as_str()];
+                let mut seen = HashSet::from([project.id.as_str()]);
+                let mut parent = project.parent_id.as_deref();
+                while let Some(parent_project) = parent.and_then(|id| by_id.get(id)) {
+                    if !seen.insert(parent_project.id.as_str()) {
+                        break;
+                    }
+                    names.push(parent_project.name.as_str());
+                    parent = parent_project.parent_id.as_deref();
+                }
+                names.reverse();
+                (project.id.as_str(), names.join(" / "))
+            })
+            .collect()
+    }
+
+    fn label_names(&self) -> HashMap<&str, &str> {
+        self.labels.iter().map(|l| (l.id.as_str(), l.name.as_str())).collect()
+    }
+}
+
+/// Service for exporting workspace data
+#[derive(Clone, Debug)]
+pub struct ExportService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl ExportService {
+    /// Create a new ExportService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 收集导出数据；`project_id` 为 `None` 时导出整个工作区（含收件箱）
+    pub async fn snapshot(&self, project_id: Option<&str>) -> Result<WorkspaceExport, TodoError> {
+        let mut projects = projects::Entity::find()
+            .filter(projects::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
+        let mut sections = sections::Entity::find()
+            .filter(sections::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
+        let mut items =
+            items::Entity::find().filter(items::Column::IsDeleted.eq(false)).all(&*self.db).await?;
+        let mut labels = labels::Entity::find()
+            .filter(labels::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
+
+        if let Some(project_id) = project_id {
+            let scope = project_tree(&projects, project_id).ok_or_else(|| {
+                TodoError::not_found("Project").with_entity("Project", project_id)
+            })?;
+            let in_scope = |id: &Option<String>| id.as_ref().is_some_and(|id| scope.contains(id));
+            projects.retain(|p| scope.contains(&p.id));
+            sections.retain(|s| in_scope(&s.project_id));
+            items.retain(|i| in_scope(&i.project_id));
+        }
+
+        // 标签以关联表为准
+        let item_ids: Vec<String> = items.iter().map(|i| i.id.clone()).collect();
+        let mut labels_by_item: HashMap<String, Vec<String>> = HashMap::new();
+        for row in item_labels::Entity::find()
+            .filter(item_labels::Column::ItemId.is_in(item_ids.clone()))
+            .all(&*self.db)
+            .await?
+        {
+            labels_by_item.entry(row.item_id).or_default().push(row.label_id);
+        }
+        let label_set: HashSet<&str> = labels.iter().map(|l| l.id.as_str()).collect();
+        for item in &mut items {
+            let mut ids = labels_by_item.remove(&item.id).unwrap_or_default();
+            ids.retain(|id| label_set.contains(id.as_str()));
+            ids.sort();
+            item.labels = (!ids.is_empty()).then(|| ids.join(";"));
+        }
+        if project_id.is_some() {
+            let used: HashSet<&str> = items.iter().flat_map(item_label_ids).collect();
+            labels.retain(|l| used.contains(l.id.as_str()));
+        }
+
+        let reminders = reminders::Entity::find()
+            .filter(reminders::Column::ItemId.is_in(item_ids))
+            .filter(reminders::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
+
+        projects.sort_by_key(|p| (p.child_order.unwrap_or(i32::MAX), p.name.clone()));
+        sections.sort_by_key(|s| (s.section_order.unwrap_or(i32::MAX), s.added_at));
+        items.sort_by_key(|i| (i.child_order.unwrap_or(i32::MAX), i.added_at));
+        labels.sort_by_key(|l| (l.item_order, l.name.clone()));
+
+        Ok(WorkspaceExport {
+            version: EXPORT_VERSION,
+            exported_at: chrono::Utc::now().naive_utc(),
+            projects,
+            sections,
+            items,
+            labels,
+            reminders,
+        })
+    }
+
+    /// 导出为指定格式的文本
+    pub async fn export(
+        &self,
+        format: ExportFormat,
+        project_id: Option<&str>,
+    ) -> Result<String, TodoError> {
+        let snapshot = self.snapshot(project_id).await?;
+        match format {
+            ExportFormat::Json => snapshot.to_json(),
+            ExportFormat::Csv => snapshot.to_csv(),
+            ExportFormat::Markdown => Ok(snapshot.to_markdown()),
+        }
+    }
+
+    /// 导出到文件
+    pub async fn export_to_file(
+        &self,
+        path: &Path,
+        format: ExportFormat,
+        project_id: Option<&str>,
+    ) -> Result<(), TodoError> {
+        let content = self.export(format, project_id).await?;
+        std::fs::write(path, content).map_err(|e| {
+            TodoError::InternalError(format!("无法写入导出文件 {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// 项目及其所有子项目的 ID；项目不存在时返回 `None`
+fn project_tree(projects: &[ProjectModel], root_id: &str) -> Option<HashSet<String>> {
+    projects.iter().find(|p| p.id == root_id)?;
+    let mut tree = HashSet::from([root_id.to_string()]);
+    loop {
+        let before = tree.len();
+        for project in projects {
+            if project.parent_id.as_ref().is_some_and(|parent| tree.contains(parent)) {
+                tree.insert(project.id.clone());
+            }
+        }
+        if tree.len() == before {
+            return Some(tree);
+        }
+    }
+}
+
+fn item_label_ids(item: &ItemModel) -> impl Iterator<Item = &str> {
+    item.labels.as_deref().unwrap_or_default().split(';').filter(|id| !id.is_empty())
+}
+
+/// 没有具体时间（00:00）时只写日期
+fn format_due(due: &DueDate) -> Option<String> {
+    let datetime = due.datetime()?;
+    let formatted = if datetime.time() == NaiveTime::MIN {
+        datetime.format("%Y-%m-%d").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M").to_string()
+    };
+    Some(formatted)
+}
+
+/// 写成快速添加能识别的形式（`every 2 weeks`）
+fn format_recurrence(due: &DueDate) -> Option<String> {
+    if !due.is_recurring {
+        return None;
+    }
+    let unit = match due.recurrency_type {
+        RecurrencyType::MINUTELY => "minute",
+        RecurrencyType::HOURLY => "hour",
+        RecurrencyType::EveryDay => "day",
+        RecurrencyType::EveryWeek => "week",
+        RecurrencyType::EveryMonth => "month",
+        RecurrencyType::EveryYear => "year",
+        RecurrencyType::NONE => return None,
+    };
+    Some(match due.recurrency_interval {
+        0 | 1 => format!("every {}", unit),
+        interval => format!("every {} {}s", interval, unit),
+    })
+}
+
+/// 一组任务后空一行；没有任务时不输出
+fn write_markdown_items(
+    out: &mut String,
+    items: &[&ItemModel],
+    children: &HashMap<Option<&str>, Vec<&ItemModel>>,
+    labels: &HashMap<&str, &str>,
+) {
+    if items.is_empty() {
+        return;
+    }
+    for item in items {
+        write_markdown_item(out, item, 0, children, labels);
+    }
+    out.push('\n');
+}
+
+fn write_markdown_item(
+    out: &mut String,
+    item: &ItemModel,
+    depth: usize,
+    children: &HashMap<Option<&str>, Vec<&ItemModel>>,
+    labels: &HashMap<&str, &str>,
+) {
+    let indent = "  ".repeat(depth);
+    let mut line =
+        format!("{}- [{}] {}", indent, if item.checked { "x" } else { " " }, item.content);
+
+    let due = item.due_date();
+    let mut meta: Vec<String> = Vec::new();
+    if let Some(due_text) = due.as_ref().and_then(format_due) {
+        meta.push(format!("due {}", due_text));
+    }
+    if let Some(recurrence) = due.as_ref().and_then(format_recurrence) {
+        meta.push(recurrence);
+    }
+    if let Some(priority @ 1..=3) = item.priority {
+        meta.push(format!("p{}", priority));
+    }
+    meta.extend(
+        item_label_ids(item).filter_map(|id| labels.get(id)).map(|name| format!("@{}", name)),
+    );
+    if !meta.is_empty() {
+        line.push_str(&format!(" · {}", meta.join(" · ")));
+    }
+    out.push_str(&line);
+    out.push('\n');
+
+    // 描述作为列表项的续行
+    for description_line in item.description.as_deref().unwrap_or_default().lines() {
+        if description_line.trim().is_empty() {
+            continue;
+        }
+        out.push_str(&format!("{}  {}\n", indent, description_line.trim_end()));
+    }
+
+    for child in children.get(&Some(item.id.as_str())).into_iter().flatten() {
+        write_markdown_item(out, child, depth + 1, children, labels);
+    }
+}
@@ -0,0 +1,78 @@
+//! Filter service for saved filters
+//!
+//! 保存的过滤器只存名称和表达式，任务匹配在内存中完成（见 [`FilterQuery`]）。
+//! 保存前校验表达式，避免把无法解析的过滤器写入数据库。
+//!
+//! [`FilterQuery`]: crate::FilterQuery
+
+use std::sync::Arc;
+
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, Set};
+
+use crate::{
+    FilterQuery,
+    entity::{FilterActiveModel, FilterModel, filters, prelude::*},
+    error::TodoError,
+};
+
+/// Service for saved filter operations
+#[derive(Clone, Debug)]
+pub struct FilterService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl FilterService {
+    /// Create a new FilterService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// Insert a new filter
+    pub async fn insert_filter(&self, filter: FilterModel) -> Result<FilterModel, TodoError> {
+        Self::validate(&filter)?;
+        let active_filter: FilterActiveModel = filter.into();
+        let filter_model = active_filter.insert(&*self.db).await?;
+        Ok(filter_model)
+    }
+
+    /// Update an existing filter
+    pub async fn update_filter(&self, filter: FilterModel) -> Result<FilterModel, TodoError> {
+        Self::validate(&filter)?;
+        let active_filter = FilterActiveModel {
+            id: Set(filter.id),
+            name: Set(filter.name),
+            query: Set(filter.query),
+            color: Set(filter.color),
+            item_order: Set(filter.item_order),
+            is_favorite: Set(filter.is_favorite),
+        };
+
+        active_filter.update(&*self.db).await.map_err(TodoError::from)
+    }
+
+    /// Delete a filter（过滤器不含数据，直接删除，不进回收站）
+    pub async fn delete_filter(&self, id: &str) -> Result<u64, TodoError> {
+        let result = FilterEntity::delete_by_id(id.to_string()).exec(&*self.db).await?;
+        if result.rows_affected == 0 {
+            return Err(TodoError::NotFound(format!("filter {}", id)));
+        }
+        Ok(result.rows_affected)
+    }
+
+    /// Get all filters，按 item_order、名称排序
+    pub async fn get_all_filters(&self) -> Result<Vec<FilterModel>, TodoError> {
+        let filters = FilterEntity::find()
+            .order_by_asc(filters::Column::ItemOrder)
+            .order_by_asc(filters::Column::Name)
+            .all(&*self.db)
+            .await?;
+        Ok(filters)
+    }
+
+    fn validate(filter: &FilterModel) -> Result<(), TodoError> {
+        if filter.name.trim().is_empty() {
+            return Err(TodoError::ValidationError("Filter name is empty".to_string()));
+        }
+        FilterQuery::parse(&filter.query).map(|_| ())
+    }
+}
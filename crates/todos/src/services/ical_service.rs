@@ -0,0 +1,491 @@
+//! iCalendar (ICS) export and VTODO import
+//!
+//! 导出：有截止时间的任务写成 `VTODO`（任务类应用）或 `VEVENT`（日历应用），
+//! 重复规则写成 `RRULE`，提醒写成 `VALARM`，标签写成 `CATEGORIES`，父任务写成 `RELATED-TO`。
+//! 时间按浮动时间（不带时区）输出，00:00 的任务按全天日期输出。
+//!
+//! 导入：读取 `.ics` 中的 `VTODO` 放入收件箱，复用 Todoist 导入的写入流程，
+//! 在一个事务中完成；`VEVENT` 等其他组件和无法映射的属性统计在 [`ImportReport`] 中。
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use chrono::{Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    ExportService, ImportReport, WorkspaceExport,
+    import_service::{ImportData, RawItem, RawReminder},
+};
+use crate::{
+    DueDate,
+    entity::{ItemModel, ReminderModel},
+    error::TodoError,
+    objects::ical::{
+        ICalComponent, ICalProperty, escape_text, format_offset_duration, parse_duration,
+    },
+    utils::DateTime,
+};
+
+pub const ICS_PRODID: &str = "-//mytool//mytool//EN";
+
+/// UTC 时间（`DTSTAMP`、`COMPLETED`、`LAST-MODIFIED`）的格式
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// 导入时直接映射或可以忽略的 `VTODO` 属性
+const TODO_PROPERTIES: &[&str] = &[
+    "UID",
+    "DTSTAMP",
+    "SUMMARY",
+    "DESCRIPTION",
+    "DUE",
+    "DTSTART",
+    "RRULE",
+    "PRIORITY",
+    "CATEGORIES",
+    "STATUS",
+    "COMPLETED",
+    "CREATED",
+    "LAST-MODIFIED",
+    "SEQUENCE",
+    "RELATED-TO",
+    "PERCENT-COMPLETE",
+];
+
+/// 导出的日历组件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IcsComponent {
+    /// 任务（`VTODO`），任务类应用可以同步完成状态
+    Todo,
+    /// 日程（`VEVENT`），多数日历应用只显示这一类
+    Event,
+}
+
+impl IcsComponent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IcsComponent::Todo => "VTODO",
+            IcsComponent::Event => "VEVENT",
+        }
+    }
+}
+
+/// Service for iCalendar export / import
+#[derive(Clone, Debug)]
+pub struct IcalService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl IcalService {
+    /// Create a new IcalService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 导出有截止时间的任务（整个工作区或指定项目）为 iCalendar 文本
+    pub async fn export_ics(
+        &self,
+        component: IcsComponent,
+        project_id: Option<&str>,
+    ) -> Result<String, TodoError> {
+        let snapshot = ExportService::new(self.db.clone()).snapshot(project_id).await?;
+        let name = match project_id {
+            Some(id) => snapshot.projects.iter().find(|p| p.id == id).map(|p| p.name.as_str()),
+            None => None,
+        };
+        Ok(to_calendar(&snapshot, component, name.unwrap_or("mytool")).to_ics())
+    }
+
+    /// 导出到 `.ics` 文件
+    pub async fn export_ics_to_file(
+        &self,
+        path: &Path,
+        component: IcsComponent,
+        project_id: Option<&str>,
+    ) -> Result<(), TodoError> {
+        let content = self.export_ics(component, project_id).await?;
+        std::fs::write(path, content).map_err(|e| {
+            TodoError::InternalError(format!("无法写入导出文件 {}: {}", path.display(), e))
+        })
+    }
+
+    /// 导入 `.ics` 文件中的任务
+    pub async fn import_ics(&self, path: &Path) -> Result<ImportReport, TodoError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            TodoError::validation(format!("无法读取日历文件 {}: {}", path.display(), e))
+        })?;
+        self.import_ics_str(&content).await
+    }
+
+    /// 导入 iCalendar 文本中的 `VTODO`，所有对象在同一个事务中写入
+    pub async fn import_ics_str(&self, content: &str) -> Result<ImportReport, TodoError> {
+        let mut report = ImportReport::default();
+        let data = read_calendar(content, &mut report)?;
+        let report = data.import(&self.db, report).await?;
+
+        tracing::info!("iCalendar import finished: {}", report.summary());
+        Ok(report)
+    }
+}
+
+// ==================== 导出 ====================
+
+fn to_calendar(export: &WorkspaceExport, component: IcsComponent, name: &str) -> ICalComponent {
+    let stamp = Utc::now().format(UTC_FORMAT).to_string();
+    let label_names: HashMap<&str, &str> =
+        export.labels.iter().map(|label| (label.id.as_str(), label.name.as_str())).collect();
+    let exported: HashSet<&str> = export
+        .items
+        .iter()
+        .filter(|item| item.due_date().is_some_and(|due| due.datetime().is_some()))
+        .map(|item| item.id.as_str())
+        .collect();
+
+    let mut calendar = ICalComponent::new("VCALENDAR");
+    calendar.push(ICalProperty::new("VERSION", "2.0"));
+    calendar.push(ICalProperty::new("PRODID", ICS_PRODID));
+    calendar.push(ICalProperty::new("CALSCALE", "GREGORIAN"));
+    calendar.push(ICalProperty::text("X-WR-CALNAME", name));
+
+    calendar.components.extend(
+        export.items.iter().filter_map(|item| {
+            to_component(item, component, &stamp, export, &exported, &label_names)
+        }),
+    );
+    calendar
+}
+
+fn to_component(
+    item: &ItemModel,
+    component: IcsComponent,
+    stamp: &str,
+    export: &WorkspaceExport,
+    exported: &HashSet<&str>,
+    label_names: &HashMap<&str, &str>,
+) -> Option<ICalComponent> {
+    if !exported.contains(item.id.as_str()) {
+        return None;
+    }
+    let related = ItemRelations {
+        uid: &item.id,
+        labels: item_label_names(item, label_names),
+        parent: item.parent_id.as_deref().filter(|parent| exported.contains(parent)),
+        reminders: export
+            .reminders
+            .iter()
+            .filter(|r| r.item_id.as_deref() == Some(item.id.as_str()))
+            .collect(),
+    };
+    item_component(item, component, stamp, &related)
+}
+
+/// 任务写成 iCalendar 组件时需要的关联数据
+pub(super) struct ItemRelations<'a> {
+    pub(super) uid: &'a str,
+    pub(super) labels: Vec<&'a str>,
+    /// 父任务的 `UID`
+    pub(super) parent: Option<&'a str>,
+    pub(super) reminders: Vec<&'a ReminderModel>,
+}
+
+/// 任务的标签名称（`labels` 中保存的是以 `;` 分隔的标签 ID）
+pub(super) fn item_label_names<'a>(
+    item: &ItemModel,
+    label_names: &HashMap<&str, &'a str>,
+) -> Vec<&'a str> {
+    item.labels
+        .as_deref()
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(|id| label_names.get(id).copied())
+        .collect()
+}
+
+/// 把任务写成 `VTODO` / `VEVENT`；没有截止时间的任务只能写成 `VTODO`
+pub(super) fn item_component(
+    item: &ItemModel,
+    component: IcsComponent,
+    stamp: &str,
+    related: &ItemRelations,
+) -> Option<ICalComponent> {
+    let due = item.due_date();
+    let start = due.as_ref().and_then(DueDate::datetime);
+    if component == IcsComponent::Event && start.is_none() {
+        return None;
+    }
+
+    let date_only = start.is_some_and(|start| start.time() == NaiveTime::MIN);
+    let date_property = |name: &str, datetime: NaiveDateTime| {
+        let value = DateTime::default().get_ical_datetime_format(&datetime, date_only);
+        let property = ICalProperty::new(name, value);
+        if date_only { property.with_param("VALUE", "DATE") } else { property }
+    };
+
+    let mut todo = ICalComponent::new(component.name());
+    todo.push(ICalProperty::new("UID", related.uid));
+    todo.push(ICalProperty::new("DTSTAMP", stamp));
+    todo.push(ICalProperty::new("LAST-MODIFIED", item.updated_at.format(UTC_FORMAT).to_string()));
+    todo.push(ICalProperty::text("SUMMARY", &item.content));
+    if let Some(description) = item.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        todo.push(ICalProperty::text("DESCRIPTION", description));
+    }
+    let rrule = due.as_ref().filter(|_| start.is_some()).and_then(DueDate::rrule);
+    match (component, start) {
+        (_, None) => {},
+        (IcsComponent::Todo, Some(start)) => {
+            // 重复规则以 DTSTART 为起点，重复的任务同时写上 DTSTART
+            if rrule.is_some() {
+                todo.push(date_property("DTSTART", start));
+            }
+            todo.push(date_property("DUE", start));
+        },
+        // 全天日程到第二天结束，其余默认一小时
+        (IcsComponent::Event, Some(start)) => {
+            let end = if date_only { Duration::days(1) } else { Duration::hours(1) };
+            todo.push(date_property("DTSTART", start));
+            todo.push(date_property("DTEND", start + end));
+        },
+    }
+    if let Some(rrule) = rrule {
+        todo.push(ICalProperty::new("RRULE", rrule));
+    }
+    // 本地 1 最高；iCalendar 1 最高、9 最低、0 未定义
+    if let Some(priority) = match item.priority {
+        Some(1) => Some("1"),
+        Some(2) => Some("5"),
+        Some(3) => Some("9"),
+        _ => None,
+    } {
+        todo.push(ICalProperty::new("PRIORITY", priority));
+    }
+    if !related.labels.is_empty() {
+        let categories: Vec<String> =
+            related.labels.iter().map(|label| escape_text(label)).collect();
+        todo.push(ICalProperty::new("CATEGORIES", categories.join(",")));
+    }
+    if let Some(parent) = related.parent {
+        todo.push(ICalProperty::new("RELATED-TO", parent));
+    }
+    if component == IcsComponent::Todo {
+        todo.push(ICalProperty::new(
+            "STATUS",
+            if item.checked { "COMPLETED" } else { "NEEDS-ACTION" },
+        ));
+        if let Some(completed_at) = item.completed_at.filter(|_| item.checked) {
+            todo.push(ICalProperty::new("COMPLETED", completed_at.format(UTC_FORMAT).to_string()));
+        }
+    }
+
+    for reminder in &related.reminders {
+        let reminder_due = reminder
+            .due
+            .as_deref()
+            .and_then(|due| NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M:%S").ok());
+        let offset = reminder
+            .mm_offset
+            .map(i64::from)
+            .or_else(|| Some((start? - reminder_due?).num_minutes()));
+        let trigger = match (offset, reminder_due) {
+            (Some(minutes), _) if minutes >= 0 => {
+                ICalProperty::new("TRIGGER", format_offset_duration(minutes))
+            },
+            // 晚于截止时间的提醒按绝对时间（UTC）输出
+            (_, Some(reminder_due)) => {
+                let Some(utc) = Local.from_local_datetime(&reminder_due).earliest() else {
+                    continue;
+                };
+                ICalProperty::new("TRIGGER", utc.with_timezone(&Utc).format(UTC_FORMAT).to_string())
+                    .with_param("VALUE", "DATE-TIME")
+            },
+            _ => continue,
+        };
+        let mut alarm = ICalComponent::new("VALARM");
+        alarm.push(ICalProperty::new("ACTION", "DISPLAY"));
+        alarm.push(ICalProperty::text("DESCRIPTION", &item.content));
+        alarm.push(trigger);
+        todo.components.push(alarm);
+    }
+    Some(todo)
+}
+
+// ==================== 导入 ====================
+
+fn read_calendar(content: &str, report: &mut ImportReport) -> Result<ImportData, TodoError> {
+    let roots = ICalComponent::parse(content)?;
+    let calendars: Vec<&ICalComponent> =
+        roots.iter().filter(|root| root.name == "VCALENDAR").collect();
+    if calendars.is_empty() {
+        return Err(TodoError::validation("不是有效的 iCalendar 文件（缺少 VCALENDAR）"));
+    }
+
+    let mut data = ImportData::default();
+    for component in calendars.iter().flat_map(|calendar| &calendar.components) {
+        match component.name.as_str() {
+            "VTODO" => {
+                let (item, reminders) = read_todo(component, report);
+                data.items.push(item);
+                data.reminders.extend(reminders);
+            },
+            "VTIMEZONE" => {},
+            other => report.unmapped(format!("ics.{}", other)),
+        }
+    }
+    if data.items.is_empty() {
+        return Err(TodoError::validation("iCalendar 文件中没有任务（VTODO）"));
+    }
+    Ok(data)
+}
+
+/// 读取一个 `VTODO`，`key` 为它的 `UID`（缺失时随机生成）
+pub(super) fn read_todo(
+    todo: &ICalComponent,
+    report: &mut ImportReport,
+) -> (RawItem, Vec<RawReminder>) {
+    let dt = DateTime::default();
+    let key = todo
+        .property("UID")
+        .map(|uid| uid.value.trim().to_string())
+        .filter(|uid| !uid.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let content = todo.property("SUMMARY").map(|summary| summary.text_value()).unwrap_or_default();
+    let content = content.trim().to_string();
+
+    for property in unmapped_properties(todo) {
+        if !property.name.starts_with("X-") {
+            report.unmapped(format!("ics.VTODO.{}", property.name));
+        }
+    }
+
+    let mut due = None;
+    if let Some(property) = todo.property("DUE").or_else(|| todo.property("DTSTART")) {
+        match dt.get_ical_datetime(property.value.trim()) {
+            Some(datetime) => {
+                let mut due_date = DueDate {
+                    timezone: property.param("TZID").unwrap_or_default().to_string(),
+                    ..Default::default()
+                };
+                due_date.set_datetime(datetime);
+                if let Some(rrule) = todo.property("RRULE")
+                    && !due_date.set_rrule(&rrule.value)
+                {
+                    report.skip(format!(
+                        "任务「{}」的重复规则「{}」无法识别，按普通日期导入",
+                        content, rrule.value
+                    ));
+                }
+                due = Some(due_date);
+            },
+            None => report.skip(format!("任务「{}」的日期「{}」无法识别", content, property.value)),
+        }
+    }
+    let due_datetime = due.as_ref().and_then(DueDate::datetime);
+
+    let priority = match todo.property("PRIORITY").and_then(|p| p.value.trim().parse::<u8>().ok()) {
+        Some(1..=4) => 1,
+        Some(5) => 2,
+        Some(6..=9) => 3,
+        _ => 4,
+    };
+    let completed_at = todo.property("COMPLETED").and_then(|p| utc_timestamp(&p.value));
+    let checked = completed_at.is_some()
+        || todo.property("STATUS").is_some_and(|s| s.value.eq_ignore_ascii_case("COMPLETED"));
+    let parent = todo
+        .properties_named("RELATED-TO")
+        .find(|p| p.param("RELTYPE").is_none_or(|reltype| reltype.eq_ignore_ascii_case("PARENT")))
+        .map(|p| p.value.trim().to_string());
+
+    let mut reminders = Vec::new();
+    for alarm in todo.children("VALARM") {
+        let Some(trigger) = alarm.property("TRIGGER") else {
+            continue;
+        };
+        let reminder =
+            if trigger.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) {
+                dt.get_ical_datetime(trigger.value.trim()).map(|due| RawReminder {
+                    item: key.clone(),
+                    due: Some(due),
+                    minute_offset: None,
+                })
+            } else {
+                match (parse_duration(&trigger.value), due_datetime) {
+                    (Some(offset), _) if offset <= Duration::zero() => Some(RawReminder {
+                        item: key.clone(),
+                        due: None,
+                        minute_offset: Some((-offset.num_minutes()) as i32),
+                    }),
+                    (Some(offset), Some(datetime)) => Some(RawReminder {
+                        item: key.clone(),
+                        due: Some(datetime + offset),
+                        minute_offset: None,
+                    }),
+                    _ => None,
+                }
+            };
+        match reminder {
+            Some(reminder) => reminders.push(reminder),
+            None => report.skip(format!("任务「{}」的提醒「{}」无法识别", content, trigger.value)),
+        }
+    }
+
+    let item = RawItem {
+        key,
+        parent,
+        description: todo
+            .property("DESCRIPTION")
+            .map(|d| d.text_value())
+            .filter(|d| !d.trim().is_empty()),
+        priority,
+        due,
+        labels: todo.properties_named("CATEGORIES").flat_map(|c| c.text_values()).collect(),
+        checked,
+        completed_at,
+        added_at: todo.property("CREATED").and_then(|p| utc_timestamp(&p.value)),
+        content,
+        ..Default::default()
+    };
+    (item, reminders)
+}
+
+/// 没有对应字段的 `VTODO` 属性（包括 `X-` 扩展属性）
+pub(super) fn unmapped_properties(todo: &ICalComponent) -> impl Iterator<Item = &ICalProperty> {
+    todo.properties.iter().filter(|property| !TODO_PROPERTIES.contains(&property.name.as_str()))
+}
+
+/// `COMPLETED` / `CREATED` 按 UTC 保存，与本地的 `completed_at` 一致
+pub(super) fn utc_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    match value.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok(),
+        None => DateTime::default()
+            .get_ical_datetime(value)
+            .and_then(|local| Local.from_local_datetime(&local).earliest())
+            .map(|local| local.with_timezone(&Utc).naive_utc()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_timestamp() {
+        let expected = NaiveDateTime::parse_from_str("2024-05-01 08:00:00", "%Y-%m-%d %H:%M:%S");
+        assert_eq!(utc_timestamp("20240501T080000Z"), expected.ok());
+        assert_eq!(utc_timestamp("garbage"), None);
+    }
+
+    #[test]
+    fn test_read_calendar_requires_todos() {
+        let mut report = ImportReport::default();
+        let event =
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Lunch\r\nEND:VEVENT\r\nEND:VCALENDAR";
+        assert!(read_calendar(event, &mut report).is_err());
+        assert_eq!(report.unmapped_fields.get("ics.VEVENT"), Some(&1));
+        assert!(read_calendar("BEGIN:VTODO\r\nEND:VTODO", &mut report).is_err());
+    }
+}
@@ -0,0 +1,927 @@
+//! Todoist backup importer
+//!
+//! 读取 Todoist 导出的备份 zip，支持两种内容：
+//! - 每个项目一个 CSV（Todoist「备份」导出的格式，列为 `TYPE`、`CONTENT`、`PRIORITY`、`INDENT`、
+//!   `DATE` 等），标签写在内容里（`Buy milk @errand`），`INDENT` 表示子任务层级；
+//! - Sync API 格式的 JSON（`projects` / `sections` / `items` / `labels` / `reminders`），
+//!   可以是一个文件，也可以按对象类型拆成 `projects.json` 等多个文件。
+//!
+//! 先把备份解析为中间结构，再在一个事务里写入，任何一步失败都不会留下半截数据。
+//! 无法映射的字段（评论、负责人、时长……）和被跳过的对象不会中断导入，统计在 [`ImportReport`] 中。
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Cursor, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use sea_orm::{ConnectionTrait, DatabaseConnection, TransactionTrait};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::{
+    DueDate, QuickAdd,
+    entity::{
+        ItemActiveModel, ItemLabelActiveModel, ItemLabelModel, ItemModel, ProjectActiveModel,
+        ProjectModel, ReminderActiveModel, ReminderModel, SectionActiveModel, SectionModel,
+    },
+    error::TodoError,
+    services::util::{RawLabel, insert_batched, resolve_labels},
+    utils::{DateTime, Util},
+};
+
+/// CSV 中会导入的列，其余非空列计入报告
+const CSV_COLUMNS: &[&str] =
+    &["TYPE", "CONTENT", "DESCRIPTION", "PRIORITY", "INDENT", "DATE", "DATE_LANG", "TIMEZONE"];
+
+const PROJECT_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "color",
+    "parent_id",
+    "child_order",
+    "is_favorite",
+    "is_archived",
+    "collapsed",
+    "view_style",
+    "inbox_project",
+];
+const SECTION_FIELDS: &[&str] =
+    &["id", "name", "project_id", "section_order", "collapsed", "is_archived"];
+const ITEM_FIELDS: &[&str] = &[
+    "id",
+    "content",
+    "description",
+    "project_id",
+    "section_id",
+    "parent_id",
+    "priority",
+    "due",
+    "labels",
+    "checked",
+    "completed_at",
+    "added_at",
+    "child_order",
+    "collapsed",
+];
+const LABEL_FIELDS: &[&str] = &["id", "name", "color", "item_order", "is_favorite"];
+const REMINDER_FIELDS: &[&str] = &["id", "item_id", "type", "due", "minute_offset"];
+
+/// 只是同步元数据、不含用户数据的字段，不计入报告
+const BOOKKEEPING_FIELDS: &[&str] = &[
+    "is_deleted",
+    "user_id",
+    "added_by_uid",
+    "sync_id",
+    "updated_at",
+    "day_order",
+    "notify_uid",
+    "v2_id",
+    "v2_parent_id",
+    "v2_project_id",
+    "v2_section_id",
+    "v2_item_id",
+];
+
+/// 导入结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub projects: usize,
+    pub sections: usize,
+    pub items: usize,
+    /// 新建的标签数量（同名标签直接复用）
+    pub labels: usize,
+    pub reminders: usize,
+    /// 无法映射而被忽略的字段及出现次数，如 `item.responsible_uid`、`csv.DURATION`、`notes`
+    pub unmapped_fields: BTreeMap<String, usize>,
+    /// 被跳过或降级导入的对象及原因
+    pub skipped: Vec<String>,
+}
+
+impl ImportReport {
+    pub(super) fn unmapped(&mut self, field: impl Into<String>) {
+        *self.unmapped_fields.entry(field.into()).or_default() += 1;
+    }
+
+    pub(super) fn skip(&mut self, reason: impl Into<String>) {
+        self.skipped.push(reason.into());
+    }
+
+    /// 是否有未能完整导入的内容
+    pub fn has_issues(&self) -> bool {
+        !self.unmapped_fields.is_empty() || !self.skipped.is_empty()
+    }
+
+    /// 一行摘要，供界面提示
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "导入 {} 个项目、{} 个分区、{} 个任务、{} 个新标签、{} 个提醒",
+            self.projects, self.sections, self.items, self.labels, self.reminders
+        );
+        if self.has_issues() {
+            summary.push_str(&format!(
+                "；{} 类字段未导入，{} 项被跳过",
+                self.unmapped_fields.len(),
+                self.skipped.len()
+            ));
+        }
+        summary
+    }
+}
+
+/// Service for importing data from other apps
+#[derive(Clone, Debug)]
+pub struct ImportService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl ImportService {
+    /// Create a new ImportService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 导入 Todoist 备份文件（zip）
+    pub async fn import_todoist_backup(&self, path: &Path) -> Result<ImportReport, TodoError> {
+        let data = std::fs::read(path).map_err(|e| {
+            TodoError::validation(format!("无法读取备份文件 {}: {}", path.display(), e))
+        })?;
+        self.import_todoist_backup_bytes(&data).await
+    }
+
+    /// 导入内存中的 Todoist 备份（zip 内容），所有对象在同一个事务中写入
+    pub async fn import_todoist_backup_bytes(
+        &self,
+        data: &[u8],
+    ) -> Result<ImportReport, TodoError> {
+        let mut report = ImportReport::default();
+        let backup = ImportData::read(data, &mut report)?;
+        let report = backup.import(&self.db, report).await?;
+
+        tracing::info!("Todoist import finished: {}", report.summary());
+        Ok(report)
+    }
+}
+
+// ==================== 中间结构 ====================
+
+#[derive(Debug, Default)]
+pub(super) struct RawProject {
+    pub(super) key: String,
+    pub(super) name: String,
+    pub(super) color: Option<String>,
+    pub(super) parent: Option<String>,
+    pub(super) order: Option<i32>,
+    pub(super) is_favorite: bool,
+    pub(super) is_archived: bool,
+    pub(super) collapsed: bool,
+    pub(super) view_style: Option<String>,
+    /// Todoist 的收件箱对应本地「无项目」，不建项目
+    pub(super) inbox: bool,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RawSection {
+    pub(super) key: String,
+    pub(super) project: String,
+    pub(super) name: String,
+    pub(super) order: Option<i32>,
+    pub(super) collapsed: bool,
+    pub(super) is_archived: bool,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RawItem {
+    pub(super) key: String,
+    pub(super) project: Option<String>,
+    pub(super) section: Option<String>,
+    pub(super) parent: Option<String>,
+    pub(super) content: String,
+    pub(super) description: Option<String>,
+    /// 本地优先级：1 最高，4 无
+    pub(super) priority: i32,
+    pub(super) due: Option<DueDate>,
+    /// 标签名称（JSON 旧格式中也可能是标签 ID）
+    pub(super) labels: Vec<String>,
+    pub(super) checked: bool,
+    pub(super) completed_at: Option<NaiveDateTime>,
+    pub(super) added_at: Option<NaiveDateTime>,
+    pub(super) order: Option<i32>,
+    pub(super) collapsed: bool,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RawReminder {
+    pub(super) item: String,
+    /// 绝对提醒的时间
+    pub(super) due: Option<NaiveDateTime>,
+    /// 相对提醒：截止时间前多少分钟
+    pub(super) minute_offset: Option<i32>,
+}
+
+/// 导入的中间结构，`key` 是来源数据中的 ID，写入时重新分配本地 ID
+#[derive(Debug, Default)]
+pub(super) struct ImportData {
+    pub(super) projects: Vec<RawProject>,
+    pub(super) sections: Vec<RawSection>,
+    pub(super) items: Vec<RawItem>,
+    pub(super) labels: Vec<RawLabel>,
+    pub(super) reminders: Vec<RawReminder>,
+}
+
+// ==================== 读取备份 ====================
+
+impl ImportData {
+    fn read(data: &[u8], report: &mut ImportReport) -> Result<Self, TodoError> {
+        let mut archive = ZipArchive::new(Cursor::new(data))
+            .map_err(|e| TodoError::validation(format!("不是有效的 Todoist 备份（zip）: {}", e)))?;
+
+        let mut backup = Self::default();
+        let mut found = false;
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|e| TodoError::validation(format!("无法读取备份内容: {}", e)))?;
+            let name = file.name().to_string();
+            if file.is_dir() || name.starts_with("__MACOSX/") {
+                continue;
+            }
+
+            let path = Path::new(&name);
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+            if !matches!(extension.as_deref(), Some("csv" | "json")) {
+                report.skip(format!("忽略文件 {}", name));
+                continue;
+            }
+
+            let mut text = String::new();
+            file.read_to_string(&mut text)
+                .map_err(|e| TodoError::validation(format!("{} 不是 UTF-8 文本: {}", name, e)))?;
+            let text = text.trim_start_matches('\u{feff}');
+            if extension.as_deref() == Some("csv") {
+                backup.read_csv(&stem, text, report)?;
+            } else {
+                backup.read_json(&name, text, report)?;
+            }
+            found = true;
+        }
+
+        if !found {
+            return Err(TodoError::validation("备份中没有 Todoist 的 CSV 或 JSON 文件"));
+        }
+        Ok(backup)
+    }
+
+    // ---------- CSV：一个文件一个项目 ----------
+
+    fn read_csv(
+        &mut self,
+        stem: &str,
+        text: &str,
+        report: &mut ImportReport,
+    ) -> Result<(), TodoError> {
+        let csv_error =
+            |e: csv::Error| TodoError::validation(format!("{}.csv 解析失败: {}", stem, e));
+
+        let name = csv_project_name(stem);
+        let project_key = format!("csv:{}", stem);
+        self.projects.push(RawProject {
+            key: project_key.clone(),
+            inbox: name.eq_ignore_ascii_case("inbox"),
+            name,
+            ..Default::default()
+        });
+
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+        let headers: Vec<String> =
+            reader.headers().map_err(csv_error)?.iter().map(|h| h.trim().to_uppercase()).collect();
+
+        let mut section: Option<String> = None;
+        // 每一级缩进最近的任务，用于确定子任务的父任务
+        let mut parents: Vec<String> = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(csv_error)?;
+            let field = |column: &str| {
+                headers
+                    .iter()
+                    .position(|h| h == column)
+                    .and_then(|index| record.get(index))
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+            };
+            let key = format!("{}:{}", project_key, row);
+            // 表头占第 1 行
+            let line = row + 2;
+
+            match field("TYPE").map(str::to_lowercase).as_deref() {
+                Some("section") => {
+                    self.sections.push(RawSection {
+                        key: key.clone(),
+                        project: project_key.clone(),
+                        name: field("CONTENT").unwrap_or("Section").to_string(),
+                        order: Some(self.sections.len() as i32),
+                        ..Default::default()
+                    });
+                    section = Some(key);
+                    parents.clear();
+                },
+                Some("task") => {
+                    let (content, labels) = split_csv_labels(field("CONTENT").unwrap_or_default());
+                    if content.is_empty() {
+                        report.skip(format!("{}.csv 第 {} 行：任务内容为空", stem, line));
+                        continue;
+                    }
+
+                    let indent =
+                        field("INDENT").and_then(|v| v.parse::<usize>().ok()).unwrap_or(1).max(1);
+                    parents.truncate(indent - 1);
+                    let parent = parents.last().cloned();
+                    parents.push(key.clone());
+
+                    let due = field("DATE").and_then(|date| {
+                        let due = parse_due_string(date, field("TIMEZONE"));
+                        if due.is_none() {
+                            report.skip(format!(
+                                "{}.csv 第 {} 行：无法识别的日期「{}」",
+                                stem, line, date
+                            ));
+                        }
+                        due
+                    });
+
+                    for (index, header) in headers.iter().enumerate() {
+                        let value = record.get(index).map(str::trim).unwrap_or_default();
+                        if !CSV_COLUMNS.contains(&header.as_str())
+                            && !value.is_empty()
+                            && value != "None"
+                        {
+                            report.unmapped(format!("csv.{}", header));
+                        }
+                    }
+
+                    self.items.push(RawItem {
+                        key,
+                        project: Some(project_key.clone()),
+                        section: section.clone(),
+                        parent,
+                        content,
+                        description: field("DESCRIPTION").map(str::to_string),
+                        priority: field("PRIORITY")
+                            .and_then(|v| v.parse::<i32>().ok())
+                            .unwrap_or(4)
+                            .clamp(1, 4),
+                        due,
+                        labels,
+                        order: Some(self.items.len() as i32),
+                        ..Default::default()
+                    });
+                },
+                // 评论没有对应的表
+                Some("note") => report.unmapped("csv.note"),
+                Some(other) => {
+                    report.skip(format!("{}.csv 第 {} 行：未知类型「{}」", stem, line, other))
+                },
+                None => {},
+            }
+        }
+        Ok(())
+    }
+
+    // ---------- JSON：Sync API 格式 ----------
+
+    fn read_json(
+        &mut self,
+        name: &str,
+        text: &str,
+        report: &mut ImportReport,
+    ) -> Result<(), TodoError> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|e| TodoError::validation(format!("{} 不是有效的 JSON: {}", name, e)))?;
+
+        match value {
+            // 按对象类型拆分的文件：projects.json、items.json ...
+            Value::Array(objects) => {
+                let kind = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+                self.read_json_objects(kind, &objects, report);
+            },
+            Value::Object(map) => {
+                for (kind, value) in &map {
+                    if let Value::Array(objects) = value {
+                        self.read_json_objects(kind, objects, report);
+                    }
+                }
+            },
+            _ => report.skip(format!("忽略文件 {}：不是对象或数组", name)),
+        }
+        Ok(())
+    }
+
+    pub(super) fn read_json_objects(
+        &mut self,
+        kind: &str,
+        objects: &[Value],
+        report: &mut ImportReport,
+    ) {
+        for object in objects.iter().filter_map(Value::as_object) {
+            if flag(object, "is_deleted") {
+                continue;
+            }
+            match kind {
+                "projects" => {
+                    report_unmapped("project", object, PROJECT_FIELDS, report);
+                    self.projects.push(RawProject {
+                        key: text(object, "id").unwrap_or_default(),
+                        name: text(object, "name").unwrap_or_else(|| "Project".to_string()),
+                        color: text(object, "color"),
+                        parent: text(object, "parent_id"),
+                        order: int(object, "child_order"),
+                        is_favorite: flag(object, "is_favorite"),
+                        is_archived: flag(object, "is_archived"),
+                        collapsed: flag(object, "collapsed"),
+                        view_style: text(object, "view_style"),
+                        inbox: flag(object, "inbox_project"),
+                    });
+                },
+                "sections" => {
+                    report_unmapped("section", object, SECTION_FIELDS, report);
+                    self.sections.push(RawSection {
+                        key: text(object, "id").unwrap_or_default(),
+                        project: text(object, "project_id").unwrap_or_default(),
+                        name: text(object, "name").unwrap_or_else(|| "Section".to_string()),
+                        order: int(object, "section_order"),
+                        collapsed: flag(object, "collapsed"),
+                        is_archived: flag(object, "is_archived"),
+                    });
+                },
+                "items" => {
+                    report_unmapped("item", object, ITEM_FIELDS, report);
+                    let content = text(object, "content").unwrap_or_default();
+                    let due = object
+                        .get("due")
+                        .and_then(Value::as_object)
+                        .and_then(|due| json_due(&content, due, report));
+                    self.items.push(RawItem {
+                        key: text(object, "id").unwrap_or_default(),
+                        project: text(object, "project_id"),
+                        section: text(object, "section_id"),
+                        parent: text(object, "parent_id"),
+                        // API 中 4 为最高（p1），本地 1 为最高
+                        priority: 5 - int(object, "priority").unwrap_or(1).clamp(1, 4),
+                        description: text(object, "description"),
+                        due,
+                        labels: object
+                            .get("labels")
+                            .and_then(Value::as_array)
+                            .map(|labels| labels.iter().filter_map(value_text).collect())
+                            .unwrap_or_default(),
+                        checked: flag(object, "checked"),
+                        completed_at: timestamp(object, "completed_at"),
+                        added_at: timestamp(object, "added_at"),
+                        order: int(object, "child_order"),
+                        collapsed: flag(object, "collapsed"),
+                        content,
+                    });
+                },
+                "labels" => {
+                    report_unmapped("label", object, LABEL_FIELDS, report);
+                    let Some(name) = text(object, "name") else {
+                        report.skip("标签缺少名称");
+                        continue;
+                    };
+                    self.labels.push(RawLabel {
+                        key: text(object, "id"),
+                        name,
+                        color: text(object, "color"),
+                        order: int(object, "item_order"),
+                        is_favorite: flag(object, "is_favorite"),
+                    });
+                },
+                "reminders" => {
+                    report_unmapped("reminder", object, REMINDER_FIELDS, report);
+                    let item = text(object, "item_id").unwrap_or_default();
+                    match text(object, "type").as_deref() {
+                        Some("relative") => self.reminders.push(RawReminder {
+                            item,
+                            minute_offset: int(object, "minute_offset"),
+                            ..Default::default()
+                        }),
+                        Some("absolute") | None => {
+                            let due = object
+                                .get("due")
+                                .and_then(Value::as_object)
+                                .and_then(|due| text(due, "date"))
+                                .and_then(|date| {
+                                    DateTime::default().get_todoist_datetime(date).ok()
+                                });
+                            if due.is_none() {
+                                report.skip(format!("任务 {} 的提醒缺少时间", item));
+                                continue;
+                            }
+                            self.reminders.push(RawReminder { item, due, ..Default::default() });
+                        },
+                        Some(other) => {
+                            report.skip(format!("任务 {} 的 {} 提醒无法导入", item, other))
+                        },
+                    }
+                },
+                // 评论、协作者、过滤器等没有对应的数据
+                _ => report.unmapped(kind),
+            }
+        }
+    }
+
+    // ==================== 写入 ====================
+
+    /// 在一个事务中写入全部对象，任何一步失败都整体回滚
+    pub(super) async fn import(
+        self,
+        db: &DatabaseConnection,
+        report: ImportReport,
+    ) -> Result<ImportReport, TodoError> {
+        db.transaction::<_, ImportReport, TodoError>(|txn| {
+            Box::pin(async move { self.write(txn, report).await })
+        })
+        .await
+        .map_err(TodoError::from)
+    }
+
+    async fn write<C: ConnectionTrait>(
+        self,
+        conn: &C,
+        mut report: ImportReport,
+    ) -> Result<ImportReport, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let util = Util::get_default();
+        let color =
+            |key: Option<String>| util.get_color(key.unwrap_or_else(|| util.get_random_color()));
+
+        // ---------- 标签：按名称（不区分大小写）复用已有标签 ----------
+        let label_names: HashMap<String, String> = self
+            .labels
+            .iter()
+            .filter_map(|label| Some((label.key.clone()?, label.name.clone())))
+            .collect();
+        let raw_labels: Vec<RawLabel> = self
+            .labels
+            .into_iter()
+            .chain(
+                self.items
+                    .iter()
+                    .flat_map(|item| &item.labels)
+                    .map(|label| label_names.get(label).unwrap_or(label))
+                    .map(|name| RawLabel { name: name.clone(), ..Default::default() }),
+            )
+            .collect();
+        let (label_ids, created) = resolve_labels(conn, raw_labels).await?;
+        report.labels = created;
+
+        // ---------- 项目（收件箱映射为无项目） ----------
+        let project_ids: HashMap<String, Option<String>> = self
+            .projects
+            .iter()
+            .map(|project| {
+                (project.key.clone(), (!project.inbox).then(|| Uuid::new_v4().to_string()))
+            })
+            .collect();
+        let mut projects = Vec::new();
+        for project in self.projects {
+            let Some(Some(id)) = project_ids.get(&project.key).cloned() else {
+                continue;
+            };
+            let parent_id = match project.parent.as_ref().map(|parent| project_ids.get(parent)) {
+                Some(Some(parent_id)) => parent_id.clone(),
+                Some(None) => {
+                    report
+                        .skip(format!("项目「{}」的父项目不存在，作为顶层项目导入", project.name));
+                    None
+                },
+                None => None,
+            };
+            projects.push(ProjectActiveModel::from(ProjectModel {
+                id,
+                name: project.name,
+                color: Some(color(project.color)),
+                child_order: project.order,
+                is_favorite: project.is_favorite,
+                is_archived: project.is_archived,
+                archived_at: project.is_archived.then_some(now),
+                collapsed: project.collapsed,
+                view_style: project.view_style,
+                parent_id,
+                ..Default::default()
+            }));
+        }
+        report.projects = projects.len();
+        insert_batched(conn, projects).await?;
+
+        // ---------- 分区 ----------
+        let mut section_ids: HashMap<String, String> = HashMap::new();
+        let mut sections = Vec::new();
+        for section in self.sections {
+            let project_id = match project_ids.get(&section.project) {
+                Some(Some(project_id)) => project_id.clone(),
+                Some(None) => {
+                    report.skip(format!(
+                        "收件箱中的分区「{}」无法导入，其中的任务放入收件箱",
+                        section.name
+                    ));
+                    continue;
+                },
+                None => {
+                    report.skip(format!("分区「{}」所属的项目不存在", section.name));
+                    continue;
+                },
+            };
+            let id = Uuid::new_v4().to_string();
+            section_ids.insert(section.key, id.clone());
+            sections.push(SectionActiveModel::from(SectionModel {
+                id,
+                name: section.name,
+                added_at: now,
+                project_id: Some(project_id),
+                section_order: section.order,
+                collapsed: section.collapsed,
+                is_archived: section.is_archived,
+                archived_at: section.is_archived.then_some(now),
+                ..Default::default()
+            }));
+        }
+        report.sections = sections.len();
+        insert_batched(conn, sections).await?;
+
+        // ---------- 任务（ID 先全部分配，子任务可引用任意顺序的父任务） ----------
+        let item_ids: HashMap<String, String> =
+            self.items.iter().map(|item| (item.key.clone(), Uuid::new_v4().to_string())).collect();
+        let mut item_dues: HashMap<String, NaiveDateTime> = HashMap::new();
+        let mut items = Vec::new();
+        let mut item_labels = Vec::new();
+        for item in self.items {
+            if item.content.trim().is_empty() {
+                report.skip("跳过内容为空的任务");
+                continue;
+            }
+            let id = item_ids[&item.key].clone();
+            let project_id = match item.project.as_ref().map(|project| project_ids.get(project)) {
+                Some(Some(project_id)) => project_id.clone(),
+                Some(None) => {
+                    report.skip(format!("任务「{}」所属的项目不存在，放入收件箱", item.content));
+                    None
+                },
+                None => None,
+            };
+            let parent_id = item.parent.as_ref().and_then(|parent| {
+                let parent_id = item_ids.get(parent).cloned();
+                if parent_id.is_none() {
+                    report
+                        .skip(format!("任务「{}」的父任务不存在，作为顶层任务导入", item.content));
+                }
+                parent_id
+            });
+
+            let mut label_set: Vec<String> = Vec::new();
+            for label in &item.labels {
+                let name = label_names.get(label).unwrap_or(label).to_lowercase();
+                if let Some(label_id) = label_ids.get(&name)
+                    && !label_set.contains(label_id)
+                {
+                    label_set.push(label_id.clone());
+                }
+            }
+            item_labels.extend(label_set.iter().map(|label_id| {
+                ItemLabelActiveModel::from(ItemLabelModel {
+                    item_id: id.clone(),
+                    label_id: label_id.clone(),
+                    created_at: now,
+                })
+            }));
+
+            let mut model = ItemModel {
+                id: id.clone(),
+                content: item.content,
+                description: item.description,
+                added_at: item.added_at.unwrap_or(now),
+                updated_at: now,
+                completed_at: item.checked.then(|| item.completed_at.unwrap_or(now)),
+                section_id: item.section.and_then(|section| section_ids.get(&section).cloned()),
+                project_id,
+                parent_id,
+                priority: Some(item.priority),
+                child_order: item.order,
+                checked: item.checked,
+                collapsed: item.collapsed,
+                labels: (!label_set.is_empty()).then(|| label_set.join(";")),
+                ..Default::default()
+            };
+            if let Some(datetime) = item.due.as_ref().and_then(DueDate::datetime) {
+                item_dues.insert(item.key, datetime);
+            }
+            model.set_due_date(item.due);
+            items.push(ItemActiveModel::from(model));
+        }
+        report.items = items.len();
+        insert_batched(conn, items).await?;
+        insert_batched(conn, item_labels).await?;
+
+        // ---------- 提醒（相对提醒按任务截止时间换算为绝对时间） ----------
+        let mut reminders = Vec::new();
+        for reminder in self.reminders {
+            let Some(item_id) = item_ids.get(&reminder.item) else {
+                report.skip(format!("提醒所属的任务 {} 不存在", reminder.item));
+                continue;
+            };
+            let due = match (reminder.due, reminder.minute_offset) {
+                (Some(due), _) => due,
+                (None, Some(offset)) => match item_dues.get(&reminder.item) {
+                    Some(item_due) => *item_due - Duration::minutes(offset as i64),
+                    None => {
+                        report
+                            .skip(format!("任务 {} 没有截止时间，相对提醒无法导入", reminder.item));
+                        continue;
+                    },
+                },
+                (None, None) => {
+                    report.skip(format!("任务 {} 的提醒缺少时间", reminder.item));
+                    continue;
+                },
+            };
+            reminders.push(ReminderActiveModel::from(ReminderModel {
+                id: Uuid::new_v4().to_string(),
+                item_id: Some(item_id.clone()),
+                due: Some(due.format("%Y-%m-%d %H:%M:%S").to_string()),
+                reminder_type: Some("time".to_string()),
+                mm_offset: reminder.minute_offset,
+                ..Default::default()
+            }));
+        }
+        report.reminders = reminders.len();
+        insert_batched(conn, reminders).await?;
+
+        Ok(report)
+    }
+}
+
+// ==================== 字段转换 ====================
+
+/// `Work [2203306141].csv` → `Work`
+fn csv_project_name(stem: &str) -> String {
+    let name = match stem.rfind(" [") {
+        Some(index) if stem.ends_with(']') => &stem[..index],
+        _ => stem,
+    };
+    name.trim().to_string()
+}
+
+/// 把内容中的 `@标签` 拆出来
+fn split_csv_labels(content: &str) -> (String, Vec<String>) {
+    let mut labels = Vec::new();
+    let words: Vec<&str> = content
+        .split_whitespace()
+        .filter(|word| match word.strip_prefix('@') {
+            Some(label) if !label.is_empty() => {
+                labels.push(label.to_string());
+                false
+            },
+            _ => true,
+        })
+        .collect();
+    (words.join(" "), labels)
+}
+
+/// 解析 Todoist 的自然语言日期（`every monday 9am`、`tomorrow`、`2024-05-10`）
+fn parse_due_string(date: &str, timezone: Option<&str>) -> Option<DueDate> {
+    let parsed = QuickAdd::parse(date, Local::now().naive_local(), &());
+    if !parsed.content.is_empty() || parsed.priority.is_some() {
+        return None;
+    }
+    let mut due = parsed.due?;
+    due.timezone = timezone.unwrap_or_default().to_string();
+    Some(due)
+}
+
+/// Sync API 的 due 对象：`date` 是下一次到期时间，重复规则只在 `string` 中
+fn json_due(
+    content: &str,
+    object: &Map<String, Value>,
+    report: &mut ImportReport,
+) -> Option<DueDate> {
+    let date = text(object, "date")?;
+    let Ok(datetime) = DateTime::default().get_todoist_datetime(date.clone()) else {
+        report.skip(format!("任务「{}」的日期「{}」无法识别", content, date));
+        return None;
+    };
+
+    let mut due = DueDate::default();
+    due.set_datetime(datetime);
+    due.timezone = text(object, "timezone").unwrap_or_default();
+    if flag(object, "is_recurring")
+        && !DateTime::parse_todoist_recurrency(&mut due, &Value::Object(object.clone()))
+    {
+        report.skip(format!(
+            "任务「{}」的重复规则「{}」无法识别，按普通日期导入",
+            content,
+            text(object, "string").unwrap_or_default()
+        ));
+    }
+    Some(due)
+}
+
+fn report_unmapped(
+    kind: &str,
+    object: &Map<String, Value>,
+    mapped: &[&str],
+    report: &mut ImportReport,
+) {
+    for (key, value) in object {
+        if !mapped.contains(&key.as_str())
+            && !BOOKKEEPING_FIELDS.contains(&key.as_str())
+            && has_data(value)
+        {
+            report.unmapped(format!("{}.{}", kind, key));
+        }
+    }
+}
+
+/// 空值、false、0、空字符串 / 数组 / 对象不算有数据
+fn has_data(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(flag) => *flag,
+        Value::Number(number) => number.as_f64() != Some(0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// 字符串或数字（旧版 API 的 ID 是数字）
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) if !text.is_empty() => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+pub(super) fn text(object: &Map<String, Value>, key: &str) -> Option<String> {
+    object.get(key).and_then(value_text)
+}
+
+fn int(object: &Map<String, Value>, key: &str) -> Option<i32> {
+    object.get(key).and_then(Value::as_i64).map(|value| value as i32)
+}
+
+pub(super) fn flag(object: &Map<String, Value>, key: &str) -> bool {
+    match object.get(key) {
+        Some(Value::Bool(flag)) => *flag,
+        Some(Value::Number(number)) => number.as_i64() == Some(1),
+        _ => false,
+    }
+}
+
+/// `2024-05-10T09:00:00.000000Z` → UTC 时间（与 `added_at` 等字段一致）
+fn timestamp(object: &Map<String, Value>, key: &str) -> Option<NaiveDateTime> {
+    let value = text(object, key)?;
+    chrono::DateTime::parse_from_rfc3339(&value)
+        .map(|dt| dt.naive_utc())
+        .or_else(|_| DateTime::default().get_todoist_datetime(value))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_helpers() {
+        assert_eq!(csv_project_name("Work [2203306141]"), "Work");
+        assert_eq!(csv_project_name("Side [project]x"), "Side [project]x");
+        assert_eq!(
+            split_csv_labels("Buy milk @errand @home"),
+            ("Buy milk".to_string(), vec!["errand".to_string(), "home".to_string()])
+        );
+        assert_eq!(split_csv_labels("Email a@ b"), ("Email a@ b".to_string(), vec![]));
+    }
+
+    #[test]
+    fn test_parse_due_string() {
+        let due = parse_due_string("every 2 weeks", Some("Europe/Berlin")).unwrap();
+        assert!(due.is_recurring);
+        assert_eq!(due.recurrency_interval, 2);
+        assert_eq!(due.timezone, "Europe/Berlin");
+        assert!(parse_due_string("every 2nd monday of the month", None).is_none());
+        assert!(parse_due_string("p1", None).is_none());
+    }
+
+    #[test]
+    fn test_has_data() {
+        assert!(!has_data(&Value::Null));
+        assert!(!has_data(&serde_json::json!(0)));
+        assert!(!has_data(&serde_json::json!([])));
+        assert!(has_data(&serde_json::json!({"amount": 15})));
+        assert!(has_data(&serde_json::json!("12345")));
+    }
+}
@@ -0,0 +1,424 @@
+//! Integrity service for soft references
+//!
+//! `items.parent_id`、`items.section_id`、`projects.parent_id` 以及旧的 `items.labels` 字符串都没有
+//! 外键约束，时间长了会出现孤儿、环和不一致。[`IntegrityService::check`] 只读检查并生成报告，
+//! 每个问题附带可选的修复方式；[`IntegrityService::repair`] 只应用调用方选中的修复。
+//!
+//! 只要行还在（包括回收站中的对象）就不算孤儿，回收站的恢复依赖这些引用。
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, QueryFilter,
+    Statement, TransactionTrait, Value, prelude::Expr,
+};
+
+use crate::{
+    entity::{item_labels, items, projects, sections},
+    error::TodoError,
+};
+
+/// 可以按行号删除的关联表（外键指向的对象已不存在时整行作废）
+const LINK_TABLES: [&str; 3] = ["item_labels", "Reminders", "Attachments"];
+
+/// 问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IntegrityIssueKind {
+    /// `PRAGMA integrity_check` 报告的损坏
+    Corruption,
+    /// `PRAGMA foreign_key_check` 报告的违反外键的行
+    ForeignKey,
+    /// 任务的父任务不存在
+    OrphanedItemParent,
+    /// 任务的父任务链成环
+    CyclicItemParent,
+    /// 任务所属项目不存在
+    OrphanedItemProject,
+    /// 任务的分区不存在
+    OrphanedItemSection,
+    /// 任务的分区属于另一个项目
+    SectionProjectMismatch,
+    /// 项目的父项目不存在
+    OrphanedProjectParent,
+    /// 项目的父项目链成环
+    CyclicProjectParent,
+    /// `items.labels` 字符串与 `item_labels` 不一致
+    LabelDrift,
+}
+
+/// 修复方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityFix {
+    /// 任务改为顶层任务
+    DetachItemParent { item_id: String },
+    /// 任务移出分区（留在原项目）
+    ClearItemSection { item_id: String },
+    /// 任务移到另一个项目（收件箱），同时移出分区
+    MoveItemToProject { item_id: String, project_id: String },
+    /// 项目改为顶层项目
+    DetachProjectParent { project_id: String },
+    /// 按 `item_labels` 重写 `items.labels`
+    RewriteItemLabels { item_id: String, labels: Option<String> },
+    /// 删除关联表中外键失效的行
+    DeleteRow { table: String, rowid: i64 },
+}
+
+/// 一个问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    /// 出问题的对象（任务 / 项目 ID，或外键检查中的表名）
+    pub object_id: String,
+    pub detail: String,
+    /// 没有安全的自动修复时为 None
+    pub fix: Option<IntegrityFix>,
+}
+
+/// 检查报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn count(&self, kind: IntegrityIssueKind) -> usize {
+        self.issues.iter().filter(|issue| issue.kind == kind).count()
+    }
+
+    /// 可以自动修复的问题
+    pub fn fixable(&self) -> Vec<IntegrityIssue> {
+        self.issues.iter().filter(|issue| issue.fix.is_some()).cloned().collect()
+    }
+}
+
+/// Service for database integrity checks and repairs
+#[derive(Clone, Debug)]
+pub struct IntegrityService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl IntegrityService {
+    /// Create a new IntegrityService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 检查整个数据库，不做任何修改
+    pub async fn check(&self) -> Result<IntegrityReport, TodoError> {
+        let mut issues = Vec::new();
+        if self.db.get_database_backend() == DbBackend::Sqlite {
+            issues.extend(self.check_sqlite_integrity().await?);
+            issues.extend(self.check_foreign_keys().await?);
+        }
+
+        let items = items::Entity::find().all(&*self.db).await?;
+        let projects = projects::Entity::find().all(&*self.db).await?;
+        let sections = sections::Entity::find().all(&*self.db).await?;
+        let item_labels = item_labels::Entity::find().all(&*self.db).await?;
+
+        let project_parents: HashMap<&str, Option<&str>> = projects
+            .iter()
+            .map(|project| (project.id.as_str(), non_empty(project.parent_id.as_deref())))
+            .collect();
+        issues.extend(check_parents(
+            &project_parents,
+            IntegrityIssueKind::OrphanedProjectParent,
+            IntegrityIssueKind::CyclicProjectParent,
+            "项目",
+            |id| IntegrityFix::DetachProjectParent { project_id: id.to_string() },
+        ));
+
+        let item_parents: HashMap<&str, Option<&str>> = items
+            .iter()
+            .map(|item| (item.id.as_str(), non_empty(item.parent_id.as_deref())))
+            .collect();
+        issues.extend(check_parents(
+            &item_parents,
+            IntegrityIssueKind::OrphanedItemParent,
+            IntegrityIssueKind::CyclicItemParent,
+            "任务",
+            |id| IntegrityFix::DetachItemParent { item_id: id.to_string() },
+        ));
+
+        let section_projects: HashMap<&str, Option<&str>> = sections
+            .iter()
+            .map(|section| (section.id.as_str(), non_empty(section.project_id.as_deref())))
+            .collect();
+        let inbox_id = projects
+            .iter()
+            .filter(|project| !project.is_deleted)
+            .find(|project| project.inbox_project.unwrap_or(0) != 0)
+            .map(|project| project.id.clone());
+        for item in &items {
+            let project_id = non_empty(item.project_id.as_deref());
+            if let Some(project_id) = project_id
+                && !project_parents.contains_key(project_id)
+            {
+                issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::OrphanedItemProject,
+                    object_id: item.id.clone(),
+                    detail: format!("任务「{}」所属的项目 {} 不存在", item.content, project_id),
+                    fix: inbox_id.clone().map(|inbox_id| IntegrityFix::MoveItemToProject {
+                        item_id: item.id.clone(),
+                        project_id: inbox_id,
+                    }),
+                });
+                continue;
+            }
+            let Some(section_id) = non_empty(item.section_id.as_deref()) else {
+                continue;
+            };
+            match section_projects.get(section_id) {
+                None => issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::OrphanedItemSection,
+                    object_id: item.id.clone(),
+                    detail: format!("任务「{}」所在的分区 {} 不存在", item.content, section_id),
+                    fix: Some(IntegrityFix::ClearItemSection { item_id: item.id.clone() }),
+                }),
+                Some(section_project) if *section_project != project_id => {
+                    issues.push(IntegrityIssue {
+                        kind: IntegrityIssueKind::SectionProjectMismatch,
+                        object_id: item.id.clone(),
+                        detail: format!(
+                            "任务「{}」的分区 {} 属于项目 {}，任务属于项目 {}",
+                            item.content,
+                            section_id,
+                            section_project.unwrap_or("-"),
+                            project_id.unwrap_or("-")
+                        ),
+                        fix: Some(IntegrityFix::ClearItemSection { item_id: item.id.clone() }),
+                    })
+                },
+                Some(_) => {},
+            }
+        }
+
+        let mut linked: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for link in &item_labels {
+            linked.entry(link.item_id.as_str()).or_default().insert(link.label_id.as_str());
+        }
+        for item in &items {
+            let stored: BTreeSet<&str> = item
+                .labels
+                .as_deref()
+                .unwrap_or_default()
+                .split(';')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .collect();
+            let expected = linked.remove(item.id.as_str()).unwrap_or_default();
+            if stored == expected {
+                continue;
+            }
+            let labels = (!expected.is_empty())
+                .then(|| expected.iter().copied().collect::<Vec<_>>().join(";"));
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::LabelDrift,
+                object_id: item.id.clone(),
+                detail: format!(
+                    "任务「{}」的标签字段为「{}」，关联表中为「{}」",
+                    item.content,
+                    item.labels.as_deref().unwrap_or_default(),
+                    labels.as_deref().unwrap_or_default()
+                ),
+                fix: Some(IntegrityFix::RewriteItemLabels { item_id: item.id.clone(), labels }),
+            });
+        }
+
+        issues.sort_by(|a, b| (a.kind, &a.object_id).cmp(&(b.kind, &b.object_id)));
+        Ok(IntegrityReport { issues })
+    }
+
+    /// 在一个事务中应用选中问题的修复，返回应用的修复数量
+    pub async fn repair(&self, issues: &[IntegrityIssue]) -> Result<usize, TodoError> {
+        let txn = self.db.begin().await?;
+        let mut applied = 0;
+        for fix in issues.iter().filter_map(|issue| issue.fix.as_ref()) {
+            Self::apply_fix_in_conn(&txn, fix).await?;
+            applied += 1;
+        }
+        txn.commit().await?;
+        if applied > 0 {
+            tracing::info!("Applied {} integrity fixes", applied);
+        }
+        Ok(applied)
+    }
+
+    async fn apply_fix_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        fix: &IntegrityFix,
+    ) -> Result<(), TodoError> {
+        match fix {
+            IntegrityFix::DetachItemParent { item_id } => {
+                items::Entity::update_many()
+                    .col_expr(items::Column::ParentId, Expr::value(Option::<String>::None))
+                    .filter(items::Column::Id.eq(item_id))
+                    .exec(conn)
+                    .await?;
+            },
+            IntegrityFix::ClearItemSection { item_id } => {
+                items::Entity::update_many()
+                    .col_expr(items::Column::SectionId, Expr::value(Option::<String>::None))
+                    .filter(items::Column::Id.eq(item_id))
+                    .exec(conn)
+                    .await?;
+            },
+            IntegrityFix::MoveItemToProject { item_id, project_id } => {
+                items::Entity::update_many()
+                    .col_expr(items::Column::ProjectId, Expr::value(project_id.clone()))
+                    .col_expr(items::Column::SectionId, Expr::value(Option::<String>::None))
+                    .filter(items::Column::Id.eq(item_id))
+                    .exec(conn)
+                    .await?;
+            },
+            IntegrityFix::DetachProjectParent { project_id } => {
+                projects::Entity::update_many()
+                    .col_expr(projects::Column::ParentId, Expr::value(Option::<String>::None))
+                    .filter(projects::Column::Id.eq(project_id))
+                    .exec(conn)
+                    .await?;
+            },
+            IntegrityFix::RewriteItemLabels { item_id, labels } => {
+                items::Entity::update_many()
+                    .col_expr(items::Column::Labels, Expr::value(labels.clone()))
+                    .filter(items::Column::Id.eq(item_id))
+                    .exec(conn)
+                    .await?;
+            },
+            IntegrityFix::DeleteRow { table, rowid } => {
+                // 表名来自外键检查，只允许已知的关联表
+                let Some(table) = LINK_TABLES.iter().find(|name| name.eq_ignore_ascii_case(table))
+                else {
+                    return Err(TodoError::ValidationError(format!("不能删除表 {} 中的行", table)));
+                };
+                conn.execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    format!("DELETE FROM {} WHERE rowid = ?", table),
+                    [Value::from(*rowid)],
+                ))
+                .await?;
+            },
+        }
+        Ok(())
+    }
+
+    async fn check_sqlite_integrity(&self) -> Result<Vec<IntegrityIssue>, TodoError> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(DbBackend::Sqlite, "PRAGMA integrity_check"))
+            .await?;
+        let mut issues = Vec::new();
+        for row in rows {
+            let message: String = row.try_get("", "integrity_check")?;
+            if message != "ok" {
+                issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::Corruption,
+                    object_id: String::new(),
+                    detail: message,
+                    fix: None,
+                });
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn check_foreign_keys(&self) -> Result<Vec<IntegrityIssue>, TodoError> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(DbBackend::Sqlite, "PRAGMA foreign_key_check"))
+            .await?;
+        let mut issues = Vec::new();
+        for row in rows {
+            let table: String = row.try_get("", "table")?;
+            let rowid: Option<i64> = row.try_get("", "rowid")?;
+            let parent: String = row.try_get("", "parent")?;
+            let deletable = LINK_TABLES.iter().any(|name| name.eq_ignore_ascii_case(&table));
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::ForeignKey,
+                detail: format!(
+                    "{} 第 {} 行引用的 {} 不存在",
+                    table,
+                    rowid.map(|rowid| rowid.to_string()).unwrap_or_default(),
+                    parent
+                ),
+                fix: rowid
+                    .filter(|_| deletable)
+                    .map(|rowid| IntegrityFix::DeleteRow { table: table.clone(), rowid }),
+                object_id: table,
+            });
+        }
+        Ok(issues)
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.filter(|value| !value.is_empty())
+}
+
+/// 检查父子引用：父对象不存在的孤儿，以及成环的父链（每个环报告一次，修复时断开环中 ID 最小的对象）
+fn check_parents(
+    parents: &HashMap<&str, Option<&str>>,
+    orphan_kind: IntegrityIssueKind,
+    cycle_kind: IntegrityIssueKind,
+    noun: &str,
+    detach: impl Fn(&str) -> IntegrityFix,
+) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    let mut ids: Vec<&str> = parents.keys().copied().collect();
+    ids.sort_unstable();
+
+    for &id in &ids {
+        if let Some(parent) = parents[id]
+            && !parents.contains_key(parent)
+        {
+            issues.push(IntegrityIssue {
+                kind: orphan_kind,
+                object_id: id.to_string(),
+                detail: format!("{} {} 的父{} {} 不存在", noun, id, noun, parent),
+                fix: Some(detach(id)),
+            });
+        }
+    }
+
+    // 0 / 缺省：未访问；1：在当前路径上；2：已确认不在环中或环已报告
+    let mut state: HashMap<&str, u8> = HashMap::new();
+    for &start in &ids {
+        if state.contains_key(start) {
+            continue;
+        }
+        let mut path: Vec<&str> = Vec::new();
+        let mut node = Some(start);
+        while let Some(id) = node {
+            match state.get(id) {
+                Some(2) => break,
+                Some(_) => {
+                    let position = path.iter().position(|&visited| visited == id).unwrap_or(0);
+                    let cycle = &path[position..];
+                    let root = cycle.iter().copied().min().unwrap_or(id);
+                    issues.push(IntegrityIssue {
+                        kind: cycle_kind,
+                        object_id: root.to_string(),
+                        detail: format!("{}的父子关系成环: {}", noun, cycle.join(" → ")),
+                        fix: Some(detach(root)),
+                    });
+                    break;
+                },
+                None => {},
+            }
+            state.insert(id, 1);
+            path.push(id);
+            node = parents[id].filter(|parent| parents.contains_key(parent));
+        }
+        for id in path {
+            state.insert(id, 2);
+        }
+    }
+    issues
+}
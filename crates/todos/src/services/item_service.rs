@@ -6,36 +6,95 @@
 use std::sync::Arc;
 
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
-    Set, TransactionTrait, prelude::Expr,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, Set, TransactionTrait,
+    prelude::Expr,
+    sea_query::{Alias, Query},
 };
 
 use crate::{
-    entity::{ItemActiveModel, ItemModel, items, prelude::*},
+    DueDate, ItemNode,
+    entity::{
+        ItemActiveModel, ItemLabelActiveModel, ItemModel, item_labels, items, prelude::*, projects,
+        sections,
+    },
     error::TodoError,
     repositories::{
-        BaseRepository, ItemLabelRepository, ItemLabelRepositoryImpl, ItemQueryRepository,
-        ItemRepositoryImpl,
+        BaseRepository, ItemLabelRepository, ItemLabelRepositoryImpl, ItemRepositoryImpl,
     },
-    services::LabelService,
+    services::{LabelService, TrashService, util::replace_item_labels},
     utils::retry_with_context,
 };
 
+/// 手动排序时相邻排序键的间隔
+///
+/// 新位置取前后两个键的中点，只写被移动的一行；间隔用尽时才整组重新编号。
+pub const ORDER_GAP: i32 = 1024;
+
+/// 手动排序的目标位置，锚点必须与被移动的任务在同一列表中
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemPosition {
+    /// 移到锚点任务之前
+    Before(String),
+    /// 移到锚点任务之后
+    After(String),
+    /// 移到列表顶部
+    Top,
+    /// 移到列表底部
+    Bottom,
+}
+
+/// 排序使用的列：项目/分区内的 child_order，或"今天"列表的 day_order
+#[derive(Clone, Copy, Debug)]
+enum OrderKey {
+    Child,
+    Day,
+}
+
+impl OrderKey {
+    fn column(self) -> items::Column {
+        match self {
+            OrderKey::Child => items::Column::ChildOrder,
+            OrderKey::Day => items::Column::DayOrder,
+        }
+    }
+
+    fn get(self, item: &ItemModel) -> Option<i32> {
+        match self {
+            OrderKey::Child => item.child_order,
+            OrderKey::Day => item.day_order,
+        }
+    }
+
+    fn set(self, item: &mut ItemModel, order: i32) {
+        match self {
+            OrderKey::Child => item.child_order = Some(order),
+            OrderKey::Day => item.day_order = Some(order),
+        }
+    }
+
+    fn cmp(self, a: &ItemModel, b: &ItemModel) -> std::cmp::Ordering {
+        match self {
+            OrderKey::Child => a.cmp_child_order(b),
+            OrderKey::Day => a.cmp_day_order(b),
+        }
+    }
+}
+
 /// Service for Item business operations
 #[derive(Clone, Debug)]
 pub struct ItemService {
     db: Arc<DatabaseConnection>,
-    label_service: Arc<LabelService>,
     item_repo: ItemRepositoryImpl,
     item_label_repo: ItemLabelRepositoryImpl,
 }
 
 impl ItemService {
     /// Create a new ItemService
-    pub fn new(db: Arc<DatabaseConnection>, label_service: Arc<LabelService>) -> Self {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
         let item_repo = ItemRepositoryImpl::new(db.clone());
         let item_label_repo = ItemLabelRepositoryImpl::new(db.clone());
-        Self { db, label_service, item_repo, item_label_repo }
+        Self { db, item_repo, item_label_repo }
     }
 
     /// Get an item by ID
@@ -48,6 +107,14 @@ impl ItemService {
         &self,
         item: ItemModel,
         _insert: bool,
+    ) -> Result<ItemModel, TodoError> {
+        Self::insert_item_in_conn(&*self.db, item).await
+    }
+
+    /// 在指定连接（可为事务）中插入任务
+    pub(crate) async fn insert_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
     ) -> Result<ItemModel, TodoError> {
         tracing::info!(
             "📝 [ItemService::insert_item] 开始插入任务, content='{}', project_id={:?}",
@@ -56,17 +123,32 @@ impl ItemService {
         );
 
         let start = std::time::Instant::now();
+        // labels 由 item_labels 关联表维护，插入后同步（快速添加会直接带上标签）
+        let label_ids: Vec<String> = item
+            .labels
+            .as_deref()
+            .map(|raw| {
+                raw.split(';')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
 
         // 将 Model 转为 ActiveModel（Sea-ORM 的可写模型）
         let active_model: ItemActiveModel = item.into();
 
-        // 执行 INSERT（SQLite 自动提交事务）
-        let item_model = match active_model.insert(&*self.db).await {
+        let mut item_model = match active_model.insert(conn).await {
             Ok(model) => model,
             Err(e) => {
                 return Err(TodoError::DatabaseError(format!("INSERT 失败: {}", e)));
             },
         };
+        if !label_ids.is_empty() {
+            replace_item_labels(conn, &item_model.id, &label_ids, item_model.added_at).await?;
+            item_model.labels = Some(label_ids.join(";"));
+        }
 
         tracing::info!(
             "✅ [ItemService::insert_item] INSERT 成功! id={}, 耗时={}ms",
@@ -113,6 +195,19 @@ impl ItemService {
         Ok(updated_item)
     }
 
+    /// 在指定连接（可为事务）中更新任务，不重试
+    pub(crate) async fn update_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
+        now: chrono::NaiveDateTime,
+    ) -> Result<ItemModel, TodoError> {
+        let rows_affected = Self::update_item_fields_in_conn(conn, &item, now).await?;
+        if rows_affected == 0 {
+            return Err(TodoError::not_found("Item").with_entity("Item", &item.id));
+        }
+        Ok(ItemModel { updated_at: now, ..item })
+    }
+
     /// 批量更新任务（单事务）
     pub async fn batch_update_items(
         &self,
@@ -123,35 +218,24 @@ impl ItemService {
         }
 
         let now = chrono::Utc::now().naive_utc();
-        let db = self.db.clone();
-        let items_to_update = items;
-
-        let updated_items = db
+        self.db
             .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
-                let items = items_to_update.clone();
-                Box::pin(async move {
-                    let mut results = Vec::with_capacity(items.len());
-                    for item in items {
-                        let item_id = item.id.clone();
-                        let rows_affected =
-                            Self::update_item_fields_in_conn(txn, &item, now).await?;
-                        if rows_affected == 0 {
-                            return Err(TodoError::not_found("Item").with_entity("Item", &item_id));
-                        }
-                        let mut updated = item;
-                        updated.updated_at = now;
-                        results.push(updated);
-                    }
-                    Ok(results)
-                })
+                Box::pin(async move { Self::batch_update_items_in_conn(txn, items, now).await })
             })
             .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => TodoError::from(db_err),
-                sea_orm::TransactionError::Transaction(err) => err,
-            })?;
+            .map_err(TodoError::from)
+    }
 
-        Ok(updated_items)
+    pub(crate) async fn batch_update_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        items: Vec<ItemModel>,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            results.push(Self::update_item_in_conn(conn, item, now).await?);
+        }
+        Ok(results)
     }
 
     /// 执行数据库更新操作，返回影响行数
@@ -215,16 +299,22 @@ impl ItemService {
 
     /// Delete an item and its children
     ///
-    /// 同时删除 item_labels 关联表中的记录（通过数据库级联删除）
+    /// 软删除：任务及其子任务移入回收站，item_labels 关联保留以便恢复。
+    /// 彻底删除见 [`TrashService::purge`](crate::services::TrashService::purge)。
     pub async fn delete_item(&self, item_id: &str) -> Result<(), TodoError> {
-        let ids = self.collect_descendant_ids(item_id).await?;
-        self.delete_items_by_ids(ids).await?;
-        Ok(())
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move { TrashService::trash_item_in_conn(txn, &item_id, now).await })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
-    /// 收集任务及其所有子任务的 ID（按层批量查询，避免 N+1 逐条删除）
-    pub(crate) async fn collect_descendant_ids(
-        &self,
+    /// 在指定连接（可为事务）中收集任务及其所有子任务的 ID
+    pub(crate) async fn collect_descendant_ids_in_conn<C: ConnectionTrait>(
+        conn: &C,
         root_id: &str,
     ) -> Result<Vec<String>, TodoError> {
         let mut result = vec![root_id.to_string()];
@@ -234,7 +324,7 @@ impl ItemService {
             let batch = std::mem::take(&mut parents_to_search);
             let children = items::Entity::find()
                 .filter(items::Column::ParentId.is_in(batch))
-                .all(&*self.db)
+                .all(conn)
                 .await?;
 
             for child in children {
@@ -246,17 +336,6 @@ impl ItemService {
         Ok(result)
     }
 
-    /// 批量删除任务（item_labels/reminders/attachments 由 FK CASCADE 处理）
-    pub(crate) async fn delete_items_by_ids(&self, ids: Vec<String>) -> Result<(), TodoError> {
-        if ids.is_empty() {
-            return Ok(());
-        }
-
-        items::Entity::delete_many().filter(items::Column::Id.is_in(ids)).exec(&*self.db).await?;
-
-        Ok(())
-    }
-
     /// Update item pin status
     pub async fn update_item_pin(&self, item_id: &str, pinned: bool) -> Result<(), TodoError> {
         let item = self
@@ -272,26 +351,55 @@ impl ItemService {
     }
 
     /// Complete/uncomplete an item
+    ///
+    /// 重复任务（`DueDate.is_recurring`）完成时不会标记为已完成，而是推进到下一次到期日期，
+    /// 直到达到重复次数或结束日期为止。
     pub async fn complete_item(
         &self,
         item_id: &str,
         checked: bool,
         complete_subitems: bool,
     ) -> Result<(), TodoError> {
+        let item_id = item_id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    Self::complete_item_in_conn(txn, &item_id, checked, complete_subitems).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn complete_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        checked: bool,
+        complete_subitems: bool,
+    ) -> Result<(), TodoError> {
+        let item = ItemEntity::find_by_id(item_id)
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Item").with_entity("Item", item_id))?;
+
+        if checked && let Some(next_due) = item.due_date().and_then(|due| due.next_occurrence()) {
+            return Self::roll_recurring_item_in_conn(conn, item, next_due).await;
+        }
+
         let active_model = ItemActiveModel {
             id: Set(item_id.to_string()),
             checked: Set(checked),
             completed_at: Set(if checked { Some(chrono::Utc::now().naive_utc()) } else { None }),
-            ..ItemEntity::find_by_id(item_id)
-                .one(&*self.db)
-                .await?
-                .ok_or_else(|| TodoError::not_found("Item").with_entity("Item", item_id))?
-                .into()
+            ..item.into()
         };
-        let item_model = active_model.update(&*self.db).await?;
+        let item_model = active_model.update(conn).await?;
 
         if complete_subitems {
-            let subitems = ItemQueryRepository::find_by_parent(&self.item_repo, item_id).await?;
+            let subitems = ItemEntity::find()
+                .filter(items::Column::ParentId.eq(item_id))
+                .filter(items::Column::IsDeleted.eq(false))
+                .all(conn)
+                .await?;
             if !subitems.is_empty() {
                 let checked_value = item_model.checked;
                 let completed_at_value =
@@ -305,7 +413,7 @@ impl ItemService {
                     .col_expr(items::Column::CompletedAt, Expr::value(completed_at_value))
                     .col_expr(items::Column::UpdatedAt, Expr::value(now))
                     .filter(items::Column::Id.is_in(sub_ids))
-                    .exec(&*self.db)
+                    .exec(conn)
                     .await?;
             }
         }
@@ -313,6 +421,597 @@ impl ItemService {
         Ok(())
     }
 
+    /// 完成一次重复任务：推进到下一次到期日期
+    ///
+    /// 任务本身保持未完成，所有子任务重置为未完成，并在 OEvents 中记录本次完成。
+    async fn roll_recurring_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
+        next_due: DueDate,
+    ) -> Result<(), TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let old_date = item.due_date().map(|due| due.date).unwrap_or_default();
+
+        tracing::info!(
+            "🔁 [ItemService::roll_recurring_item] id={}, {} -> {}",
+            item.id,
+            old_date,
+            next_due.date
+        );
+
+        let due_value = serde_json::to_value(&next_due)
+            .map_err(|e| TodoError::InternalError(format!("序列化 DueDate 失败: {}", e)))?;
+
+        items::Entity::update_many()
+            .col_expr(items::Column::Due, Expr::value(due_value))
+            .col_expr(items::Column::Checked, Expr::value(false))
+            .col_expr(items::Column::CompletedAt, Expr::value(None::<chrono::NaiveDateTime>))
+            .col_expr(items::Column::UpdatedAt, Expr::value(now))
+            .filter(items::Column::Id.eq(item.id.clone()))
+            .exec(conn)
+            .await?;
+
+        // 重置所有层级的子任务
+        let mut descendant_ids = Self::collect_descendant_ids_in_conn(conn, &item.id).await?;
+        descendant_ids.retain(|id| id != &item.id);
+        if !descendant_ids.is_empty() {
+            items::Entity::update_many()
+                .col_expr(items::Column::Checked, Expr::value(false))
+                .col_expr(items::Column::CompletedAt, Expr::value(None::<chrono::NaiveDateTime>))
+                .col_expr(items::Column::UpdatedAt, Expr::value(now))
+                .filter(items::Column::Id.is_in(descendant_ids))
+                .exec(conn)
+                .await?;
+        }
+
+        // 记录本次完成（event_type = "complete"，旧值/新值为前后两次到期日期）
+        let stmt = Query::insert()
+            .into_table(Alias::new("oevents"))
+            .columns([
+                Alias::new("event_type"),
+                Alias::new("object_id"),
+                Alias::new("object_type"),
+                Alias::new("object_key"),
+                Alias::new("object_old_value"),
+                Alias::new("object_new_value"),
+                Alias::new("parent_item_id"),
+                Alias::new("parent_project_id"),
+            ])
+            .values_panic([
+                "complete".into(),
+                item.id.clone().into(),
+                "item".into(),
+                "due".into(),
+                old_date.into(),
+                next_due.date.clone().into(),
+                item.parent_id.clone().into(),
+                item.project_id.clone().into(),
+            ])
+            .to_owned();
+        conn.execute(conn.get_database_backend().build(&stmt)).await?;
+
+        Ok(())
+    }
+
+    // ==================== Ordering ====================
+
+    /// 在同一项目、分区、父任务下的兄弟任务中移动（写 child_order）
+    ///
+    /// 返回所有排序键被改写的任务：通常只有被移动的一行，间隔用尽时为整组兄弟任务。
+    pub async fn move_item(
+        &self,
+        item_id: &str,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        self.reorder(item_id, position, OrderKey::Child).await
+    }
+
+    /// 在同一天到期的未完成任务中移动（写 day_order），用于"今天"列表的手动排序
+    pub async fn move_item_in_day(
+        &self,
+        item_id: &str,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        self.reorder(item_id, position, OrderKey::Day).await
+    }
+
+    async fn reorder(
+        &self,
+        item_id: &str,
+        position: ItemPosition,
+        key: OrderKey,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let item = Self::find_item_in_conn(txn, &item_id).await?;
+                    Self::reorder_in_conn(txn, item, &position, key, now).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 在指定连接（可为事务）中把任务放到目标位置，返回排序键被改写的任务
+    async fn reorder_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
+        position: &ItemPosition,
+        key: OrderKey,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let mut siblings = Self::order_siblings_in_conn(conn, &item, key).await?;
+        siblings.sort_by(|a, b| key.cmp(a, b));
+
+        let index = match position {
+            ItemPosition::Top => 0,
+            ItemPosition::Bottom => siblings.len(),
+            ItemPosition::Before(anchor) | ItemPosition::After(anchor) => {
+                let pos = siblings.iter().position(|s| &s.id == anchor).ok_or_else(|| {
+                    TodoError::validation(format!(
+                        "锚点任务 {} 与任务 {} 不在同一列表中",
+                        anchor, item.id
+                    ))
+                })?;
+                if matches!(position, ItemPosition::After(_)) { pos + 1 } else { pos }
+            },
+        };
+
+        let prev = index.checked_sub(1).map(|i| key.get(&siblings[i]));
+        let next = siblings.get(index).and_then(|s| key.get(s));
+        // 未排序（None）的任务排在最后：后面没有已排序的键时可以直接追加
+        let slot = match (prev, next) {
+            (None, None) => Some(ORDER_GAP),
+            (None, Some(next)) => next.checked_sub(ORDER_GAP),
+            (Some(None), _) => None,
+            (Some(Some(prev)), None) => prev.checked_add(ORDER_GAP),
+            (Some(Some(prev)), Some(next)) => {
+                let (prev, next) = (i64::from(prev), i64::from(next));
+                (next - prev > 1).then(|| ((prev + next) / 2) as i32)
+            },
+        };
+
+        let mut changed = Vec::new();
+        match slot {
+            Some(order) => {
+                changed.push(Self::write_order_in_conn(conn, item, key, order, now).await?)
+            },
+            None => {
+                tracing::debug!("排序键间隔用尽，重新编号 {} 个任务", siblings.len() + 1);
+                siblings.insert(index, item);
+                for (i, sibling) in siblings.into_iter().enumerate() {
+                    let order = ORDER_GAP.saturating_mul(i as i32 + 1);
+                    if key.get(&sibling) != Some(order) {
+                        changed
+                            .push(Self::write_order_in_conn(conn, sibling, key, order, now).await?);
+                    }
+                }
+            },
+        }
+        Ok(changed)
+    }
+
+    /// 在指定连接（可为事务）中把任务放到同一列表中锚点任务之后
+    pub(crate) async fn place_after_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
+        anchor_id: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let position = ItemPosition::After(anchor_id.to_string());
+        Self::reorder_in_conn(conn, item, &position, OrderKey::Child, now).await
+    }
+
+    async fn find_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+    ) -> Result<ItemModel, TodoError> {
+        ItemEntity::find_by_id(item_id.to_string())
+            .one(conn)
+            .await?
+            .filter(|item| !item.is_deleted)
+            .ok_or_else(|| TodoError::not_found("Item").with_entity("Item", item_id))
+    }
+
+    /// 与任务处于同一排序列表的其他未删除任务（不含任务本身）
+    async fn order_siblings_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: &ItemModel,
+        key: OrderKey,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let query = ItemEntity::find()
+            .filter(items::Column::Id.ne(item.id.clone()))
+            .filter(items::Column::IsDeleted.eq(false));
+        match key {
+            OrderKey::Child => Ok(query
+                .filter(Self::same_container(items::Column::ProjectId, item.project_id.as_deref()))
+                .filter(Self::same_container(items::Column::SectionId, item.section_id.as_deref()))
+                .filter(Self::same_container(items::Column::ParentId, item.parent_id.as_deref()))
+                .all(conn)
+                .await?),
+            OrderKey::Day => {
+                let day = item.due_date_naive().ok_or_else(|| {
+                    TodoError::validation(format!("任务 {} 没有截止日期，无法按天排序", item.id))
+                })?;
+                // 截止日期存为 JSON，按天过滤在内存中完成
+                let candidates = query
+                    .filter(items::Column::Checked.eq(false))
+                    .filter(items::Column::Due.is_not_null())
+                    .all(conn)
+                    .await?;
+                Ok(candidates.into_iter().filter(|other| other.is_due_on_date(day)).collect())
+            },
+        }
+    }
+
+    /// 收件箱任务的 project_id 可能为 NULL 或空字符串，两者视为同一列表
+    fn same_container(column: items::Column, value: Option<&str>) -> Condition {
+        match value.filter(|value| !value.is_empty()) {
+            Some(value) => Condition::all().add(column.eq(value)),
+            None => Condition::any().add(column.is_null()).add(column.eq("")),
+        }
+    }
+
+    async fn write_order_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        mut item: ItemModel,
+        key: OrderKey,
+        order: i32,
+        now: chrono::NaiveDateTime,
+    ) -> Result<ItemModel, TodoError> {
+        items::Entity::update_many()
+            .col_expr(key.column(), Expr::value(order))
+            .col_expr(items::Column::UpdatedAt, Expr::value(now))
+            .filter(items::Column::Id.eq(item.id.clone()))
+            .exec(conn)
+            .await?;
+        key.set(&mut item, order);
+        item.updated_at = now;
+        Ok(item)
+    }
+
+    // ==================== Subtask Tree ====================
+
+    /// 获取任务及其所有未删除的子孙组成的树，子任务按 child_order 排序
+    pub async fn get_item_tree(&self, item_id: &str) -> Result<ItemNode, TodoError> {
+        let ids = Self::collect_descendant_ids_in_conn(&*self.db, item_id).await?;
+        let items = ItemEntity::find()
+            .filter(items::Column::Id.is_in(ids))
+            .filter(items::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
+        ItemNode::build_forest(items)
+            .into_iter()
+            .find(|node| node.item.id == item_id)
+            .ok_or_else(|| TodoError::not_found("Item").with_entity("Item", item_id))
+    }
+
+    /// 缩进：成为前一个兄弟任务的最后一个子任务，子孙随之移动
+    ///
+    /// 返回所有被改写的任务，第一个为被移动的任务。
+    pub async fn indent(&self, item_id: &str) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move { Self::indent_in_conn(txn, &item_id, now).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn indent_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item = Self::find_item_in_conn(conn, item_id).await?;
+        let siblings = Self::order_siblings_in_conn(conn, &item, OrderKey::Child).await?;
+        let parent = siblings
+            .into_iter()
+            .filter(|sibling| sibling.cmp_child_order(&item).is_lt())
+            .max_by(|a, b| a.cmp_child_order(b))
+            .ok_or_else(|| {
+                TodoError::validation(format!("任务 {} 前面没有可作为父任务的任务", item_id))
+            })?;
+        Self::reparent_in_conn(conn, item, Some(&parent), &ItemPosition::Bottom, now).await
+    }
+
+    /// 取消缩进：移到父任务之后，成为父任务的兄弟，子孙随之移动
+    ///
+    /// 返回所有被改写的任务，第一个为被移动的任务。
+    pub async fn outdent(&self, item_id: &str) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move { Self::outdent_in_conn(txn, &item_id, now).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn outdent_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item = Self::find_item_in_conn(conn, item_id).await?;
+        let parent_id = item
+            .parent_id
+            .clone()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| TodoError::validation(format!("任务 {} 已是顶层任务", item_id)))?;
+        let parent = Self::find_item_in_conn(conn, &parent_id).await?;
+        let grandparent = match parent.parent_id.as_deref().filter(|id| !id.is_empty()) {
+            Some(id) => Some(Self::find_item_in_conn(conn, id).await?),
+            None => None,
+        };
+        let position = ItemPosition::After(parent.id.clone());
+        let item = ItemModel {
+            project_id: parent.project_id.clone(),
+            section_id: parent.section_id.clone(),
+            ..item
+        };
+        Self::reparent_in_conn(conn, item, grandparent.as_ref(), &position, now).await
+    }
+
+    /// 把任务连同子孙移到新的父任务下（`None` 为顶层），`position` 为在新兄弟任务中的位置
+    ///
+    /// 新父任务不能是任务本身或它的子孙；父任务在其他项目或分区时，整棵子树一起跟过去。
+    /// 返回所有被改写的任务，第一个为被移动的任务。
+    pub async fn move_subtree(
+        &self,
+        item_id: &str,
+        new_parent_id: Option<&str>,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let new_parent_id = new_parent_id.map(str::to_string);
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    Self::move_subtree_in_conn(
+                        txn,
+                        &item_id,
+                        new_parent_id.as_deref(),
+                        position,
+                        now,
+                    )
+                    .await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn move_subtree_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        new_parent_id: Option<&str>,
+        position: ItemPosition,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item = Self::find_item_in_conn(conn, item_id).await?;
+        let parent = match new_parent_id.filter(|id| !id.is_empty()) {
+            Some(parent_id) => {
+                let subtree = Self::collect_descendant_ids_in_conn(conn, item_id).await?;
+                if subtree.iter().any(|id| id == parent_id) {
+                    return Err(TodoError::validation(format!(
+                        "不能把任务 {} 移到自己或自己的子任务 {} 下",
+                        item_id, parent_id
+                    )));
+                }
+                Some(Self::find_item_in_conn(conn, parent_id).await?)
+            },
+            None => None,
+        };
+        Self::reparent_in_conn(conn, item, parent.as_ref(), &position, now).await
+    }
+
+    /// 把一组任务连同子孙移到另一个项目 / 分区，放在目标列表末尾
+    ///
+    /// `project_id` 为 None 表示收件箱；`section_id` 为 None 表示不放入分区，否则必须属于目标项目。
+    /// 被移动的任务成为顶层任务，子孙保持原有层级并随之更新项目和分区；
+    /// 某个任务的祖先也在列表中时，它只随祖先移动。全部移动在一个事务中完成，
+    /// 返回所有被改写的任务。
+    pub async fn move_items(
+        &self,
+        item_ids: &[String],
+        project_id: Option<&str>,
+        section_id: Option<&str>,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item_ids = item_ids.to_vec();
+        let project_id = project_id.map(str::to_string);
+        let section_id = section_id.map(str::to_string);
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    Self::move_items_in_conn(
+                        txn,
+                        &item_ids,
+                        project_id.as_deref(),
+                        section_id.as_deref(),
+                        now,
+                    )
+                    .await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn move_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_ids: &[String],
+        project_id: Option<&str>,
+        section_id: Option<&str>,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let project_id = project_id.filter(|id| !id.is_empty());
+        let section_id = section_id.filter(|id| !id.is_empty());
+        Self::check_move_target_in_conn(conn, project_id, section_id).await?;
+
+        // 已在其他被移动任务子树中的任务随祖先移动，不单独处理
+        let mut subtrees: Vec<(String, Vec<String>)> = Vec::new();
+        for id in item_ids {
+            if subtrees.iter().any(|(root, _)| root == id) {
+                continue;
+            }
+            let ids = Self::collect_descendant_ids_in_conn(conn, id).await?;
+            subtrees.push((id.clone(), ids));
+        }
+        let roots: Vec<&String> = subtrees
+            .iter()
+            .filter(|(root, _)| {
+                !subtrees.iter().any(|(other, ids)| other != root && ids.contains(root))
+            })
+            .map(|(root, _)| root)
+            .collect();
+
+        let mut changed: Vec<ItemModel> = Vec::new();
+        for root in roots {
+            let item = Self::find_item_in_conn(conn, root).await?;
+            let item = ItemModel {
+                project_id: project_id.map(str::to_string),
+                section_id: section_id.map(str::to_string),
+                ..item
+            };
+            let rows = Self::reparent_in_conn(conn, item, None, &ItemPosition::Bottom, now).await?;
+            // 同一任务可能被多次改写（如重新编号），保留最新的一行
+            for row in rows {
+                match changed.iter_mut().find(|other| other.id == row.id) {
+                    Some(other) => *other = row,
+                    None => changed.push(row),
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// 检查移动目标：项目存在，分区存在且属于该项目
+    async fn check_move_target_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project_id: Option<&str>,
+        section_id: Option<&str>,
+    ) -> Result<(), TodoError> {
+        if let Some(project_id) = project_id {
+            ProjectEntity::find_by_id(project_id)
+                .filter(projects::Column::IsDeleted.eq(false))
+                .one(conn)
+                .await?
+                .ok_or_else(|| {
+                    TodoError::not_found("Project").with_entity("Project", project_id)
+                })?;
+        }
+        if let Some(section_id) = section_id {
+            let section = SectionEntity::find_by_id(section_id)
+                .filter(sections::Column::IsDeleted.eq(false))
+                .one(conn)
+                .await?
+                .ok_or_else(|| {
+                    TodoError::not_found("Section").with_entity("Section", section_id)
+                })?;
+            if section.project_id.as_deref().filter(|id| !id.is_empty()) != project_id {
+                return Err(TodoError::validation(format!(
+                    "分区 {} 不属于目标项目 {}",
+                    section_id,
+                    project_id.unwrap_or("Inbox")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 设置任务的父任务并放到新兄弟任务中的指定位置
+    ///
+    /// 有父任务时项目和分区随父任务；没有时沿用 `item` 上的值。项目或分区变化时子孙一起更新。
+    async fn reparent_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item: ItemModel,
+        parent: Option<&ItemModel>,
+        position: &ItemPosition,
+        now: chrono::NaiveDateTime,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let before = Self::find_item_in_conn(conn, &item.id).await?;
+        let (project_id, section_id) = match parent {
+            Some(parent) => (parent.project_id.clone(), parent.section_id.clone()),
+            None => (item.project_id.clone(), item.section_id.clone()),
+        };
+        let item = ItemModel {
+            parent_id: parent.map(|parent| parent.id.clone()),
+            project_id,
+            section_id,
+            updated_at: now,
+            ..item
+        };
+        items::Entity::update_many()
+            .col_expr(items::Column::ParentId, Expr::value(item.parent_id.clone()))
+            .col_expr(items::Column::ProjectId, Expr::value(item.project_id.clone()))
+            .col_expr(items::Column::SectionId, Expr::value(item.section_id.clone()))
+            .col_expr(items::Column::UpdatedAt, Expr::value(now))
+            .filter(items::Column::Id.eq(item.id.clone()))
+            .exec(conn)
+            .await?;
+
+        let mut descendants = Vec::new();
+        if (&before.project_id, &before.section_id) != (&item.project_id, &item.section_id) {
+            let mut ids = Self::collect_descendant_ids_in_conn(conn, &item.id).await?;
+            ids.retain(|id| *id != item.id);
+            if !ids.is_empty() {
+                items::Entity::update_many()
+                    .col_expr(items::Column::ProjectId, Expr::value(item.project_id.clone()))
+                    .col_expr(items::Column::SectionId, Expr::value(item.section_id.clone()))
+                    .col_expr(items::Column::UpdatedAt, Expr::value(now))
+                    .filter(items::Column::Id.is_in(ids.clone()))
+                    .exec(conn)
+                    .await?;
+                descendants =
+                    ItemEntity::find().filter(items::Column::Id.is_in(ids)).all(conn).await?;
+            }
+        }
+
+        let reordered =
+            Self::reorder_in_conn(conn, item.clone(), position, OrderKey::Child, now).await?;
+        let moved_id = item.id.clone();
+        let moved = reordered.iter().find(|other| other.id == moved_id).cloned().unwrap_or(item);
+        let mut changed = vec![moved];
+        changed.extend(reordered.into_iter().filter(|other| other.id != moved_id));
+        changed.extend(descendants);
+        Ok(changed)
+    }
+
+    /// 折叠 / 展开子任务
+    pub async fn set_item_collapsed(
+        &self,
+        item_id: &str,
+        collapsed: bool,
+    ) -> Result<ItemModel, TodoError> {
+        Self::set_item_collapsed_in_conn(&*self.db, item_id, collapsed).await
+    }
+
+    pub(crate) async fn set_item_collapsed_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        collapsed: bool,
+    ) -> Result<ItemModel, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let result = items::Entity::update_many()
+            .col_expr(items::Column::Collapsed, Expr::value(collapsed))
+            .col_expr(items::Column::UpdatedAt, Expr::value(now))
+            .filter(items::Column::Id.eq(item_id))
+            .exec(conn)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(TodoError::not_found("Item").with_entity("Item", item_id));
+        }
+        Self::find_item_in_conn(conn, item_id).await
+    }
+
     // ==================== Additional Business Logic Methods ====================
 
     /// Get all items in a project
@@ -322,6 +1021,7 @@ impl ItemService {
     ) -> Result<Vec<ItemModel>, TodoError> {
         let items = ItemEntity::find()
             .filter(items::Column::ProjectId.eq(project_id))
+            .filter(items::Column::IsDeleted.eq(false))
             .all(&*self.db)
             .await?;
         Ok(items)
@@ -332,8 +1032,24 @@ impl ItemService {
     /// 🚀 关键修复：使用批量加载 labels，避免 N+1 查询问题
     /// 原来：每个 item 都会触发一次 get_labels_by_item 查询
     /// 现在：只触发一次 get_all_item_labels 查询，然后将结果填充到 items
+    ///
+    /// 已归档的任务以及归档项目中的任务不返回
     pub async fn get_all_items(&self) -> Result<Vec<ItemModel>, TodoError> {
-        let items = ItemEntity::find().all(&*self.db).await?;
+        let archived_projects = Query::select()
+            .column(projects::Column::Id)
+            .from(projects::Entity)
+            .and_where(projects::Column::IsArchived.eq(true))
+            .to_owned();
+        let items = ItemEntity::find()
+            .filter(items::Column::IsDeleted.eq(false))
+            .filter(items::Column::ArchivedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(items::Column::ProjectId.is_null())
+                    .add(items::Column::ProjectId.not_in_subquery(archived_projects)),
+            )
+            .all(&*self.db)
+            .await?;
 
         tracing::info!("get_all_items: loaded {} items from database", items.len());
 
@@ -364,9 +1080,24 @@ impl ItemService {
         item_id: &str,
         label_name: &str,
     ) -> Result<(), TodoError> {
-        let label = self.label_service.get_or_create_label(label_name, item_id).await?;
+        Self::add_label_to_item_in_conn(&*self.db, item_id, label_name).await
+    }
+
+    pub(crate) async fn add_label_to_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        label_name: &str,
+    ) -> Result<(), TodoError> {
+        let label = LabelService::get_or_create_label_in_conn(conn, label_name, item_id).await?;
 
-        self.item_label_repo.add_label_to_item(item_id, &label.id).await?;
+        ItemLabelActiveModel {
+            item_id: Set(item_id.to_string()),
+            label_id: Set(label.id),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await
+        .map_err(|e| TodoError::DatabaseError(format!("Failed to add label to item: {}", e)))?;
 
         Ok(())
     }
@@ -379,7 +1110,22 @@ impl ItemService {
         item_id: &str,
         label_id: &str,
     ) -> Result<(), TodoError> {
-        self.item_label_repo.remove_label_from_item(item_id, label_id).await?;
+        Self::remove_label_from_item_in_conn(&*self.db, item_id, label_id).await
+    }
+
+    pub(crate) async fn remove_label_from_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        label_id: &str,
+    ) -> Result<(), TodoError> {
+        item_labels::Entity::delete_many()
+            .filter(item_labels::Column::ItemId.eq(item_id))
+            .filter(item_labels::Column::LabelId.eq(label_id))
+            .exec(conn)
+            .await
+            .map_err(|e| {
+                TodoError::DatabaseError(format!("Failed to remove label from item: {}", e))
+            })?;
 
         Ok(())
     }
@@ -403,7 +1149,23 @@ impl ItemService {
         item_id: &str,
         label_ids: &[String],
     ) -> Result<(), TodoError> {
-        self.item_label_repo.set_item_labels(item_id, label_ids).await?;
+        Self::set_item_labels_in_conn(&*self.db, item_id, label_ids).await
+    }
+
+    pub(crate) async fn set_item_labels_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        label_ids: &[String],
+    ) -> Result<(), TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        replace_item_labels(conn, item_id, label_ids, now).await?;
+        // 同步旧的 labels 字段，避免与关联表不一致
+        let labels = (!label_ids.is_empty()).then(|| label_ids.join(";"));
+        items::Entity::update_many()
+            .col_expr(items::Column::Labels, Expr::value(labels))
+            .filter(items::Column::Id.eq(item_id))
+            .exec(conn)
+            .await?;
 
         Ok(())
     }
@@ -3,28 +3,45 @@
 //! This module provides business logic for Label operations,
 //! separating it from data access layer.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, Set, TransactionTrait, prelude::Expr,
+    sea_query::JoinType,
+};
 
 use crate::{
-    entity::{LabelActiveModel, LabelModel, labels, prelude::*},
+    entity::{
+        ItemLabelActiveModel, ItemLabelModel, LabelActiveModel, LabelModel, item_labels, items,
+        labels, prelude::*,
+    },
     error::TodoError,
-    repositories::{BaseRepository, LabelRepositoryImpl},
+    services::{
+        TrashService,
+        util::{insert_batched, revive_labels},
+    },
 };
 
+/// 标签合并的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelMerge {
+    /// 合并后保留的标签
+    pub label: LabelModel,
+    /// 关联发生变化的任务
+    pub item_ids: Vec<String>,
+}
+
 /// Service for Label business operations
 #[derive(Clone, Debug)]
 pub struct LabelService {
     db: Arc<DatabaseConnection>,
-    label_repo: LabelRepositoryImpl,
 }
 
 impl LabelService {
     /// Create a new LabelService
     pub fn new(db: Arc<DatabaseConnection>) -> Self {
-        let label_repo = LabelRepositoryImpl::new(db.clone());
-        Self { db, label_repo }
+        Self { db }
     }
 
     /// Insert a new label
@@ -36,6 +53,13 @@ impl LabelService {
 
     /// Update an existing label
     pub async fn update_label(&self, label: LabelModel) -> Result<LabelModel, TodoError> {
+        Self::update_label_in_conn(&*self.db, label).await
+    }
+
+    pub(crate) async fn update_label_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        label: LabelModel,
+    ) -> Result<LabelModel, TodoError> {
         let active_label = LabelActiveModel {
             id: Set(label.id),
             name: Set(label.name),
@@ -45,34 +69,48 @@ impl LabelService {
             is_favorite: Set(label.is_favorite),
             backend_type: Set(label.backend_type),
             source_id: Set(label.source_id),
+            deleted_at: Set(label.deleted_at),
         };
 
-        active_label.update(&*self.db).await.map_err(TodoError::from)
+        active_label.update(conn).await.map_err(TodoError::from)
     }
 
     /// Delete a label
+    ///
+    /// 软删除：标签移入回收站，任务上的关联保留，恢复后原样生效
     pub async fn delete_label(&self, id: &str) -> Result<u64, TodoError> {
-        let deleted = BaseRepository::delete(&self.label_repo, id).await?;
-        Ok(if deleted { 1 } else { 0 })
+        let now = chrono::Utc::now().naive_utc();
+        TrashService::trash_label_in_conn(&*self.db, id, now).await
     }
 
     /// Get or create a label by name
     ///
     /// 🚀 修复：首先全局查找 name（忽略 source_id），避免 UNIQUE constraint 错误
     /// 因为 labels 表有 UNIQUE(name) 约束，相同 name 的 label 只能存在一个
+    ///
+    /// 同名标签在回收站中时取回它，但不带回删除前的任务关联（见 `util::revive_labels`）
     pub async fn get_or_create_label(
         &self,
         name: &str,
         source_id: &str,
     ) -> Result<LabelModel, TodoError> {
-        let existing = LabelEntity::find()
-            .filter(labels::Column::Name.eq(name))
-            .filter(labels::Column::IsDeleted.eq(false))
-            .one(&*self.db)
-            .await?;
+        Self::get_or_create_label_in_conn(&*self.db, name, source_id).await
+    }
+
+    pub(crate) async fn get_or_create_label_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        name: &str,
+        source_id: &str,
+    ) -> Result<LabelModel, TodoError> {
+        let existing = LabelEntity::find().filter(labels::Column::Name.eq(name)).one(conn).await?;
 
         if let Some(label) = existing {
-            return Ok(label);
+            if !label.is_deleted {
+                return Ok(label);
+            }
+            // 同名标签在回收站中：只恢复标签本身，避免 UNIQUE(name) 冲突
+            revive_labels(conn, vec![label.id.clone()]).await?;
+            return Ok(LabelModel { is_deleted: false, deleted_at: None, ..label });
         }
 
         let new_label = LabelModel {
@@ -82,13 +120,229 @@ impl LabelService {
             ..Default::default()
         };
 
-        self.insert_label(new_label).await
+        LabelActiveModel::from(new_label).insert(conn).await.map_err(TodoError::from)
     }
 
-    /// Get all labels
+    /// Get all labels，按 item_order、名称排序
     pub async fn get_all_labels(&self) -> Result<Vec<LabelModel>, TodoError> {
-        let labels =
-            LabelEntity::find().filter(labels::Column::IsDeleted.eq(false)).all(&*self.db).await?;
+        let labels = LabelEntity::find()
+            .filter(labels::Column::IsDeleted.eq(false))
+            .order_by_asc(labels::Column::ItemOrder)
+            .order_by_asc(labels::Column::Name)
+            .all(&*self.db)
+            .await?;
         Ok(labels)
     }
+
+    /// Rename a label
+    ///
+    /// 名称不区分大小写地与其他标签（含回收站中的）冲突时返回 `AlreadyExists`，
+    /// 由调用方决定是否改为合并；只改大小写不算冲突
+    pub async fn rename_label(&self, id: &str, name: &str) -> Result<LabelModel, TodoError> {
+        Self::rename_label_in_conn(&*self.db, id, name).await
+    }
+
+    pub(crate) async fn rename_label_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+        name: &str,
+    ) -> Result<LabelModel, TodoError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(TodoError::validation("标签名称不能为空"));
+        }
+        let label = Self::find_live_in_conn(conn, id).await?;
+        let lower = name.to_lowercase();
+        let conflict = LabelEntity::find()
+            .filter(labels::Column::Id.ne(id))
+            .all(conn)
+            .await?
+            .into_iter()
+            .find(|other| other.name.to_lowercase() == lower);
+        if let Some(other) = conflict {
+            let place = if other.is_deleted { "（在回收站中）" } else { "" };
+            return Err(TodoError::already_exists(format!("标签「{}」{}", other.name, place)));
+        }
+
+        let active_label = LabelActiveModel { name: Set(name.to_string()), ..label.into() };
+        active_label.update(conn).await.map_err(TodoError::from)
+    }
+
+    /// Merge `source_id` into `target_id`
+    ///
+    /// 在一个事务中把源标签的任务关联改指向目标标签（已有目标标签的任务只去掉源标签），
+    /// 同步任务的 labels 字段，然后把源标签移入回收站；任一标签是收藏时合并结果也是收藏
+    pub async fn merge_labels(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<LabelMerge, TodoError> {
+        let source_id = source_id.to_string();
+        let target_id = target_id.to_string();
+        self.db
+            .transaction::<_, LabelMerge, TodoError>(|txn| {
+                Box::pin(
+                    async move { Self::merge_labels_in_conn(txn, &source_id, &target_id).await },
+                )
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn merge_labels_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<LabelMerge, TodoError> {
+        if source_id == target_id {
+            return Err(TodoError::validation("不能把标签合并到自身"));
+        }
+        let source = Self::find_live_in_conn(conn, source_id).await?;
+        let target = Self::find_live_in_conn(conn, target_id).await?;
+        let now = chrono::Utc::now().naive_utc();
+
+        let item_ids: Vec<String> = item_labels::Entity::find()
+            .filter(item_labels::Column::LabelId.eq(&source.id))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|link| link.item_id)
+            .collect();
+        let tagged: Vec<String> = item_labels::Entity::find()
+            .filter(item_labels::Column::LabelId.eq(&target.id))
+            .filter(item_labels::Column::ItemId.is_in(item_ids.clone()))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|link| link.item_id)
+            .collect();
+        insert_batched(
+            conn,
+            item_ids
+                .iter()
+                .filter(|item_id| !tagged.contains(item_id))
+                .map(|item_id| {
+                    ItemLabelActiveModel::from(ItemLabelModel {
+                        item_id: item_id.clone(),
+                        label_id: target.id.clone(),
+                        created_at: now,
+                    })
+                })
+                .collect(),
+        )
+        .await?;
+        item_labels::Entity::delete_many()
+            .filter(item_labels::Column::LabelId.eq(&source.id))
+            .exec(conn)
+            .await?;
+
+        // 任务上冗余的 labels 字段：源标签替换为目标标签，保持原有顺序
+        let tagged_items = items::Entity::find()
+            .filter(items::Column::Id.is_in(item_ids.clone()))
+            .all(conn)
+            .await?;
+        for item in tagged_items {
+            let mut ids: Vec<&str> = Vec::new();
+            for id in item.labels.as_deref().unwrap_or_default().split(';') {
+                let id = if id == source.id { target.id.as_str() } else { id };
+                if !id.is_empty() && !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            if !ids.contains(&target.id.as_str()) {
+                ids.push(target.id.as_str());
+            }
+            items::Entity::update_many()
+                .col_expr(items::Column::Labels, Expr::value(ids.join(";")))
+                .filter(items::Column::Id.eq(&item.id))
+                .exec(conn)
+                .await?;
+        }
+
+        TrashService::trash_label_in_conn(conn, &source.id, now).await?;
+        let label = if source.is_favorite && !target.is_favorite {
+            LabelActiveModel { is_favorite: Set(true), ..target.into() }.update(conn).await?
+        } else {
+            target
+        };
+        Ok(LabelMerge { label, item_ids })
+    }
+
+    /// Reorder labels
+    ///
+    /// 按 `ids` 的顺序重写 item_order（从 0 开始），返回更新后的标签
+    pub async fn reorder_labels(&self, ids: &[String]) -> Result<Vec<LabelModel>, TodoError> {
+        let ids = ids.to_vec();
+        self.db
+            .transaction::<_, Vec<LabelModel>, TodoError>(|txn| {
+                Box::pin(async move { Self::reorder_labels_in_conn(txn, &ids).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn reorder_labels_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        ids: &[String],
+    ) -> Result<Vec<LabelModel>, TodoError> {
+        let mut reordered = Vec::with_capacity(ids.len());
+        for (order, id) in ids.iter().enumerate() {
+            let label = LabelEntity::find_by_id(id)
+                .filter(labels::Column::IsDeleted.eq(false))
+                .one(conn)
+                .await?
+                .ok_or_else(|| TodoError::not_found("Label").with_entity("Label", id))?;
+            let active_label = LabelActiveModel { item_order: Set(order as i32), ..label.into() };
+            reordered.push(active_label.update(conn).await?);
+        }
+        Ok(reordered)
+    }
+
+    /// Mark or unmark a label as favorite
+    pub async fn set_label_favorite(
+        &self,
+        id: &str,
+        is_favorite: bool,
+    ) -> Result<LabelModel, TodoError> {
+        Self::set_label_favorite_in_conn(&*self.db, id, is_favorite).await
+    }
+
+    pub(crate) async fn set_label_favorite_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+        is_favorite: bool,
+    ) -> Result<LabelModel, TodoError> {
+        let label = Self::find_live_in_conn(conn, id).await?;
+        let active_label = LabelActiveModel { is_favorite: Set(is_favorite), ..label.into() };
+        active_label.update(conn).await.map_err(TodoError::from)
+    }
+
+    /// Count tasks per label
+    ///
+    /// 只统计未删除的任务（含已完成）；没有任务的标签不在结果中
+    pub async fn get_label_usage(&self) -> Result<HashMap<String, u64>, TodoError> {
+        let rows: Vec<(String, i64)> = item_labels::Entity::find()
+            .select_only()
+            .column(item_labels::Column::LabelId)
+            .column_as(item_labels::Column::ItemId.count(), "usage")
+            .join(JoinType::InnerJoin, item_labels::Relation::Item.def())
+            .filter(items::Column::IsDeleted.eq(false))
+            .group_by(item_labels::Column::LabelId)
+            .into_tuple()
+            .all(&*self.db)
+            .await?;
+        Ok(rows.into_iter().map(|(label_id, usage)| (label_id, usage as u64)).collect())
+    }
+
+    /// 未删除的标签
+    async fn find_live_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<LabelModel, TodoError> {
+        LabelEntity::find_by_id(id)
+            .filter(labels::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Label").with_entity("Label", id))
+    }
 }
@@ -1,14 +1,43 @@
 pub mod attachment_service;
+pub mod caldav_service;
+pub mod duplicate_service;
+pub mod event_service;
+pub mod export_service;
+pub mod filter_service;
+pub mod ical_service;
+pub mod import_service;
+pub mod integrity_service;
 pub mod item_service;
 pub mod label_service;
 pub mod project_service;
 pub mod reminder_service;
+pub mod search_service;
 pub mod section_service;
 pub mod store;
+pub mod todoist_sync_service;
+pub mod trash_service;
+mod util;
 pub use attachment_service::AttachmentService;
-pub use item_service::ItemService;
-pub use label_service::LabelService;
-pub use project_service::ProjectService;
+pub use caldav_service::{CALDAV_SOURCE_TYPE, CalDavConfig, CalDavService, CalDavSyncReport};
+pub use duplicate_service::{DuplicateResult, DuplicateService};
+pub use event_service::{EventEntry, EventKind, EventService};
+pub use export_service::{EXPORT_VERSION, ExportFormat, ExportService, WorkspaceExport};
+pub use filter_service::FilterService;
+pub use ical_service::{ICS_PRODID, IcalService, IcsComponent};
+pub use import_service::{ImportReport, ImportService};
+pub use integrity_service::{
+    IntegrityFix, IntegrityIssue, IntegrityIssueKind, IntegrityReport, IntegrityService,
+};
+pub use item_service::{ItemPosition, ItemService, ORDER_GAP};
+pub use label_service::{LabelMerge, LabelService};
+pub use project_service::{ArchivedProject, ProjectService};
 pub use reminder_service::ReminderService;
+pub use search_service::{
+    DEFAULT_SEARCH_LIMIT, HighlightedText, SearchHit, SearchObjectType, SearchService,
+};
 pub use section_service::SectionService;
 pub use store::Store;
+pub use todoist_sync_service::{
+    LocalChange, TODOIST_SOURCE_TYPE, TodoistConfig, TodoistSyncReport, TodoistSyncService,
+};
+pub use trash_service::{TrashEntry, TrashObjectType, TrashService};
@@ -2,34 +2,77 @@
 //!
 //! This module provides business logic for Project operations,
 //! separating it from data access layer.
+//!
+//! 归档项目时子项目、分区与未完成任务一起标记，共享同一个 `archived_at`，
+//! 取消归档时据此整体还原；之前单独归档的分区 / 子项目保持归档。
+//!
+//! 项目模板见 [`ProjectTemplate`]：保存时截止日期换算为相对天数，实例化时按开始日期还原。
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set, TransactionTrait, prelude::Expr,
+};
+use uuid::Uuid;
 
 use crate::{
-    entity::{ProjectActiveModel, ProjectModel, items, prelude::*, projects, sections},
+    DueDate,
+    entity::{
+        ItemActiveModel, ItemLabelActiveModel, ItemLabelModel, ItemModel, ProjectActiveModel,
+        ProjectModel, ReminderActiveModel, ReminderModel, SectionActiveModel, SectionModel, items,
+        labels, prelude::*, projects, reminders, sections,
+    },
     error::TodoError,
-    services::ItemService,
+    objects::project_template::{
+        ProjectTemplate, TEMPLATE_VERSION, TemplateDue, TemplateItem, TemplateProject,
+        TemplateReminder, TemplateSection,
+    },
+    services::{
+        ORDER_GAP, TrashObjectType, TrashService,
+        util::{RawLabel, insert_batched, resolve_labels},
+    },
 };
 
+/// 归档页中的项目
+///
+/// 只列出归档操作的根项目，随父项目一起归档的子项目跟随父项目恢复 / 删除。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedProject {
+    pub project: ProjectModel,
+    /// 随项目一起归档的子项目数量
+    pub subproject_count: usize,
+    /// 随项目一起归档的未完成任务数量
+    pub item_count: usize,
+}
+
 /// Service for Project business operations
 #[derive(Clone, Debug)]
 pub struct ProjectService {
     db: Arc<DatabaseConnection>,
-    item_service: Arc<ItemService>,
 }
 
 impl ProjectService {
     /// Create a new ProjectService
-    pub fn new(db: Arc<DatabaseConnection>, item_service: Arc<ItemService>) -> Self {
-        Self { db, item_service }
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
     }
 
     /// Insert a new project
     pub async fn insert_project(&self, project: ProjectModel) -> Result<ProjectModel, TodoError> {
+        Self::insert_project_in_conn(&*self.db, project).await
+    }
+
+    pub(crate) async fn insert_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project: ProjectModel,
+    ) -> Result<ProjectModel, TodoError> {
         let active_project: ProjectActiveModel = project.into();
-        match active_project.insert(&*self.db).await {
+        match active_project.insert(conn).await {
             Ok(model) => Ok(model),
             Err(e) => Err(TodoError::DbError(Box::new(e))),
         }
@@ -37,6 +80,13 @@ impl ProjectService {
 
     /// Update an existing project
     pub async fn update_project(&self, project: ProjectModel) -> Result<ProjectModel, TodoError> {
+        Self::update_project_in_conn(&*self.db, project).await
+    }
+
+    pub(crate) async fn update_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project: ProjectModel,
+    ) -> Result<ProjectModel, TodoError> {
         // 显式设置需要更新的字段
         let active_project = ProjectActiveModel {
             id: Set(project.id),
@@ -62,77 +112,609 @@ impl ProjectService {
             inbox_section_hidded: Set(project.inbox_section_hidded),
             sync_id: Set(project.sync_id),
             source_id: Set(project.source_id),
+            deleted_at: Set(project.deleted_at),
+            archived_at: Set(project.archived_at),
         };
 
-        let result = active_project.update(&*self.db).await?;
+        let result = active_project.update(conn).await?;
 
         Ok(result)
     }
 
     /// Delete a project and its children
+    ///
+    /// 软删除：子项目、分区与任务一并移入回收站，可通过 [`TrashService`] 恢复
     pub async fn delete_project(&self, id: &str) -> Result<(), TodoError> {
-        // 收集项目树中所有项目 ID
-        let mut project_ids = vec![id.to_string()];
-        let mut idx = 0;
-        while idx < project_ids.len() {
-            let current_id = project_ids[idx].clone();
-            idx += 1;
-            let subprojects = ProjectEntity::find()
-                .filter(projects::Column::ParentId.eq(&current_id))
-                .all(&*self.db)
-                .await?;
-            for project in subprojects {
-                project_ids.push(project.id);
+        let id = id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move { TrashService::trash_project_in_conn(txn, &id, now).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// Get a project by ID (including trashed ones)
+    pub async fn get_project(&self, id: &str) -> Result<Option<ProjectModel>, TodoError> {
+        Ok(ProjectEntity::find_by_id(id).one(&*self.db).await?)
+    }
+
+    /// Get all projects (excluding archived ones)
+    pub async fn get_all_projects(&self) -> Result<Vec<ProjectModel>, TodoError> {
+        let projects: Vec<ProjectModel> = ProjectEntity::find()
+            .filter(projects::Column::IsDeleted.eq(false))
+            .filter(projects::Column::IsArchived.eq(false))
+            .all(&*self.db)
+            .await?;
+        Ok(projects)
+    }
+
+    // ==================== 归档 ====================
+
+    /// 归档项目，子项目、分区和未完成任务一起归档
+    pub async fn archive_project(&self, id: &str) -> Result<ProjectModel, TodoError> {
+        let id = id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move { Self::archive_in_conn(txn, &id, now).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 检查项目未归档后归档整棵项目树，返回归档后的项目
+    pub(crate) async fn archive_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+        now: NaiveDateTime,
+    ) -> Result<ProjectModel, TodoError> {
+        let project = Self::find_live_project_in_conn(conn, id).await?;
+        if project.is_archived {
+            return Err(TodoError::validation("project is already archived"));
+        }
+        Self::archive_project_in_conn(conn, id, now).await?;
+        Self::find_live_project_in_conn(conn, id).await
+    }
+
+    /// 取消归档，还原与项目同一次归档的子项目、分区和任务
+    pub async fn unarchive_project(&self, id: &str) -> Result<ProjectModel, TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move { Self::unarchive_in_conn(txn, &id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 检查项目已归档后取消归档，返回还原后的项目
+    pub(crate) async fn unarchive_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<ProjectModel, TodoError> {
+        let project = Self::find_live_project_in_conn(conn, id).await?;
+        if !project.is_archived {
+            return Err(TodoError::validation("project is not archived"));
+        }
+        Self::unarchive_project_in_conn(conn, &project).await?;
+        Self::find_live_project_in_conn(conn, id).await
+    }
+
+    /// 彻底删除已归档的项目（含子项目、分区和任务），不经过回收站
+    pub async fn delete_archived_project(&self, id: &str) -> Result<(), TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move { Self::delete_archived_in_conn(txn, &id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn delete_archived_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        let project = Self::find_live_project_in_conn(conn, id).await?;
+        if !project.is_archived {
+            return Err(TodoError::validation("only archived projects can be deleted here"));
+        }
+        TrashService::purge_in_conn(conn, TrashObjectType::Project, id).await
+    }
+
+    /// 列出归档的根项目（按归档时间倒序）
+    pub async fn list_archive(&self) -> Result<Vec<ArchivedProject>, TodoError> {
+        let conn = &*self.db;
+        let archived: Vec<ProjectModel> = ProjectEntity::find()
+            .filter(projects::Column::IsDeleted.eq(false))
+            .filter(projects::Column::IsArchived.eq(true))
+            .all(conn)
+            .await?;
+        let archived_items = items::Entity::find()
+            .filter(items::Column::IsDeleted.eq(false))
+            .filter(items::Column::ArchivedAt.is_not_null())
+            .all(conn)
+            .await?;
+        let stamps: HashMap<&str, Option<NaiveDateTime>> =
+            archived.iter().map(|p| (p.id.as_str(), p.archived_at)).collect();
+        // 父项目与自身在同一次归档中 → 不是根项目
+        let archived_with_parent = |project: &ProjectModel| {
+            project
+                .parent_id
+                .as_deref()
+                .and_then(|parent| stamps.get(parent))
+                .is_some_and(|stamp| *stamp == project.archived_at)
+        };
+
+        let mut entries = Vec::new();
+        for project in archived.iter().filter(|p| !archived_with_parent(p)) {
+            // 同一次归档的子项目
+            let mut tree = vec![project.id.as_str()];
+            let mut idx = 0;
+            while idx < tree.len() {
+                let parent = tree[idx];
+                idx += 1;
+                let children: Vec<&str> = archived
+                    .iter()
+                    .filter(|p| {
+                        p.parent_id.as_deref() == Some(parent)
+                            && p.archived_at == project.archived_at
+                            && !tree.contains(&p.id.as_str())
+                    })
+                    .map(|p| p.id.as_str())
+                    .collect();
+                tree.extend(children);
             }
+            let item_count = archived_items
+                .iter()
+                .filter(|i| {
+                    i.archived_at == project.archived_at
+                        && i.project_id.as_deref().is_some_and(|pid| tree.contains(&pid))
+                })
+                .count();
+            entries.push(ArchivedProject {
+                project: project.clone(),
+                subproject_count: tree.len() - 1,
+                item_count,
+            });
         }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.project.archived_at));
+        Ok(entries)
+    }
 
-        // 批量收集需删除的任务 ID（含子任务树）
-        let mut item_ids = HashSet::new();
+    // ==================== 模板 ====================
 
+    /// 把项目保存为模板（不含已完成、已删除和已归档的内容）
+    ///
+    /// 截止日期与提醒换算为相对 `base_date` 的天数，未指定时以最早的任务截止日期为基准，
+    /// 都没有截止日期时以今天为基准。
+    pub async fn create_template(
+        &self,
+        project_id: &str,
+        base_date: Option<NaiveDate>,
+    ) -> Result<ProjectTemplate, TodoError> {
+        let conn = &*self.db;
+        let project = Self::find_live_project_in_conn(conn, project_id).await?;
+        let mut project_sections = sections::Entity::find()
+            .filter(sections::Column::ProjectId.eq(project_id))
+            .filter(sections::Column::IsDeleted.eq(false))
+            .filter(sections::Column::IsArchived.eq(false))
+            .all(conn)
+            .await?;
+        project_sections.sort_by_key(|section| (section.section_order, section.added_at));
         let project_items = items::Entity::find()
-            .filter(items::Column::ProjectId.is_in(project_ids.clone()))
-            .all(&*self.db)
+            .filter(items::Column::ProjectId.eq(project_id))
+            .filter(items::Column::IsDeleted.eq(false))
+            .filter(items::Column::Checked.eq(false))
+            .filter(items::Column::ArchivedAt.is_null())
+            .all(conn)
             .await?;
-        for item in &project_items {
-            let ids = self.item_service.collect_descendant_ids(&item.id).await?;
-            item_ids.extend(ids);
+        let item_reminders = reminders::Entity::find()
+            .filter(
+                reminders::Column::ItemId
+                    .is_in(project_items.iter().map(|item| item.id.clone()).collect::<Vec<_>>()),
+            )
+            .filter(reminders::Column::IsDeleted.eq(false))
+            .all(conn)
+            .await?;
+        let label_names: HashMap<String, String> = labels::Entity::find()
+            .filter(labels::Column::IsDeleted.eq(false))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|label| (label.id, label.name))
+            .collect();
+
+        let base = base_date
+            .or_else(|| project_items.iter().filter_map(ItemModel::due_date_naive).min())
+            .unwrap_or_else(|| Local::now().date_naive());
+
+        let section_keys: HashMap<&str, String> = project_sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| (section.id.as_str(), format!("s{}", index + 1)))
+            .collect();
+        let sections = project_sections
+            .iter()
+            .map(|section| TemplateSection {
+                key: section_keys[section.id.as_str()].clone(),
+                name: section.name.clone(),
+                color: section.color.clone(),
+                description: section.description.clone(),
+                collapsed: section.collapsed,
+            })
+            .collect();
+
+        let ordered = depth_first(&project_items);
+        let item_keys: HashMap<&str, String> = ordered
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.id.as_str(), format!("i{}", index + 1)))
+            .collect();
+        let items = ordered
+            .iter()
+            .map(|item| {
+                let due = item.due_datetime();
+                TemplateItem {
+                    key: item_keys[item.id.as_str()].clone(),
+                    content: item.content.clone(),
+                    description: item.description.clone(),
+                    parent: item
+                        .parent_id
+                        .as_deref()
+                        .and_then(|parent| item_keys.get(parent).cloned()),
+                    section: item
+                        .section_id
+                        .as_deref()
+                        .and_then(|section| section_keys.get(section).cloned()),
+                    priority: item.priority,
+                    collapsed: item.collapsed,
+                    pinned: item.pinned,
+                    labels: item
+                        .labels
+                        .as_deref()
+                        .unwrap_or_default()
+                        .split(';')
+                        .filter_map(|label_id| label_names.get(label_id).cloned())
+                        .collect(),
+                    due: item.due_date().and_then(|due| TemplateDue::from_due(&due, base)),
+                    reminders: item_reminders
+                        .iter()
+                        .filter(|reminder| reminder.item_id.as_deref() == Some(item.id.as_str()))
+                        .filter_map(|reminder| {
+                            NaiveDateTime::parse_from_str(
+                                reminder.due.as_deref()?,
+                                "%Y-%m-%d %H:%M:%S",
+                            )
+                            .ok()
+                        })
+                        .map(|at| TemplateReminder::from_reminder(at, due, base))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(ProjectTemplate {
+            version: TEMPLATE_VERSION,
+            created_at: chrono::Utc::now().naive_utc(),
+            project: TemplateProject {
+                name: project.name,
+                color: project.color,
+                description: project.description,
+                emoji: project.emoji,
+                icon_style: project.icon_style,
+                view_style: project.view_style,
+                sort_order: project.sort_order,
+                show_completed: project.show_completed,
+                is_favorite: project.is_favorite,
+                due_offset_days: project
+                    .due_date
+                    .as_deref()
+                    .and_then(|due| NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok())
+                    .map(|due| (due - base).num_days()),
+            },
+            sections,
+            items,
+        })
+    }
+
+    /// 按模板新建项目，相对日期以 `start` 为第 0 天换算；`name` 为空时使用模板中的名称
+    pub async fn instantiate_template(
+        &self,
+        template: &ProjectTemplate,
+        name: Option<&str>,
+        start: NaiveDate,
+    ) -> Result<ProjectModel, TodoError> {
+        let template = template.clone();
+        let name = name.map(str::to_string);
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    Self::instantiate_template_in_conn(txn, &template, name.as_deref(), start).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn instantiate_template_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        template: &ProjectTemplate,
+        name: Option<&str>,
+        start: NaiveDate,
+    ) -> Result<ProjectModel, TodoError> {
+        let name = name
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(template.project.name.trim())
+            .to_string();
+        if name.is_empty() {
+            return Err(TodoError::validation("project name cannot be empty"));
         }
+        if template.items.iter().any(|item| item.content.trim().is_empty()) {
+            return Err(TodoError::validation("template contains a task without content"));
+        }
+        Self::instantiate_in_conn(conn, template.clone(), name, start).await
+    }
 
-        let section_ids: Vec<String> = SectionEntity::find()
-            .filter(sections::Column::ProjectId.is_in(project_ids.clone()))
-            .all(&*self.db)
-            .await?
+    async fn instantiate_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        template: ProjectTemplate,
+        name: String,
+        start: NaiveDate,
+    ) -> Result<ProjectModel, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        // 标签按名称复用，没有的新建
+        let raw_labels = template
+            .items
+            .iter()
+            .flat_map(|item| &item.labels)
+            .map(|name| RawLabel { name: name.clone(), ..Default::default() })
+            .collect();
+        let (label_ids, _) = resolve_labels(conn, raw_labels).await?;
+
+        let source = template.project;
+        let project = ProjectActiveModel::from(ProjectModel {
+            id: Uuid::new_v4().to_string(),
+            name,
+            color: source.color,
+            description: source.description,
+            emoji: source.emoji,
+            icon_style: source.icon_style,
+            view_style: source.view_style,
+            sort_order: source.sort_order,
+            show_completed: source.show_completed,
+            is_favorite: source.is_favorite,
+            due_date: source
+                .due_offset_days
+                .map(|offset| (start + Duration::days(offset)).format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        })
+        .insert(conn)
+        .await?;
+
+        let section_ids: HashMap<String, String> = template
+            .sections
+            .iter()
+            .map(|section| (section.key.clone(), Uuid::new_v4().to_string()))
+            .collect();
+        let new_sections = template
+            .sections
             .into_iter()
-            .map(|s| s.id)
+            .enumerate()
+            .map(|(index, section)| {
+                SectionActiveModel::from(SectionModel {
+                    id: section_ids[&section.key].clone(),
+                    name: section.name,
+                    added_at: now,
+                    project_id: Some(project.id.clone()),
+                    section_order: Some(index as i32),
+                    collapsed: section.collapsed,
+                    color: section.color,
+                    description: section.description,
+                    ..Default::default()
+                })
+            })
             .collect();
+        insert_batched(conn, new_sections).await?;
 
-        if !section_ids.is_empty() {
-            let section_items = items::Entity::find()
-                .filter(items::Column::SectionId.is_in(section_ids))
-                .all(&*self.db)
-                .await?;
-            for item in &section_items {
-                let ids = self.item_service.collect_descendant_ids(&item.id).await?;
-                item_ids.extend(ids);
+        // 任务 ID 先全部分配；同一父任务（顶层任务按分区）下按模板顺序排列
+        let item_ids: HashMap<String, String> = template
+            .items
+            .iter()
+            .map(|item| (item.key.clone(), Uuid::new_v4().to_string()))
+            .collect();
+        let mut sibling_counts: HashMap<(Option<String>, Option<String>), i32> = HashMap::new();
+        let mut new_items = Vec::new();
+        let mut new_item_labels = Vec::new();
+        let mut new_reminders = Vec::new();
+        for item in template.items {
+            let id = item_ids[&item.key].clone();
+            let parent_id = item.parent.as_ref().and_then(|parent| item_ids.get(parent).cloned());
+            let section_id =
+                item.section.as_ref().and_then(|section| section_ids.get(section).cloned());
+            let group = match &parent_id {
+                Some(parent_id) => (Some(parent_id.clone()), None),
+                None => (None, section_id.clone()),
+            };
+            let count = sibling_counts.entry(group).or_default();
+            *count += 1;
+
+            let mut label_set: Vec<String> = Vec::new();
+            for label in &item.labels {
+                if let Some(label_id) = label_ids.get(&label.to_lowercase())
+                    && !label_set.contains(label_id)
+                {
+                    label_set.push(label_id.clone());
+                }
             }
-        }
+            new_item_labels.extend(label_set.iter().map(|label_id| {
+                ItemLabelActiveModel::from(ItemLabelModel {
+                    item_id: id.clone(),
+                    label_id: label_id.clone(),
+                    created_at: now,
+                })
+            }));
 
-        if !item_ids.is_empty() {
-            self.item_service.delete_items_by_ids(item_ids.into_iter().collect()).await?;
+            let due = item.due.as_ref().map(|due| due.resolve(start));
+            let due_datetime = due.as_ref().and_then(DueDate::datetime);
+            new_reminders.extend(item.reminders.iter().filter_map(|reminder| {
+                let at = reminder.resolve(start, due_datetime)?;
+                Some(ReminderActiveModel::from(ReminderModel {
+                    id: Uuid::new_v4().to_string(),
+                    item_id: Some(id.clone()),
+                    due: Some(at.format("%Y-%m-%d %H:%M:%S").to_string()),
+                    reminder_type: Some("time".to_string()),
+                    mm_offset: match reminder {
+                        TemplateReminder::BeforeDue { minutes } => i32::try_from(*minutes).ok(),
+                        TemplateReminder::At { .. } => None,
+                    },
+                    ..Default::default()
+                }))
+            }));
+
+            let mut model = ItemModel {
+                id,
+                content: item.content,
+                description: item.description,
+                added_at: now,
+                updated_at: now,
+                section_id,
+                project_id: Some(project.id.clone()),
+                parent_id,
+                priority: item.priority,
+                child_order: Some(ORDER_GAP.saturating_mul(*count)),
+                collapsed: item.collapsed,
+                pinned: item.pinned,
+                labels: (!label_set.is_empty()).then(|| label_set.join(";")),
+                ..Default::default()
+            };
+            model.set_due_date(due);
+            new_items.push(ItemActiveModel::from(model));
         }
+        insert_batched(conn, new_items).await?;
+        insert_batched(conn, new_item_labels).await?;
+        insert_batched(conn, new_reminders).await?;
+
+        Ok(project)
+    }
+
+    async fn find_live_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<ProjectModel, TodoError> {
+        ProjectEntity::find_by_id(id)
+            .filter(projects::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Project").with_entity("Project", id))
+    }
 
-        // Sections 随 Projects FK CASCADE 自动删除
-        ProjectEntity::delete_many()
+    /// 标记项目树为已归档（已归档的子项目 / 分区、已完成的任务保持不变）
+    pub(crate) async fn archive_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let project_ids = TrashService::collect_project_tree(conn, project_id).await?;
+
+        items::Entity::update_many()
+            .col_expr(items::Column::ArchivedAt, Expr::value(now))
+            .filter(items::Column::ProjectId.is_in(project_ids.clone()))
+            .filter(items::Column::IsDeleted.eq(false))
+            .filter(items::Column::Checked.eq(false))
+            .filter(items::Column::ArchivedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        sections::Entity::update_many()
+            .col_expr(sections::Column::IsArchived, Expr::value(true))
+            .col_expr(sections::Column::ArchivedAt, Expr::value(now))
+            .filter(sections::Column::ProjectId.is_in(project_ids.clone()))
+            .filter(sections::Column::IsDeleted.eq(false))
+            .filter(sections::Column::IsArchived.eq(false))
+            .exec(conn)
+            .await?;
+
+        projects::Entity::update_many()
+            .col_expr(projects::Column::IsArchived, Expr::value(true))
+            .col_expr(projects::Column::ArchivedAt, Expr::value(now))
             .filter(projects::Column::Id.is_in(project_ids))
-            .exec(&*self.db)
+            .filter(projects::Column::IsDeleted.eq(false))
+            .filter(projects::Column::IsArchived.eq(false))
+            .exec(conn)
             .await?;
 
         Ok(())
     }
 
-    /// Get all projects
-    pub async fn get_all_projects(&self) -> Result<Vec<ProjectModel>, TodoError> {
-        let projects: Vec<ProjectModel> = ProjectEntity::find().all(&*self.db).await?;
-        Ok(projects)
+    /// 还原与项目归档时间相同的子项目、分区和任务
+    pub(crate) async fn unarchive_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project: &ProjectModel,
+    ) -> Result<(), TodoError> {
+        if let Some(stamp) = project.archived_at {
+            let project_ids = TrashService::collect_project_tree(conn, &project.id).await?;
+
+            items::Entity::update_many()
+                .col_expr(items::Column::ArchivedAt, Expr::value(Option::<NaiveDateTime>::None))
+                .filter(items::Column::ProjectId.is_in(project_ids.clone()))
+                .filter(items::Column::ArchivedAt.eq(stamp))
+                .exec(conn)
+                .await?;
+
+            sections::Entity::update_many()
+                .col_expr(sections::Column::IsArchived, Expr::value(false))
+                .col_expr(sections::Column::ArchivedAt, Expr::value(Option::<NaiveDateTime>::None))
+                .filter(sections::Column::ProjectId.is_in(project_ids.clone()))
+                .filter(sections::Column::ArchivedAt.eq(stamp))
+                .exec(conn)
+                .await?;
+
+            projects::Entity::update_many()
+                .col_expr(projects::Column::IsArchived, Expr::value(false))
+                .col_expr(projects::Column::ArchivedAt, Expr::value(Option::<NaiveDateTime>::None))
+                .filter(projects::Column::Id.is_in(project_ids))
+                .filter(projects::Column::ArchivedAt.eq(stamp))
+                .exec(conn)
+                .await?;
+        }
+
+        // 没有归档时间的旧数据（如从 Todoist 同步的归档状态）只还原项目本身
+        projects::Entity::update_many()
+            .col_expr(projects::Column::IsArchived, Expr::value(false))
+            .col_expr(projects::Column::ArchivedAt, Expr::value(Option::<NaiveDateTime>::None))
+            .filter(projects::Column::Id.eq(&project.id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// 按父子关系深度优先排列任务，同级按 `child_order` 排序；父任务不在列表中的视为顶层任务
+pub(super) fn depth_first(items: &[ItemModel]) -> Vec<&ItemModel> {
+    let ids: HashSet<&str> = items.iter().map(|item| item.id.as_str()).collect();
+    let mut children: HashMap<Option<&str>, Vec<&ItemModel>> = HashMap::new();
+    for item in items {
+        let parent = item.parent_id.as_deref().filter(|id| ids.contains(id));
+        children.entry(parent).or_default().push(item);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|item| (item.child_order.unwrap_or(i32::MAX), item.added_at));
+    }
+
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut stack: Vec<&ItemModel> =
+        children.get(&None).map(|roots| roots.iter().rev().copied().collect()).unwrap_or_default();
+    while let Some(item) = stack.pop() {
+        ordered.push(item);
+        if let Some(subtasks) = children.get(&Some(item.id.as_str())) {
+            stack.extend(subtasks.iter().rev());
+        }
     }
+    ordered
 }
@@ -0,0 +1,328 @@
+//! Full-text search over tasks, projects, sections and labels
+//!
+//! 索引表 `search_index` 由补丁 003 创建并通过触发器保持同步，本服务只读。
+//! 触发器只处理删除，归档的对象仍在索引中，查询时按归档状态排除。
+//! 查询按空白拆分为多个词，所有词都必须命中（AND）。
+//!
+//! SQLite 上是 FTS5（trigram 分词）：不少于 3 个字符的词走 `MATCH` 并按 bm25 排序，
+//! 更短的词（如两个汉字）trigram 无法索引，退化为 `LIKE` 子串匹配。
+//! PostgreSQL 上是普通表，所有词都用 `ILIKE` 匹配，按标题 / 描述命中的加权和排序。
+//! 高亮区间在 Rust 侧计算，返回原文中的字节区间，界面可直接用于渲染。
+
+use std::{ops::Range, sync::Arc};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::error::TodoError;
+
+/// 默认返回的最大结果数
+pub const DEFAULT_SEARCH_LIMIT: u64 = 50;
+
+/// 摘要中命中位置前保留的字符数
+const SNIPPET_CONTEXT_BEFORE: usize = 24;
+/// 摘要的最大字符数
+const SNIPPET_MAX_CHARS: usize = 96;
+
+/// trigram 分词可索引的最短词长（字符）
+const MIN_MATCH_CHARS: usize = 3;
+
+/// 搜索结果的对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SearchObjectType {
+    Item,
+    Project,
+    Section,
+    Label,
+}
+
+impl SearchObjectType {
+    /// 索引表中 `object_type` 列的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Item => "item",
+            Self::Project => "project",
+            Self::Section => "section",
+            Self::Label => "label",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "item" => Some(Self::Item),
+            "project" => Some(Self::Project),
+            "section" => Some(Self::Section),
+            "label" => Some(Self::Label),
+            _ => None,
+        }
+    }
+}
+
+/// 带高亮区间的文本，`ranges` 为 `text` 中的字节区间（有序、不重叠）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighlightedText {
+    pub text: String,
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// 一条搜索结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub object_type: SearchObjectType,
+    pub id: String,
+    /// 任务内容或项目 / 分区 / 标签名称
+    pub title: HighlightedText,
+    /// 描述中命中的片段，描述未命中时为 None
+    pub snippet: Option<HighlightedText>,
+    /// 任务或分区所属的项目
+    pub project_id: Option<String>,
+    /// 任务所在的分区
+    pub section_id: Option<String>,
+    /// 任务是否已完成
+    pub checked: bool,
+    /// 相关度得分，越小越相关（SQLite 为 bm25，只有短词时为 0）
+    pub rank: f64,
+}
+
+/// Service for full-text search
+#[derive(Clone, Debug)]
+pub struct SearchService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SearchService {
+    /// Create a new SearchService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 搜索任务、项目、分区和标签，按相关度排序；空查询返回空结果
+    pub async fn search(&self, query: &str, limit: u64) -> Result<Vec<SearchHit>, TodoError> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let statement = match self.db.get_database_backend() {
+            DbBackend::Postgres => postgres_statement(&terms, limit),
+            _ => sqlite_statement(&terms, limit),
+        };
+        let rows = self.db.query_all(statement).await?;
+
+        let mut hits = Vec::with_capacity(rows.len());
+        for row in rows {
+            if let Some(hit) = Self::hit_from_row(&row, &terms)? {
+                hits.push(hit);
+            }
+        }
+        Ok(hits)
+    }
+
+    fn hit_from_row(row: &QueryResult, terms: &[&str]) -> Result<Option<SearchHit>, TodoError> {
+        let object_type: String = row.try_get("", "object_type")?;
+        let Some(object_type) = SearchObjectType::parse(&object_type) else {
+            return Ok(None);
+        };
+        let title: String = row.try_get("", "title")?;
+        let body: String = row.try_get("", "body")?;
+        let checked: Option<bool> = row.try_get("", "checked")?;
+
+        Ok(Some(SearchHit {
+            object_type,
+            id: row.try_get("", "object_id")?,
+            title: HighlightedText { ranges: highlight_ranges(&title, terms), text: title },
+            snippet: snippet(&body, terms),
+            project_id: row.try_get("", "project_id")?,
+            section_id: row.try_get("", "section_id")?,
+            checked: checked.unwrap_or(false),
+            rank: row.try_get("", "rank")?,
+        }))
+    }
+}
+
+/// FTS5 查询：长词走 `MATCH`，短词走 `LIKE`
+fn sqlite_statement(terms: &[&str], limit: u64) -> Statement {
+    let (match_terms, like_terms): (Vec<&str>, Vec<&str>) =
+        terms.iter().partition(|term| term.chars().count() >= MIN_MATCH_CHARS);
+
+    let mut conditions = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if !match_terms.is_empty() {
+        // 每个词作为短语加引号，避免用户输入被解析为 FTS5 语法
+        let expr = match_terms
+            .iter()
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        conditions.push("search_index MATCH ?".to_string());
+        values.push(expr.into());
+    }
+    for term in &like_terms {
+        let pattern = format!("%{}%", escape_like(term));
+        conditions.push(
+            "(search_index.title LIKE ? ESCAPE '\\' OR search_index.body LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        values.push(pattern.clone().into());
+        values.push(pattern.into());
+    }
+    values.push((limit as i64).into());
+
+    let rank_expr = if match_terms.is_empty() { "0.0" } else { "bm25(search_index, 10.0, 1.0)" };
+    let sql = format!(
+        "SELECT search_index.object_type AS object_type, search_index.object_id AS object_id, \
+         search_index.title AS title, search_index.body AS body, {rank_expr} AS rank, \
+         COALESCE(Items.project_id, Sections.project_id) AS project_id, Items.section_id AS \
+         section_id, COALESCE(Items.checked, 0) AS checked FROM search_index LEFT JOIN Items ON \
+         search_index.object_type = 'item' AND Items.id = search_index.object_id LEFT JOIN \
+         Sections ON search_index.object_type = 'section' AND Sections.id = \
+         search_index.object_id {ARCHIVE_JOINS} WHERE {} AND {} ORDER BY rank, search_index.title \
+         LIMIT ?",
+        conditions.join(" AND "),
+        not_archived("0")
+    );
+
+    Statement::from_sql_and_values(DbBackend::Sqlite, sql, values)
+}
+
+/// PostgreSQL 查询：每个词用 `ILIKE` 子串匹配，标题命中权重 10、描述命中权重 1，
+/// 取负的加权和作为得分
+fn postgres_statement(terms: &[&str], limit: u64) -> Statement {
+    let mut conditions = Vec::new();
+    let mut weights = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    for term in terms {
+        values.push(format!("%{}%", escape_like(term)).into());
+        let param = values.len();
+        conditions.push(format!(
+            "(search_index.title ILIKE ${param} ESCAPE '\\' OR search_index.body ILIKE ${param} \
+             ESCAPE '\\')"
+        ));
+        weights.push(format!(
+            "CASE WHEN search_index.title ILIKE ${param} ESCAPE '\\' THEN 10 ELSE 0 END + CASE \
+             WHEN search_index.body ILIKE ${param} ESCAPE '\\' THEN 1 ELSE 0 END"
+        ));
+    }
+    values.push((limit as i64).into());
+
+    let sql = format!(
+        "SELECT search_index.object_type AS object_type, search_index.object_id AS object_id, \
+         search_index.title AS title, search_index.body AS body, (-({}))::FLOAT8 AS rank, \
+         COALESCE(Items.project_id, Sections.project_id) AS project_id, Items.section_id AS \
+         section_id, COALESCE(Items.checked, FALSE) AS checked FROM search_index LEFT JOIN Items \
+         ON search_index.object_type = 'item' AND Items.id = search_index.object_id LEFT JOIN \
+         Sections ON search_index.object_type = 'section' AND Sections.id = \
+         search_index.object_id {ARCHIVE_JOINS} WHERE {} AND {} ORDER BY rank, search_index.title \
+         LIMIT ${}",
+        weights.join(" + "),
+        conditions.join(" AND "),
+        not_archived("FALSE"),
+        values.len()
+    );
+    Statement::from_sql_and_values(DbBackend::Postgres, sql, values)
+}
+
+/// 用于排除归档对象的连接：项目本身，以及任务 / 分区所属的项目
+const ARCHIVE_JOINS: &str = "LEFT JOIN Projects ON search_index.object_type = 'project' AND \
+                             Projects.id = search_index.object_id LEFT JOIN Projects AS owner ON \
+                             owner.id = COALESCE(Items.project_id, Sections.project_id)";
+
+/// 索引由触发器维护，只随删除同步；归档的项目、分区、任务以及归档项目中的内容在查询时排除
+fn not_archived(false_value: &str) -> String {
+    format!(
+        "COALESCE(Projects.is_archived, {false_value}) = {false_value} AND \
+         COALESCE(Sections.is_archived, {false_value}) = {false_value} AND \
+         COALESCE(owner.is_archived, {false_value}) = {false_value} AND Items.archived_at IS NULL"
+    )
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 计算 `terms` 在 `text` 中的命中区间（不区分大小写），返回合并后的字节区间
+pub fn highlight_ranges(text: &str, terms: &[&str]) -> Vec<Range<usize>> {
+    // 逐字符比较小写形式，保证区间落在原文的字符边界上
+    let chars: Vec<(usize, char)> = text.char_indices().map(|(i, c)| (i, fold(c))).collect();
+    let mut ranges = Vec::new();
+
+    for term in terms {
+        let needle: Vec<char> = term.chars().map(fold).collect();
+        if needle.is_empty() || needle.len() > chars.len() {
+            continue;
+        }
+        for start in 0..=chars.len() - needle.len() {
+            if chars[start..start + needle.len()].iter().map(|(_, c)| *c).eq(needle.iter().copied())
+            {
+                let end = chars.get(start + needle.len()).map(|(i, _)| *i).unwrap_or(text.len());
+                ranges.push(chars[start].0..end);
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// 截取描述中第一处命中附近的片段，超出部分用 “…” 表示
+fn snippet(body: &str, terms: &[&str]) -> Option<HighlightedText> {
+    let ranges = highlight_ranges(body, terms);
+    let first = ranges.first()?.start;
+
+    let offsets: Vec<usize> = body.char_indices().map(|(i, _)| i).collect();
+    let first_char = offsets.partition_point(|&i| i < first);
+    let start_char = first_char.saturating_sub(SNIPPET_CONTEXT_BEFORE);
+    let end_char = (start_char + SNIPPET_MAX_CHARS).min(offsets.len());
+    let start = offsets[start_char];
+    let end = offsets.get(end_char).copied().unwrap_or(body.len());
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < body.len() { "…" } else { "" };
+    let text = format!("{}{}{}", prefix, &body[start..end], suffix);
+    let shift = prefix.len();
+    let ranges = ranges
+        .into_iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start + shift..range.end - start + shift)
+        .collect();
+
+    Some(HighlightedText { text, ranges })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_ranges_case_insensitive_and_merged() {
+        let ranges = highlight_ranges("Buy MILK and milkshake", &["milk", "milks"]);
+        assert_eq!(ranges, vec![4..8, 13..18]);
+    }
+
+    #[test]
+    fn test_highlight_ranges_multibyte() {
+        let text = "去超市买牛奶";
+        let ranges = highlight_ranges(text, &["牛奶"]);
+        assert_eq!(&text[ranges[0].clone()], "牛奶");
+    }
+
+    #[test]
+    fn test_snippet_window() {
+        let body = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let snippet = snippet(&body, &["needle"]).unwrap();
+        assert!(snippet.text.starts_with('…') && snippet.text.ends_with('…'));
+        assert_eq!(&snippet.text[snippet.ranges[0].clone()], "needle");
+        assert!(super::snippet("nothing here", &["needle"]).is_none());
+    }
+}
@@ -3,42 +3,56 @@
 //! This module provides business logic for Section operations,
 //! separating it from data access layer.
 
-use std::{collections::HashSet, sync::Arc};
+use std::sync::Arc;
 
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set, TransactionTrait, prelude::Expr,
+};
 
 use crate::{
-    entity::{SectionActiveModel, SectionModel},
+    entity::{SectionActiveModel, SectionModel, items, sections},
     error::TodoError,
-    repositories::{BaseRepository, SectionRepositoryImpl},
-    services::ItemService,
+    services::TrashService,
 };
 
 /// Service for Section business operations
 #[derive(Clone, Debug)]
 pub struct SectionService {
     db: Arc<DatabaseConnection>,
-    section_repo: SectionRepositoryImpl,
-    item_service: Arc<ItemService>,
 }
 
 impl SectionService {
     /// Create a new SectionService
-    pub fn new(db: Arc<DatabaseConnection>, item_service: Arc<ItemService>) -> Self {
-        let section_repo = SectionRepositoryImpl::new(db.clone());
-        Self { db, section_repo, item_service }
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
     }
 
     /// Insert a new section
     pub async fn insert_section(&self, section: SectionModel) -> Result<SectionModel, TodoError> {
+        Self::insert_section_in_conn(&*self.db, section).await
+    }
+
+    pub(crate) async fn insert_section_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        section: SectionModel,
+    ) -> Result<SectionModel, TodoError> {
         let active_section: SectionActiveModel = section.into();
-        let section_model = active_section.insert(&*self.db).await?;
+        let section_model = active_section.insert(conn).await?;
 
         Ok(section_model)
     }
 
     /// Update an existing section
     pub async fn update_section(&self, section: SectionModel) -> Result<SectionModel, TodoError> {
+        Self::update_section_in_conn(&*self.db, section).await
+    }
+
+    pub(crate) async fn update_section_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        section: SectionModel,
+    ) -> Result<SectionModel, TodoError> {
         // 显式设置需要更新的字段
         let active_section = SectionActiveModel {
             id: Set(section.id),
@@ -53,34 +67,101 @@ impl SectionService {
             color: Set(section.color),
             description: Set(section.description),
             hidded: Set(section.hidded),
+            deleted_at: Set(section.deleted_at),
         };
 
-        let result = active_section.update(&*self.db).await?;
+        let result = active_section.update(conn).await?;
 
         Ok(result)
     }
 
     /// Delete a section and its items
+    ///
+    /// 软删除：分区及其中的任务移入回收站，可通过 [`TrashService`] 恢复
     pub async fn delete_section(&self, section_id: &str) -> Result<(), TodoError> {
-        let section_items = self.get_items_by_section(section_id).await?;
-        let mut item_ids = HashSet::new();
-        for item in section_items {
-            let ids = self.item_service.collect_descendant_ids(&item.id).await?;
-            item_ids.extend(ids);
+        let section_id = section_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(
+                    async move { TrashService::trash_section_in_conn(txn, &section_id, now).await },
+                )
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 归档 / 取消归档分区
+    ///
+    /// 归档时分区中未完成的任务一起归档（共享分区的 `archived_at`），取消归档时据此还原。
+    pub async fn set_section_archived(
+        &self,
+        section_id: &str,
+        archived: bool,
+    ) -> Result<SectionModel, TodoError> {
+        let section_id = section_id.to_string();
+        self.db
+            .transaction::<_, SectionModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    Self::set_section_archived_in_conn(txn, &section_id, archived).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub(crate) async fn set_section_archived_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        section_id: &str,
+        archived: bool,
+    ) -> Result<SectionModel, TodoError> {
+        let now = chrono::Utc::now().naive_utc();
+        let section = sections::Entity::find_by_id(section_id)
+            .filter(sections::Column::IsDeleted.eq(false))
+            .one(conn)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Section").with_entity("Section", section_id))?;
+        if section.is_archived == archived {
+            return Ok(section);
         }
 
-        if !item_ids.is_empty() {
-            self.item_service.delete_items_by_ids(item_ids.into_iter().collect()).await?;
+        if archived {
+            items::Entity::update_many()
+                .col_expr(items::Column::ArchivedAt, Expr::value(now))
+                .filter(items::Column::SectionId.eq(section_id))
+                .filter(items::Column::IsDeleted.eq(false))
+                .filter(items::Column::Checked.eq(false))
+                .filter(items::Column::ArchivedAt.is_null())
+                .exec(conn)
+                .await?;
+        } else if let Some(stamp) = section.archived_at {
+            items::Entity::update_many()
+                .col_expr(items::Column::ArchivedAt, Expr::value(Option::<NaiveDateTime>::None))
+                .filter(items::Column::SectionId.eq(section_id))
+                .filter(items::Column::ArchivedAt.eq(stamp))
+                .exec(conn)
+                .await?;
         }
 
-        BaseRepository::delete(&self.section_repo, section_id).await?;
+        let active_section = SectionActiveModel {
+            is_archived: Set(archived),
+            archived_at: Set(archived.then_some(now)),
+            ..section.into()
+        };
+        Ok(active_section.update(conn).await?)
+    }
 
-        Ok(())
+    /// Get a section by ID (including trashed ones)
+    pub async fn get_section(&self, id: &str) -> Result<Option<SectionModel>, TodoError> {
+        Ok(sections::Entity::find_by_id(id).one(&*self.db).await?)
     }
 
     /// Get all sections
     pub async fn get_all_sections(&self) -> Result<Vec<SectionModel>, TodoError> {
-        let sections = BaseRepository::find_all(&self.section_repo).await?;
+        let sections: Vec<SectionModel> = sections::Entity::find()
+            .filter(sections::Column::IsDeleted.eq(false))
+            .all(&*self.db)
+            .await?;
         Ok(sections)
     }
 
@@ -92,6 +173,7 @@ impl SectionService {
         use crate::entity::items;
         let items: Vec<crate::entity::ItemModel> = items::Entity::find()
             .filter(items::Column::SectionId.eq(section_id))
+            .filter(items::Column::IsDeleted.eq(false))
             .all(&*self.db)
             .await?;
         Ok(items)
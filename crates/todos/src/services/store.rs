@@ -3,29 +3,50 @@
 //! Thin passthrough for GUI/cold-start hot paths only. Prefer specialized
 //! services for new call sites.
 
-use std::sync::Arc;
+use std::{collections::HashMap, path::Path, sync::Arc};
 
-use sea_orm::DatabaseConnection;
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
 
 use crate::{
+    ItemNode, ProjectTemplate,
     app::PatchManager,
-    entity::{AttachmentModel, ItemModel, LabelModel, ProjectModel, ReminderModel, SectionModel},
+    entity::{
+        AttachmentModel, FilterModel, ItemModel, LabelModel, ProjectModel, QueueModel,
+        ReminderModel, SectionModel, SourceModel, items, projects, sections,
+    },
     error::TodoError,
     services::{
-        AttachmentService, ItemService, LabelService, ProjectService, ReminderService,
-        SectionService,
+        ArchivedProject, AttachmentService, CalDavConfig, CalDavService, CalDavSyncReport,
+        DuplicateResult, DuplicateService, EventEntry, EventService, ExportFormat, ExportService,
+        FilterService, IcalService, IcsComponent, ImportReport, ImportService, IntegrityIssue,
+        IntegrityReport, IntegrityService, ItemPosition, ItemService, LabelMerge, LabelService,
+        LocalChange, ProjectService, ReminderService, SearchHit, SearchService, SectionService,
+        TodoistConfig, TodoistSyncReport, TodoistSyncService, TrashEntry, TrashObjectType,
+        TrashService,
     },
 };
 
 /// Unified Store implementation holding domain services directly
 #[derive(Clone, Debug)]
 pub struct Store {
+    db: Arc<DatabaseConnection>,
     item_service: ItemService,
     project_service: ProjectService,
     section_service: SectionService,
     label_service: LabelService,
     reminder_service: ReminderService,
     attachment_service: AttachmentService,
+    trash_service: TrashService,
+    event_service: EventService,
+    search_service: SearchService,
+    filter_service: FilterService,
+    import_service: ImportService,
+    integrity_service: IntegrityService,
+    export_service: ExportService,
+    ical_service: IcalService,
+    caldav_service: CalDavService,
+    todoist_sync_service: TodoistSyncService,
 }
 
 impl Store {
@@ -37,44 +58,102 @@ impl Store {
         patch_manager.apply_patches().await?;
 
         let label_service = LabelService::new(db.clone());
-        let label_service_for_item = Arc::new(label_service.clone());
-        let item_service = ItemService::new(db.clone(), label_service_for_item);
-        let item_service_for_deps = Arc::new(item_service.clone());
-        let section_service = SectionService::new(db.clone(), item_service_for_deps.clone());
-        let project_service = ProjectService::new(db.clone(), item_service_for_deps);
+        let item_service = ItemService::new(db.clone());
+        let section_service = SectionService::new(db.clone());
+        let project_service = ProjectService::new(db.clone());
         let reminder_service = ReminderService::new(db.clone());
         let attachment_service = AttachmentService::new(db.clone());
+        let trash_service = TrashService::new(db.clone());
+        let event_service = EventService::new(db.clone());
+        let search_service = SearchService::new(db.clone());
+        let filter_service = FilterService::new(db.clone());
+        let import_service = ImportService::new(db.clone());
+        let integrity_service = IntegrityService::new(db.clone());
+        let export_service = ExportService::new(db.clone());
+        let ical_service = IcalService::new(db.clone());
+        let caldav_service = CalDavService::new(db.clone());
+        let todoist_sync_service = TodoistSyncService::new(db.clone());
 
         Ok(Arc::new(Self {
+            db,
             item_service,
             project_service,
             section_service,
             label_service,
             reminder_service,
             attachment_service,
+            trash_service,
+            event_service,
+            search_service,
+            filter_service,
+            import_service,
+            integrity_service,
+            export_service,
+            ical_service,
+            caldav_service,
+            todoist_sync_service,
         }))
     }
 
+    // 会同步到 Todoist 的修改与对应的队列命令在同一个事务中写入，两者一起提交或回滚
+
     // ==================== Item Operations ====================
 
     pub async fn get_item(&self, id: &str) -> Option<ItemModel> {
         self.item_service.get_item(id).await
     }
 
-    pub async fn insert_item(&self, item: ItemModel, insert: bool) -> Result<ItemModel, TodoError> {
-        self.item_service.insert_item(item, insert).await
+    pub async fn insert_item(
+        &self,
+        item: ItemModel,
+        _insert: bool,
+    ) -> Result<ItemModel, TodoError> {
+        self.db
+            .transaction::<_, ItemModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let item = ItemService::insert_item_in_conn(txn, item).await?;
+                    TodoistSyncService::record_in_conn(txn, [LocalChange::ItemAdded(item.clone())])
+                        .await?;
+                    Ok(item)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn update_item(
         &self,
         item: ItemModel,
-        update_id: &str,
+        _update_id: &str,
     ) -> Result<ItemModel, TodoError> {
-        self.item_service.update_item(item, update_id).await
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, ItemModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = items::Entity::find_by_id(&item.id).one(txn).await?;
+                    let after = ItemService::update_item_in_conn(txn, item, now).await?;
+                    let change = LocalChange::ItemUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn delete_item(&self, item_id: &str) -> Result<(), TodoError> {
-        self.item_service.delete_item(item_id).await
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    TrashService::trash_item_in_conn(txn, &item_id, now).await?;
+                    TodoistSyncService::record_in_conn(txn, [LocalChange::ItemDeleted(item_id)])
+                        .await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn update_item_pin(&self, item_id: &str, pinned: bool) -> Result<(), TodoError> {
@@ -87,7 +166,168 @@ impl Store {
         checked: bool,
         complete_sub_items: bool,
     ) -> Result<(), TodoError> {
-        self.item_service.complete_item(item_id, checked, complete_sub_items).await
+        let item_id = item_id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    ItemService::complete_item_in_conn(txn, &item_id, checked, complete_sub_items)
+                        .await?;
+                    let change = LocalChange::ItemCompleted { item_id, checked };
+                    TodoistSyncService::record_in_conn(txn, [change]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 手动排序不进入 Todoist 同步队列（item_update 不携带 child_order）
+    pub async fn move_item(
+        &self,
+        item_id: &str,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        self.item_service.move_item(item_id, position).await
+    }
+
+    pub async fn move_item_in_day(
+        &self,
+        item_id: &str,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        self.item_service.move_item_in_day(item_id, position).await
+    }
+
+    pub async fn get_item_tree(&self, item_id: &str) -> Result<ItemNode, TodoError> {
+        self.item_service.get_item_tree(item_id).await
+    }
+
+    pub async fn indent_item(&self, item_id: &str) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = items::Entity::find_by_id(&item_id).one(txn).await?;
+                    let changed = ItemService::indent_in_conn(txn, &item_id, now).await?;
+                    TodoistSyncService::record_in_conn(txn, subtree_move(before, &changed)).await?;
+                    Ok(changed)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn outdent_item(&self, item_id: &str) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = items::Entity::find_by_id(&item_id).one(txn).await?;
+                    let changed = ItemService::outdent_in_conn(txn, &item_id, now).await?;
+                    TodoistSyncService::record_in_conn(txn, subtree_move(before, &changed)).await?;
+                    Ok(changed)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn move_subtree(
+        &self,
+        item_id: &str,
+        new_parent_id: Option<&str>,
+        position: ItemPosition,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item_id = item_id.to_string();
+        let new_parent_id = new_parent_id.map(str::to_string);
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = items::Entity::find_by_id(&item_id).one(txn).await?;
+                    let changed = ItemService::move_subtree_in_conn(
+                        txn,
+                        &item_id,
+                        new_parent_id.as_deref(),
+                        position,
+                        now,
+                    )
+                    .await?;
+                    TodoistSyncService::record_in_conn(txn, subtree_move(before, &changed)).await?;
+                    Ok(changed)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn move_items(
+        &self,
+        item_ids: &[String],
+        project_id: Option<&str>,
+        section_id: Option<&str>,
+    ) -> Result<Vec<ItemModel>, TodoError> {
+        let item_ids = item_ids.to_vec();
+        let project_id = project_id.map(str::to_string);
+        let section_id = section_id.map(str::to_string);
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let mut befores = items::Entity::find()
+                        .filter(items::Column::Id.is_in(item_ids.clone()))
+                        .all(txn)
+                        .await?;
+                    befores.sort_by_key(|item| item_ids.iter().position(|id| *id == item.id));
+                    let changed = ItemService::move_items_in_conn(
+                        txn,
+                        &item_ids,
+                        project_id.as_deref(),
+                        section_id.as_deref(),
+                        now,
+                    )
+                    .await?;
+                    // 随祖先移动的任务保留父任务，只有成为顶层的任务需要同步
+                    let changes: Vec<LocalChange> = befores
+                        .into_iter()
+                        .filter_map(|before| {
+                            let after = changed
+                                .iter()
+                                .find(|item| item.id == before.id && item.parent_id.is_none())?;
+                            Some(LocalChange::ItemUpdated {
+                                before: Some(before),
+                                after: after.clone(),
+                            })
+                        })
+                        .collect();
+                    TodoistSyncService::record_in_conn(txn, changes).await?;
+                    Ok(changed)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn set_item_collapsed(
+        &self,
+        item_id: &str,
+        collapsed: bool,
+    ) -> Result<ItemModel, TodoError> {
+        let item_id = item_id.to_string();
+        self.db
+            .transaction::<_, ItemModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = items::Entity::find_by_id(&item_id).one(txn).await?;
+                    let after =
+                        ItemService::set_item_collapsed_in_conn(txn, &item_id, collapsed).await?;
+                    let change = LocalChange::ItemUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn get_all_items(&self) -> Result<Vec<ItemModel>, TodoError> {
@@ -106,7 +346,18 @@ impl Store {
         item_id: &str,
         label_name: &str,
     ) -> Result<(), TodoError> {
-        self.item_service.add_label_to_item(item_id, label_name).await
+        let item_id = item_id.to_string();
+        let label_name = label_name.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    ItemService::add_label_to_item_in_conn(txn, &item_id, &label_name).await?;
+                    let change = LocalChange::ItemLabelsChanged(item_id);
+                    TodoistSyncService::record_in_conn(txn, [change]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn remove_label_from_item(
@@ -114,7 +365,18 @@ impl Store {
         item_id: &str,
         label_id: &str,
     ) -> Result<(), TodoError> {
-        self.item_service.remove_label_from_item(item_id, label_id).await
+        let item_id = item_id.to_string();
+        let label_id = label_id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    ItemService::remove_label_from_item_in_conn(txn, &item_id, &label_id).await?;
+                    let change = LocalChange::ItemLabelsChanged(item_id);
+                    TodoistSyncService::record_in_conn(txn, [change]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn set_item_labels(
@@ -122,7 +384,18 @@ impl Store {
         item_id: &str,
         label_ids: &[String],
     ) -> Result<(), TodoError> {
-        self.item_service.set_item_labels(item_id, label_ids).await
+        let item_id = item_id.to_string();
+        let label_ids = label_ids.to_vec();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    ItemService::set_item_labels_in_conn(txn, &item_id, &label_ids).await?;
+                    let change = LocalChange::ItemLabelsChanged(item_id);
+                    TodoistSyncService::record_in_conn(txn, [change]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn get_labels_by_item(&self, item_id: &str) -> Result<Vec<LabelModel>, TodoError> {
@@ -132,33 +405,168 @@ impl Store {
     // ==================== Project Operations ====================
 
     pub async fn insert_project(&self, project: ProjectModel) -> Result<ProjectModel, TodoError> {
-        self.project_service.insert_project(project).await
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let project = ProjectService::insert_project_in_conn(txn, project).await?;
+                    let change = LocalChange::ProjectAdded(project.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(project)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn update_project(&self, project: ProjectModel) -> Result<ProjectModel, TodoError> {
-        self.project_service.update_project(project).await
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = projects::Entity::find_by_id(&project.id).one(txn).await?;
+                    let after = ProjectService::update_project_in_conn(txn, project).await?;
+                    let change = LocalChange::ProjectUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn delete_project(&self, id: &str) -> Result<(), TodoError> {
-        self.project_service.delete_project(id).await
+        let id = id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    TrashService::trash_project_in_conn(txn, &id, now).await?;
+                    TodoistSyncService::record_in_conn(txn, [LocalChange::ProjectDeleted(id)]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn get_all_projects(&self) -> Result<Vec<ProjectModel>, TodoError> {
         self.project_service.get_all_projects().await
     }
 
+    pub async fn archive_project(&self, id: &str) -> Result<ProjectModel, TodoError> {
+        let id = id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = projects::Entity::find_by_id(&id).one(txn).await?;
+                    let after = ProjectService::archive_in_conn(txn, &id, now).await?;
+                    let change = LocalChange::ProjectUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn unarchive_project(&self, id: &str) -> Result<ProjectModel, TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = projects::Entity::find_by_id(&id).one(txn).await?;
+                    let after = ProjectService::unarchive_in_conn(txn, &id).await?;
+                    let change = LocalChange::ProjectUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn delete_archived_project(&self, id: &str) -> Result<(), TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    ProjectService::delete_archived_in_conn(txn, &id).await?;
+                    TodoistSyncService::record_in_conn(txn, [LocalChange::ProjectDeleted(id)]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn list_archive(&self) -> Result<Vec<ArchivedProject>, TodoError> {
+        self.project_service.list_archive().await
+    }
+
     // ==================== Section Operations ====================
 
     pub async fn insert_section(&self, section: SectionModel) -> Result<SectionModel, TodoError> {
-        self.section_service.insert_section(section).await
+        self.db
+            .transaction::<_, SectionModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let section = SectionService::insert_section_in_conn(txn, section).await?;
+                    let change = LocalChange::SectionAdded(section.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(section)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn update_section(&self, section: SectionModel) -> Result<SectionModel, TodoError> {
-        self.section_service.update_section(section).await
+        self.db
+            .transaction::<_, SectionModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = sections::Entity::find_by_id(&section.id).one(txn).await?;
+                    let after = SectionService::update_section_in_conn(txn, section).await?;
+                    let change = LocalChange::SectionUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn delete_section(&self, section_id: &str) -> Result<(), TodoError> {
-        self.section_service.delete_section(section_id).await
+        let section_id = section_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    TrashService::trash_section_in_conn(txn, &section_id, now).await?;
+                    let change = LocalChange::SectionDeleted(section_id);
+                    TodoistSyncService::record_in_conn(txn, [change]).await
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn set_section_archived(
+        &self,
+        section_id: &str,
+        archived: bool,
+    ) -> Result<SectionModel, TodoError> {
+        let section_id = section_id.to_string();
+        self.db
+            .transaction::<_, SectionModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let before = sections::Entity::find_by_id(&section_id).one(txn).await?;
+                    let after =
+                        SectionService::set_section_archived_in_conn(txn, &section_id, archived)
+                            .await?;
+                    let change = LocalChange::SectionUpdated { before, after: after.clone() };
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(after)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn get_all_sections(&self) -> Result<Vec<SectionModel>, TodoError> {
@@ -172,17 +580,123 @@ impl Store {
     }
 
     pub async fn update_label(&self, label: LabelModel) -> Result<LabelModel, TodoError> {
-        self.label_service.update_label(label).await
+        self.db
+            .transaction::<_, LabelModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let label = LabelService::update_label_in_conn(txn, label).await?;
+                    let change = LocalChange::LabelUpdated(label.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(label)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn delete_label(&self, id: &str) -> Result<u64, TodoError> {
-        self.label_service.delete_label(id).await
+        let id = id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, u64, TodoError>(|txn| {
+                Box::pin(async move {
+                    let deleted = TrashService::trash_label_in_conn(txn, &id, now).await?;
+                    TodoistSyncService::record_in_conn(txn, [LocalChange::LabelDeleted(id)])
+                        .await?;
+                    Ok(deleted)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 
     pub async fn get_all_labels(&self) -> Result<Vec<LabelModel>, TodoError> {
         self.label_service.get_all_labels().await
     }
 
+    pub async fn rename_label(&self, id: &str, name: &str) -> Result<LabelModel, TodoError> {
+        let id = id.to_string();
+        let name = name.to_string();
+        self.db
+            .transaction::<_, LabelModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let label = LabelService::rename_label_in_conn(txn, &id, &name).await?;
+                    let change = LocalChange::LabelUpdated(label.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(label)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 把 `source_id` 合并到 `target_id`，源标签移入回收站
+    pub async fn merge_labels(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<LabelMerge, TodoError> {
+        let source_id = source_id.to_string();
+        let target_id = target_id.to_string();
+        self.db
+            .transaction::<_, LabelMerge, TodoError>(|txn| {
+                Box::pin(async move {
+                    let merge =
+                        LabelService::merge_labels_in_conn(txn, &source_id, &target_id).await?;
+                    let mut changes: Vec<LocalChange> = merge
+                        .item_ids
+                        .iter()
+                        .map(|item_id| LocalChange::ItemLabelsChanged(item_id.clone()))
+                        .collect();
+                    changes.push(LocalChange::LabelUpdated(merge.label.clone()));
+                    changes.push(LocalChange::LabelDeleted(source_id));
+                    TodoistSyncService::record_in_conn(txn, changes).await?;
+                    Ok(merge)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn reorder_labels(&self, ids: &[String]) -> Result<Vec<LabelModel>, TodoError> {
+        let ids = ids.to_vec();
+        self.db
+            .transaction::<_, Vec<LabelModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let labels = LabelService::reorder_labels_in_conn(txn, &ids).await?;
+                    let changes = labels.iter().cloned().map(LocalChange::LabelUpdated);
+                    TodoistSyncService::record_in_conn(txn, changes).await?;
+                    Ok(labels)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn set_label_favorite(
+        &self,
+        id: &str,
+        is_favorite: bool,
+    ) -> Result<LabelModel, TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, LabelModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let label =
+                        LabelService::set_label_favorite_in_conn(txn, &id, is_favorite).await?;
+                    let change = LocalChange::LabelUpdated(label.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(label)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 每个标签下未删除的任务数
+    pub async fn get_label_usage(&self) -> Result<HashMap<String, u64>, TodoError> {
+        self.label_service.get_label_usage().await
+    }
+
     // ==================== Reminder Operations ====================
 
     pub async fn get_reminders_by_item(
@@ -223,12 +737,314 @@ impl Store {
         self.attachment_service.delete_attachment(attachment_id).await
     }
 
+    // ==================== Trash Operations ====================
+
+    pub async fn list_trash(&self) -> Result<Vec<TrashEntry>, TodoError> {
+        self.trash_service.list_trash().await
+    }
+
+    pub async fn restore_from_trash(
+        &self,
+        object_type: TrashObjectType,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        self.trash_service.restore(object_type, id).await
+    }
+
+    pub async fn purge_from_trash(
+        &self,
+        object_type: TrashObjectType,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        self.trash_service.purge(object_type, id).await
+    }
+
+    pub async fn empty_trash(&self) -> Result<usize, TodoError> {
+        self.trash_service.empty_trash().await
+    }
+
+    pub async fn purge_expired_trash(&self, retention_days: u32) -> Result<usize, TodoError> {
+        self.trash_service.purge_expired(retention_days).await
+    }
+
+    // ==================== Event Operations ====================
+
+    pub async fn get_events_by_item(&self, item_id: &str) -> Result<Vec<EventEntry>, TodoError> {
+        self.event_service.get_events_by_item(item_id).await
+    }
+
+    pub async fn get_events_by_project(
+        &self,
+        project_id: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<EventEntry>, TodoError> {
+        self.event_service.get_events_by_project(project_id, limit).await
+    }
+
+    pub async fn get_events_between(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<EventEntry>, TodoError> {
+        self.event_service.get_events_between(start, end).await
+    }
+
+    // ==================== Search Operations ====================
+
+    pub async fn search(&self, query: &str, limit: u64) -> Result<Vec<SearchHit>, TodoError> {
+        self.search_service.search(query, limit).await
+    }
+
+    // ==================== Filter Operations ====================
+
+    pub async fn get_all_filters(&self) -> Result<Vec<FilterModel>, TodoError> {
+        self.filter_service.get_all_filters().await
+    }
+
+    pub async fn insert_filter(&self, filter: FilterModel) -> Result<FilterModel, TodoError> {
+        self.filter_service.insert_filter(filter).await
+    }
+
+    pub async fn update_filter(&self, filter: FilterModel) -> Result<FilterModel, TodoError> {
+        self.filter_service.update_filter(filter).await
+    }
+
+    pub async fn delete_filter(&self, id: &str) -> Result<u64, TodoError> {
+        self.filter_service.delete_filter(id).await
+    }
+
+    // ==================== Integrity Operations ====================
+
+    pub async fn check_integrity(&self) -> Result<IntegrityReport, TodoError> {
+        self.integrity_service.check().await
+    }
+
+    pub async fn repair_integrity(&self, issues: &[IntegrityIssue]) -> Result<usize, TodoError> {
+        self.integrity_service.repair(issues).await
+    }
+
+    // ==================== Import Operations ====================
+
+    pub async fn import_todoist_backup(&self, path: &Path) -> Result<ImportReport, TodoError> {
+        self.import_service.import_todoist_backup(path).await
+    }
+
+    pub async fn import_ics(&self, path: &Path) -> Result<ImportReport, TodoError> {
+        self.ical_service.import_ics(path).await
+    }
+
+    // ==================== Export Operations ====================
+
+    pub async fn export_to_file(
+        &self,
+        path: &Path,
+        format: ExportFormat,
+        project_id: Option<&str>,
+    ) -> Result<(), TodoError> {
+        self.export_service.export_to_file(path, format, project_id).await
+    }
+
+    pub async fn export_ics_to_file(
+        &self,
+        path: &Path,
+        component: IcsComponent,
+        project_id: Option<&str>,
+    ) -> Result<(), TodoError> {
+        self.ical_service.export_ics_to_file(path, component, project_id).await
+    }
+
+    // ==================== Duplicate Operations ====================
+
+    pub async fn duplicate_item(&self, item_id: &str) -> Result<DuplicateResult, TodoError> {
+        let item_id = item_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move {
+                    let result = DuplicateService::duplicate_item_in_conn(txn, &item_id).await?;
+                    TodoistSyncService::record_in_conn(txn, duplicate_changes(&result)).await?;
+                    Ok(result)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn duplicate_section(&self, section_id: &str) -> Result<DuplicateResult, TodoError> {
+        let section_id = section_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move {
+                    let result =
+                        DuplicateService::duplicate_section_in_conn(txn, &section_id).await?;
+                    TodoistSyncService::record_in_conn(txn, duplicate_changes(&result)).await?;
+                    Ok(result)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    pub async fn duplicate_project(&self, project_id: &str) -> Result<DuplicateResult, TodoError> {
+        let project_id = project_id.to_string();
+        self.db
+            .transaction::<_, DuplicateResult, TodoError>(|txn| {
+                Box::pin(async move {
+                    let result =
+                        DuplicateService::duplicate_project_in_conn(txn, &project_id).await?;
+                    TodoistSyncService::record_in_conn(txn, duplicate_changes(&result)).await?;
+                    Ok(result)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    // ==================== Template Operations ====================
+
+    pub async fn create_project_template(
+        &self,
+        project_id: &str,
+        base_date: Option<NaiveDate>,
+    ) -> Result<ProjectTemplate, TodoError> {
+        self.project_service.create_template(project_id, base_date).await
+    }
+
+    /// 把项目保存为模板文件
+    pub async fn save_project_template(
+        &self,
+        project_id: &str,
+        path: &Path,
+        base_date: Option<NaiveDate>,
+    ) -> Result<ProjectTemplate, TodoError> {
+        let template = self.project_service.create_template(project_id, base_date).await?;
+        std::fs::write(path, template.to_json()?).map_err(|e| {
+            TodoError::InternalError(format!("无法写入模板文件 {}: {}", path.display(), e))
+        })?;
+        Ok(template)
+    }
+
+    pub async fn instantiate_template(
+        &self,
+        template: &ProjectTemplate,
+        name: Option<&str>,
+        start: NaiveDate,
+    ) -> Result<ProjectModel, TodoError> {
+        let template = template.clone();
+        let name = name.map(str::to_string);
+        self.db
+            .transaction::<_, ProjectModel, TodoError>(|txn| {
+                Box::pin(async move {
+                    let project = ProjectService::instantiate_template_in_conn(
+                        txn,
+                        &template,
+                        name.as_deref(),
+                        start,
+                    )
+                    .await?;
+                    let change = LocalChange::ProjectAdded(project.clone());
+                    TodoistSyncService::record_in_conn(txn, [change]).await?;
+                    Ok(project)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 读取模板文件并实例化为新项目
+    pub async fn instantiate_template_file(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        start: NaiveDate,
+    ) -> Result<ProjectModel, TodoError> {
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            TodoError::InternalError(format!("无法读取模板文件 {}: {}", path.display(), e))
+        })?;
+        let template = ProjectTemplate::from_json(&json)?;
+        self.instantiate_template(&template, name, start).await
+    }
+
+    // ==================== Sync Operations ====================
+
+    pub async fn add_caldav_source(&self, config: CalDavConfig) -> Result<SourceModel, TodoError> {
+        self.caldav_service.add_source(config).await
+    }
+
+    pub async fn get_caldav_sources(&self) -> Result<Vec<SourceModel>, TodoError> {
+        self.caldav_service.get_sources().await
+    }
+
+    pub async fn remove_caldav_source(&self, source_id: &str) -> Result<(), TodoError> {
+        self.caldav_service.remove_source(source_id).await
+    }
+
+    pub async fn sync_caldav(&self, source_id: &str) -> Result<CalDavSyncReport, TodoError> {
+        self.caldav_service.sync(source_id).await
+    }
+
+    pub async fn add_todoist_source(
+        &self,
+        config: TodoistConfig,
+    ) -> Result<SourceModel, TodoError> {
+        self.todoist_sync_service.add_source(config).await
+    }
+
+    pub async fn get_todoist_source(&self) -> Result<Option<SourceModel>, TodoError> {
+        self.todoist_sync_service.get_source().await
+    }
+
+    pub async fn remove_todoist_source(&self, source_id: &str) -> Result<(), TodoError> {
+        self.todoist_sync_service.remove_source(source_id).await
+    }
+
+    pub async fn sync_todoist(&self, source_id: &str) -> Result<TodoistSyncReport, TodoError> {
+        self.todoist_sync_service.sync(source_id).await
+    }
+
+    pub async fn pending_sync_commands(&self) -> Result<Vec<QueueModel>, TodoError> {
+        self.todoist_sync_service.pending_commands().await
+    }
+
     // ==================== Batch Operations ====================
 
     pub async fn batch_update_items(
         &self,
         items: Vec<ItemModel>,
     ) -> Result<Vec<ItemModel>, TodoError> {
-        self.item_service.batch_update_items(items).await
+        if items.is_empty() {
+            return Ok(vec![]);
+        }
+        let now = chrono::Utc::now().naive_utc();
+        self.db
+            .transaction::<_, Vec<ItemModel>, TodoError>(|txn| {
+                Box::pin(async move {
+                    let mut befores = Vec::with_capacity(items.len());
+                    for item in &items {
+                        befores.push(items::Entity::find_by_id(&item.id).one(txn).await?);
+                    }
+                    let updated = ItemService::batch_update_items_in_conn(txn, items, now).await?;
+                    let changes = befores
+                        .into_iter()
+                        .zip(updated.iter().cloned())
+                        .map(|(before, after)| LocalChange::ItemUpdated { before, after });
+                    TodoistSyncService::record_in_conn(txn, changes).await?;
+                    Ok(updated)
+                })
+            })
+            .await
+            .map_err(TodoError::from)
     }
 }
+
+/// 子任务随父任务一起移动，同步时只需记录被移动的根任务
+fn subtree_move(before: Option<ItemModel>, changed: &[ItemModel]) -> Option<LocalChange> {
+    changed.first().map(|after| LocalChange::ItemUpdated { before, after: after.clone() })
+}
+
+/// 副本按父对象在前的顺序登记为新增
+fn duplicate_changes(result: &DuplicateResult) -> Vec<LocalChange> {
+    let projects = result.projects.iter().cloned().map(LocalChange::ProjectAdded);
+    let sections = result.sections.iter().cloned().map(LocalChange::SectionAdded);
+    let items = result.items.iter().cloned().map(LocalChange::ItemAdded);
+    projects.chain(sections).chain(items).collect()
+}
@@ -0,0 +1,1166 @@
+//! Todoist two-way sync via the Sync API
+//!
+//! 本地修改不直接访问网络，而是在写入成功后转换为 Sync API 的命令排入 `queue` 表
+//! （`query` 为命令类型，`args` 为参数，新建对象的 `temp_id` 就是本地 ID），同步时按加入顺序
+//! 每批最多 100 条发送。离线时命令留在队列中，下次同步继续发送。
+//!
+//! 本地 ID 与服务器 ID 的对应保存在 `cur_temp_ids`（`id` 为服务器 ID，`temp_id` 为本地 ID）：
+//! - 本地新建的对象保留本地 ID，服务器在 `temp_id_mapping` 中返回正式 ID 后记录对应关系，
+//!   之后的命令在发送前把参数中的本地 ID 换成服务器 ID；
+//! - 从服务器拉取的新对象直接使用服务器 ID，不需要记录。
+//!
+//! 每次请求都带上 `sync_token`，响应中是此后有变化的项目、分区、任务和标签，与命令结果、
+//! 新的 `sync_token` 在同一个事务中写入。拉取的修改直接写数据库，不会再次排入队列。
+//!
+//! 只同步属于 Todoist 的对象：`backend_type = "todoist"` 的项目及其中的分区和任务、
+//! Todoist 的标签。任务上的标签按名称发送，服务器上没有的标签会自动创建。
+//! 只支持一个 Todoist 账号。
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use chrono::{Duration, NaiveDateTime, NaiveTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait, prelude::Expr,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use super::{
+    ImportReport, TrashService,
+    import_service::{ImportData, flag, text},
+    util::{RawLabel, insert_batched, replace_item_labels, resolve_labels},
+};
+use crate::{
+    DueDate,
+    entity::{
+        CurTempIdActiveModel, CurTempIdModel, ItemActiveModel, ItemModel, LabelActiveModel,
+        LabelModel, ProjectActiveModel, ProjectModel, QueueActiveModel, QueueModel,
+        SectionActiveModel, SectionModel, SourceActiveModel, SourceModel, cur_temp_ids,
+        item_labels, items, labels, projects, queue, sections, sources,
+    },
+    enums::RecurrencyType,
+    error::TodoError,
+    sync::{
+        HttpTransport, ReqwestTransport, SyncCommand, SyncResponse, TodoistClient,
+        todoist::{COMMAND_BATCH, FULL_SYNC_TOKEN},
+    },
+    utils::Util,
+};
+
+/// `sources.source_type`、`projects.backend_type` 和 `labels.backend_type` 中 Todoist 的取值
+pub const TODOIST_SOURCE_TYPE: &str = "todoist";
+
+/// `queue.date_added` 的格式，固定宽度，按字符串排序即按时间排序
+const QUEUE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Todoist 账号的连接配置
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoistConfig {
+    /// API 令牌（Todoist 设置 → 集成 → 开发者）
+    pub token: String,
+    pub display_name: Option<String>,
+    /// Sync API 地址，为空时使用官方地址
+    #[serde(default)]
+    pub sync_url: Option<String>,
+}
+
+impl fmt::Debug for TodoistConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TodoistConfig")
+            .field("display_name", &self.display_name)
+            .field("sync_url", &self.sync_url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TodoistSyncReport {
+    /// 服务器执行成功的命令数
+    pub pushed: usize,
+    /// 被服务器拒绝的命令（已从队列中移除）：`命令类型 对象 ID：错误信息`
+    pub rejected: Vec<String>,
+    /// 从服务器新建 / 更新 / 移入回收站的本地对象
+    pub pulled_created: usize,
+    pub pulled_updated: usize,
+    pub pulled_deleted: usize,
+    /// 是否为全量同步（第一次同步或服务器要求重新同步）
+    pub full_sync: bool,
+    /// 无法识别而被跳过或降级的内容
+    pub skipped: Vec<String>,
+}
+
+impl TodoistSyncReport {
+    /// 本地数据是否有变化
+    pub fn has_changes(&self) -> bool {
+        self.pulled_created + self.pulled_updated + self.pulled_deleted > 0
+    }
+
+    /// 一行摘要，供界面提示
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "发送 {} 条修改，拉取新建 {}、更新 {}、删除 {}",
+            self.pushed, self.pulled_created, self.pulled_updated, self.pulled_deleted
+        );
+        if !self.rejected.is_empty() {
+            summary.push_str(&format!("，{} 条修改被拒绝", self.rejected.len()));
+        }
+        summary
+    }
+}
+
+/// 本地的一次修改，由 [`Store`](crate::Store) 在写入成功后记录
+///
+/// 修改前的对象用于判断移动、完成状态等需要单独命令的变化，以及对象是否移入 / 移出了
+/// Todoist 的项目。
+#[derive(Debug, Clone)]
+pub enum LocalChange {
+    ItemAdded(ItemModel),
+    ItemUpdated {
+        before: Option<ItemModel>,
+        after: ItemModel,
+    },
+    ItemCompleted {
+        item_id: String,
+        checked: bool,
+    },
+    /// 任务的标签（`item_labels`）有变化
+    ItemLabelsChanged(String),
+    ItemDeleted(String),
+    ProjectAdded(ProjectModel),
+    ProjectUpdated {
+        before: Option<ProjectModel>,
+        after: ProjectModel,
+    },
+    ProjectDeleted(String),
+    SectionAdded(SectionModel),
+    SectionUpdated {
+        before: Option<SectionModel>,
+        after: SectionModel,
+    },
+    SectionDeleted(String),
+    LabelUpdated(LabelModel),
+    LabelDeleted(String),
+}
+
+/// 保存在 `sources.data` 中的同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncState {
+    config: TodoistConfig,
+    sync_token: String,
+}
+
+impl SyncState {
+    fn read(source: &SourceModel) -> Result<Self, TodoError> {
+        if source.source_type != TODOIST_SOURCE_TYPE {
+            return Err(TodoError::validation(format!(
+                "同步源 {} 不是 Todoist 账号（{}）",
+                source.id, source.source_type
+            )));
+        }
+        serde_json::from_str(source.data.as_deref().unwrap_or_default()).map_err(|e| {
+            TodoError::InternalError(format!("同步源 {} 的状态无法解析: {}", source.id, e))
+        })
+    }
+}
+
+/// 排入队列前的命令，参数中的 ID 都是本地 ID
+#[derive(Debug)]
+struct PendingCommand {
+    kind: &'static str,
+    object_id: String,
+    temp_id: Option<String>,
+    args: Value,
+}
+
+impl PendingCommand {
+    fn new(kind: &'static str, object_id: &str, args: Value) -> Self {
+        Self { kind, object_id: object_id.to_string(), temp_id: None, args }
+    }
+
+    /// `*_add` 命令：本地 ID 作为临时 ID
+    fn add(kind: &'static str, object_id: &str, args: Value) -> Self {
+        Self { temp_id: Some(object_id.to_string()), ..Self::new(kind, object_id, args) }
+    }
+}
+
+/// Service for Todoist sync
+#[derive(Clone, Debug)]
+pub struct TodoistSyncService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl TodoistSyncService {
+    /// Create a new TodoistSyncService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    /// 添加 Todoist 账号，第一次同步时拉取全部数据
+    pub async fn add_source(&self, config: TodoistConfig) -> Result<SourceModel, TodoError> {
+        if config.token.trim().is_empty() {
+            return Err(TodoError::validation("Todoist API 令牌不能为空"));
+        }
+        if self.get_source().await?.is_some() {
+            return Err(TodoError::already_exists("Todoist 账号"));
+        }
+
+        let now = Utc::now().naive_utc();
+        let name = config
+            .display_name
+            .clone()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "Todoist".to_string());
+        let state = SyncState {
+            config: TodoistConfig {
+                token: config.token.trim().to_string(),
+                display_name: Some(name.clone()),
+                ..config
+            },
+            sync_token: FULL_SYNC_TOKEN.to_string(),
+        };
+        let source = SourceModel {
+            id: Uuid::new_v4().to_string(),
+            source_type: TODOIST_SOURCE_TYPE.to_string(),
+            display_name: Some(name),
+            added_at: now,
+            updated_at: now,
+            is_visible: true,
+            child_order: None,
+            sync_server: None,
+            last_sync: None,
+            data: Some(state_json(&state)?),
+        };
+        insert_batched(&*self.db, vec![SourceActiveModel::from(source.clone())]).await?;
+        Ok(source)
+    }
+
+    /// 已添加的 Todoist 账号
+    pub async fn get_source(&self) -> Result<Option<SourceModel>, TodoError> {
+        Ok(sources::Entity::find()
+            .filter(sources::Column::SourceType.eq(TODOIST_SOURCE_TYPE))
+            .one(&*self.db)
+            .await?)
+    }
+
+    /// 移除账号；项目、任务和标签保留为本地数据，未发送的修改和 ID 对应关系一并清除
+    pub async fn remove_source(&self, source_id: &str) -> Result<(), TodoError> {
+        let source = self.get_source_by_id(source_id).await?;
+        SyncState::read(&source)?;
+        let source_id = source.id;
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    projects::Entity::update_many()
+                        .col_expr(projects::Column::BackendType, Expr::value(None::<String>))
+                        .col_expr(projects::Column::SourceId, Expr::value(None::<String>))
+                        .filter(projects::Column::BackendType.eq(TODOIST_SOURCE_TYPE))
+                        .exec(txn)
+                        .await?;
+                    labels::Entity::update_many()
+                        .col_expr(labels::Column::BackendType, Expr::value(None::<String>))
+                        .col_expr(labels::Column::SourceId, Expr::value(None::<String>))
+                        .filter(labels::Column::BackendType.eq(TODOIST_SOURCE_TYPE))
+                        .exec(txn)
+                        .await?;
+                    queue::Entity::delete_many().exec(txn).await?;
+                    cur_temp_ids::Entity::delete_many().exec(txn).await?;
+                    sources::Entity::delete_by_id(source_id).exec(txn).await?;
+                    Ok(())
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 等待发送的命令（按加入顺序）
+    pub async fn pending_commands(&self) -> Result<Vec<QueueModel>, TodoError> {
+        Ok(queue::Entity::find()
+            .order_by_asc(queue::Column::DateAdded)
+            .order_by_asc(queue::Column::Id)
+            .all(&*self.db)
+            .await?)
+    }
+
+    /// 把本地修改转换为命令排入队列；没有 Todoist 账号或对象不属于 Todoist 时什么也不做
+    pub async fn record(&self, change: LocalChange) -> Result<(), TodoError> {
+        Self::record_in_conn(&*self.db, [change]).await
+    }
+
+    /// 在修改所在的连接（通常是同一个事务）中排入命令，修改和命令一起提交或回滚
+    pub(crate) async fn record_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        changes: impl IntoIterator<Item = LocalChange>,
+    ) -> Result<(), TodoError> {
+        let has_source = sources::Entity::find()
+            .filter(sources::Column::SourceType.eq(TODOIST_SOURCE_TYPE))
+            .one(conn)
+            .await?
+            .is_some();
+        if !has_source {
+            return Ok(());
+        }
+        let mut commands = Vec::new();
+        for change in changes {
+            commands.extend(commands_for(conn, change).await?);
+        }
+        enqueue(conn, commands).await
+    }
+
+    /// 同步 Todoist 账号：发送队列中的修改并拉取服务器上的变化
+    pub async fn sync(&self, source_id: &str) -> Result<TodoistSyncReport, TodoError> {
+        self.sync_with_transport(source_id, Arc::new(ReqwestTransport::new("", ""))).await
+    }
+
+    /// 通过指定的传输同步（测试中使用本地的替身服务器）
+    pub async fn sync_with_transport(
+        &self,
+        source_id: &str,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Result<TodoistSyncReport, TodoError> {
+        let source = self.get_source_by_id(source_id).await?;
+        let mut state = SyncState::read(&source)?;
+        let mut client = TodoistClient::new(transport, &state.config.token);
+        if let Some(url) = state.config.sync_url.as_deref().filter(|url| !url.is_empty()) {
+            client = client.with_url(url);
+        }
+        let mut report = TodoistSyncReport::default();
+
+        // 队列为空时也发送一次请求，拉取服务器上的变化
+        loop {
+            let queued = queue::Entity::find()
+                .order_by_asc(queue::Column::DateAdded)
+                .order_by_asc(queue::Column::Id)
+                .limit(COMMAND_BATCH as u64)
+                .all(&*self.db)
+                .await?;
+            let ids = TempIds::load(&*self.db).await?;
+            let commands: Vec<SyncCommand> = queued.iter().map(|row| ids.command(row)).collect();
+            let response = client.sync(&state.sync_token, &commands).await?;
+
+            let batch_report = std::mem::take(&mut report);
+            let sync_token = response.sync_token.clone();
+            let next_state = SyncState { sync_token: sync_token.clone(), ..state.clone() };
+            let source_id = source.id.clone();
+            let done = queued.len() < COMMAND_BATCH;
+            report = self
+                .db
+                .transaction::<_, TodoistSyncReport, TodoError>(|txn| {
+                    Box::pin(async move {
+                        let report =
+                            apply_response(txn, &source_id, queued, response, batch_report).await?;
+                        save_state(txn, &source_id, &next_state, done).await?;
+                        Ok(report)
+                    })
+                })
+                .await?;
+            state.sync_token = sync_token;
+            if done {
+                break;
+            }
+        }
+
+        tracing::info!("Todoist sync finished: {}", report.summary());
+        Ok(report)
+    }
+
+    async fn get_source_by_id(&self, source_id: &str) -> Result<SourceModel, TodoError> {
+        sources::Entity::find_by_id(source_id)
+            .one(&*self.db)
+            .await?
+            .ok_or_else(|| TodoError::not_found("Source").with_entity("Source", source_id))
+    }
+}
+
+fn state_json(state: &SyncState) -> Result<String, TodoError> {
+    serde_json::to_string(state)
+        .map_err(|e| TodoError::InternalError(format!("同步状态序列化失败: {}", e)))
+}
+
+async fn save_state<C: ConnectionTrait>(
+    conn: &C,
+    source_id: &str,
+    state: &SyncState,
+    synced: bool,
+) -> Result<(), TodoError> {
+    let now = Utc::now().naive_utc();
+    let mut update = sources::Entity::update_many()
+        .col_expr(sources::Column::Data, Expr::value(state_json(state)?))
+        .col_expr(sources::Column::UpdatedAt, Expr::value(now))
+        .filter(sources::Column::Id.eq(source_id));
+    if synced {
+        update = update.col_expr(
+            sources::Column::LastSync,
+            Expr::value(now.format("%Y-%m-%d %H:%M:%S").to_string()),
+        );
+    }
+    update.exec(conn).await?;
+    Ok(())
+}
+
+// ==================== ID 对应 ====================
+
+/// 命令参数中引用其他对象的字段，发送前换成服务器 ID
+const ID_FIELDS: &[&str] = &["id", "project_id", "section_id", "parent_id", "item_id"];
+
+/// `cur_temp_ids` 中的对应关系
+#[derive(Debug, Default)]
+struct TempIds {
+    /// 本地 ID → 服务器 ID
+    remote: HashMap<String, String>,
+    /// 服务器 ID → 本地 ID
+    local: HashMap<String, String>,
+}
+
+impl TempIds {
+    async fn load<C: ConnectionTrait>(conn: &C) -> Result<Self, TodoError> {
+        let mut ids = Self::default();
+        for row in cur_temp_ids::Entity::find().all(conn).await? {
+            if let Some(temp_id) = row.temp_id {
+                ids.insert(&temp_id, &row.id);
+            }
+        }
+        Ok(ids)
+    }
+
+    fn insert(&mut self, local: &str, remote: &str) {
+        self.remote.insert(local.to_string(), remote.to_string());
+        self.local.insert(remote.to_string(), local.to_string());
+    }
+
+    fn remote_id<'a>(&'a self, local: &'a str) -> &'a str {
+        self.remote.get(local).map_or(local, String::as_str)
+    }
+
+    fn local_id(&self, remote: &str) -> String {
+        self.local.get(remote).cloned().unwrap_or_else(|| remote.to_string())
+    }
+
+    /// 队列中的一行转换为要发送的命令
+    fn command(&self, row: &QueueModel) -> SyncCommand {
+        let mut args = row
+            .args
+            .as_deref()
+            .and_then(|args| serde_json::from_str(args).ok())
+            .unwrap_or_else(|| json!({}));
+        if let Value::Object(map) = &mut args {
+            for field in ID_FIELDS {
+                if let Some(Value::String(id)) = map.get_mut(*field) {
+                    *id = self.remote_id(id).to_string();
+                }
+            }
+        }
+        SyncCommand {
+            kind: row.query.clone().unwrap_or_default(),
+            uuid: row.id.clone(),
+            temp_id: row.temp_id.clone(),
+            args,
+        }
+    }
+}
+
+// ==================== 本地修改 → 命令 ====================
+
+/// 按加入顺序写入队列
+async fn enqueue<C: ConnectionTrait>(
+    conn: &C,
+    commands: Vec<PendingCommand>,
+) -> Result<(), TodoError> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+    let last = queue::Entity::find()
+        .order_by_desc(queue::Column::DateAdded)
+        .one(conn)
+        .await?
+        .and_then(|row| row.date_added)
+        .and_then(|at| NaiveDateTime::parse_from_str(&at, QUEUE_TIME_FORMAT).ok());
+    let mut at = Utc::now().naive_utc();
+    if let Some(last) = last
+        && at <= last
+    {
+        at = last + Duration::microseconds(1);
+    }
+
+    let mut rows = Vec::with_capacity(commands.len());
+    for command in commands {
+        rows.push(QueueActiveModel::from(QueueModel {
+            id: Uuid::new_v4().to_string(),
+            object_id: Some(command.object_id),
+            query: Some(command.kind.to_string()),
+            temp_id: command.temp_id,
+            args: Some(command.args.to_string()),
+            date_added: Some(at.format(QUEUE_TIME_FORMAT).to_string()),
+        }));
+        at += Duration::microseconds(1);
+    }
+    insert_batched(conn, rows).await
+}
+
+async fn commands_for<C: ConnectionTrait>(
+    conn: &C,
+    change: LocalChange,
+) -> Result<Vec<PendingCommand>, TodoError> {
+    let mut commands = Vec::new();
+    match change {
+        // ---------- 任务 ----------
+        LocalChange::ItemAdded(item) => {
+            if in_todoist_project(conn, item.project_id.as_deref()).await? {
+                push_item_add(conn, &item, &mut commands).await?;
+            }
+        },
+        LocalChange::ItemUpdated { before, after } => {
+            let synced = in_todoist_project(conn, after.project_id.as_deref()).await?;
+            let was_synced = match &before {
+                Some(before) => in_todoist_project(conn, before.project_id.as_deref()).await?,
+                None => synced,
+            };
+            match (was_synced, synced) {
+                (false, true) => push_item_add(conn, &after, &mut commands).await?,
+                (true, false) => {
+                    commands.push(PendingCommand::new("item_delete", &after.id, id_args(&after.id)))
+                },
+                (true, true) => {
+                    let changed = before.as_ref().is_none_or(|before| {
+                        (&before.content, &before.description, before.priority, &before.due)
+                            != (&after.content, &after.description, after.priority, &after.due)
+                            || (before.labels != after.labels
+                                || before.collapsed != after.collapsed)
+                    });
+                    if changed {
+                        let args = item_update_args(conn, &after).await?;
+                        commands.push(PendingCommand::new("item_update", &after.id, args));
+                    }
+                    if let Some(before) = &before {
+                        if (&before.project_id, &before.section_id, &before.parent_id)
+                            != (&after.project_id, &after.section_id, &after.parent_id)
+                        {
+                            commands.push(PendingCommand::new(
+                                "item_move",
+                                &after.id,
+                                item_move_args(&after),
+                            ));
+                        }
+                        if before.checked != after.checked {
+                            commands.push(completion_command(&after.id, after.checked));
+                        }
+                    }
+                },
+                (false, false) => {},
+            }
+        },
+        LocalChange::ItemCompleted { item_id, checked } => {
+            if let Some(item) = items::Entity::find_by_id(item_id.clone()).one(conn).await?
+                && in_todoist_project(conn, item.project_id.as_deref()).await?
+            {
+                commands.push(completion_command(&item_id, checked));
+            }
+        },
+        LocalChange::ItemLabelsChanged(item_id) => {
+            if let Some(item) = items::Entity::find_by_id(item_id.clone()).one(conn).await?
+                && in_todoist_project(conn, item.project_id.as_deref()).await?
+            {
+                let labels = item_label_names(conn, &item_id).await?;
+                let args = json!({ "id": item_id, "labels": labels });
+                commands.push(PendingCommand::new("item_update", &item_id, args));
+            }
+        },
+        LocalChange::ItemDeleted(item_id) => {
+            if let Some(item) = items::Entity::find_by_id(item_id.clone()).one(conn).await?
+                && in_todoist_project(conn, item.project_id.as_deref()).await?
+            {
+                commands.push(PendingCommand::new("item_delete", &item_id, id_args(&item_id)));
+            }
+        },
+
+        // ---------- 项目 ----------
+        LocalChange::ProjectAdded(project) => {
+            if is_todoist(project.backend_type.as_deref()) {
+                commands.push(project_add(&project));
+            }
+        },
+        LocalChange::ProjectUpdated { before, after } => {
+            let synced = is_todoist(after.backend_type.as_deref());
+            let was_synced = before.as_ref().is_none_or(|b| is_todoist(b.backend_type.as_deref()));
+            match (was_synced, synced, before) {
+                (false, true, _) => commands.push(project_add(&after)),
+                (true, true, before) => {
+                    let changed = before.as_ref().is_none_or(|before| {
+                        (&before.name, &before.color, before.is_favorite, before.collapsed)
+                            != (&after.name, &after.color, after.is_favorite, after.collapsed)
+                            || before.view_style != after.view_style
+                    });
+                    if changed {
+                        let args = json!({
+                            "id": after.id,
+                            "name": after.name,
+                            "color": color_key(after.color.as_deref()),
+                            "is_favorite": after.is_favorite,
+                            "collapsed": after.collapsed,
+                            "view_style": after.view_style,
+                        });
+                        commands.push(PendingCommand::new("project_update", &after.id, args));
+                    }
+                    if let Some(before) = before {
+                        if before.parent_id != after.parent_id {
+                            let args = json!({ "id": after.id, "parent_id": after.parent_id });
+                            commands.push(PendingCommand::new("project_move", &after.id, args));
+                        }
+                        if before.is_archived != after.is_archived {
+                            let kind = if after.is_archived {
+                                "project_archive"
+                            } else {
+                                "project_unarchive"
+                            };
+                            commands.push(PendingCommand::new(kind, &after.id, id_args(&after.id)));
+                        }
+                    }
+                },
+                // 本地解除关联的项目不删除服务器上的数据
+                _ => {},
+            }
+        },
+        LocalChange::ProjectDeleted(project_id) => {
+            if let Some(project) =
+                projects::Entity::find_by_id(project_id.clone()).one(conn).await?
+                && is_todoist(project.backend_type.as_deref())
+            {
+                commands.push(PendingCommand::new(
+                    "project_delete",
+                    &project_id,
+                    id_args(&project_id),
+                ));
+            }
+        },
+
+        // ---------- 分区 ----------
+        LocalChange::SectionAdded(section) => {
+            if in_todoist_project(conn, section.project_id.as_deref()).await? {
+                commands.push(section_add(&section));
+            }
+        },
+        LocalChange::SectionUpdated { before, after } => {
+            let synced = in_todoist_project(conn, after.project_id.as_deref()).await?;
+            let was_synced = match &before {
+                Some(before) => in_todoist_project(conn, before.project_id.as_deref()).await?,
+                None => synced,
+            };
+            match (was_synced, synced) {
+                (false, true) => commands.push(section_add(&after)),
+                (true, false) => commands.push(PendingCommand::new(
+                    "section_delete",
+                    &after.id,
+                    id_args(&after.id),
+                )),
+                (true, true) => {
+                    if before.as_ref().is_none_or(|before| {
+                        (&before.name, before.collapsed) != (&after.name, after.collapsed)
+                    }) {
+                        let args = json!({ "id": after.id, "name": after.name, "collapsed": after.collapsed });
+                        commands.push(PendingCommand::new("section_update", &after.id, args));
+                    }
+                    if let Some(before) = &before {
+                        if before.project_id != after.project_id {
+                            let args = json!({ "id": after.id, "project_id": after.project_id });
+                            commands.push(PendingCommand::new("section_move", &after.id, args));
+                        }
+                        if before.is_archived != after.is_archived {
+                            let kind = if after.is_archived {
+                                "section_archive"
+                            } else {
+                                "section_unarchive"
+                            };
+                            commands.push(PendingCommand::new(kind, &after.id, id_args(&after.id)));
+                        }
+                    }
+                },
+                (false, false) => {},
+            }
+        },
+        LocalChange::SectionDeleted(section_id) => {
+            if let Some(section) =
+                sections::Entity::find_by_id(section_id.clone()).one(conn).await?
+                && in_todoist_project(conn, section.project_id.as_deref()).await?
+            {
+                commands.push(PendingCommand::new(
+                    "section_delete",
+                    &section_id,
+                    id_args(&section_id),
+                ));
+            }
+        },
+
+        // ---------- 标签（新标签随任务按名称创建） ----------
+        LocalChange::LabelUpdated(label) => {
+            if is_todoist(label.backend_type.as_deref()) {
+                let args = json!({
+                    "id": label.id,
+                    "name": label.name,
+                    "color": color_key(Some(&label.color)),
+                    "item_order": label.item_order,
+                    "is_favorite": label.is_favorite,
+                });
+                commands.push(PendingCommand::new("label_update", &label.id, args));
+            }
+        },
+        LocalChange::LabelDeleted(label_id) => {
+            if let Some(label) = labels::Entity::find_by_id(label_id.clone()).one(conn).await?
+                && is_todoist(label.backend_type.as_deref())
+            {
+                commands.push(PendingCommand::new("label_delete", &label_id, id_args(&label_id)));
+            }
+        },
+    }
+    Ok(commands)
+}
+
+fn is_todoist(backend_type: Option<&str>) -> bool {
+    backend_type == Some(TODOIST_SOURCE_TYPE)
+}
+
+async fn in_todoist_project<C: ConnectionTrait>(
+    conn: &C,
+    project_id: Option<&str>,
+) -> Result<bool, TodoError> {
+    let Some(project_id) = project_id else {
+        return Ok(false);
+    };
+    Ok(projects::Entity::find_by_id(project_id)
+        .one(conn)
+        .await?
+        .is_some_and(|project| is_todoist(project.backend_type.as_deref())))
+}
+
+fn id_args(id: &str) -> Value {
+    json!({ "id": id })
+}
+
+async fn push_item_add<C: ConnectionTrait>(
+    conn: &C,
+    item: &ItemModel,
+    commands: &mut Vec<PendingCommand>,
+) -> Result<(), TodoError> {
+    let mut args = item_update_args(conn, item).await?;
+    if let Value::Object(map) = &mut args {
+        map.remove("id");
+        map.insert("project_id".to_string(), json!(item.project_id));
+        if let Some(section_id) = &item.section_id {
+            map.insert("section_id".to_string(), json!(section_id));
+        }
+        if let Some(parent_id) = &item.parent_id {
+            map.insert("parent_id".to_string(), json!(parent_id));
+        }
+        if let Some(order) = item.child_order {
+            map.insert("child_order".to_string(), json!(order));
+        }
+    }
+    commands.push(PendingCommand::add("item_add", &item.id, args));
+    if item.checked {
+        commands.push(completion_command(&item.id, true));
+    }
+    Ok(())
+}
+
+async fn item_update_args<C: ConnectionTrait>(
+    conn: &C,
+    item: &ItemModel,
+) -> Result<Value, TodoError> {
+    Ok(json!({
+        "id": item.id,
+        "content": item.content,
+        "description": item.description.clone().unwrap_or_default(),
+        // 本地 1 为最高，API 中 4 为最高（p1）
+        "priority": 5 - item.priority.unwrap_or(4).clamp(1, 4),
+        "due": due_value(item.due_date()),
+        "labels": item_label_names(conn, &item.id).await?,
+        "collapsed": item.collapsed,
+    }))
+}
+
+/// `item_move` 只接受一个目标：父任务、分区或项目
+fn item_move_args(item: &ItemModel) -> Value {
+    if let Some(parent_id) = &item.parent_id {
+        json!({ "id": item.id, "parent_id": parent_id })
+    } else if let Some(section_id) = &item.section_id {
+        json!({ "id": item.id, "section_id": section_id })
+    } else {
+        json!({ "id": item.id, "project_id": item.project_id })
+    }
+}
+
+/// 完成用 `item_close`：重复任务由服务器推进到下一次，与本地的处理一致
+fn completion_command(item_id: &str, checked: bool) -> PendingCommand {
+    let kind = if checked { "item_close" } else { "item_uncomplete" };
+    PendingCommand::new(kind, item_id, id_args(item_id))
+}
+
+fn project_add(project: &ProjectModel) -> PendingCommand {
+    let mut args = json!({
+        "name": project.name,
+        "color": color_key(project.color.as_deref()),
+        "is_favorite": project.is_favorite,
+    });
+    if let Some(parent_id) = &project.parent_id {
+        args["parent_id"] = json!(parent_id);
+    }
+    if let Some(view_style) = &project.view_style {
+        args["view_style"] = json!(view_style);
+    }
+    PendingCommand::add("project_add", &project.id, args)
+}
+
+fn section_add(section: &SectionModel) -> PendingCommand {
+    let mut args = json!({ "name": section.name, "project_id": section.project_id });
+    if let Some(order) = section.section_order {
+        args["section_order"] = json!(order);
+    }
+    PendingCommand::add("section_add", &section.id, args)
+}
+
+/// 任务的标签名称（以 `item_labels` 为准，不含回收站中的标签）
+async fn item_label_names<C: ConnectionTrait>(
+    conn: &C,
+    item_id: &str,
+) -> Result<Vec<String>, TodoError> {
+    let label_ids: Vec<String> = item_labels::Entity::find()
+        .filter(item_labels::Column::ItemId.eq(item_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|link| link.label_id)
+        .collect();
+    if label_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(labels::Entity::find()
+        .filter(labels::Column::Id.is_in(label_ids))
+        .filter(labels::Column::IsDeleted.eq(false))
+        .order_by_asc(labels::Column::Name)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|label| label.name)
+        .collect())
+}
+
+/// 本地保存的是十六进制颜色，API 使用颜色名（`berry_red`）；不是预设颜色时不发送
+fn color_key(color: Option<&str>) -> Option<String> {
+    let color = color?;
+    let colors = Util::get_default().get_colors();
+    if colors.contains_key(color) {
+        return Some(color.to_string());
+    }
+    colors
+        .into_iter()
+        .find(|(_, preset)| preset.hexadecimal.eq_ignore_ascii_case(color))
+        .map(|(key, _)| key)
+}
+
+/// Sync API 的 due 对象；重复规则用自然语言写在 `string` 中，没有截止时间时为 `null`（清除）
+fn due_value(due: Option<DueDate>) -> Value {
+    let Some(datetime) = due.as_ref().and_then(DueDate::datetime) else {
+        return Value::Null;
+    };
+    let date = if datetime.time() == NaiveTime::MIN {
+        datetime.format("%Y-%m-%d").to_string()
+    } else {
+        datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+    };
+    let mut value = json!({ "date": date });
+    if let Some(string) = due.as_ref().and_then(recurrence_string) {
+        value["string"] = json!(string);
+        value["is_recurring"] = json!(true);
+    }
+    value
+}
+
+/// `every day`、`every 2 weeks`，与快速添加能识别的写法一致
+fn recurrence_string(due: &DueDate) -> Option<String> {
+    if !due.is_recurring {
+        return None;
+    }
+    let unit = match due.recurrency_type {
+        RecurrencyType::MINUTELY => "minute",
+        RecurrencyType::HOURLY => "hour",
+        RecurrencyType::EveryDay => "day",
+        RecurrencyType::EveryWeek => "week",
+        RecurrencyType::EveryMonth => "month",
+        RecurrencyType::EveryYear => "year",
+        RecurrencyType::NONE => return None,
+    };
+    Some(match due.recurrency_interval {
+        interval if interval > 1 => format!("every {} {}s", interval, unit),
+        _ => format!("every {}", unit),
+    })
+}
+
+// ==================== 服务器响应 → 本地 ====================
+
+/// 在事务中写入一次响应：记录新 ID、移除已发送的命令、应用服务器上的变化
+async fn apply_response<C: ConnectionTrait>(
+    conn: &C,
+    source_id: &str,
+    queued: Vec<QueueModel>,
+    response: SyncResponse,
+    mut report: TodoistSyncReport,
+) -> Result<TodoistSyncReport, TodoError> {
+    let now = Utc::now().naive_utc();
+    report.full_sync |= response.full_sync;
+    let mut ids = TempIds::load(conn).await?;
+
+    // ---------- 命令结果 ----------
+    let mut new_ids = Vec::new();
+    for (temp_id, remote_id) in &response.temp_id_mapping {
+        if temp_id == remote_id || ids.local.contains_key(remote_id) {
+            continue;
+        }
+        // 对象类型取自命令类型：`item_add` → `item`
+        let object = queued
+            .iter()
+            .find(|row| row.temp_id.as_ref() == Some(temp_id))
+            .and_then(|row| row.query.as_deref())
+            .and_then(|query| query.split('_').next())
+            .map(str::to_string);
+        ids.insert(temp_id, remote_id);
+        new_ids.push(CurTempIdActiveModel::from(CurTempIdModel {
+            id: remote_id.clone(),
+            temp_id: Some(temp_id.clone()),
+            object,
+        }));
+    }
+    insert_batched(conn, new_ids).await?;
+
+    for row in &queued {
+        match response.command_error(&row.id) {
+            None => report.pushed += 1,
+            Some(error) => report.rejected.push(format!(
+                "{} {}：{}",
+                row.query.as_deref().unwrap_or_default(),
+                row.object_id.as_deref().unwrap_or_default(),
+                error
+            )),
+        }
+    }
+    if !queued.is_empty() {
+        queue::Entity::delete_many()
+            .filter(queue::Column::Id.is_in(queued.into_iter().map(|row| row.id)))
+            .exec(conn)
+            .await?;
+    }
+
+    // ---------- 服务器上的变化 ----------
+    let mut data = ImportData::default();
+    let mut import_report = ImportReport::default();
+    let mut deleted = Vec::new();
+    for (kind, objects) in [
+        ("projects", &response.projects),
+        ("sections", &response.sections),
+        ("labels", &response.labels),
+        ("items", &response.items),
+    ] {
+        for object in objects.iter().filter_map(Value::as_object) {
+            if flag(object, "is_deleted")
+                && let Some(id) = text(object, "id")
+            {
+                deleted.push((kind, ids.local_id(&id)));
+            }
+        }
+        // 已删除的对象在这里被跳过
+        data.read_json_objects(kind, objects, &mut import_report);
+    }
+    report.skipped.extend(import_report.skipped);
+
+    let util = Util::get_default();
+    for raw in data.projects.iter().filter(|raw| !raw.key.is_empty()) {
+        let id = ids.local_id(&raw.key);
+        let existing = projects::Entity::find_by_id(id.clone()).one(conn).await?;
+        let mut project =
+            existing.clone().unwrap_or_else(|| ProjectModel { id, ..Default::default() });
+        project.name = raw.name.clone();
+        project.color = raw
+            .color
+            .clone()
+            .map(|key| util.get_color(key))
+            .or(project.color)
+            .or_else(|| Some(util.get_color(util.get_random_color())));
+        project.parent_id = raw.parent.as_deref().map(|parent| ids.local_id(parent));
+        project.child_order = raw.order.or(project.child_order);
+        project.is_favorite = raw.is_favorite;
+        project.is_archived = raw.is_archived;
+        project.archived_at = raw.is_archived.then(|| project.archived_at.unwrap_or(now));
+        project.collapsed = raw.collapsed;
+        project.view_style = raw.view_style.clone().or(project.view_style);
+        project.inbox_project = Some(i32::from(raw.inbox));
+        project.backend_type = Some(TODOIST_SOURCE_TYPE.to_string());
+        project.source_id = Some(source_id.to_string());
+        project.is_deleted = false;
+        project.deleted_at = None;
+        if existing.is_some() {
+            ProjectActiveModel::from(project).reset_all().update(conn).await?;
+            report.pulled_updated += 1;
+        } else {
+            insert_batched(conn, vec![ProjectActiveModel::from(project)]).await?;
+            report.pulled_created += 1;
+        }
+    }
+
+    for raw in data.sections.iter().filter(|raw| !raw.key.is_empty()) {
+        let id = ids.local_id(&raw.key);
+        let existing = sections::Entity::find_by_id(id.clone()).one(conn).await?;
+        let mut section = existing.clone().unwrap_or_else(|| SectionModel {
+            id,
+            added_at: now,
+            ..Default::default()
+        });
+        section.name = raw.name.clone();
+        section.project_id = Some(ids.local_id(&raw.project));
+        section.section_order = raw.order.or(section.section_order);
+        section.collapsed = raw.collapsed;
+        section.archived_at = raw.is_archived.then(|| section.archived_at.unwrap_or(now));
+        section.is_archived = raw.is_archived;
+        section.is_deleted = false;
+        section.deleted_at = None;
+        if existing.is_some() {
+            SectionActiveModel::from(section).reset_all().update(conn).await?;
+            report.pulled_updated += 1;
+        } else {
+            insert_batched(conn, vec![SectionActiveModel::from(section)]).await?;
+            report.pulled_created += 1;
+        }
+    }
+
+    let local_labels = labels::Entity::find().all(conn).await?;
+    for raw in &data.labels {
+        let Some(key) = raw.key.as_deref() else {
+            continue;
+        };
+        let id = ids.local_id(key);
+        // 同名的本地标签直接对应到服务器上的标签
+        let existing = local_labels.iter().find(|label| label.id == id).or_else(|| {
+            local_labels.iter().find(|label| label.name.to_lowercase() == raw.name.to_lowercase())
+        });
+        if let Some(label) = existing
+            && label.id != id
+        {
+            ids.insert(&label.id, key);
+            insert_batched(conn, vec![CurTempIdActiveModel::from(CurTempIdModel {
+                id: key.to_string(),
+                temp_id: Some(label.id.clone()),
+                object: Some("label".to_string()),
+            })])
+            .await?;
+        }
+        let mut label = existing.cloned().unwrap_or_else(|| LabelModel {
+            id,
+            color: util.get_color(util.get_random_color()),
+            ..Default::default()
+        });
+        label.name = raw.name.clone();
+        if let Some(color) = raw.color.clone() {
+            label.color = util.get_color(color);
+        }
+        label.item_order = raw.order.unwrap_or(label.item_order);
+        label.is_favorite = raw.is_favorite;
+        label.backend_type = Some(TODOIST_SOURCE_TYPE.to_string());
+        label.source_id = Some(source_id.to_string());
+        label.is_deleted = false;
+        label.deleted_at = None;
+        if existing.is_some() {
+            LabelActiveModel::from(label).reset_all().update(conn).await?;
+            report.pulled_updated += 1;
+        } else {
+            insert_batched(conn, vec![LabelActiveModel::from(label)]).await?;
+            report.pulled_created += 1;
+        }
+    }
+
+    let names: Vec<RawLabel> = data
+        .items
+        .iter()
+        .flat_map(|raw| &raw.labels)
+        .map(|name| RawLabel { name: name.clone(), ..Default::default() })
+        .collect();
+    let (label_ids, _) = resolve_labels(conn, names).await?;
+    for raw in data.items.iter().filter(|raw| !raw.key.is_empty()) {
+        let id = ids.local_id(&raw.key);
+        let mut labels: Vec<String> = Vec::new();
+        for name in &raw.labels {
+            if let Some(label_id) = label_ids.get(&name.to_lowercase())
+                && !labels.contains(label_id)
+            {
+                labels.push(label_id.clone());
+            }
+        }
+
+        let existing = items::Entity::find_by_id(id.clone()).one(conn).await?;
+        let mut item = existing.clone().unwrap_or_else(|| ItemModel {
+            id,
+            added_at: raw.added_at.unwrap_or(now),
+            ..Default::default()
+        });
+        item.content = raw.content.clone();
+        item.description = raw.description.clone();
+        item.project_id = raw.project.as_deref().map(|project| ids.local_id(project));
+        item.section_id = raw.section.as_deref().map(|section| ids.local_id(section));
+        item.parent_id = raw.parent.as_deref().map(|parent| ids.local_id(parent));
+        item.priority = Some(raw.priority);
+        item.set_due_date(raw.due.clone());
+        item.completed_at =
+            raw.checked.then(|| raw.completed_at.or(item.completed_at).unwrap_or(now));
+        item.checked = raw.checked;
+        item.child_order = raw.order.or(item.child_order);
+        item.collapsed = raw.collapsed;
+        item.labels = (!labels.is_empty()).then(|| labels.join(";"));
+        item.is_deleted = false;
+        item.deleted_at = None;
+        item.updated_at = now;
+        let item_id = item.id.clone();
+        if existing.is_some() {
+            ItemActiveModel::from(item).reset_all().update(conn).await?;
+            report.pulled_updated += 1;
+        } else {
+            insert_batched(conn, vec![ItemActiveModel::from(item)]).await?;
+            report.pulled_created += 1;
+        }
+        replace_item_labels(conn, &item_id, &labels, now).await?;
+    }
+
+    // ---------- 服务器上删除的对象移入回收站 ----------
+    for (kind, id) in deleted {
+        let active = match kind {
+            "projects" => projects::Entity::find_by_id(id.clone())
+                .one(conn)
+                .await?
+                .is_some_and(|project| !project.is_deleted),
+            "sections" => sections::Entity::find_by_id(id.clone())
+                .one(conn)
+                .await?
+                .is_some_and(|section| !section.is_deleted),
+            "labels" => labels::Entity::find_by_id(id.clone())
+                .one(conn)
+                .await?
+                .is_some_and(|label| !label.is_deleted),
+            _ => items::Entity::find_by_id(id.clone())
+                .one(conn)
+                .await?
+                .is_some_and(|item| !item.is_deleted),
+        };
+        if !active {
+            continue;
+        }
+        match kind {
+            "projects" => TrashService::trash_project_in_conn(conn, &id, now).await?,
+            "sections" => TrashService::trash_section_in_conn(conn, &id, now).await?,
+            "labels" => {
+                TrashService::trash_label_in_conn(conn, &id, now).await?;
+            },
+            _ => TrashService::trash_item_in_conn(conn, &id, now).await?,
+        }
+        report.pulled_deleted += 1;
+    }
+
+    Ok(report)
+}
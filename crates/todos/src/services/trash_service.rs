@@ -0,0 +1,761 @@
+//! Trash service for soft-deleted objects
+//!
+//! Item / Section / Project / Label 删除时只标记 `is_deleted` 与 `deleted_at`。
+//! 同一次删除级联标记的子对象共享同一个 `deleted_at`，恢复时据此整体还原；
+//! 超过保留期的对象由 [`TrashService::purge_expired`] 彻底删除。
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait,
+    prelude::Expr, sea_query::SimpleExpr,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        ItemModel, LabelModel, ProjectModel, SectionModel, attachments, item_labels, items, labels,
+        projects, reminders, sections,
+    },
+    error::TodoError,
+    services::ItemService,
+};
+
+/// 回收站对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TrashObjectType {
+    Item,
+    Section,
+    Project,
+    Label,
+}
+
+impl TrashObjectType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Item => "item",
+            Self::Section => "section",
+            Self::Project => "project",
+            Self::Label => "label",
+        }
+    }
+}
+
+/// 回收站条目
+///
+/// 只列出删除操作的根对象，级联删除的子对象随根对象一起恢复 / 清理。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+    pub object_type: TrashObjectType,
+    pub id: String,
+    pub name: String,
+    pub deleted_at: Option<NaiveDateTime>,
+    /// 所属项目（Item / Section）
+    pub project_id: Option<String>,
+    /// 随根对象一起删除的任务数量
+    pub children_count: usize,
+}
+
+/// Service for trash bin operations
+#[derive(Clone, Debug)]
+pub struct TrashService {
+    db: Arc<DatabaseConnection>,
+}
+
+impl TrashService {
+    /// Create a new TrashService
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    // ==================== 移入回收站 ====================
+
+    /// 将任务及其所有子任务移入回收站
+    pub(crate) async fn trash_item_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        item_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let ids = ItemService::collect_descendant_ids_in_conn(conn, item_id).await?;
+        Self::mark_items_in_conn(conn, ids, now).await
+    }
+
+    /// 将分区及其中的任务移入回收站
+    pub(crate) async fn trash_section_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        section_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let item_ids = Self::collect_items_in_sections(conn, &[section_id.to_string()]).await?;
+        Self::mark_items_in_conn(conn, item_ids, now).await?;
+
+        sections::Entity::update_many()
+            .col_expr(sections::Column::IsDeleted, Expr::value(true))
+            .col_expr(sections::Column::DeletedAt, Expr::value(now))
+            .filter(sections::Column::Id.eq(section_id))
+            .filter(sections::Column::IsDeleted.eq(false))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 将项目（含子项目、分区和任务）移入回收站
+    pub(crate) async fn trash_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        project_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        let project_ids = Self::collect_project_tree(conn, project_id).await?;
+        let section_ids = Self::section_ids_of_projects(conn, &project_ids).await?;
+        let item_ids = Self::collect_items_in_projects(conn, &project_ids, &section_ids).await?;
+
+        Self::mark_items_in_conn(conn, item_ids, now).await?;
+
+        if !section_ids.is_empty() {
+            sections::Entity::update_many()
+                .col_expr(sections::Column::IsDeleted, Expr::value(true))
+                .col_expr(sections::Column::DeletedAt, Expr::value(now))
+                .filter(sections::Column::Id.is_in(section_ids))
+                .filter(sections::Column::IsDeleted.eq(false))
+                .exec(conn)
+                .await?;
+        }
+
+        projects::Entity::update_many()
+            .col_expr(projects::Column::IsDeleted, Expr::value(true))
+            .col_expr(projects::Column::DeletedAt, Expr::value(now))
+            .filter(projects::Column::Id.is_in(project_ids))
+            .filter(projects::Column::IsDeleted.eq(false))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 将标签移入回收站（item_labels 关联保留，恢复时原样生效）
+    pub(crate) async fn trash_label_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        label_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<u64, TodoError> {
+        let result = labels::Entity::update_many()
+            .col_expr(labels::Column::IsDeleted, Expr::value(true))
+            .col_expr(labels::Column::DeletedAt, Expr::value(now))
+            .filter(labels::Column::Id.eq(label_id))
+            .filter(labels::Column::IsDeleted.eq(false))
+            .exec(conn)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// 标记任务为已删除（已在回收站中的任务保留原删除时间）
+    async fn mark_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        ids: Vec<String>,
+        now: NaiveDateTime,
+    ) -> Result<(), TodoError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        items::Entity::update_many()
+            .col_expr(items::Column::IsDeleted, Expr::value(true))
+            .col_expr(items::Column::DeletedAt, Expr::value(now))
+            .filter(items::Column::Id.is_in(ids))
+            .filter(items::Column::IsDeleted.eq(false))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // ==================== 查询 ====================
+
+    /// 列出回收站中的根对象（按删除时间倒序）
+    pub async fn list_trash(&self) -> Result<Vec<TrashEntry>, TodoError> {
+        let conn = &*self.db;
+        let trashed_items: Vec<ItemModel> =
+            items::Entity::find().filter(items::Column::IsDeleted.eq(true)).all(conn).await?;
+        let trashed_sections: Vec<SectionModel> =
+            sections::Entity::find().filter(sections::Column::IsDeleted.eq(true)).all(conn).await?;
+        let trashed_projects: Vec<ProjectModel> =
+            projects::Entity::find().filter(projects::Column::IsDeleted.eq(true)).all(conn).await?;
+        let trashed_labels: Vec<LabelModel> =
+            labels::Entity::find().filter(labels::Column::IsDeleted.eq(true)).all(conn).await?;
+
+        let item_stamps: HashMap<&str, Option<NaiveDateTime>> =
+            trashed_items.iter().map(|i| (i.id.as_str(), i.deleted_at)).collect();
+        let section_stamps: HashMap<&str, Option<NaiveDateTime>> =
+            trashed_sections.iter().map(|s| (s.id.as_str(), s.deleted_at)).collect();
+        let project_stamps: HashMap<&str, Option<NaiveDateTime>> =
+            trashed_projects.iter().map(|p| (p.id.as_str(), p.deleted_at)).collect();
+
+        // 父对象与自身在同一次删除中进入回收站 → 不是根对象
+        let deleted_with = |stamps: &HashMap<&str, Option<NaiveDateTime>>,
+                            parent: Option<&String>,
+                            stamp: Option<NaiveDateTime>| {
+            parent.and_then(|id| stamps.get(id.as_str())).is_some_and(|s| *s == stamp)
+        };
+
+        let mut entries = Vec::new();
+
+        for project in &trashed_projects {
+            if deleted_with(&project_stamps, project.parent_id.as_ref(), project.deleted_at) {
+                continue;
+            }
+            let tree = Self::subtree(&trashed_projects, &project.id, |p| p.parent_id.as_ref());
+            let children_count = trashed_items
+                .iter()
+                .filter(|i| {
+                    i.deleted_at == project.deleted_at
+                        && i.project_id.as_ref().is_some_and(|pid| tree.contains(pid))
+                })
+                .count();
+            entries.push(TrashEntry {
+                object_type: TrashObjectType::Project,
+                id: project.id.clone(),
+                name: project.name.clone(),
+                deleted_at: project.deleted_at,
+                project_id: project.parent_id.clone(),
+                children_count,
+            });
+        }
+
+        for section in &trashed_sections {
+            if deleted_with(&project_stamps, section.project_id.as_ref(), section.deleted_at) {
+                continue;
+            }
+            let children_count = trashed_items
+                .iter()
+                .filter(|i| {
+                    i.deleted_at == section.deleted_at
+                        && i.section_id.as_deref() == Some(section.id.as_str())
+                })
+                .count();
+            entries.push(TrashEntry {
+                object_type: TrashObjectType::Section,
+                id: section.id.clone(),
+                name: section.name.clone(),
+                deleted_at: section.deleted_at,
+                project_id: section.project_id.clone(),
+                children_count,
+            });
+        }
+
+        for item in &trashed_items {
+            if deleted_with(&item_stamps, item.parent_id.as_ref(), item.deleted_at)
+                || deleted_with(&section_stamps, item.section_id.as_ref(), item.deleted_at)
+                || deleted_with(&project_stamps, item.project_id.as_ref(), item.deleted_at)
+            {
+                continue;
+            }
+            let tree = Self::subtree(&trashed_items, &item.id, |i| i.parent_id.as_ref());
+            entries.push(TrashEntry {
+                object_type: TrashObjectType::Item,
+                id: item.id.clone(),
+                name: item.content.clone(),
+                deleted_at: item.deleted_at,
+                project_id: item.project_id.clone(),
+                children_count: tree.len() - 1,
+            });
+        }
+
+        for label in &trashed_labels {
+            entries.push(TrashEntry {
+                object_type: TrashObjectType::Label,
+                id: label.id.clone(),
+                name: label.name.clone(),
+                deleted_at: label.deleted_at,
+                project_id: None,
+                children_count: 0,
+            });
+        }
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+        Ok(entries)
+    }
+
+    /// 在内存中收集以 root_id 为根的子树 ID（含自身）
+    fn subtree<T>(
+        rows: &[T],
+        root_id: &str,
+        parent_of: impl Fn(&T) -> Option<&String>,
+    ) -> HashSet<String>
+    where
+        T: HasId,
+    {
+        let mut tree: HashSet<String> = HashSet::from([root_id.to_string()]);
+        let mut frontier = vec![root_id.to_string()];
+        while let Some(parent_id) = frontier.pop() {
+            for row in rows {
+                if parent_of(row) == Some(&parent_id) && tree.insert(row.id().to_string()) {
+                    frontier.push(row.id().to_string());
+                }
+            }
+        }
+        tree
+    }
+
+    // ==================== 恢复 ====================
+
+    /// 从回收站恢复对象，以及与它同一次删除的所有子对象（单事务）
+    pub async fn restore(&self, object_type: TrashObjectType, id: &str) -> Result<(), TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    match object_type {
+                        TrashObjectType::Item => Self::restore_item_in_conn(txn, &id).await,
+                        TrashObjectType::Section => Self::restore_section_in_conn(txn, &id).await,
+                        TrashObjectType::Project => Self::restore_project_in_conn(txn, &id).await,
+                        TrashObjectType::Label => Self::restore_label_in_conn(txn, &id).await,
+                    }
+                })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    async fn restore_item_in_conn<C: ConnectionTrait>(conn: &C, id: &str) -> Result<(), TodoError> {
+        let item = items::Entity::find_by_id(id)
+            .one(conn)
+            .await?
+            .filter(|i| i.is_deleted)
+            .ok_or_else(|| TodoError::not_found("Trashed item").with_entity("Item", id))?;
+
+        let ids = ItemService::collect_descendant_ids_in_conn(conn, id).await?;
+        // 与根任务同一次删除的子树才会被恢复
+        let restored: Vec<ItemModel> = items::Entity::find()
+            .filter(items::Column::Id.is_in(ids.clone()))
+            .filter(items::Column::IsDeleted.eq(true))
+            .filter(Self::same_stamp(items::Column::DeletedAt, item.deleted_at))
+            .all(conn)
+            .await?;
+        Self::unmark_items_in_conn(conn, ids, item.deleted_at).await?;
+
+        // 父任务仍在回收站中时，将根任务挂回顶层，避免恢复后不可见
+        if let Some(parent_id) = &item.parent_id
+            && Self::is_item_trashed(conn, parent_id).await?
+        {
+            items::Entity::update_many()
+                .col_expr(items::Column::ParentId, Expr::value(None::<String>))
+                .filter(items::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+
+        // 所属项目或分区仍在回收站中时，整棵恢复的子树都要移出，否则子任务依旧不可见
+        let restored_ids: Vec<String> = restored.iter().map(|i| i.id.clone()).collect();
+        let section_ids: HashSet<String> =
+            restored.iter().filter_map(|i| i.section_id.clone()).collect();
+        let trashed_sections: Vec<String> = sections::Entity::find()
+            .filter(sections::Column::Id.is_in(section_ids))
+            .filter(sections::Column::IsDeleted.eq(true))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        if !trashed_sections.is_empty() {
+            items::Entity::update_many()
+                .col_expr(items::Column::SectionId, Expr::value(None::<String>))
+                .filter(items::Column::Id.is_in(restored_ids.clone()))
+                .filter(items::Column::SectionId.is_in(trashed_sections))
+                .exec(conn)
+                .await?;
+        }
+
+        let project_ids: HashSet<String> =
+            restored.iter().filter_map(|i| i.project_id.clone()).collect();
+        let trashed_projects: Vec<String> = projects::Entity::find()
+            .filter(projects::Column::Id.is_in(project_ids))
+            .filter(projects::Column::IsDeleted.eq(true))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        if !trashed_projects.is_empty() {
+            items::Entity::update_many()
+                .col_expr(items::Column::ProjectId, Expr::value(None::<String>))
+                .col_expr(items::Column::SectionId, Expr::value(None::<String>))
+                .filter(items::Column::Id.is_in(restored_ids))
+                .filter(items::Column::ProjectId.is_in(trashed_projects))
+                .exec(conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn restore_section_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        let section =
+            sections::Entity::find_by_id(id).one(conn).await?.filter(|s| s.is_deleted).ok_or_else(
+                || TodoError::not_found("Trashed section").with_entity("Section", id),
+            )?;
+
+        if let Some(project_id) = &section.project_id
+            && Self::is_project_trashed(conn, project_id).await?
+        {
+            return Err(TodoError::validation("所属项目仍在回收站中，请先恢复项目"));
+        }
+
+        let item_ids = Self::collect_items_in_sections(conn, &[id.to_string()]).await?;
+        Self::unmark_items_in_conn(conn, item_ids, section.deleted_at).await?;
+
+        sections::Entity::update_many()
+            .col_expr(sections::Column::IsDeleted, Expr::value(false))
+            .col_expr(sections::Column::DeletedAt, Expr::value(None::<NaiveDateTime>))
+            .filter(sections::Column::Id.eq(id))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn restore_project_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        let project =
+            projects::Entity::find_by_id(id).one(conn).await?.filter(|p| p.is_deleted).ok_or_else(
+                || TodoError::not_found("Trashed project").with_entity("Project", id),
+            )?;
+        let stamp = project.deleted_at;
+
+        let project_ids = Self::collect_project_tree(conn, id).await?;
+        let section_ids = Self::section_ids_of_projects(conn, &project_ids).await?;
+        let item_ids = Self::collect_items_in_projects(conn, &project_ids, &section_ids).await?;
+
+        Self::unmark_items_in_conn(conn, item_ids, stamp).await?;
+
+        if !section_ids.is_empty() {
+            sections::Entity::update_many()
+                .col_expr(sections::Column::IsDeleted, Expr::value(false))
+                .col_expr(sections::Column::DeletedAt, Expr::value(None::<NaiveDateTime>))
+                .filter(sections::Column::Id.is_in(section_ids))
+                .filter(sections::Column::IsDeleted.eq(true))
+                .filter(Self::same_stamp(sections::Column::DeletedAt, stamp))
+                .exec(conn)
+                .await?;
+        }
+
+        projects::Entity::update_many()
+            .col_expr(projects::Column::IsDeleted, Expr::value(false))
+            .col_expr(projects::Column::DeletedAt, Expr::value(None::<NaiveDateTime>))
+            .filter(projects::Column::Id.is_in(project_ids))
+            .filter(projects::Column::IsDeleted.eq(true))
+            .filter(Self::same_stamp(projects::Column::DeletedAt, stamp))
+            .exec(conn)
+            .await?;
+
+        if let Some(parent_id) = &project.parent_id
+            && Self::is_project_trashed(conn, parent_id).await?
+        {
+            projects::Entity::update_many()
+                .col_expr(projects::Column::ParentId, Expr::value(None::<String>))
+                .filter(projects::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn restore_label_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        let result = labels::Entity::update_many()
+            .col_expr(labels::Column::IsDeleted, Expr::value(false))
+            .col_expr(labels::Column::DeletedAt, Expr::value(None::<NaiveDateTime>))
+            .filter(labels::Column::Id.eq(id))
+            .filter(labels::Column::IsDeleted.eq(true))
+            .exec(conn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(TodoError::not_found("Trashed label").with_entity("Label", id));
+        }
+        Ok(())
+    }
+
+    /// 恢复与根对象同一次删除的任务
+    async fn unmark_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        ids: Vec<String>,
+        stamp: Option<NaiveDateTime>,
+    ) -> Result<(), TodoError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        items::Entity::update_many()
+            .col_expr(items::Column::IsDeleted, Expr::value(false))
+            .col_expr(items::Column::DeletedAt, Expr::value(None::<NaiveDateTime>))
+            .filter(items::Column::Id.is_in(ids))
+            .filter(items::Column::IsDeleted.eq(true))
+            .filter(Self::same_stamp(items::Column::DeletedAt, stamp))
+            .exec(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    fn same_stamp(column: impl ColumnTrait, stamp: Option<NaiveDateTime>) -> SimpleExpr {
+        match stamp {
+            Some(stamp) => column.eq(stamp),
+            None => column.is_null(),
+        }
+    }
+
+    // ==================== 彻底删除 ====================
+
+    /// 彻底删除回收站中的对象及其所有子对象（单事务）
+    pub async fn purge(&self, object_type: TrashObjectType, id: &str) -> Result<(), TodoError> {
+        let id = id.to_string();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move { Self::purge_in_conn(txn, object_type, &id).await })
+            })
+            .await
+            .map_err(TodoError::from)
+    }
+
+    /// 清空回收站，返回清理的根对象数量
+    pub async fn empty_trash(&self) -> Result<usize, TodoError> {
+        let entries = self.list_trash().await?;
+        self.purge_entries(entries).await
+    }
+
+    /// 清理超过保留期的对象，`retention_days == 0` 表示永久保留
+    ///
+    /// 迁移 002 之前删除的对象没有 `deleted_at`，无法判断删除时间，不自动清理，只能手动清空。
+    pub async fn purge_expired(&self, retention_days: u32) -> Result<usize, TodoError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        let cutoff =
+            chrono::Utc::now().naive_utc() - chrono::Duration::days(i64::from(retention_days));
+        let expired: Vec<TrashEntry> = self
+            .list_trash()
+            .await?
+            .into_iter()
+            .filter(|entry| entry.deleted_at.is_some_and(|at| at < cutoff))
+            .collect();
+
+        let purged = self.purge_entries(expired).await?;
+        if purged > 0 {
+            tracing::info!("🗑️ 回收站自动清理 {} 个超过 {} 天的对象", purged, retention_days);
+        }
+        Ok(purged)
+    }
+
+    async fn purge_entries(&self, entries: Vec<TrashEntry>) -> Result<usize, TodoError> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let count = entries.len();
+        self.db
+            .transaction::<_, (), TodoError>(|txn| {
+                Box::pin(async move {
+                    for entry in entries {
+                        Self::purge_in_conn(txn, entry.object_type, &entry.id).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .await?;
+
+        Ok(count)
+    }
+
+    pub(crate) async fn purge_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        object_type: TrashObjectType,
+        id: &str,
+    ) -> Result<(), TodoError> {
+        match object_type {
+            TrashObjectType::Item => {
+                let ids = ItemService::collect_descendant_ids_in_conn(conn, id).await?;
+                Self::purge_items_in_conn(conn, ids).await?;
+            },
+            TrashObjectType::Section => {
+                let ids = Self::collect_items_in_sections(conn, &[id.to_string()]).await?;
+                Self::purge_items_in_conn(conn, ids).await?;
+                sections::Entity::delete_by_id(id).exec(conn).await?;
+            },
+            TrashObjectType::Project => {
+                let project_ids = Self::collect_project_tree(conn, id).await?;
+                let section_ids = Self::section_ids_of_projects(conn, &project_ids).await?;
+                let ids = Self::collect_items_in_projects(conn, &project_ids, &section_ids).await?;
+                Self::purge_items_in_conn(conn, ids).await?;
+                if !section_ids.is_empty() {
+                    sections::Entity::delete_many()
+                        .filter(sections::Column::Id.is_in(section_ids))
+                        .exec(conn)
+                        .await?;
+                }
+                projects::Entity::delete_many()
+                    .filter(projects::Column::Id.is_in(project_ids))
+                    .exec(conn)
+                    .await?;
+            },
+            TrashObjectType::Label => {
+                item_labels::Entity::delete_many()
+                    .filter(item_labels::Column::LabelId.eq(id))
+                    .exec(conn)
+                    .await?;
+                labels::Entity::delete_by_id(id).exec(conn).await?;
+            },
+        }
+        Ok(())
+    }
+
+    /// 硬删除任务及其关联数据（不依赖连接上是否开启了外键级联）
+    async fn purge_items_in_conn<C: ConnectionTrait>(
+        conn: &C,
+        ids: Vec<String>,
+    ) -> Result<(), TodoError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        item_labels::Entity::delete_many()
+            .filter(item_labels::Column::ItemId.is_in(ids.clone()))
+            .exec(conn)
+            .await?;
+        reminders::Entity::delete_many()
+            .filter(reminders::Column::ItemId.is_in(ids.clone()))
+            .exec(conn)
+            .await?;
+        attachments::Entity::delete_many()
+            .filter(attachments::Column::ItemId.is_in(ids.clone()))
+            .exec(conn)
+            .await?;
+        items::Entity::delete_many().filter(items::Column::Id.is_in(ids)).exec(conn).await?;
+
+        Ok(())
+    }
+
+    // ==================== 树收集辅助方法 ====================
+
+    /// 收集项目及其所有子项目 ID
+    pub(crate) async fn collect_project_tree<C: ConnectionTrait>(
+        conn: &C,
+        root_id: &str,
+    ) -> Result<Vec<String>, TodoError> {
+        let mut project_ids = vec![root_id.to_string()];
+        let mut idx = 0;
+        while idx < project_ids.len() {
+            let current_id = project_ids[idx].clone();
+            idx += 1;
+            let subprojects = projects::Entity::find()
+                .filter(projects::Column::ParentId.eq(&current_id))
+                .all(conn)
+                .await?;
+            for project in subprojects {
+                if !project_ids.contains(&project.id) {
+                    project_ids.push(project.id);
+                }
+            }
+        }
+        Ok(project_ids)
+    }
+
+    async fn section_ids_of_projects<C: ConnectionTrait>(
+        conn: &C,
+        project_ids: &[String],
+    ) -> Result<Vec<String>, TodoError> {
+        Ok(sections::Entity::find()
+            .filter(sections::Column::ProjectId.is_in(project_ids.to_vec()))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect())
+    }
+
+    /// 收集分区中的任务及其子任务 ID
+    async fn collect_items_in_sections<C: ConnectionTrait>(
+        conn: &C,
+        section_ids: &[String],
+    ) -> Result<Vec<String>, TodoError> {
+        let roots = items::Entity::find()
+            .filter(items::Column::SectionId.is_in(section_ids.to_vec()))
+            .all(conn)
+            .await?;
+        Self::collect_item_trees(conn, roots).await
+    }
+
+    /// 收集项目（及其分区）中的任务及其子任务 ID
+    async fn collect_items_in_projects<C: ConnectionTrait>(
+        conn: &C,
+        project_ids: &[String],
+        section_ids: &[String],
+    ) -> Result<Vec<String>, TodoError> {
+        let mut roots = items::Entity::find()
+            .filter(items::Column::ProjectId.is_in(project_ids.to_vec()))
+            .all(conn)
+            .await?;
+        if !section_ids.is_empty() {
+            roots.extend(
+                items::Entity::find()
+                    .filter(items::Column::SectionId.is_in(section_ids.to_vec()))
+                    .all(conn)
+                    .await?,
+            );
+        }
+        Self::collect_item_trees(conn, roots).await
+    }
+
+    async fn collect_item_trees<C: ConnectionTrait>(
+        conn: &C,
+        roots: Vec<ItemModel>,
+    ) -> Result<Vec<String>, TodoError> {
+        let mut ids: HashSet<String> = HashSet::new();
+        for item in roots {
+            if ids.contains(&item.id) {
+                continue;
+            }
+            ids.extend(ItemService::collect_descendant_ids_in_conn(conn, &item.id).await?);
+        }
+        Ok(ids.into_iter().collect())
+    }
+
+    async fn is_item_trashed<C: ConnectionTrait>(conn: &C, id: &str) -> Result<bool, TodoError> {
+        Ok(items::Entity::find_by_id(id).one(conn).await?.is_some_and(|i| i.is_deleted))
+    }
+
+    async fn is_project_trashed<C: ConnectionTrait>(conn: &C, id: &str) -> Result<bool, TodoError> {
+        Ok(projects::Entity::find_by_id(id).one(conn).await?.is_some_and(|p| p.is_deleted))
+    }
+}
+
+/// 用于内存子树遍历的 ID 访问
+trait HasId {
+    fn id(&self) -> &str;
+}
+
+impl HasId for ItemModel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl HasId for ProjectModel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,172 @@
+//! 服务之间共用的写入辅助函数
+//!
+//! 导入、同步、复制和模板都需要按预先分配的 ID 批量插入，以及按名称解析标签，放在这里
+//! 避免各个服务互相依赖。
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    prelude::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    entity::{
+        ItemLabelActiveModel, ItemLabelModel, LabelActiveModel, LabelModel, item_labels, items,
+        labels,
+    },
+    error::TodoError,
+    utils::Util,
+};
+
+/// SQLite 单条语句的变量数有上限，按批插入
+const INSERT_BATCH: usize = 200;
+
+/// 待解析的标签（导入、同步、模板共用）；`key` 是来源中的标签 ID，按名称匹配时为空
+#[derive(Debug, Default)]
+pub(super) struct RawLabel {
+    pub(super) key: Option<String>,
+    pub(super) name: String,
+    pub(super) color: Option<String>,
+    pub(super) order: Option<i32>,
+    pub(super) is_favorite: bool,
+}
+
+/// 按名称（不区分大小写）查找标签，没有的新建，在回收站中的取回（名称唯一，见 [`revive_labels`]）
+///
+/// 返回小写名称到标签 ID 的映射（包含所有已有标签）和新建的数量。
+pub(super) async fn resolve_labels<C: ConnectionTrait>(
+    conn: &C,
+    raw_labels: Vec<RawLabel>,
+) -> Result<(HashMap<String, String>, usize), TodoError> {
+    let util = Util::get_default();
+    let existing = labels::Entity::find().all(conn).await?;
+    let mut label_ids: HashMap<String, String> = existing
+        .iter()
+        .filter(|label| !label.is_deleted)
+        .map(|label| (label.name.to_lowercase(), label.id.clone()))
+        .collect();
+    let mut restored_labels = Vec::new();
+    let mut new_labels = Vec::new();
+
+    for label in raw_labels {
+        let lower = label.name.to_lowercase();
+        if label_ids.contains_key(&lower) {
+            continue;
+        }
+        if let Some(deleted) =
+            existing.iter().find(|l| l.is_deleted && l.name.to_lowercase() == lower)
+        {
+            restored_labels.push(deleted.id.clone());
+            label_ids.insert(lower, deleted.id.clone());
+            continue;
+        }
+        let id = Uuid::new_v4().to_string();
+        label_ids.insert(lower, id.clone());
+        new_labels.push(LabelActiveModel::from(LabelModel {
+            id,
+            name: label.name,
+            color: util.get_color(label.color.unwrap_or_else(|| util.get_random_color())),
+            item_order: label.order.unwrap_or(new_labels.len() as i32),
+            is_favorite: label.is_favorite,
+            ..Default::default()
+        }));
+    }
+
+    let created = new_labels.len();
+    insert_batched(conn, new_labels).await?;
+    revive_labels(conn, restored_labels).await?;
+    Ok((label_ids, created))
+}
+
+/// 取回回收站中的标签：只恢复标签本身，丢弃它在回收站里保留的任务关联
+///
+/// 新建与回收站中同名的标签时使用（名称唯一，不能另建一个）。标签从回收站消失，
+/// 删除前打过这个标签的任务不会重新带上它。
+pub(super) async fn revive_labels<C: ConnectionTrait>(
+    conn: &C,
+    label_ids: Vec<String>,
+) -> Result<(), TodoError> {
+    if label_ids.is_empty() {
+        return Ok(());
+    }
+    let stale = item_labels::Entity::find()
+        .filter(item_labels::Column::LabelId.is_in(label_ids.clone()))
+        .all(conn)
+        .await?;
+    item_labels::Entity::delete_many()
+        .filter(item_labels::Column::LabelId.is_in(label_ids.clone()))
+        .exec(conn)
+        .await?;
+    // 同步旧的 labels 字段，避免与关联表不一致
+    let item_ids: Vec<String> = stale.into_iter().map(|link| link.item_id).collect();
+    for item in items::Entity::find().filter(items::Column::Id.is_in(item_ids)).all(conn).await? {
+        let kept: Vec<&str> = item
+            .labels
+            .as_deref()
+            .unwrap_or_default()
+            .split(';')
+            .filter(|id| !id.is_empty() && !label_ids.iter().any(|revived| revived == id))
+            .collect();
+        items::Entity::update_many()
+            .col_expr(
+                items::Column::Labels,
+                Expr::value((!kept.is_empty()).then(|| kept.join(";"))),
+            )
+            .filter(items::Column::Id.eq(item.id))
+            .exec(conn)
+            .await?;
+    }
+    labels::Entity::update_many()
+        .col_expr(labels::Column::IsDeleted, Expr::value(false))
+        .col_expr(labels::Column::DeletedAt, Expr::value(Option::<NaiveDateTime>::None))
+        .filter(labels::Column::Id.is_in(label_ids))
+        .filter(labels::Column::IsDeleted.eq(true))
+        .exec(conn)
+        .await?;
+    Ok(())
+}
+
+/// 以给定的标签整体替换任务的 `item_labels`（同步时以服务器为准）
+pub(super) async fn replace_item_labels<C: ConnectionTrait>(
+    conn: &C,
+    item_id: &str,
+    label_ids: &[String],
+    now: NaiveDateTime,
+) -> Result<(), TodoError> {
+    item_labels::Entity::delete_many()
+        .filter(item_labels::Column::ItemId.eq(item_id))
+        .exec(conn)
+        .await?;
+    insert_batched(
+        conn,
+        label_ids
+            .iter()
+            .map(|label_id| {
+                ItemLabelActiveModel::from(ItemLabelModel {
+                    item_id: item_id.to_string(),
+                    label_id: label_id.clone(),
+                    created_at: now,
+                })
+            })
+            .collect(),
+    )
+    .await
+}
+
+/// 分批插入；ID 已预先分配，不走 `ActiveModelBehavior::before_save`
+pub(super) async fn insert_batched<C, A>(conn: &C, models: Vec<A>) -> Result<(), TodoError>
+where
+    C: ConnectionTrait,
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    let mut models = models.into_iter().peekable();
+    while models.peek().is_some() {
+        let batch: Vec<A> = models.by_ref().take(INSERT_BATCH).collect();
+        A::Entity::insert_many(batch).exec_without_returning(conn).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,299 @@
+//! CalDAV (RFC 4791) 客户端
+//!
+//! 只实现同步需要的几个请求：
+//! - `PROPFIND`（Depth: 0）读取日历集合的 `getctag`，没有变化时跳过整个列表
+//! - `REPORT calendar-query` 列出所有 `VTODO` 资源的 `ETag`
+//! - `REPORT calendar-multiget` 批量读取有变化的资源
+//! - 带 `If-Match` / `If-None-Match` 的 `PUT` 和 `DELETE`，避免覆盖服务器上的新修改
+
+use std::sync::Arc;
+
+use reqwest::Url;
+
+use super::{HttpRequest, HttpResponse, HttpTransport};
+use crate::error::TodoError;
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+
+/// 服务器上的一个日历资源（一个 `.ics` 文件）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalDavResource {
+    /// 服务器返回的路径，如 `/calendars/me/tasks/1234.ics`
+    pub href: String,
+    pub etag: Option<String>,
+    /// `calendar-data`，只有 multiget 的结果中有
+    pub data: Option<String>,
+}
+
+/// 条件写入的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteResult {
+    /// 写入成功，附带服务器返回的新 `ETag`（有些服务器不返回）
+    Written(Option<String>),
+    /// 412：服务器上的资源在上次同步后被修改（或已存在）
+    PreconditionFailed,
+}
+
+/// 一个日历集合的 CalDAV 客户端
+#[derive(Clone)]
+pub struct CalDavClient {
+    transport: Arc<dyn HttpTransport>,
+    calendar_url: Url,
+}
+
+impl CalDavClient {
+    pub fn new(transport: Arc<dyn HttpTransport>, calendar_url: &str) -> Result<Self, TodoError> {
+        Ok(Self { transport, calendar_url: parse_calendar_url(calendar_url)? })
+    }
+
+    /// 检查并规范化日历集合地址（以 `/` 结尾）
+    pub fn normalize_url(calendar_url: &str) -> Result<String, TodoError> {
+        parse_calendar_url(calendar_url).map(String::from)
+    }
+
+    pub fn calendar_url(&self) -> &str {
+        self.calendar_url.as_str()
+    }
+
+    /// 集合中新资源的路径
+    pub fn resource_href(&self, uid: &str) -> String {
+        let name: String = uid
+            .chars()
+            .map(
+                |c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' },
+            )
+            .collect();
+        format!("{}{}.ics", self.calendar_url.path(), name)
+    }
+
+    /// 日历集合的 `getctag`（集合中任何资源变化时都会改变），服务器不支持时为 `None`
+    pub async fn ctag(&self) -> Result<Option<String>, TodoError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="{DAV}" xmlns:cs="{CALENDARSERVER}"><d:prop><cs:getctag/></d:prop></d:propfind>"#
+        );
+        let request = self.request("PROPFIND", self.calendar_url.as_str(), "0", body);
+        let responses = self.multistatus(request).await?;
+        Ok(responses.into_iter().find_map(|response| response.ctag))
+    }
+
+    /// 列出集合中所有 `VTODO` 资源及其 `ETag`
+    pub async fn list(&self) -> Result<Vec<CalDavResource>, TodoError> {
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><c:calendar-query xmlns:d="{DAV}" xmlns:c="{CALDAV}"><d:prop><d:getetag/></d:prop><c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter></c:calendar-query>"#
+        );
+        let request = self.request("REPORT", self.calendar_url.as_str(), "1", body);
+        let collection = self.calendar_url.path();
+        Ok(self
+            .multistatus(request)
+            .await?
+            .into_iter()
+            .filter(|response| response.href != collection)
+            .map(DavResponse::into_resource)
+            .collect())
+    }
+
+    /// 批量读取资源内容
+    pub async fn multiget(&self, hrefs: &[String]) -> Result<Vec<CalDavResource>, TodoError> {
+        if hrefs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let hrefs: String =
+            hrefs.iter().map(|href| format!("<d:href>{}</d:href>", escape_xml(href))).collect();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><c:calendar-multiget xmlns:d="{DAV}" xmlns:c="{CALDAV}"><d:prop><d:getetag/><c:calendar-data/></d:prop>{hrefs}</c:calendar-multiget>"#
+        );
+        let request = self.request("REPORT", self.calendar_url.as_str(), "1", body);
+        Ok(self
+            .multistatus(request)
+            .await?
+            .into_iter()
+            .filter(|response| response.data.is_some())
+            .map(DavResponse::into_resource)
+            .collect())
+    }
+
+    /// 写入资源：`etag` 为 `None` 时只在资源不存在时创建，否则只在 `ETag` 未变时覆盖
+    pub async fn put(
+        &self,
+        href: &str,
+        ics: String,
+        etag: Option<&str>,
+    ) -> Result<WriteResult, TodoError> {
+        let url = self.resolve(href)?;
+        let request = HttpRequest::new("PUT", url.as_str())
+            .with_header("Content-Type", "text/calendar; charset=utf-8")
+            .with_header(
+                if etag.is_some() { "If-Match" } else { "If-None-Match" },
+                etag.unwrap_or("*"),
+            )
+            .with_body(ics);
+        let response = self.transport.send(request).await?;
+        match response.status {
+            412 => Ok(WriteResult::PreconditionFailed),
+            _ if response.is_success() => {
+                Ok(WriteResult::Written(response.header("ETag").map(str::to_string)))
+            },
+            _ => Err(status_error("PUT", url.as_str(), &response)),
+        }
+    }
+
+    /// 删除资源；已经不存在（404）也视为成功
+    pub async fn delete(&self, href: &str, etag: Option<&str>) -> Result<WriteResult, TodoError> {
+        let url = self.resolve(href)?;
+        let mut request = HttpRequest::new("DELETE", url.as_str());
+        if let Some(etag) = etag {
+            request = request.with_header("If-Match", etag);
+        }
+        let response = self.transport.send(request).await?;
+        match response.status {
+            412 => Ok(WriteResult::PreconditionFailed),
+            404 => Ok(WriteResult::Written(None)),
+            _ if response.is_success() => Ok(WriteResult::Written(None)),
+            _ => Err(status_error("DELETE", url.as_str(), &response)),
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Result<Url, TodoError> {
+        self.calendar_url
+            .join(href)
+            .map_err(|e| TodoError::validation(format!("无效的资源路径「{}」: {}", href, e)))
+    }
+
+    fn request(&self, method: &str, url: &str, depth: &str, body: String) -> HttpRequest {
+        HttpRequest::new(method, url)
+            .with_header("Depth", depth)
+            .with_header("Content-Type", "application/xml; charset=utf-8")
+            .with_body(body)
+    }
+
+    async fn multistatus(&self, request: HttpRequest) -> Result<Vec<DavResponse>, TodoError> {
+        let (method, url) = (request.method.clone(), request.url.clone());
+        let response = self.transport.send(request).await?;
+        if response.status != 207 {
+            return Err(status_error(&method, &url, &response));
+        }
+        parse_multistatus(&response.body)
+    }
+}
+
+fn parse_calendar_url(calendar_url: &str) -> Result<Url, TodoError> {
+    let mut url = Url::parse(calendar_url.trim())
+        .map_err(|e| TodoError::validation(format!("无效的日历地址「{}」: {}", calendar_url, e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(TodoError::validation(format!("日历地址必须是 HTTP(S): {}", calendar_url)));
+    }
+    // 集合地址以 `/` 结尾，资源路径才能相对它解析
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    Ok(url)
+}
+
+fn status_error(method: &str, url: &str, response: &HttpResponse) -> TodoError {
+    let reason = match response.status {
+        401 | 403 => "认证失败，请检查用户名和密码",
+        404 => "日历不存在",
+        _ => "服务器返回错误",
+    };
+    TodoError::network(format!("{} {} 返回 {}：{}", method, url, response.status, reason))
+}
+
+// ==================== multistatus ====================
+
+/// `<d:response>` 中成功（200）的属性
+#[derive(Debug, Default)]
+struct DavResponse {
+    href: String,
+    etag: Option<String>,
+    ctag: Option<String>,
+    data: Option<String>,
+}
+
+impl DavResponse {
+    fn into_resource(self) -> CalDavResource {
+        CalDavResource { href: self.href, etag: self.etag, data: self.data }
+    }
+}
+
+fn parse_multistatus(body: &str) -> Result<Vec<DavResponse>, TodoError> {
+    let document = roxmltree::Document::parse(body)
+        .map_err(|e| TodoError::network(format!("无法解析服务器的 XML 响应: {}", e)))?;
+    let is = |node: &roxmltree::Node, namespace: &str, name: &str| {
+        node.is_element()
+            && node.tag_name().namespace() == Some(namespace)
+            && node.tag_name().name() == name
+    };
+    let child_text = |node: roxmltree::Node, namespace: &str, name: &str| {
+        node.children().find(|child| is(child, namespace, name)).map(|child| {
+            child.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).collect::<String>()
+        })
+    };
+
+    let mut responses = Vec::new();
+    for node in document.descendants().filter(|node| is(node, DAV, "response")) {
+        let Some(href) = child_text(node, DAV, "href") else {
+            continue;
+        };
+        let mut response = DavResponse { href: href.trim().to_string(), ..Default::default() };
+        for propstat in node.children().filter(|child| is(child, DAV, "propstat")) {
+            let ok =
+                child_text(propstat, DAV, "status").is_some_and(|status| status.contains(" 200"));
+            let prop = propstat.children().find(|child| is(child, DAV, "prop"));
+            let Some(prop) = prop.filter(|_| ok) else {
+                continue;
+            };
+            let value = |namespace: &str, name: &str| {
+                child_text(prop, namespace, name).filter(|value| !value.trim().is_empty())
+            };
+            response.etag =
+                response.etag.or_else(|| value(DAV, "getetag").map(|v| v.trim().to_string()));
+            response.ctag = response
+                .ctag
+                .or_else(|| value(CALENDARSERVER, "getctag").map(|v| v.trim().to_string()));
+            response.data = response.data.or_else(|| value(CALDAV, "calendar-data"));
+        }
+        responses.push(response);
+    }
+    Ok(responses)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let body = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/cal/tasks/a.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>"1"</d:getetag><cal:calendar-data>BEGIN:VCALENDAR&#13;
+END:VCALENDAR</cal:calendar-data></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/cal/tasks/b.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+        let responses = parse_multistatus(body).unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].href, "/cal/tasks/a.ics");
+        assert_eq!(responses[0].etag.as_deref(), Some("\"1\""));
+        assert_eq!(responses[0].data.as_deref(), Some("BEGIN:VCALENDAR\r\nEND:VCALENDAR"));
+        assert_eq!((responses[1].etag.as_ref(), responses[1].data.as_ref()), (None, None));
+        assert!(parse_multistatus("not xml").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! 远程同步的传输层
+//!
+//! 同步引擎只通过 [`HttpTransport`] 发送请求：应用中使用 [`ReqwestTransport`]，
+//! 测试中换成进程内的替身服务器，不需要真实的网络。
+
+pub mod caldav;
+pub mod todoist;
+
+use std::fmt;
+
+use async_trait::async_trait;
+pub use caldav::{CalDavClient, CalDavResource, WriteResult};
+pub use todoist::{SyncCommand, SyncResponse, TodoistClient};
+
+use crate::error::TodoError;
+
+/// 一个 HTTP 请求（WebDAV 方法如 `PROPFIND`、`REPORT` 也用字符串表示）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: impl Into<String>, url: impl Into<String>) -> Self {
+        Self { method: method.into(), url: url.into(), headers: Vec::new(), body: None }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// 请求头的值（名称不区分大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// HTTP 响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self { status, headers: Vec::new(), body: body.into() }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 响应头的值（名称不区分大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+/// 发送 HTTP 请求；只有连接层面的失败返回错误，非 2xx 状态码由调用方判断
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TodoError>;
+}
+
+/// 基于 reqwest 的传输，使用 HTTP Basic 认证
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    username: String,
+    password: String,
+}
+
+impl ReqwestTransport {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl fmt::Debug for ReqwestTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReqwestTransport").field("username", &self.username).finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TodoError> {
+        let method = reqwest::Method::from_bytes(request.method.as_bytes())
+            .map_err(|e| TodoError::network(format!("无效的请求方法 {}: {}", request.method, e)))?;
+        let mut builder = self.client.request(method, &request.url);
+        if !self.username.is_empty() {
+            builder = builder.basic_auth(&self.username, Some(&self.password));
+        }
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            TodoError::network(format!("{} {} 失败: {}", request.method, request.url, e))
+        })?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| TodoError::network(format!("读取 {} 的响应失败: {}", request.url, e)))?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
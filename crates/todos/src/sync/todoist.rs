@@ -0,0 +1,163 @@
+//! Todoist Sync API 客户端
+//!
+//! 所有读写都通过同一个 `sync` 端点：请求带上本地的 `sync_token` 和一批命令，
+//! 响应中是命令的执行结果（`sync_status`）、新对象的 `temp_id → id` 映射，
+//! 以及自 `sync_token` 以来有变化的对象。`sync_token` 为 `*` 时返回全部数据。
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{HttpRequest, HttpTransport};
+use crate::error::TodoError;
+
+/// 官方的 Sync API 地址
+pub const TODOIST_SYNC_URL: &str = "https://api.todoist.com/sync/v9/sync";
+
+/// 第一次同步（全量）使用的 `sync_token`
+pub const FULL_SYNC_TOKEN: &str = "*";
+
+/// 一个请求最多携带的命令数（Todoist 的限制）
+pub const COMMAND_BATCH: usize = 100;
+
+/// 同步时读取的对象类型
+const RESOURCE_TYPES: &str = r#"["projects","sections","items","labels"]"#;
+
+/// 一条写命令，如 `item_add`、`project_update`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncCommand {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 命令的唯一 ID，重复发送同一个 `uuid` 不会重复执行
+    pub uuid: String,
+    /// 新建对象的临时 ID，服务器在 `temp_id_mapping` 中返回正式 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_id: Option<String>,
+    pub args: Value,
+}
+
+/// `sync` 端点的响应
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SyncResponse {
+    pub sync_token: String,
+    pub full_sync: bool,
+    /// 命令的临时 ID → 服务器分配的 ID
+    pub temp_id_mapping: HashMap<String, String>,
+    /// 命令 `uuid` → `"ok"` 或 `{"error_code": ..., "error": ...}`
+    pub sync_status: HashMap<String, Value>,
+    pub projects: Vec<Value>,
+    pub sections: Vec<Value>,
+    pub items: Vec<Value>,
+    pub labels: Vec<Value>,
+}
+
+impl SyncResponse {
+    /// 命令被服务器拒绝时的错误信息；执行成功或响应中没有该命令时为 `None`
+    pub fn command_error(&self, uuid: &str) -> Option<String> {
+        match self.sync_status.get(uuid)? {
+            Value::String(status) if status == "ok" => None,
+            Value::Object(error) => Some(
+                error
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| Value::Object(error.clone()).to_string(), str::to_string),
+            ),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+/// 一个 Todoist 账号的 Sync API 客户端
+#[derive(Clone)]
+pub struct TodoistClient {
+    transport: Arc<dyn HttpTransport>,
+    url: String,
+    token: String,
+}
+
+impl TodoistClient {
+    pub fn new(transport: Arc<dyn HttpTransport>, token: impl Into<String>) -> Self {
+        Self { transport, url: TODOIST_SYNC_URL.to_string(), token: token.into() }
+    }
+
+    /// 使用其他地址（自建代理或测试用的本地服务器）
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// 发送一批命令并读取自 `sync_token` 以来的变化
+    pub async fn sync(
+        &self,
+        sync_token: &str,
+        commands: &[SyncCommand],
+    ) -> Result<SyncResponse, TodoError> {
+        let mut fields = vec![("sync_token", sync_token.to_string())];
+        fields.push(("resource_types", RESOURCE_TYPES.to_string()));
+        if !commands.is_empty() {
+            let commands = serde_json::to_string(commands)
+                .map_err(|e| TodoError::InternalError(format!("同步命令序列化失败: {}", e)))?;
+            fields.push(("commands", commands));
+        }
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let request = HttpRequest::new("POST", &self.url)
+            .with_header("Authorization", format!("Bearer {}", self.token))
+            .with_header("Content-Type", "application/x-www-form-urlencoded")
+            .with_body(body);
+        let response = self.transport.send(request).await?;
+        if !response.is_success() {
+            let reason = match response.status {
+                401 | 403 => "认证失败，请检查 API 令牌",
+                429 => "请求过于频繁，请稍后再试",
+                _ => "服务器返回错误",
+            };
+            return Err(TodoError::network(format!(
+                "Todoist 同步返回 {}：{}",
+                response.status, reason
+            )));
+        }
+        serde_json::from_str(&response.body)
+            .map_err(|e| TodoError::network(format!("无法解析 Todoist 的响应: {}", e)))
+    }
+}
+
+/// `application/x-www-form-urlencoded` 编码
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                encoded.push(byte as char)
+            },
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_status() {
+        let response: SyncResponse = serde_json::from_str(
+            r#"{"sync_token":"t1","sync_status":{"a":"ok","b":{"error_code":15,"error":"Invalid temporary id"}},"items":[{"id":"1"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.sync_token, "t1");
+        assert_eq!(response.items.len(), 1);
+        assert_eq!(response.command_error("a"), None);
+        assert_eq!(response.command_error("b").as_deref(), Some("Invalid temporary id"));
+        assert_eq!(response.command_error("c"), None);
+        assert_eq!(form_encode("[\"a b\",\"ä&\"]"), "%5B%22a+b%22%2C%22%C3%A4%26%22%5D");
+    }
+}
@@ -6,7 +6,7 @@ use chrono::{
 };
 use serde_json::Value;
 
-use crate::{enums::RecurrencyType, objects::DueDate};
+use crate::{QuickAdd, enums::RecurrencyType, objects::DueDate};
 pub const EMPTY_DATETIME: NaiveDateTime =
     chrono::DateTime::from_timestamp(0, 0).unwrap().naive_utc();
 #[derive(Default)]
@@ -17,9 +17,13 @@ impl DateTime {
         // YYYY-MM-DD
         if date.len() == 10 {
             NaiveDateTime::parse_from_str(format!("{date} 00:00:00").as_str(), "%Y-%m-%d %H:%M:%S")
+        } else if let Some(utc) = date.strip_suffix('Z') {
+            // 固定时区的任务以 UTC 保存（YYYY-MM-DDTHH:MM:SSZ），转换为本地时间
+            NaiveDateTime::parse_from_str(utc, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|dt| dt.and_utc().with_timezone(&Local).naive_local())
         } else {
-            // YYYY-MM-DDTHH:MM:SS
-            NaiveDateTime::parse_from_str(&date, "%Y-%m-%d %H:%M:%S")
+            // YYYY-MM-DDTHH:MM:SS（兼容空格分隔）
+            NaiveDateTime::parse_from_str(&date.replacen('T', " ", 1), "%Y-%m-%d %H:%M:%S%.f")
         }
     }
 
@@ -136,7 +140,26 @@ impl DateTime {
         "planner-scheduled"
     }
 
-    pub fn parse_todoist_recurrency(_duedate: DueDate, _object: Value) {}
+    /// 按 Todoist 的 due 对象（`{"date", "string", "is_recurring", ...}`）填充重复规则
+    ///
+    /// Todoist 只在自然语言的 `string` 里记录重复规则，这里复用快速添加的解析；
+    /// 无法完整识别时返回 `false`，`duedate` 保持不变。
+    pub fn parse_todoist_recurrency(duedate: &mut DueDate, object: &Value) -> bool {
+        let Some(string) = object.get("string").and_then(Value::as_str) else {
+            return false;
+        };
+        let parsed = QuickAdd::parse(string, Local::now().naive_local(), &());
+        match parsed.due {
+            Some(due) if due.is_recurring && parsed.content.is_empty() => {
+                duedate.is_recurring = true;
+                duedate.recurrency_supported = true;
+                duedate.recurrency_type = due.recurrency_type;
+                duedate.recurrency_interval = due.recurrency_interval;
+                true
+            },
+            _ => false,
+        }
+    }
 
     pub fn has_time(&self, datetime: &NaiveDateTime) -> bool {
         datetime == &EMPTY_DATETIME && datetime.time() != NaiveTime::default()
@@ -287,7 +310,7 @@ impl DateTime {
                 if duedate.recurrency_weeks.is_empty() {
                     returned + Duration::days(duedate.recurrency_interval * 7)
                 } else {
-                    self.next_recurrency_week(datetime, duedate, false)
+                    self.next_recurrency_week(datetime, duedate)
                 }
             },
             RecurrencyType::EveryMonth => {
@@ -305,7 +328,7 @@ impl DateTime {
         duedate: DueDate,
     ) -> Option<i32> {
         let weeks: Vec<&str> = duedate.recurrency_weeks.split(",").collect();
-        let day_of_week = datetime.weekday().num_days_from_monday() as i32;
+        let day_of_week = datetime.weekday().number_from_monday() as i32;
         for week in &weeks {
             if let Ok(week) = week.parse::<i32>() {
                 if week > day_of_week {
@@ -324,36 +347,31 @@ impl DateTime {
         None
     }
 
-    pub fn next_recurrency_week(
-        &self,
-        datetime: NaiveDateTime,
-        duedate: DueDate,
-        user: bool,
-    ) -> NaiveDateTime {
-        let weeks: Vec<&str> = duedate.recurrency_weeks.split(",").collect(); // [1, 2, 3]
-        let day_of_week: i64 = datetime.weekday().num_days_from_monday() as i64; // 2
-        let mut index = 0;
-        let mut recurrency_interval = 0;
-
-        for (i, week_str) in weeks.iter().enumerate() {
-            if day_of_week < week_str.parse::<i64>().unwrap() {
-                index = i;
-                break;
-            }
-        }
-        let next_day: i64 = weeks[index].parse::<i64>().unwrap();
-
-        let days: i64 = if day_of_week < next_day {
-            next_day - day_of_week
-        } else {
-            7 - (day_of_week - next_day)
+    /// 按 `recurrency_weeks`（1 为周一 … 7 为周日）计算下一次到期时间
+    ///
+    /// 本周后面还有指定的星期几时取最近的一天；否则回到下一周的第一个指定日，
+    /// 并按 `recurrency_interval` 再跳过若干周。无法解析的值被忽略，全部无效时按整周重复。
+    pub fn next_recurrency_week(&self, datetime: NaiveDateTime, duedate: DueDate) -> NaiveDateTime {
+        let mut days: Vec<i64> = duedate
+            .recurrency_weeks
+            .split(',')
+            .filter_map(|day| day.trim().parse::<i64>().ok())
+            .filter(|day| (1..=7).contains(day))
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+
+        let interval = duedate.recurrency_interval.max(1);
+        let day_of_week = datetime.weekday().number_from_monday() as i64;
+        let offset = match days.iter().find(|&&day| day > day_of_week) {
+            Some(next_day) => next_day - day_of_week,
+            None => match days.first() {
+                Some(first_day) => 7 - day_of_week + first_day + (interval - 1) * 7,
+                None => interval * 7,
+            },
         };
 
-        if user && index == 0 {
-            recurrency_interval = (duedate.recurrency_interval - 1) * 7;
-        }
-
-        datetime + Duration::days(days) + Duration::days(recurrency_interval)
+        datetime + Duration::days(offset)
     }
 
     pub fn get_recurrency_weeks(
@@ -428,6 +446,31 @@ impl DateTime {
         }
     }
 
+    /// 解析 iCalendar 的 DATE / DATE-TIME 值：`20300107`、`20300107T090000`（浮动时间）
+    /// 或 `20300107T090000Z`（UTC，转换为本地时间）
+    pub fn get_ical_datetime(&self, value: &str) -> Option<NaiveDateTime> {
+        if value.len() == 8 {
+            return NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .map(|date| self.format_date(date));
+        }
+        match value.strip_suffix('Z') {
+            Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                .ok()
+                .map(|dt| dt.and_utc().with_timezone(&Local).naive_local()),
+            None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+        }
+    }
+
+    /// 格式化为 iCalendar 值；`date_only` 时输出 DATE，否则输出浮动的 DATE-TIME
+    pub fn get_ical_datetime_format(&self, date: &NaiveDateTime, date_only: bool) -> String {
+        if date_only {
+            date.format("%Y%m%d").to_string()
+        } else {
+            date.format("%Y%m%dT%H%M%S").to_string()
+        }
+    }
+
     pub fn has_time_from_string(&self, date: &NaiveDateTime) -> bool {
         self.has_time(date)
     }
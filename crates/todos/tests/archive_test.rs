@@ -0,0 +1,160 @@
+//! 项目 / 分区归档测试（归档、取消归档、归档页、彻底删除）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel, SectionModel},
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+async fn project(store: &Store, name: &str, parent: Option<&str>) -> ProjectModel {
+    store
+        .insert_project(ProjectModel {
+            name: name.to_string(),
+            parent_id: parent.map(str::to_string),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+async fn section(store: &Store, name: &str, project_id: &str) -> SectionModel {
+    store
+        .insert_section(SectionModel {
+            name: name.to_string(),
+            project_id: Some(project_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+async fn item(
+    store: &Store,
+    content: &str,
+    project_id: &str,
+    section_id: Option<&str>,
+) -> ItemModel {
+    store
+        .insert_item(
+            ItemModel {
+                content: content.to_string(),
+                project_id: Some(project_id.to_string()),
+                section_id: section_id.map(str::to_string),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap()
+}
+
+async fn visible_items(store: &Store) -> Vec<String> {
+    let mut contents: Vec<String> =
+        store.get_all_items().await.unwrap().into_iter().map(|item| item.content).collect();
+    contents.sort();
+    contents
+}
+
+#[tokio::test]
+async fn test_archive_project_cascades_and_restores() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let client = project(&store, "Client", Some(&work.id)).await;
+    let home = project(&store, "Home", None).await;
+    let backlog = section(&store, "Backlog", &work.id).await;
+    item(&store, "open", &work.id, Some(&backlog.id)).await;
+    item(&store, "nested", &client.id, None).await;
+    let done = item(&store, "done", &work.id, None).await;
+    store.complete_item(&done.id, true, false).await.unwrap();
+    item(&store, "home task", &home.id, None).await;
+
+    let archived = store.archive_project(&work.id).await.unwrap();
+    assert!(archived.is_archived);
+    assert!(archived.archived_at.is_some());
+    assert!(store.archive_project(&work.id).await.is_err(), "already archived");
+
+    // 归档项目树从项目和任务列表中消失
+    let projects: Vec<String> =
+        store.get_all_projects().await.unwrap().into_iter().map(|p| p.name).collect();
+    assert_eq!(projects, ["Home"]);
+    assert_eq!(visible_items(&store).await, ["home task"]);
+    let sections = store.get_all_sections().await.unwrap();
+    let backlog_now = sections.iter().find(|s| s.id == backlog.id).unwrap();
+    assert!(backlog_now.is_archived);
+    assert_eq!(backlog_now.archived_at, archived.archived_at);
+    // 已完成的任务不标记归档
+    assert_eq!(store.get_item(&done.id).await.unwrap().archived_at, None);
+
+    // 归档页只列出根项目
+    let archive = store.list_archive().await.unwrap();
+    assert_eq!(archive.len(), 1);
+    assert_eq!(archive[0].project.id, work.id);
+    assert_eq!(archive[0].subproject_count, 1);
+    assert_eq!(archive[0].item_count, 2);
+
+    let restored = store.unarchive_project(&work.id).await.unwrap();
+    assert!(!restored.is_archived);
+    assert_eq!(restored.archived_at, None);
+    assert_eq!(store.get_all_projects().await.unwrap().len(), 3);
+    assert_eq!(visible_items(&store).await, ["done", "home task", "nested", "open"]);
+    let sections = store.get_all_sections().await.unwrap();
+    assert!(!sections.iter().find(|s| s.id == backlog.id).unwrap().is_archived);
+    assert!(store.list_archive().await.unwrap().is_empty());
+    assert!(store.unarchive_project(&work.id).await.is_err(), "not archived");
+}
+
+#[tokio::test]
+async fn test_unarchive_keeps_separately_archived_children() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let client = project(&store, "Client", Some(&work.id)).await;
+    let old = section(&store, "Old", &work.id).await;
+    item(&store, "old task", &work.id, Some(&old.id)).await;
+    item(&store, "client task", &client.id, None).await;
+
+    store.set_section_archived(&old.id, true).await.unwrap();
+    store.archive_project(&client.id).await.unwrap();
+    assert!(visible_items(&store).await.is_empty());
+    store.archive_project(&work.id).await.unwrap();
+
+    // 子项目先单独归档，是独立的根
+    let archive = store.list_archive().await.unwrap();
+    assert_eq!(archive.len(), 2);
+
+    store.unarchive_project(&work.id).await.unwrap();
+    let projects: Vec<String> =
+        store.get_all_projects().await.unwrap().into_iter().map(|p| p.name).collect();
+    assert_eq!(projects, ["Work"]);
+    let sections = store.get_all_sections().await.unwrap();
+    assert!(sections.iter().find(|s| s.id == old.id).unwrap().is_archived);
+    assert!(visible_items(&store).await.is_empty());
+
+    store.set_section_archived(&old.id, false).await.unwrap();
+    assert_eq!(visible_items(&store).await, ["old task"]);
+}
+
+#[tokio::test]
+async fn test_delete_archived_project() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let client = project(&store, "Client", Some(&work.id)).await;
+    let task = item(&store, "task", &client.id, None).await;
+
+    assert!(store.delete_archived_project(&work.id).await.is_err(), "not archived");
+    store.archive_project(&work.id).await.unwrap();
+    store.delete_archived_project(&work.id).await.unwrap();
+
+    assert!(store.list_archive().await.unwrap().is_empty());
+    assert!(store.get_item(&task.id).await.is_none());
+    // 彻底删除，不进回收站
+    assert!(store.list_trash().await.unwrap().is_empty());
+    assert!(store.delete_archived_project("missing").await.is_err());
+}
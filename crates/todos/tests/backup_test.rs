@@ -0,0 +1,160 @@
+//! 备份测试：临时目录中的 SQLite 文件，快照、轮换与恢复
+
+use std::{fs, path::PathBuf, sync::Arc};
+
+use sea_orm::{Database, DatabaseConnection};
+use todos::{
+    BackupManager, BackupPolicy, BackupReason, PatchManager, Store, apply_pending_restore,
+    entity::ItemModel, pending_restore_path,
+};
+
+struct Fixture {
+    dir: PathBuf,
+    db_path: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("backup-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("todos.sqlite");
+        Self { dir, db_path }
+    }
+
+    async fn connect(&self) -> DatabaseConnection {
+        Database::connect(format!("sqlite://{}?mode=rwc", self.db_path.display()))
+            .await
+            .expect("connect sqlite file")
+    }
+
+    fn manager(&self, db: &DatabaseConnection, policy: BackupPolicy) -> BackupManager {
+        BackupManager::new(Arc::new(db.clone()), &self.db_path, policy)
+    }
+
+    fn backup_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(self.dir.join("backups"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn item(content: &str) -> ItemModel {
+    ItemModel { content: content.to_string(), ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_snapshot_lists_item_counts() {
+    let fixture = Fixture::new();
+    let db = fixture.connect().await;
+    let store = Store::new(db.clone()).await.unwrap();
+    store.insert_item(item("One"), true).await.unwrap();
+    let deleted = store.insert_item(item("Two"), true).await.unwrap();
+    store.delete_item(&deleted.id).await.unwrap();
+
+    let manager = fixture.manager(&db, BackupPolicy::default());
+    assert!(manager.list().await.unwrap().is_empty());
+    let snapshot = manager.create(BackupReason::Startup).await.unwrap();
+    assert!(snapshot.path.starts_with(manager.dir()));
+    assert!(snapshot.size > 0);
+
+    // 之后的修改不影响快照
+    store.insert_item(item("Three"), true).await.unwrap();
+    let listed = manager.list().await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].reason, BackupReason::Startup);
+    assert_eq!(listed[0].item_count, Some(1));
+    let latest = PatchManager::new(Arc::new(db.clone())).latest_version();
+    assert_eq!(listed[0].schema_version, Some(latest));
+}
+
+#[tokio::test]
+async fn test_rotation_keeps_daily_and_weekly_copies() {
+    let fixture = Fixture::new();
+    let db = fixture.connect().await;
+    let manager = fixture.manager(&db, BackupPolicy { daily: 2, weekly: 2 });
+    let backups = fixture.dir.join("backups");
+    fs::create_dir_all(&backups).unwrap();
+    for name in [
+        // 2026-10-12 ~ 10-18 是同一周
+        "20261017-080000000-startup",
+        "20261017-140000000-scheduled",
+        "20261016-090000000-startup",
+        "20261015-090000000-startup",
+        "20261008-090000000-scheduled",
+        "20261007-090000000-startup",
+        "20260930-090000000-startup",
+        "20261016-100000000-pre-migration",
+        "20261001-100000000-pre-restore",
+        "20261002-100000000-pre-restore",
+    ] {
+        fs::write(backups.join(format!("{}.sqlite", name)), b"").unwrap();
+    }
+    fs::write(backups.join("notes.txt"), b"").unwrap();
+
+    let removed = manager.rotate().unwrap();
+    assert_eq!(removed.len(), 5);
+    assert_eq!(fixture.backup_names(), vec![
+        "20261002-100000000-pre-restore.sqlite",
+        "20261008-090000000-scheduled.sqlite",
+        "20261016-090000000-startup.sqlite",
+        "20261016-100000000-pre-migration.sqlite",
+        "20261017-140000000-scheduled.sqlite",
+        "notes.txt",
+    ]);
+}
+
+#[tokio::test]
+async fn test_restore_is_staged_and_applied_before_connecting() {
+    let fixture = Fixture::new();
+    let db = fixture.connect().await;
+    let store = Store::new(db.clone()).await.unwrap();
+    store.insert_item(item("Before"), true).await.unwrap();
+    let manager = fixture.manager(&db, BackupPolicy::default());
+    let snapshot = manager.create(BackupReason::Scheduled).await.unwrap();
+    store.insert_item(item("After"), true).await.unwrap();
+
+    // 准备恢复：当前数据库先备份，正在使用的文件不变
+    let staged = manager.stage_restore(&snapshot.path).await.unwrap();
+    assert_eq!(staged, pending_restore_path(&fixture.db_path));
+    let listed = manager.list().await.unwrap();
+    assert_eq!(listed[0].reason, BackupReason::PreRestore);
+    assert_eq!(listed[0].item_count, Some(2));
+    assert_eq!(store.get_all_items().await.unwrap().len(), 2);
+    drop(store);
+    db.close().await.unwrap();
+
+    assert!(apply_pending_restore(&fixture.db_path).unwrap());
+    assert!(!staged.exists());
+    assert!(!apply_pending_restore(&fixture.db_path).unwrap());
+
+    let db = fixture.connect().await;
+    let store = Store::new(db.clone()).await.unwrap();
+    let items = store.get_all_items().await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].content, "Before");
+}
+
+#[tokio::test]
+async fn test_damaged_snapshot_is_refused() {
+    let fixture = Fixture::new();
+    let db = fixture.connect().await;
+    Store::new(db.clone()).await.unwrap();
+    let manager = fixture.manager(&db, BackupPolicy::default());
+    let backups = fixture.dir.join("backups");
+    fs::create_dir_all(&backups).unwrap();
+    let damaged = backups.join("20261017-080000000-startup.sqlite");
+    fs::write(&damaged, b"not a database").unwrap();
+
+    assert!(manager.stage_restore(&damaged).await.is_err());
+    assert!(!pending_restore_path(&fixture.db_path).exists());
+    assert_eq!(manager.list().await.unwrap()[0].item_count, None);
+}
@@ -0,0 +1,420 @@
+//! CalDAV 双向同步的集成测试：内存 SQLite + 进程内的 CalDAV 替身服务器
+
+mod common;
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel},
+    error::TodoError,
+    services::{CalDavConfig, CalDavService},
+    sync::{HttpRequest, HttpResponse, HttpTransport},
+};
+
+const CALENDAR: &str = "/dav/calendars/me/tasks/";
+
+/// 最小的 CalDAV 服务器：一个日历集合，支持 ctag、ETag 和条件写入
+#[derive(Default)]
+struct FakeCalDav {
+    inner: Mutex<Calendar>,
+}
+
+#[derive(Default)]
+struct Calendar {
+    ctag: u32,
+    next_etag: u32,
+    /// 资源名 → (ETag, 内容)
+    resources: BTreeMap<String, (String, String)>,
+    /// 收到的请求（方法 + 资源名）
+    log: Vec<String>,
+}
+
+impl FakeCalDav {
+    /// 模拟其他客户端写入资源
+    fn put(&self, name: &str, ics: &str) {
+        let mut calendar = self.inner.lock().unwrap();
+        calendar.write(name, ics.to_string());
+    }
+
+    fn remove(&self, name: &str) {
+        let mut calendar = self.inner.lock().unwrap();
+        calendar.resources.remove(name);
+        calendar.ctag += 1;
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.inner.lock().unwrap().resources.get(name).map(|(_, ics)| ics.clone())
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.inner.lock().unwrap().resources.keys().cloned().collect()
+    }
+
+    fn take_log(&self) -> Vec<String> {
+        std::mem::take(&mut self.inner.lock().unwrap().log)
+    }
+}
+
+impl Calendar {
+    fn write(&mut self, name: &str, ics: String) -> String {
+        self.next_etag += 1;
+        self.ctag += 1;
+        let etag = format!("\"{}\"", self.next_etag);
+        self.resources.insert(name.to_string(), (etag.clone(), ics));
+        etag
+    }
+
+    fn multistatus(responses: &[String]) -> HttpResponse {
+        HttpResponse::new(
+            207,
+            format!(
+                r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">{}</d:multistatus>"#,
+                responses.concat()
+            ),
+        )
+    }
+
+    fn response(href: &str, props: &str) -> String {
+        [
+            "<d:response><d:href>",
+            href,
+            "</d:href><d:propstat><d:prop>",
+            props,
+            "</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        ]
+        .concat()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[async_trait]
+impl HttpTransport for FakeCalDav {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TodoError> {
+        let mut calendar = self.inner.lock().unwrap();
+        let path =
+            request.url.split_once("://").and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]));
+        let Some(name) = path.and_then(|path| path.strip_prefix(CALENDAR)) else {
+            return Ok(HttpResponse::new(404, ""));
+        };
+        let name = name.to_string();
+        calendar.log.push(format!("{} {}", request.method, name).trim().to_string());
+        let body = request.body.clone().unwrap_or_default();
+
+        let response = match request.method.as_str() {
+            "PROPFIND" => Calendar::multistatus(&[Calendar::response(
+                CALENDAR,
+                &format!("<cs:getctag>ctag-{}</cs:getctag>", calendar.ctag),
+            )]),
+            "REPORT" if body.contains("calendar-multiget") => {
+                let responses: Vec<String> = body
+                    .split("<d:href>")
+                    .skip(1)
+                    .filter_map(|part| part.split("</d:href>").next())
+                    .filter_map(|href| {
+                        let (etag, ics) = calendar.resources.get(href.strip_prefix(CALENDAR)?)?;
+                        Some(Calendar::response(
+                            href,
+                            &format!(
+                                "<d:getetag>{}</d:getetag><c:calendar-data>{}</c:calendar-data>",
+                                escape(etag),
+                                escape(ics)
+                            ),
+                        ))
+                    })
+                    .collect();
+                Calendar::multistatus(&responses)
+            },
+            "REPORT" => {
+                let responses: Vec<String> = calendar
+                    .resources
+                    .iter()
+                    .map(|(name, (etag, _))| {
+                        Calendar::response(
+                            &format!("{CALENDAR}{name}"),
+                            &format!("<d:getetag>{}</d:getetag>", escape(etag)),
+                        )
+                    })
+                    .collect();
+                Calendar::multistatus(&responses)
+            },
+            "PUT" | "DELETE" => {
+                let current = calendar.resources.get(&name).map(|(etag, _)| etag.clone());
+                let precondition =
+                    match (request.header("If-Match"), request.header("If-None-Match")) {
+                        (Some(expected), _) => current.as_deref() == Some(expected),
+                        (None, Some("*")) => current.is_none(),
+                        _ => true,
+                    };
+                if !precondition {
+                    HttpResponse::new(412, "")
+                } else if request.method == "PUT" {
+                    let etag = calendar.write(&name, body);
+                    HttpResponse::new(201, "").with_header("ETag", etag)
+                } else if calendar.resources.remove(&name).is_some() {
+                    calendar.ctag += 1;
+                    HttpResponse::new(204, "")
+                } else {
+                    HttpResponse::new(404, "")
+                }
+            },
+            _ => HttpResponse::new(405, ""),
+        };
+        Ok(response)
+    }
+}
+
+struct Fixture {
+    store: Arc<Store>,
+    caldav: CalDavService,
+    server: Arc<FakeCalDav>,
+    source_id: String,
+    project_id: String,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let db = common::connect().await;
+        let store = Store::new(db.clone()).await.expect("create store");
+        let caldav = CalDavService::new(Arc::new(db));
+        let source = caldav
+            .add_source(CalDavConfig {
+                calendar_url: format!("https://dav.example.com{}", CALENDAR.trim_end_matches('/')),
+                username: "me".to_string(),
+                password: "secret".to_string(),
+                display_name: None,
+            })
+            .await
+            .unwrap();
+        let project = store
+            .get_all_projects()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|project| project.source_id.as_deref() == Some(source.id.as_str()))
+            .unwrap();
+        Self {
+            store,
+            caldav,
+            server: Arc::new(FakeCalDav::default()),
+            source_id: source.id,
+            project_id: project.id,
+        }
+    }
+
+    async fn sync(&self) -> todos::services::CalDavSyncReport {
+        self.caldav.sync_with_transport(&self.source_id, self.server.clone()).await.unwrap()
+    }
+
+    async fn items(&self) -> Vec<ItemModel> {
+        let mut items = self.store.get_items_by_project(&self.project_id).await.unwrap();
+        items.sort_by(|a, b| a.content.cmp(&b.content));
+        items
+    }
+
+    async fn item(&self, content: &str) -> ItemModel {
+        self.items().await.into_iter().find(|item| item.content == content).unwrap()
+    }
+}
+
+fn vtodo(uid: &str, lines: &[&str]) -> String {
+    let mut ics = vec!["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//Other//EN", "BEGIN:VTODO"];
+    let uid = format!("UID:{uid}");
+    ics.push(&uid);
+    ics.extend_from_slice(lines);
+    ics.extend_from_slice(&["END:VTODO", "END:VCALENDAR"]);
+    ics.join("\r\n")
+}
+
+#[tokio::test]
+async fn test_add_source_creates_project() {
+    let fixture = Fixture::new().await;
+    let project: ProjectModel = fixture
+        .store
+        .get_all_projects()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|project| project.id == fixture.project_id)
+        .unwrap();
+    assert_eq!(project.name, "tasks");
+    assert_eq!(project.backend_type.as_deref(), Some("caldav"));
+    assert_eq!(project.sync_id.as_deref(), Some("https://dav.example.com/dav/calendars/me/tasks/"));
+
+    let sources = fixture.caldav.get_sources().await.unwrap();
+    assert_eq!(sources.len(), 1);
+
+    let invalid =
+        CalDavConfig { calendar_url: "ftp://example.com".to_string(), ..Default::default() };
+    assert!(fixture.caldav.add_source(invalid).await.is_err());
+
+    fixture.caldav.remove_source(&fixture.source_id).await.unwrap();
+    assert!(fixture.caldav.get_sources().await.unwrap().is_empty());
+    let projects = fixture.store.get_all_projects().await.unwrap();
+    let project = projects.iter().find(|project| project.id == fixture.project_id).unwrap();
+    assert_eq!(project.backend_type, None);
+}
+
+#[tokio::test]
+async fn test_pull_and_push() {
+    let fixture = Fixture::new().await;
+    fixture.server.put(
+        "parent.ics",
+        &vtodo("parent-uid", &[
+            "SUMMARY:Plan trip",
+            "DUE;VALUE=DATE:20300105",
+            "PRIORITY:1",
+            "CATEGORIES:travel",
+            "LOCATION:Home",
+            "X-APPLE-SORT-ORDER:7",
+        ]),
+    );
+    fixture.server.put(
+        "child.ics",
+        &vtodo("child-uid", &["SUMMARY:Book hotel", "RELATED-TO:parent-uid", "STATUS:COMPLETED"]),
+    );
+
+    let report = fixture.sync().await;
+    assert_eq!((report.pulled_created, report.pushed_created, report.conflicts), (2, 0, 0));
+    let items = fixture.items().await;
+    assert_eq!(items.len(), 2);
+    let parent = fixture.item("Plan trip").await;
+    let child = fixture.item("Book hotel").await;
+    assert_eq!(child.parent_id.as_deref(), Some(parent.id.as_str()));
+    assert!(child.checked);
+    assert_eq!(parent.priority, Some(1));
+    assert_eq!(parent.due_date().unwrap().date, "2030-01-05 00:00:00");
+    let labels = fixture.store.get_labels_by_item(&parent.id).await.unwrap();
+    assert_eq!(labels[0].name, "travel");
+
+    // 没有任何变化：ctag 相同，只发一个 PROPFIND
+    fixture.server.take_log();
+    let report = fixture.sync().await;
+    assert!(!report.has_changes());
+    assert_eq!(fixture.server.take_log(), vec!["PROPFIND"]);
+
+    // 本地新建和修改
+    let local = fixture
+        .store
+        .insert_item(
+            ItemModel {
+                content: "Pack bags".to_string(),
+                project_id: Some(fixture.project_id.clone()),
+                parent_id: Some(parent.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    let mut renamed = parent.clone();
+    renamed.content = "Plan summer trip".to_string();
+    fixture.store.update_item(renamed, "").await.unwrap();
+
+    let report = fixture.sync().await;
+    assert_eq!((report.pushed_created, report.pushed_updated, report.pulled_updated), (1, 1, 0));
+    let created = fixture.server.get(&format!("{}.ics", local.id)).unwrap();
+    assert!(created.contains(&format!("UID:{}\r\n", local.id)));
+    assert!(created.contains("RELATED-TO:parent-uid\r\n"));
+    let updated = fixture.server.get("parent.ics").unwrap();
+    assert!(updated.contains("SUMMARY:Plan summer trip\r\n"));
+    assert!(updated.contains("UID:parent-uid\r\n"));
+    // 无法映射的属性原样写回
+    assert!(updated.contains("LOCATION:Home\r\n"));
+    assert!(updated.contains("X-APPLE-SORT-ORDER:7\r\n"));
+
+    // 推送后服务器上的 ETag 已记录，再次同步没有变化
+    let report = fixture.sync().await;
+    assert!(!report.has_changes(), "{:?}", report);
+    assert_eq!(fixture.items().await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_remote_and_local_deletes() {
+    let fixture = Fixture::new().await;
+    fixture.server.put("a.ics", &vtodo("a", &["SUMMARY:Alpha"]));
+    fixture.server.put("b.ics", &vtodo("b", &["SUMMARY:Beta"]));
+    fixture.sync().await;
+
+    // 服务器上修改 a、删除 b
+    fixture.server.put("a.ics", &vtodo("a", &["SUMMARY:Alpha v2", "PRIORITY:5"]));
+    fixture.server.remove("b.ics");
+    let report = fixture.sync().await;
+    assert_eq!((report.pulled_updated, report.pulled_deleted), (1, 1));
+    let items = fixture.items().await;
+    assert_eq!(items.len(), 1);
+    assert_eq!((items[0].content.as_str(), items[0].priority), ("Alpha v2", Some(2)));
+    let trash = fixture.store.list_trash().await.unwrap();
+    assert_eq!(trash.len(), 1);
+
+    // 本地删除 a，服务器上也删除
+    fixture.store.delete_item(&items[0].id).await.unwrap();
+    let report = fixture.sync().await;
+    assert_eq!(report.pushed_deleted, 1);
+    assert!(fixture.server.names().is_empty());
+}
+
+#[tokio::test]
+async fn test_conflicts_resolve_by_modification_time() {
+    let fixture = Fixture::new().await;
+    fixture.server.put("a.ics", &vtodo("a", &["SUMMARY:Alpha"]));
+    fixture.server.put("b.ics", &vtodo("b", &["SUMMARY:Beta"]));
+    fixture.server.put("c.ics", &vtodo("c", &["SUMMARY:Gamma"]));
+    fixture.sync().await;
+
+    for (content, renamed) in
+        [("Alpha", "Alpha local"), ("Beta", "Beta local"), ("Gamma", "Gamma local")]
+    {
+        let mut item = fixture.item(content).await;
+        item.content = renamed.to_string();
+        fixture.store.update_item(item, "").await.unwrap();
+    }
+    // a：服务器修改得更晚；b：服务器修改得更早；c：服务器删除
+    fixture
+        .server
+        .put("a.ics", &vtodo("a", &["SUMMARY:Alpha remote", "LAST-MODIFIED:20990101T000000Z"]));
+    fixture
+        .server
+        .put("b.ics", &vtodo("b", &["SUMMARY:Beta remote", "LAST-MODIFIED:20000101T000000Z"]));
+    fixture.server.remove("c.ics");
+
+    let report = fixture.sync().await;
+    assert_eq!(report.conflicts, 3);
+    let contents: Vec<String> =
+        fixture.items().await.into_iter().map(|item| item.content).collect();
+    assert_eq!(contents, vec!["Alpha remote", "Beta local", "Gamma local"]);
+    assert!(fixture.server.get("b.ics").unwrap().contains("SUMMARY:Beta local\r\n"));
+    // 服务器上删除但本地修改过的任务重新创建
+    assert_eq!(fixture.server.names().len(), 3);
+    assert_eq!(report.pushed_created, 1);
+
+    let report = fixture.sync().await;
+    assert!(!report.has_changes(), "{:?}", report);
+    assert_eq!(report.conflicts, 0);
+}
+
+#[tokio::test]
+async fn test_remote_edit_restores_local_delete() {
+    let fixture = Fixture::new().await;
+    fixture.server.put("a.ics", &vtodo("a", &["SUMMARY:Alpha"]));
+    fixture.sync().await;
+
+    let item = fixture.item("Alpha").await;
+    fixture.store.delete_item(&item.id).await.unwrap();
+    fixture.server.put("a.ics", &vtodo("a", &["SUMMARY:Alpha edited"]));
+
+    let report = fixture.sync().await;
+    assert_eq!((report.pulled_updated, report.pushed_deleted), (1, 0));
+    let items = fixture.items().await;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, item.id);
+    assert_eq!(items[0].content, "Alpha edited");
+    assert_eq!(fixture.server.names(), vec!["a.ics"]);
+}
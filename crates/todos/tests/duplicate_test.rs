@@ -0,0 +1,250 @@
+//! 深拷贝测试（任务、分区、项目）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::{AttachmentModel, ItemModel, LabelModel, ProjectModel, ReminderModel, SectionModel},
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+async fn project(store: &Store, name: &str, parent: Option<&str>) -> ProjectModel {
+    store
+        .insert_project(ProjectModel {
+            name: name.to_string(),
+            parent_id: parent.map(str::to_string),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+async fn section(store: &Store, name: &str, project_id: &str, order: i32) -> SectionModel {
+    store
+        .insert_section(SectionModel {
+            name: name.to_string(),
+            project_id: Some(project_id.to_string()),
+            section_order: Some(order),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+async fn item(
+    store: &Store,
+    content: &str,
+    project_id: &str,
+    section_id: Option<&str>,
+    parent_id: Option<&str>,
+) -> ItemModel {
+    store
+        .insert_item(
+            ItemModel {
+                content: content.to_string(),
+                project_id: Some(project_id.to_string()),
+                section_id: section_id.map(str::to_string),
+                parent_id: parent_id.map(str::to_string),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_duplicate_item_copies_subtree_and_details() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let first = item(&store, "first", &work.id, None, None).await;
+    let next = item(&store, "next", &work.id, None, None).await;
+    store.move_item(&first.id, todos::services::ItemPosition::Top).await.unwrap();
+    store.move_item(&next.id, todos::services::ItemPosition::Bottom).await.unwrap();
+    let child = item(&store, "child", &work.id, None, Some(&first.id)).await;
+    let grandchild = item(&store, "grandchild", &work.id, None, Some(&child.id)).await;
+    let removed = item(&store, "removed", &work.id, None, Some(&first.id)).await;
+    store.delete_item(&removed.id).await.unwrap();
+
+    let label = store
+        .insert_label(LabelModel { name: "errand".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.set_item_labels(&child.id, std::slice::from_ref(&label.id)).await.unwrap();
+    store
+        .insert_reminder(ReminderModel {
+            item_id: Some(child.id.clone()),
+            due: Some("2025-03-06 09:00:00".to_string()),
+            reminder_type: Some("time".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    store
+        .insert_attachment(AttachmentModel {
+            item_id: child.id.clone(),
+            file_name: "notes.txt".to_string(),
+            file_size: 12,
+            file_path: "/tmp/notes.txt".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let result = store.duplicate_item(&first.id).await.unwrap();
+    assert_eq!(result.item_ids.len(), 3, "deleted subtasks are not copied");
+    assert!(!result.item_ids.contains_key(&removed.id));
+    let copy_id = &result.item_ids[&first.id];
+    assert_eq!(&result.items[0].id, copy_id);
+
+    // 副本排在原任务之后、下一个任务之前
+    let top_level: Vec<ItemModel> = {
+        let mut items: Vec<ItemModel> = store
+            .get_items_by_project(&work.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.parent_id.is_none())
+            .collect();
+        items.sort_by_key(|item| item.child_order);
+        items
+    };
+    let order: Vec<&str> = top_level.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(order, [first.id.as_str(), copy_id.as_str(), next.id.as_str()]);
+
+    let child_copy = store.get_item(&result.item_ids[&child.id]).await.unwrap();
+    assert_eq!(child_copy.parent_id.as_ref(), Some(copy_id));
+    assert_eq!(child_copy.content, "child");
+    let grandchild_copy = store.get_item(&result.item_ids[&grandchild.id]).await.unwrap();
+    assert_eq!(grandchild_copy.parent_id.as_ref(), Some(&child_copy.id));
+
+    let labels = store.get_labels_by_item(&child_copy.id).await.unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].id, label.id);
+    let reminders = store.get_reminders_by_item(&child_copy.id).await.unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].due.as_deref(), Some("2025-03-06 09:00:00"));
+    let attachments = store.get_attachments_by_item(&child_copy.id).await.unwrap();
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].file_path, "/tmp/notes.txt");
+    // 原任务不受影响
+    assert_eq!(store.get_reminders_by_item(&child.id).await.unwrap().len(), 1);
+
+    assert!(store.duplicate_item(&removed.id).await.is_err());
+    assert!(store.duplicate_item("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_duplicate_synced_item_drops_remote_identity() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let synced = store
+        .insert_item(
+            ItemModel {
+                content: "synced".to_string(),
+                project_id: Some(work.id.clone()),
+                checked: true,
+                completed_at: Some(chrono::Utc::now().naive_utc()),
+                extra_data: Some(serde_json::json!({ "todoist_id": "6Xq2Fm", "sync_id": "42" })),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+
+    let result = store.duplicate_item(&synced.id).await.unwrap();
+    let copy = store.get_item(&result.item_ids[&synced.id]).await.unwrap();
+    assert_ne!(copy.id, synced.id);
+    assert_eq!(copy.extra_data, None, "the copy is not linked to the remote task");
+    assert!(!copy.checked);
+    assert_eq!(copy.completed_at, None);
+
+    // 原任务保留远端标识
+    let original = store.get_item(&synced.id).await.unwrap();
+    assert!(original.extra_data.is_some());
+    assert_eq!(original.extra_data, synced.extra_data);
+    assert!(original.checked);
+}
+
+#[tokio::test]
+async fn test_duplicate_section() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let todo = section(&store, "Todo", &work.id, 0).await;
+    let done = section(&store, "Done", &work.id, 1).await;
+    let task = item(&store, "task", &work.id, Some(&todo.id), None).await;
+    item(&store, "step", &work.id, Some(&todo.id), Some(&task.id)).await;
+    item(&store, "elsewhere", &work.id, Some(&done.id), None).await;
+
+    let result = store.duplicate_section(&todo.id).await.unwrap();
+    let copy_id = &result.section_ids[&todo.id];
+    assert_eq!(result.items.len(), 2);
+
+    let mut sections: Vec<SectionModel> = store.get_all_sections().await.unwrap();
+    sections.sort_by_key(|s| s.section_order);
+    let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Todo", "Todo (copy)", "Done"]);
+
+    let task_copy = store.get_item(&result.item_ids[&task.id]).await.unwrap();
+    assert_eq!(task_copy.section_id.as_ref(), Some(copy_id));
+    assert_eq!(task_copy.project_id.as_ref(), Some(&work.id));
+    assert_eq!(task_copy.parent_id, None);
+    let step_copy = result.items.iter().find(|item| item.content == "step").unwrap();
+    assert_eq!(step_copy.parent_id.as_ref(), Some(&task_copy.id));
+    assert_eq!(step_copy.section_id.as_ref(), Some(copy_id));
+}
+
+#[tokio::test]
+async fn test_duplicate_project_tree() {
+    let store = setup_store().await;
+    let work = project(&store, "Work", None).await;
+    let home = project(&store, "Home", None).await;
+    let client = project(&store, "Client", Some(&work.id)).await;
+    let old = project(&store, "Old", Some(&work.id)).await;
+    let backlog = section(&store, "Backlog", &work.id, 0).await;
+    let task = item(&store, "task", &work.id, Some(&backlog.id), None).await;
+    item(&store, "subtask", &work.id, Some(&backlog.id), Some(&task.id)).await;
+    item(&store, "client task", &client.id, None, None).await;
+    item(&store, "old task", &old.id, None, None).await;
+    store.archive_project(&old.id).await.unwrap();
+
+    let result = store.duplicate_project(&work.id).await.unwrap();
+    assert_eq!(result.project_ids.len(), 2, "archived sub-projects are skipped");
+    assert_eq!(result.sections.len(), 1);
+    assert_eq!(result.items.len(), 3);
+
+    let copy = &result.projects[0];
+    assert_eq!(copy.name, "Work (copy)");
+    assert_eq!(copy.parent_id, None);
+    let client_copy = &result.projects[1];
+    assert_eq!(client_copy.name, "Client");
+    assert_eq!(client_copy.parent_id.as_ref(), Some(&copy.id));
+
+    // 归档的 Old 不在列表中，Home 没有被复制
+    let mut projects: Vec<String> =
+        store.get_all_projects().await.unwrap().into_iter().map(|p| p.name).collect();
+    projects.sort();
+    assert_eq!(projects, ["Client", "Client", home.name.as_str(), "Work", "Work (copy)"]);
+
+    let section_copy = &result.sections[0];
+    assert_eq!(section_copy.name, "Backlog");
+    assert_eq!(section_copy.project_id.as_ref(), Some(&copy.id));
+    let items = store.get_items_by_project(&copy.id).await.unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.section_id.as_ref() == Some(&section_copy.id)));
+    let task_copy = items.iter().find(|item| item.content == "task").unwrap();
+    let subtask_copy = items.iter().find(|item| item.content == "subtask").unwrap();
+    assert_eq!(subtask_copy.parent_id.as_ref(), Some(&task_copy.id));
+    assert_eq!(store.get_items_by_project(&client_copy.id).await.unwrap().len(), 1);
+
+    // 原项目树不变
+    assert_eq!(store.get_items_by_project(&work.id).await.unwrap().len(), 2);
+    assert!(store.duplicate_project("missing").await.is_err());
+}
@@ -0,0 +1,92 @@
+//! OEvents 活动记录查询的集成测试（内存 SQLite）
+
+mod common;
+
+use chrono::{Duration, Local};
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel},
+    services::EventKind,
+};
+
+async fn setup_store() -> std::sync::Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+#[tokio::test]
+async fn test_item_events_follow_triggers() {
+    let store = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Work".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let item = store
+        .insert_item(
+            ItemModel {
+                content: "Draft".to_string(),
+                project_id: Some(project.id.clone()),
+                priority: Some(1),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+
+    let renamed = ItemModel { content: "Draft report".to_string(), priority: Some(4), ..item };
+    let renamed = store.update_item(renamed, "").await.unwrap();
+    store.complete_item(&renamed.id, true, false).await.unwrap();
+
+    let events = store.get_events_by_item(&renamed.id).await.unwrap();
+    let kinds: Vec<EventKind> = events.iter().map(|e| e.kind).collect();
+    assert_eq!(kinds.last(), Some(&EventKind::Created), "oldest event is the insert");
+    assert_eq!(kinds.first(), Some(&EventKind::CheckedChanged), "newest event comes first");
+    assert!(kinds.contains(&EventKind::ContentChanged));
+    assert!(kinds.contains(&EventKind::PriorityChanged));
+
+    let content = events.iter().find(|e| e.kind == EventKind::ContentChanged).unwrap();
+    assert_eq!(content.old_value.as_deref(), Some("Draft"));
+    assert_eq!(content.new_value.as_deref(), Some("Draft report"));
+    assert!(content.event_date.is_some());
+}
+
+#[tokio::test]
+async fn test_project_and_range_queries() {
+    let store = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Home".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    for content in ["Sweep", "Mop", "Dust"] {
+        store
+            .insert_item(
+                ItemModel {
+                    content: content.to_string(),
+                    project_id: Some(project.id.clone()),
+                    ..Default::default()
+                },
+                true,
+            )
+            .await
+            .unwrap();
+    }
+    store
+        .insert_item(ItemModel { content: "Elsewhere".to_string(), ..Default::default() }, true)
+        .await
+        .unwrap();
+
+    assert_eq!(store.get_events_by_project(&project.id, None).await.unwrap().len(), 3);
+    assert_eq!(store.get_events_by_project(&project.id, Some(2)).await.unwrap().len(), 2);
+
+    let now = Local::now().naive_local();
+    let recent = store
+        .get_events_between(now - Duration::minutes(5), now + Duration::minutes(5))
+        .await
+        .unwrap();
+    assert_eq!(recent.len(), 4);
+    let past =
+        store.get_events_between(now - Duration::days(2), now - Duration::days(1)).await.unwrap();
+    assert!(past.is_empty());
+    assert!(store.get_events_between(now, now).await.is_err());
+}
@@ -0,0 +1,201 @@
+//! 导出（JSON / CSV / Markdown）的集成测试（内存 SQLite）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    DueDate, Store,
+    entity::{ItemModel, LabelModel, ProjectModel, SectionModel},
+    enums::RecurrencyType,
+    services::{ExportFormat, ExportService, WorkspaceExport},
+};
+
+struct Fixture {
+    store: Arc<Store>,
+    exporter: ExportService,
+    work_id: String,
+    client_id: String,
+}
+
+async fn setup() -> Fixture {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    let exporter = ExportService::new(Arc::new(db));
+
+    let work = store
+        .insert_project(ProjectModel {
+            name: "Work".to_string(),
+            child_order: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let client = store
+        .insert_project(ProjectModel {
+            name: "Client".to_string(),
+            parent_id: Some(work.id.clone()),
+            child_order: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let section = store
+        .insert_section(SectionModel {
+            name: "Next".to_string(),
+            project_id: Some(work.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "deep work".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let mut due = DueDate {
+        is_recurring: true,
+        recurrency_type: RecurrencyType::EveryWeek,
+        recurrency_interval: 2,
+        ..Default::default()
+    };
+    due.date = "2030-01-07 09:00:00".to_string();
+    let mut report = ItemModel {
+        content: "Weekly report".to_string(),
+        description: Some("Send to \"team\", cc boss".to_string()),
+        project_id: Some(work.id.clone()),
+        section_id: Some(section.id.clone()),
+        priority: Some(1),
+        child_order: Some(1),
+        labels: Some(label.id.clone()),
+        ..Default::default()
+    };
+    report.set_due_date(Some(due));
+    let report = store.insert_item(report, true).await.unwrap();
+
+    let subtask = ItemModel {
+        content: "Draft outline".to_string(),
+        project_id: Some(work.id.clone()),
+        section_id: Some(section.id.clone()),
+        parent_id: Some(report.id.clone()),
+        checked: true,
+        ..Default::default()
+    };
+    store.insert_item(subtask, true).await.unwrap();
+
+    for (content, project_id) in [
+        ("Loose end", Some(work.id.clone())),
+        ("Kickoff", Some(client.id.clone())),
+        ("Buy milk", None),
+    ] {
+        let item = ItemModel { content: content.to_string(), project_id, ..Default::default() };
+        store.insert_item(item, true).await.unwrap();
+    }
+    let trashed = ItemModel { content: "Trashed".to_string(), ..Default::default() };
+    let trashed = store.insert_item(trashed, true).await.unwrap();
+    store.delete_item(&trashed.id).await.unwrap();
+
+    Fixture { store, exporter, work_id: work.id, client_id: client.id }
+}
+
+#[tokio::test]
+async fn test_json_round_trip() {
+    let fixture = setup().await;
+    let json = fixture.exporter.export(ExportFormat::Json, None).await.unwrap();
+    let export = WorkspaceExport::from_json(&json).unwrap();
+
+    assert_eq!(
+        export,
+        fixture
+            .exporter
+            .snapshot(None)
+            .await
+            .map(|mut s| {
+                s.exported_at = export.exported_at;
+                s
+            })
+            .unwrap()
+    );
+    assert_eq!(export.projects.len(), 2);
+    assert_eq!(export.items.len(), 5, "trashed items are not exported");
+    assert_eq!(export.labels.len(), 1);
+    let report = export.items.iter().find(|i| i.content == "Weekly report").unwrap();
+    assert_eq!(report.labels.as_deref(), Some(export.labels[0].id.as_str()));
+    assert_eq!(report.due_date().unwrap().recurrency_interval, 2);
+
+    let newer = json.replacen("\"version\": 1", "\"version\": 99", 1);
+    assert!(WorkspaceExport::from_json(&newer).is_err());
+}
+
+#[tokio::test]
+async fn test_csv_one_row_per_item() {
+    let fixture = setup().await;
+    let csv = fixture.exporter.export(ExportFormat::Csv, None).await.unwrap();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(&headers[3], "project");
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 5);
+
+    let report = rows.iter().find(|r| &r[1] == "Weekly report").unwrap();
+    assert_eq!(&report[2], "Send to \"team\", cc boss");
+    assert_eq!((&report[3], &report[4], &report[6]), ("Work", "Next", "deep work"));
+    assert_eq!((&report[7], &report[8], &report[9]), ("1", "2030-01-07 09:00", "every 2 weeks"));
+
+    let kickoff = rows.iter().find(|r| &r[1] == "Kickoff").unwrap();
+    assert_eq!(&kickoff[3], "Work / Client");
+    let milk = rows.iter().find(|r| &r[1] == "Buy milk").unwrap();
+    assert_eq!(&milk[3], "Inbox");
+}
+
+#[tokio::test]
+async fn test_markdown_checklist() {
+    let fixture = setup().await;
+    let markdown = fixture.exporter.export(ExportFormat::Markdown, None).await.unwrap();
+    let expected = "\
+# Inbox
+
+- [ ] Buy milk
+
+# Work
+
+- [ ] Loose end
+
+## Next
+
+- [ ] Weekly report · due 2030-01-07 09:00 · every 2 weeks · p1 · @deep work
+  Send to \"team\", cc boss
+  - [x] Draft outline
+
+# Work / Client
+
+- [ ] Kickoff
+";
+    assert_eq!(markdown, expected);
+}
+
+#[tokio::test]
+async fn test_export_single_project() {
+    let fixture = setup().await;
+    let export = fixture.exporter.snapshot(Some(&fixture.work_id)).await.unwrap();
+    // 包含子项目，不含收件箱
+    assert_eq!(export.projects.len(), 2);
+    assert_eq!(export.items.len(), 4);
+    assert!(export.items.iter().all(|i| i.project_id.is_some()));
+
+    let client = fixture.exporter.snapshot(Some(&fixture.client_id)).await.unwrap();
+    assert_eq!(client.items.len(), 1);
+    assert!(client.labels.is_empty(), "only labels used in the project are exported");
+    assert_eq!(client.to_markdown(), "# Client\n\n- [ ] Kickoff\n");
+
+    assert!(fixture.exporter.snapshot(Some("missing")).await.is_err());
+
+    let path = std::env::temp_dir().join(format!("export-{}.md", fixture.client_id));
+    fixture
+        .store
+        .export_to_file(&path, ExportFormat::Markdown, Some(&fixture.client_id))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Client\n\n- [ ] Kickoff\n");
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,140 @@
+//! 过滤表达式求值与保存的过滤器的集成测试（内存 SQLite）
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use todos::{
+    DueDate, FilterIndex, FilterQuery, Store,
+    entity::{FilterModel, ItemModel},
+    error::TodoError,
+};
+
+/// 测试用数据源：项目 / 分区 / 标签名称直接写在任务上
+struct Fixture {
+    today: NaiveDate,
+    items: Vec<Arc<ItemModel>>,
+}
+
+impl Fixture {
+    fn by(&self, f: impl Fn(&ItemModel) -> bool) -> Vec<Arc<ItemModel>> {
+        self.items.iter().filter(|item| f(item)).cloned().collect()
+    }
+}
+
+impl FilterIndex for Fixture {
+    fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    fn open_items(&self) -> Vec<Arc<ItemModel>> {
+        self.by(|item| !item.checked)
+    }
+
+    fn items_in_project(&self, name: &str, include_subprojects: bool) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        self.by(|item| {
+            let project = item.project_id.as_deref().unwrap_or_default();
+            project == name || (include_subprojects && project.starts_with(&format!("{name}/")))
+        })
+    }
+
+    fn items_in_section(&self, name: &str) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        self.by(|item| item.section_id.as_deref() == Some(name.as_str()))
+    }
+
+    fn items_with_label(&self, name: &str) -> Vec<Arc<ItemModel>> {
+        let name = name.to_lowercase();
+        self.by(|item| item.labels.as_deref().is_some_and(|l| l.split(';').any(|l| l == name)))
+    }
+
+    fn pinned_items(&self) -> Vec<Arc<ItemModel>> {
+        self.by(|item| item.pinned)
+    }
+}
+
+fn item(id: &str, due: Option<&str>, priority: i32, project: &str, labels: &str) -> Arc<ItemModel> {
+    let mut item = ItemModel {
+        id: id.to_string(),
+        content: id.to_string(),
+        priority: Some(priority),
+        project_id: Some(project.to_string()),
+        labels: (!labels.is_empty()).then(|| labels.to_string()),
+        ..Default::default()
+    };
+    if let Some(date) = due {
+        item.set_due_date(Some(DueDate { date: date.to_string(), ..Default::default() }));
+    }
+    Arc::new(item)
+}
+
+fn fixture() -> Fixture {
+    Fixture {
+        today: NaiveDate::from_ymd_opt(2024, 5, 10).unwrap(),
+        items: vec![
+            item("report", Some("2024-05-10T09:00:00"), 1, "work", ""),
+            item("invoice", Some("2024-05-08T00:00:00"), 1, "work", "waiting"),
+            item("deploy", Some("2024-05-15T00:00:00"), 1, "work/infra", ""),
+            item("groceries", Some("2024-05-10T00:00:00"), 4, "home", ""),
+            item("someday", None, 1, "work", ""),
+            Arc::new(ItemModel {
+                checked: true,
+                ..item("done", Some("2024-05-10T00:00:00"), 1, "work", "").as_ref().clone()
+            }),
+        ],
+    }
+}
+
+fn ids(query: &str, fixture: &Fixture) -> Vec<String> {
+    let query = FilterQuery::parse(query).unwrap();
+    query.evaluate(fixture).iter().map(|item| item.id.clone()).collect()
+}
+
+#[test]
+fn test_evaluate_filter_expressions() {
+    let fixture = fixture();
+    assert_eq!(
+        ids("(today | overdue) & p1 & #work & !@waiting & due before: +7d", &fixture),
+        vec!["report"]
+    );
+    assert_eq!(ids("(today | overdue) & p1 & #work", &fixture), vec!["report", "invoice"]);
+    assert_eq!(ids("##work & p1 & next 7 days", &fixture), vec!["report", "deploy"]);
+    assert_eq!(ids("no date", &fixture), vec!["someday"]);
+    assert_eq!(ids("today", &fixture), vec!["report", "groceries"], "completed tasks excluded");
+    assert_eq!(ids("due after: yesterday & !##Work", &fixture), vec!["groceries"]);
+    assert_eq!(FilterQuery::parse("p1").unwrap().count(&fixture), 4);
+}
+
+#[tokio::test]
+async fn test_saved_filters_round_trip() {
+    let db = common::connect().await;
+    let store = Store::new(db).await.expect("create store");
+
+    let saved = store
+        .insert_filter(FilterModel {
+            name: "Urgent".to_string(),
+            query: "(today | overdue) & p1".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(!saved.id.is_empty());
+
+    let invalid = FilterModel { query: "today &".to_string(), ..saved.clone() };
+    assert!(matches!(store.update_filter(invalid).await, Err(TodoError::ValidationError(_))));
+    let unnamed =
+        FilterModel { name: " ".to_string(), query: "p1".to_string(), ..Default::default() };
+    assert!(matches!(store.insert_filter(unnamed).await, Err(TodoError::ValidationError(_))));
+
+    let renamed = FilterModel { name: "Urgent work".to_string(), ..saved.clone() };
+    store.update_filter(renamed).await.unwrap();
+    let filters = store.get_all_filters().await.unwrap();
+    assert_eq!(filters.len(), 1);
+    assert_eq!(filters[0].name, "Urgent work");
+
+    store.delete_filter(&saved.id).await.unwrap();
+    assert!(store.get_all_filters().await.unwrap().is_empty());
+    assert!(matches!(store.delete_filter(&saved.id).await, Err(TodoError::NotFound(_))));
+}
@@ -0,0 +1,212 @@
+//! iCalendar 导出 / 导入的集成测试（内存 SQLite）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    DueDate, Store,
+    entity::{ItemModel, LabelModel, ReminderModel},
+    enums::RecurrencyType,
+    services::{IcalService, IcsComponent},
+};
+
+async fn setup() -> (Arc<Store>, IcalService) {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    (store, IcalService::new(Arc::new(db)))
+}
+
+fn due(date: &str) -> DueDate {
+    DueDate { date: date.to_string(), ..Default::default() }
+}
+
+async fn seed(store: &Store) {
+    let label = store
+        .insert_label(LabelModel { name: "deep, work".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let mut weekly = due("2030-01-07 09:00:00");
+    weekly.is_recurring = true;
+    weekly.recurrency_type = RecurrencyType::EveryWeek;
+    weekly.recurrency_interval = 2;
+    weekly.recurrency_weeks = "1,3".to_string();
+    let mut report = ItemModel {
+        content: "Weekly report".to_string(),
+        description: Some("Send to team;\nthen relax".to_string()),
+        priority: Some(1),
+        labels: Some(label.id.clone()),
+        ..Default::default()
+    };
+    report.set_due_date(Some(weekly));
+    let report = store.insert_item(report, true).await.unwrap();
+    store
+        .insert_reminder(ReminderModel {
+            item_id: Some(report.id.clone()),
+            due: Some("2030-01-07 08:30:00".to_string()),
+            reminder_type: Some("time".to_string()),
+            mm_offset: Some(30),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let mut outline = ItemModel {
+        content: "Draft outline".to_string(),
+        parent_id: Some(report.id.clone()),
+        checked: true,
+        ..Default::default()
+    };
+    outline.set_due_date(Some(due("2030-01-05 00:00:00")));
+    store.insert_item(outline, true).await.unwrap();
+
+    let undated = ItemModel { content: "Someday".to_string(), ..Default::default() };
+    store.insert_item(undated, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_export_vtodo() {
+    let (store, ical) = setup().await;
+    seed(&store).await;
+    let ics = ical.export_ics(IcsComponent::Todo, None).await.unwrap();
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert_eq!(ics.matches("BEGIN:VTODO").count(), 2, "items without due date are skipped");
+    assert!(!ics.contains("Someday"));
+    for line in [
+        "SUMMARY:Weekly report",
+        "DESCRIPTION:Send to team\\;\\nthen relax",
+        "DTSTART:20300107T090000",
+        "DUE:20300107T090000",
+        "RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE",
+        "PRIORITY:1",
+        "CATEGORIES:deep\\, work",
+        "TRIGGER:-PT30M",
+        "DUE;VALUE=DATE:20300105",
+        "STATUS:COMPLETED",
+        "STATUS:NEEDS-ACTION",
+    ] {
+        assert!(ics.contains(&format!("{line}\r\n")), "missing {line} in\n{ics}");
+    }
+
+    let events = ical.export_ics(IcsComponent::Event, None).await.unwrap();
+    assert_eq!(events.matches("BEGIN:VEVENT").count(), 2);
+    assert!(events.contains("DTEND:20300107T100000\r\n"));
+    assert!(events.contains("DTEND;VALUE=DATE:20300106\r\n"));
+    assert!(!events.contains("STATUS:"));
+}
+
+#[tokio::test]
+async fn test_round_trip() {
+    let (store, ical) = setup().await;
+    seed(&store).await;
+    let ics = ical.export_ics(IcsComponent::Todo, None).await.unwrap();
+
+    let (target, target_ical) = setup().await;
+    let report = target_ical.import_ics_str(&ics).await.unwrap();
+    assert_eq!((report.items, report.labels, report.reminders), (2, 1, 1));
+    assert!(!report.has_issues(), "{:?}", report);
+
+    let items = target.get_all_items().await.unwrap();
+    let weekly = items.iter().find(|i| i.content == "Weekly report").unwrap();
+    assert_eq!(weekly.description.as_deref(), Some("Send to team;\nthen relax"));
+    assert_eq!(weekly.priority, Some(1));
+    assert_eq!(weekly.project_id, None);
+    let due = weekly.due_date().unwrap();
+    assert_eq!(due.date, "2030-01-07 09:00:00");
+    assert_eq!(
+        (due.recurrency_type, due.recurrency_interval, due.recurrency_weeks.as_str()),
+        (RecurrencyType::EveryWeek, 2, "1,3")
+    );
+    let labels = target.get_labels_by_item(&weekly.id).await.unwrap();
+    assert_eq!(labels[0].name, "deep, work");
+    let reminders = target.get_reminders_by_item(&weekly.id).await.unwrap();
+    assert_eq!(
+        (reminders[0].due.as_deref(), reminders[0].mm_offset),
+        (Some("2030-01-07 08:30:00"), Some(30))
+    );
+
+    let outline = items.iter().find(|i| i.content == "Draft outline").unwrap();
+    assert_eq!(outline.parent_id.as_deref(), Some(weekly.id.as_str()));
+    assert!(outline.checked);
+    assert_eq!(outline.due_date().unwrap().date, "2030-01-05 00:00:00");
+}
+
+#[tokio::test]
+async fn test_import_foreign_calendar() {
+    let (store, ical) = setup().await;
+    let ics = [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Example//Tasks//EN",
+        "BEGIN:VTIMEZONE",
+        "TZID:Europe/Berlin",
+        "END:VTIMEZONE",
+        "BEGIN:VTODO",
+        "UID:a1",
+        "SUMMARY:Pay rent",
+        "DUE;TZID=Europe/Berlin:20300201T100000",
+        "RRULE:FREQ=MONTHLY;BYMONTHDAY=1",
+        "PRIORITY:5",
+        "LOCATION:Bank",
+        "X-APPLE-SORT-ORDER:1",
+        "BEGIN:VALARM",
+        "ACTION:DISPLAY",
+        "TRIGGER;VALUE=DATE-TIME:20300201T090000",
+        "END:VALARM",
+        "BEGIN:VALARM",
+        "ACTION:DISPLAY",
+        "TRIGGER:PT15M",
+        "END:VALARM",
+        "END:VTODO",
+        "BEGIN:VTODO",
+        "UID:a2",
+        "SUMMARY:Filed taxes",
+        "STATUS:COMPLETED",
+        "COMPLETED:20240501T080000Z",
+        "END:VTODO",
+        "BEGIN:VEVENT",
+        "UID:e1",
+        "SUMMARY:Lunch",
+        "DTSTART:20300201T120000",
+        "END:VEVENT",
+        "END:VCALENDAR",
+    ]
+    .join("\n");
+    let report = ical.import_ics_str(&ics).await.unwrap();
+
+    assert_eq!((report.items, report.reminders), (2, 2));
+    assert_eq!(report.unmapped_fields.get("ics.VEVENT"), Some(&1));
+    assert_eq!(report.unmapped_fields.get("ics.VTODO.LOCATION"), Some(&1));
+    assert!(!report.unmapped_fields.keys().any(|field| field.contains("X-APPLE")));
+    assert!(report.skipped.iter().any(|s| s.contains("BYMONTHDAY")));
+
+    let items = store.get_all_items().await.unwrap();
+    let rent = items.iter().find(|i| i.content == "Pay rent").unwrap();
+    assert_eq!(rent.priority, Some(2));
+    let due = rent.due_date().unwrap();
+    assert_eq!(
+        (due.date.as_str(), due.timezone.as_str()),
+        ("2030-02-01 10:00:00", "Europe/Berlin")
+    );
+    assert!(!due.is_recurring);
+    let mut reminders: Vec<_> = store
+        .get_reminders_by_item(&rent.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| r.due)
+        .collect();
+    reminders.sort();
+    assert_eq!(reminders, vec!["2030-02-01 09:00:00", "2030-02-01 10:15:00"]);
+
+    let taxes = items.iter().find(|i| i.content == "Filed taxes").unwrap();
+    assert!(taxes.checked);
+    assert_eq!(taxes.due, None);
+    assert_eq!(taxes.completed_at.map(|at| at.to_string()).as_deref(), Some("2024-05-01 08:00:00"));
+
+    // 格式错误或没有任务时整体失败，不写入任何数据
+    assert!(ical.import_ics_str("BEGIN:VCALENDAR\nBEGIN:VTODO\nEND:VCALENDAR").await.is_err());
+    assert_eq!(store.get_all_items().await.unwrap().len(), 2);
+}
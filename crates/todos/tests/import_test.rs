@@ -0,0 +1,225 @@
+//! Todoist 备份导入的集成测试（内存 SQLite）
+
+mod common;
+
+use std::{
+    io::{Cursor, Write},
+    sync::Arc,
+};
+
+use serde_json::json;
+use todos::{
+    Store,
+    entity::LabelModel,
+    enums::RecurrencyType,
+    services::{ImportReport, ImportService},
+};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+async fn setup() -> (Arc<Store>, ImportService) {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    (store, ImportService::new(Arc::new(db)))
+}
+
+fn zip_backup(files: &[(&str, String)]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+        writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn sync_backup() -> String {
+    json!({
+        "sync_token": "abc",
+        "projects": [
+            {"id": "p-inbox", "name": "Inbox", "inbox_project": true},
+            {"id": "p-work", "name": "Work", "color": "berry_red", "child_order": 1},
+            {"id": "p-client", "name": "Client", "parent_id": "p-work", "is_favorite": true},
+            {"id": "p-old", "name": "Old", "is_deleted": true}
+        ],
+        "sections": [
+            {"id": "s-next", "name": "Next", "project_id": "p-work", "section_order": 1}
+        ],
+        "labels": [
+            {"id": "l-1", "name": "errand", "color": "green"}
+        ],
+        "items": [
+            {
+                "id": "i-report",
+                "content": "Weekly report",
+                "project_id": "p-work",
+                "section_id": "s-next",
+                "priority": 4,
+                "labels": ["errand", "deep work"],
+                "responsible_uid": "42",
+                "due": {
+                    "date": "2030-01-07T09:00:00",
+                    "string": "every monday 9am",
+                    "is_recurring": true,
+                    "timezone": null
+                }
+            },
+            {
+                "id": "i-draft",
+                "content": "Draft outline",
+                "project_id": "p-work",
+                "parent_id": "i-report",
+                "checked": true,
+                "completed_at": "2024-05-01T08:00:00.000000Z"
+            },
+            {
+                "id": "i-milk",
+                "content": "Buy milk",
+                "project_id": "p-inbox",
+                "due": {"date": "2030-01-01", "string": "every 2nd tuesday", "is_recurring": true}
+            }
+        ],
+        "reminders": [
+            {"id": "r-1", "item_id": "i-report", "type": "relative", "minute_offset": 30},
+            {"id": "r-2", "item_id": "i-report", "type": "location", "name": "Office"}
+        ],
+        "notes": [
+            {"id": "n-1", "item_id": "i-report", "content": "see attachment"}
+        ]
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_import_sync_json() {
+    let (store, importer) = setup().await;
+    let data = zip_backup(&[("todoist.json", sync_backup())]);
+    let report = importer.import_todoist_backup_bytes(&data).await.unwrap();
+
+    assert_eq!(
+        (report.projects, report.sections, report.items, report.labels, report.reminders),
+        (2, 1, 3, 2, 1)
+    );
+    assert_eq!(report.unmapped_fields.get("item.responsible_uid"), Some(&1));
+    assert_eq!(report.unmapped_fields.get("notes"), Some(&1));
+    assert!(report.skipped.iter().any(|s| s.contains("location")));
+    assert!(report.skipped.iter().any(|s| s.contains("every 2nd tuesday")));
+
+    let projects = store.get_all_projects().await.unwrap();
+    let work = projects.iter().find(|p| p.name == "Work").unwrap();
+    let client = projects.iter().find(|p| p.name == "Client").unwrap();
+    assert_eq!(client.parent_id.as_deref(), Some(work.id.as_str()));
+    assert_eq!(work.color.as_deref(), Some("#b8256f"));
+    assert!(projects.iter().all(|p| p.name != "Inbox" && p.name != "Old"));
+
+    let sections = store.get_all_sections().await.unwrap();
+    assert_eq!(sections[0].project_id.as_deref(), Some(work.id.as_str()));
+
+    let items = store.get_all_items().await.unwrap();
+    let report_item = items.iter().find(|i| i.content == "Weekly report").unwrap();
+    assert_eq!(report_item.project_id.as_deref(), Some(work.id.as_str()));
+    assert_eq!(report_item.section_id.as_deref(), Some(sections[0].id.as_str()));
+    assert_eq!(report_item.priority, Some(1));
+    let due = report_item.due_date().unwrap();
+    assert!(due.is_recurring);
+    assert_eq!(due.recurrency_type, RecurrencyType::EveryWeek);
+    assert_eq!(due.date, "2030-01-07 09:00:00");
+
+    let draft = items.iter().find(|i| i.content == "Draft outline").unwrap();
+    assert_eq!(draft.parent_id.as_deref(), Some(report_item.id.as_str()));
+    assert!(draft.checked);
+
+    // 收件箱任务没有项目；无法识别的重复规则按普通日期导入
+    let milk = items.iter().find(|i| i.content == "Buy milk").unwrap();
+    assert_eq!(milk.project_id, None);
+    assert!(!milk.due_date().unwrap().is_recurring);
+
+    let labels = store.get_labels_by_item(&report_item.id).await.unwrap();
+    let mut names: Vec<_> = labels.iter().map(|l| l.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["deep work", "errand"]);
+
+    let reminders = store.get_reminders_by_item(&report_item.id).await.unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].due.as_deref(), Some("2030-01-07 08:30:00"));
+}
+
+#[tokio::test]
+async fn test_import_csv_backup() {
+    let (store, importer) = setup().await;
+    // 已有同名标签时直接复用
+    store
+        .insert_label(LabelModel { name: "Errand".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let work = [
+        "\u{feff}TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,\
+         TIMEZONE,DURATION,DURATION_UNIT",
+        "section,Planning,,,,,,,,,,",
+        "task,Plan sprint @errand,Backlog first,1,1,Ann (1),,every day,en,UTC,30,minute",
+        "task,Collect tickets,,4,2,Ann (1),,,en,,,None",
+        "note,Remember the retro,,,,Ann (1),,,,,,",
+        ",,,,,,,,,,,",
+        "task,Ship it,,2,1,Ann (1),,someday maybe,en,,,None",
+    ]
+    .join("\n");
+    let inbox = "TYPE,CONTENT,PRIORITY,INDENT\ntask,Call mom,4,1\n";
+    let data = zip_backup(&[
+        ("Work [2203306141].csv", work),
+        ("Inbox.csv", inbox.to_string()),
+        ("readme.txt", "hi".to_string()),
+    ]);
+    let report = importer.import_todoist_backup_bytes(&data).await.unwrap();
+
+    assert_eq!((report.projects, report.sections, report.items, report.labels), (1, 1, 4, 0));
+    assert_eq!(report.unmapped_fields.get("csv.AUTHOR"), Some(&3));
+    assert_eq!(report.unmapped_fields.get("csv.DURATION"), Some(&1));
+    assert_eq!(report.unmapped_fields.get("csv.note"), Some(&1));
+    assert!(report.skipped.iter().any(|s| s.contains("someday maybe")));
+    assert!(report.skipped.iter().any(|s| s.contains("readme.txt")));
+
+    let projects = store.get_all_projects().await.unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].name, "Work");
+
+    let items = store.get_all_items().await.unwrap();
+    let plan = items.iter().find(|i| i.content == "Plan sprint").unwrap();
+    assert_eq!(plan.description.as_deref(), Some("Backlog first"));
+    assert_eq!(plan.priority, Some(1));
+    assert!(plan.section_id.is_some());
+    let due = plan.due_date().unwrap();
+    assert_eq!((due.recurrency_type, due.timezone.as_str()), (RecurrencyType::EveryDay, "UTC"));
+    let labels = store.get_labels_by_item(&plan.id).await.unwrap();
+    assert_eq!(labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["Errand"]);
+
+    let tickets = items.iter().find(|i| i.content == "Collect tickets").unwrap();
+    assert_eq!(tickets.parent_id.as_deref(), Some(plan.id.as_str()));
+    let ship = items.iter().find(|i| i.content == "Ship it").unwrap();
+    assert_eq!(ship.parent_id, None);
+    assert_eq!(ship.due, None);
+
+    let call = items.iter().find(|i| i.content == "Call mom").unwrap();
+    assert_eq!(call.project_id, None);
+}
+
+#[tokio::test]
+async fn test_import_is_atomic() {
+    let (store, importer) = setup().await;
+    // 重复的任务 ID 会在写入时冲突，之前写入的项目也要回滚
+    let backup = json!({
+        "projects": [{"id": "p-1", "name": "Work"}],
+        "items": [
+            {"id": "i-1", "content": "One", "project_id": "p-1"},
+            {"id": "i-1", "content": "Two", "project_id": "p-1"}
+        ]
+    });
+    let data = zip_backup(&[("projects.json", backup.to_string())]);
+    assert!(importer.import_todoist_backup_bytes(&data).await.is_err());
+    assert!(store.get_all_projects().await.unwrap().is_empty());
+    assert!(store.get_all_items().await.unwrap().is_empty());
+
+    let report = importer.import_todoist_backup_bytes(b"not a zip").await;
+    assert!(report.is_err());
+    let empty = zip_backup(&[("notes.txt", String::new())]);
+    assert!(importer.import_todoist_backup_bytes(&empty).await.is_err());
+    assert!(!ImportReport::default().summary().contains("跳过"));
+}
@@ -0,0 +1,196 @@
+//! 完整性检查与修复测试（内存 SQLite）
+
+mod common;
+
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use todos::{
+    Store,
+    entity::{ItemModel, LabelModel, ProjectModel, SectionModel},
+    services::{IntegrityFix, IntegrityIssueKind},
+};
+
+async fn setup_store() -> (Arc<Store>, DatabaseConnection) {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    (store, db)
+}
+
+async fn project(store: &Store, name: &str, inbox: bool) -> ProjectModel {
+    store
+        .insert_project(ProjectModel {
+            name: name.to_string(),
+            inbox_project: inbox.then_some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+async fn item(store: &Store, content: &str, project_id: &str) -> ItemModel {
+    store
+        .insert_item(
+            ItemModel {
+                content: content.to_string(),
+                project_id: Some(project_id.to_string()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap()
+}
+
+async fn exec(db: &DatabaseConnection, sql: String) {
+    db.execute_unprepared(&sql).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_clean_database_has_no_issues() {
+    let (store, _db) = setup_store().await;
+    let work = project(&store, "Work", false).await;
+    let section = store
+        .insert_section(SectionModel {
+            name: "Backlog".to_string(),
+            project_id: Some(work.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let parent = item(&store, "Parent", &work.id).await;
+    store
+        .insert_item(
+            ItemModel {
+                content: "Child".to_string(),
+                project_id: Some(work.id.clone()),
+                section_id: Some(section.id.clone()),
+                parent_id: Some(parent.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "urgent".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.set_item_labels(&parent.id, std::slice::from_ref(&label.id)).await.unwrap();
+    // 回收站中的父任务仍然算存在
+    store.delete_item(&parent.id).await.unwrap();
+
+    let report = store.check_integrity().await.unwrap();
+    assert!(report.is_clean(), "{:?}", report.issues);
+}
+
+#[tokio::test]
+async fn test_detects_and_repairs_broken_references() {
+    let (store, db) = setup_store().await;
+    let inbox = project(&store, "Inbox", true).await;
+    let work = project(&store, "Work", false).await;
+    let home = project(&store, "Home", false).await;
+    let home_section = store
+        .insert_section(SectionModel {
+            name: "Garden".to_string(),
+            project_id: Some(home.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let orphan = item(&store, "Orphan", &work.id).await;
+    let first = item(&store, "First", &work.id).await;
+    let second = item(&store, "Second", &work.id).await;
+    let lost = item(&store, "Lost", &work.id).await;
+    let misplaced = item(&store, "Misplaced", &work.id).await;
+    let labelled = item(&store, "Labelled", &work.id).await;
+
+    exec(&db, format!("UPDATE items SET parent_id = 'gone' WHERE id = '{}'", orphan.id)).await;
+    exec(&db, format!("UPDATE items SET parent_id = '{}' WHERE id = '{}'", second.id, first.id))
+        .await;
+    exec(&db, format!("UPDATE items SET parent_id = '{}' WHERE id = '{}'", first.id, second.id))
+        .await;
+    exec(&db, format!("UPDATE items SET project_id = 'gone' WHERE id = '{}'", lost.id)).await;
+    exec(
+        &db,
+        format!(
+            "UPDATE items SET section_id = '{}' WHERE id = '{}'",
+            home_section.id, misplaced.id
+        ),
+    )
+    .await;
+    exec(&db, format!("UPDATE items SET labels = 'stale' WHERE id = '{}'", labelled.id)).await;
+    exec(&db, format!("UPDATE projects SET parent_id = '{}' WHERE id = '{}'", work.id, work.id))
+        .await;
+
+    let report = store.check_integrity().await.unwrap();
+    assert_eq!(report.count(IntegrityIssueKind::OrphanedItemParent), 1);
+    assert_eq!(report.count(IntegrityIssueKind::CyclicItemParent), 1);
+    assert_eq!(report.count(IntegrityIssueKind::OrphanedItemProject), 1);
+    assert_eq!(report.count(IntegrityIssueKind::SectionProjectMismatch), 1);
+    assert_eq!(report.count(IntegrityIssueKind::CyclicProjectParent), 1);
+    assert_eq!(report.count(IntegrityIssueKind::LabelDrift), 1);
+    assert_eq!(report.issues.len(), 6);
+
+    let lost_issue = report
+        .issues
+        .iter()
+        .find(|issue| issue.kind == IntegrityIssueKind::OrphanedItemProject)
+        .unwrap();
+    assert_eq!(
+        lost_issue.fix,
+        Some(IntegrityFix::MoveItemToProject { item_id: lost.id.clone(), project_id: inbox.id })
+    );
+
+    // 修复是可选的：只修复父任务问题
+    let selected: Vec<_> = report
+        .fixable()
+        .into_iter()
+        .filter(|issue| {
+            matches!(
+                issue.kind,
+                IntegrityIssueKind::OrphanedItemParent | IntegrityIssueKind::CyclicItemParent
+            )
+        })
+        .collect();
+    assert_eq!(store.repair_integrity(&selected).await.unwrap(), 2);
+    let report = store.check_integrity().await.unwrap();
+    assert_eq!(report.issues.len(), 4);
+    assert_eq!(store.get_item(&orphan.id).await.unwrap().parent_id, None);
+
+    assert_eq!(store.repair_integrity(&report.fixable()).await.unwrap(), 4);
+    assert!(store.check_integrity().await.unwrap().is_clean());
+    assert_eq!(store.get_item(&misplaced.id).await.unwrap().section_id, None);
+}
+
+#[tokio::test]
+async fn test_foreign_key_violations_in_link_tables() {
+    let (store, db) = setup_store().await;
+    let work = project(&store, "Work", false).await;
+    // PostgreSQL 始终检查外键，无法写入违反外键的行
+    if common::is_postgres(&db) {
+        return;
+    }
+    let task = item(&store, "Task", &work.id).await;
+    exec(&db, "PRAGMA foreign_keys = OFF".to_string()).await;
+    exec(
+        &db,
+        format!("INSERT INTO item_labels (item_id, label_id) VALUES ('{}', 'missing')", task.id),
+    )
+    .await;
+    exec(&db, format!("UPDATE items SET labels = 'missing' WHERE id = '{}'", task.id)).await;
+
+    let report = store.check_integrity().await.unwrap();
+    assert_eq!(report.count(IntegrityIssueKind::ForeignKey), 1);
+    let issue = &report.issues[0];
+    assert_eq!(issue.object_id, "item_labels");
+    assert!(matches!(issue.fix, Some(IntegrityFix::DeleteRow { .. })));
+
+    store.repair_integrity(&report.fixable()).await.unwrap();
+    // 删除失效的关联后，labels 字段与关联表又不一致了，再修一次
+    let report = store.check_integrity().await.unwrap();
+    assert_eq!(report.count(IntegrityIssueKind::LabelDrift), 1);
+    store.repair_integrity(&report.fixable()).await.unwrap();
+    assert!(store.check_integrity().await.unwrap().is_clean());
+}
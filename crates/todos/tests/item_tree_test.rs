@@ -0,0 +1,280 @@
+//! 子任务树测试（缩进、取消缩进、移动子树、跨项目移动、折叠）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel, SectionModel},
+    services::ItemPosition,
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+async fn project(store: &Store, name: &str) -> ProjectModel {
+    store
+        .insert_project(ProjectModel { name: name.to_string(), ..Default::default() })
+        .await
+        .unwrap()
+}
+
+async fn item(store: &Store, content: &str, project_id: &str, parent: Option<&str>) -> ItemModel {
+    let item = store
+        .insert_item(
+            ItemModel {
+                content: content.to_string(),
+                project_id: Some(project_id.to_string()),
+                parent_id: parent.map(str::to_string),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    // 按插入顺序排好，便于断言
+    store.move_item(&item.id, ItemPosition::Bottom).await.unwrap();
+    store.get_item(&item.id).await.unwrap()
+}
+
+async fn parent_of(store: &Store, id: &str) -> Option<String> {
+    store.get_item(id).await.unwrap().parent_id
+}
+
+#[tokio::test]
+async fn test_get_item_tree() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let root = item(&store, "root", &work.id, None).await;
+    let b = item(&store, "b", &work.id, Some(&root.id)).await;
+    let a = item(&store, "a", &work.id, Some(&root.id)).await;
+    item(&store, "a child", &work.id, Some(&a.id)).await;
+    store.move_item(&a.id, ItemPosition::Before(b.id.clone())).await.unwrap();
+
+    let tree = store.get_item_tree(&root.id).await.unwrap();
+    assert_eq!(tree.item.id, root.id);
+    let children: Vec<&str> = tree.children.iter().map(|node| node.item.content.as_str()).collect();
+    assert_eq!(children, ["a", "b"]);
+    assert_eq!(tree.children[0].children[0].item.content, "a child");
+
+    // 已删除的子任务不出现在树中
+    store.delete_item(&b.id).await.unwrap();
+    let tree = store.get_item_tree(&root.id).await.unwrap();
+    assert_eq!(tree.children.len(), 1);
+    assert!(store.get_item_tree("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_indent_and_outdent() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let a = item(&store, "a", &work.id, None).await;
+    let b = item(&store, "b", &work.id, None).await;
+    let c = item(&store, "c", &work.id, None).await;
+    let b_child = item(&store, "b child", &work.id, Some(&b.id)).await;
+
+    // 第一个任务前面没有兄弟任务，不能缩进
+    assert!(store.indent_item(&a.id).await.is_err());
+
+    let changed = store.indent_item(&b.id).await.unwrap();
+    assert_eq!(changed[0].id, b.id);
+    assert_eq!(parent_of(&store, &b.id).await, Some(a.id.clone()));
+    // 子任务随父任务移动，父任务关系不变
+    assert_eq!(parent_of(&store, &b_child.id).await, Some(b.id.clone()));
+
+    // c 缩进后排在 b 之后
+    store.indent_item(&c.id).await.unwrap();
+    let tree = store.get_item_tree(&a.id).await.unwrap();
+    let children: Vec<&str> = tree.children.iter().map(|node| node.item.content.as_str()).collect();
+    assert_eq!(children, ["b", "c"]);
+
+    // 取消缩进后紧跟在原父任务之后
+    store.outdent_item(&b.id).await.unwrap();
+    assert_eq!(parent_of(&store, &b.id).await, None);
+    let mut top: Vec<ItemModel> = store
+        .get_items_by_project(&work.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|item| item.parent_id.is_none())
+        .collect();
+    top.sort_by(|x, y| x.cmp_child_order(y));
+    let top: Vec<&str> = top.iter().map(|item| item.content.as_str()).collect();
+    assert_eq!(top, ["a", "b"]);
+
+    assert!(store.outdent_item(&a.id).await.is_err(), "top-level items cannot be outdented");
+}
+
+#[tokio::test]
+async fn test_move_subtree_prevents_cycles() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let a = item(&store, "a", &work.id, None).await;
+    let child = item(&store, "child", &work.id, Some(&a.id)).await;
+    let grandchild = item(&store, "grandchild", &work.id, Some(&child.id)).await;
+
+    let err = store.move_subtree(&a.id, Some(&grandchild.id), ItemPosition::Bottom).await;
+    assert!(err.is_err(), "moving under a descendant must be rejected");
+    let err = store.move_subtree(&a.id, Some(&a.id), ItemPosition::Bottom).await;
+    assert!(err.is_err(), "moving under itself must be rejected");
+    assert_eq!(parent_of(&store, &a.id).await, None);
+
+    // 移到顶层
+    store.move_subtree(&grandchild.id, None, ItemPosition::Top).await.unwrap();
+    assert_eq!(parent_of(&store, &grandchild.id).await, None);
+}
+
+#[tokio::test]
+async fn test_move_subtree_across_projects() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let home = project(&store, "Home").await;
+    let section = store
+        .insert_section(SectionModel {
+            name: "Errands".to_string(),
+            project_id: Some(home.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let target = store
+        .insert_item(
+            ItemModel {
+                content: "target".to_string(),
+                project_id: Some(home.id.clone()),
+                section_id: Some(section.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    let a = item(&store, "a", &work.id, None).await;
+    let child = item(&store, "child", &work.id, Some(&a.id)).await;
+
+    let changed = store.move_subtree(&a.id, Some(&target.id), ItemPosition::Bottom).await.unwrap();
+    assert!(changed.iter().any(|item| item.id == child.id));
+
+    for id in [&a.id, &child.id] {
+        let moved = store.get_item(id).await.unwrap();
+        assert_eq!(moved.project_id.as_deref(), Some(home.id.as_str()));
+        assert_eq!(moved.section_id.as_deref(), Some(section.id.as_str()));
+    }
+    assert_eq!(parent_of(&store, &child.id).await, Some(a.id.clone()));
+}
+
+#[tokio::test]
+async fn test_set_item_collapsed() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let a = item(&store, "a", &work.id, None).await;
+
+    let updated = store.set_item_collapsed(&a.id, true).await.unwrap();
+    assert!(updated.collapsed);
+    assert!(store.get_item(&a.id).await.unwrap().collapsed);
+    store.set_item_collapsed(&a.id, false).await.unwrap();
+    assert!(!store.get_item(&a.id).await.unwrap().collapsed);
+    assert!(store.set_item_collapsed("missing", true).await.is_err());
+}
+
+#[tokio::test]
+async fn test_move_items_with_subtrees() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let home = project(&store, "Home").await;
+    let work_section = store
+        .insert_section(SectionModel {
+            name: "Backlog".to_string(),
+            project_id: Some(work.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let a = item(&store, "a", &work.id, None).await;
+    let a_child = item(&store, "a child", &work.id, Some(&a.id)).await;
+    let a_grandchild = item(&store, "a grandchild", &work.id, Some(&a_child.id)).await;
+    let b = store
+        .insert_item(
+            ItemModel {
+                content: "b".to_string(),
+                project_id: Some(work.id.clone()),
+                section_id: Some(work_section.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    let b_child = item(&store, "b child", &work.id, Some(&b.id)).await;
+
+    // a_child 的祖先也在列表中，只随 a 移动
+    let ids = vec![a.id.clone(), a_child.id.clone(), b_child.id.clone(), b.id.clone()];
+    let changed = store.move_items(&ids, Some(&home.id), None).await.unwrap();
+    for id in [&a.id, &a_child.id, &a_grandchild.id, &b.id, &b_child.id] {
+        assert!(changed.iter().any(|item| &item.id == id));
+        let moved = store.get_item(id).await.unwrap();
+        assert_eq!(moved.project_id.as_deref(), Some(home.id.as_str()));
+        // 旧项目的分区不能留在新项目的任务上
+        assert_eq!(moved.section_id, None);
+    }
+    assert_eq!(parent_of(&store, &a.id).await, None);
+    assert_eq!(parent_of(&store, &a_child.id).await, Some(a.id.clone()));
+    assert_eq!(parent_of(&store, &a_grandchild.id).await, Some(a_child.id.clone()));
+    // b_child 的父任务也被移动，保持层级
+    assert_eq!(parent_of(&store, &b_child.id).await, Some(b.id.clone()));
+
+    // 按给定顺序排在目标列表末尾
+    let mut top: Vec<ItemModel> = store
+        .get_items_by_project(&home.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|item| item.parent_id.is_none())
+        .collect();
+    top.sort_by(|x, y| x.cmp_child_order(y));
+    let top: Vec<&str> = top.iter().map(|item| item.content.as_str()).collect();
+    assert_eq!(top, ["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_move_items_into_section_and_inbox() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let home = project(&store, "Home").await;
+    let errands = store
+        .insert_section(SectionModel {
+            name: "Errands".to_string(),
+            project_id: Some(home.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let a = item(&store, "a", &work.id, None).await;
+    let child = item(&store, "child", &work.id, Some(&a.id)).await;
+    let ids = vec![a.id.clone()];
+
+    // 分区必须属于目标项目
+    let err = store.move_items(&ids, Some(&work.id), Some(&errands.id)).await;
+    assert!(err.is_err(), "section from another project must be rejected");
+    assert!(store.move_items(&ids, Some("missing"), None).await.is_err());
+    assert!(store.move_items(&["missing".to_string()], Some(&home.id), None).await.is_err());
+    assert_eq!(store.get_item(&a.id).await.unwrap().project_id.as_deref(), Some(work.id.as_str()));
+
+    store.move_items(&ids, Some(&home.id), Some(&errands.id)).await.unwrap();
+    for id in [&a.id, &child.id] {
+        let moved = store.get_item(id).await.unwrap();
+        assert_eq!(moved.project_id.as_deref(), Some(home.id.as_str()));
+        assert_eq!(moved.section_id.as_deref(), Some(errands.id.as_str()));
+    }
+
+    // 移到收件箱
+    store.move_items(&ids, None, None).await.unwrap();
+    for id in [&a.id, &child.id] {
+        let moved = store.get_item(id).await.unwrap();
+        assert_eq!(moved.project_id, None);
+        assert_eq!(moved.section_id, None);
+    }
+}
@@ -0,0 +1,166 @@
+//! 写入日志的集成测试：内存 SQLite + 临时目录中的日志文件
+
+mod common;
+
+use std::{path::PathBuf, sync::Arc};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use todos::{JournalOp, JournalStatus, Store, WriteJournal, entity::ItemModel, error::TodoError};
+
+struct Fixture {
+    db: DatabaseConnection,
+    store: Arc<Store>,
+    path: PathBuf,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let db = common::connect().await;
+        let store = Store::new(db.clone()).await.expect("create store");
+        let path = std::env::temp_dir()
+            .join(format!("journal-{}", uuid::Uuid::new_v4()))
+            .join("todos.db.pending.jsonl");
+        Self { db, store, path }
+    }
+
+    fn journal(&self) -> WriteJournal {
+        WriteJournal::open(&self.path).unwrap()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        if let Some(dir) = self.path.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+fn busy() -> TodoError {
+    TodoError::DatabaseError("database is locked".to_string())
+}
+
+fn item(id: &str, content: &str) -> ItemModel {
+    ItemModel { id: id.to_string(), content: content.to_string(), ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_journal_survives_restart_and_replays_in_order() {
+    let fixture = Fixture::new().await;
+    let existing = fixture.store.insert_item(item("", "Existing"), true).await.unwrap();
+
+    let journal = fixture.journal();
+    assert!(journal.is_empty());
+    let parent =
+        JournalOp::AddItem { temp_id: Some("temp_1".to_string()), item: item("", "Parent") };
+    journal.append(parent, &busy()).unwrap();
+    let mut child = item("", "Child");
+    child.parent_id = Some("temp_1".to_string());
+    journal
+        .append(JournalOp::AddItem { temp_id: Some("temp_2".to_string()), item: child }, &busy())
+        .unwrap();
+    // 修改还没有落盘的新任务：合并到新建中
+    journal
+        .append(JournalOp::UpdateItem { item: item("temp_1", "Parent renamed") }, &busy())
+        .unwrap();
+    let mut renamed = existing.clone();
+    renamed.content = "Existing renamed".to_string();
+    journal.append(JournalOp::UpdateItem { item: renamed }, &busy()).unwrap();
+    assert_eq!(journal.pending_count(), 3);
+    assert!(journal.has_pending_for("temp_1"));
+    assert!(journal.has_pending_for(&existing.id));
+
+    // 重新打开（模拟重启）后按顺序重放
+    let journal = fixture.journal();
+    assert_eq!(journal.entries().len(), 3);
+    assert_eq!(journal.entries()[0].op.item().content, "Parent renamed");
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert_eq!(report.saved.len(), 3);
+    assert_eq!((report.pending, report.failed.len(), report.error), (0, 0, None));
+    assert!(journal.is_empty());
+    assert!(!fixture.path.exists());
+
+    let parent = &report.saved[0];
+    assert_eq!(parent.temp_id.as_deref(), Some("temp_1"));
+    assert!(!parent.item.id.starts_with("temp_"));
+    let child = fixture.store.get_item(&report.saved[1].item.id).await.unwrap();
+    assert_eq!(child.parent_id.as_deref(), Some(parent.item.id.as_str()));
+    let stored = fixture.store.get_item(&existing.id).await.unwrap();
+    assert_eq!(stored.content, "Existing renamed");
+}
+
+#[tokio::test]
+async fn test_failed_writes_wait_for_the_user() {
+    let fixture = Fixture::new().await;
+    let journal = fixture.journal();
+    journal.append(JournalOp::UpdateItem { item: item("missing", "Gone") }, &busy()).unwrap();
+    journal
+        .append(
+            JournalOp::AddItem { temp_id: Some("temp_1".to_string()), item: item("", "New") },
+            &busy(),
+        )
+        .unwrap();
+
+    // 任务已不存在：标记为失败，后面的写入继续
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert_eq!(report.saved.len(), 1);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(journal.failed_count(), 1);
+    assert_eq!(fixture.journal().entries()[0].status, JournalStatus::Failed);
+
+    assert_eq!(journal.retry_failed().unwrap(), 1);
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert_eq!(report.failed.len(), 1);
+    let discarded = journal.discard_failed().unwrap();
+    assert_eq!(discarded[0].op.item().id, "missing");
+    assert!(journal.is_empty());
+}
+
+#[tokio::test]
+async fn test_retryable_error_stops_replay() {
+    let fixture = Fixture::new().await;
+    let existing = fixture.store.insert_item(item("", "Existing"), true).await.unwrap();
+    let journal = fixture.journal();
+    let mut renamed = existing.clone();
+    renamed.content = "First".to_string();
+    journal.append(JournalOp::UpdateItem { item: renamed.clone() }, &busy()).unwrap();
+    renamed.content = "Second".to_string();
+    journal.append(JournalOp::UpdateItem { item: renamed }, &busy()).unwrap();
+
+    // 数据库不可用：停在第一条，顺序不变
+    fixture.db.execute_unprepared("ALTER TABLE items RENAME TO items_offline").await.unwrap();
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert!(report.saved.is_empty());
+    assert_eq!(report.pending, 2);
+    assert!(report.error.is_some());
+    let entries = journal.entries();
+    assert_eq!((entries[0].attempts, entries[1].attempts), (1, 0));
+
+    fixture.db.execute_unprepared("ALTER TABLE items_offline RENAME TO items").await.unwrap();
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert_eq!(report.saved.len(), 2);
+    assert_eq!(fixture.store.get_item(&existing.id).await.unwrap().content, "Second");
+
+    let entry = journal.append(JournalOp::UpdateItem { item: existing }, &busy()).unwrap();
+    assert_eq!(journal.discard(&entry.id).unwrap().map(|e| e.id), Some(entry.id));
+    assert!(journal.is_empty());
+}
+
+#[tokio::test]
+async fn test_in_memory_journal_replays_without_a_file() {
+    let fixture = Fixture::new().await;
+    let journal = WriteJournal::in_memory();
+    assert!(journal.path().is_none());
+    journal
+        .append(
+            JournalOp::AddItem { temp_id: Some("temp_1".to_string()), item: item("", "Offline") },
+            &busy(),
+        )
+        .unwrap();
+    assert_eq!(journal.pending_count(), 1);
+
+    let report = journal.replay(&fixture.store).await.unwrap();
+    assert_eq!(report.saved.len(), 1);
+    assert!(journal.is_empty());
+    assert!(!fixture.path.exists());
+}
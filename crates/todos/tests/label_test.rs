@@ -0,0 +1,170 @@
+//! 标签管理测试（重命名、合并、排序、收藏、使用次数）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    Store,
+    entity::{ItemModel, LabelModel},
+    error::TodoError,
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+async fn label(store: &Store, name: &str) -> LabelModel {
+    store.insert_label(LabelModel { name: name.to_string(), ..Default::default() }).await.unwrap()
+}
+
+async fn tagged_item(store: &Store, content: &str, labels: &[&LabelModel]) -> ItemModel {
+    let item = store
+        .insert_item(ItemModel { content: content.to_string(), ..Default::default() }, true)
+        .await
+        .unwrap();
+    let ids: Vec<String> = labels.iter().map(|label| label.id.clone()).collect();
+    store.set_item_labels(&item.id, &ids).await.unwrap();
+    item
+}
+
+#[tokio::test]
+async fn test_rename_label_conflicts() {
+    let store = setup_store().await;
+    let work = label(&store, "work").await;
+    let home = label(&store, "home").await;
+    let old = label(&store, "old").await;
+    store.delete_label(&old.id).await.unwrap();
+
+    let renamed = store.rename_label(&work.id, "  Office ").await.unwrap();
+    assert_eq!(renamed.name, "Office");
+    // 只改大小写不算冲突
+    assert_eq!(store.rename_label(&work.id, "OFFICE").await.unwrap().name, "OFFICE");
+
+    let conflict = store.rename_label(&work.id, "Home").await.unwrap_err();
+    assert!(matches!(conflict, TodoError::AlreadyExists(_)), "{conflict:?}");
+    // 回收站中的同名标签同样冲突
+    assert!(matches!(
+        store.rename_label(&home.id, "old").await.unwrap_err(),
+        TodoError::AlreadyExists(_)
+    ));
+    assert!(store.rename_label(&home.id, " ").await.is_err());
+    assert!(store.rename_label(&old.id, "restored").await.is_err(), "trashed label");
+    assert!(store.rename_label("missing", "x").await.is_err());
+}
+
+#[tokio::test]
+async fn test_merge_labels() {
+    let store = setup_store().await;
+    let todo = label(&store, "todo").await;
+    let tasks = label(&store, "tasks").await;
+    let urgent = label(&store, "urgent").await;
+    store.set_label_favorite(&todo.id, true).await.unwrap();
+
+    let only_source = tagged_item(&store, "only source", &[&todo, &urgent]).await;
+    let both = tagged_item(&store, "both", &[&tasks, &todo]).await;
+    let only_target = tagged_item(&store, "only target", &[&tasks]).await;
+
+    let merge = store.merge_labels(&todo.id, &tasks.id).await.unwrap();
+    assert_eq!(merge.label.id, tasks.id);
+    assert!(merge.label.is_favorite, "favorite carries over");
+    let mut changed = merge.item_ids.clone();
+    changed.sort();
+    let mut expected = vec![only_source.id.clone(), both.id.clone()];
+    expected.sort();
+    assert_eq!(changed, expected);
+
+    let label_ids = |labels: Vec<LabelModel>| -> Vec<String> {
+        labels.into_iter().map(|label| label.id).collect()
+    };
+    let mut source_labels = label_ids(store.get_labels_by_item(&only_source.id).await.unwrap());
+    source_labels.sort();
+    let mut expected = vec![tasks.id.clone(), urgent.id.clone()];
+    expected.sort();
+    assert_eq!(source_labels, expected);
+    assert_eq!(label_ids(store.get_labels_by_item(&both.id).await.unwrap()), [tasks.id.as_str()]);
+    let target_labels = store.get_labels_by_item(&only_target.id).await.unwrap();
+    assert_eq!(label_ids(target_labels), [tasks.id.as_str()]);
+
+    // 冗余的 labels 字段保持原有顺序
+    let item = store.get_item(&only_source.id).await.unwrap();
+    assert_eq!(item.labels, Some(format!("{};{}", tasks.id, urgent.id)));
+    let item = store.get_item(&both.id).await.unwrap();
+    assert_eq!(item.labels, Some(tasks.id.clone()));
+
+    // 源标签进入回收站
+    let names: Vec<String> =
+        store.get_all_labels().await.unwrap().into_iter().map(|label| label.name).collect();
+    assert!(!names.contains(&"todo".to_string()));
+    assert_eq!(store.get_label_usage().await.unwrap()[&tasks.id], 3);
+
+    assert!(store.merge_labels(&tasks.id, &tasks.id).await.is_err());
+    assert!(store.merge_labels(&todo.id, &tasks.id).await.is_err(), "source already merged");
+}
+
+#[tokio::test]
+async fn test_reorder_and_favorite_labels() {
+    let store = setup_store().await;
+    let a = label(&store, "a").await;
+    let b = label(&store, "b").await;
+    let c = label(&store, "c").await;
+
+    let reordered =
+        store.reorder_labels(&[c.id.clone(), a.id.clone(), b.id.clone()]).await.unwrap();
+    let orders: Vec<i32> = reordered.iter().map(|label| label.item_order).collect();
+    assert_eq!(orders, [0, 1, 2]);
+    let names: Vec<String> =
+        store.get_all_labels().await.unwrap().into_iter().map(|label| label.name).collect();
+    assert_eq!(names, ["c", "a", "b"]);
+
+    assert!(store.reorder_labels(&[a.id.clone(), "missing".to_string()]).await.is_err());
+    // 失败时整体回滚
+    let names: Vec<String> =
+        store.get_all_labels().await.unwrap().into_iter().map(|label| label.name).collect();
+    assert_eq!(names, ["c", "a", "b"]);
+
+    assert!(store.set_label_favorite(&b.id, true).await.unwrap().is_favorite);
+    assert!(!store.set_label_favorite(&b.id, false).await.unwrap().is_favorite);
+}
+
+#[tokio::test]
+async fn test_label_usage() {
+    let store = setup_store().await;
+    let work = label(&store, "work").await;
+    let home = label(&store, "home").await;
+    let unused = label(&store, "unused").await;
+
+    tagged_item(&store, "one", &[&work]).await;
+    let done = tagged_item(&store, "two", &[&work, &home]).await;
+    store.complete_item(&done.id, true, false).await.unwrap();
+    let removed = tagged_item(&store, "three", &[&home]).await;
+    store.delete_item(&removed.id).await.unwrap();
+
+    let usage = store.get_label_usage().await.unwrap();
+    assert_eq!(usage.get(&work.id), Some(&2), "completed tasks count");
+    assert_eq!(usage.get(&home.id), Some(&1), "deleted tasks do not");
+    assert_eq!(usage.get(&unused.id), None);
+}
+
+#[tokio::test]
+async fn test_reusing_trashed_label_name_drops_old_associations() {
+    let store = setup_store().await;
+    let family = label(&store, "family").await;
+    let old_task = tagged_item(&store, "call mom", &[&family]).await;
+    store.delete_label(&family.id).await.unwrap();
+    let new_task = tagged_item(&store, "book flights", &[]).await;
+
+    store.add_label_to_item(&new_task.id, "family").await.unwrap();
+
+    // 名称唯一：取回的是回收站中的标签，但删除前的关联不会回来
+    let labels = store.get_all_labels().await.unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].id, family.id);
+    assert!(store.list_trash().await.unwrap().is_empty());
+    let new_labels = store.get_labels_by_item(&new_task.id).await.unwrap();
+    assert_eq!(new_labels.len(), 1);
+    assert_eq!(new_labels[0].id, family.id);
+    assert!(store.get_labels_by_item(&old_task.id).await.unwrap().is_empty());
+    assert_eq!(store.get_item(&old_task.id).await.unwrap().labels, None);
+}
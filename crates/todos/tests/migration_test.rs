@@ -0,0 +1,161 @@
+//! 迁移框架测试：事务内应用、校验和、预览、回滚与版本检查
+
+mod common;
+
+use std::sync::Arc;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement};
+use todos::{PatchManager, Store, entity::AttachmentModel};
+
+async fn setup() -> Arc<DatabaseConnection> {
+    Arc::new(common::connect().await)
+}
+
+// PostgreSQL 中未加引号的表名折叠为小写
+async fn table_exists(db: &DatabaseConnection, name: &str) -> bool {
+    let sql = if common::is_postgres(db) {
+        format!(
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = \
+             current_schema() AND table_name = '{}'",
+            name.to_lowercase()
+        )
+    } else {
+        format!("SELECT name FROM sqlite_master WHERE type = 'table' AND name = '{}'", name)
+    };
+    db.query_one(Statement::from_string(db.get_database_backend(), sql)).await.unwrap().is_some()
+}
+
+#[tokio::test]
+async fn test_apply_records_checksums() {
+    let db = setup().await;
+    let manager = PatchManager::new(db.clone());
+
+    // 预览不修改数据库
+    let pending = manager.dry_run().await.unwrap();
+    assert_eq!(pending.len(), manager.patches().len());
+    assert_eq!(pending[0].version, 1);
+    assert!(!table_exists(&db, "db_version").await);
+
+    manager.apply_patches().await.unwrap();
+    assert_eq!(manager.get_current_version().await.unwrap(), manager.latest_version());
+    assert!(manager.dry_run().await.unwrap().is_empty());
+
+    let applied = manager.applied_patches().await.unwrap();
+    assert_eq!(applied.len(), manager.patches().len());
+    for (applied, patch) in applied.iter().zip(manager.patches()) {
+        assert_eq!(applied.version, patch.version);
+        assert_eq!(applied.checksum.as_deref(), Some(patch.checksum().as_str()));
+        assert_eq!(applied.description.as_deref(), Some(patch.description));
+    }
+
+    // 再次应用不重复执行
+    manager.apply_patches().await.unwrap();
+    assert_eq!(manager.applied_patches().await.unwrap().len(), manager.patches().len());
+}
+
+#[tokio::test]
+async fn test_checksum_backfill_and_mismatch() {
+    let db = setup().await;
+    let manager = PatchManager::new(db.clone());
+    manager.apply_patches().await.unwrap();
+
+    // 加入校验和之前应用的补丁：补写
+    db.execute_unprepared("UPDATE db_version SET checksum = NULL, description = NULL")
+        .await
+        .unwrap();
+    assert!(manager.verify_checksums().await.unwrap().is_empty());
+    assert!(manager.applied_patches().await.unwrap().iter().all(|a| a.checksum.is_some()));
+
+    // 应用后被修改的脚本
+    db.execute_unprepared("UPDATE db_version SET checksum = 'tampered' WHERE version = 2")
+        .await
+        .unwrap();
+    assert_eq!(manager.verify_checksums().await.unwrap(), vec![2]);
+    let err = manager.apply_patches().await.unwrap_err();
+    assert!(matches!(err, DbErr::Migration(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_legacy_version_table_is_upgraded() {
+    let db = setup().await;
+    let id_column = if common::is_postgres(&db) {
+        "id SERIAL PRIMARY KEY"
+    } else {
+        "id INTEGER PRIMARY KEY AUTOINCREMENT"
+    };
+    db.execute_unprepared(&format!(
+        "CREATE TABLE db_version ({}, version INTEGER NOT NULL, applied_at TIMESTAMP DEFAULT \
+         CURRENT_TIMESTAMP)",
+        id_column
+    ))
+    .await
+    .unwrap();
+
+    let manager = PatchManager::new(db.clone());
+    manager.apply_patches().await.unwrap();
+    let applied = manager.applied_patches().await.unwrap();
+    assert_eq!(applied.len(), manager.patches().len());
+    assert!(applied.iter().all(|a| a.checksum.is_some()));
+}
+
+#[tokio::test]
+async fn test_rollback_and_reapply() {
+    let db = setup().await;
+    let store = Store::new((*db).clone()).await.unwrap();
+    let item = store.insert_item(Default::default(), true).await.unwrap();
+    store
+        .insert_attachment(AttachmentModel {
+            item_id: item.id.clone(),
+            file_name: "notes.txt".to_string(),
+            file_size: 2048,
+            file_path: "/tmp/notes.txt".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let manager = PatchManager::new(db.clone());
+    let plan = manager.rollback_plan(3).await.unwrap();
+    let versions: Vec<i32> = plan.iter().map(|step| step.version).collect();
+    assert_eq!(versions, vec![7, 6, 5, 4]);
+    // 预览不执行
+    assert!(table_exists(&db, "Filters").await);
+
+    assert_eq!(manager.rollback_to(3).await.unwrap(), vec![7, 6, 5, 4]);
+    assert_eq!(manager.get_current_version().await.unwrap(), 3);
+    assert!(!table_exists(&db, "Filters").await);
+    assert!(!table_exists(&db, "cur_temp_ids").await);
+    assert!(table_exists(&db, "CurTempIds").await);
+
+    manager.apply_patches().await.unwrap();
+    assert_eq!(manager.get_current_version().await.unwrap(), manager.latest_version());
+    assert!(table_exists(&db, "Filters").await);
+    assert_eq!(store.get_all_items().await.unwrap().len(), 1);
+    // 附件大小在列类型来回转换后保持不变
+    let attachments = store.get_attachments_by_item(&item.id).await.unwrap();
+    assert_eq!(attachments[0].file_size, 2048);
+
+    // 全部回滚只留下 db_version
+    manager.rollback_to(0).await.unwrap();
+    assert_eq!(manager.get_current_version().await.unwrap(), 0);
+    assert!(!table_exists(&db, "Items").await);
+    assert!(!table_exists(&db, "search_index").await);
+}
+
+#[tokio::test]
+async fn test_refuses_newer_database() {
+    let db = setup().await;
+    let manager = PatchManager::new(db.clone());
+    manager.apply_patches().await.unwrap();
+    db.execute_unprepared(&format!(
+        "INSERT INTO db_version (version) VALUES ({})",
+        manager.latest_version() + 1
+    ))
+    .await
+    .unwrap();
+
+    let err = manager.check_compatible().await.unwrap_err();
+    assert!(err.to_string().contains("请升级程序"));
+    assert!(manager.apply_patches().await.is_err());
+    assert!(Store::new((*db).clone()).await.is_err());
+}
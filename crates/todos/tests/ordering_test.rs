@@ -0,0 +1,186 @@
+//! 手动排序测试（child_order / day_order）
+
+mod common;
+
+use std::sync::Arc;
+
+use todos::{
+    DueDate, Store,
+    entity::{ItemModel, ProjectModel, SectionModel},
+    services::{ItemPosition, ORDER_GAP},
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+async fn project(store: &Store, name: &str) -> ProjectModel {
+    store
+        .insert_project(ProjectModel { name: name.to_string(), ..Default::default() })
+        .await
+        .unwrap()
+}
+
+async fn insert(store: &Store, item: ItemModel) -> ItemModel {
+    store.insert_item(item, true).await.unwrap()
+}
+
+async fn item(store: &Store, content: &str, project_id: &str) -> ItemModel {
+    insert(store, ItemModel {
+        content: content.to_string(),
+        project_id: Some(project_id.to_string()),
+        ..Default::default()
+    })
+    .await
+}
+
+async fn due_item(store: &Store, content: &str, project_id: &str, date: &str) -> ItemModel {
+    let mut item = ItemModel {
+        content: content.to_string(),
+        project_id: Some(project_id.to_string()),
+        ..Default::default()
+    };
+    item.set_due_date(Some(DueDate { date: date.to_string(), ..Default::default() }));
+    insert(store, item).await
+}
+
+/// 项目中顶层任务按 child_order 排好后的内容
+async fn project_order(store: &Store, project_id: &str) -> Vec<String> {
+    let mut items: Vec<ItemModel> = store
+        .get_items_by_project(project_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|item| item.section_id.is_none() && item.parent_id.is_none())
+        .collect();
+    items.sort_by(|a, b| a.cmp_child_order(b));
+    items.into_iter().map(|item| item.content).collect()
+}
+
+#[tokio::test]
+async fn test_move_item_positions() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let a = item(&store, "a", &work.id).await;
+    let b = item(&store, "b", &work.id).await;
+    let c = item(&store, "c", &work.id).await;
+    let d = item(&store, "d", &work.id).await;
+    assert_eq!(project_order(&store, &work.id).await, ["a", "b", "c", "d"]);
+
+    store.move_item(&c.id, ItemPosition::Top).await.unwrap();
+    assert_eq!(project_order(&store, &work.id).await, ["c", "a", "b", "d"]);
+
+    store.move_item(&a.id, ItemPosition::Bottom).await.unwrap();
+    assert_eq!(project_order(&store, &work.id).await, ["c", "b", "d", "a"]);
+
+    store.move_item(&d.id, ItemPosition::Before(c.id.clone())).await.unwrap();
+    assert_eq!(project_order(&store, &work.id).await, ["d", "c", "b", "a"]);
+
+    store.move_item(&b.id, ItemPosition::After(a.id.clone())).await.unwrap();
+    assert_eq!(project_order(&store, &work.id).await, ["d", "c", "a", "b"]);
+}
+
+#[tokio::test]
+async fn test_move_item_writes_single_row_when_gap_available() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let a = item(&store, "a", &work.id).await;
+    let b = item(&store, "b", &work.id).await;
+    let c = item(&store, "c", &work.id).await;
+
+    let changed = store.move_item(&a.id, ItemPosition::Top).await.unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].child_order, Some(ORDER_GAP));
+
+    let changed = store.move_item(&b.id, ItemPosition::After(a.id.clone())).await.unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].child_order, Some(2 * ORDER_GAP));
+
+    let changed = store.move_item(&c.id, ItemPosition::Before(b.id.clone())).await.unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].id, c.id);
+    assert_eq!(changed[0].child_order, Some(ORDER_GAP + ORDER_GAP / 2));
+    assert_eq!(project_order(&store, &work.id).await, ["a", "c", "b"]);
+}
+
+#[tokio::test]
+async fn test_move_item_renumbers_when_gap_exhausted() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let mut a = item(&store, "a", &work.id).await;
+    let mut b = item(&store, "b", &work.id).await;
+    let c = item(&store, "c", &work.id).await;
+    a.child_order = Some(1);
+    b.child_order = Some(2);
+    let a = store.update_item(a, "").await.unwrap();
+    store.update_item(b, "").await.unwrap();
+
+    let changed = store.move_item(&c.id, ItemPosition::After(a.id.clone())).await.unwrap();
+    assert!(changed.len() > 1);
+    assert_eq!(project_order(&store, &work.id).await, ["a", "c", "b"]);
+
+    let orders: Vec<Option<i32>> = store
+        .get_items_by_project(&work.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|item| item.child_order)
+        .collect();
+    assert!(orders.iter().all(|order| order.is_some_and(|order| order % ORDER_GAP == 0)));
+}
+
+#[tokio::test]
+async fn test_move_item_scoped_to_section() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let home = project(&store, "Home").await;
+    let section = store
+        .insert_section(SectionModel {
+            name: "Backlog".to_string(),
+            project_id: Some(work.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let top = item(&store, "top", &work.id).await;
+    let in_section = insert(&store, ItemModel {
+        content: "in section".to_string(),
+        project_id: Some(work.id.clone()),
+        section_id: Some(section.id.clone()),
+        ..Default::default()
+    })
+    .await;
+    let elsewhere = item(&store, "elsewhere", &home.id).await;
+
+    let err = store.move_item(&top.id, ItemPosition::Before(in_section.id.clone())).await;
+    assert!(err.is_err(), "anchor in another section must be rejected");
+    let err = store.move_item(&top.id, ItemPosition::After(elsewhere.id.clone())).await;
+    assert!(err.is_err(), "anchor in another project must be rejected");
+    assert!(store.move_item("missing", ItemPosition::Top).await.is_err());
+}
+
+#[tokio::test]
+async fn test_move_item_in_day() {
+    let store = setup_store().await;
+    let work = project(&store, "Work").await;
+    let home = project(&store, "Home").await;
+    let report = due_item(&store, "report", &work.id, "2024-05-10T09:00:00").await;
+    let groceries = due_item(&store, "groceries", &home.id, "2024-05-10T00:00:00").await;
+    let later = due_item(&store, "later", &work.id, "2024-05-11T00:00:00").await;
+    let undated = item(&store, "undated", &work.id).await;
+
+    store.move_item_in_day(&groceries.id, ItemPosition::Top).await.unwrap();
+    let changed = store
+        .move_item_in_day(&report.id, ItemPosition::Before(groceries.id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(changed.len(), 1);
+    assert!(changed[0].day_order.unwrap() < ORDER_GAP);
+    // child_order 不受影响
+    assert_eq!(store.get_item(&report.id).await.unwrap().child_order, None);
+
+    let err = store.move_item_in_day(&later.id, ItemPosition::After(report.id.clone())).await;
+    assert!(err.is_err(), "items due on another day are not in the same list");
+    assert!(store.move_item_in_day(&undated.id, ItemPosition::Top).await.is_err());
+}
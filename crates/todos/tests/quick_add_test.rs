@@ -0,0 +1,172 @@
+//! 快速添加解析与保存的集成测试（内存 SQLite）
+
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use todos::{
+    QuickAdd, QuickAddIndex, QuickAddTokenKind, Store,
+    entity::{ItemModel, LabelModel},
+    enums::RecurrencyType,
+};
+
+/// 测试用数据源：名称即 ID 的小写形式
+struct Fixture;
+
+impl QuickAddIndex for Fixture {
+    fn project_id(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        ["finance", "side project"].contains(&name.as_str()).then_some(name)
+    }
+
+    fn section(&self, project_id: Option<&str>, name: &str) -> Option<(String, Option<String>)> {
+        let name = name.to_lowercase();
+        match (project_id, name.as_str()) {
+            (None | Some("finance"), "bills") => {
+                Some(("bills".to_string(), Some("finance".to_string())))
+            },
+            _ => None,
+        }
+    }
+
+    fn label_id(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        ["home", "errand"].contains(&name.as_str()).then_some(name)
+    }
+}
+
+fn now() -> NaiveDateTime {
+    // 2024-05-10 是星期五
+    NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(10, 0, 0).unwrap()
+}
+
+fn parse(source: &str) -> QuickAdd {
+    QuickAdd::parse(source, now(), &Fixture)
+}
+
+fn due_date(source: &str) -> String {
+    parse(source).due.map(|due| due.date).unwrap_or_default()
+}
+
+#[test]
+fn test_parse_full_example() {
+    let source = "Pay rent every month on the 1st 9am p1 #Finance /Bills @home";
+    let parsed = parse(source);
+    assert_eq!(parsed.content, "Pay rent");
+    assert_eq!(parsed.priority, Some(1));
+    assert_eq!(parsed.project_id.as_deref(), Some("finance"));
+    assert_eq!(parsed.section_id.as_deref(), Some("bills"));
+    assert_eq!(parsed.label_ids, vec!["home"]);
+
+    let due = parsed.due.unwrap();
+    assert_eq!(due.date, "2024-06-01 09:00:00");
+    assert!(due.is_recurring);
+    assert_eq!(due.recurrency_type, RecurrencyType::EveryMonth);
+    assert_eq!(due.recurrency_interval, 1);
+
+    let highlighted: Vec<(QuickAddTokenKind, &str)> =
+        parsed.tokens.iter().map(|token| (token.kind, &source[token.range.clone()])).collect();
+    assert_eq!(highlighted, vec![
+        (QuickAddTokenKind::Date, "every month on the 1st"),
+        (QuickAddTokenKind::Date, "9am"),
+        (QuickAddTokenKind::Priority, "p1"),
+        (QuickAddTokenKind::Project, "#Finance"),
+        (QuickAddTokenKind::Section, "/Bills"),
+        (QuickAddTokenKind::Label, "@home"),
+    ]);
+}
+
+#[test]
+fn test_parse_dates() {
+    assert_eq!(due_date("a today"), "2024-05-10 00:00:00");
+    assert_eq!(due_date("a tom 9:30pm"), "2024-05-11 21:30:00");
+    assert_eq!(due_date("a monday"), "2024-05-13 00:00:00");
+    assert_eq!(due_date("a fri"), "2024-05-10 00:00:00");
+    assert_eq!(due_date("a next friday"), "2024-05-17 00:00:00");
+    assert_eq!(due_date("a next week"), "2024-05-13 00:00:00");
+    assert_eq!(due_date("a next month"), "2024-06-01 00:00:00");
+    assert_eq!(due_date("a in 3 days"), "2024-05-13 00:00:00");
+    assert_eq!(due_date("a in 2 months"), "2024-07-10 00:00:00");
+    assert_eq!(due_date("a jan 5"), "2025-01-05 00:00:00");
+    assert_eq!(due_date("a 20th may at 14:00"), "2024-05-20 14:00:00");
+    assert_eq!(due_date("a 2024-05-01"), "2024-05-01 00:00:00");
+    assert_eq!(due_date("a on the 31st"), "2024-05-31 00:00:00");
+    // 只写时间且已过去，顺延到明天
+    assert_eq!(due_date("a 9am"), "2024-05-11 09:00:00");
+    assert_eq!(due_date("a today 9am"), "2024-05-10 09:00:00");
+}
+
+#[test]
+fn test_parse_recurrence() {
+    let due = parse("Standup every weekday").due;
+    assert!(due.is_none(), "unsupported units stay in the content");
+
+    let due = parse("Water plants every other day 9am").due.unwrap();
+    assert_eq!((due.recurrency_type, due.recurrency_interval), (RecurrencyType::EveryDay, 2));
+    // 今天 9:00 已过，从下一次开始
+    assert_eq!(due.date, "2024-05-12 09:00:00");
+
+    let due = parse("Gym every tuesday").due.unwrap();
+    assert_eq!(
+        (due.recurrency_type, due.date.as_str()),
+        (RecurrencyType::EveryWeek, "2024-05-14 00:00:00")
+    );
+
+    let due = parse("Taxes yearly").due.unwrap();
+    assert_eq!(due.recurrency_type, RecurrencyType::EveryYear);
+    assert_eq!(due.date, "2024-05-10 00:00:00");
+}
+
+#[test]
+fn test_parse_names_and_leftovers() {
+    let parsed = parse("Plan #Side Project launch @errand @unknown #Finance p2 p3");
+    assert_eq!(parsed.content, "Plan launch @unknown #Finance p3");
+    assert_eq!(parsed.project_id.as_deref(), Some("side project"));
+    assert_eq!(parsed.label_ids, vec!["errand"]);
+    assert_eq!(parsed.priority, Some(2));
+
+    // 没有项目时取分区所属的项目
+    let parsed = parse("Electricity /bills");
+    assert_eq!(parsed.project_id.as_deref(), Some("finance"));
+    assert_eq!(parsed.section_id.as_deref(), Some("bills"));
+
+    let parsed = parse("Buy 2 apples");
+    assert_eq!(parsed.content, "Buy 2 apples");
+    assert!(!parsed.has_attributes());
+}
+
+#[test]
+fn test_apply_merges_labels_and_resets_section() {
+    let mut item = ItemModel {
+        project_id: Some("inbox".to_string()),
+        section_id: Some("someday".to_string()),
+        labels: Some("errand".to_string()),
+        ..Default::default()
+    };
+    parse("Call bank #Finance @home @errand").apply(&mut item);
+    assert_eq!(item.content, "Call bank");
+    assert_eq!(item.project_id.as_deref(), Some("finance"));
+    assert_eq!(item.section_id, None);
+    assert_eq!(item.labels.as_deref(), Some("errand;home"));
+    assert_eq!(item.priority, None);
+}
+
+#[tokio::test]
+async fn test_insert_item_persists_quick_add_labels() {
+    let db = common::connect().await;
+    let store = Store::new(db).await.expect("create store");
+    let label = store
+        .insert_label(LabelModel { name: "home".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let mut item = ItemModel::default();
+    parse("Fix the sink @home").apply(&mut item);
+    // 测试数据源的 ID 即名称，这里换成真实的标签 ID
+    item.labels = Some(label.id.clone());
+    let saved = store.insert_item(item, true).await.unwrap();
+
+    assert_eq!(saved.content, "Fix the sink");
+    assert_eq!(saved.labels.as_deref(), Some(label.id.as_str()));
+    let labels = store.get_labels_by_item(&saved.id).await.unwrap();
+    assert_eq!(labels.iter().map(|l| l.id.as_str()).collect::<Vec<_>>(), vec![label.id.as_str()]);
+}
@@ -0,0 +1,110 @@
+//! 重复任务完成后滚动到下一次到期日期的集成测试（内存 SQLite）
+
+mod common;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use todos::{DueDate, Store, entity::ItemModel, enums::RecurrencyType};
+
+async fn setup_store() -> (std::sync::Arc<Store>, DatabaseConnection) {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    (store, db)
+}
+
+fn recurring_item(content: &str, date: &str, count: i64) -> ItemModel {
+    let mut item = ItemModel { content: content.to_string(), ..Default::default() };
+    item.set_due_date(Some(DueDate {
+        date: date.to_string(),
+        is_recurring: true,
+        recurrency_type: RecurrencyType::EveryDay,
+        recurrency_interval: 1,
+        recurrency_count: count,
+        ..Default::default()
+    }));
+    item
+}
+
+#[tokio::test]
+async fn test_complete_recurring_item_rolls_forward() {
+    let (store, db) = setup_store().await;
+    let parent = store
+        .insert_item(recurring_item("Water plants", "2025-02-22 09:00:00", 0), true)
+        .await
+        .unwrap();
+    let child = store
+        .insert_item(
+            ItemModel {
+                content: "Fill can".to_string(),
+                parent_id: Some(parent.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    store.complete_item(&child.id, true, false).await.unwrap();
+
+    store.complete_item(&parent.id, true, false).await.unwrap();
+
+    let parent = store.get_item(&parent.id).await.unwrap();
+    assert!(!parent.checked);
+    assert!(parent.completed_at.is_none());
+    assert_eq!(parent.due_date().unwrap().date, "2025-02-23 09:00:00");
+    assert!(!store.get_item(&child.id).await.unwrap().checked);
+
+    let logged = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            format!(
+                "SELECT COUNT(*) AS cnt FROM OEvents WHERE event_type = 'complete' AND object_id \
+                 = '{}'",
+                parent.id
+            ),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get::<i64>("", "cnt")
+        .unwrap();
+    assert_eq!(logged, 1);
+}
+
+#[tokio::test]
+async fn test_complete_recurring_item_last_occurrence() {
+    let (store, _db) = setup_store().await;
+    let item = store
+        .insert_item(recurring_item("Pay rent", "2025-02-22 09:00:00", 1), true)
+        .await
+        .unwrap();
+
+    store.complete_item(&item.id, true, false).await.unwrap();
+
+    let item = store.get_item(&item.id).await.unwrap();
+    assert!(item.checked);
+    assert_eq!(item.due_date().unwrap().date, "2025-02-22 09:00:00");
+}
+
+#[tokio::test]
+async fn test_complete_weekly_item_rolls_to_next_weekday() {
+    let (store, _db) = setup_store().await;
+    let mut item = ItemModel { content: "Team sync".to_string(), ..Default::default() };
+    // 2025-02-24 是周一；每两周的周一和周三
+    item.set_due_date(Some(DueDate {
+        date: "2025-02-24 09:00:00".to_string(),
+        is_recurring: true,
+        recurrency_type: RecurrencyType::EveryWeek,
+        recurrency_interval: 2,
+        recurrency_weeks: "1,3".to_string(),
+        ..Default::default()
+    }));
+    let item = store.insert_item(item, true).await.unwrap();
+
+    store.complete_item(&item.id, true, false).await.unwrap();
+    let rolled = store.get_item(&item.id).await.unwrap();
+    assert_eq!(rolled.due_date().unwrap().date, "2025-02-26 09:00:00");
+
+    store.complete_item(&item.id, true, false).await.unwrap();
+    let rolled = store.get_item(&item.id).await.unwrap();
+    assert!(!rolled.checked);
+    assert_eq!(rolled.due_date().unwrap().date, "2025-03-10 09:00:00");
+}
@@ -0,0 +1,154 @@
+//! 全文搜索（FTS5 索引 + 触发器同步）的集成测试（内存 SQLite）
+
+mod common;
+
+use todos::{
+    Store,
+    entity::{ItemModel, LabelModel, ProjectModel, SectionModel},
+    services::{DEFAULT_SEARCH_LIMIT, SearchObjectType, TrashObjectType},
+};
+
+async fn setup_store() -> std::sync::Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+#[tokio::test]
+async fn test_search_ranks_and_highlights_across_objects() {
+    let store = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Groceries".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let section = store
+        .insert_section(SectionModel {
+            name: "Dairy".to_string(),
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let item = store
+        .insert_item(
+            ItemModel {
+                content: "Buy milk".to_string(),
+                description: Some("Oat milk if the store is out of regular".to_string()),
+                project_id: Some(project.id.clone()),
+                section_id: Some(section.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    store
+        .insert_item(
+            ItemModel {
+                content: "Call the bank".to_string(),
+                description: Some("Ask about milk money".to_string()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    store
+        .insert_label(LabelModel { name: "errands".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let hits = store.search("MILK", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    assert_eq!(hits.len(), 2);
+    // 标题命中权重更高
+    let top = &hits[0];
+    assert_eq!(top.id, item.id);
+    assert_eq!(top.object_type, SearchObjectType::Item);
+    assert_eq!(top.project_id.as_deref(), Some(project.id.as_str()));
+    assert_eq!(top.section_id.as_deref(), Some(section.id.as_str()));
+    assert_eq!(&top.title.text[top.title.ranges[0].clone()], "milk");
+    let snippet = top.snippet.as_ref().expect("description matched");
+    assert_eq!(&snippet.text[snippet.ranges[0].clone()], "milk");
+    assert!(hits[1].snippet.is_some());
+
+    let hits = store.search("dairy", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    assert_eq!(hits[0].object_type, SearchObjectType::Section);
+    assert_eq!(hits[0].project_id.as_deref(), Some(project.id.as_str()));
+
+    let hits = store.search("err", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    assert_eq!(hits[0].object_type, SearchObjectType::Label);
+
+    // 多个词必须同时命中；短词走 LIKE
+    let hits = store.search("buy mi", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, item.id);
+    assert!(store.search("   ", DEFAULT_SEARCH_LIMIT).await.unwrap().is_empty());
+    // FTS5 语法字符按普通文本处理
+    assert!(store.search("\"milk OR", DEFAULT_SEARCH_LIMIT).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_search_index_follows_updates_and_trash() {
+    let store = setup_store().await;
+    let item = store
+        .insert_item(ItemModel { content: "整理季度报告".to_string(), ..Default::default() }, true)
+        .await
+        .unwrap();
+
+    let hits = store.search("报告", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(&hits[0].title.text[hits[0].title.ranges[0].clone()], "报告");
+    assert_eq!(store.search("季度报告", DEFAULT_SEARCH_LIMIT).await.unwrap().len(), 1);
+
+    let renamed = ItemModel { content: "Prepare quarterly review".to_string(), ..item };
+    let renamed = store.update_item(renamed, "").await.unwrap();
+    assert!(store.search("报告", DEFAULT_SEARCH_LIMIT).await.unwrap().is_empty());
+    assert_eq!(store.search("quarterly", DEFAULT_SEARCH_LIMIT).await.unwrap().len(), 1);
+
+    store.delete_item(&renamed.id).await.unwrap();
+    assert!(store.search("quarterly", DEFAULT_SEARCH_LIMIT).await.unwrap().is_empty());
+
+    store.restore_from_trash(TrashObjectType::Item, &renamed.id).await.unwrap();
+    assert_eq!(store.search("quarterly", DEFAULT_SEARCH_LIMIT).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_search_excludes_archived_projects() {
+    let store = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Garden plans".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store
+        .insert_section(SectionModel {
+            name: "Garden beds".to_string(),
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let open = ItemModel {
+        content: "Water the garden".to_string(),
+        project_id: Some(project.id.clone()),
+        ..Default::default()
+    };
+    store.insert_item(open.clone(), true).await.unwrap();
+    let done = store
+        .insert_item(ItemModel { content: "Buy garden hose".to_string(), ..open }, true)
+        .await
+        .unwrap();
+    store.complete_item(&done.id, true, false).await.unwrap();
+    store
+        .insert_item(ItemModel { content: "Garden gloves".to_string(), ..Default::default() }, true)
+        .await
+        .unwrap();
+    assert_eq!(store.search("garden", DEFAULT_SEARCH_LIMIT).await.unwrap().len(), 5);
+
+    // 归档后项目、分区和其中的任务（包括已完成的）都不再出现
+    store.archive_project(&project.id).await.unwrap();
+    let hits = store.search("garden", DEFAULT_SEARCH_LIMIT).await.unwrap();
+    let titles: Vec<&str> = hits.iter().map(|hit| hit.title.text.as_str()).collect();
+    assert_eq!(titles, ["Garden gloves"]);
+
+    store.unarchive_project(&project.id).await.unwrap();
+    assert_eq!(store.search("garden", DEFAULT_SEARCH_LIMIT).await.unwrap().len(), 5);
+}
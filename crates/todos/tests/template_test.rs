@@ -0,0 +1,225 @@
+//! 项目模板测试（保存模板、按开始日期实例化、模板文件）
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use todos::{
+    DueDate, ProjectTemplate, Store, TemplateReminder,
+    entity::{ItemModel, LabelModel, ProjectModel, ReminderModel, SectionModel},
+};
+
+async fn setup_store() -> Arc<Store> {
+    let db = common::connect().await;
+    Store::new(db).await.expect("create store")
+}
+
+fn date(value: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+}
+
+fn datetime(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+}
+
+async fn item(
+    store: &Store,
+    content: &str,
+    project_id: &str,
+    section_id: Option<&str>,
+    parent_id: Option<&str>,
+    due: Option<NaiveDateTime>,
+) -> ItemModel {
+    let mut model = ItemModel {
+        content: content.to_string(),
+        project_id: Some(project_id.to_string()),
+        section_id: section_id.map(str::to_string),
+        parent_id: parent_id.map(str::to_string),
+        ..Default::default()
+    };
+    model.set_due_date(due.map(|due| {
+        let mut due_date = DueDate::default();
+        due_date.set_datetime(due);
+        due_date
+    }));
+    store.insert_item(model, true).await.unwrap()
+}
+
+/// 发布流程：准备（第 0 天）、发布（第 3 天 10:00，提前一小时提醒）、子任务、已完成任务
+async fn launch_project(store: &Store) -> ProjectModel {
+    let project = store
+        .insert_project(ProjectModel {
+            name: "Launch".to_string(),
+            emoji: Some("🚀".to_string()),
+            view_style: Some("board".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let prep = store
+        .insert_section(SectionModel {
+            name: "Prep".to_string(),
+            project_id: Some(project.id.clone()),
+            section_order: Some(0),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "Urgent".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+
+    let mut plan = ItemModel {
+        content: "Plan".to_string(),
+        project_id: Some(project.id.clone()),
+        section_id: Some(prep.id.clone()),
+        labels: Some(label.id.clone()),
+        priority: Some(4),
+        ..Default::default()
+    };
+    plan.set_due_date(Some(DueDate { date: "2025-03-03".to_string(), ..Default::default() }));
+    let plan = store.insert_item(plan, true).await.unwrap();
+    store.set_item_labels(&plan.id, std::slice::from_ref(&label.id)).await.unwrap();
+    item(store, "Draft outline", &project.id, Some(&prep.id), Some(&plan.id), None).await;
+
+    let release =
+        item(store, "Release", &project.id, None, None, Some(datetime("2025-03-06 10:00"))).await;
+    store
+        .insert_reminder(ReminderModel {
+            item_id: Some(release.id.clone()),
+            due: Some("2025-03-06 09:00:00".to_string()),
+            reminder_type: Some("time".to_string()),
+            mm_offset: Some(60),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let done = item(store, "Old chore", &project.id, None, None, None).await;
+    store.complete_item(&done.id, true, false).await.unwrap();
+    project
+}
+
+#[tokio::test]
+async fn test_create_template() {
+    let store = setup_store().await;
+    let project = launch_project(&store).await;
+
+    let template = store.create_project_template(&project.id, None).await.unwrap();
+    assert_eq!(template.project.name, "Launch");
+    assert_eq!(template.project.emoji.as_deref(), Some("🚀"));
+    assert_eq!(template.sections.len(), 1);
+
+    // 已完成的任务不进模板，子任务紧跟父任务
+    let contents: Vec<&str> = template.items.iter().map(|item| item.content.as_str()).collect();
+    assert_eq!(contents, ["Plan", "Draft outline", "Release"]);
+    let plan = &template.items[0];
+    assert_eq!(template.items[1].parent.as_ref(), Some(&plan.key));
+    assert_eq!(plan.section.as_ref(), Some(&template.sections[0].key));
+    assert_eq!(plan.labels, ["Urgent"]);
+
+    // 基准日为最早的截止日期
+    assert_eq!(plan.due.as_ref().unwrap().offset_days, 0);
+    let release = &template.items[2];
+    assert_eq!(release.due.as_ref().unwrap().offset_days, 3);
+    assert_eq!(release.reminders, [TemplateReminder::BeforeDue { minutes: 60 }]);
+
+    // 指定基准日
+    let template =
+        store.create_project_template(&project.id, Some(date("2025-03-01"))).await.unwrap();
+    assert_eq!(template.items[0].due.as_ref().unwrap().offset_days, 2);
+    assert!(store.create_project_template("missing", None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_instantiate_template_shifts_dates() {
+    let store = setup_store().await;
+    let project = launch_project(&store).await;
+    let template = store.create_project_template(&project.id, None).await.unwrap();
+
+    let copy =
+        store.instantiate_template(&template, Some("Launch v2"), date("2025-06-02")).await.unwrap();
+    assert_ne!(copy.id, project.id);
+    assert_eq!(copy.name, "Launch v2");
+    assert_eq!(copy.view_style.as_deref(), Some("board"));
+
+    let items = store.get_items_by_project(&copy.id).await.unwrap();
+    assert_eq!(items.len(), 3);
+    let find = |content: &str| items.iter().find(|item| item.content == content).unwrap();
+    let plan = find("Plan");
+    let outline = find("Draft outline");
+    let release = find("Release");
+
+    assert_eq!(plan.due_date_naive(), Some(date("2025-06-02")));
+    assert_eq!(release.due_datetime(), Some(datetime("2025-06-05 10:00")));
+    assert_eq!(outline.parent_id.as_ref(), Some(&plan.id));
+    assert_eq!(outline.due_datetime(), None);
+    assert_eq!(plan.priority, Some(4));
+
+    let sections = store.get_all_sections().await.unwrap();
+    let prep = sections
+        .iter()
+        .find(|s| s.project_id.as_deref() == Some(copy.id.as_str()))
+        .expect("section copied");
+    assert_eq!(prep.name, "Prep");
+    assert_eq!(plan.section_id.as_ref(), Some(&prep.id));
+
+    // 标签按名称复用已有标签，不重复创建
+    assert_eq!(store.get_all_labels().await.unwrap().len(), 1);
+    let labels = store.get_labels_by_item(&plan.id).await.unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(plan.labels.as_deref(), Some(labels[0].id.as_str()));
+
+    // 提醒随截止时间平移
+    let reminders = store.get_reminders_by_item(&release.id).await.unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].due.as_deref(), Some("2025-06-05 09:00:00"));
+    assert_eq!(reminders[0].mm_offset, Some(60));
+
+    // 原项目不受影响
+    let original = store.get_items_by_project(&project.id).await.unwrap();
+    assert_eq!(original.iter().filter(|item| !item.checked).count(), 3);
+}
+
+#[tokio::test]
+async fn test_template_file_round_trip() {
+    let store = setup_store().await;
+    let project = launch_project(&store).await;
+    let path = std::env::temp_dir().join(format!("template-{}.json", project.id));
+
+    let saved = store.save_project_template(&project.id, &path, None).await.unwrap();
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(ProjectTemplate::from_json(&json).unwrap(), saved);
+
+    // 同一个模板可以多次实例化，名称默认取模板中的项目名
+    let first = store.instantiate_template_file(&path, None, date("2025-09-01")).await.unwrap();
+    let second = store.instantiate_template_file(&path, None, date("2025-10-01")).await.unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(first.name, "Launch");
+    assert_ne!(first.id, second.id);
+    let second_items = store.get_items_by_project(&second.id).await.unwrap();
+    let plan = second_items.iter().find(|item| item.content == "Plan").unwrap();
+    assert_eq!(plan.due_date_naive(), Some(date("2025-10-01")));
+
+    assert!(
+        store.instantiate_template_file(&path, None, date("2025-10-01")).await.is_err(),
+        "file removed"
+    );
+}
+
+#[tokio::test]
+async fn test_instantiate_rejects_invalid_template() {
+    let store = setup_store().await;
+    let project = launch_project(&store).await;
+    let mut template = store.create_project_template(&project.id, None).await.unwrap();
+
+    template.items[0].content = "  ".to_string();
+    assert!(store.instantiate_template(&template, None, date("2025-06-02")).await.is_err());
+
+    template.items[0].content = "Plan".to_string();
+    template.project.name = String::new();
+    assert!(store.instantiate_template(&template, Some(" "), date("2025-06-02")).await.is_err());
+    assert_eq!(store.get_all_projects().await.unwrap().len(), 1, "nothing was created");
+}
@@ -0,0 +1,578 @@
+//! Todoist 同步的集成测试：内存 SQLite + 进程内的 Sync API 替身服务器
+
+mod common;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use serde_json::{Map, Value, json};
+use todos::{
+    Store,
+    entity::{ItemModel, ProjectModel},
+    error::TodoError,
+    services::{TodoistConfig, TodoistSyncReport, TodoistSyncService},
+    sync::{HttpRequest, HttpResponse, HttpTransport},
+};
+
+/// 最小的 Sync API：按 `sync_token` 返回增量，执行命令并解析临时 ID
+#[derive(Default)]
+struct FakeTodoist {
+    inner: Mutex<Account>,
+}
+
+#[derive(Default)]
+struct Account {
+    /// 当前的 `sync_token` 序号
+    version: u32,
+    next_id: u32,
+    /// (类型, ID) → (修改时的序号, 对象)
+    objects: BTreeMap<(String, String), (u32, Map<String, Value>)>,
+    /// 收到的命令 `(type, args)`
+    commands: Vec<(String, Value)>,
+    /// 收到的 `sync_token`
+    tokens: Vec<String>,
+    offline: bool,
+}
+
+impl FakeTodoist {
+    /// 模拟其他客户端写入对象
+    fn put(&self, kind: &str, object: Value) {
+        let mut account = self.inner.lock().unwrap();
+        account.version += 1;
+        let object = object.as_object().unwrap().clone();
+        let id = object["id"].as_str().unwrap().to_string();
+        let version = account.version;
+        account.objects.insert((kind.to_string(), id), (version, object));
+    }
+
+    /// 服务器忘记了对象（之后引用它的命令会失败）
+    fn remove(&self, kind: &str, id: &str) {
+        let mut account = self.inner.lock().unwrap();
+        account.objects.remove(&(kind.to_string(), id.to_string()));
+    }
+
+    fn get(&self, kind: &str, id: &str) -> Option<Map<String, Value>> {
+        let account = self.inner.lock().unwrap();
+        account.objects.get(&(kind.to_string(), id.to_string())).map(|(_, object)| object.clone())
+    }
+
+    fn find(&self, kind: &str, field: &str, value: &str) -> Option<Map<String, Value>> {
+        let account = self.inner.lock().unwrap();
+        account
+            .objects
+            .iter()
+            .filter(|((object_kind, _), _)| object_kind == kind)
+            .map(|(_, (_, object))| object)
+            .find(|object| object.get(field).and_then(Value::as_str) == Some(value))
+            .cloned()
+    }
+
+    fn set_offline(&self, offline: bool) {
+        self.inner.lock().unwrap().offline = offline;
+    }
+
+    fn take_commands(&self) -> Vec<(String, Value)> {
+        std::mem::take(&mut self.inner.lock().unwrap().commands)
+    }
+
+    fn take_tokens(&self) -> Vec<String> {
+        std::mem::take(&mut self.inner.lock().unwrap().tokens)
+    }
+}
+
+impl Account {
+    fn execute(
+        &mut self,
+        command: &Value,
+        temp_ids: &mut HashMap<String, String>,
+    ) -> Result<(), String> {
+        let kind = command["type"].as_str().unwrap_or_default().to_string();
+        let mut args = command["args"].as_object().cloned().unwrap_or_default();
+        for field in ["id", "project_id", "section_id", "parent_id"] {
+            if let Some(Value::String(id)) = args.get_mut(field)
+                && let Some(real) = temp_ids.get(id.as_str())
+            {
+                *id = real.clone();
+            }
+        }
+        self.commands.push((kind.clone(), Value::Object(args.clone())));
+
+        let (object, action) = kind.split_once('_').ok_or("Unknown command")?;
+        let collection = format!("{}s", object);
+        self.version += 1;
+        let version = self.version;
+        if action == "add" {
+            self.next_id += 1;
+            let id = format!("{}-{}", object, self.next_id);
+            if let Some(temp_id) = command["temp_id"].as_str() {
+                temp_ids.insert(temp_id.to_string(), id.clone());
+            }
+            args.insert("id".to_string(), json!(id));
+            self.objects.insert((collection, id), (version, args));
+            return Ok(());
+        }
+
+        let id = args.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+        let Some((changed, existing)) = self.objects.get_mut(&(collection, id)) else {
+            return Err(format!("{} not found", object));
+        };
+        *changed = version;
+        match action {
+            "close" => existing.insert("checked".to_string(), json!(true)),
+            "uncomplete" => existing.insert("checked".to_string(), json!(false)),
+            "delete" => existing.insert("is_deleted".to_string(), json!(true)),
+            "archive" => existing.insert("is_archived".to_string(), json!(true)),
+            "unarchive" => existing.insert("is_archived".to_string(), json!(false)),
+            _ => {
+                existing.extend(args);
+                None
+            },
+        };
+        Ok(())
+    }
+}
+
+fn form_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap();
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                index += 2;
+            },
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+impl FakeTodoist {
+    fn handle(&self, request: HttpRequest) -> HttpResponse {
+        let mut account = self.inner.lock().unwrap();
+        if account.offline {
+            return HttpResponse::new(503, "unavailable");
+        }
+        if request.header("Authorization") != Some("Bearer secret-token") {
+            return HttpResponse::new(401, "");
+        }
+        let form: HashMap<String, String> = request
+            .body
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.to_string(), form_decode(value)))
+            .collect();
+        let sync_token = form.get("sync_token").cloned().unwrap_or_default();
+        account.tokens.push(sync_token.clone());
+
+        let commands: Vec<Value> =
+            form.get("commands").map(|c| serde_json::from_str(c).unwrap()).unwrap_or_default();
+        let mut temp_ids = HashMap::new();
+        let mut status = Map::new();
+        for command in &commands {
+            let result = match account.execute(command, &mut temp_ids) {
+                Ok(()) => json!("ok"),
+                Err(error) => json!({ "error_code": 20, "error": error }),
+            };
+            status.insert(command["uuid"].as_str().unwrap().to_string(), result);
+        }
+
+        let full_sync = sync_token == "*";
+        let since: u32 = sync_token.parse().unwrap_or(0);
+        let mut response = json!({
+            "sync_token": account.version.to_string(),
+            "full_sync": full_sync,
+            "temp_id_mapping": temp_ids,
+            "sync_status": status,
+            "projects": [], "sections": [], "items": [], "labels": [],
+        });
+        for ((kind, _), (changed, object)) in &account.objects {
+            let deleted = object.get("is_deleted") == Some(&json!(true));
+            if (full_sync && !deleted) || (!full_sync && *changed > since) {
+                response[kind.as_str()].as_array_mut().unwrap().push(Value::Object(object.clone()));
+            }
+        }
+        HttpResponse::new(200, response.to_string())
+    }
+
+    /// 在 localhost 上监听，通过真实的 HTTP 请求访问；返回 Sync API 地址
+    fn serve(self: &Arc<Self>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sync/v9/sync", listener.local_addr().unwrap());
+        let server = self.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                server.respond(stream);
+            }
+        });
+        url
+    }
+
+    fn respond(&self, stream: TcpStream) {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut request = HttpRequest::new(method, path);
+        let mut length = 0;
+        loop {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            let Some((name, value)) = line.trim_end().split_once(':') else { break };
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap();
+            }
+            request = request.with_header(name, value.trim());
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        let request = request.with_body(String::from_utf8(body).unwrap());
+
+        let response = self.handle(request);
+        let mut stream = reader.into_inner();
+        let head = [
+            format!("HTTP/1.1 {} X", response.status),
+            "Content-Type: application/json".to_string(),
+            format!("Content-Length: {}", response.body.len()),
+            "Connection: close".to_string(),
+            String::new(),
+            String::new(),
+        ]
+        .join("\r\n");
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(response.body.as_bytes()).unwrap();
+    }
+}
+
+#[async_trait]
+impl HttpTransport for FakeTodoist {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TodoError> {
+        Ok(self.handle(request))
+    }
+}
+
+struct Fixture {
+    db: DatabaseConnection,
+    store: Arc<Store>,
+    todoist: TodoistSyncService,
+    server: Arc<FakeTodoist>,
+    source_id: String,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let db = common::connect().await;
+        let store = Store::new(db.clone()).await.expect("create store");
+        let todoist = TodoistSyncService::new(Arc::new(db.clone()));
+        let source = store
+            .add_todoist_source(TodoistConfig {
+                token: " secret-token ".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let server = Arc::new(FakeTodoist::default());
+        server.put("projects", json!({ "id": "p-inbox", "name": "Inbox", "inbox_project": true }));
+        server.put(
+            "projects",
+            json!({ "id": "p-work", "name": "Work", "color": "berry_red", "is_favorite": true }),
+        );
+        server.put("sections", json!({ "id": "s-1", "project_id": "p-work", "name": "Doing" }));
+        server.put("labels", json!({ "id": "l-1", "name": "errand", "color": "green" }));
+        server.put(
+            "items",
+            json!({
+                "id": "i-1", "project_id": "p-work", "section_id": "s-1", "content": "Write report",
+                "priority": 4, "labels": ["errand"], "due": { "date": "2030-01-05", "is_recurring": false },
+            }),
+        );
+        server.put(
+            "items",
+            json!({ "id": "i-2", "project_id": "p-work", "parent_id": "i-1", "content": "Outline" }),
+        );
+        server.put("items", json!({ "id": "i-3", "project_id": "p-inbox", "content": "Call mom" }));
+        Self { db, store, todoist, server, source_id: source.id }
+    }
+
+    async fn sync(&self) -> TodoistSyncReport {
+        self.todoist.sync_with_transport(&self.source_id, self.server.clone()).await.unwrap()
+    }
+
+    async fn project(&self, name: &str) -> ProjectModel {
+        let projects = self.store.get_all_projects().await.unwrap();
+        projects.into_iter().find(|project| project.name == name).unwrap()
+    }
+
+    async fn item(&self, content: &str) -> ItemModel {
+        let items = self.store.get_all_items().await.unwrap();
+        items.into_iter().find(|item| item.content == content).unwrap()
+    }
+
+    async fn pending(&self) -> Vec<String> {
+        let commands = self.store.pending_sync_commands().await.unwrap();
+        commands.into_iter().map(|command| command.query.unwrap_or_default()).collect()
+    }
+}
+
+#[tokio::test]
+async fn test_full_sync_pulls_workspace() {
+    let fixture = Fixture::new().await;
+    let source = fixture.store.get_todoist_source().await.unwrap().unwrap();
+    assert_eq!(source.display_name.as_deref(), Some("Todoist"));
+    let duplicate = TodoistConfig { token: "other".to_string(), ..Default::default() };
+    assert!(fixture.store.add_todoist_source(duplicate).await.is_err());
+
+    let report = fixture.sync().await;
+    assert!(report.full_sync);
+    assert_eq!((report.pushed, report.pulled_created, report.pulled_deleted), (0, 7, 0));
+    assert_eq!(fixture.server.take_tokens(), vec!["*"]);
+
+    let inbox = fixture.project("Inbox").await;
+    assert_eq!(inbox.inbox_project, Some(1));
+    let work = fixture.project("Work").await;
+    assert_eq!(work.id, "p-work");
+    assert_eq!(work.backend_type.as_deref(), Some("todoist"));
+    assert_eq!(work.source_id.as_deref(), Some(fixture.source_id.as_str()));
+    assert!(work.is_favorite);
+
+    let report_item = fixture.item("Write report").await;
+    assert_eq!(report_item.section_id.as_deref(), Some("s-1"));
+    // API 的 4（p1）对应本地的 1
+    assert_eq!(report_item.priority, Some(1));
+    assert_eq!(report_item.due_date().unwrap().date, "2030-01-05 00:00:00");
+    let labels = fixture.store.get_labels_by_item(&report_item.id).await.unwrap();
+    assert_eq!(labels[0].name, "errand");
+    assert_eq!(labels[0].backend_type.as_deref(), Some("todoist"));
+    assert_eq!(fixture.item("Outline").await.parent_id.as_deref(), Some("i-1"));
+
+    // 增量同步：带上次的 sync_token，没有变化
+    let report = fixture.sync().await;
+    assert!(!report.full_sync);
+    assert!(!report.has_changes());
+    assert_ne!(fixture.server.take_tokens(), vec!["*"]);
+
+    // 服务器上的修改和删除
+    fixture.server.put(
+        "items",
+        json!({ "id": "i-3", "project_id": "p-inbox", "content": "Call mom tonight" }),
+    );
+    fixture.server.put(
+        "items",
+        json!({ "id": "i-2", "project_id": "p-work", "content": "Outline", "is_deleted": true }),
+    );
+    let report = fixture.sync().await;
+    assert_eq!((report.pulled_updated, report.pulled_deleted), (1, 1));
+    assert_eq!(fixture.item("Call mom tonight").await.id, "i-3");
+    let items = fixture.store.get_all_items().await.unwrap();
+    assert!(items.iter().all(|item| item.content != "Outline"));
+    assert!(fixture.pending().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_local_changes_are_queued_and_pushed() {
+    let fixture = Fixture::new().await;
+    fixture.sync().await;
+    fixture.server.take_commands();
+
+    // 本地项目不属于 Todoist，不排队
+    let local = fixture
+        .store
+        .insert_project(ProjectModel { name: "Private".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    fixture
+        .store
+        .insert_item(
+            ItemModel {
+                content: "Diary".to_string(),
+                project_id: Some(local.id.clone()),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    assert!(fixture.pending().await.is_empty());
+
+    let project = fixture
+        .store
+        .insert_project(ProjectModel {
+            name: "Garden".to_string(),
+            backend_type: Some("todoist".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let item = fixture
+        .store
+        .insert_item(
+            ItemModel {
+                content: "Plant tulips".to_string(),
+                project_id: Some(project.id.clone()),
+                priority: Some(2),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    fixture.store.complete_item(&item.id, true, false).await.unwrap();
+    let mut renamed = fixture.item("Write report").await;
+    renamed.content = "Write final report".to_string();
+    fixture.store.update_item(renamed, "").await.unwrap();
+    fixture.store.delete_item("i-3").await.unwrap();
+    assert_eq!(fixture.pending().await, vec![
+        "project_add",
+        "item_add",
+        "item_close",
+        "item_update",
+        "item_delete"
+    ]);
+
+    let report = fixture.sync().await;
+    assert_eq!(report.pushed, 5);
+    assert!(report.rejected.is_empty());
+    assert!(fixture.pending().await.is_empty());
+
+    // 临时 ID 在同一批命令中解析：任务进入新项目
+    let remote_project = fixture.server.find("projects", "name", "Garden").unwrap();
+    let remote_item = fixture.server.find("items", "content", "Plant tulips").unwrap();
+    assert_eq!(remote_item["project_id"], remote_project["id"]);
+    assert_eq!(remote_item["priority"], json!(3));
+    assert_eq!(remote_item["checked"], json!(true));
+    assert_eq!(fixture.server.get("items", "i-1").unwrap()["content"], "Write final report");
+    assert_eq!(fixture.server.get("items", "i-3").unwrap()["is_deleted"], json!(true));
+    // 本地对象保留本地 ID，回显的对象写回同一行
+    let garden = fixture.project("Garden").await;
+    assert_eq!(garden.id, project.id);
+    assert_eq!(garden.source_id.as_deref(), Some(fixture.source_id.as_str()));
+
+    // 之后的命令使用服务器 ID
+    fixture.server.take_commands();
+    let mut renamed = fixture.item("Plant tulips").await;
+    renamed.content = "Plant red tulips".to_string();
+    renamed.section_id = None;
+    fixture.store.update_item(renamed, "").await.unwrap();
+    fixture.store.set_item_labels(&item.id, &[]).await.unwrap();
+    fixture.sync().await;
+    let commands = fixture.server.take_commands();
+    assert_eq!(commands.len(), 2);
+    assert!(commands.iter().all(|(_, args)| args["id"] == remote_item["id"]));
+    assert_eq!(
+        fixture.server.get("items", remote_item["id"].as_str().unwrap()).unwrap()["content"],
+        "Plant red tulips"
+    );
+}
+
+#[tokio::test]
+async fn test_change_rolls_back_when_queueing_fails() {
+    let fixture = Fixture::new().await;
+    fixture.sync().await;
+
+    // 命令写不进队列时修改一起回滚，不会出现本地已改而服务器永远收不到的情况
+    fixture.db.execute_unprepared("ALTER TABLE queue RENAME TO queue_broken").await.unwrap();
+    let mut item = fixture.item("Call mom").await;
+    item.content = "Call dad".to_string();
+    assert!(fixture.store.update_item(item, "").await.is_err());
+    assert!(fixture.store.delete_item("i-1").await.is_err());
+    fixture.db.execute_unprepared("ALTER TABLE queue_broken RENAME TO queue").await.unwrap();
+
+    assert_eq!(fixture.item("Call mom").await.id, "i-3");
+    assert!(!fixture.item("Write report").await.is_deleted);
+    assert!(fixture.pending().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_offline_and_rejected_commands() {
+    let fixture = Fixture::new().await;
+    fixture.sync().await;
+
+    let mut item = fixture.item("Call mom").await;
+    item.priority = Some(1);
+    fixture.store.update_item(item, "").await.unwrap();
+
+    // 离线时同步失败，命令留在队列中
+    fixture.server.set_offline(true);
+    let error = fixture.todoist.sync_with_transport(&fixture.source_id, fixture.server.clone());
+    assert!(error.await.is_err());
+    assert_eq!(fixture.pending().await, vec!["item_update"]);
+
+    // 服务器上已不存在的任务：命令被拒绝并移出队列
+    fixture.server.set_offline(false);
+    fixture.server.remove("items", "i-3");
+    let report = fixture.sync().await;
+    assert_eq!(report.pushed, 0);
+    assert_eq!(report.rejected.len(), 1);
+    assert!(report.rejected[0].contains("item_update i-3"));
+    assert!(fixture.pending().await.is_empty());
+
+    // 移除账号后项目保留为本地项目，修改不再排队
+    fixture.store.remove_todoist_source(&fixture.source_id).await.unwrap();
+    assert!(fixture.store.get_todoist_source().await.unwrap().is_none());
+    let work = fixture.project("Work").await;
+    assert_eq!(work.backend_type, None);
+    fixture.store.delete_item("i-1").await.unwrap();
+    assert!(fixture.pending().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_over_http() {
+    let db = common::connect().await;
+    let store = Store::new(db).await.expect("create store");
+    let server = Arc::new(FakeTodoist::default());
+    server.put("projects", json!({ "id": "p-1", "name": "Errands" }));
+    let config = TodoistConfig {
+        token: "secret-token".to_string(),
+        display_name: Some("Me".to_string()),
+        sync_url: Some(server.serve()),
+    };
+    let source = store.add_todoist_source(config).await.unwrap();
+
+    let report = store.sync_todoist(&source.id).await.unwrap();
+    assert_eq!(report.pulled_created, 1);
+    let project = store.get_all_projects().await.unwrap();
+    let project = project.into_iter().find(|project| project.id == "p-1").unwrap();
+    store
+        .insert_item(
+            ItemModel {
+                content: "Buy stamps".to_string(),
+                project_id: Some(project.id),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    let report = store.sync_todoist(&source.id).await.unwrap();
+    assert_eq!(report.pushed, 1);
+    assert!(server.find("items", "content", "Buy stamps").is_some());
+    assert!(store.pending_sync_commands().await.unwrap().is_empty());
+
+    // 令牌错误：401 作为网络错误返回
+    store.remove_todoist_source(&source.id).await.unwrap();
+    let config = TodoistConfig {
+        token: "wrong".to_string(),
+        sync_url: Some(server.serve()),
+        ..Default::default()
+    };
+    let source = store.add_todoist_source(config).await.unwrap();
+    let error = store.sync_todoist(&source.id).await.unwrap_err();
+    assert!(error.to_string().contains("401"), "{}", error);
+}
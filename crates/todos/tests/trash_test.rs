@@ -0,0 +1,274 @@
+//! 回收站（软删除 / 恢复 / 彻底删除）集成测试（内存 SQLite）
+
+mod common;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use todos::{
+    Store,
+    entity::{ItemModel, LabelModel, ProjectModel, SectionModel},
+    services::TrashObjectType,
+};
+
+async fn setup_store() -> (std::sync::Arc<Store>, DatabaseConnection) {
+    let db = common::connect().await;
+    let store = Store::new(db.clone()).await.expect("create store");
+    (store, db)
+}
+
+async fn count(db: &DatabaseConnection, sql: &str) -> i64 {
+    let row = db
+        .query_one(Statement::from_string(db.get_database_backend(), sql.to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    row.try_get::<i64>("", "cnt").unwrap()
+}
+
+fn item(content: &str, project_id: &str) -> ItemModel {
+    ItemModel {
+        content: content.to_string(),
+        project_id: Some(project_id.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_delete_project_moves_tree_to_trash_and_restores() {
+    let (store, db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Work".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let section = store
+        .insert_section(SectionModel {
+            name: "Backlog".to_string(),
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let task = store.insert_item(item("Write report", &project.id), true).await.unwrap();
+    let subtask = store
+        .insert_item(
+            ItemModel {
+                content: "Collect data".to_string(),
+                parent_id: Some(task.id.clone()),
+                ..item("", &project.id)
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    store
+        .insert_item(
+            ItemModel { section_id: Some(section.id.clone()), ..item("Triage", &project.id) },
+            true,
+        )
+        .await
+        .unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "urgent".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.set_item_labels(&subtask.id, std::slice::from_ref(&label.id)).await.unwrap();
+
+    store.delete_project(&project.id).await.unwrap();
+
+    assert!(store.get_all_projects().await.unwrap().is_empty());
+    assert!(store.get_all_sections().await.unwrap().is_empty());
+    assert!(store.get_all_items().await.unwrap().is_empty());
+
+    let trash = store.list_trash().await.unwrap();
+    assert_eq!(trash.len(), 1, "only the deleted project is a trash root");
+    assert_eq!(trash[0].object_type, TrashObjectType::Project);
+    assert_eq!(trash[0].children_count, 3);
+
+    store.restore_from_trash(TrashObjectType::Project, &project.id).await.unwrap();
+
+    assert_eq!(store.get_all_projects().await.unwrap().len(), 1);
+    assert_eq!(store.get_all_sections().await.unwrap().len(), 1);
+    assert_eq!(store.get_all_items().await.unwrap().len(), 3);
+    let labels = store.get_labels_by_item(&subtask.id).await.unwrap();
+    assert_eq!(labels.len(), 1, "item_labels survive the round trip");
+    assert!(store.list_trash().await.unwrap().is_empty());
+    assert_eq!(
+        count(&db, "SELECT COUNT(*) AS cnt FROM Items WHERE deleted_at IS NOT NULL").await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_restore_item_keeps_separately_deleted_children_in_trash() {
+    let (store, _db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Home".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let parent = store.insert_item(item("Clean", &project.id), true).await.unwrap();
+    let child = store
+        .insert_item(
+            ItemModel { parent_id: Some(parent.id.clone()), ..item("Kitchen", &project.id) },
+            true,
+        )
+        .await
+        .unwrap();
+
+    store.delete_item(&child.id).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    store.delete_item(&parent.id).await.unwrap();
+    assert_eq!(store.list_trash().await.unwrap().len(), 2);
+
+    store.restore_from_trash(TrashObjectType::Item, &parent.id).await.unwrap();
+
+    let items = store.get_all_items().await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, parent.id);
+    let trash = store.list_trash().await.unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, child.id);
+}
+
+#[tokio::test]
+async fn test_purge_and_empty_trash_remove_rows() {
+    let (store, db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Errands".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let task = store.insert_item(item("Buy milk", &project.id), true).await.unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "shop".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.set_item_labels(&task.id, std::slice::from_ref(&label.id)).await.unwrap();
+
+    store.delete_item(&task.id).await.unwrap();
+    store.purge_from_trash(TrashObjectType::Item, &task.id).await.unwrap();
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM Items").await, 0);
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM item_labels").await, 0);
+
+    store.delete_label(&label.id).await.unwrap();
+    store.delete_project(&project.id).await.unwrap();
+    assert_eq!(store.empty_trash().await.unwrap(), 2);
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM Projects").await, 0);
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM Labels").await, 0);
+}
+
+#[tokio::test]
+async fn test_restore_item_rehomes_subtree_out_of_trashed_project() {
+    let (store, _db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Home".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let section = store
+        .insert_section(SectionModel {
+            name: "Chores".to_string(),
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let in_section = |content: &str| ItemModel {
+        section_id: Some(section.id.clone()),
+        ..item(content, &project.id)
+    };
+    let parent = store.insert_item(in_section("Clean"), true).await.unwrap();
+    let child = store
+        .insert_item(
+            ItemModel { parent_id: Some(parent.id.clone()), ..in_section("Kitchen") },
+            true,
+        )
+        .await
+        .unwrap();
+    let grandchild = store
+        .insert_item(ItemModel { parent_id: Some(child.id.clone()), ..in_section("Sink") }, true)
+        .await
+        .unwrap();
+
+    store.delete_item(&parent.id).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    store.delete_project(&project.id).await.unwrap();
+
+    store.restore_from_trash(TrashObjectType::Item, &parent.id).await.unwrap();
+
+    // 项目仍在回收站中，整棵子树都移出项目和分区，层级保持不变
+    let items = store.get_all_items().await.unwrap();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|i| i.project_id.is_none() && i.section_id.is_none()));
+    let find = |id: &str| items.iter().find(|i| i.id == id).unwrap();
+    assert_eq!(find(&parent.id).parent_id, None);
+    assert_eq!(find(&child.id).parent_id.as_ref(), Some(&parent.id));
+    assert_eq!(find(&grandchild.id).parent_id.as_ref(), Some(&child.id));
+}
+
+#[tokio::test]
+async fn test_purge_expired_respects_retention() {
+    let (store, db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Old".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.delete_project(&project.id).await.unwrap();
+
+    assert_eq!(store.purge_expired_trash(0).await.unwrap(), 0, "0 keeps trash forever");
+    assert_eq!(store.purge_expired_trash(30).await.unwrap(), 0);
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "UPDATE Projects SET deleted_at = '2000-01-01T00:00:00'".to_string(),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(store.purge_expired_trash(30).await.unwrap(), 1);
+    assert!(store.list_trash().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_purge_expired_keeps_rows_deleted_before_upgrade() {
+    let (store, db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Legacy".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.insert_item(item("old task", &project.id), true).await.unwrap();
+    store.delete_project(&project.id).await.unwrap();
+
+    // 迁移 002 之前删除的行只有 is_deleted，没有删除时间
+    for table in ["Projects", "Items"] {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            format!("UPDATE {} SET deleted_at = NULL", table),
+        ))
+        .await
+        .unwrap();
+    }
+    assert_eq!(store.purge_expired_trash(30).await.unwrap(), 0);
+    assert_eq!(store.list_trash().await.unwrap().len(), 1);
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM Items").await, 1);
+
+    // 手动清空仍然有效
+    assert_eq!(store.empty_trash().await.unwrap(), 1);
+    assert_eq!(count(&db, "SELECT COUNT(*) AS cnt FROM Items").await, 0);
+}
+
+#[tokio::test]
+async fn test_get_or_create_label_revives_trashed_label() {
+    let (store, _db) = setup_store().await;
+    let project = store
+        .insert_project(ProjectModel { name: "Inbox".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    let task = store.insert_item(item("Call mom", &project.id), true).await.unwrap();
+    let label = store
+        .insert_label(LabelModel { name: "family".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    store.delete_label(&label.id).await.unwrap();
+
+    store.add_label_to_item(&task.id, "family").await.unwrap();
+
+    let labels = store.get_all_labels().await.unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].id, label.id);
+}